@@ -9,23 +9,29 @@ use edgezero_core::http::{
 };
 use edgezero_core::router::RouterService;
 use error_stack::Report;
+use trusted_server_core::access_log::AccessLogMiddleware;
+use trusted_server_core::auction::beacon::handle_event_beacon;
 use trusted_server_core::auction::endpoints::handle_auction;
 use trusted_server_core::auction::{AuctionOrchestrator, build_orchestrator};
 use trusted_server_core::cache_policy::EdgeCacheHeader;
+use trusted_server_core::config_release::handle_admin_config_release;
 use trusted_server_core::ec::EcContext;
 use trusted_server_core::ec::admin::{
     admin_ec_lookup_not_supported, deny_admin_diagnostic_fallback, handle_admin_eids_lookup,
 };
 use trusted_server_core::ec::registry::PartnerRegistry;
 use trusted_server_core::error::{IntoHttpResponse as _, TrustedServerError};
+use trusted_server_core::experiments::warn_experiments_unsupported;
 use trusted_server_core::integrations::{IntegrationRegistry, ProxyDispatchInput};
+use trusted_server_core::metrics::{MetricsRegistry, handle_admin_metrics};
 use trusted_server_core::proxy::{
     handle_first_party_click, handle_first_party_proxy, handle_first_party_proxy_rebuild,
     handle_first_party_proxy_sign,
 };
 use trusted_server_core::publisher::{
-    AuctionDispatch, PAGE_BIDS_LEGACY_PATH, PAGE_BIDS_PATH, buffer_publisher_response_async,
-    handle_page_bids, handle_publisher_request, handle_tsjs_dynamic, page_bids_preflight_denied,
+    AuctionDispatch, PAGE_BIDS_LEGACY_PATH, PAGE_BIDS_PATH, SLOT_AUCTION_PATH,
+    buffer_publisher_response_async, handle_page_bids, handle_publisher_request,
+    handle_slot_auction, handle_tsjs_dynamic, page_bids_preflight_denied,
 };
 use trusted_server_core::request_signing::{
     handle_trusted_server_discovery, handle_verify_signature,
@@ -37,7 +43,7 @@ use trusted_server_core::settings_data::{
 
 use trusted_server_core::platform::RuntimeServices;
 
-use crate::middleware::{AuthMiddleware, FinalizeResponseMiddleware};
+use crate::middleware::{AuthMiddleware, FinalizeResponseMiddleware, RequestMetricsMiddleware};
 use crate::platform::{AxumPlatformConfigStore, build_runtime_services};
 
 // ---------------------------------------------------------------------------
@@ -49,6 +55,7 @@ pub struct AppState {
     settings: Arc<Settings>,
     orchestrator: Arc<AuctionOrchestrator>,
     registry: Arc<IntegrationRegistry>,
+    metrics: Arc<MetricsRegistry>,
 }
 
 /// Build the application state, loading settings and constructing all per-application components.
@@ -62,6 +69,7 @@ fn build_state() -> Result<Arc<AppState>, Report<TrustedServerError>> {
     let config_key = default_config_key();
     let settings =
         get_settings_from_config_store(&AxumPlatformConfigStore, &store_name, &config_key)?;
+    warn_experiments_unsupported(&settings, "axum");
     build_state_with_settings(settings)
 }
 
@@ -81,6 +89,7 @@ fn build_state_with_settings(
         settings: Arc::new(settings),
         orchestrator: Arc::new(orchestrator),
         registry: Arc::new(registry),
+        metrics: Arc::new(MetricsRegistry::new()),
     }))
 }
 
@@ -133,7 +142,7 @@ where
     F: FnOnce(Arc<AppState>, RuntimeServices, Request) -> Fut,
     Fut: Future<Output = Result<Response, Report<TrustedServerError>>>,
 {
-    let services = build_runtime_services(&ctx);
+    let services = build_runtime_services(&ctx).with_metrics(Arc::clone(&state.metrics));
     let mut req = ctx.into_request();
     if let Err(error) = trusted_server_core::integrations::gpt_diagnostics::prepare_request(
         &state.settings,
@@ -271,11 +280,15 @@ enum NamedRouteHandler {
     AdminNotSupported,
     AdminEcNotSupported,
     AdminEidsLookup,
+    AdminMetrics,
+    AdminConfigRelease,
     /// Legacy `/admin/keys/*` aliases — denied locally with 404 so they never
     /// reach the publisher fallback (which would leak admin credentials).
     LegacyAdminDenied,
     Auction,
+    EventBeacon,
     PageBids,
+    SlotAuction,
     FirstPartyProxy,
     FirstPartyClick,
     FirstPartySign,
@@ -298,7 +311,7 @@ const LEGACY_ADMIN_DENY_METHODS: &[Method] = &[
     Method::DELETE,
 ];
 
-fn named_routes() -> [NamedRoute; 16] {
+fn named_routes() -> [NamedRoute; 19] {
     [
         NamedRoute {
             path: "/.well-known/trusted-server.json",
@@ -343,6 +356,18 @@ fn named_routes() -> [NamedRoute; 16] {
             primary_methods: &[Method::GET],
             handler: NamedRouteHandler::AdminEidsLookup,
         },
+        // Prometheus scrape target. The dev server is long-lived, so the
+        // process-wide registry is rendered directly.
+        NamedRoute {
+            path: "/_ts/admin/metrics",
+            primary_methods: &[Method::GET],
+            handler: NamedRouteHandler::AdminMetrics,
+        },
+        NamedRoute {
+            path: "/_ts/admin/config/release",
+            primary_methods: &[Method::GET],
+            handler: NamedRouteHandler::AdminConfigRelease,
+        },
         // The legacy non-`/_ts` aliases (`/admin/keys/*`) are denied locally with
         // a 404, matching the Fastly and Cloudflare adapters: the production
         // basic-auth handler regex `^/_ts/admin` does not match them, and letting
@@ -364,6 +389,11 @@ fn named_routes() -> [NamedRoute; 16] {
             primary_methods: &[Method::POST],
             handler: NamedRouteHandler::Auction,
         },
+        NamedRoute {
+            path: "/_ts/api/v1/event",
+            primary_methods: &[Method::POST],
+            handler: NamedRouteHandler::EventBeacon,
+        },
         // GET runs the SPA re-auction; OPTIONS is denied in-handler as a CORS
         // preflight guard for this side-effecting endpoint.
         NamedRoute {
@@ -379,6 +409,13 @@ fn named_routes() -> [NamedRoute; 16] {
             primary_methods: &[Method::GET, Method::OPTIONS],
             handler: NamedRouteHandler::PageBids,
         },
+        // POST runs a lazy-load or refresh auction; OPTIONS is denied
+        // in-handler like page-bids.
+        NamedRoute {
+            path: SLOT_AUCTION_PATH,
+            primary_methods: &[Method::POST, Method::OPTIONS],
+            handler: NamedRouteHandler::SlotAuction,
+        },
         NamedRoute {
             path: "/first-party/proxy",
             primary_methods: &[Method::GET],
@@ -449,6 +486,12 @@ fn named_route_handler(
                             PartnerRegistry::from_config(&state.settings.ec.partners)?;
                         handle_admin_eids_lookup(&partner_registry, &req)
                     }
+                    NamedRouteHandler::AdminMetrics => Ok(handle_admin_metrics(&state.metrics)),
+                    NamedRouteHandler::AdminConfigRelease => handle_admin_config_release(
+                        &AxumPlatformConfigStore,
+                        &default_config_store_name(),
+                        &default_config_key(),
+                    ),
                     NamedRouteHandler::LegacyAdminDenied => Ok(legacy_admin_alias_denied()),
                     NamedRouteHandler::Auction => {
                         // Build the geo-aware EC context so the auction consent
@@ -466,6 +509,10 @@ fn named_route_handler(
                         )
                         .await
                     }
+                    NamedRouteHandler::EventBeacon => {
+                        let ec_context = build_ec_context(&state, &services, &req);
+                        handle_event_beacon(&state.settings, &services, &ec_context, req).await
+                    }
                     NamedRouteHandler::PageBids => {
                         // SPA re-auction endpoint. `OPTIONS` is a CORS preflight
                         // for this side-effecting GET and is always denied so the
@@ -490,6 +537,27 @@ fn named_route_handler(
                             .await
                         }
                     }
+                    NamedRouteHandler::SlotAuction => {
+                        if req.method() == Method::OPTIONS {
+                            Ok(page_bids_preflight_denied())
+                        } else {
+                            let ec_context = build_ec_context(&state, &services, &req);
+                            let auction = AuctionDispatch {
+                                orchestrator: &state.orchestrator,
+                                slots: state.settings.creative_opportunity_slots(),
+                                registry: None,
+                            };
+                            handle_slot_auction(
+                                &state.settings,
+                                &services,
+                                None,
+                                auction,
+                                &ec_context,
+                                req,
+                            )
+                            .await
+                        }
+                    }
                     NamedRouteHandler::FirstPartyProxy => {
                         handle_first_party_proxy(&state.settings, &services, req).await
                     }
@@ -600,6 +668,11 @@ fn build_router(state: &Arc<AppState>) -> RouterService {
     let fallback = fallback_handler(Arc::clone(state));
 
     let mut router = RouterService::builder()
+        .middleware(AccessLogMiddleware::new(
+            Arc::clone(&state.settings),
+            build_runtime_services,
+        ))
+        .middleware(RequestMetricsMiddleware::new(Arc::clone(&state.metrics)))
         .middleware(FinalizeResponseMiddleware::new(Arc::clone(&state.settings)))
         .middleware(AuthMiddleware::new(Arc::clone(&state.settings)));
 
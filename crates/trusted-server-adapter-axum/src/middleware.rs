@@ -7,8 +7,39 @@ use edgezero_core::http::{HeaderValue, Response};
 use edgezero_core::middleware::{Middleware, Next};
 use trusted_server_core::auth::enforce_basic_auth;
 use trusted_server_core::constants::HEADER_X_GEO_INFO_AVAILABLE;
+use trusted_server_core::metrics::{MetricsRegistry, record_http_request};
 use trusted_server_core::settings::Settings;
 
+// ---------------------------------------------------------------------------
+// RequestMetricsMiddleware
+// ---------------------------------------------------------------------------
+
+/// Outermost middleware: counts every response by route class and status.
+///
+/// Wraps [`FinalizeResponseMiddleware`] so auth challenges and router-level
+/// responses are counted alongside handler responses.
+pub struct RequestMetricsMiddleware {
+    metrics: Arc<MetricsRegistry>,
+}
+
+impl RequestMetricsMiddleware {
+    /// Creates a new [`RequestMetricsMiddleware`] recording into `metrics`.
+    #[must_use]
+    pub fn new(metrics: Arc<MetricsRegistry>) -> Self {
+        Self { metrics }
+    }
+}
+
+#[async_trait(?Send)]
+impl Middleware for RequestMetricsMiddleware {
+    async fn handle(&self, ctx: RequestContext, next: Next<'_>) -> Result<Response, EdgeError> {
+        let path = ctx.request().uri().path().to_owned();
+        let response = next.run(ctx).await?;
+        record_http_request(&self.metrics, &path, response.status());
+        Ok(response)
+    }
+}
+
 // ---------------------------------------------------------------------------
 // FinalizeResponseMiddleware
 // ---------------------------------------------------------------------------
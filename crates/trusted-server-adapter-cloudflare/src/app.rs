@@ -8,6 +8,8 @@ use edgezero_core::error::EdgeError;
 use edgezero_core::http::{HeaderValue, Method, Request, Response, StatusCode, header};
 use edgezero_core::router::RouterService;
 use error_stack::Report;
+use trusted_server_core::access_log::AccessLogMiddleware;
+use trusted_server_core::auction::beacon::handle_event_beacon;
 use trusted_server_core::auction::endpoints::handle_auction;
 use trusted_server_core::auction::{AuctionOrchestrator, build_orchestrator};
 use trusted_server_core::cache_policy::EdgeCacheHeader;
@@ -20,16 +22,18 @@ use trusted_server_core::ec::admin::{
 };
 use trusted_server_core::ec::registry::PartnerRegistry;
 use trusted_server_core::error::{IntoHttpResponse as _, TrustedServerError};
+use trusted_server_core::experiments::warn_experiments_unsupported;
 use trusted_server_core::integrations::{IntegrationRegistry, ProxyDispatchInput};
+use trusted_server_core::metrics::admin_metrics_not_supported;
 use trusted_server_core::platform::RuntimeServices;
 use trusted_server_core::proxy::{
     handle_first_party_click, handle_first_party_proxy, handle_first_party_proxy_rebuild,
     handle_first_party_proxy_sign,
 };
 use trusted_server_core::publisher::{
-    AuctionDispatch, PAGE_BIDS_LEGACY_PATH, PAGE_BIDS_PATH, PublisherResponse,
+    AuctionDispatch, PAGE_BIDS_LEGACY_PATH, PAGE_BIDS_PATH, PublisherResponse, SLOT_AUCTION_PATH,
     buffer_publisher_response_async, handle_page_bids, handle_publisher_request,
-    handle_tsjs_dynamic, page_bids_preflight_denied,
+    handle_slot_auction, handle_tsjs_dynamic, page_bids_preflight_denied,
 };
 use trusted_server_core::request_signing::{
     handle_trusted_server_discovery, handle_verify_signature,
@@ -66,6 +70,7 @@ pub struct AppState {
 /// registry fail to initialise.
 fn build_state() -> Result<Arc<AppState>, Report<TrustedServerError>> {
     let settings = load_startup_settings()?;
+    warn_experiments_unsupported(&settings, "cloudflare");
     build_state_with_settings(settings)
 }
 
@@ -462,6 +467,10 @@ fn build_router(state: &Arc<AppState>) -> RouterService {
         };
 
         let mut router = RouterService::builder()
+            .middleware(AccessLogMiddleware::new(
+                Arc::clone(&state.settings),
+                build_per_request_services,
+            ))
             .middleware(FinalizeResponseMiddleware::new(Arc::clone(&state.settings)))
             .middleware(AuthMiddleware::new(Arc::clone(&state.settings)))
             .get(
@@ -512,6 +521,11 @@ fn build_router(state: &Arc<AppState>) -> RouterService {
                     handle_admin_eids_lookup(&partner_registry, &req)
                 }),
             )
+            // Workers isolates are not a scrape target, so there is no
+            // process-wide registry to serve.
+            .get("/_ts/admin/metrics", |_ctx: RequestContext| async {
+                Ok::<Response, EdgeError>(admin_metrics_not_supported())
+            })
             .post(
                 "/auction",
                 make_handler(Arc::clone(&state), |s, services, req| async move {
@@ -531,6 +545,13 @@ fn build_router(state: &Arc<AppState>) -> RouterService {
                     .await
                 }),
             )
+            .post(
+                "/_ts/api/v1/event",
+                make_handler(Arc::clone(&state), |s, services, req| async move {
+                    let ec_context = build_ec_context(&s.settings, &services, &req);
+                    handle_event_beacon(&s.settings, &services, &ec_context, req).await
+                }),
+            )
             .get(
                 "/first-party/proxy",
                 make_handler(Arc::clone(&state), |s, services, req| async move {
@@ -599,6 +620,20 @@ fn build_router(state: &Arc<AppState>) -> RouterService {
             router = router.route(path, Method::OPTIONS, page_bids_preflight.clone());
         }
 
+        // POST /_ts/api/v1/slot-auction — lazy-load and refresh auctions. The
+        // preflight is denied for the same reason as page-bids.
+        let slot_auction = make_handler(Arc::clone(&state), |s, services, req| async move {
+            let ec_context = build_ec_context(&s.settings, &services, &req);
+            let auction = AuctionDispatch {
+                orchestrator: &s.orchestrator,
+                slots: s.settings.creative_opportunity_slots(),
+                registry: None,
+            };
+            handle_slot_auction(&s.settings, &services, None, auction, &ec_context, req).await
+        });
+        router = router.route(SLOT_AUCTION_PATH, Method::POST, slot_auction);
+        router = router.route(SLOT_AUCTION_PATH, Method::OPTIONS, page_bids_preflight);
+
         let legacy_admin_deny =
             make_handler(Arc::clone(&state), |_s, _services, _req| async move {
                 Ok(legacy_admin_alias_denied())
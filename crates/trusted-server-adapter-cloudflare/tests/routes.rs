@@ -255,7 +255,9 @@ fn all_explicit_routes_are_registered() {
         ("GET", "/_ts/admin/ec"),
         ("GET", "/_ts/admin/ec/{id}"),
         ("GET", "/_ts/admin/eids"),
+        ("GET", "/_ts/admin/metrics"),
         ("POST", "/auction"),
+        ("POST", "/_ts/api/v1/event"),
         // SPA re-auction endpoint, plus its deprecated `/__ts/` alias. Both
         // paths are spelled out as literals rather than referencing
         // `PAGE_BIDS_PATH` / `PAGE_BIDS_LEGACY_PATH` so this test pins the
@@ -266,6 +268,8 @@ fn all_explicit_routes_are_registered() {
         ("OPTIONS", "/_ts/page-bids"),
         ("GET", "/__ts/page-bids"),
         ("OPTIONS", "/__ts/page-bids"),
+        ("POST", "/_ts/api/v1/slot-auction"),
+        ("OPTIONS", "/_ts/api/v1/slot-auction"),
         ("GET", "/first-party/proxy"),
         ("GET", "/first-party/click"),
         ("GET", "/first-party/sign"),
@@ -395,6 +399,7 @@ async fn authenticated_admin_diagnostic_fallback_is_denied_locally() {
         "/_ts/admin/ec".to_owned(),
         format!("/_ts/admin/ec/{ec_id}"),
         "/_ts/admin/eids".to_owned(),
+        "/_ts/admin/metrics".to_owned(),
     ];
 
     for path in valid_paths {
@@ -25,11 +25,15 @@
 //! | GET | `/_ts/admin/ec` | [`handle_admin_ec_lookup`] |
 //! | GET | `/_ts/admin/ec/{id}` | [`handle_admin_ec_lookup`] |
 //! | GET | `/_ts/admin/eids` | [`handle_admin_eids_lookup`] |
+//! | GET | `/_ts/admin/metrics` | [`admin_metrics_not_supported`] |
+//! | GET | `/_ts/admin/config/release` | [`handle_admin_config_release`] |
 //! | POST | `/_ts/api/v1/batch-sync` | [`handle_batch_sync`] |
 //! | GET | `/_ts/api/v1/identify` | [`handle_identify`] |
 //! | GET | `/_ts/set-tester` | [`handle_set_tester`] |
 //! | GET | `/_ts/clear-tester` | [`handle_clear_tester`] |
 //! | OPTIONS | `/_ts/api/v1/identify` | [`cors_preflight_identify`] |
+//! | POST | `/_ts/api/v1/event` | [`handle_event_beacon`] |
+//! | POST | `/_ts/api/v1/slot-auction` | [`handle_slot_auction`] |
 //! | POST | `/auction` | [`handle_auction`] |
 //! | GET | `/first-party/proxy` | [`handle_first_party_proxy`] |
 //! | GET | `/first-party/click` | [`handle_first_party_click`] |
@@ -98,10 +102,14 @@ use edgezero_core::http::{
 };
 use edgezero_core::router::RouterService;
 use error_stack::Report;
+use trusted_server_core::access_log::AccessLogMiddleware;
 use trusted_server_core::auction::AuctionTelemetrySink;
+use trusted_server_core::auction::beacon::handle_event_beacon;
+use trusted_server_core::auction::bid_cache::{BidCacheWrites, flush_bid_cache_best_effort};
 use trusted_server_core::auction::endpoints::handle_auction;
 use trusted_server_core::auction::{AuctionOrchestrator, build_orchestrator};
 use trusted_server_core::cache_policy::EdgeCacheHeader;
+use trusted_server_core::config_release::handle_admin_config_release;
 use trusted_server_core::constants::{COOKIE_SHAREDID, COOKIE_TS_EIDS};
 use trusted_server_core::ec::EcContext;
 use trusted_server_core::ec::admin::{
@@ -114,19 +122,27 @@ use trusted_server_core::ec::identify::{cors_preflight_identify, handle_identify
 use trusted_server_core::ec::kv::KvIdentityGraph;
 use trusted_server_core::ec::registry::PartnerRegistry;
 use trusted_server_core::error::{IntoHttpResponse as _, TrustedServerError};
+use trusted_server_core::experiments::{ExperimentUnits, apply_experiments};
 use trusted_server_core::http_util::is_navigation_request;
 use trusted_server_core::integrations::{
     IntegrationRegistry, ProxyDispatchInput, RequestFilterEffects, RequestFilterRegistryInput,
     RequestFilterRegistryOutcome,
 };
-use trusted_server_core::platform::{ClientInfo, GeoInfo, PlatformKvStore, RuntimeServices};
+use trusted_server_core::metrics::{
+    MetricsRegistry, MetricsSink, admin_metrics_not_supported, flush_metrics_best_effort,
+    metrics_sink_from_settings,
+};
+use trusted_server_core::otel::{RequestTrace, TraceExporter, export_request_trace_best_effort};
+use trusted_server_core::platform::{
+    ClientInfo, GeoInfo, PlatformKvStore, RuntimeServices, RuntimeServicesBuilder,
+};
 use trusted_server_core::proxy::{
     AssetProxyCachePolicy, handle_asset_proxy_request, handle_first_party_click,
     handle_first_party_proxy, handle_first_party_proxy_rebuild, handle_first_party_proxy_sign,
 };
 use trusted_server_core::publisher::{
-    AuctionDispatch, PAGE_BIDS_LEGACY_PATH, PAGE_BIDS_PATH, handle_page_bids,
-    handle_publisher_request, handle_tsjs_dynamic, page_bids_preflight_denied,
+    AuctionDispatch, PAGE_BIDS_LEGACY_PATH, PAGE_BIDS_PATH, SLOT_AUCTION_PATH, handle_page_bids,
+    handle_publisher_request, handle_slot_auction, handle_tsjs_dynamic, page_bids_preflight_denied,
     publisher_response_into_streaming_response,
 };
 use trusted_server_core::request_signing::{
@@ -135,9 +151,13 @@ use trusted_server_core::request_signing::{
 };
 use trusted_server_core::settings::{ProxyAssetRoute, Settings};
 use trusted_server_core::settings_data::{
-    default_config_key, default_config_store_name, get_settings_from_config_store,
+    default_config_key, default_config_store_name, get_settings_for_rollout,
+    get_settings_from_config_store,
 };
 use trusted_server_core::tester_cookie::{handle_clear_tester, handle_set_tester};
+use trusted_server_core::third_party_log::{
+    ThirdPartyRequestLog, ThirdPartyRequestSink, flush_third_party_requests_best_effort,
+};
 
 use crate::middleware::{AuthMiddleware, FinalizeResponseMiddleware};
 use crate::platform::{
@@ -159,16 +179,38 @@ pub(crate) struct AppState {
     pub(crate) registry: Arc<IntegrationRegistry>,
     pub(crate) default_kv_store: Arc<dyn PlatformKvStore>,
     pub(crate) auction_telemetry_sink: Arc<dyn AuctionTelemetrySink>,
+    pub(crate) trace_exporter: Arc<dyn TraceExporter>,
+    pub(crate) metrics: Arc<MetricsRegistry>,
+    pub(crate) metrics_sink: Arc<dyn MetricsSink>,
+    pub(crate) third_party_log: Arc<ThirdPartyRequestLog>,
+    pub(crate) third_party_sink: Arc<dyn ThirdPartyRequestSink>,
+    pub(crate) bid_cache_writes: Arc<BidCacheWrites>,
 }
 
 /// Build the application state, loading settings and constructing all per-application components.
 ///
+/// `rollout_bucket` is the request's EC rollout bucket; it selects between the
+/// stable and staged config versions of a versioned release.
+/// `experiment_units` assigns the request to experiment arms, whose overrides
+/// are applied before any component is built from the settings.
+///
 /// # Errors
 ///
 /// Returns an error when settings, the auction orchestrator, or the integration
 /// registry fail to initialise.
-pub(crate) fn build_state() -> Result<Arc<AppState>, Report<TrustedServerError>> {
-    build_state_from_settings(load_settings_from_config_store()?)
+pub(crate) fn build_state(
+    rollout_bucket: Option<u8>,
+    experiment_units: &ExperimentUnits,
+) -> Result<Arc<AppState>, Report<TrustedServerError>> {
+    let store_name = default_config_store_name();
+    let config_key = default_config_key();
+    let settings = get_settings_for_rollout(
+        &FastlyPlatformConfigStore,
+        &store_name,
+        &config_key,
+        rollout_bucket,
+    )?;
+    build_state_from_settings(apply_experiments(settings, experiment_units))
 }
 
 pub(crate) fn load_settings_from_config_store() -> Result<Settings, Report<TrustedServerError>> {
@@ -186,6 +228,15 @@ pub(crate) fn build_state_from_settings(
     let registry = IntegrationRegistry::new(&settings)?;
 
     let auction_telemetry_sink = crate::tinybird::auction_sink_from_settings(&settings);
+    let trace_exporter = crate::otlp::trace_exporter_from_settings(&settings);
+    let metrics_sink = metrics_sink_from_settings(&settings);
+    let third_party_sink = crate::tinybird::third_party_sink_from_settings(&settings);
+    // One instance serves one request, so sampling here samples the request.
+    let third_party_log = if third_party_sink.is_enabled() {
+        ThirdPartyRequestLog::with_sample_rate(settings.tinybird.third_party_sample_rate)
+    } else {
+        ThirdPartyRequestLog::default()
+    };
     let default_kv_store = Arc::new(UnavailableKvStore) as Arc<dyn PlatformKvStore>;
 
     Ok(Arc::new(AppState {
@@ -194,6 +245,12 @@ pub(crate) fn build_state_from_settings(
         registry: Arc::new(registry),
         default_kv_store,
         auction_telemetry_sink,
+        trace_exporter,
+        metrics: Arc::new(MetricsRegistry::new()),
+        metrics_sink,
+        third_party_log: Arc::new(third_party_log),
+        third_party_sink,
+        bid_cache_writes: Arc::default(),
     }))
 }
 
@@ -235,6 +292,78 @@ pub(crate) fn runtime_services_for_consent_route(
         })
 }
 
+/// Resolves per-request services for `/first-party/proxy` when the creative
+/// cache is enabled.
+///
+/// Unlike consent, the cache is an optimisation: when the named KV store cannot
+/// be opened the proxy keeps working against upstream, so this logs and returns
+/// the unchanged services rather than failing the request.
+pub(crate) fn runtime_services_for_creative_cache_route(
+    settings: &Settings,
+    runtime_services: &RuntimeServices,
+) -> RuntimeServices {
+    let cache = &settings.proxy.creative_cache;
+    let Some(store_name) = cache.kv_store.as_deref().filter(|_| cache.enabled) else {
+        return runtime_services.clone();
+    };
+
+    match open_kv_store(store_name) {
+        Ok(store) => runtime_services.clone().with_kv_store(store),
+        Err(e) => {
+            log::warn!("creative cache KV store `{store_name}` unavailable: {e}");
+            runtime_services.clone()
+        }
+    }
+}
+
+/// Resolves per-request services for the event beacon route when
+/// `events.kv_store` is configured.
+///
+/// Deduplication needs the named store: without it every beacon fails to
+/// record, which withholds server-side notifications. When the store cannot be
+/// opened this logs and returns the unchanged services, so beacons are still
+/// recorded but notifications stay withheld rather than risk firing twice.
+pub(crate) fn runtime_services_for_event_route(
+    settings: &Settings,
+    runtime_services: &RuntimeServices,
+) -> RuntimeServices {
+    let events = &settings.events;
+    let Some(store_name) = events.kv_store.as_deref().filter(|_| events.enabled) else {
+        return runtime_services.clone();
+    };
+
+    match open_kv_store(store_name) {
+        Ok(store) => runtime_services.clone().with_kv_store(store),
+        Err(e) => {
+            log::warn!("events KV store `{store_name}` unavailable: {e}");
+            runtime_services.clone()
+        }
+    }
+}
+
+/// Attach the bid cache KV store to auction route services when
+/// `auction.bid_cache` is enabled.
+///
+/// Fails open like the creative cache: without the store the auction runs
+/// with fresh bids only.
+pub(crate) fn runtime_services_with_bid_cache(
+    settings: &Settings,
+    runtime_services: RuntimeServices,
+) -> RuntimeServices {
+    let cache = &settings.auction.bid_cache;
+    let Some(store_name) = cache.kv_store.as_deref().filter(|_| cache.enabled) else {
+        return runtime_services;
+    };
+
+    match open_kv_store(store_name) {
+        Ok(store) => runtime_services.with_bid_cache_store(store),
+        Err(e) => {
+            log::warn!("bid cache KV store `{store_name}` unavailable: {e}");
+            runtime_services
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Per-request RuntimeServices
 // ---------------------------------------------------------------------------
@@ -250,6 +379,9 @@ pub(crate) fn runtime_services_for_consent_route(
 /// absent (e.g. tests that dispatch without the entry point). Scheme detection
 /// continues to rely on the trusted `fastly-ssl` header injected by
 /// `edgezero_main` after sanitization.
+///
+/// The [`RequestTrace`] created by `edgezero_main` is read back the same way so
+/// spans recorded by handlers land in the trace exported after the send.
 fn build_per_request_services(state: &AppState, ctx: &RequestContext) -> RuntimeServices {
     let client_info = ctx
         .request()
@@ -260,7 +392,43 @@ fn build_per_request_services(state: &AppState, ctx: &RequestContext) -> Runtime
             client_ip: FastlyRequestContext::get(ctx.request()).and_then(|c| c.client_ip),
             ..ClientInfo::default()
         });
+    let request_trace = ctx
+        .request()
+        .extensions()
+        .get::<Arc<RequestTrace>>()
+        .cloned()
+        .unwrap_or_default();
+
+    base_services_builder(state)
+        .client_info(client_info)
+        .request_trace(request_trace)
+        .build()
+}
+
+/// Export the request's spans, flush its metrics and third-party request log,
+/// and write its queued bid cache updates once the response has been sent.
+///
+/// Runs outside the router, so services are rebuilt from `state` with the
+/// trace that `edgezero_main` inserted into the request extensions. Each Wasm
+/// instance serves one request, so the metrics registry, third-party log and
+/// bid cache queue are drained here rather than scraped.
+pub(crate) fn export_telemetry_after_send(state: &AppState, trace: Arc<RequestTrace>) {
+    let services = base_services_builder(state)
+        .request_trace(trace)
+        .client_info(ClientInfo::default())
+        .build();
+    futures::executor::block_on(async {
+        export_request_trace_best_effort(&services).await;
+        flush_metrics_best_effort(&services).await;
+        flush_third_party_requests_best_effort(&services).await;
+        if !state.bid_cache_writes.is_empty() {
+            let cache_services = runtime_services_with_bid_cache(&state.settings, services);
+            flush_bid_cache_best_effort(&state.settings.auction.bid_cache, &cache_services).await;
+        }
+    });
+}
 
+fn base_services_builder(state: &AppState) -> RuntimeServicesBuilder {
     RuntimeServices::builder()
         .config_store(Arc::new(FastlyPlatformConfigStore))
         .secret_store(Arc::new(FastlyPlatformSecretStore))
@@ -274,8 +442,12 @@ fn build_per_request_services(state: &AppState, ctx: &RequestContext) -> Runtime
         .http_client(Arc::new(FastlyPlatformHttpClient))
         .geo(Arc::new(FastlyPlatformGeo))
         .auction_telemetry_sink(Arc::clone(&state.auction_telemetry_sink))
-        .client_info(client_info)
-        .build()
+        .trace_exporter(Arc::clone(&state.trace_exporter))
+        .metrics(Arc::clone(&state.metrics))
+        .metrics_sink(Arc::clone(&state.metrics_sink))
+        .third_party_log(Arc::clone(&state.third_party_log))
+        .third_party_sink(Arc::clone(&state.third_party_sink))
+        .bid_cache_writes(Arc::clone(&state.bid_cache_writes))
 }
 
 fn publisher_fallback_methods() -> [Method; 7] {
@@ -430,7 +602,7 @@ fn build_ec_request_state(
     // Bot gate: suppress KV-backed EC writes for unrecognized clients, except
     // consent withdrawals. Revocations keep the write path so tombstones stay
     // authoritative even for privacy-extension-heavy clients.
-    let kv_graph = crate::maybe_identity_graph(settings);
+    let kv_graph = crate::maybe_identity_graph(settings, services.metrics());
     let finalize_kv_graph = if setup_error.is_none()
         && (is_real_browser || ec_consent_withdrawn(ec_context.consent()))
     {
@@ -538,6 +710,22 @@ async fn execute_named(
         return Ok(run_batch_sync(&state, &services, req));
     }
 
+    if matches!(handler, NamedRouteHandler::AdminMetrics) {
+        return Ok(admin_metrics_not_supported());
+    }
+
+    // Read-only like the diagnostics below; reads the config store entry the
+    // instance was loaded from rather than the settings it is serving.
+    if matches!(handler, NamedRouteHandler::AdminConfigRelease) {
+        let response = handle_admin_config_release(
+            &FastlyPlatformConfigStore,
+            &default_config_store_name(),
+            &default_config_key(),
+        )
+        .unwrap_or_else(|error| http_error(&error));
+        return Ok(response);
+    }
+
     // These diagnostics are read-only. Running the normal EC lifecycle would
     // attach finalization state and could ingest request cookies into KV after
     // the handler returns, violating that contract.
@@ -551,7 +739,7 @@ async fn execute_named(
                     // Deliberately do not use an EC request-state graph: that
                     // copy is bot-gated, while operators use curl for this
                     // authenticated diagnostic.
-                    let kv = crate::maybe_identity_graph(&state.settings);
+                    let kv = crate::maybe_identity_graph(&state.settings, &state.metrics);
                     handle_admin_ec_lookup(kv.as_ref(), &registry, &req)
                 }
                 NamedRouteHandler::AdminEidsLookup => handle_admin_eids_lookup(&registry, &req),
@@ -610,7 +798,10 @@ async fn run_named_route(
         }
         NamedRouteHandler::RotateKey => handle_rotate_key(&state.settings, services, req),
         NamedRouteHandler::DeactivateKey => handle_deactivate_key(&state.settings, services, req),
-        NamedRouteHandler::AdminEcLookup | NamedRouteHandler::AdminEidsLookup => {
+        NamedRouteHandler::AdminEcLookup
+        | NamedRouteHandler::AdminEidsLookup
+        | NamedRouteHandler::AdminMetrics
+        | NamedRouteHandler::AdminConfigRelease => {
             unreachable!("admin diagnostics should be handled before EC setup")
         }
         NamedRouteHandler::LegacyAdminDenied => Ok(legacy_admin_alias_denied()),
@@ -622,7 +813,7 @@ async fn run_named_route(
             if req.method() == Method::OPTIONS {
                 cors_preflight_identify(&state.settings, &req)
             } else {
-                let kv = crate::require_identity_graph(&state.settings)?;
+                let kv = crate::require_identity_graph(&state.settings, &state.metrics)?;
                 let partner_registry = PartnerRegistry::from_config(&state.settings.ec.partners)?;
                 handle_identify(
                     &state.settings,
@@ -633,13 +824,20 @@ async fn run_named_route(
                 )
             }
         }
+        NamedRouteHandler::EventBeacon => {
+            let event_services = runtime_services_for_event_route(&state.settings, services);
+            handle_event_beacon(&state.settings, &event_services, &ec.ec_context, req).await
+        }
         NamedRouteHandler::SetTester => handle_set_tester(&state.settings),
         NamedRouteHandler::ClearTester => handle_clear_tester(&state.settings),
         NamedRouteHandler::Auction => {
             // The auction reads consent data, so the consent KV store must be
             // available — fail closed with 503 when it is configured but
             // cannot be opened, matching legacy behavior.
-            let consent_services = runtime_services_for_consent_route(&state.settings, services)?;
+            let consent_services = runtime_services_with_bid_cache(
+                &state.settings,
+                runtime_services_for_consent_route(&state.settings, services)?,
+            );
             let partner_registry = PartnerRegistry::from_config(&state.settings.ec.partners)?;
             let registry_ref = if partner_registry.is_empty() {
                 None
@@ -657,17 +855,20 @@ async fn run_named_route(
             )
             .await
         }
-        NamedRouteHandler::PageBids => {
-            // SPA re-auction endpoint. `OPTIONS` is a CORS preflight for this
-            // side-effecting GET and is always denied so the GET handler's
-            // `X-TSJS-Page-Bids` gate stays trustworthy.
+        NamedRouteHandler::PageBids | NamedRouteHandler::SlotAuction => {
+            // SPA re-auction and slot-auction endpoints. `OPTIONS` is a CORS
+            // preflight for these side-effecting requests and is always denied
+            // so the handlers' `X-TSJS-*` gates stay trustworthy.
             if req.method() == Method::OPTIONS {
                 return Ok(page_bids_preflight_denied());
             }
-            // Like the auction, page-bids reads consent data, so the consent KV
+            // Like the auction, these endpoints read consent data, so the consent KV
             // store must be available — fail closed with 503 when configured but
             // unopenable, matching legacy.
-            let consent_services = runtime_services_for_consent_route(&state.settings, services)?;
+            let consent_services = runtime_services_with_bid_cache(
+                &state.settings,
+                runtime_services_for_consent_route(&state.settings, services)?,
+            );
             let partner_registry = PartnerRegistry::from_config(&state.settings.ec.partners)?;
             let registry_ref = if partner_registry.is_empty() {
                 None
@@ -679,18 +880,32 @@ async fn run_named_route(
                 slots: state.settings.creative_opportunity_slots(),
                 registry: registry_ref,
             };
-            handle_page_bids(
-                &state.settings,
-                &consent_services,
-                ec.kv_graph.as_ref(),
-                auction,
-                &ec.ec_context,
-                req,
-            )
-            .await
+            if matches!(handler, NamedRouteHandler::SlotAuction) {
+                handle_slot_auction(
+                    &state.settings,
+                    &consent_services,
+                    ec.kv_graph.as_ref(),
+                    auction,
+                    &ec.ec_context,
+                    req,
+                )
+                .await
+            } else {
+                handle_page_bids(
+                    &state.settings,
+                    &consent_services,
+                    ec.kv_graph.as_ref(),
+                    auction,
+                    &ec.ec_context,
+                    req,
+                )
+                .await
+            }
         }
         NamedRouteHandler::FirstPartyProxy => {
-            handle_first_party_proxy(&state.settings, services, req).await
+            let proxy_services =
+                runtime_services_for_creative_cache_route(&state.settings, services);
+            handle_first_party_proxy(&state.settings, &proxy_services, req).await
         }
         NamedRouteHandler::FirstPartyClick => {
             handle_first_party_click(&state.settings, services, req).await
@@ -713,7 +928,7 @@ fn run_batch_sync(state: &AppState, services: &RuntimeServices, req: Request) ->
     let eids_cookie = crate::extract_cookie_value(&req, COOKIE_TS_EIDS);
     let sharedid_cookie = crate::extract_cookie_value(&req, COOKIE_SHAREDID);
 
-    let result = crate::require_identity_graph(&state.settings).and_then(|kv| {
+    let result = crate::require_identity_graph(&state.settings, &state.metrics).and_then(|kv| {
         let partner_registry = PartnerRegistry::from_config(&state.settings.ec.partners)?;
         let limiter = FastlyRateLimiter::new(RATE_COUNTER_NAME);
         handle_batch_sync(&kv, &partner_registry, &limiter, req)
@@ -830,6 +1045,8 @@ async fn dispatch_fallback(
         // be opened, matching legacy behavior.
         match runtime_services_for_consent_route(&state.settings, services) {
             Ok(publisher_services) => {
+                let publisher_services =
+                    runtime_services_with_bid_cache(&state.settings, publisher_services);
                 // Run the server-side auction with the configured creative-
                 // opportunity slots and collect dispatched bids from the lazy
                 // publisher body stream. `handle_publisher_request` matches the
@@ -1047,15 +1264,19 @@ enum NamedRouteHandler {
     DeactivateKey,
     AdminEcLookup,
     AdminEidsLookup,
+    AdminMetrics,
+    AdminConfigRelease,
     /// Legacy `/admin/keys/*` aliases — denied locally with 404 so they never
     /// reach the publisher fallback (which would leak admin credentials).
     LegacyAdminDenied,
     BatchSync,
     Identify,
+    EventBeacon,
     SetTester,
     ClearTester,
     Auction,
     PageBids,
+    SlotAuction,
     FirstPartyProxy,
     FirstPartyClick,
     FirstPartySign,
@@ -1118,6 +1339,20 @@ const NAMED_ROUTES: &[NamedRoute] = &[
         primary_methods: &[Method::GET],
         handler: NamedRouteHandler::AdminEidsLookup,
     },
+    // Registered so the admin path never reaches the publisher fallback; the
+    // per-request registry is flushed to Tinybird instead of being scraped.
+    NamedRoute {
+        path: "/_ts/admin/metrics",
+        primary_methods: &[Method::GET],
+        handler: NamedRouteHandler::AdminMetrics,
+    },
+    // Serves the deployed release manifest to `ts config history`, `promote`,
+    // and `rollback`.
+    NamedRoute {
+        path: "/_ts/admin/config/release",
+        primary_methods: &[Method::GET],
+        handler: NamedRouteHandler::AdminConfigRelease,
+    },
     // The legacy non-`/_ts` aliases (`/admin/keys/*`) are denied locally with a
     // 404 instead of executing key operations: the production basic-auth handler
     // regex `^/_ts/admin` does not match them, and letting them fall through to
@@ -1144,6 +1379,11 @@ const NAMED_ROUTES: &[NamedRoute] = &[
         primary_methods: &[Method::GET, Method::OPTIONS],
         handler: NamedRouteHandler::Identify,
     },
+    NamedRoute {
+        path: "/_ts/api/v1/event",
+        primary_methods: &[Method::POST],
+        handler: NamedRouteHandler::EventBeacon,
+    },
     NamedRoute {
         path: "/_ts/set-tester",
         primary_methods: &[Method::GET],
@@ -1176,6 +1416,13 @@ const NAMED_ROUTES: &[NamedRoute] = &[
         primary_methods: &[Method::GET, Method::OPTIONS],
         handler: NamedRouteHandler::PageBids,
     },
+    // POST runs a lazy-load or refresh auction; OPTIONS is denied in-handler
+    // like page-bids.
+    NamedRoute {
+        path: SLOT_AUCTION_PATH,
+        primary_methods: &[Method::POST, Method::OPTIONS],
+        handler: NamedRouteHandler::SlotAuction,
+    },
     NamedRoute {
         path: "/first-party/proxy",
         primary_methods: &[Method::GET],
@@ -1228,15 +1475,21 @@ fn fallback_route_handler(
 pub struct TrustedServerApp;
 
 impl TrustedServerApp {
-    pub(crate) fn build_app_with_state() -> (App, Option<Arc<AppState>>) {
-        let (router, state) = Self::router_with_state();
+    pub(crate) fn build_app_with_state(
+        rollout_bucket: Option<u8>,
+        experiment_units: &ExperimentUnits,
+    ) -> (App, Option<Arc<AppState>>) {
+        let (router, state) = Self::router_with_state(rollout_bucket, experiment_units);
         let mut app = App::with_name(router, Self::name());
         Self::configure(&mut app);
         (app, state)
     }
 
-    fn router_with_state() -> (RouterService, Option<Arc<AppState>>) {
-        let state = match build_state() {
+    fn router_with_state(
+        rollout_bucket: Option<u8>,
+        experiment_units: &ExperimentUnits,
+    ) -> (RouterService, Option<Arc<AppState>>) {
+        let state = match build_state(rollout_bucket, experiment_units) {
             Ok(state) => state,
             Err(ref e) => {
                 log::error!("failed to build application state: {:?}", e);
@@ -1248,7 +1501,12 @@ impl TrustedServerApp {
     }
 
     fn routes_for_state(state: &Arc<AppState>) -> RouterService {
+        let access_log_state = Arc::clone(state);
         let mut router = RouterService::builder()
+            .middleware(AccessLogMiddleware::new(
+                Arc::clone(&state.settings),
+                move |ctx: &RequestContext| build_per_request_services(&access_log_state, ctx),
+            ))
             .middleware(FinalizeResponseMiddleware::new(
                 Arc::clone(&state.settings),
                 Arc::new(FastlyPlatformGeo),
@@ -1294,7 +1552,7 @@ impl Hooks for TrustedServerApp {
     }
 
     fn routes() -> RouterService {
-        Self::router_with_state().0
+        Self::router_with_state(None, &ExperimentUnits::default()).0
     }
 }
 
@@ -1321,6 +1579,7 @@ mod tests {
     use error_stack::Report;
     use futures::executor::block_on;
     use serde_json::json;
+    use trusted_server_core::auction::beacon::mint_event_token_for_test;
     use trusted_server_core::constants::HEADER_X_GEO_INFO_AVAILABLE;
     use trusted_server_core::ec::device::DeviceSignals;
     use trusted_server_core::error::TrustedServerError;
@@ -1494,6 +1753,14 @@ mod tests {
             auction_telemetry_sink: Arc::new(
                 trusted_server_core::auction::NoopAuctionTelemetrySink,
             ),
+            trace_exporter: Arc::new(trusted_server_core::otel::NoopTraceExporter),
+            metrics: Arc::new(trusted_server_core::metrics::MetricsRegistry::new()),
+            metrics_sink: Arc::new(trusted_server_core::metrics::NoopMetricsSink),
+            third_party_log: Arc::default(),
+            third_party_sink: Arc::new(
+                trusted_server_core::third_party_log::NoopThirdPartyRequestSink,
+            ),
+            bid_cache_writes: Arc::default(),
             settings: Arc::new(settings),
             orchestrator: Arc::new(orchestrator),
             registry: Arc::new(registry),
@@ -1830,6 +2097,26 @@ mod tests {
         }
     }
 
+    #[test]
+    fn slot_auction_handles_post_and_denies_preflight_in_handler() {
+        // Literal path for the same reason as the page-bids test: tsjs
+        // hardcodes it, so a const rename must not pass silently.
+        let route = NAMED_ROUTES
+            .iter()
+            .find(|route| route.path == "/_ts/api/v1/slot-auction")
+            .expect("should register the slot-auction route");
+
+        assert!(
+            matches!(route.handler, NamedRouteHandler::SlotAuction),
+            "slot-auction path must map to the slot-auction handler"
+        );
+        assert_eq!(
+            route.primary_methods,
+            &[Method::POST, Method::OPTIONS],
+            "slot-auction must handle POST and OPTIONS directly, not fall through to the publisher"
+        );
+    }
+
     #[test]
     fn legacy_admin_aliases_denied_locally_not_proxied_to_publisher() {
         // Regression for the credential-leak finding: with a production-shaped
@@ -2692,6 +2979,102 @@ mod tests {
         );
     }
 
+    /// Records the URI of every fire-and-forget request and reports it as
+    /// unsupported, like a notification that never resolves.
+    #[derive(Default)]
+    struct RecordingNotificationClient {
+        uris: Mutex<Vec<String>>,
+    }
+
+    #[async_trait::async_trait(?Send)]
+    impl PlatformHttpClient for RecordingNotificationClient {
+        async fn send(
+            &self,
+            _request: PlatformHttpRequest,
+        ) -> Result<PlatformResponse, Report<PlatformError>> {
+            Err(Report::new(PlatformError::Unsupported))
+        }
+
+        async fn send_async(
+            &self,
+            request: PlatformHttpRequest,
+        ) -> Result<PlatformPendingRequest, Report<PlatformError>> {
+            self.uris
+                .lock()
+                .expect("should lock recorded URIs")
+                .push(request.request.uri().to_string());
+            Err(Report::new(PlatformError::Unsupported))
+        }
+
+        async fn select(
+            &self,
+            _pending_requests: Vec<PlatformPendingRequest>,
+        ) -> Result<PlatformSelectResult, Report<PlatformError>> {
+            Err(Report::new(PlatformError::Unsupported))
+        }
+    }
+
+    #[test]
+    fn event_beacon_fires_notifications_through_the_events_store() {
+        // Regression guard: the beacon route must open `events.kv_store`
+        // rather than use the per-request `UnavailableKvStore`, otherwise every
+        // first-use claim fails and server-side nurl/burl are never fired.
+        let mut settings = test_settings();
+        settings.events.enabled = true;
+        settings.events.server_side_notifications = true;
+        settings.events.kv_store = Some("events_store".to_owned());
+        let state = build_state_from_settings(settings).expect("should build state");
+        let http_client = Arc::new(RecordingNotificationClient::default());
+        let services = RuntimeServices::builder()
+            .config_store(Arc::new(crate::platform::FastlyPlatformConfigStore))
+            .secret_store(Arc::new(crate::platform::FastlyPlatformSecretStore))
+            .kv_store(Arc::new(crate::platform::UnavailableKvStore) as Arc<dyn PlatformKvStore>)
+            .backend(Arc::new(FixedBackend))
+            .http_client(Arc::clone(&http_client) as Arc<dyn PlatformHttpClient>)
+            .geo(Arc::new(crate::platform::FastlyPlatformGeo))
+            .client_info(ClientInfo::default())
+            .build();
+        let token = mint_event_token_for_test(
+            &state.settings,
+            "atf",
+            Some("https://ssp.example/win?price=1.5"),
+            Some("https://ssp.example/bill?price=1.5"),
+        );
+        let beacon = || {
+            request_builder()
+                .method(Method::POST)
+                .uri("https://test-publisher.com/_ts/api/v1/event")
+                .header(header::CONTENT_TYPE, "text/plain;charset=UTF-8")
+                .body(Body::from(
+                    json!({ "type": "render", "token": token, "slot": "atf" }).to_string(),
+                ))
+                .expect("should build beacon request")
+        };
+
+        for _ in 0..2 {
+            let req = beacon();
+            let mut ec = super::build_ec_request_state(&state.settings, &services, &req);
+            let response = block_on(super::run_named_route(
+                &state,
+                &services,
+                req,
+                NamedRouteHandler::EventBeacon,
+                &mut ec,
+            ))
+            .expect("should accept render beacon");
+            assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        }
+
+        assert_eq!(
+            *http_client.uris.lock().expect("should lock recorded URIs"),
+            vec![
+                "https://ssp.example/win?price=1.5".to_owned(),
+                "https://ssp.example/bill?price=1.5".to_owned(),
+            ],
+            "should fire nurl and burl once, and not again for the replay"
+        );
+    }
+
     #[test]
     fn dispatch_runs_request_filter_and_threads_response_effects() {
         // Regression guard for the EdgeZero request-filter bypass: the publisher
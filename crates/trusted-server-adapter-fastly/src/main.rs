@@ -5,13 +5,15 @@ use edgezero_adapter_fastly::request::into_core_request;
 use edgezero_core::body::Body as EdgeBody;
 use edgezero_core::config_store::ConfigStoreHandle;
 use edgezero_core::error::EdgeError;
-use edgezero_core::http::{Request as HttpRequest, Response as HttpResponse};
+use edgezero_core::http::{HeaderValue, Request as HttpRequest, Response as HttpResponse, header};
 use edgezero_core::response::IntoResponse;
 use error_stack::Report;
 use fastly::http::Method as FastlyMethod;
 use fastly::{Request as FastlyRequest, Response as FastlyResponse};
 
 use trusted_server_core::cache_policy::EdgeCacheHeader;
+use trusted_server_core::config_release::rollout_bucket_from_cookie_header;
+use trusted_server_core::consent::ConsentContext;
 use trusted_server_core::ec::device::DeviceSignals;
 use trusted_server_core::ec::finalize::ec_finalize_response;
 use trusted_server_core::ec::kv::KvIdentityGraph;
@@ -20,7 +22,10 @@ use trusted_server_core::ec::pull_sync::{
 };
 use trusted_server_core::ec::registry::PartnerRegistry;
 use trusted_server_core::error::TrustedServerError;
+use trusted_server_core::experiments::{ExperimentUnits, experiment_set_cookies};
 use trusted_server_core::integrations::RequestFilterEffects;
+use trusted_server_core::metrics::{MetricsRegistry, record_http_request};
+use trusted_server_core::otel::RequestTrace;
 use trusted_server_core::platform::PlatformGeo as _;
 use trusted_server_core::platform::RuntimeServices;
 use trusted_server_core::proxy::{AssetProxyCachePolicy, stream_asset_body};
@@ -35,12 +40,16 @@ mod esi_assembly;
 mod logging;
 mod management_api;
 mod middleware;
+mod otlp;
 mod platform;
 mod rate_limiter;
 mod template_cache;
 mod tinybird;
 
-use crate::app::{EcFinalizeState, TrustedServerApp, load_settings_from_config_store};
+use crate::app::{
+    AppState, EcFinalizeState, TrustedServerApp, export_telemetry_after_send,
+    load_settings_from_config_store,
+};
 use crate::ec_kv::FastlyEcKvStore;
 use crate::middleware::{HEADER_X_TS_FINALIZED, apply_finalize_headers, resolve_geo_for_response};
 use crate::platform::{FastlyPlatformGeo, client_info_from_request};
@@ -122,7 +131,13 @@ fn edgezero_main(mut req: FastlyRequest) {
         }
     };
 
-    let (app, app_state) = TrustedServerApp::build_app_with_state();
+    // Staged config rollouts and experiment arms are assigned from request
+    // cookies, so both must be known before settings are loaded.
+    let cookie_header = req.get_header_str("cookie");
+    let rollout_bucket = cookie_header.and_then(rollout_bucket_from_cookie_header);
+    let experiment_units = ExperimentUnits::from_cookie_header(cookie_header);
+    let (app, app_state) =
+        TrustedServerApp::build_app_with_state(rollout_bucket, &experiment_units);
     let settings_snapshot = app_state.as_ref().map(|state| Arc::clone(&state.settings));
 
     // Strip client-spoofable forwarded headers before dispatch.
@@ -163,19 +178,8 @@ fn edgezero_main(mut req: FastlyRequest) {
     let client_info = client_info_from_request(&req);
     let device_signals = derive_device_signals(&req);
 
-    // Dispatch directly through the EdgeZero router without an intermediate
-    // fastly::Response conversion. That preserves duplicate header values such
-    // as multiple Set-Cookie headers.
-    let mut response = match into_core_request(req) {
-        Ok(mut core_req) => {
-            core_req.extensions_mut().insert(config_store);
-            core_req.extensions_mut().insert(device_signals);
-            core_req.extensions_mut().insert(client_info);
-            match futures::executor::block_on(app.router().oneshot(core_req)) {
-                Ok(response) => response,
-                Err(error) => edge_error_response(error),
-            }
-        }
+    let mut core_req = match into_core_request(req) {
+        Ok(core_req) => core_req,
         Err(e) => {
             log::error!("EdgeZero request conversion failed: {e}");
             FastlyResponse::from_status(fastly::http::StatusCode::INTERNAL_SERVER_ERROR)
@@ -185,8 +189,42 @@ fn edgezero_main(mut req: FastlyRequest) {
         }
     };
 
+    // Start the request trace before dispatch so handlers record spans into
+    // it. The guard exports it and flushes metrics on every exit path below,
+    // after the response (including any held streaming body) has been sent.
+    let telemetry_export = app_state.as_ref().map(|state| {
+        let trace = Arc::new(RequestTrace::from_request_headers(
+            core_req.headers(),
+            &state.settings.tracing,
+        ));
+        core_req.extensions_mut().insert(Arc::clone(&trace));
+        TelemetryExportOnDrop {
+            state: Arc::clone(state),
+            trace,
+        }
+    });
+    let request_path = core_req.uri().path().to_owned();
+
+    // Dispatch directly through the EdgeZero router without an intermediate
+    // fastly::Response conversion. That preserves duplicate header values such
+    // as multiple Set-Cookie headers.
+    core_req.extensions_mut().insert(config_store);
+    core_req.extensions_mut().insert(device_signals);
+    core_req.extensions_mut().insert(client_info);
+    let mut response = match futures::executor::block_on(app.router().oneshot(core_req)) {
+        Ok(response) => response,
+        Err(error) => edge_error_response(error),
+    };
+    if let Some(guard) = &telemetry_export {
+        record_http_request(&guard.state.metrics, &request_path, response.status());
+    }
+
     // Pop response extensions before the Fastly conversion, which drops them.
     let ec_state = response.extensions_mut().remove::<EcFinalizeState>();
+    if let Some(settings) = settings_snapshot.as_deref() {
+        let consent = ec_state.as_ref().map(|state| state.ec_context.consent());
+        append_experiment_cookies(settings, &experiment_units, consent, &mut response);
+    }
     let asset_cache_policy = response.extensions_mut().remove::<AssetProxyCachePolicy>();
     let request_filter_effects = response.extensions_mut().remove::<RequestFilterEffects>();
 
@@ -253,6 +291,38 @@ fn edgezero_main(mut req: FastlyRequest) {
     send_edgezero_response(response, request_filter_effects.as_ref());
 }
 
+/// Exports the request trace and flushes metrics when `edgezero_main` returns.
+struct TelemetryExportOnDrop {
+    state: Arc<AppState>,
+    trace: Arc<RequestTrace>,
+}
+
+impl Drop for TelemetryExportOnDrop {
+    fn drop(&mut self) {
+        export_telemetry_after_send(&self.state, Arc::clone(&self.trace));
+    }
+}
+
+/// Persists the request's experiment unit and arms so tsjs can read them.
+///
+/// `consent` is `None` on routes that did not evaluate EC consent, which
+/// keeps the unit cookie unset.
+fn append_experiment_cookies(
+    settings: &Settings,
+    experiment_units: &ExperimentUnits,
+    consent: Option<&ConsentContext>,
+    response: &mut HttpResponse,
+) {
+    for cookie in experiment_set_cookies(settings, experiment_units, consent) {
+        match HeaderValue::from_str(&cookie) {
+            Ok(value) => {
+                response.headers_mut().append(header::SET_COOKIE, value);
+            }
+            Err(e) => log::warn!("experiment cookie skipped: invalid header value: {e}"),
+        }
+    }
+}
+
 fn edge_error_response(error: EdgeError) -> HttpResponse {
     log::error!("EdgeZero router returned error: {error:?}");
     match error.into_response() {
@@ -295,7 +365,7 @@ fn apply_edgezero_ec_finalize(
 ) -> Result<PartnerRegistry, Report<TrustedServerError>> {
     let partner_registry = PartnerRegistry::from_config(&settings.ec.partners)?;
     let finalize_kv_graph = if ec_state.use_finalize_kv {
-        maybe_identity_graph(settings)
+        maybe_identity_graph(settings, ec_state.services.metrics())
     } else {
         None
     };
@@ -432,12 +502,13 @@ fn build_ja4_debug_response(req: &FastlyRequest) -> FastlyResponse {
         .with_body(body)
 }
 
-pub(crate) fn maybe_identity_graph(settings: &Settings) -> Option<KvIdentityGraph> {
-    settings
-        .ec
-        .ec_store
-        .as_ref()
-        .map(|store_name| KvIdentityGraph::new(FastlyEcKvStore::new(store_name)))
+pub(crate) fn maybe_identity_graph(
+    settings: &Settings,
+    metrics: &Arc<MetricsRegistry>,
+) -> Option<KvIdentityGraph> {
+    settings.ec.ec_store.as_ref().map(|store_name| {
+        KvIdentityGraph::new(FastlyEcKvStore::new(store_name)).with_metrics(Arc::clone(metrics))
+    })
 }
 
 fn run_pull_sync_after_send(
@@ -446,7 +517,7 @@ fn run_pull_sync_after_send(
     context: &PullSyncContext,
     services: &RuntimeServices,
 ) {
-    let kv = match require_identity_graph(settings) {
+    let kv = match require_identity_graph(settings, services.metrics()) {
         Ok(kv) => kv,
         Err(err) => {
             log::debug!("Pull sync: identity graph unavailable, skipping: {err:?}");
@@ -462,6 +533,7 @@ fn run_pull_sync_after_send(
 /// `ec_store` config is not set.
 pub(crate) fn require_identity_graph(
     settings: &Settings,
+    metrics: &Arc<MetricsRegistry>,
 ) -> Result<KvIdentityGraph, Report<TrustedServerError>> {
     let store_name = settings.ec.ec_store.as_deref().ok_or_else(|| {
         Report::new(TrustedServerError::KvStore {
@@ -469,7 +541,7 @@ pub(crate) fn require_identity_graph(
             message: "ec.ec_store is not configured".to_owned(),
         })
     })?;
-    Ok(KvIdentityGraph::new(FastlyEcKvStore::new(store_name)))
+    Ok(KvIdentityGraph::new(FastlyEcKvStore::new(store_name)).with_metrics(Arc::clone(metrics)))
 }
 
 /// Extracts a named cookie value from the request's `Cookie` header.
@@ -0,0 +1,479 @@
+//! OTLP/HTTP JSON trace exporter for the Fastly adapter.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use edgezero_core::body::Body;
+use edgezero_core::http::{HeaderName, HeaderValue, Method, header, request_builder};
+use error_stack::{Report, ResultExt as _};
+use trusted_server_core::error::TrustedServerError;
+use trusted_server_core::otel::{NoopTraceExporter, SpanBatch, TraceExporter};
+use trusted_server_core::platform::{
+    PlatformBackendSpec, PlatformHttpRequest, RuntimeServices, StoreName,
+};
+use trusted_server_core::settings::{Settings, TracingSettings};
+use url::Url;
+
+const OTLP_JSON_CONTENT_TYPE: &str = "application/json";
+const OTLP_FIRST_BYTE_TIMEOUT: Duration = Duration::from_secs(2);
+const OTLP_BETWEEN_BYTES_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Build the configured trace exporter.
+#[must_use]
+pub(crate) fn trace_exporter_from_settings(settings: &Settings) -> Arc<dyn TraceExporter> {
+    if !settings.tracing.enabled {
+        return Arc::new(NoopTraceExporter);
+    }
+    match FastlyOtlpTraceExporter::new(&settings.tracing) {
+        Ok(exporter) => Arc::new(exporter),
+        Err(e) => {
+            log::warn!("trace export disabled: {e:?}");
+            Arc::new(NoopTraceExporter)
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct FastlyOtlpTraceExporter {
+    service_name: String,
+    uri: String,
+    host: String,
+    secret_store: StoreName,
+    auth_header: HeaderName,
+    auth_token_secret: Option<String>,
+    backend_spec: PlatformBackendSpec,
+}
+
+impl FastlyOtlpTraceExporter {
+    fn new(config: &TracingSettings) -> Result<Self, Report<TrustedServerError>> {
+        let uri = config.traces_url();
+        let parsed = Url::parse(&uri).change_context(TrustedServerError::Configuration {
+            message: "tracing.endpoint is not a valid URL".to_owned(),
+        })?;
+        let host = parsed
+            .host_str()
+            .ok_or_else(|| {
+                Report::new(TrustedServerError::Configuration {
+                    message: "tracing.endpoint is missing a host".to_owned(),
+                })
+            })?
+            .to_owned();
+        let auth_header = HeaderName::from_bytes(config.auth_header.as_bytes()).change_context(
+            TrustedServerError::Configuration {
+                message: "tracing.auth_header is not a valid header name".to_owned(),
+            },
+        )?;
+        let backend_spec = otlp_backend_spec(&host, parsed.port());
+        Ok(Self {
+            service_name: config.service_name.clone(),
+            uri,
+            host,
+            secret_store: StoreName::from(config.secret_store.clone()),
+            auth_header,
+            auth_token_secret: config.auth_token_secret.clone(),
+            backend_spec,
+        })
+    }
+
+    fn load_auth_value(
+        &self,
+        services: &RuntimeServices,
+    ) -> Result<Option<HeaderValue>, Report<TrustedServerError>> {
+        let Some(secret) = self.auth_token_secret.as_deref() else {
+            return Ok(None);
+        };
+        let token = services
+            .secret_store()
+            .get_string(&self.secret_store, secret)
+            .change_context(TrustedServerError::Proxy {
+                message: "OTLP collector credential unavailable".to_owned(),
+            })?;
+        let token = token.trim();
+        if token.is_empty() {
+            return Err(Report::new(TrustedServerError::Proxy {
+                message: "OTLP collector credential is empty".to_owned(),
+            }));
+        }
+        HeaderValue::from_str(token).map(Some).change_context(
+            TrustedServerError::InvalidHeaderValue {
+                message: "invalid OTLP collector credential header".to_owned(),
+            },
+        )
+    }
+
+    fn ensure_backend(
+        &self,
+        services: &RuntimeServices,
+    ) -> Result<String, Report<TrustedServerError>> {
+        services
+            .backend()
+            .ensure(&self.backend_spec)
+            .change_context(TrustedServerError::Proxy {
+                message: "OTLP collector backend registration failed".to_owned(),
+            })
+    }
+
+    fn build_traces_request(
+        &self,
+        body: String,
+        auth_value: Option<HeaderValue>,
+    ) -> Result<edgezero_core::http::Request, Report<TrustedServerError>> {
+        let mut builder = request_builder()
+            .method(Method::POST)
+            .uri(self.uri.as_str())
+            .header(header::CONTENT_TYPE, OTLP_JSON_CONTENT_TYPE);
+        if let Some(auth_value) = auth_value {
+            builder = builder.header(self.auth_header.clone(), auth_value);
+        }
+        builder
+            .body(Body::from(body))
+            .change_context(TrustedServerError::Proxy {
+                message: "failed to build OTLP traces request".to_owned(),
+            })
+    }
+
+    async fn send_fire_and_forget(
+        services: &RuntimeServices,
+        request: edgezero_core::http::Request,
+        backend_name: String,
+    ) -> Result<(), Report<TrustedServerError>> {
+        let pending = services
+            .http_client()
+            .send_async(PlatformHttpRequest::new(request, backend_name))
+            .await
+            .change_context(TrustedServerError::Proxy {
+                message: "failed to start OTLP traces request".to_owned(),
+            })?;
+        drop(pending);
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl TraceExporter for FastlyOtlpTraceExporter {
+    async fn export_spans(
+        &self,
+        services: &RuntimeServices,
+        batch: SpanBatch,
+    ) -> Result<(), Report<TrustedServerError>> {
+        if batch.is_empty() {
+            return Ok(());
+        }
+
+        let body = batch.to_otlp_json(&self.service_name).to_string();
+        let auth_value = self.load_auth_value(services)?;
+        let backend_name = self.ensure_backend(services)?;
+        let request = self.build_traces_request(body, auth_value)?;
+
+        log::debug!(
+            "exporting {} span(s) to OTLP collector host={} backend={}",
+            batch.spans().len(),
+            self.host,
+            backend_name
+        );
+
+        Self::send_fire_and_forget(services, request, backend_name).await
+    }
+}
+
+fn otlp_backend_spec(host: &str, port: Option<u16>) -> PlatformBackendSpec {
+    PlatformBackendSpec {
+        scheme: "https".to_owned(),
+        host: host.to_owned(),
+        port,
+        host_header_override: None,
+        certificate_check: true,
+        first_byte_timeout: OTLP_FIRST_BYTE_TIMEOUT,
+        between_bytes_timeout: OTLP_BETWEEN_BYTES_TIMEOUT,
+        discriminator: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    use edgezero_core::http::HeaderMap;
+    use error_stack::Report;
+    use trusted_server_core::otel::{RequestTrace, SpanKind};
+    use trusted_server_core::platform::{
+        ClientInfo, PlatformBackend, PlatformConfigStore, PlatformError, PlatformGeo,
+        PlatformHttpClient, PlatformPendingRequest, PlatformResponse, PlatformSecretStore,
+        PlatformSelectResult, RuntimeServices, StoreId,
+    };
+
+    use super::*;
+
+    struct NoopConfigStore;
+
+    impl PlatformConfigStore for NoopConfigStore {
+        fn get(
+            &self,
+            _store_name: &StoreName,
+            _key: &str,
+        ) -> Result<String, Report<PlatformError>> {
+            Err(Report::new(PlatformError::Unsupported))
+        }
+
+        fn put(
+            &self,
+            _store_id: &StoreId,
+            _key: &str,
+            _value: &str,
+        ) -> Result<(), Report<PlatformError>> {
+            Err(Report::new(PlatformError::Unsupported))
+        }
+
+        fn delete(&self, _store_id: &StoreId, _key: &str) -> Result<(), Report<PlatformError>> {
+            Err(Report::new(PlatformError::Unsupported))
+        }
+    }
+
+    struct MapSecretStore(HashMap<String, Vec<u8>>);
+
+    impl PlatformSecretStore for MapSecretStore {
+        fn get_bytes(
+            &self,
+            _store_name: &StoreName,
+            key: &str,
+        ) -> Result<Vec<u8>, Report<PlatformError>> {
+            self.0
+                .get(key)
+                .cloned()
+                .ok_or_else(|| Report::new(PlatformError::SecretStore))
+        }
+
+        fn create(
+            &self,
+            _store_id: &StoreId,
+            _name: &str,
+            _value: &str,
+        ) -> Result<(), Report<PlatformError>> {
+            Err(Report::new(PlatformError::Unsupported))
+        }
+
+        fn delete(&self, _store_id: &StoreId, _name: &str) -> Result<(), Report<PlatformError>> {
+            Err(Report::new(PlatformError::Unsupported))
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingBackend {
+        specs: Mutex<Vec<PlatformBackendSpec>>,
+    }
+
+    impl PlatformBackend for RecordingBackend {
+        fn predict_name(
+            &self,
+            _spec: &PlatformBackendSpec,
+        ) -> Result<String, Report<PlatformError>> {
+            Ok("otlp-backend".to_owned())
+        }
+
+        fn ensure(&self, spec: &PlatformBackendSpec) -> Result<String, Report<PlatformError>> {
+            self.specs
+                .lock()
+                .expect("should lock backend specs")
+                .push(spec.clone());
+            Ok("otlp-backend".to_owned())
+        }
+    }
+
+    #[derive(Debug)]
+    struct RecordedRequest {
+        uri: String,
+        headers: Vec<(String, String)>,
+        body: Vec<u8>,
+    }
+
+    #[derive(Default)]
+    struct RecordingHttpClient {
+        requests: Mutex<Vec<RecordedRequest>>,
+    }
+
+    #[async_trait::async_trait(?Send)]
+    impl PlatformHttpClient for RecordingHttpClient {
+        async fn send(
+            &self,
+            _request: PlatformHttpRequest,
+        ) -> Result<PlatformResponse, Report<PlatformError>> {
+            Err(Report::new(PlatformError::Unsupported))
+        }
+
+        async fn send_async(
+            &self,
+            request: PlatformHttpRequest,
+        ) -> Result<PlatformPendingRequest, Report<PlatformError>> {
+            let (parts, body) = request.request.into_parts();
+            let headers = parts
+                .headers
+                .iter()
+                .filter_map(|(name, value)| {
+                    value
+                        .to_str()
+                        .ok()
+                        .map(|value| (name.as_str().to_owned(), value.to_owned()))
+                })
+                .collect();
+            self.requests
+                .lock()
+                .expect("should lock recorded requests")
+                .push(RecordedRequest {
+                    uri: parts.uri.to_string(),
+                    headers,
+                    body: body.into_bytes().unwrap_or_default().to_vec(),
+                });
+            Ok(PlatformPendingRequest::new(()).with_backend_name("otlp-backend"))
+        }
+
+        async fn select(
+            &self,
+            _pending_requests: Vec<PlatformPendingRequest>,
+        ) -> Result<PlatformSelectResult, Report<PlatformError>> {
+            Err(Report::new(PlatformError::Unsupported))
+        }
+    }
+
+    struct NoopGeo;
+
+    impl PlatformGeo for NoopGeo {
+        fn lookup(
+            &self,
+            _client_ip: Option<std::net::IpAddr>,
+        ) -> Result<Option<trusted_server_core::platform::GeoInfo>, Report<PlatformError>> {
+            Ok(None)
+        }
+    }
+
+    fn services(
+        backend: Arc<RecordingBackend>,
+        http_client: Arc<RecordingHttpClient>,
+        secrets: HashMap<String, Vec<u8>>,
+    ) -> RuntimeServices {
+        RuntimeServices::builder()
+            .config_store(Arc::new(NoopConfigStore))
+            .secret_store(Arc::new(MapSecretStore(secrets)))
+            .kv_store(Arc::new(edgezero_core::key_value_store::NoopKvStore))
+            .backend(backend)
+            .http_client(http_client)
+            .geo(Arc::new(NoopGeo))
+            .client_info(ClientInfo::default())
+            .build()
+    }
+
+    fn enabled_config() -> TracingSettings {
+        TracingSettings {
+            enabled: true,
+            endpoint: "https://otlp.example.com:4318".to_owned(),
+            sample_rate: 1.0,
+            auth_header: "x-collector-key".to_owned(),
+            auth_token_secret: Some("otlp_collector_key".to_owned()),
+            ..TracingSettings::default()
+        }
+    }
+
+    fn sampled_batch(config: &TracingSettings) -> SpanBatch {
+        let trace = Arc::new(RequestTrace::from_request_headers(
+            &HeaderMap::new(),
+            config,
+        ));
+        trace.start_span("origin.fetch", SpanKind::Client).end();
+        trace.finish().expect("should finish a sampled trace")
+    }
+
+    fn header_value<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+        headers
+            .iter()
+            .find(|(header_name, _)| header_name == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    #[test]
+    fn backend_spec_keeps_explicit_collector_port() {
+        let exporter =
+            FastlyOtlpTraceExporter::new(&enabled_config()).expect("should build exporter");
+        assert_eq!(exporter.backend_spec.host, "otlp.example.com");
+        assert_eq!(exporter.backend_spec.port, Some(4318));
+        assert!(
+            exporter.backend_spec.certificate_check,
+            "should verify collector TLS cert"
+        );
+        assert_eq!(exporter.uri, "https://otlp.example.com:4318/v1/traces");
+    }
+
+    #[test]
+    fn exporter_posts_otlp_json_with_secret_header() {
+        let backend = Arc::new(RecordingBackend::default());
+        let http_client = Arc::new(RecordingHttpClient::default());
+        let services = services(
+            Arc::clone(&backend),
+            Arc::clone(&http_client),
+            HashMap::from([("otlp_collector_key".to_owned(), b" key-123\n".to_vec())]),
+        );
+        let config = enabled_config();
+        let exporter = FastlyOtlpTraceExporter::new(&config).expect("should build exporter");
+
+        futures::executor::block_on(exporter.export_spans(&services, sampled_batch(&config)))
+            .expect("should start OTLP request");
+
+        assert_eq!(
+            backend.specs.lock().expect("should lock specs").len(),
+            1,
+            "should ensure one backend"
+        );
+        let requests = http_client
+            .requests
+            .lock()
+            .expect("should lock recorded requests");
+        assert_eq!(requests.len(), 1, "should start one async POST");
+        assert_eq!(requests[0].uri, "https://otlp.example.com:4318/v1/traces");
+        assert_eq!(
+            header_value(&requests[0].headers, "x-collector-key"),
+            Some("key-123")
+        );
+        assert_eq!(
+            header_value(&requests[0].headers, header::CONTENT_TYPE.as_str()),
+            Some(OTLP_JSON_CONTENT_TYPE)
+        );
+        let body: serde_json::Value =
+            serde_json::from_slice(&requests[0].body).expect("should send JSON body");
+        assert!(
+            body["resourceSpans"][0]["scopeSpans"][0]["spans"].is_array(),
+            "should send OTLP resourceSpans"
+        );
+    }
+
+    #[test]
+    fn exporter_without_credential_sends_no_auth_header() {
+        let backend = Arc::new(RecordingBackend::default());
+        let http_client = Arc::new(RecordingHttpClient::default());
+        let services = services(
+            Arc::clone(&backend),
+            Arc::clone(&http_client),
+            HashMap::new(),
+        );
+        let mut config = enabled_config();
+        config.auth_token_secret = None;
+        let exporter = FastlyOtlpTraceExporter::new(&config).expect("should build exporter");
+
+        futures::executor::block_on(exporter.export_spans(&services, sampled_batch(&config)))
+            .expect("should start OTLP request");
+
+        let requests = http_client
+            .requests
+            .lock()
+            .expect("should lock recorded requests");
+        assert_eq!(requests.len(), 1, "should start one async POST");
+        assert_eq!(header_value(&requests[0].headers, "x-collector-key"), None);
+    }
+
+    #[test]
+    fn disabled_tracing_builds_noop_exporter() {
+        let settings = Settings::default();
+        assert!(
+            !trace_exporter_from_settings(&settings).is_enabled(),
+            "should not export when tracing is disabled"
+        );
+    }
+}
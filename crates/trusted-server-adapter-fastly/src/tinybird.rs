@@ -1,24 +1,22 @@
 //! Tinybird direct-ingest telemetry sink for the Fastly adapter.
+//!
+//! The metrics sink is shared with Spin and lives in
+//! [`trusted_server_core::metrics`].
 
 use std::sync::Arc;
-use std::time::Duration;
 
-use edgezero_core::body::Body;
-use edgezero_core::http::{HeaderValue, Method, header, request_builder};
-use error_stack::{Report, ResultExt as _};
+use error_stack::Report;
 use trusted_server_core::auction::telemetry::{
     AuctionEventBatch, AuctionTelemetrySink, NoopAuctionTelemetrySink,
 };
 use trusted_server_core::error::TrustedServerError;
-use trusted_server_core::platform::{
-    PlatformBackendSpec, PlatformHttpRequest, RuntimeServices, StoreName,
-};
+use trusted_server_core::platform::RuntimeServices;
 use trusted_server_core::settings::{Settings, TinybirdSettings};
+use trusted_server_core::third_party_log::{
+    NoopThirdPartyRequestSink, ThirdPartyRequestBatch, ThirdPartyRequestSink,
+};
+use trusted_server_core::tinybird::TinybirdEventsTarget;
 
-const TINYBIRD_EVENTS_PATH: &str = "/v0/events";
-const TINYBIRD_NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
-const TINYBIRD_FIRST_BYTE_TIMEOUT: Duration = Duration::from_secs(2);
-const TINYBIRD_BETWEEN_BYTES_TIMEOUT: Duration = Duration::from_secs(2);
 const TINYBIRD_MAX_ROWS_PER_AUCTION_BATCH: usize = 512;
 
 /// Build the configured auction telemetry sink.
@@ -39,39 +37,16 @@ struct FastlyTinybirdAuctionTelemetrySink {
     target: TinybirdEventsTarget,
 }
 
-#[derive(Debug, Clone)]
-struct TinybirdEventsTarget {
-    api_host: String,
-    dataset: String,
-    secret_store: StoreName,
-    token_secret: String,
-    uri: String,
-    backend_spec: PlatformBackendSpec,
-    max_body_bytes: usize,
-}
-
-impl TinybirdEventsTarget {
-    fn from_config(config: TinybirdSettings) -> Self {
-        let uri = tinybird_events_uri(&config.api_host, &config.auction_dataset);
-        let backend_spec = tinybird_backend_spec(&config.api_host);
-        Self {
-            api_host: config.api_host,
-            dataset: config.auction_dataset,
-            secret_store: StoreName::from(config.secret_store),
-            token_secret: config.auction_token_secret,
-            uri,
-            backend_spec,
-            max_body_bytes: config.max_body_bytes,
-        }
-    }
-}
-
 impl FastlyTinybirdAuctionTelemetrySink {
     fn new(config: TinybirdSettings) -> Self {
-        let enabled = config.enabled;
         Self {
-            enabled,
-            target: TinybirdEventsTarget::from_config(config),
+            enabled: config.enabled,
+            target: TinybirdEventsTarget::new(
+                "auction",
+                &config,
+                &config.auction_dataset,
+                &config.auction_token_secret,
+            ),
         }
     }
 
@@ -87,84 +62,6 @@ impl FastlyTinybirdAuctionTelemetrySink {
         }
         Ok(())
     }
-
-    fn serialize_batch(
-        &self,
-        batch: &AuctionEventBatch,
-    ) -> Result<String, Report<TrustedServerError>> {
-        batch.to_ndjson(self.target.max_body_bytes)
-    }
-
-    fn load_append_token(
-        &self,
-        services: &RuntimeServices,
-    ) -> Result<String, Report<TrustedServerError>> {
-        let token = services
-            .secret_store()
-            .get_string(&self.target.secret_store, &self.target.token_secret)
-            .change_context(TrustedServerError::Proxy {
-                message: "Tinybird auction append token unavailable".to_owned(),
-            })?;
-        let token = token.trim().to_owned();
-        if token.is_empty() {
-            return Err(Report::new(TrustedServerError::Proxy {
-                message: "Tinybird auction append token is empty".to_owned(),
-            }));
-        }
-        Ok(token)
-    }
-
-    fn ensure_backend(
-        &self,
-        services: &RuntimeServices,
-    ) -> Result<String, Report<TrustedServerError>> {
-        services
-            .backend()
-            .ensure(&self.target.backend_spec)
-            .change_context(TrustedServerError::Proxy {
-                message: "Tinybird backend registration failed".to_owned(),
-            })
-    }
-
-    fn authorization_header(token: &str) -> Result<HeaderValue, Report<TrustedServerError>> {
-        HeaderValue::from_str(&format!("Bearer {token}")).change_context(
-            TrustedServerError::InvalidHeaderValue {
-                message: "invalid Tinybird authorization header".to_owned(),
-            },
-        )
-    }
-
-    fn build_events_request(
-        &self,
-        body: String,
-        auth_header: HeaderValue,
-    ) -> Result<edgezero_core::http::Request, Report<TrustedServerError>> {
-        request_builder()
-            .method(Method::POST)
-            .uri(self.target.uri.as_str())
-            .header(header::AUTHORIZATION, auth_header)
-            .header(header::CONTENT_TYPE, TINYBIRD_NDJSON_CONTENT_TYPE)
-            .body(Body::from(body))
-            .change_context(TrustedServerError::Proxy {
-                message: "failed to build Tinybird Events API request".to_owned(),
-            })
-    }
-
-    async fn send_fire_and_forget(
-        services: &RuntimeServices,
-        request: edgezero_core::http::Request,
-        backend_name: String,
-    ) -> Result<(), Report<TrustedServerError>> {
-        let pending = services
-            .http_client()
-            .send_async(PlatformHttpRequest::new(request, backend_name))
-            .await
-            .change_context(TrustedServerError::Proxy {
-                message: "failed to start Tinybird Events API request".to_owned(),
-            })?;
-        drop(pending);
-        Ok(())
-    }
 }
 
 #[async_trait::async_trait(?Send)]
@@ -183,44 +80,59 @@ impl AuctionTelemetrySink for FastlyTinybirdAuctionTelemetrySink {
         }
 
         Self::validate_batch(&batch)?;
-        let body = self.serialize_batch(&batch)?;
-        let body_len = body.len();
-        let token = self.load_append_token(services)?;
-        let auth_header = Self::authorization_header(&token)?;
-        let backend_name = self.ensure_backend(services)?;
-        let request = self.build_events_request(body, auth_header)?;
-
-        log::info!(
-            "sending auction telemetry to Tinybird dataset={} rows={} bytes={} host={} backend={}",
-            self.target.dataset,
-            batch.row_count(),
-            body_len,
-            self.target.api_host,
-            backend_name
-        );
+        let body = batch.to_ndjson(self.target.max_body_bytes())?;
+        self.target
+            .post_ndjson(services, body, batch.row_count())
+            .await
+    }
+}
 
-        Self::send_fire_and_forget(services, request, backend_name).await
+/// Build the configured third-party request log sink.
+#[must_use]
+pub(crate) fn third_party_sink_from_settings(
+    settings: &Settings,
+) -> Arc<dyn ThirdPartyRequestSink> {
+    if settings.tinybird.enabled && settings.tinybird.third_party_enabled {
+        Arc::new(FastlyTinybirdThirdPartySink::new(&settings.tinybird))
+    } else {
+        Arc::new(NoopThirdPartyRequestSink)
     }
 }
 
-fn tinybird_backend_spec(api_host: &str) -> PlatformBackendSpec {
-    PlatformBackendSpec {
-        scheme: "https".to_owned(),
-        host: api_host.to_owned(),
-        port: None,
-        host_header_override: None,
-        certificate_check: true,
-        first_byte_timeout: TINYBIRD_FIRST_BYTE_TIMEOUT,
-        between_bytes_timeout: TINYBIRD_BETWEEN_BYTES_TIMEOUT,
-        discriminator: None,
+#[derive(Debug, Clone)]
+struct FastlyTinybirdThirdPartySink {
+    target: TinybirdEventsTarget,
+}
+
+impl FastlyTinybirdThirdPartySink {
+    fn new(config: &TinybirdSettings) -> Self {
+        Self {
+            target: TinybirdEventsTarget::new(
+                "third-party",
+                config,
+                &config.third_party_dataset,
+                &config.third_party_token_secret,
+            ),
+        }
     }
 }
 
-fn tinybird_events_uri(api_host: &str, dataset: &str) -> String {
-    format!(
-        "https://{api_host}{TINYBIRD_EVENTS_PATH}?name={}",
-        urlencoding::encode(dataset)
-    )
+#[async_trait::async_trait(?Send)]
+impl ThirdPartyRequestSink for FastlyTinybirdThirdPartySink {
+    async fn emit_third_party_requests(
+        &self,
+        services: &RuntimeServices,
+        batch: ThirdPartyRequestBatch,
+    ) -> Result<(), Report<TrustedServerError>> {
+        if batch.is_empty() {
+            return Ok(());
+        }
+
+        let body = batch.to_ndjson(self.target.max_body_bytes())?;
+        self.target
+            .post_ndjson(services, body, batch.row_count())
+            .await
+    }
 }
 
 #[cfg(test)]
@@ -228,13 +140,18 @@ mod tests {
     use std::collections::HashMap;
     use std::sync::{Arc, Mutex};
 
+    use edgezero_core::http::{Method, header};
     use error_stack::Report;
+    use trusted_server_core::access_log::AccessLogField;
     use trusted_server_core::auction::telemetry::{AuctionEventBatch, AuctionEventRow};
+    use trusted_server_core::metrics::{MetricsSink as _, TinybirdMetricsSink};
     use trusted_server_core::platform::{
-        ClientInfo, PlatformBackend, PlatformConfigStore, PlatformError, PlatformGeo,
-        PlatformHttpClient, PlatformPendingRequest, PlatformResponse, PlatformSecretStore,
-        PlatformSelectResult, RuntimeServices, StoreId,
+        ClientInfo, PlatformBackend, PlatformBackendSpec, PlatformConfigStore, PlatformError,
+        PlatformGeo, PlatformHttpClient, PlatformHttpRequest, PlatformPendingRequest,
+        PlatformResponse, PlatformSecretStore, PlatformSelectResult, RuntimeServices, StoreId,
+        StoreName,
     };
+    use trusted_server_core::tinybird::TINYBIRD_NDJSON_CONTENT_TYPE;
 
     use super::*;
 
@@ -411,6 +328,7 @@ mod tests {
             status: None,
             provider_response_time_ms: None,
             provider_bid_count: None,
+            provider_deal_bid_count: None,
             slot_id: None,
             slot_w: None,
             slot_h: None,
@@ -421,6 +339,9 @@ mod tests {
             is_win: None,
             ad_domain: None,
             ad_id: None,
+            deal_id: None,
+            bid_cache_hit: None,
+            experiment_arms: None,
         }
     }
 
@@ -451,35 +372,18 @@ mod tests {
             access_dataset: "access_logs_raw".to_owned(),
             access_token_secret: "tinybird_access_append_token".to_owned(),
             access_sample_rate: 0.0,
+            access_fields: AccessLogField::ALL.to_vec(),
+            metrics_enabled: false,
+            metrics_dataset: "metrics_raw".to_owned(),
+            metrics_token_secret: "tinybird_metrics_append_token".to_owned(),
+            third_party_enabled: false,
+            third_party_dataset: "third_party_requests_raw".to_owned(),
+            third_party_token_secret: "tinybird_third_party_append_token".to_owned(),
+            third_party_sample_rate: 0.1,
             max_body_bytes: 1024 * 1024,
         }
     }
 
-    #[test]
-    fn events_uri_targets_dataset_on_region_host() {
-        assert_eq!(
-            tinybird_events_uri("api.us-east.aws.tinybird.co", "auction_events_raw"),
-            "https://api.us-east.aws.tinybird.co/v0/events?name=auction_events_raw"
-        );
-    }
-
-    #[test]
-    fn events_uri_urlencodes_dataset_name() {
-        assert_eq!(
-            tinybird_events_uri("api.us-east.aws.tinybird.co", "auction events/raw"),
-            "https://api.us-east.aws.tinybird.co/v0/events?name=auction%20events%2Fraw"
-        );
-    }
-
-    #[test]
-    fn backend_spec_uses_matching_tls_host() {
-        let spec = tinybird_backend_spec("api.us-east.aws.tinybird.co");
-        assert_eq!(spec.scheme, "https");
-        assert_eq!(spec.host, "api.us-east.aws.tinybird.co");
-        assert_eq!(spec.host_header_override, None);
-        assert!(spec.certificate_check, "should verify Tinybird TLS cert");
-    }
-
     #[test]
     fn sink_posts_ndjson_with_secret_token_and_does_not_wait() {
         let backend = Arc::new(RecordingBackend::default());
@@ -659,6 +563,105 @@ mod tests {
         );
     }
 
+    #[test]
+    fn metrics_sink_posts_snapshot_to_metrics_dataset() {
+        let backend = Arc::new(RecordingBackend::default());
+        let http_client = Arc::new(RecordingHttpClient::default());
+        let services = services(
+            backend,
+            Arc::clone(&http_client),
+            HashMap::from([(
+                "tinybird_metrics_append_token".to_owned(),
+                b"metrics-token".to_vec(),
+            )]),
+        );
+        let mut config = enabled_config();
+        config.metrics_enabled = true;
+        let sink = TinybirdMetricsSink::new(&config);
+        let registry = trusted_server_core::metrics::MetricsRegistry::new();
+        registry.increment(
+            trusted_server_core::metrics::METRIC_HTTP_REQUESTS,
+            &[("route", "publisher"), ("status", "2xx")],
+        );
+
+        futures::executor::block_on(sink.emit_metrics(&services, registry.take_snapshot()))
+            .expect("should start Tinybird metrics request");
+
+        let requests = http_client
+            .requests
+            .lock()
+            .expect("should lock recorded requests");
+        assert_eq!(requests.len(), 1, "should start one async POST");
+        assert_eq!(
+            requests[0].uri,
+            "https://api.us-east.aws.tinybird.co/v0/events?name=metrics_raw"
+        );
+        assert_eq!(
+            header_value(&requests[0].headers, header::AUTHORIZATION.as_str()),
+            Some("Bearer metrics-token")
+        );
+        assert!(
+            std::str::from_utf8(&requests[0].body)
+                .expect("should record utf8 ndjson body")
+                .contains("ts_http_requests_total"),
+            "should send the drained metric rows"
+        );
+    }
+
+    #[test]
+    fn third_party_sink_posts_batch_to_third_party_dataset() {
+        let backend = Arc::new(RecordingBackend::default());
+        let http_client = Arc::new(RecordingHttpClient::default());
+        let services = services(
+            backend,
+            Arc::clone(&http_client),
+            HashMap::from([(
+                "tinybird_third_party_append_token".to_owned(),
+                b"third-party-token".to_vec(),
+            )]),
+        );
+        let mut config = enabled_config();
+        config.third_party_enabled = true;
+        let sink = FastlyTinybirdThirdPartySink::new(&config);
+        let batch = ThirdPartyRequestBatch::new(vec![
+            trusted_server_core::third_party_log::ThirdPartyRequestRow {
+                event_ts: "2026-05-13 12:00:00.000".to_owned(),
+                publisher_domain: "test-publisher.example".to_owned(),
+                integration: "lockr".to_owned(),
+                destination_host: "aim.loc.kr".to_owned(),
+                method: "GET".to_owned(),
+                transport: "fetch".to_owned(),
+                status: 200,
+                response_bytes: Some(512),
+                latency_ms: 42,
+                allowlisted: 1,
+            },
+        ]);
+
+        futures::executor::block_on(sink.emit_third_party_requests(&services, batch))
+            .expect("should start Tinybird third-party request");
+
+        let requests = http_client
+            .requests
+            .lock()
+            .expect("should lock recorded requests");
+        assert_eq!(requests.len(), 1, "should start one async POST");
+        assert_eq!(
+            requests[0].uri,
+            "https://api.us-east.aws.tinybird.co/v0/events?name=third_party_requests_raw"
+        );
+        assert_eq!(
+            header_value(&requests[0].headers, header::AUTHORIZATION.as_str()),
+            Some("Bearer third-party-token")
+        );
+        assert!(
+            std::str::from_utf8(&requests[0].body)
+                .expect("should record utf8 ndjson body")
+                .contains("\"destination_host\":\"aim.loc.kr\""),
+            "should send the drained third-party rows"
+        );
+    }
+
     fn header_value<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
         headers
             .iter()
@@ -8,6 +8,8 @@ use edgezero_core::error::EdgeError;
 use edgezero_core::http::{HeaderValue, Method, Request, Response, StatusCode, header};
 use edgezero_core::router::RouterService;
 use error_stack::Report;
+use trusted_server_core::access_log::AccessLogMiddleware;
+use trusted_server_core::auction::beacon::handle_event_beacon;
 use trusted_server_core::auction::endpoints::handle_auction;
 use trusted_server_core::auction::{AuctionOrchestrator, build_orchestrator};
 use trusted_server_core::cache_policy::EdgeCacheHeader;
@@ -18,24 +20,30 @@ use trusted_server_core::ec::admin::{
 };
 use trusted_server_core::ec::registry::PartnerRegistry;
 use trusted_server_core::error::{IntoHttpResponse as _, TrustedServerError};
+use trusted_server_core::experiments::warn_experiments_unsupported;
 use trusted_server_core::http_util::sanitize_forwarded_headers;
 use trusted_server_core::integrations::{IntegrationRegistry, ProxyDispatchInput};
+use trusted_server_core::metrics::{
+    MetricsRegistry, MetricsSink, admin_metrics_not_supported, metrics_sink_from_settings,
+};
 use trusted_server_core::platform::RuntimeServices;
 use trusted_server_core::proxy::{
     handle_first_party_click, handle_first_party_proxy, handle_first_party_proxy_rebuild,
     handle_first_party_proxy_sign,
 };
 use trusted_server_core::publisher::{
-    AuctionDispatch, PAGE_BIDS_LEGACY_PATH, PAGE_BIDS_PATH, PublisherResponse,
+    AuctionDispatch, PAGE_BIDS_LEGACY_PATH, PAGE_BIDS_PATH, PublisherResponse, SLOT_AUCTION_PATH,
     buffer_publisher_response_async, handle_page_bids, handle_publisher_request,
-    handle_tsjs_dynamic, page_bids_preflight_denied,
+    handle_slot_auction, handle_tsjs_dynamic, page_bids_preflight_denied,
 };
 use trusted_server_core::request_signing::{
     handle_trusted_server_discovery, handle_verify_signature,
 };
 use trusted_server_core::settings::Settings;
 
-use crate::middleware::{AuthMiddleware, FinalizeResponseMiddleware, NormalizeMiddleware};
+use crate::middleware::{
+    AuthMiddleware, FinalizeResponseMiddleware, NormalizeMiddleware, RequestMetricsMiddleware,
+};
 use crate::platform::build_runtime_services;
 
 // ---------------------------------------------------------------------------
@@ -47,6 +55,16 @@ pub struct AppState {
     settings: Arc<Settings>,
     orchestrator: Arc<AuctionOrchestrator>,
     registry: Arc<IntegrationRegistry>,
+    metrics: Arc<MetricsRegistry>,
+    metrics_sink: Arc<dyn MetricsSink>,
+}
+
+impl AppState {
+    /// Build request services that record into the shared metrics registry,
+    /// so [`RequestMetricsMiddleware`] flushes handler metrics too.
+    fn runtime_services(&self, ctx: &RequestContext) -> RuntimeServices {
+        build_runtime_services(ctx).with_metrics(Arc::clone(&self.metrics))
+    }
 }
 
 /// Build the application state, loading settings and constructing all per-application components.
@@ -57,6 +75,7 @@ pub struct AppState {
 /// registry fail to initialise.
 fn build_state() -> Result<Arc<AppState>, Report<TrustedServerError>> {
     let settings = Settings::from_toml(include_str!("../../../trusted-server.example.toml"))?;
+    warn_experiments_unsupported(&settings, "spin");
     build_state_with_settings(settings)
 }
 
@@ -71,11 +90,14 @@ fn build_state_with_settings(
 ) -> Result<Arc<AppState>, Report<TrustedServerError>> {
     let orchestrator = build_orchestrator(&settings)?;
     let registry = IntegrationRegistry::new(&settings)?;
+    let metrics_sink = metrics_sink_from_settings(&settings);
 
     Ok(Arc::new(AppState {
         settings: Arc::new(settings),
         orchestrator: Arc::new(orchestrator),
         registry: Arc::new(registry),
+        metrics: Arc::new(MetricsRegistry::new()),
+        metrics_sink,
     }))
 }
 
@@ -148,7 +170,7 @@ const LEGACY_ADMIN_DENY_METHODS: &[Method] = &[
     Method::DELETE,
 ];
 
-fn named_fallback_paths() -> [(&'static str, &'static [Method]); 16] {
+fn named_fallback_paths() -> [(&'static str, &'static [Method]); 19] {
     [
         ("/.well-known/trusted-server.json", &[Method::GET]),
         ("/verify-signature", &[Method::POST]),
@@ -157,11 +179,14 @@ fn named_fallback_paths() -> [(&'static str, &'static [Method]); 16] {
         ("/_ts/admin/ec", &[Method::GET]),
         ("/_ts/admin/ec/{id}", &[Method::GET]),
         ("/_ts/admin/eids", &[Method::GET]),
+        ("/_ts/admin/metrics", &[Method::GET]),
         ("/admin/keys/rotate", LEGACY_ADMIN_DENY_METHODS),
         ("/admin/keys/deactivate", LEGACY_ADMIN_DENY_METHODS),
         ("/auction", &[Method::POST]),
+        ("/_ts/api/v1/event", &[Method::POST]),
         (PAGE_BIDS_PATH, &[Method::GET, Method::OPTIONS]),
         (PAGE_BIDS_LEGACY_PATH, &[Method::GET, Method::OPTIONS]),
+        (SLOT_AUCTION_PATH, &[Method::POST, Method::OPTIONS]),
         ("/first-party/proxy", &[Method::GET]),
         ("/first-party/click", &[Method::GET]),
         ("/first-party/sign", &[Method::GET, Method::POST]),
@@ -503,7 +528,7 @@ fn build_router(state: &Arc<AppState>) -> RouterService {
         let discovery_handler = move |ctx: RequestContext| {
             let s = Arc::clone(&s);
             async move {
-                let services = build_runtime_services(&ctx);
+                let services = s.runtime_services(&ctx);
                 let req = ctx.into_request();
                 Ok(handle_trusted_server_discovery(&s.settings, &services, req)
                     .unwrap_or_else(|e| http_error(&e)))
@@ -515,7 +540,7 @@ fn build_router(state: &Arc<AppState>) -> RouterService {
         let verify_handler = move |ctx: RequestContext| {
             let s = Arc::clone(&s);
             async move {
-                let services = build_runtime_services(&ctx);
+                let services = s.runtime_services(&ctx);
                 let req = ctx.into_request();
                 Ok(handle_verify_signature(&s.settings, &services, req)
                     .unwrap_or_else(|e| http_error(&e)))
@@ -548,7 +573,7 @@ fn build_router(state: &Arc<AppState>) -> RouterService {
         let auction_handler = move |ctx: RequestContext| {
             let s = Arc::clone(&s);
             async move {
-                let services = build_runtime_services(&ctx);
+                let services = s.runtime_services(&ctx);
                 // Request normalization (forwarded-header stripping, trusted
                 // Host/scheme/client-IP derivation) is applied centrally by
                 // `NormalizeMiddleware` before this handler runs, so the signed
@@ -581,12 +606,28 @@ fn build_router(state: &Arc<AppState>) -> RouterService {
             }
         };
 
+        // POST /_ts/api/v1/event — creative render/viewable/click beacons.
+        let s = Arc::clone(&state);
+        let event_beacon_handler = move |ctx: RequestContext| {
+            let s = Arc::clone(&s);
+            async move {
+                let services = s.runtime_services(&ctx);
+                let req = ctx.into_request();
+                let ec_context = build_ec_context(&s.settings, &services, &req);
+                Ok::<Response, EdgeError>(
+                    handle_event_beacon(&s.settings, &services, &ec_context, req)
+                        .await
+                        .unwrap_or_else(|e| http_error(&e)),
+                )
+            }
+        };
+
         // GET /_ts/page-bids — SPA re-auction endpoint.
         let s = Arc::clone(&state);
         let page_bids_handler = move |ctx: RequestContext| {
             let s = Arc::clone(&s);
             async move {
-                let services = build_runtime_services(&ctx);
+                let services = s.runtime_services(&ctx);
                 let mut req = ctx.into_request();
                 if let Err(error) =
                     trusted_server_core::integrations::gpt_diagnostics::prepare_request(
@@ -616,12 +657,33 @@ fn build_router(state: &Arc<AppState>) -> RouterService {
             Ok::<Response, EdgeError>(page_bids_preflight_denied())
         };
 
+        // POST /_ts/api/v1/slot-auction — lazy-load and refresh auctions.
+        let s = Arc::clone(&state);
+        let slot_auction_handler = move |ctx: RequestContext| {
+            let s = Arc::clone(&s);
+            async move {
+                let services = s.runtime_services(&ctx);
+                let req = ctx.into_request();
+                let ec_context = build_ec_context(&s.settings, &services, &req);
+                let auction = AuctionDispatch {
+                    orchestrator: &s.orchestrator,
+                    slots: s.settings.creative_opportunity_slots(),
+                    registry: None,
+                };
+                Ok(
+                    handle_slot_auction(&s.settings, &services, None, auction, &ec_context, req)
+                        .await
+                        .unwrap_or_else(|e| http_error(&e)),
+                )
+            }
+        };
+
         // GET /first-party/proxy
         let s = Arc::clone(&state);
         let fp_proxy_handler = move |ctx: RequestContext| {
             let s = Arc::clone(&s);
             async move {
-                let services = build_runtime_services(&ctx);
+                let services = s.runtime_services(&ctx);
                 let req = ctx.into_request();
                 Ok(handle_first_party_proxy(&s.settings, &services, req)
                     .await
@@ -634,7 +696,7 @@ fn build_router(state: &Arc<AppState>) -> RouterService {
         let fp_click_handler = move |ctx: RequestContext| {
             let s = Arc::clone(&s);
             async move {
-                let services = build_runtime_services(&ctx);
+                let services = s.runtime_services(&ctx);
                 let req = ctx.into_request();
                 Ok(handle_first_party_click(&s.settings, &services, req)
                     .await
@@ -647,7 +709,7 @@ fn build_router(state: &Arc<AppState>) -> RouterService {
         let fp_sign_handler = move |ctx: RequestContext| {
             let s = Arc::clone(&s);
             async move {
-                let services = build_runtime_services(&ctx);
+                let services = s.runtime_services(&ctx);
                 let req = ctx.into_request();
                 Ok(handle_first_party_proxy_sign(&s.settings, &services, req)
                     .await
@@ -664,7 +726,7 @@ fn build_router(state: &Arc<AppState>) -> RouterService {
         let fp_rebuild_handler = move |ctx: RequestContext| {
             let s = Arc::clone(&s);
             async move {
-                let services = build_runtime_services(&ctx);
+                let services = s.runtime_services(&ctx);
                 let req = ctx.into_request();
                 Ok(
                     handle_first_party_proxy_rebuild(&s.settings, &services, req)
@@ -680,7 +742,7 @@ fn build_router(state: &Arc<AppState>) -> RouterService {
             state: Arc<AppState>,
             ctx: RequestContext,
         ) -> Result<Response, EdgeError> {
-            let services = build_runtime_services(&ctx);
+            let services = state.runtime_services(&ctx);
             let mut req = ctx.into_request();
             if let Some(response) = deny_admin_diagnostic_fallback(&req) {
                 return Ok(response);
@@ -766,14 +828,24 @@ fn build_router(state: &Arc<AppState>) -> RouterService {
             |_ctx: RequestContext| async { Ok::<Response, EdgeError>(legacy_admin_alias_denied()) };
 
         let mut builder = RouterService::builder()
+            .middleware(RequestMetricsMiddleware::new(
+                Arc::clone(&state.metrics),
+                Arc::clone(&state.metrics_sink),
+            ))
             .middleware(FinalizeResponseMiddleware::new(Arc::clone(&state.settings)))
             .middleware(AuthMiddleware::new(Arc::clone(&state.settings)))
-            // Innermost middleware: normalize every routed request (strip
+            // Normalize every routed request (strip
             // spoofable forwarded headers, derive the trusted Host/scheme/client-IP
             // from Spin's synthetic runtime headers) so no handler can opt out of
             // the de-spoofing invariant. Runs after auth so the basic-auth gate
             // continues to see the original request, matching prior behaviour.
             .middleware(NormalizeMiddleware::new())
+            // Access logging runs after normalization so its geo lookup sees the
+            // trusted client IP. Auth challenges are therefore not logged on Spin.
+            .middleware(AccessLogMiddleware::new(
+                Arc::clone(&state.settings),
+                build_runtime_services,
+            ))
             // Cheap liveness probe, matching the Fastly/Axum adapters. Registered
             // explicitly so it is not absorbed by the publisher `/{*rest}` fallback.
             .get("/health", |_ctx: RequestContext| async {
@@ -800,7 +872,14 @@ fn build_router(state: &Arc<AppState>) -> RouterService {
             .get("/_ts/admin/ec", admin_ec_not_supported_handler)
             .get("/_ts/admin/ec/{id}", admin_ec_not_supported_handler)
             .get("/_ts/admin/eids", admin_eids_handler)
+            // Spin instances do not outlive a request, so there is no
+            // process-wide registry to scrape; `RequestMetricsMiddleware`
+            // flushes each request's metrics through `[tinybird]` instead.
+            .get("/_ts/admin/metrics", |_ctx: RequestContext| async {
+                Ok::<Response, EdgeError>(admin_metrics_not_supported())
+            })
             .post("/auction", auction_handler)
+            .post("/_ts/api/v1/event", event_beacon_handler)
             .get(PAGE_BIDS_PATH, page_bids_handler.clone())
             .route(PAGE_BIDS_PATH, Method::OPTIONS, page_bids_options_handler)
             // Deprecated double-underscore alias, kept so tsjs bundles served
@@ -813,6 +892,12 @@ fn build_router(state: &Arc<AppState>) -> RouterService {
                 Method::OPTIONS,
                 page_bids_options_handler,
             )
+            .post(SLOT_AUCTION_PATH, slot_auction_handler)
+            .route(
+                SLOT_AUCTION_PATH,
+                Method::OPTIONS,
+                page_bids_options_handler,
+            )
             .get("/first-party/proxy", fp_proxy_handler)
             .get("/first-party/click", fp_click_handler)
             .get("/first-party/sign", fp_sign_handler)
@@ -7,8 +7,51 @@ use edgezero_core::http::{HeaderValue, Response};
 use edgezero_core::middleware::{Middleware, Next};
 use trusted_server_core::auth::enforce_basic_auth;
 use trusted_server_core::constants::HEADER_X_GEO_INFO_AVAILABLE;
+use trusted_server_core::metrics::{
+    MetricsRegistry, MetricsSink, flush_metrics_best_effort, record_http_request,
+};
 use trusted_server_core::settings::Settings;
 
+use crate::platform::build_runtime_services;
+
+// ---------------------------------------------------------------------------
+// RequestMetricsMiddleware
+// ---------------------------------------------------------------------------
+
+/// Outermost middleware: counts every response by route class and status and
+/// flushes the request's metrics to the configured sink.
+///
+/// Spin instances do not outlive a request and have no after-send hook, so the
+/// flush runs before the response is returned. With metrics disabled the flush
+/// is a no-op.
+pub struct RequestMetricsMiddleware {
+    metrics: Arc<MetricsRegistry>,
+    sink: Arc<dyn MetricsSink>,
+}
+
+impl RequestMetricsMiddleware {
+    /// Creates a new [`RequestMetricsMiddleware`] recording into `metrics` and
+    /// flushing to `sink`.
+    #[must_use]
+    pub fn new(metrics: Arc<MetricsRegistry>, sink: Arc<dyn MetricsSink>) -> Self {
+        Self { metrics, sink }
+    }
+}
+
+#[async_trait(?Send)]
+impl Middleware for RequestMetricsMiddleware {
+    async fn handle(&self, ctx: RequestContext, next: Next<'_>) -> Result<Response, EdgeError> {
+        let path = ctx.request().uri().path().to_owned();
+        let services = build_runtime_services(&ctx)
+            .with_metrics(Arc::clone(&self.metrics))
+            .with_metrics_sink(Arc::clone(&self.sink));
+        let response = next.run(ctx).await?;
+        record_http_request(&self.metrics, &path, response.status());
+        flush_metrics_best_effort(&services).await;
+        Ok(response)
+    }
+}
+
 // ---------------------------------------------------------------------------
 // FinalizeResponseMiddleware
 // ---------------------------------------------------------------------------
@@ -95,7 +138,7 @@ impl Middleware for AuthMiddleware {
 /// signing handler that begins deriving an issuer/audience from `RequestInfo`,
 /// cannot silently trust spoofable input by forgetting to opt in.
 ///
-/// Registered after [`AuthMiddleware`] (innermost) so the basic-auth gate still
+/// Registered after [`AuthMiddleware`] so the basic-auth gate still
 /// evaluates the original request, preserving prior behaviour.
 #[derive(Default)]
 pub struct NormalizeMiddleware;
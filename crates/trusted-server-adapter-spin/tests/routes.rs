@@ -193,6 +193,7 @@ async fn authenticated_admin_diagnostic_fallback_is_denied_locally() {
         "/_ts/admin/ec".to_owned(),
         format!("/_ts/admin/ec/{ec_id}"),
         "/_ts/admin/eids".to_owned(),
+        "/_ts/admin/metrics".to_owned(),
     ];
 
     for path in valid_paths {
@@ -566,6 +567,30 @@ async fn page_bids_get_is_routed_on_canonical_path_and_alias() {
     );
 }
 
+/// `POST` on the slot-auction endpoint must reach its handler. Anchored on the
+/// handler's not-configured body for the same reason as the page-bids test.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn slot_auction_post_is_routed() {
+    let req = request_builder()
+        .method("POST")
+        .uri("/_ts/api/v1/slot-auction")
+        .header("sec-fetch-site", "same-origin")
+        .body(edgezero_core::body::Body::from(
+            r#"{"path":"/","slots":[]}"#,
+        ))
+        .expect("should build request");
+    let resp = route(test_router(), req).await;
+    let status = resp.status().as_u16();
+    let body =
+        String::from_utf8_lossy(&resp.into_body().into_bytes().unwrap_or_default()).into_owned();
+
+    assert!(
+        body.contains("Creative opportunities not configured"),
+        "POST /_ts/api/v1/slot-auction must reach the slot-auction handler, \
+         got status {status} body {body:?}"
+    );
+}
+
 // ---------------------------------------------------------------------------
 // Publisher fallback method parity — non-GET/POST methods must reach the
 // publisher origin fallback (not a router-level 405), matching Fastly/Axum.
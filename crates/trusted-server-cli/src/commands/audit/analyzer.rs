@@ -1,4 +1,4 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::sync::LazyLock;
 
 use regex::Regex;
@@ -6,29 +6,19 @@ use scraper::{Html, Selector};
 use url::Url;
 
 use crate::commands::audit::collector::CollectedPage;
+use crate::commands::audit::vendors::{SignalKind, catalog};
 use crate::commands::audit::{AssetParty, AuditArtifact, AuditedAsset, DetectedIntegration};
 use crate::error::{CliResult, report_error};
 
 static GTM_REGEX: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"\bGTM-[A-Z0-9]+\b").expect("should compile GTM regex"));
-static GPT_INLINE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"(?i)\b(?:googletag|gpt\.js|googletagservices|securepubads)\b")
-        .expect("should compile GPT inline regex")
-});
-static DIDOMI_INLINE_REGEX: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"(?i)\bdidomi\b").expect("should compile Didomi inline regex"));
-static DATADOME_INLINE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"(?i)\bdatadome\b").expect("should compile DataDome inline regex")
-});
-static PERMUTIVE_INLINE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"(?i)\bpermutive\b").expect("should compile Permutive inline regex")
-});
-static LOCKR_INLINE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"(?i)(?:\blockr\b|\bloc\.kr\b)").expect("should compile Lockr inline regex")
-});
-static PREBID_INLINE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"(?i)(?:\bprebid\b|\bpbjs\b)").expect("should compile Prebid inline regex")
-});
+
+/// Evidence gathered for one vendor across every signal on a page.
+#[derive(Default)]
+struct VendorEvidence {
+    evidence: String,
+    kinds: BTreeSet<SignalKind>,
+}
 
 pub(crate) fn analyze_collected_page(collected: &CollectedPage) -> CliResult<AuditArtifact> {
     let final_url = collected
@@ -54,7 +44,7 @@ pub(crate) fn analyze_collected_page(collected: &CollectedPage) -> CliResult<Aud
         .filter(|title| !title.is_empty());
 
     let mut assets_by_url = BTreeMap::<String, AuditedAsset>::new();
-    let mut integrations = BTreeMap::<String, String>::new();
+    let mut integrations = BTreeMap::<String, VendorEvidence>::new();
     let mut warnings = collected.warnings.clone();
 
     if requested_url != final_url {
@@ -67,7 +57,12 @@ pub(crate) fn analyze_collected_page(collected: &CollectedPage) -> CliResult<Aud
         if let Some(src) = &tag.src {
             if let Ok(asset_url) = final_url.join(src) {
                 let integration = detect_integration_from_url(&asset_url);
-                record_integration(&mut integrations, &integration, asset_url.as_str());
+                record_integration(
+                    &mut integrations,
+                    integration.as_deref(),
+                    SignalKind::Script,
+                    asset_url.as_str(),
+                );
                 insert_asset(&mut assets_by_url, &final_url, &asset_url, integration);
             } else {
                 warnings.push(format!("could not resolve script URL `{src}`"));
@@ -76,23 +71,64 @@ pub(crate) fn analyze_collected_page(collected: &CollectedPage) -> CliResult<Aud
 
         if let Some(inline_text) = &tag.inline_text {
             for (integration_id, evidence) in detect_integrations_from_inline_script(inline_text) {
-                integrations.entry(integration_id).or_insert(evidence);
+                record_integration(
+                    &mut integrations,
+                    Some(&integration_id),
+                    SignalKind::Inline,
+                    &evidence,
+                );
             }
         }
     }
 
     for request in &collected.network_requests {
+        let Ok(request_url) = Url::parse(&request.url) else {
+            continue;
+        };
+        for vendor in catalog().match_endpoint(&request_url) {
+            record_integration(
+                &mut integrations,
+                Some(&vendor.id),
+                SignalKind::Endpoint,
+                request_url.as_str(),
+            );
+        }
+
         let is_script = request
             .resource_type
             .as_deref()
             .is_some_and(|resource_type| resource_type.eq_ignore_ascii_case("script"));
-        if !is_script {
-            continue;
+        if is_script {
+            let integration = detect_integration_from_url(&request_url);
+            record_integration(
+                &mut integrations,
+                integration.as_deref(),
+                SignalKind::Script,
+                request_url.as_str(),
+            );
+            insert_asset(&mut assets_by_url, &final_url, &request_url, integration);
         }
-        if let Ok(asset_url) = Url::parse(&request.url) {
-            let integration = detect_integration_from_url(&asset_url);
-            record_integration(&mut integrations, &integration, asset_url.as_str());
-            insert_asset(&mut assets_by_url, &final_url, &asset_url, integration);
+    }
+
+    for global in &collected.globals {
+        for vendor in catalog().match_global(global) {
+            record_integration(
+                &mut integrations,
+                Some(&vendor.id),
+                SignalKind::Global,
+                &format!("window.{global}"),
+            );
+        }
+    }
+
+    for cookie in &collected.cookies {
+        for vendor in catalog().match_cookie(cookie) {
+            record_integration(
+                &mut integrations,
+                Some(&vendor.id),
+                SignalKind::Cookie,
+                &format!("cookie `{cookie}`"),
+            );
         }
     }
 
@@ -117,13 +153,27 @@ pub(crate) fn analyze_collected_page(collected: &CollectedPage) -> CliResult<Aud
         third_party_asset_count,
         detected_integrations: integrations
             .into_iter()
-            .map(|(id, evidence)| DetectedIntegration { id, evidence })
+            .map(|(id, found)| detected_integration(id, found))
             .collect(),
         assets,
         warnings,
+        pages: Vec::new(),
     })
 }
 
+fn detected_integration(id: String, found: VendorEvidence) -> DetectedIntegration {
+    let vendor = catalog().get(&id);
+    DetectedIntegration {
+        name: vendor.map_or_else(|| id.clone(), |vendor| vendor.name.clone()),
+        category: vendor.map(|vendor| vendor.category.clone()),
+        confidence: catalog().confidence(found.kinds),
+        config_snippet: vendor.and_then(|vendor| vendor.config_snippet.clone()),
+        id,
+        evidence: found.evidence,
+        pages: Vec::new(),
+    }
+}
+
 fn insert_asset(
     assets_by_url: &mut BTreeMap<String, AuditedAsset>,
     page_url: &Url,
@@ -145,15 +195,19 @@ fn insert_asset(
     }
 }
 
+/// Records one signal for a vendor; the first evidence seen is kept.
 fn record_integration(
-    integrations: &mut BTreeMap<String, String>,
-    integration: &Option<String>,
+    integrations: &mut BTreeMap<String, VendorEvidence>,
+    integration: Option<&str>,
+    kind: SignalKind,
     evidence: &str,
 ) {
     if let Some(integration_id) = integration {
-        integrations
-            .entry(integration_id.clone())
-            .or_insert_with(|| evidence.to_string());
+        let found = integrations.entry(integration_id.to_string()).or_default();
+        if found.evidence.is_empty() {
+            found.evidence = evidence.to_string();
+        }
+        found.kinds.insert(kind);
     }
 }
 
@@ -180,35 +234,15 @@ fn host_matches(page_host: &str, asset_host: &str) -> bool {
 }
 
 pub(crate) fn detect_integration_from_url(url: &Url) -> Option<String> {
-    let host = url.host_str().unwrap_or_default();
-    let path = url.path();
-    let value = format!("{host}{path}").to_ascii_lowercase();
-
-    if value.contains("googletagmanager.com") {
-        Some("google_tag_manager".to_string())
-    } else if value.contains("securepubads.g.doubleclick.net")
-        || value.contains("googletagservices.com")
-        || value.contains("doubleclick.net/tag/js/gpt")
-    {
-        Some("gpt".to_string())
-    } else if value.contains("privacy-center.org") {
-        Some("didomi".to_string())
-    } else if value.contains("datadome.co") {
-        Some("datadome".to_string())
-    } else if value.contains("permutive") {
-        Some("permutive".to_string())
-    } else if value.contains("loc.kr") {
-        Some("lockr".to_string())
-    } else if value.contains("prebid") {
-        Some("prebid".to_string())
-    } else {
-        None
-    }
+    catalog()
+        .match_script_url(url)
+        .map(|vendor| vendor.id.clone())
 }
 
 pub(crate) fn detect_integrations_from_inline_script(script: &str) -> Vec<(String, String)> {
     let mut matches = Vec::new();
 
+    // The container ID is the useful evidence for GTM: the draft config needs it.
     if let Some(container_id) = GTM_REGEX.find(script) {
         matches.push((
             "google_tag_manager".to_string(),
@@ -216,20 +250,11 @@ pub(crate) fn detect_integrations_from_inline_script(script: &str) -> Vec<(Strin
         ));
     }
 
-    for (integration, regex) in [
-        ("gpt", &*GPT_INLINE_REGEX),
-        ("didomi", &*DIDOMI_INLINE_REGEX),
-        ("datadome", &*DATADOME_INLINE_REGEX),
-        ("permutive", &*PERMUTIVE_INLINE_REGEX),
-        ("lockr", &*LOCKR_INLINE_REGEX),
-        ("prebid", &*PREBID_INLINE_REGEX),
-    ] {
-        if regex.is_match(script) {
-            matches.push((
-                integration.to_string(),
-                format!("inline script matched `{integration}`"),
-            ));
-        }
+    for vendor in catalog().match_inline(script) {
+        matches.push((
+            vendor.id.clone(),
+            format!("inline script matched `{}`", vendor.id),
+        ));
     }
 
     matches
@@ -283,6 +308,8 @@ mod tests {
                 url: "https://cdn.example.com/dynamic.js".to_string(),
                 resource_type: Some("Script".to_string()),
             }],
+            globals: Vec::new(),
+            cookies: Vec::new(),
             warnings: vec!["partial settle".to_string()],
         };
 
@@ -319,6 +346,8 @@ mod tests {
             html: "<html><head><title>HTML Title</title></head></html>".to_string(),
             script_tags: Vec::new(),
             network_requests: Vec::new(),
+            globals: Vec::new(),
+            cookies: Vec::new(),
             warnings: Vec::new(),
         };
 
@@ -336,6 +365,8 @@ mod tests {
             html: "<html><head><title>HTML Title</title></head></html>".to_string(),
             script_tags: Vec::new(),
             network_requests: Vec::new(),
+            globals: Vec::new(),
+            cookies: Vec::new(),
             warnings: Vec::new(),
         };
 
@@ -360,6 +391,8 @@ mod tests {
                 url: "https://cdn.example.com/prebid.js".to_string(),
                 resource_type: Some("script".to_string()),
             }],
+            globals: Vec::new(),
+            cookies: Vec::new(),
             warnings: Vec::new(),
         };
 
@@ -394,6 +427,8 @@ mod tests {
                 },
             ],
             network_requests: Vec::new(),
+            globals: Vec::new(),
+            cookies: Vec::new(),
             warnings: Vec::new(),
         };
 
@@ -424,6 +459,8 @@ mod tests {
             html: "<html><head></head></html>".to_string(),
             script_tags: Vec::new(),
             network_requests: Vec::new(),
+            globals: Vec::new(),
+            cookies: Vec::new(),
             warnings: Vec::new(),
         };
 
@@ -531,6 +568,67 @@ mod tests {
         }
     }
 
+    #[test]
+    fn analyze_collected_page_scores_vendors_from_globals_cookies_and_endpoints() {
+        let collected = CollectedPage {
+            requested_url: "https://publisher.example/page".to_string(),
+            final_url: "https://publisher.example/page".to_string(),
+            page_title: None,
+            html: "<html></html>".to_string(),
+            script_tags: vec![CollectedScriptTag {
+                src: Some(
+                    "https://cdn.privacy-mgmt.com/unified/wrapperMessagingWithoutDetection.js"
+                        .to_string(),
+                ),
+                inline_text: None,
+            }],
+            network_requests: vec![CollectedRequest {
+                url: "https://region1.google-analytics.com/g/collect?v=2".to_string(),
+                resource_type: Some("fetch".to_string()),
+            }],
+            globals: vec!["_sp_".to_string()],
+            cookies: vec!["consentUUID".to_string(), "OptanonConsent".to_string()],
+            warnings: Vec::new(),
+        };
+
+        let artifact = analyze_collected_page(&collected).expect("should analyze collected page");
+        let find = |id: &str| {
+            artifact
+                .detected_integrations
+                .iter()
+                .find(|integration| integration.id == id)
+                .unwrap_or_else(|| panic!("should detect {id}"))
+        };
+
+        let sourcepoint = find("sourcepoint");
+        assert_eq!(sourcepoint.name, "Sourcepoint");
+        assert_eq!(
+            sourcepoint.confidence, 92,
+            "script, global and cookie signals should combine"
+        );
+        assert!(
+            sourcepoint
+                .config_snippet
+                .as_deref()
+                .is_some_and(|snippet| snippet.contains("[integrations.sourcepoint]")),
+            "should offer the integration snippet"
+        );
+
+        let onetrust = find("onetrust");
+        assert_eq!(onetrust.confidence, 30, "a cookie alone is weak evidence");
+        assert_eq!(onetrust.evidence, "cookie `OptanonConsent`");
+        assert!(
+            onetrust.config_snippet.is_none(),
+            "OneTrust has no Trusted Server integration"
+        );
+
+        assert_eq!(find("ga4").confidence, 50, "should match network endpoints");
+        assert_eq!(
+            artifact.js_asset_count, 1,
+            "non-script requests should not count as JS assets"
+        );
+    }
+
     #[test]
     fn extract_gtm_container_id_reads_query_parameter_urls() {
         let artifact = AuditArtifact {
@@ -548,6 +646,7 @@ mod tests {
                 integration: Some("google_tag_manager".to_string()),
             }],
             warnings: Vec::new(),
+            pages: Vec::new(),
         };
 
         assert_eq!(
@@ -566,7 +665,12 @@ mod tests {
             third_party_asset_count: 1,
             detected_integrations: vec![DetectedIntegration {
                 id: "gpt".to_string(),
+                name: "gpt".to_string(),
+                category: None,
+                confidence: 70,
                 evidence: "https://securepubads.g.doubleclick.net/tag/js/gpt.js".to_string(),
+                config_snippet: None,
+                pages: Vec::new(),
             }],
             assets: vec![AuditedAsset {
                 kind: "script".to_string(),
@@ -576,6 +680,7 @@ mod tests {
                 integration: Some("gpt".to_string()),
             }],
             warnings: Vec::new(),
+            pages: Vec::new(),
         };
 
         let toml = toml::to_string_pretty(&artifact).expect("should serialize artifact");
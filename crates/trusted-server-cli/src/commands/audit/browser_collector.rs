@@ -14,6 +14,7 @@ use which::which;
 use crate::commands::audit::collector::{
     AuditCollector, CollectedPage, CollectedRequest, CollectedScriptTag,
 };
+use crate::commands::audit::vendors::catalog;
 use crate::error::{CliResult, report_error};
 
 const SETTLE_QUIET_PERIOD: Duration = Duration::from_millis(750);
@@ -41,9 +42,51 @@ impl AuditCollector for BrowserAuditCollector {
 
         runtime.block_on(collect_page_via_browser_async(target_url))
     }
+
+    fn fetch_text(&self, url: &Url) -> CliResult<Option<String>> {
+        let runtime = Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|error| {
+                report_error(format!(
+                    "failed to build Tokio runtime for browser audit: {error}"
+                ))
+            })?;
+
+        runtime.block_on(fetch_text_via_browser_async(url))
+    }
+}
+
+/// A launched headless browser and the resources that must outlive it.
+struct AuditBrowser {
+    browser: Browser,
+    handler_task: tokio::task::JoinHandle<()>,
+    _user_data_dir: TempDir,
 }
 
 async fn collect_page_via_browser_async(target_url: &Url) -> CliResult<CollectedPage> {
+    let mut audit_browser = launch_audit_browser().await?;
+    let result = collect_page_from_browser(&mut audit_browser.browser, target_url).await;
+    let close_result = close_audit_browser(audit_browser).await;
+
+    match (result, close_result) {
+        (Ok(collected), Ok(())) => Ok(collected),
+        (Ok(_), Err(error)) | (Err(error), _) => Err(error),
+    }
+}
+
+async fn fetch_text_via_browser_async(url: &Url) -> CliResult<Option<String>> {
+    let mut audit_browser = launch_audit_browser().await?;
+    let result = fetch_text_from_browser(&mut audit_browser.browser, url).await;
+    let close_result = close_audit_browser(audit_browser).await;
+
+    match (result, close_result) {
+        (Ok(text), Ok(())) => Ok(text),
+        (Ok(_), Err(error)) | (Err(error), _) => Err(error),
+    }
+}
+
+async fn launch_audit_browser() -> CliResult<AuditBrowser> {
     let chrome_executable = find_browser_executable()?;
     let user_data_dir = TempDir::new().map_err(|error| {
         report_error(format!(
@@ -61,7 +104,7 @@ async fn collect_page_via_browser_async(target_url: &Url) -> CliResult<Collected
             ))
         })?;
 
-    let (mut browser, mut handler) = Browser::launch(config).await.map_err(|error| {
+    let (browser, mut handler) = Browser::launch(config).await.map_err(|error| {
         report_error(format!(
             "failed to launch Chrome/Chromium for audit: {error}"
         ))
@@ -75,8 +118,19 @@ async fn collect_page_via_browser_async(target_url: &Url) -> CliResult<Collected
         }
     });
 
-    let result = collect_page_from_browser(&mut browser, target_url).await;
+    Ok(AuditBrowser {
+        browser,
+        handler_task,
+        _user_data_dir: user_data_dir,
+    })
+}
 
+async fn close_audit_browser(audit_browser: AuditBrowser) -> CliResult<()> {
+    let AuditBrowser {
+        mut browser,
+        handler_task,
+        _user_data_dir,
+    } = audit_browser;
     let close_result = timeout(BROWSER_CLOSE_TIMEOUT, browser.close())
         .await
         .map_err(|_| report_error("timed out closing browser after audit"))
@@ -89,11 +143,35 @@ async fn collect_page_via_browser_async(target_url: &Url) -> CliResult<Collected
         handler_task.abort();
     }
     let _ = handler_task.await;
+    close_result.map(|_| ())
+}
 
-    match (result, close_result) {
-        (Ok(collected), Ok(_)) => Ok(collected),
-        (Ok(_), Err(error)) | (Err(error), _) => Err(error),
-    }
+/// Fetches `url` from a page on its own origin, so the request carries the
+/// browser's normal headers, and returns the body of a successful response.
+async fn fetch_text_from_browser(browser: &mut Browser, url: &Url) -> CliResult<Option<String>> {
+    let page = browser.new_page("about:blank").await.map_err(|error| {
+        report_error(format!("failed to create browser page for audit: {error}"))
+    })?;
+
+    timeout(NAVIGATION_TIMEOUT, page.goto(url.as_str()))
+        .await
+        .map_err(|_| report_error(format!("timed out navigating to `{url}`")))?
+        .map_err(|error| report_error(format!("failed to navigate to `{url}`: {error}")))?;
+
+    timeout(
+        NAVIGATION_TIMEOUT,
+        page.evaluate(
+            r#"async () => {
+                const response = await fetch(location.href);
+                return response.ok ? await response.text() : null;
+            }"#,
+        ),
+    )
+    .await
+    .map_err(|_| report_error(format!("timed out fetching `{url}`")))?
+    .map_err(|error| report_error(format!("failed to fetch `{url}`: {error}")))?
+    .into_value()
+    .map_err(|error| report_error(format!("failed to decode `{url}`: {error}")))
 }
 
 async fn collect_page_from_browser(
@@ -187,6 +265,25 @@ async fn collect_page_from_browser(
         warnings.push(warning.to_string());
     }
 
+    let globals: Vec<String> = page
+        .evaluate(defined_globals_script(&catalog().global_names())?)
+        .await
+        .map_err(|error| report_error(format!("failed to read page globals: {error}")))?
+        .into_value()
+        .map_err(|error| report_error(format!("failed to decode page globals: {error}")))?;
+
+    let cookies: Vec<String> = page
+        .evaluate(
+            r#"() => document.cookie
+                .split(';')
+                .map((cookie) => cookie.split('=')[0].trim())
+                .filter((name) => name.length > 0)"#,
+        )
+        .await
+        .map_err(|error| report_error(format!("failed to read page cookies: {error}")))?
+        .into_value()
+        .map_err(|error| report_error(format!("failed to decode page cookies: {error}")))?;
+
     Ok(CollectedPage {
         requested_url: target_url.to_string(),
         final_url,
@@ -206,10 +303,21 @@ async fn collect_page_from_browser(
                 resource_type: entry.initiator_type,
             })
             .collect(),
+        globals,
+        cookies,
         warnings,
     })
 }
 
+/// Builds a page function returning which of `names` are defined on `window`.
+fn defined_globals_script(names: &[&str]) -> CliResult<String> {
+    let names = serde_json::to_string(names)
+        .map_err(|error| report_error(format!("failed to encode vendor globals: {error}")))?;
+    Ok(format!(
+        "() => {names}.filter((name) => typeof window[name] !== 'undefined')"
+    ))
+}
+
 async fn wait_for_page_settle(page: &chromiumoxide::Page) -> CliResult<bool> {
     let mut elapsed = Duration::ZERO;
     let mut previous_count = None;
@@ -5,6 +5,13 @@ use crate::error::CliResult;
 
 pub(crate) trait AuditCollector {
     fn collect_page(&self, target_url: &Url) -> CliResult<CollectedPage>;
+
+    /// Fetches a raw text document such as `sitemap.xml`, returning `None` when
+    /// it does not exist. Collectors that cannot fetch raw documents return
+    /// `None`, which skips sitemap discovery.
+    fn fetch_text(&self, _url: &Url) -> CliResult<Option<String>> {
+        Ok(None)
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
@@ -15,6 +22,12 @@ pub(crate) struct CollectedPage {
     pub(crate) html: String,
     pub(crate) script_tags: Vec<CollectedScriptTag>,
     pub(crate) network_requests: Vec<CollectedRequest>,
+    /// Vendor `window` properties defined once the page settled.
+    #[serde(default)]
+    pub(crate) globals: Vec<String>,
+    /// Cookie names readable from `document.cookie`.
+    #[serde(default)]
+    pub(crate) cookies: Vec<String>,
     pub(crate) warnings: Vec<String>,
 }
 
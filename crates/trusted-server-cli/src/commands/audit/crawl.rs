@@ -0,0 +1,506 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::sync::LazyLock;
+
+use regex::Regex;
+use scraper::{Html, Selector};
+use url::Url;
+
+use crate::commands::audit::analyzer::{analyze_collected_page, classify_party};
+use crate::commands::audit::collector::{AuditCollector, CollectedPage};
+use crate::commands::audit::{
+    AssetParty, AuditArtifact, AuditedAsset, AuditedPage, DetectedIntegration, PageEvidence,
+};
+use crate::error::{CliResult, report_error};
+
+/// Upper bound on sitemap documents fetched, including nested sitemap indexes.
+const MAX_SITEMAP_DOCUMENTS: usize = 5;
+
+/// Link targets that are never HTML pages and are not worth a browser visit.
+const NON_PAGE_EXTENSIONS: [&str; 14] = [
+    ".css", ".gif", ".ico", ".jpeg", ".jpg", ".js", ".json", ".mp3", ".mp4", ".pdf", ".png",
+    ".svg", ".webp", ".zip",
+];
+
+static SITEMAP_LOC_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?is)<loc>\s*(.*?)\s*</loc>").expect("should compile sitemap loc regex")
+});
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct CrawlLimits {
+    pub(crate) max_depth: usize,
+    pub(crate) max_pages: usize,
+    pub(crate) use_sitemap: bool,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct CrawledPage {
+    pub(crate) depth: usize,
+    pub(crate) collected: CollectedPage,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct CrawlResult {
+    pub(crate) pages: Vec<CrawledPage>,
+    pub(crate) warnings: Vec<String>,
+}
+
+/// Visits same-site pages breadth-first from `start_url`.
+///
+/// Links come from rendered `<a href>` elements and AMP alternates
+/// (`<link rel="amphtml">`); once the start page is loaded, URLs from its
+/// `/sitemap.xml` are queued behind the start page's own links. A page that
+/// fails to load is skipped with a warning, except the start page, whose
+/// failure fails the audit.
+pub(crate) fn crawl_site(
+    start_url: &Url,
+    limits: CrawlLimits,
+    collector: &dyn AuditCollector,
+) -> CliResult<CrawlResult> {
+    let mut queue = VecDeque::from([(start_url.clone(), 0)]);
+    let mut seen = BTreeSet::from([crawl_key(start_url)]);
+    let mut pages = Vec::new();
+    let mut warnings = Vec::new();
+    let mut site_url: Option<Url> = None;
+
+    while let Some((url, depth)) = queue.pop_front() {
+        if pages.len() >= limits.max_pages {
+            break;
+        }
+
+        let collected = match collector.collect_page(&url) {
+            Ok(collected) => collected,
+            Err(error) if pages.is_empty() => return Err(error),
+            Err(error) => {
+                warnings.push(format!("skipped `{url}`: {error}"));
+                continue;
+            }
+        };
+        let page_url = collected.final_url().unwrap_or_else(|_| url.clone());
+        seen.insert(crawl_key(&page_url));
+
+        // Same-site is judged against where the start page landed, so an
+        // apex-to-www or http-to-https redirect does not end the crawl.
+        let site = site_url.get_or_insert_with(|| page_url.clone()).clone();
+        if depth < limits.max_depth {
+            for link in discover_links(&collected.html, &page_url) {
+                if is_same_site(&site, &link) && seen.insert(crawl_key(&link)) {
+                    queue.push_back((link, depth + 1));
+                }
+            }
+        }
+
+        let is_start_page = pages.is_empty();
+        pages.push(CrawledPage { depth, collected });
+
+        if is_start_page && limits.use_sitemap && limits.max_depth > 0 {
+            for link in sitemap_urls(&site, collector, &mut warnings) {
+                if is_same_site(&site, &link) && seen.insert(crawl_key(&link)) {
+                    queue.push_back((link, 1));
+                }
+            }
+        }
+    }
+
+    if !queue.is_empty() {
+        warnings.push(format!(
+            "crawl stopped at --max-pages {}; {} discovered page(s) were not audited",
+            limits.max_pages,
+            queue.len()
+        ));
+    }
+
+    Ok(CrawlResult { pages, warnings })
+}
+
+/// Analyzes every crawled page and merges the results into one artifact.
+///
+/// Assets are de-duplicated by URL. Each detected integration keeps the first
+/// evidence seen, its highest per-page confidence and the evidence from every
+/// page it was detected on, and each page warning is prefixed with the page it
+/// came from.
+pub(crate) fn analyze_crawl(crawl: &CrawlResult) -> CliResult<AuditArtifact> {
+    let mut assets_by_url = BTreeMap::<String, AuditedAsset>::new();
+    let mut integrations = BTreeMap::<String, DetectedIntegration>::new();
+    let mut warnings = Vec::new();
+    let mut pages = Vec::new();
+    let mut start: Option<AuditArtifact> = None;
+
+    for crawled in &crawl.pages {
+        let artifact = analyze_collected_page(&crawled.collected)?;
+        for integration in &artifact.detected_integrations {
+            let merged = integrations
+                .entry(integration.id.clone())
+                .or_insert_with(|| integration.clone());
+            merged.confidence = merged.confidence.max(integration.confidence);
+            merged.pages.push(PageEvidence {
+                url: artifact.audited_url.clone(),
+                evidence: integration.evidence.clone(),
+            });
+        }
+        for asset in &artifact.assets {
+            assets_by_url
+                .entry(asset.url.clone())
+                .or_insert_with(|| asset.clone());
+        }
+        warnings.extend(
+            artifact
+                .warnings
+                .iter()
+                .map(|warning| format!("{}: {warning}", artifact.audited_url)),
+        );
+        pages.push(AuditedPage {
+            url: artifact.audited_url.clone(),
+            page_title: artifact.page_title.clone(),
+            depth: crawled.depth,
+            js_asset_count: artifact.js_asset_count,
+            detected_integrations: artifact
+                .detected_integrations
+                .iter()
+                .map(|integration| integration.id.clone())
+                .collect(),
+        });
+        start.get_or_insert(artifact);
+    }
+
+    let start = start.ok_or_else(|| report_error("crawl did not audit any pages"))?;
+    warnings.extend(crawl.warnings.iter().cloned());
+    let assets = assets_by_url.into_values().collect::<Vec<_>>();
+    let third_party_asset_count = assets
+        .iter()
+        .filter(|asset| asset.party == AssetParty::ThirdParty)
+        .count();
+
+    Ok(AuditArtifact {
+        audited_url: start.audited_url,
+        page_title: start.page_title,
+        js_asset_count: assets.len(),
+        third_party_asset_count,
+        detected_integrations: integrations.into_values().collect(),
+        assets,
+        warnings,
+        pages,
+    })
+}
+
+fn is_same_site(site_url: &Url, link: &Url) -> bool {
+    classify_party(site_url, link) == AssetParty::FirstParty
+}
+
+/// The de-duplication key for a page: its URL without the fragment.
+fn crawl_key(url: &Url) -> String {
+    let mut url = url.clone();
+    url.set_fragment(None);
+    url.to_string()
+}
+
+pub(crate) fn discover_links(html: &str, page_url: &Url) -> Vec<Url> {
+    let document = Html::parse_document(html);
+    let selector = Selector::parse(r#"a[href], link[rel~="amphtml"][href]"#)
+        .expect("should parse crawl link selector");
+
+    document
+        .select(&selector)
+        .filter_map(|element| element.value().attr("href"))
+        .filter_map(|href| page_url.join(href.trim()).ok())
+        .filter_map(|mut link| {
+            link.set_fragment(None);
+            is_crawlable(&link).then_some(link)
+        })
+        .collect()
+}
+
+fn is_crawlable(url: &Url) -> bool {
+    if !matches!(url.scheme(), "http" | "https") {
+        return false;
+    }
+    let path = url.path().to_ascii_lowercase();
+    !NON_PAGE_EXTENSIONS
+        .iter()
+        .any(|extension| path.ends_with(extension))
+}
+
+/// Reads `/sitemap.xml` and, for a sitemap index, its nested sitemaps up to
+/// [`MAX_SITEMAP_DOCUMENTS`]. Fetch failures become warnings.
+fn sitemap_urls(
+    site_url: &Url,
+    collector: &dyn AuditCollector,
+    warnings: &mut Vec<String>,
+) -> Vec<Url> {
+    let Ok(root) = site_url.join("/sitemap.xml") else {
+        return Vec::new();
+    };
+    let mut documents = VecDeque::from([root]);
+    let mut fetched = 0;
+    let mut pages = Vec::new();
+
+    while let Some(sitemap) = documents.pop_front() {
+        if fetched >= MAX_SITEMAP_DOCUMENTS {
+            break;
+        }
+        fetched += 1;
+        let text = match collector.fetch_text(&sitemap) {
+            Ok(Some(text)) => text,
+            Ok(None) => continue,
+            Err(error) => {
+                warnings.push(format!("could not read sitemap `{sitemap}`: {error}"));
+                continue;
+            }
+        };
+        let is_index = text.contains("<sitemapindex");
+        for location in parse_sitemap_locations(&text) {
+            let Ok(url) = Url::parse(&location) else {
+                continue;
+            };
+            if is_index {
+                documents.push_back(url);
+            } else if is_crawlable(&url) {
+                pages.push(url);
+            }
+        }
+    }
+
+    pages
+}
+
+pub(crate) fn parse_sitemap_locations(xml: &str) -> Vec<String> {
+    SITEMAP_LOC_REGEX
+        .captures_iter(xml)
+        .filter_map(|captures| captures.get(1))
+        .map(|location| unescape_xml(location.as_str()))
+        .collect()
+}
+
+fn unescape_xml(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::commands::audit::collector::CollectedScriptTag;
+
+    #[derive(Default)]
+    struct SiteCollector {
+        pages: HashMap<String, CollectedPage>,
+        documents: HashMap<String, String>,
+        visits: RefCell<Vec<String>>,
+    }
+
+    impl SiteCollector {
+        fn page(mut self, url: &str, html: &str, script: Option<&str>) -> Self {
+            self.pages.insert(
+                url.to_string(),
+                CollectedPage {
+                    requested_url: url.to_string(),
+                    final_url: url.to_string(),
+                    page_title: None,
+                    html: html.to_string(),
+                    script_tags: script
+                        .map(|src| CollectedScriptTag {
+                            src: Some(src.to_string()),
+                            inline_text: None,
+                        })
+                        .into_iter()
+                        .collect(),
+                    network_requests: Vec::new(),
+                    globals: Vec::new(),
+                    cookies: Vec::new(),
+                    warnings: Vec::new(),
+                },
+            );
+            self
+        }
+
+        fn document(mut self, url: &str, text: &str) -> Self {
+            self.documents.insert(url.to_string(), text.to_string());
+            self
+        }
+    }
+
+    impl AuditCollector for SiteCollector {
+        fn collect_page(&self, target_url: &Url) -> CliResult<CollectedPage> {
+            self.visits.borrow_mut().push(target_url.to_string());
+            self.pages
+                .get(target_url.as_str())
+                .cloned()
+                .ok_or_else(|| format!("no page at {target_url}"))
+        }
+
+        fn fetch_text(&self, url: &Url) -> CliResult<Option<String>> {
+            Ok(self.documents.get(url.as_str()).cloned())
+        }
+    }
+
+    fn start_url() -> Url {
+        Url::parse("https://publisher.example/").expect("should parse URL")
+    }
+
+    fn limits(max_depth: usize, max_pages: usize) -> CrawlLimits {
+        CrawlLimits {
+            max_depth,
+            max_pages,
+            use_sitemap: true,
+        }
+    }
+
+    #[test]
+    fn discover_links_keeps_pages_and_amp_alternates() {
+        let html = r#"<html><head><link rel="amphtml" href="/story/amp"></head><body>
+            <a href="/news#top">News</a>
+            <a href="mailto:desk@publisher.example">Mail</a>
+            <a href="/media/report.pdf">Report</a>
+            <a href="https://other.example/">Other</a>
+        </body></html>"#;
+
+        let links = discover_links(html, &start_url())
+            .into_iter()
+            .map(String::from)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            links,
+            vec![
+                "https://publisher.example/story/amp",
+                "https://publisher.example/news",
+                "https://other.example/",
+            ],
+            "should resolve links, drop fragments and skip non-page targets"
+        );
+    }
+
+    #[test]
+    fn crawl_follows_same_site_links_within_depth() {
+        let collector = SiteCollector::default()
+            .page(
+                "https://publisher.example/",
+                r#"<a href="/news">News</a><a href="https://other.example/">Other</a>"#,
+                None,
+            )
+            .page(
+                "https://publisher.example/news",
+                r#"<a href="/news/story">Story</a><a href="/">Home</a>"#,
+                None,
+            )
+            .page("https://publisher.example/news/story", "", None);
+
+        let result =
+            crawl_site(&start_url(), limits(1, 10), &collector).expect("should crawl site");
+
+        assert_eq!(
+            *collector.visits.borrow(),
+            vec![
+                "https://publisher.example/",
+                "https://publisher.example/news"
+            ],
+            "should stay on the site, visit each page once and stop at the depth limit"
+        );
+        assert_eq!(result.pages[1].depth, 1, "should record link depth");
+    }
+
+    #[test]
+    fn crawl_seeds_from_sitemap_and_respects_page_limit() {
+        let collector = SiteCollector::default()
+            .page("https://publisher.example/", "", None)
+            .page("https://publisher.example/a?x=1&y=2", "", None)
+            .document(
+                "https://publisher.example/sitemap.xml",
+                "<sitemapindex><sitemap><loc>https://publisher.example/news.xml</loc></sitemap></sitemapindex>",
+            )
+            .document(
+                "https://publisher.example/news.xml",
+                "<urlset><url><loc> https://publisher.example/a?x=1&amp;y=2 </loc></url>\
+                 <url><loc>https://publisher.example/b</loc></url></urlset>",
+            );
+
+        let result = crawl_site(&start_url(), limits(2, 2), &collector).expect("should crawl site");
+
+        assert_eq!(
+            *collector.visits.borrow(),
+            vec![
+                "https://publisher.example/",
+                "https://publisher.example/a?x=1&y=2"
+            ],
+            "should follow the sitemap index and unescape locations"
+        );
+        assert!(
+            result
+                .warnings
+                .iter()
+                .any(|warning| warning.contains("--max-pages 2")),
+            "should report pages left unaudited"
+        );
+    }
+
+    #[test]
+    fn crawl_skips_failed_pages_but_fails_on_start_page() {
+        let collector = SiteCollector::default().page(
+            "https://publisher.example/",
+            r#"<a href="/missing">Missing</a>"#,
+            None,
+        );
+
+        let result =
+            crawl_site(&start_url(), limits(1, 10), &collector).expect("should crawl site");
+
+        assert_eq!(result.pages.len(), 1, "should keep the start page");
+        assert!(
+            result.warnings[0].starts_with("skipped `https://publisher.example/missing`"),
+            "should warn about the failed page"
+        );
+
+        let empty = SiteCollector::default();
+        assert!(
+            crawl_site(&start_url(), limits(1, 10), &empty).is_err(),
+            "should fail when the start page cannot be audited"
+        );
+    }
+
+    #[test]
+    fn analyze_crawl_merges_integrations_with_per_page_evidence() {
+        let gpt = "https://securepubads.g.doubleclick.net/tag/js/gpt.js";
+        let collector = SiteCollector::default()
+            .page(
+                "https://publisher.example/",
+                r#"<a href="/amp">AMP</a>"#,
+                Some(gpt),
+            )
+            .page(
+                "https://publisher.example/amp",
+                "",
+                Some("https://sdk.privacy-center.org/loader.js"),
+            );
+        let crawl = crawl_site(&start_url(), limits(1, 10), &collector).expect("should crawl");
+
+        let artifact = analyze_crawl(&crawl).expect("should merge crawl");
+
+        assert_eq!(artifact.audited_url, "https://publisher.example/");
+        assert_eq!(artifact.pages.len(), 2, "should list every page");
+        assert_eq!(artifact.js_asset_count, 2, "should merge assets");
+        let didomi = artifact
+            .detected_integrations
+            .iter()
+            .find(|integration| integration.id == "didomi")
+            .expect("should detect Didomi on the AMP page");
+        assert_eq!(
+            didomi.pages,
+            vec![PageEvidence {
+                url: "https://publisher.example/amp".to_string(),
+                evidence: "https://sdk.privacy-center.org/loader.js".to_string(),
+            }],
+            "should record the page each integration was found on"
+        );
+        assert_eq!(
+            artifact.pages[0].detected_integrations,
+            vec!["gpt".to_string()],
+            "should summarize integrations per page"
+        );
+    }
+}
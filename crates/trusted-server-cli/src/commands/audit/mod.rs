@@ -1,6 +1,8 @@
 mod analyzer;
 pub(crate) mod browser_collector;
 pub(crate) mod collector;
+mod crawl;
+mod vendors;
 
 use std::collections::BTreeSet;
 use std::fs;
@@ -36,6 +38,19 @@ pub(crate) struct AuditArgs {
     /// Overwrite existing output files.
     #[arg(long)]
     pub(crate) force: bool,
+    /// Also audit same-site pages linked from the URL (including AMP
+    /// alternates and `/sitemap.xml` entries) and merge the results.
+    #[arg(long)]
+    pub(crate) crawl: bool,
+    /// Maximum link depth from the audited URL when crawling.
+    #[arg(long, default_value_t = 2, requires = "crawl")]
+    pub(crate) max_depth: usize,
+    /// Maximum number of pages to audit when crawling.
+    #[arg(long, default_value_t = 20, requires = "crawl")]
+    pub(crate) max_pages: usize,
+    /// Do not seed the crawl from `/sitemap.xml`.
+    #[arg(long, requires = "crawl")]
+    pub(crate) no_sitemap: bool,
 }
 
 const DEFAULT_JS_ASSETS_PATH: &str = "js-assets.toml";
@@ -61,9 +76,38 @@ pub(crate) struct AuditedAsset {
 #[derive(Debug, Clone, Serialize, PartialEq, Eq)]
 pub(crate) struct DetectedIntegration {
     pub(crate) id: String,
+    pub(crate) name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) category: Option<String>,
+    /// 0-100 score combining every kind of signal that matched the vendor.
+    pub(crate) confidence: u8,
+    pub(crate) evidence: String,
+    /// The `[integrations.*]` section to enable, for vendors with a Trusted
+    /// Server integration.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) config_snippet: Option<String>,
+    /// Per-page evidence when the integration was merged from a crawl.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) pages: Vec<PageEvidence>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub(crate) struct PageEvidence {
+    pub(crate) url: String,
     pub(crate) evidence: String,
 }
 
+/// One page visited by `ts audit --crawl`.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub(crate) struct AuditedPage {
+    pub(crate) url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) page_title: Option<String>,
+    pub(crate) depth: usize,
+    pub(crate) js_asset_count: usize,
+    pub(crate) detected_integrations: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, PartialEq, Eq)]
 pub(crate) struct AuditArtifact {
     pub(crate) audited_url: String,
@@ -74,6 +118,9 @@ pub(crate) struct AuditArtifact {
     pub(crate) detected_integrations: Vec<DetectedIntegration>,
     pub(crate) assets: Vec<AuditedAsset>,
     pub(crate) warnings: Vec<String>,
+    /// Pages merged into this artifact; empty for a single-page audit.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) pages: Vec<AuditedPage>,
 }
 
 #[derive(Debug, Clone)]
@@ -96,8 +143,21 @@ pub(crate) fn run_audit(
 ) -> CliResult<()> {
     let target_url = parse_audit_url(&args.url)?;
     let plan = resolve_output_plan(args)?;
-    let collected = collector.collect_page(&target_url)?;
-    let outputs = build_audit_outputs(&collected)?;
+    let outputs = if args.crawl {
+        if args.max_pages == 0 {
+            return cli_error("--max-pages must be at least 1");
+        }
+        let limits = crawl::CrawlLimits {
+            max_depth: args.max_depth,
+            max_pages: args.max_pages,
+            use_sitemap: !args.no_sitemap,
+        };
+        let crawled = crawl::crawl_site(&target_url, limits, collector)?;
+        build_crawl_outputs(&crawled)?
+    } else {
+        let collected = collector.collect_page(&target_url)?;
+        build_audit_outputs(&collected)?
+    };
     let wrote_config = plan.config_path.is_some();
     let written = write_audit_outputs(&outputs, &plan)?;
     write_success_summary(&outputs, &written, wrote_config, out)
@@ -172,9 +232,20 @@ fn build_audit_outputs(collected: &collector::CollectedPage) -> CliResult<AuditO
     let final_url = collected
         .final_url()
         .map_err(|error| report_error(format!("invalid final URL: {error}")))?;
+    finish_audit_outputs(artifact, &final_url)
+}
+
+fn build_crawl_outputs(crawled: &crawl::CrawlResult) -> CliResult<AuditOutputs> {
+    let artifact = crawl::analyze_crawl(crawled)?;
+    let final_url = Url::parse(&artifact.audited_url)
+        .map_err(|error| report_error(format!("invalid final URL: {error}")))?;
+    finish_audit_outputs(artifact, &final_url)
+}
+
+fn finish_audit_outputs(artifact: AuditArtifact, final_url: &Url) -> CliResult<AuditOutputs> {
     let js_assets_toml = toml::to_string_pretty(&artifact)
         .map_err(|error| report_error(format!("failed to serialize audit artifact: {error}")))?;
-    let draft_config_toml = build_draft_config(&final_url, &artifact)?;
+    let draft_config_toml = build_draft_config(final_url, &artifact)?;
 
     Ok(AuditOutputs {
         artifact,
@@ -265,7 +336,28 @@ fn write_success_summary(
         },
         draft_note
     )
-    .map_err(|error| report_error(format!("failed to write command output: {error}")))
+    .map_err(|error| report_error(format!("failed to write command output: {error}")))?;
+
+    if outputs.artifact.pages.is_empty() {
+        return Ok(());
+    }
+    writeln!(out, "Pages audited: {}", outputs.artifact.pages.len())
+        .map_err(|error| report_error(format!("failed to write command output: {error}")))?;
+    for page in &outputs.artifact.pages {
+        writeln!(
+            out,
+            "  {} (depth {}): {}",
+            page.url,
+            page.depth,
+            if page.detected_integrations.is_empty() {
+                "none".to_string()
+            } else {
+                page.detected_integrations.join(", ")
+            }
+        )
+        .map_err(|error| report_error(format!("failed to write command output: {error}")))?;
+    }
+    Ok(())
 }
 
 fn build_draft_config(target_url: &Url, artifact: &AuditArtifact) -> CliResult<String> {
@@ -331,12 +423,21 @@ fn build_draft_config(target_url: &Url, artifact: &AuditArtifact) -> CliResult<S
         }
     }
 
+    // Vendors the catalog knows have no Trusted Server integration are listed
+    // in the JS asset audit only; everything else needs a human decision.
+    let mut without_integration = Vec::new();
     for integration in detected {
-        if !matches!(
+        if matches!(
             integration,
             "gpt" | "didomi" | "datadome" | "google_tag_manager"
         ) {
-            manual_review.push(integration);
+            continue;
+        }
+        match vendors::catalog().get(integration) {
+            Some(vendor) if vendor.config_snippet.is_none() => {
+                without_integration.push(integration)
+            }
+            _ => manual_review.push(integration),
         }
     }
 
@@ -346,12 +447,37 @@ fn build_draft_config(target_url: &Url, artifact: &AuditArtifact) -> CliResult<S
         }
         draft.push_str("\n# Audit findings requiring manual review\n");
         for integration in manual_review {
+            let section = format!("[integrations.{integration}]");
+            if draft.contains(&section) {
+                draft.push_str(&format!(
+                    "# - Detected {integration}; review the corresponding {section} section before enabling it.\n"
+                ));
+                continue;
+            }
             draft.push_str(&format!(
-                "# - Detected {integration}; review the corresponding [integrations.{integration}] section before enabling it.\n"
+                "# - Detected {integration}; add a {section} section before enabling it:\n"
             ));
+            if let Some(snippet) = vendors::catalog()
+                .get(integration)
+                .and_then(|vendor| vendor.config_snippet.as_deref())
+            {
+                for line in snippet.lines() {
+                    draft.push_str(&format!("#   {line}\n"));
+                }
+            }
         }
     }
 
+    if !without_integration.is_empty() {
+        if !draft.ends_with('\n') {
+            draft.push('\n');
+        }
+        draft.push_str(&format!(
+            "\n# Also detected (no Trusted Server integration): {}\n",
+            without_integration.join(", ")
+        ));
+    }
+
     Ok(draft)
 }
 
@@ -457,6 +583,8 @@ mod tests {
                 url: "https://cdn.publisher.example/app.js".to_string(),
                 resource_type: Some("script".to_string()),
             }],
+            globals: Vec::new(),
+            cookies: Vec::new(),
             warnings: Vec::new(),
         }
     }
@@ -469,6 +597,10 @@ mod tests {
             no_js_assets: false,
             no_config: false,
             force: false,
+            crawl: false,
+            max_depth: 2,
+            max_pages: 20,
+            no_sitemap: false,
         }
     }
 
@@ -551,6 +683,10 @@ mod tests {
             no_js_assets: false,
             no_config: false,
             force: false,
+            crawl: false,
+            max_depth: 2,
+            max_pages: 20,
+            no_sitemap: false,
         };
         let collector = FakeCollector::new(collected_page());
         let mut out = Vec::new();
@@ -628,6 +764,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn run_audit_crawl_lists_pages_in_artifact_and_summary() {
+        let temp = TempDir::new().expect("should create temp dir");
+        let js_assets = temp.path().join("js-assets.toml");
+        let mut args = audit_args("https://publisher.example/page");
+        args.js_assets = Some(js_assets.clone());
+        args.no_config = true;
+        args.crawl = true;
+        let collector = FakeCollector::new(collected_page());
+        let mut out = Vec::new();
+
+        run_audit(&args, &collector, &mut out).expect("should run crawl audit");
+
+        let summary = String::from_utf8(out).expect("summary should be UTF-8");
+        assert!(summary.contains("Pages audited: 1"));
+        assert!(
+            summary.contains("https://publisher.example/page (depth 0): google_tag_manager, gpt")
+        );
+        let artifact = fs::read_to_string(js_assets).expect("should read artifact");
+        assert!(
+            artifact.contains("[[pages]]"),
+            "should persist the crawled page list"
+        );
+    }
+
+    #[test]
+    fn run_audit_crawl_rejects_zero_page_limit() {
+        let mut args = audit_args("https://publisher.example/page");
+        args.crawl = true;
+        args.max_pages = 0;
+        args.no_config = true;
+        args.js_assets = Some(
+            TempDir::new()
+                .expect("should create temp dir")
+                .path()
+                .join("js-assets.toml"),
+        );
+        let collector = FakeCollector::new(collected_page());
+
+        let error =
+            run_audit(&args, &collector, &mut Vec::new()).expect_err("should reject zero pages");
+
+        assert_eq!(collector.calls.get(), 0, "should not collect page");
+        assert!(error.contains("--max-pages"), "should name the flag");
+    }
+
     #[test]
     fn run_audit_conflict_prevents_collection() {
         let temp = TempDir::new().expect("should create temp dir");
@@ -659,19 +841,35 @@ mod tests {
             detected_integrations: vec![
                 DetectedIntegration {
                     id: "google_tag_manager".to_string(),
+                    name: "google_tag_manager".to_string(),
+                    category: None,
+                    confidence: 70,
                     evidence: "GTM-ABC123".to_string(),
+                    config_snippet: None,
+                    pages: Vec::new(),
                 },
                 DetectedIntegration {
                     id: "gpt".to_string(),
+                    name: "gpt".to_string(),
+                    category: None,
+                    confidence: 70,
                     evidence: "https://securepubads.g.doubleclick.net/tag/js/gpt.js".to_string(),
+                    config_snippet: None,
+                    pages: Vec::new(),
                 },
                 DetectedIntegration {
                     id: "prebid".to_string(),
+                    name: "prebid".to_string(),
+                    category: None,
+                    confidence: 70,
                     evidence: "inline script matched `prebid`".to_string(),
+                    config_snippet: None,
+                    pages: Vec::new(),
                 },
             ],
             assets: Vec::new(),
             warnings: Vec::new(),
+            pages: Vec::new(),
         };
 
         let draft = build_draft_config(&url, &artifact).expect("should build draft config");
@@ -686,6 +884,38 @@ mod tests {
         toml::from_str::<toml::Value>(&draft).expect("draft should parse as TOML");
     }
 
+    #[test]
+    fn build_draft_config_adds_missing_snippets_and_lists_unsupported_vendors() {
+        let url = Url::parse("https://publisher.example/path").expect("should parse URL");
+        let detected = |id: &str| DetectedIntegration {
+            id: id.to_string(),
+            name: id.to_string(),
+            category: None,
+            confidence: 70,
+            evidence: "cookie".to_string(),
+            config_snippet: None,
+            pages: Vec::new(),
+        };
+        let artifact = AuditArtifact {
+            audited_url: url.to_string(),
+            page_title: None,
+            js_asset_count: 0,
+            third_party_asset_count: 0,
+            detected_integrations: vec![detected("ga4"), detected("onetrust"), detected("osano")],
+            assets: Vec::new(),
+            warnings: Vec::new(),
+            pages: Vec::new(),
+        };
+
+        let draft = build_draft_config(&url, &artifact).expect("should build draft config");
+
+        assert!(draft.contains("# - Detected osano; add a [integrations.osano] section"));
+        assert!(draft.contains("#   [integrations.osano]\n#   enabled = true"));
+        assert!(draft.contains("# Also detected (no Trusted Server integration): ga4, onetrust"));
+        assert!(!draft.contains("[integrations.onetrust]"));
+        toml::from_str::<toml::Value>(&draft).expect("draft should parse as TOML");
+    }
+
     #[test]
     fn build_draft_config_does_not_enable_gtm_without_container_id() {
         let url = Url::parse("https://publisher.example/path").expect("should parse URL");
@@ -696,10 +926,16 @@ mod tests {
             third_party_asset_count: 1,
             detected_integrations: vec![DetectedIntegration {
                 id: "google_tag_manager".to_string(),
+                name: "google_tag_manager".to_string(),
+                category: None,
+                confidence: 70,
                 evidence: "https://www.googletagmanager.com/gtm.js".to_string(),
+                config_snippet: None,
+                pages: Vec::new(),
             }],
             assets: Vec::new(),
             warnings: Vec::new(),
+            pages: Vec::new(),
         };
 
         let draft = build_draft_config(&url, &artifact).expect("should build draft config");
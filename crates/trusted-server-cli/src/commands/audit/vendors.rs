@@ -0,0 +1,328 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::Deserialize;
+use url::Url;
+
+/// The vendor signature catalog shipped with the CLI.
+const VENDOR_CATALOG: &str = include_str!("vendors.toml");
+
+static CATALOG: LazyLock<VendorCatalog> = LazyLock::new(|| {
+    VendorCatalog::parse(VENDOR_CATALOG).expect("should parse bundled vendor catalog")
+});
+
+/// The kinds of page evidence a vendor signature can match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum SignalKind {
+    Script,
+    Endpoint,
+    Global,
+    Cookie,
+    Inline,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CatalogFile {
+    signal_weights: SignalWeights,
+    vendors: Vec<VendorEntry>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SignalWeights {
+    script: u8,
+    endpoint: u8,
+    global: u8,
+    cookie: u8,
+    inline: u8,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct VendorEntry {
+    id: String,
+    name: String,
+    category: String,
+    #[serde(default)]
+    script_urls: Vec<String>,
+    #[serde(default)]
+    endpoints: Vec<String>,
+    #[serde(default)]
+    globals: Vec<String>,
+    #[serde(default)]
+    cookies: Vec<String>,
+    #[serde(default)]
+    inline_patterns: Vec<String>,
+    config_snippet: Option<String>,
+}
+
+#[derive(Debug)]
+pub(crate) struct VendorSignature {
+    pub(crate) id: String,
+    pub(crate) name: String,
+    pub(crate) category: String,
+    /// The `[integrations.<id>]` snippet, present only for vendors with a
+    /// Trusted Server integration.
+    pub(crate) config_snippet: Option<String>,
+    script_urls: Vec<String>,
+    endpoints: Vec<String>,
+    globals: Vec<String>,
+    cookies: Vec<String>,
+    inline_patterns: Vec<Regex>,
+}
+
+#[derive(Debug)]
+pub(crate) struct VendorCatalog {
+    weights: SignalWeights,
+    vendors: Vec<VendorSignature>,
+}
+
+/// Returns the bundled vendor catalog.
+pub(crate) fn catalog() -> &'static VendorCatalog {
+    &CATALOG
+}
+
+impl VendorCatalog {
+    fn parse(text: &str) -> Result<Self, String> {
+        let file: CatalogFile =
+            toml::from_str(text).map_err(|error| format!("invalid vendor catalog: {error}"))?;
+        let vendors = file
+            .vendors
+            .into_iter()
+            .map(|entry| {
+                let inline_patterns = entry
+                    .inline_patterns
+                    .iter()
+                    .map(|pattern| {
+                        Regex::new(pattern).map_err(|error| {
+                            format!("invalid inline pattern for vendor `{}`: {error}", entry.id)
+                        })
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(VendorSignature {
+                    id: entry.id,
+                    name: entry.name,
+                    category: entry.category,
+                    config_snippet: entry.config_snippet,
+                    script_urls: lowercase(entry.script_urls),
+                    endpoints: lowercase(entry.endpoints),
+                    globals: entry.globals,
+                    cookies: entry.cookies,
+                    inline_patterns,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(Self {
+            weights: file.signal_weights,
+            vendors,
+        })
+    }
+
+    pub(crate) fn get(&self, id: &str) -> Option<&VendorSignature> {
+        self.vendors.iter().find(|vendor| vendor.id == id)
+    }
+
+    /// The first vendor whose script signature matches `url`.
+    pub(crate) fn match_script_url(&self, url: &Url) -> Option<&VendorSignature> {
+        let value = host_and_path(url);
+        self.vendors.iter().find(|vendor| {
+            vendor
+                .script_urls
+                .iter()
+                .any(|pattern| value.contains(pattern))
+        })
+    }
+
+    /// Every vendor whose network endpoint signature matches `url`.
+    pub(crate) fn match_endpoint(&self, url: &Url) -> impl Iterator<Item = &VendorSignature> {
+        let value = host_and_path(url);
+        self.vendors.iter().filter(move |vendor| {
+            vendor
+                .endpoints
+                .iter()
+                .any(|pattern| value.contains(pattern))
+        })
+    }
+
+    /// Every vendor that defines the `window` property `name`.
+    pub(crate) fn match_global<'a>(
+        &'a self,
+        name: &'a str,
+    ) -> impl Iterator<Item = &'a VendorSignature> {
+        self.vendors
+            .iter()
+            .filter(move |vendor| vendor.globals.iter().any(|global| global == name))
+    }
+
+    /// Every vendor that sets the cookie `name`.
+    pub(crate) fn match_cookie<'a>(
+        &'a self,
+        name: &'a str,
+    ) -> impl Iterator<Item = &'a VendorSignature> {
+        self.vendors.iter().filter(move |vendor| {
+            vendor
+                .cookies
+                .iter()
+                .any(|cookie| cookie_matches(cookie, name))
+        })
+    }
+
+    /// Every vendor with an inline pattern matching `script`.
+    pub(crate) fn match_inline<'a>(
+        &'a self,
+        script: &'a str,
+    ) -> impl Iterator<Item = &'a VendorSignature> {
+        self.vendors.iter().filter(move |vendor| {
+            vendor
+                .inline_patterns
+                .iter()
+                .any(|pattern| pattern.is_match(script))
+        })
+    }
+
+    /// Every `window` property named by any vendor, for collectors to probe.
+    pub(crate) fn global_names(&self) -> Vec<&str> {
+        let mut names = self
+            .vendors
+            .iter()
+            .flat_map(|vendor| vendor.globals.iter().map(String::as_str))
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+        names.dedup();
+        names
+    }
+
+    /// Combines independent signal kinds into a 0-100 confidence score.
+    pub(crate) fn confidence(&self, kinds: impl IntoIterator<Item = SignalKind>) -> u8 {
+        let mut kinds = kinds.into_iter().collect::<Vec<_>>();
+        kinds.sort_unstable();
+        kinds.dedup();
+        let missed = kinds.iter().fold(1.0_f64, |missed, kind| {
+            missed * (1.0 - f64::from(self.weight(*kind).min(100)) / 100.0)
+        });
+        // Within 0..=100 by construction, so the cast cannot truncate.
+        ((1.0 - missed) * 100.0).round() as u8
+    }
+
+    fn weight(&self, kind: SignalKind) -> u8 {
+        match kind {
+            SignalKind::Script => self.weights.script,
+            SignalKind::Endpoint => self.weights.endpoint,
+            SignalKind::Global => self.weights.global,
+            SignalKind::Cookie => self.weights.cookie,
+            SignalKind::Inline => self.weights.inline,
+        }
+    }
+}
+
+fn lowercase(values: Vec<String>) -> Vec<String> {
+    values
+        .into_iter()
+        .map(|value| value.to_ascii_lowercase())
+        .collect()
+}
+
+fn host_and_path(url: &Url) -> String {
+    format!("{}{}", url.host_str().unwrap_or_default(), url.path()).to_ascii_lowercase()
+}
+
+fn cookie_matches(pattern: &str, name: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => name.starts_with(prefix),
+        None => pattern == name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(value: &str) -> Url {
+        Url::parse(value).expect("should parse URL")
+    }
+
+    #[test]
+    fn bundled_catalog_parses_with_unique_ids() {
+        let catalog = catalog();
+        let mut ids = catalog
+            .vendors
+            .iter()
+            .map(|vendor| vendor.id.as_str())
+            .collect::<Vec<_>>();
+        let count = ids.len();
+        ids.sort_unstable();
+        ids.dedup();
+
+        assert_eq!(ids.len(), count, "vendor ids should be unique");
+        for id in ["sourcepoint", "osano", "onetrust", "id5", "liveramp", "ga4"] {
+            assert!(catalog.get(id).is_some(), "should catalog {id}");
+        }
+    }
+
+    #[test]
+    fn config_snippets_parse_as_integration_sections() {
+        for vendor in &catalog().vendors {
+            let Some(snippet) = &vendor.config_snippet else {
+                continue;
+            };
+            let value = toml::from_str::<toml::Value>(snippet)
+                .unwrap_or_else(|error| panic!("{} snippet should parse: {error}", vendor.id));
+            assert!(
+                value
+                    .get("integrations")
+                    .and_then(|integrations| integrations.get(&vendor.id))
+                    .is_some(),
+                "{} snippet should define [integrations.{}]",
+                vendor.id,
+                vendor.id
+            );
+        }
+    }
+
+    #[test]
+    fn script_urls_distinguish_gtm_from_ga4() {
+        let catalog = catalog();
+
+        assert_eq!(
+            catalog
+                .match_script_url(&url("https://www.googletagmanager.com/gtag/js?id=G-ABC"))
+                .map(|vendor| vendor.id.as_str()),
+            Some("ga4")
+        );
+        assert_eq!(
+            catalog
+                .match_script_url(&url("https://www.googletagmanager.com/gtm.js?id=GTM-ABC"))
+                .map(|vendor| vendor.id.as_str()),
+            Some("google_tag_manager")
+        );
+    }
+
+    #[test]
+    fn cookie_patterns_support_prefix_wildcards() {
+        let matched = catalog()
+            .match_cookie("_ga_ABC123")
+            .map(|vendor| vendor.id.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(matched, vec!["ga4"]);
+        assert_eq!(catalog().match_cookie("_gab").count(), 0);
+    }
+
+    #[test]
+    fn confidence_combines_distinct_signal_kinds() {
+        let catalog = catalog();
+
+        assert_eq!(catalog.confidence([SignalKind::Script]), 70);
+        assert_eq!(
+            catalog.confidence([SignalKind::Script, SignalKind::Script]),
+            70,
+            "repeated signals of one kind should not raise confidence"
+        );
+        assert_eq!(
+            catalog.confidence([SignalKind::Script, SignalKind::Global]),
+            88
+        );
+        assert_eq!(catalog.confidence(std::iter::empty()), 0);
+    }
+}
@@ -1 +1,4 @@
 pub mod init;
+pub mod push;
+pub mod release;
+pub mod schema;
@@ -0,0 +1,268 @@
+use std::fs;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+
+use edgezero_cli::args::ConfigPushArgs;
+use tempfile::NamedTempFile;
+use trusted_server_core::config::TrustedServerAppConfig;
+use trusted_server_core::config_signing::{
+    CONFIG_SIGNATURE_FIELD, ConfigSignature, ConfigSigner, PINNED_KEYS_ENV, SignedConfig,
+};
+
+use trusted_server_core::settings_data::{default_config_key, last_good_config_key};
+
+use crate::commands::config::release::{VersionedPushArgs, push_versioned};
+
+const DEFAULT_APP_CONFIG: &str = "trusted-server.toml";
+
+/// Adapters that apply `[[experiments]]` per request. The others build
+/// settings once per process and would serve base settings to every arm.
+const EXPERIMENT_ADAPTERS: &[&str] = &["fastly"];
+
+#[derive(Debug, clap::Args)]
+pub struct ConfigPushCommandArgs {
+    #[command(flatten)]
+    pub push: ConfigPushArgs,
+    #[command(flatten)]
+    pub signing: SigningArgs,
+    #[command(flatten)]
+    pub versioned: VersionedPushArgs,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct SigningArgs {
+    /// File holding the standard base64 Ed25519 private key that signs the
+    /// pushed config.
+    #[arg(long, requires = "signing_kid")]
+    pub signing_key: Option<PathBuf>,
+    /// Key id recorded in the signature; must match a key pinned into the
+    /// deployed binary.
+    #[arg(long, requires = "signing_key")]
+    pub signing_kid: Option<String>,
+}
+
+impl SigningArgs {
+    /// Loads the signer for this push, or `None` when no key was given.
+    pub(crate) fn signer(&self) -> Result<Option<ConfigSigner>, String> {
+        let signer = self.load_signer()?;
+        if let Some(signer) = &signer {
+            log::info!(
+                "[ts] signing config with key `{}`; pin it at build time with {PINNED_KEYS_ENV}={}={}",
+                signer.kid(),
+                signer.kid(),
+                signer.public_key()
+            );
+        }
+        Ok(signer)
+    }
+
+    fn load_signer(&self) -> Result<Option<ConfigSigner>, String> {
+        let (Some(path), Some(kid)) = (&self.signing_key, &self.signing_kid) else {
+            return Ok(None);
+        };
+        let key = fs::read_to_string(path)
+            .map_err(|error| format!("failed to read signing key {}: {error}", path.display()))?;
+        ConfigSigner::from_base64_key(kid.as_str(), &key)
+            .map(Some)
+            .map_err(|report| format!("invalid signing key {}: {report}", path.display()))
+    }
+}
+
+pub fn run_config_push(mut args: ConfigPushCommandArgs) -> Result<(), String> {
+    ensure_experiments_supported(&args.push.adapter, &app_config_path(&args.push))?;
+    let signer = args.signing.signer()?;
+    if args.versioned.versioned {
+        return push_versioned(args.push, &args.versioned, signer.as_ref());
+    }
+    let key = args.push.key.clone().unwrap_or_else(default_config_key);
+    // The runtime falls back to the last-good copy when the live entry is
+    // rejected, so refresh it only after the live push succeeded.
+    push_settings(
+        &mut args.push,
+        signer.as_ref(),
+        &[key.clone(), last_good_config_key(&key)],
+    )
+}
+
+/// Pushes the settings at `push.app_config` to each of `keys`, in order.
+///
+/// With a signer, the file is signed as written and `push` is pointed at a
+/// signed copy. Environment overlays are disabled for that push because they
+/// would change the settings after signing.
+pub(crate) fn push_settings(
+    push: &mut ConfigPushArgs,
+    signer: Option<&ConfigSigner>,
+    keys: &[String],
+) -> Result<(), String> {
+    let Some(signer) = signer else {
+        for key in keys {
+            push.key = Some(key.clone());
+            edgezero_cli::run_config_push_typed::<TrustedServerAppConfig>(push)?;
+        }
+        return Ok(());
+    };
+    let path = app_config_path(push);
+    let contents = fs::read_to_string(&path)
+        .map_err(|error| format!("failed to read config {}: {error}", path.display()))?;
+    let table: toml::Table = toml::from_str(&contents)
+        .map_err(|error| format!("failed to parse config {}: {error}", path.display()))?;
+    let config: TrustedServerAppConfig = toml::from_str(&contents)
+        .map_err(|error| format!("failed to parse config {}: {error}", path.display()))?;
+    let signed = SignedConfig::sign(config, signer).map_err(|report| format!("{report}"))?;
+
+    let file = write_config_file(table, Some(signed.signature()))?;
+    if !push.no_env {
+        log::info!(
+            "[ts] skipping environment overlays: the signature covers {} as written",
+            path.display()
+        );
+    }
+    push.app_config = Some(file.path().to_path_buf());
+    push.no_env = true;
+    for key in keys {
+        push.key = Some(key.clone());
+        edgezero_cli::run_config_push_typed::<SignedConfig<TrustedServerAppConfig>>(push)?;
+    }
+    Ok(())
+}
+
+fn app_config_path(push: &ConfigPushArgs) -> PathBuf {
+    push.app_config
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_APP_CONFIG))
+}
+
+/// Rejects a config with enabled `[[experiments]]` for adapters that would
+/// silently ignore them.
+fn ensure_experiments_supported(adapter: &str, path: &Path) -> Result<(), String> {
+    if EXPERIMENT_ADAPTERS.contains(&adapter) {
+        return Ok(());
+    }
+    let contents = fs::read_to_string(path)
+        .map_err(|error| format!("failed to read config {}: {error}", path.display()))?;
+    let table: toml::Table = toml::from_str(&contents)
+        .map_err(|error| format!("failed to parse config {}: {error}", path.display()))?;
+    let enabled = table
+        .get("experiments")
+        .and_then(toml::Value::as_array)
+        .is_some_and(|experiments| {
+            experiments.iter().any(|experiment| {
+                experiment.get("enabled").and_then(toml::Value::as_bool) != Some(false)
+            })
+        });
+    if enabled {
+        return Err(format!(
+            "the {adapter} adapter does not apply [[experiments]]; remove them or disable them \
+             with `enabled = false` (supported: {})",
+            EXPERIMENT_ADAPTERS.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// Writes `table` to a temporary TOML file for the typed push path, adding
+/// `signature` under [`CONFIG_SIGNATURE_FIELD`] when given.
+pub(crate) fn write_config_file(
+    mut table: toml::Table,
+    signature: Option<&ConfigSignature>,
+) -> Result<NamedTempFile, String> {
+    if let Some(signature) = signature {
+        let signature = toml::Value::try_from(signature)
+            .map_err(|error| format!("failed to serialize config signature: {error}"))?;
+        table.insert(CONFIG_SIGNATURE_FIELD.to_string(), signature);
+    }
+    let contents = toml::to_string(&table)
+        .map_err(|error| format!("failed to serialize config file: {error}"))?;
+    let mut file = tempfile::Builder::new()
+        .prefix("ts-config-")
+        .suffix(".toml")
+        .tempfile()
+        .map_err(|error| format!("failed to create config file: {error}"))?;
+    file.write_all(contents.as_bytes())
+        .map_err(|error| format!("failed to write config file: {error}"))?;
+    Ok(file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn signing_args(signing_key: Option<PathBuf>, signing_kid: Option<&str>) -> SigningArgs {
+        SigningArgs {
+            signing_key,
+            signing_kid: signing_kid.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn load_signer_reads_base64_key_file() {
+        let temp = TempDir::new().expect("should create temp dir");
+        let path = temp.path().join("config-signing.key");
+        fs::write(&path, "AQIDBAUGBwgJCgsMDQ4PEBESExQVFhcYGRobHB0eHyA=\n")
+            .expect("should write key file");
+
+        let signer = signing_args(Some(path), Some("ops-2026"))
+            .load_signer()
+            .expect("should load signer")
+            .expect("should build a signer when a key is given");
+
+        assert_eq!(signer.kid(), "ops-2026", "should keep the key id");
+        assert_eq!(
+            signer.public_key().len(),
+            43,
+            "should expose a URL-safe base64 public key"
+        );
+    }
+
+    #[test]
+    fn load_signer_rejects_malformed_key_file() {
+        let temp = TempDir::new().expect("should create temp dir");
+        let path = temp.path().join("config-signing.key");
+        fs::write(&path, "not a key").expect("should write key file");
+
+        let err = signing_args(Some(path), Some("ops-2026"))
+            .load_signer()
+            .err()
+            .expect("should reject a malformed key");
+
+        assert!(
+            err.contains("invalid signing key"),
+            "error should name the key file: {err}"
+        );
+    }
+
+    #[test]
+    fn experiments_are_rejected_for_adapters_that_ignore_them() {
+        let temp = TempDir::new().expect("should create temp dir");
+        let path = temp.path().join("trusted-server.toml");
+        fs::write(
+            &path,
+            "[[experiments]]\nid = \"layout\"\n[[experiments]]\nid = \"off\"\nenabled = false\n",
+        )
+        .expect("should write config");
+
+        ensure_experiments_supported("fastly", &path).expect("should accept fastly");
+        let err = ensure_experiments_supported("axum", &path)
+            .expect_err("should reject experiments for axum");
+        assert!(
+            err.contains("does not apply [[experiments]]"),
+            "error should explain why: {err}"
+        );
+
+        fs::write(&path, "[[experiments]]\nid = \"off\"\nenabled = false\n")
+            .expect("should write config");
+        ensure_experiments_supported("spin", &path).expect("should accept disabled experiments");
+    }
+
+    #[test]
+    fn unsigned_push_loads_no_signer() {
+        assert!(
+            signing_args(None, None)
+                .load_signer()
+                .expect("should accept an unsigned push")
+                .is_none(),
+            "should not sign without a key"
+        );
+    }
+}
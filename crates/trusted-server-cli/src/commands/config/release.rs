@@ -0,0 +1,558 @@
+use std::io::Write;
+use std::time::Duration;
+
+use chrono::{SecondsFormat, Utc};
+use edgezero_cli::args::ConfigPushArgs;
+use trusted_server_core::config_release::{
+    CONFIG_RELEASE_FIELD, ConfigRelease, ConfigReleaseDocument, DEFAULT_RETAINED_VERSIONS,
+    DeployedConfigRelease, version_key,
+};
+use trusted_server_core::config_signing::{ConfigSigner, SignedConfig};
+use trusted_server_core::settings_data::{default_config_key, last_good_config_key};
+use url::Url;
+
+use crate::commands::config::push::{SigningArgs, push_settings, write_config_file};
+use crate::commands::doctor::checks::endpoint;
+use crate::commands::keys::AdminCredentials;
+use crate::http::{HttpClient, HttpRequest, ReqwestHttpClient, parse_deployment_url};
+
+const CONFIG_RELEASE_PATH: &str = "/_ts/admin/config/release";
+
+/// Adapters whose runtime resolves a release manifest from the config store.
+/// Cloudflare reads a single blob from `TRUSTED_SERVER_CONFIG` and Spin loads
+/// settings baked into the binary.
+const MANIFEST_ADAPTERS: &[&str] = &["fastly", "axum"];
+
+/// Adapters that pick the config version per request by EC bucket. Axum
+/// loads settings once at startup, so a staged candidate would never serve.
+const STAGED_ROLLOUT_ADAPTERS: &[&str] = &["fastly"];
+
+#[derive(Debug, clap::Args)]
+pub struct DeploymentArgs {
+    /// Public URL of the deployment. The release manifest is read from its
+    /// config store through `/_ts/admin/config/release`, so every operator
+    /// starts from the release that is actually deployed.
+    #[arg(long)]
+    pub url: Option<String>,
+    /// Basic-auth user of the `/_ts/admin` handler. The password is read from
+    /// `TS_ADMIN_PASSWORD`.
+    #[arg(long, default_value = "admin")]
+    pub admin_user: String,
+    /// Per-request timeout in seconds.
+    #[arg(long, default_value_t = 15)]
+    pub timeout: u64,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct VersionedPushArgs {
+    /// Keep the pushed config as a new version and update the release
+    /// manifest instead of overwriting the config in place.
+    #[arg(long)]
+    pub versioned: bool,
+    /// Serve the new version to this percentage of EC-bucketed traffic until
+    /// `ts config promote`.
+    #[arg(long, requires = "versioned", value_parser = clap::value_parser!(u8).range(1..=99))]
+    pub stage: Option<u8>,
+    /// Note recorded with the version in `ts config history`.
+    #[arg(long, requires = "versioned")]
+    pub note: Option<String>,
+    /// Number of versions to keep in the release manifest.
+    #[arg(long, default_value_t = DEFAULT_RETAINED_VERSIONS)]
+    pub retain: usize,
+    #[command(flatten)]
+    pub deployment: DeploymentArgs,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct ConfigHistoryArgs {
+    /// Config-store key of the release. Defaults to the `EdgeZero` app-config
+    /// key.
+    #[arg(long)]
+    pub key: Option<String>,
+    #[command(flatten)]
+    pub deployment: DeploymentArgs,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct ConfigRollbackArgs {
+    /// Retained version to serve to all traffic.
+    pub version: u32,
+    #[command(flatten)]
+    pub push: ConfigPushArgs,
+    #[command(flatten)]
+    pub signing: SigningArgs,
+    #[command(flatten)]
+    pub deployment: DeploymentArgs,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct ConfigPromoteArgs {
+    /// Widen the staged rollout to this percentage instead of promoting the
+    /// candidate to all traffic.
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=99))]
+    pub percent: Option<u8>,
+    #[command(flatten)]
+    pub push: ConfigPushArgs,
+    #[command(flatten)]
+    pub signing: SigningArgs,
+    #[command(flatten)]
+    pub deployment: DeploymentArgs,
+}
+
+/// Pushes the config to a new version key, then points the release manifest
+/// at it.
+pub(crate) fn push_versioned(
+    mut push: ConfigPushArgs,
+    versioned: &VersionedPushArgs,
+    signer: Option<&ConfigSigner>,
+) -> Result<(), String> {
+    ensure_adapter_supports(&push.adapter, versioned.stage.is_some())?;
+    let key = push.key.clone().unwrap_or_else(default_config_key);
+    let mut release = load_deployed_release(&versioned.deployment, &key)?;
+    let pushed_at = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+    let version = release
+        .record_push(pushed_at, versioned.note.clone(), versioned.stage)
+        .map_err(|report| format!("{report}"))?;
+    let dropped = release.retain_latest(versioned.retain);
+
+    push_settings(&mut push, signer, &[version_key(&key, version)])?;
+    push_release_manifest(push, &key, &release, signer)?;
+
+    log_release_state(&release);
+    if !dropped.is_empty() {
+        let keys: Vec<String> = dropped.iter().map(|v| version_key(&key, *v)).collect();
+        log::info!(
+            "[ts] dropped versions from the release history; their entries remain in the config store: {}",
+            keys.join(", ")
+        );
+    }
+    Ok(())
+}
+
+pub fn run_config_history(args: &ConfigHistoryArgs) -> Result<(), String> {
+    let key = args.key.clone().unwrap_or_else(default_config_key);
+    let release = load_deployed_release(&args.deployment, &key)?;
+    let stdout = std::io::stdout();
+    let mut out = stdout.lock();
+    write_history(&release, &mut out)
+}
+
+pub fn run_config_rollback(args: ConfigRollbackArgs) -> Result<(), String> {
+    ensure_adapter_supports(&args.push.adapter, false)?;
+    let signer = args.signing.signer()?;
+    let key = args.push.key.clone().unwrap_or_else(default_config_key);
+    let mut release = load_deployed_release(&args.deployment, &key)?;
+    release
+        .rollback(args.version)
+        .map_err(|report| format!("{report}"))?;
+    push_release_manifest(args.push, &key, &release, signer.as_ref())?;
+    log_release_state(&release);
+    Ok(())
+}
+
+pub fn run_config_promote(args: ConfigPromoteArgs) -> Result<(), String> {
+    ensure_adapter_supports(&args.push.adapter, args.percent.is_some())?;
+    let signer = args.signing.signer()?;
+    let key = args.push.key.clone().unwrap_or_else(default_config_key);
+    let mut release = load_deployed_release(&args.deployment, &key)?;
+    release
+        .promote(args.percent)
+        .map_err(|report| format!("{report}"))?;
+    push_release_manifest(args.push, &key, &release, signer.as_ref())?;
+    log_release_state(&release);
+    Ok(())
+}
+
+/// Logs the rollout `release` now records, which can differ from what was
+/// asked for: the first version of a release is stable even when staged.
+fn log_release_state(release: &ConfigRelease) {
+    match release.candidate {
+        Some(candidate) => log::info!(
+            "[ts] config version {} is staged to {}% of traffic; version {} stays stable",
+            candidate.version,
+            candidate.percent,
+            release.stable
+        ),
+        None => log::info!("[ts] config version {} is now stable", release.stable),
+    }
+}
+
+/// Reads the release manifest deployed at `key` from the deployment in `args`.
+fn load_deployed_release(args: &DeploymentArgs, key: &str) -> Result<ConfigRelease, String> {
+    let Some(url) = args.url.as_deref() else {
+        return Err(
+            "pass --url: the release manifest is read from the deployed config store".to_string(),
+        );
+    };
+    let base_url = parse_deployment_url(url)?;
+    let credentials = AdminCredentials::from_env(&args.admin_user)?;
+    let client = ReqwestHttpClient::new(Duration::from_secs(args.timeout))?;
+    fetch_deployed_release(&client, &base_url, &credentials, key)
+}
+
+/// Fetches the deployed release manifest. A deployment whose settings were
+/// pushed in place has no manifest yet, so its release starts empty.
+fn fetch_deployed_release(
+    client: &dyn HttpClient,
+    base_url: &Url,
+    credentials: &AdminCredentials,
+    key: &str,
+) -> Result<ConfigRelease, String> {
+    let response = client.send(
+        &HttpRequest::get(endpoint(base_url, CONFIG_RELEASE_PATH))
+            .with_basic_auth(&credentials.user, &credentials.password),
+    )?;
+    let deployed: DeployedConfigRelease = match response.status {
+        200 => serde_json::from_str(&response.body).map_err(|error| {
+            format!("{CONFIG_RELEASE_PATH} returned an unexpected body: {error}")
+        })?,
+        401 | 403 => {
+            return Err(format!(
+                "{CONFIG_RELEASE_PATH} rejected the admin credentials for `{}`",
+                credentials.user
+            ));
+        }
+        404 => {
+            return Err(format!(
+                "{CONFIG_RELEASE_PATH} returned HTTP 404; deploy a build that serves it and check \
+                 that a basic-auth handler covers `^/_ts/admin`"
+            ));
+        }
+        status => {
+            return Err(format!(
+                "{CONFIG_RELEASE_PATH} returned HTTP {status}: {}",
+                response.body.trim()
+            ));
+        }
+    };
+    if deployed.key != key {
+        return Err(format!(
+            "the deployment serves config key `{}`, not `{key}`",
+            deployed.key
+        ));
+    }
+    Ok(deployed.release.unwrap_or_default())
+}
+
+/// Rejects versioned releases for adapters that would not serve them, and
+/// staged rollouts for adapters that cannot bucket per request.
+fn ensure_adapter_supports(adapter: &str, staged: bool) -> Result<(), String> {
+    if !MANIFEST_ADAPTERS.contains(&adapter) {
+        return Err(format!(
+            "the {adapter} adapter cannot read versioned releases; push without --versioned \
+             (supported: {})",
+            MANIFEST_ADAPTERS.join(", ")
+        ));
+    }
+    if staged && !STAGED_ROLLOUT_ADAPTERS.contains(&adapter) {
+        return Err(format!(
+            "the {adapter} adapter cannot serve a staged rollout because it does not pick the \
+             config version per request; push without --stage or promote without --percent \
+             (supported: {})",
+            STAGED_ROLLOUT_ADAPTERS.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// Publishes `release` at `key`, then at its last-good copy, through the typed
+/// push path so it is hashed, chunked, and signed like a settings blob.
+fn push_release_manifest(
+    mut push: ConfigPushArgs,
+    key: &str,
+    release: &ConfigRelease,
+    signer: Option<&ConfigSigner>,
+) -> Result<(), String> {
+    let mut table = toml::Table::new();
+    table.insert(
+        CONFIG_RELEASE_FIELD.to_string(),
+        toml::Value::try_from(release)
+            .map_err(|error| format!("failed to serialize release manifest: {error}"))?,
+    );
+    // Environment overlays target settings, not the manifest.
+    push.no_env = true;
+    let keys = [key.to_string(), last_good_config_key(key)];
+
+    let Some(signer) = signer else {
+        let file = write_config_file(table, None)?;
+        push.app_config = Some(file.path().to_path_buf());
+        for key in keys {
+            push.key = Some(key);
+            edgezero_cli::run_config_push_typed::<ConfigReleaseDocument>(&push)?;
+        }
+        return Ok(());
+    };
+    let document = ConfigReleaseDocument {
+        release: release.clone(),
+    };
+    let signed = SignedConfig::sign(document, signer).map_err(|report| format!("{report}"))?;
+    let file = write_config_file(table, Some(signed.signature()))?;
+    push.app_config = Some(file.path().to_path_buf());
+    for key in keys {
+        push.key = Some(key);
+        edgezero_cli::run_config_push_typed::<SignedConfig<ConfigReleaseDocument>>(&push)?;
+    }
+    Ok(())
+}
+
+fn write_history(release: &ConfigRelease, out: &mut dyn Write) -> Result<(), String> {
+    let write_error = |error: std::io::Error| format!("failed to write command output: {error}");
+    if release.versions.is_empty() {
+        writeln!(out, "No versioned config pushes recorded.").map_err(write_error)?;
+        return Ok(());
+    }
+    writeln!(
+        out,
+        "{:<8} {:<21} {:<16} NOTE",
+        "VERSION", "PUSHED AT", "STATUS"
+    )
+    .map_err(write_error)?;
+    for entry in release.versions.iter().rev() {
+        let status = match release.candidate {
+            Some(candidate) if candidate.version == entry.version => {
+                format!("candidate {}%", candidate.percent)
+            }
+            _ if release.stable == entry.version => "stable".to_string(),
+            _ => String::new(),
+        };
+        writeln!(
+            out,
+            "{:<8} {:<21} {:<16} {}",
+            entry.version,
+            entry.pushed_at,
+            status,
+            entry.note.as_deref().unwrap_or_default()
+        )
+        .map_err(write_error)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::fs;
+
+    use crate::error::CliResult;
+    use crate::http::HttpResponse;
+    use trusted_server_core::config_signing::{parse_pinned_config_keys, verify_config_value};
+
+    fn staged_release() -> ConfigRelease {
+        let mut release = ConfigRelease::default();
+        release
+            .record_push("2026-10-01T00:00:00Z".to_string(), None, None)
+            .expect("should record push");
+        release
+            .record_push(
+                "2026-10-02T00:00:00Z".to_string(),
+                Some("new bidder".to_string()),
+                Some(10),
+            )
+            .expect("should record staged push");
+        release
+    }
+
+    struct FakeDeployment {
+        status: u16,
+        body: String,
+        requests: RefCell<Vec<HttpRequest>>,
+    }
+
+    impl FakeDeployment {
+        fn serving(key: &str, release: Option<ConfigRelease>) -> Self {
+            let body = serde_json::to_string(&DeployedConfigRelease {
+                key: key.to_string(),
+                release,
+            })
+            .expect("should serialize deployed release");
+            Self {
+                status: 200,
+                body,
+                requests: RefCell::new(Vec::new()),
+            }
+        }
+    }
+
+    impl HttpClient for FakeDeployment {
+        fn send(&self, request: &HttpRequest) -> CliResult<HttpResponse> {
+            self.requests.borrow_mut().push(request.clone());
+            Ok(HttpResponse {
+                status: self.status,
+                headers: Vec::new(),
+                body: self.body.clone(),
+            })
+        }
+    }
+
+    fn base_url() -> Url {
+        Url::parse("https://publisher.example").expect("should parse URL")
+    }
+
+    fn credentials() -> AdminCredentials {
+        AdminCredentials {
+            user: "admin".to_string(),
+            password: "secret".to_string(),
+        }
+    }
+
+    #[test]
+    fn reads_the_release_deployed_in_the_config_store() {
+        let deployment = FakeDeployment::serving("trusted_server_config", Some(staged_release()));
+
+        let release = fetch_deployed_release(
+            &deployment,
+            &base_url(),
+            &credentials(),
+            "trusted_server_config",
+        )
+        .expect("should fetch the deployed release");
+
+        assert_eq!(
+            release,
+            staged_release(),
+            "should use the deployed manifest"
+        );
+        let requests = deployment.requests.borrow();
+        assert_eq!(requests[0].url.path(), CONFIG_RELEASE_PATH);
+        assert_eq!(
+            requests[0].basic_auth,
+            Some(("admin".to_string(), "secret".to_string())),
+            "should authenticate to the admin route"
+        );
+    }
+
+    #[test]
+    fn in_place_settings_start_an_empty_release() {
+        let deployment = FakeDeployment::serving("trusted_server_config", None);
+
+        let release = fetch_deployed_release(
+            &deployment,
+            &base_url(),
+            &credentials(),
+            "trusted_server_config",
+        )
+        .expect("should fetch the deployed release");
+
+        assert_eq!(release, ConfigRelease::default());
+    }
+
+    #[test]
+    fn deployed_release_must_be_at_the_pushed_key() {
+        let deployment = FakeDeployment::serving("trusted_server_config", Some(staged_release()));
+
+        let err = fetch_deployed_release(&deployment, &base_url(), &credentials(), "other_key")
+            .expect_err("should reject a release at another key");
+
+        assert!(
+            err.contains("trusted_server_config"),
+            "error should name the served key: {err}"
+        );
+    }
+
+    #[test]
+    fn unauthorized_release_read_names_the_admin_user() {
+        let mut deployment = FakeDeployment::serving("trusted_server_config", None);
+        deployment.status = 401;
+
+        let err = fetch_deployed_release(
+            &deployment,
+            &base_url(),
+            &credentials(),
+            "trusted_server_config",
+        )
+        .expect_err("should fail on rejected credentials");
+
+        assert!(err.contains("`admin`"), "error should name the user: {err}");
+    }
+
+    #[test]
+    fn history_lists_newest_first_with_status() {
+        let mut out = Vec::new();
+        write_history(&staged_release(), &mut out).expect("should write history");
+        let out = String::from_utf8(out).expect("should be UTF-8");
+        let lines: Vec<&str> = out.lines().collect();
+
+        assert_eq!(
+            lines.len(),
+            3,
+            "should print a header and one row per version"
+        );
+        assert!(
+            lines[1].starts_with('2') && lines[1].contains("candidate 10%"),
+            "newest version should be the staged candidate: {out}"
+        );
+        assert!(lines[1].ends_with("new bidder"), "should print the note");
+        assert!(
+            lines[2].starts_with('1') && lines[2].contains("stable"),
+            "older version should be stable: {out}"
+        );
+    }
+
+    #[test]
+    fn versioned_releases_require_a_manifest_aware_adapter() {
+        ensure_adapter_supports("fastly", true).expect("should stage on fastly");
+        ensure_adapter_supports("axum", false).expect("should version on axum");
+
+        for adapter in ["cloudflare", "spin"] {
+            let err = ensure_adapter_supports(adapter, false)
+                .expect_err("should reject versioned releases");
+            assert!(
+                err.contains("--versioned"),
+                "error should name the flag for {adapter}: {err}"
+            );
+        }
+        let err = ensure_adapter_supports("axum", true).expect_err("should reject staging on axum");
+        assert!(err.contains("--stage"), "error should name the flag: {err}");
+    }
+
+    fn manifest_table(release: &ConfigRelease) -> toml::Table {
+        toml::Table::from_iter([(
+            CONFIG_RELEASE_FIELD.to_string(),
+            toml::Value::try_from(release).expect("should serialize release"),
+        )])
+    }
+
+    #[test]
+    fn manifest_toml_parses_as_a_release_document() {
+        let release = staged_release();
+        let file =
+            write_config_file(manifest_table(&release), None).expect("should write manifest");
+        let manifest = fs::read_to_string(file.path()).expect("should read manifest");
+
+        let document: ConfigReleaseDocument =
+            toml::from_str(&manifest).expect("should parse the manifest document");
+
+        assert_eq!(document.release, release, "should round-trip through TOML");
+    }
+
+    #[test]
+    fn signed_manifest_file_carries_a_verifiable_signature() {
+        let signer = ConfigSigner::from_base64_key(
+            "ops-2026",
+            "AQIDBAUGBwgJCgsMDQ4PEBESExQVFhcYGRobHB0eHyA=",
+        )
+        .expect("should parse signing key");
+        let pinned = parse_pinned_config_keys(&format!("ops-2026={}", signer.public_key()))
+            .expect("should parse pinned key");
+        let release = staged_release();
+        let signed = SignedConfig::sign(
+            ConfigReleaseDocument {
+                release: release.clone(),
+            },
+            &signer,
+        )
+        .expect("should sign manifest");
+        let file = write_config_file(manifest_table(&release), Some(signed.signature()))
+            .expect("should write signed manifest");
+        let manifest = fs::read_to_string(file.path()).expect("should read manifest");
+
+        let document: SignedConfig<ConfigReleaseDocument> =
+            toml::from_str(&manifest).expect("should parse the signed manifest");
+        let mut payload =
+            serde_json::to_value(&document).expect("should serialize the pushed payload");
+
+        verify_config_value(&mut payload, &pinned).expect("should verify the pushed payload");
+        assert_eq!(document.config().release, release);
+    }
+}
@@ -0,0 +1,423 @@
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+
+use edgezero_cli::args::ConfigValidateArgs;
+use serde_json::Value as JsonValue;
+use toml_edit::{DocumentMut, Item, value};
+use trusted_server_core::config::TrustedServerAppConfig;
+use trusted_server_core::config_schema::{
+    CONFIG_SCHEMA_VERSION, CONFIG_SCHEMA_VERSION_FIELD, KeySegment, SCHEMA_CHANGES, SchemaChange,
+    detect_schema_version, find_keys, key_segments, keys_unknown_to, renamed_path,
+    validate_schema_version,
+};
+
+const DEFAULT_APP_CONFIG: &str = "trusted-server.toml";
+
+#[derive(Debug, clap::Args)]
+pub struct ConfigMigrateArgs {
+    /// App-config path rewritten in place.
+    #[arg(long = "app-config", alias = "config", default_value = DEFAULT_APP_CONFIG)]
+    pub app_config: PathBuf,
+    /// Config schema version to migrate to. Defaults to the schema of this
+    /// `ts` build.
+    #[arg(long)]
+    pub to: Option<u32>,
+    /// Print the changes without rewriting the file.
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct ConfigValidateCommandArgs {
+    #[command(flatten)]
+    pub validate: ConfigValidateArgs,
+    /// Also report the keys a binary on this config schema version would
+    /// reject in the pushed config.
+    #[arg(long, value_name = "SCHEMA_VERSION")]
+    pub target_binary: Option<u32>,
+}
+
+struct Migration {
+    from: u32,
+    document: String,
+    steps: Vec<String>,
+}
+
+pub fn run_config_migrate(args: &ConfigMigrateArgs) -> Result<(), String> {
+    let stdout = std::io::stdout();
+    let mut out = stdout.lock();
+    run_config_migrate_with_writer(args, &mut out)
+}
+
+fn run_config_migrate_with_writer(
+    args: &ConfigMigrateArgs,
+    out: &mut dyn Write,
+) -> Result<(), String> {
+    let write_error = |error: std::io::Error| format!("failed to write command output: {error}");
+    let to = args.to.unwrap_or(CONFIG_SCHEMA_VERSION);
+    let contents = fs::read_to_string(&args.app_config).map_err(|error| {
+        format!(
+            "failed to read config {}: {error}",
+            args.app_config.display()
+        )
+    })?;
+    let migration = migrate_document(&contents, to, SCHEMA_CHANGES)?;
+
+    for step in &migration.steps {
+        writeln!(out, "{step}").map_err(write_error)?;
+    }
+    writeln!(
+        out,
+        "Migrated {} from config schema {} to {to}",
+        args.app_config.display(),
+        migration.from
+    )
+    .map_err(write_error)?;
+    if args.dry_run {
+        return Ok(());
+    }
+    fs::write(&args.app_config, migration.document).map_err(|error| {
+        format!(
+            "failed to write config {}: {error}",
+            args.app_config.display()
+        )
+    })
+}
+
+/// Rewrites `contents` for schema `to`, keeping comments and formatting of
+/// every key it does not touch. Migrating down removes keys the older schema
+/// rejects, so their settings fall back to that binary's behavior.
+fn migrate_document(
+    contents: &str,
+    to: u32,
+    changes: &[SchemaChange],
+) -> Result<Migration, String> {
+    validate_schema_version(to).map_err(|report| format!("{report}"))?;
+    let mut document = contents
+        .parse::<DocumentMut>()
+        .map_err(|error| format!("failed to parse config: {error}"))?;
+    let from = detect_schema_version(&document_json(&document)?, changes);
+    validate_schema_version(from)
+        .map_err(|report| format!("config declares {CONFIG_SCHEMA_VERSION_FIELD}: {report}"))?;
+
+    let mut steps = Vec::new();
+    if to >= from {
+        for change in changes
+            .iter()
+            .filter(|change| change.since() > from && change.since() <= to)
+        {
+            if let SchemaChange::Renamed { path, to: name, .. } = change {
+                for key in find_keys(&document_json(&document)?, path) {
+                    rename_key(&mut document, &key, name)?;
+                    steps.push(format!("renamed {key} to {}", renamed_path(&key, name)));
+                }
+            }
+        }
+    } else {
+        for change in changes
+            .iter()
+            .rev()
+            .filter(|change| change.since() > to && change.since() <= from)
+        {
+            match change {
+                SchemaChange::Added { since, path } => {
+                    for key in find_keys(&document_json(&document)?, path) {
+                        remove_key(&mut document, &key)?;
+                        steps.push(format!("removed {key} (added in config schema {since})"));
+                    }
+                }
+                SchemaChange::Renamed { path, to: name, .. } => {
+                    let original = path.rsplit('.').next().unwrap_or(path);
+                    for key in find_keys(&document_json(&document)?, &renamed_path(path, name)) {
+                        rename_key(&mut document, &key, original)?;
+                        steps.push(format!("renamed {key} to {}", renamed_path(&key, original)));
+                    }
+                }
+            }
+        }
+    }
+
+    match document.get_mut(CONFIG_SCHEMA_VERSION_FIELD) {
+        Some(Item::Value(current)) => {
+            let decor = current.decor().clone();
+            *current = i64::from(to).into();
+            *current.decor_mut() = decor;
+        }
+        _ => {
+            document.insert(CONFIG_SCHEMA_VERSION_FIELD, value(i64::from(to)));
+        }
+    }
+
+    Ok(Migration {
+        from,
+        document: document.to_string(),
+        steps,
+    })
+}
+
+fn document_json(document: &DocumentMut) -> Result<JsonValue, String> {
+    toml::from_str(&document.to_string())
+        .map_err(|error| format!("failed to parse config: {error}"))
+}
+
+fn parent_item<'a>(
+    document: &'a mut DocumentMut,
+    path: &str,
+) -> Result<(&'a mut Item, String), String> {
+    let mut segments = key_segments(path);
+    let Some(KeySegment::Field(field)) = segments.pop() else {
+        return Err(format!("config key {path} does not name a field"));
+    };
+    let mut item = document.as_item_mut();
+    for segment in segments {
+        let next = match segment {
+            KeySegment::Field(name) => item.get_mut(name.as_str()),
+            KeySegment::Index(index) => item.get_mut(index),
+        };
+        item = next.ok_or_else(|| format!("config key {path} not found"))?;
+    }
+    Ok((item, field))
+}
+
+fn remove_key(document: &mut DocumentMut, path: &str) -> Result<(), String> {
+    let (parent, field) = parent_item(document, path)?;
+    parent
+        .as_table_like_mut()
+        .and_then(|table| table.remove(&field))
+        .map(|_| ())
+        .ok_or_else(|| format!("config key {path} not found"))
+}
+
+fn rename_key(document: &mut DocumentMut, path: &str, name: &str) -> Result<(), String> {
+    let (parent, field) = parent_item(document, path)?;
+    let table = parent
+        .as_table_like_mut()
+        .ok_or_else(|| format!("config key {path} not found"))?;
+    if table.contains_key(name) {
+        return Err(format!(
+            "cannot rename {path}: {} is already set",
+            renamed_path(path, name)
+        ));
+    }
+    let item = table
+        .remove(&field)
+        .ok_or_else(|| format!("config key {path} not found"))?;
+    table.insert(name, item);
+    Ok(())
+}
+
+pub fn run_config_validate(args: &ConfigValidateCommandArgs) -> Result<(), String> {
+    edgezero_cli::run_config_validate_typed::<TrustedServerAppConfig>(&args.validate)?;
+    let Some(target) = args.target_binary else {
+        return Ok(());
+    };
+    validate_schema_version(target).map_err(|report| format!("{report}"))?;
+
+    // Environment overlays only replace leaves already present in the file,
+    // so the file decides which keys reach the blob.
+    let path = args
+        .validate
+        .app_config
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_APP_CONFIG));
+    let contents = fs::read_to_string(&path)
+        .map_err(|error| format!("failed to read config {}: {error}", path.display()))?;
+    let config: TrustedServerAppConfig = toml::from_str(&contents)
+        .map_err(|error| format!("failed to parse config {}: {error}", path.display()))?;
+    let blob = serde_json::to_value(config.settings())
+        .map_err(|error| format!("failed to serialize config: {error}"))?;
+
+    let stdout = std::io::stdout();
+    let mut out = stdout.lock();
+    check_target_binary(&blob, target, &mut out)
+}
+
+fn check_target_binary(blob: &JsonValue, target: u32, out: &mut dyn Write) -> Result<(), String> {
+    let write_error = |error: std::io::Error| format!("failed to write command output: {error}");
+    let unknown = keys_unknown_to(blob, target, SCHEMA_CHANGES);
+    if unknown.is_empty() {
+        writeln!(out, "Config loads on config schema {target} binaries").map_err(write_error)?;
+        return Ok(());
+    }
+    for key in &unknown {
+        writeln!(out, "{} (config schema {})", key.path, key.since).map_err(write_error)?;
+    }
+    Err(format!(
+        "config schema {target} binaries reject {} pushed key(s); upgrade the binary first or run `ts config migrate --to {target}`",
+        unknown.len()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const RENAMES: &[SchemaChange] = &[SchemaChange::Renamed {
+        since: 2,
+        path: "integrations.aps.pub_id",
+        to: "account_id",
+    }];
+
+    const SCHEMA_2_CONFIG: &str = r#"# Publisher config
+[publisher]
+domain = "example.com"
+
+[auction]
+# Per-user bid cache
+bid_cache = { enabled = true, kv_store = "bids" }
+timeout_ms = 800
+
+[[creative_opportunities.slot]]
+id = "top"
+
+[[creative_opportunities.slot]]
+id = "side"
+
+# Refresh the sidebar
+[creative_opportunities.slot.refresh]
+interval_secs = 30
+max_refreshes = 3
+"#;
+
+    #[test]
+    fn migrate_down_removes_newer_keys_and_keeps_comments() {
+        let migration =
+            migrate_document(SCHEMA_2_CONFIG, 1, SCHEMA_CHANGES).expect("should migrate to 1");
+
+        assert_eq!(migration.from, 2, "should detect schema 2 from its keys");
+        assert_eq!(
+            migration.steps,
+            vec![
+                "removed creative_opportunities.slot[1].refresh (added in config schema 2)",
+                "removed auction.bid_cache (added in config schema 2)",
+            ]
+        );
+        assert!(
+            migration
+                .document
+                .starts_with("config_schema_version = 1\n# Publisher config\n[publisher]"),
+            "should record the schema version and keep comments: {}",
+            migration.document
+        );
+        assert!(
+            !migration.document.contains("bid_cache") && !migration.document.contains("refresh"),
+            "should remove the newer keys: {}",
+            migration.document
+        );
+        assert!(
+            migration.document.contains("timeout_ms = 800"),
+            "should keep older keys"
+        );
+
+        let redetected =
+            migrate_document(&migration.document, 1, SCHEMA_CHANGES).expect("should migrate again");
+        assert!(
+            redetected.steps.is_empty(),
+            "a migrated config should need no further steps"
+        );
+    }
+
+    #[test]
+    fn migrate_up_applies_renames_and_back() {
+        let config = "[integrations.aps]\n# APS account\npub_id = \"5000\"\n";
+
+        let up = migrate_document(config, 2, RENAMES).expect("should migrate up");
+        assert_eq!(
+            up.steps,
+            vec!["renamed integrations.aps.pub_id to integrations.aps.account_id"]
+        );
+        assert!(
+            up.document.contains("account_id = \"5000\"") && !up.document.contains("pub_id"),
+            "should rename the key: {}",
+            up.document
+        );
+
+        let down = migrate_document(&up.document, 1, RENAMES).expect("should migrate down");
+        assert!(
+            down.document.contains("pub_id = \"5000\"") && !down.document.contains("account_id"),
+            "should restore the old name: {}",
+            down.document
+        );
+        assert!(
+            down.document.contains("config_schema_version = 1"),
+            "should update the declared version in place: {}",
+            down.document
+        );
+    }
+
+    #[test]
+    fn migrate_rejects_unknown_schema_versions() {
+        let err = migrate_document(SCHEMA_2_CONFIG, CONFIG_SCHEMA_VERSION + 1, SCHEMA_CHANGES)
+            .err()
+            .expect("should reject a newer target");
+        assert!(err.contains("is not supported"), "unexpected error: {err}");
+
+        let declared_newer = format!(
+            "{CONFIG_SCHEMA_VERSION_FIELD} = {}\n{SCHEMA_2_CONFIG}",
+            CONFIG_SCHEMA_VERSION + 1
+        );
+        assert!(
+            migrate_document(&declared_newer, 1, SCHEMA_CHANGES).is_err(),
+            "should refuse a config written for a newer schema"
+        );
+    }
+
+    #[test]
+    fn migrate_command_honors_dry_run() {
+        let temp = TempDir::new().expect("should create temp dir");
+        let path = temp.path().join(DEFAULT_APP_CONFIG);
+        fs::write(&path, SCHEMA_2_CONFIG).expect("should write config");
+        let mut args = ConfigMigrateArgs {
+            app_config: path.clone(),
+            to: Some(1),
+            dry_run: true,
+        };
+
+        let mut out = Vec::new();
+        run_config_migrate_with_writer(&args, &mut out).expect("should dry-run migration");
+        assert_eq!(
+            fs::read_to_string(&path).expect("should read config"),
+            SCHEMA_2_CONFIG,
+            "dry run should not rewrite the file"
+        );
+        let out = String::from_utf8(out).expect("should be UTF-8");
+        assert!(
+            out.contains("removed auction.bid_cache"),
+            "should print the steps: {out}"
+        );
+
+        args.dry_run = false;
+        run_config_migrate_with_writer(&args, &mut Vec::new()).expect("should migrate");
+        assert!(
+            !fs::read_to_string(&path)
+                .expect("should read config")
+                .contains("bid_cache"),
+            "should rewrite the file"
+        );
+    }
+
+    #[test]
+    fn target_binary_check_lists_rejected_keys() {
+        let blob = serde_json::json!({
+            "publisher": { "domain": "example.com" },
+            "auction": { "bid_cache": { "enabled": true } },
+        });
+
+        let mut out = Vec::new();
+        let err = check_target_binary(&blob, 1, &mut out)
+            .err()
+            .expect("should fail for an older schema");
+        let out = String::from_utf8(out).expect("should be UTF-8");
+        assert_eq!(out, "auction.bid_cache (config schema 2)\n");
+        assert!(
+            err.contains("ts config migrate --to 1"),
+            "should suggest the migration: {err}"
+        );
+
+        let mut out = Vec::new();
+        check_target_binary(&blob, CONFIG_SCHEMA_VERSION, &mut out)
+            .expect("the current schema should accept the config");
+    }
+}
@@ -1,28 +1,28 @@
-// `ts dev proxy` is macOS-only; its dependencies are scoped to macOS in
-// `Cargo.toml`, so the module and the `Proxy` subcommand only exist there. On
-// other host targets `ts dev` parses but exposes no subcommands.
-#[cfg(target_os = "macos")]
+// `ts dev proxy` runs on macOS and Linux; its dependencies are scoped to those
+// hosts in `Cargo.toml`, so the module and the `Proxy` subcommand only exist
+// there. On other host targets `ts dev` parses but exposes no subcommands.
+#[cfg(any(target_os = "macos", target_os = "linux"))]
 pub mod proxy;
 
 /// The `ts dev …` command group.
 #[derive(Debug, clap::Subcommand)]
 pub enum DevCommand {
-    /// Run the local production-hostname dev proxy (macOS only).
-    #[cfg(target_os = "macos")]
+    /// Run the local production-hostname dev proxy (macOS and Linux).
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
     Proxy(proxy::ProxyArgs),
 }
 
 /// Dispatches a `dev` subcommand.
 ///
 /// # Errors
-/// Returns the subcommand's failure rendered as a message. On non-macOS targets
+/// Returns the subcommand's failure rendered as a message. On other targets
 /// `DevCommand` has no variants, so this never returns an error there.
-// On non-macOS targets `DevCommand` is an empty enum: the by-value parameter is
+// On other targets `DevCommand` is an empty enum: the by-value parameter is
 // consumed by an empty `match`, which clippy reads as a needless by-value pass.
 // Taking `&DevCommand` is not an option — a zero-arm `match` is not exhaustive
 // over a reference type — so the owned parameter is required.
 #[cfg_attr(
-    not(target_os = "macos"),
+    not(any(target_os = "macos", target_os = "linux")),
     allow(
         clippy::needless_pass_by_value,
         reason = "empty enum requires owned value for exhaustive match"
@@ -30,7 +30,7 @@ pub enum DevCommand {
 )]
 pub fn run(command: DevCommand) -> Result<(), String> {
     match command {
-        #[cfg(target_os = "macos")]
+        #[cfg(any(target_os = "macos", target_os = "linux"))]
         DevCommand::Proxy(args) => proxy::run(&args).map_err(|report| format!("{report:?}")),
     }
 }
@@ -2,6 +2,8 @@
 
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 use std::path::Path;
+#[cfg(target_os = "linux")]
+use std::path::PathBuf;
 use std::process::Command;
 
 use super::ProxyError;
@@ -18,6 +20,7 @@ use crate::output;
 /// is `on` or `off`. A missing third line is tolerated when reading (treated as
 /// `on` if a URL is present, else `off`) for forward-compatibility with the
 /// earlier two-line format.
+#[cfg(target_os = "macos")]
 const SAFARI_RESTORE_FILE: &str = "safari-proxy-restore";
 
 /// Generates a PAC script that proxies only `https://` requests for matched FROM hosts.
@@ -69,9 +72,12 @@ fn login_keychain() -> String {
     format!("{home}/Library/Keychains/login.keychain-db")
 }
 
-/// Adds the CA certificate to the macOS login keychain (spec §7.3).
+/// Adds the CA certificate to the OS trust store (spec §7.3).
 ///
-/// On non-macOS systems, or if the `security` command fails, prints manual
+/// On macOS this is the login keychain. On Linux the CA is imported into the
+/// per-user NSS databases Chrome/Chromium read (see `linux_nss_dbs`) and then
+/// into the system trust store, which needs `sudo`. Whatever cannot be automated
+/// (no `security`/`certutil`, sudo declined, another OS) prints manual
 /// instructions via [`crate::output`]. Never panics.
 pub fn ca_install(cert_path: &Path) {
     #[cfg(target_os = "macos")]
@@ -92,21 +98,29 @@ pub fn ca_install(cert_path: &Path) {
             )),
         }
     }
-    #[cfg(not(target_os = "macos"))]
+    #[cfg(target_os = "linux")]
+    {
+        for db_dir in linux_nss_dbs(&linux_home()) {
+            nss_install(&db_dir, cert_path);
+        }
+        system_trust_install(cert_path);
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
     output::info(&format!(
         "add this CA to your OS trust store manually: {}",
         cert_path.display()
     ));
 }
 
-/// Removes the dev CA from the macOS login keychain (spec §7.3).
+/// Removes the dev CA from the OS trust store (spec §7.3).
 ///
 /// Returns `true` when the CA is confirmed absent afterward (removed, or never
 /// installed), and `false` when a removal may have failed and old trust could
 /// remain — in which case it warns loudly. There can be more than one entry with
 /// the CA's common name after repeated installs, so it deletes until none are
-/// found. On non-macOS systems, prints a manual note and returns `true`. Never
-/// panics.
+/// found. On macOS this covers the login keychain; on Linux, the NSS databases
+/// and the system trust store [`ca_install`] writes to. On other systems,
+/// prints a manual note and returns `true`. Never panics.
 #[must_use]
 pub fn ca_uninstall() -> bool {
     #[cfg(target_os = "macos")]
@@ -143,13 +157,362 @@ pub fn ca_uninstall() -> bool {
         );
         false
     }
-    #[cfg(not(target_os = "macos"))]
+    #[cfg(target_os = "linux")]
+    {
+        // Check every location even after one fails, so a single stuck store
+        // does not leave the others trusting the CA.
+        let mut removed = true;
+        for db_dir in linux_nss_dbs(&linux_home()) {
+            removed &= nss_uninstall(&db_dir);
+        }
+        removed &= system_trust_uninstall();
+        removed
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
     {
         output::info("remove the dev CA from your OS trust store manually");
         true
     }
 }
 
+/// The current user's home directory, from `$HOME`.
+#[cfg(target_os = "linux")]
+fn linux_home() -> PathBuf {
+    PathBuf::from(std::env::var_os("HOME").unwrap_or_default())
+}
+
+/// The per-user NSS databases the dev CA is trusted in on Linux.
+///
+/// Chrome and Chromium on Linux ignore the system trust store and read the
+/// shared NSS database at `~/.pki/nssdb` — regardless of `--user-data-dir` —
+/// so that is always included. The Chromium snap is confined to its own copy
+/// under `~/snap/chromium/current`, included when the snap is installed.
+/// Firefox gets the CA in its throwaway profile instead (see
+/// [`launch_firefox`]).
+#[cfg(target_os = "linux")]
+fn linux_nss_dbs(home: &Path) -> Vec<PathBuf> {
+    let mut dbs = vec![home.join(".pki").join("nssdb")];
+    let snap = home.join("snap").join("chromium");
+    if snap.is_dir() {
+        dbs.push(snap.join("current").join(".pki").join("nssdb"));
+    }
+    dbs
+}
+
+/// Imports the CA into the NSS database at `db_dir`, creating an empty
+/// database first when the account has never had one.
+#[cfg(target_os = "linux")]
+fn nss_install(db_dir: &Path, cert_path: &Path) {
+    use std::os::unix::fs::DirBuilderExt as _;
+
+    let db = format!("sql:{}", db_dir.display());
+    if !db_dir.join("cert9.db").exists() {
+        // Best-effort: `certutil -A` below is the step we check. `0700` matches
+        // the directory Chrome itself creates.
+        let _ = std::fs::DirBuilder::new()
+            .recursive(true)
+            .mode(0o700)
+            .create(db_dir);
+        let _ = Command::new("certutil")
+            .args(["-N", "--empty-password", "-d", &db])
+            .status();
+    }
+    let imported = Command::new("certutil")
+        .args(["-A", "-n", CA_COMMON_NAME, "-t", "CT,,", "-i"])
+        .arg(cert_path)
+        .args(["-d", &db])
+        .status();
+    match imported {
+        Ok(s) if s.success() => {
+            output::info(&format!(
+                "CA added to the NSS database {}",
+                db_dir.display()
+            ));
+        }
+        _ => output::warn(&format!(
+            "could not add the CA to the NSS database {} (certutil missing or failed — it \
+             ships in libnss3-tools / nss-tools); Chrome will not trust it until you run: \
+             certutil -A -n \"{CA_COMMON_NAME}\" -t \"CT,,\" -i {} -d {}",
+            db_dir.display(),
+            shell_quote(&cert_path.display().to_string()),
+            shell_quote(&db),
+        )),
+    }
+}
+
+/// Deletes every entry with the CA's nickname from the NSS database at
+/// `db_dir`. Returns `true` when none remain (or there is no database).
+#[cfg(target_os = "linux")]
+fn nss_uninstall(db_dir: &Path) -> bool {
+    if !db_dir.exists() {
+        return true;
+    }
+    let db = format!("sql:{}", db_dir.display());
+    for _ in 0..16 {
+        match Command::new("certutil")
+            .args(["-L", "-n", CA_COMMON_NAME, "-d", &db])
+            .output()
+        {
+            // Without certutil nothing can be confirmed — fall through to the warning.
+            Err(_) => break,
+            Ok(o) if !o.status.success() => {
+                output::info(&format!(
+                    "CA is not present in the NSS database {} (removed or never installed)",
+                    db_dir.display()
+                ));
+                return true;
+            }
+            Ok(_) => {}
+        }
+        let deleted = Command::new("certutil")
+            .args(["-D", "-n", CA_COMMON_NAME, "-d", &db])
+            .status()
+            .map(|s| s.success())
+            .unwrap_or(false);
+        if !deleted {
+            break;
+        }
+    }
+    output::warn(&format!(
+        "could not fully remove the dev CA from the NSS database {}; it may still be trusted \
+         — run: certutil -D -n \"{CA_COMMON_NAME}\" -d {}",
+        db_dir.display(),
+        shell_quote(&db),
+    ));
+    false
+}
+
+/// Where the CA is anchored in a Debian-family system trust store.
+#[cfg(target_os = "linux")]
+const DEBIAN_ANCHOR_PATH: &str = "/usr/local/share/ca-certificates/trusted-server-dev-proxy.crt";
+
+/// The Linux system trust store tooling available on this machine.
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SystemTrust {
+    /// Debian/Ubuntu: a PEM `.crt` under `/usr/local/share/ca-certificates`,
+    /// compiled in by `update-ca-certificates`.
+    UpdateCaCertificates,
+    /// Fedora/RHEL/Arch: p11-kit anchors managed with `trust anchor`.
+    P11Kit,
+}
+
+/// Detects the system trust store tooling. `update-ca-certificates` lives in
+/// `/usr/sbin`, which is often not on a regular user's `PATH`, so that is
+/// checked explicitly.
+#[cfg(target_os = "linux")]
+fn detect_system_trust() -> Option<SystemTrust> {
+    let update_ca = which::which("update-ca-certificates").is_ok()
+        || Path::new("/usr/sbin/update-ca-certificates").exists();
+    if update_ca
+        && Path::new(DEBIAN_ANCHOR_PATH)
+            .parent()
+            .is_some_and(Path::is_dir)
+    {
+        Some(SystemTrust::UpdateCaCertificates)
+    } else if which::which("trust").is_ok() {
+        Some(SystemTrust::P11Kit)
+    } else {
+        None
+    }
+}
+
+/// The `sudo` commands that anchor `cert_path` in the system trust store.
+#[cfg(target_os = "linux")]
+fn system_trust_install_commands(kind: SystemTrust, cert_path: &str) -> Vec<Vec<String>> {
+    let args = |args: &[&str]| args.iter().map(|a| (*a).to_string()).collect::<Vec<_>>();
+    match kind {
+        SystemTrust::UpdateCaCertificates => vec![
+            args(&["install", "-m", "0644", cert_path, DEBIAN_ANCHOR_PATH]),
+            args(&["update-ca-certificates"]),
+        ],
+        SystemTrust::P11Kit => vec![args(&["trust", "anchor", "--store", cert_path])],
+    }
+}
+
+/// Runs each command under `sudo`, stopping at the first failure.
+#[cfg(target_os = "linux")]
+fn run_sudo(commands: &[Vec<String>]) -> bool {
+    commands
+        .iter()
+        .all(|args| matches!(Command::new("sudo").args(args).status(), Ok(s) if s.success()))
+}
+
+/// Renders `commands` as one copy-pasteable `sudo … && sudo …` line.
+#[cfg(target_os = "linux")]
+fn manual_sudo_line(commands: &[Vec<String>]) -> String {
+    commands
+        .iter()
+        .map(|args| {
+            let quoted: Vec<String> = args.iter().map(|a| shell_quote(a)).collect();
+            format!("sudo {}", quoted.join(" "))
+        })
+        .collect::<Vec<_>>()
+        .join(" && ")
+}
+
+/// Anchors the CA in the system trust store so non-browser clients (`curl`,
+/// language runtimes) accept the proxy's certificates too.
+#[cfg(target_os = "linux")]
+fn system_trust_install(cert_path: &Path) {
+    let Some(kind) = detect_system_trust() else {
+        output::info(&format!(
+            "no supported system trust store tooling found (update-ca-certificates or p11-kit \
+             trust); add this CA to it manually if non-browser clients need it: {}",
+            cert_path.display()
+        ));
+        return;
+    };
+    let commands = system_trust_install_commands(kind, &cert_path.display().to_string());
+    output::info(
+        "system trust store: adding the CA needs admin — sudo will prompt for your password \
+         (only the trust-store commands are elevated).",
+    );
+    if run_sudo(&commands) {
+        output::info("CA added to the system trust store");
+    } else {
+        output::warn(&format!(
+            "could not add the CA to the system trust store (sudo declined or no terminal); \
+             run: {}",
+            manual_sudo_line(&commands)
+        ));
+    }
+}
+
+/// Removes the CA from the system trust store. Returns `true` when it is
+/// confirmed absent afterward.
+#[cfg(target_os = "linux")]
+fn system_trust_uninstall() -> bool {
+    match detect_system_trust() {
+        // Nothing this tool could have installed into.
+        None => true,
+        Some(SystemTrust::UpdateCaCertificates) => {
+            let anchor = Path::new(DEBIAN_ANCHOR_PATH);
+            if !anchor.exists() {
+                return true;
+            }
+            // `--fresh` rebuilds the bundle so the removed anchor is dropped
+            // from it, not just from the source directory.
+            let commands = [
+                vec![
+                    "rm".to_string(),
+                    "-f".to_string(),
+                    DEBIAN_ANCHOR_PATH.to_string(),
+                ],
+                vec!["update-ca-certificates".to_string(), "--fresh".to_string()],
+            ];
+            if run_sudo(&commands) && !anchor.exists() {
+                output::info("CA removed from the system trust store");
+                return true;
+            }
+            output::warn(&format!(
+                "could not remove the dev CA from the system trust store; it may still be \
+                 trusted — run: {}",
+                manual_sudo_line(&commands)
+            ));
+            false
+        }
+        Some(SystemTrust::P11Kit) => {
+            let Some(uris) = p11kit_anchor_uris() else {
+                output::warn(
+                    "could not list the system trust anchors (`trust list` failed); the dev CA \
+                     may still be trusted — remove it with `sudo trust anchor --remove`",
+                );
+                return false;
+            };
+            if uris.is_empty() {
+                return true;
+            }
+            // Remove by PKCS#11 URI rather than by certificate file, so an anchor
+            // whose on-disk cert was already deleted can still be revoked.
+            let commands: Vec<Vec<String>> = uris
+                .iter()
+                .map(|uri| {
+                    vec![
+                        "trust".into(),
+                        "anchor".into(),
+                        "--remove".into(),
+                        uri.clone(),
+                    ]
+                })
+                .collect();
+            if run_sudo(&commands) && p11kit_anchor_uris().is_some_and(|left| left.is_empty()) {
+                output::info("CA removed from the system trust store");
+                return true;
+            }
+            output::warn(&format!(
+                "could not remove the dev CA from the system trust store; it may still be \
+                 trusted — run: {}",
+                manual_sudo_line(&commands)
+            ));
+            false
+        }
+    }
+}
+
+/// PKCS#11 URIs of the p11-kit anchors labelled with the CA's common name, or
+/// `None` when `trust list` cannot be run.
+#[cfg(target_os = "linux")]
+fn p11kit_anchor_uris() -> Option<Vec<String>> {
+    let out = Command::new("trust")
+        .args(["list", "--filter=ca-anchors"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    Some(parse_trust_list(
+        &String::from_utf8_lossy(&out.stdout),
+        CA_COMMON_NAME,
+    ))
+}
+
+/// Picks the URIs of entries labelled `label` out of `trust list` output, whose
+/// entries look like:
+///
+/// ```text
+/// pkcs11:id=%AB%CD;type=cert
+///     type: certificate
+///     label: Trusted Server DEV-ONLY Proxy CA
+///     trust: anchor
+///     category: authority
+/// ```
+#[cfg(target_os = "linux")]
+fn parse_trust_list(text: &str, label: &str) -> Vec<String> {
+    let mut uris = Vec::new();
+    let mut current: Option<&str> = None;
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("pkcs11:") {
+            current = Some(trimmed);
+        } else if let Some(value) = trimmed.strip_prefix("label:")
+            && value.trim() == label
+            && let Some(uri) = current.take()
+        {
+            uris.push(uri.to_string());
+        }
+    }
+    uris
+}
+
+/// Returns the first of `names` found on `PATH`.
+#[cfg(target_os = "linux")]
+fn find_on_path(names: &[&str]) -> Option<PathBuf> {
+    names.iter().find_map(|name| which::which(name).ok())
+}
+
+/// Launcher names Chrome and Chromium ship under across Linux distributions,
+/// in preference order.
+#[cfg(target_os = "linux")]
+const LINUX_CHROME_BINARIES: &[&str] = &[
+    "google-chrome",
+    "google-chrome-stable",
+    "chromium",
+    "chromium-browser",
+];
+
+/// Launcher names Firefox ships under across Linux distributions.
+#[cfg(target_os = "linux")]
+const LINUX_FIREFOX_BINARIES: &[&str] = &["firefox", "firefox-esr"];
+
 /// Launches and configures each requested browser against the proxy (spec §9).
 ///
 /// A browser that cannot be launched or configured logs manual steps and is
@@ -167,7 +530,10 @@ pub fn launch(
         match browser {
             Browser::Chrome => launch_chrome(cfg),
             Browser::Firefox => launch_firefox(cfg),
+            #[cfg(target_os = "macos")]
             Browser::Safari => launch_safari(cfg),
+            #[cfg(not(target_os = "macos"))]
+            Browser::Safari => output::warn("Safari: only available on macOS; skipping"),
         }
     }
     Ok(())
@@ -326,8 +692,10 @@ fn chrome_command() -> Command {
     }
     #[cfg(target_os = "linux")]
     {
-        // Linux: the `google-chrome` launcher (unreached on the macOS-only build).
-        Command::new("google-chrome")
+        // Fall back to `google-chrome` so a failed spawn names a real launcher.
+        Command::new(
+            find_on_path(LINUX_CHROME_BINARIES).unwrap_or_else(|| PathBuf::from("google-chrome")),
+        )
     }
     #[cfg(not(any(target_os = "macos", target_os = "linux")))]
     {
@@ -438,7 +806,13 @@ fn firefox_command() -> Command {
         let app = "/Applications/Firefox.app/Contents/MacOS/firefox";
         Command::new(app)
     }
-    #[cfg(not(target_os = "macos"))]
+    #[cfg(target_os = "linux")]
+    {
+        Command::new(
+            find_on_path(LINUX_FIREFOX_BINARIES).unwrap_or_else(|| PathBuf::from("firefox")),
+        )
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
     {
         Command::new("firefox")
     }
@@ -453,6 +827,7 @@ fn firefox_command() -> Command {
 /// The restore file is consumed by [`restore_system_proxy_if_pending`] — either
 /// at the next startup (crash recovery) or on clean Ctrl-C exit.  If the
 /// process is SIGKILL'd the file remains and is recovered on the next run.
+#[cfg(target_os = "macos")]
 fn launch_safari(cfg: &ResolvedConfig) {
     let pac_url = format!("http://{}/proxy.pac", proxy_connect_addr(cfg.listen));
 
@@ -548,34 +923,29 @@ fn launch_safari(cfg: &ResolvedConfig) {
 }
 
 /// Returns the active Wi-Fi/Ethernet network service name, or `None`.
+#[cfg(target_os = "macos")]
 fn detect_network_service() -> Option<String> {
-    #[cfg(not(target_os = "macos"))]
-    return None;
+    // Find the default-route interface name.
+    let route_out = Command::new("route")
+        .args(["-n", "get", "default"])
+        .output()
+        .ok()?;
+    let route_text = String::from_utf8_lossy(&route_out.stdout);
+    let interface = route_text
+        .lines()
+        .find(|l| l.trim_start().starts_with("interface:"))?
+        .split(':')
+        .nth(1)?
+        .trim()
+        .to_string();
 
-    #[cfg(target_os = "macos")]
-    {
-        // Find the default-route interface name.
-        let route_out = Command::new("route")
-            .args(["-n", "get", "default"])
-            .output()
-            .ok()?;
-        let route_text = String::from_utf8_lossy(&route_out.stdout);
-        let interface = route_text
-            .lines()
-            .find(|l| l.trim_start().starts_with("interface:"))?
-            .split(':')
-            .nth(1)?
-            .trim()
-            .to_string();
-
-        // Map interface → service name via networksetup -listnetworkserviceorder.
-        let ns_out = Command::new("networksetup")
-            .arg("-listnetworkserviceorder")
-            .output()
-            .ok()?;
-        let ns_text = String::from_utf8_lossy(&ns_out.stdout);
-        service_for_interface(&ns_text, &interface)
-    }
+    // Map interface → service name via networksetup -listnetworkserviceorder.
+    let ns_out = Command::new("networksetup")
+        .arg("-listnetworkserviceorder")
+        .output()
+        .ok()?;
+    let ns_text = String::from_utf8_lossy(&ns_out.stdout);
+    service_for_interface(&ns_text, &interface)
 }
 
 /// Maps a default-route interface (e.g. `en0`) to its macOS network-service name
@@ -627,6 +997,7 @@ fn service_for_interface(ns_output: &str, interface: &str) -> Option<String> {
 ///
 /// A `URL:` of `(null)` (or empty) yields `None`; `Enabled:` is `true` only for
 /// a `Yes` (case-insensitive). Pure and unit-testable.
+#[cfg(target_os = "macos")]
 fn parse_auto_proxy_state(text: &str) -> (Option<String>, bool) {
     let mut url = None;
     let mut enabled = false;
@@ -646,6 +1017,7 @@ fn parse_auto_proxy_state(text: &str) -> (Option<String>, bool) {
 /// Returns the current auto-proxy `(url, enabled)` state for a network service.
 ///
 /// A failure to run `networksetup` is reported as `(None, false)`.
+#[cfg(target_os = "macos")]
 fn get_auto_proxy_state(service: &str) -> (Option<String>, bool) {
     let Ok(out) = Command::new("networksetup")
         .args(["-getautoproxyurl", service])
@@ -886,6 +1258,7 @@ mod tests {
         restore_system_proxy_if_pending(dir.path(), false);
     }
 
+    #[cfg(target_os = "macos")]
     #[test]
     fn restore_system_proxy_if_pending_removes_file_with_empty_service() {
         let dir = tempfile::tempdir().expect("should create temp dir");
@@ -901,6 +1274,7 @@ mod tests {
         );
     }
 
+    #[cfg(target_os = "macos")]
     #[test]
     fn parse_auto_proxy_state_reads_url_and_enabled() {
         let (url, enabled) =
@@ -913,6 +1287,7 @@ mod tests {
         assert!(enabled, "Enabled: Yes parses as enabled");
     }
 
+    #[cfg(target_os = "macos")]
     #[test]
     fn parse_auto_proxy_state_handles_disabled_with_url() {
         // A saved-but-disabled PAC URL: URL present, Enabled No.
@@ -926,10 +1301,81 @@ mod tests {
         assert!(!enabled, "Enabled: No parses as disabled");
     }
 
+    #[cfg(target_os = "macos")]
     #[test]
     fn parse_auto_proxy_state_treats_null_url_as_none() {
         let (url, enabled) = parse_auto_proxy_state("URL: (null)\nEnabled: No\n");
         assert_eq!(url, None, "(null) URL parses as no URL");
         assert!(!enabled, "disabled with no URL");
     }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn linux_nss_dbs_adds_the_chromium_snap_db_only_when_installed() {
+        let home = tempfile::tempdir().expect("should create temp dir");
+        assert_eq!(
+            linux_nss_dbs(home.path()),
+            vec![home.path().join(".pki/nssdb")],
+            "should always include the shared NSS database"
+        );
+
+        std::fs::create_dir_all(home.path().join("snap/chromium")).expect("should create snap dir");
+        assert_eq!(
+            linux_nss_dbs(home.path()),
+            vec![
+                home.path().join(".pki/nssdb"),
+                home.path().join("snap/chromium/current/.pki/nssdb"),
+            ],
+            "should add the snap-confined database when the Chromium snap is installed"
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn parse_trust_list_picks_uris_labelled_with_the_ca_name() {
+        let text = "pkcs11:id=%01;type=cert\n    \
+                    type: certificate\n    \
+                    label: ISRG Root X1\n    \
+                    trust: anchor\n\
+                    \n\
+                    pkcs11:id=%02;type=cert\n    \
+                    type: certificate\n    \
+                    label: Dev CA\n    \
+                    trust: anchor\n\
+                    \n\
+                    pkcs11:id=%03;type=cert\n    \
+                    label: Dev CA (old)\n";
+        assert_eq!(
+            parse_trust_list(text, "Dev CA"),
+            vec!["pkcs11:id=%02;type=cert".to_string()],
+            "should match the label exactly and return its entry's URI"
+        );
+        assert!(
+            parse_trust_list(text, "Missing CA").is_empty(),
+            "should return nothing when no entry carries the label"
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn system_trust_commands_render_as_a_quoted_sudo_line() {
+        let debian =
+            system_trust_install_commands(SystemTrust::UpdateCaCertificates, "/tmp/my ca.pem");
+        assert_eq!(
+            manual_sudo_line(&debian),
+            format!(
+                "sudo 'install' '-m' '0644' '/tmp/my ca.pem' '{DEBIAN_ANCHOR_PATH}' && \
+                 sudo 'update-ca-certificates'"
+            ),
+            "should copy the anchor into place, then rebuild the bundle"
+        );
+        assert_eq!(
+            manual_sudo_line(&system_trust_install_commands(
+                SystemTrust::P11Kit,
+                "/tmp/ca.pem"
+            )),
+            "sudo 'trust' 'anchor' '--store' '/tmp/ca.pem'",
+            "should store a p11-kit anchor"
+        );
+    }
 }
@@ -144,7 +144,8 @@ pub struct ResolvedConfig {
 }
 
 /// Default CA directory (spec §7.1/§12): `$XDG_DATA_HOME/trusted-server/dev-proxy`,
-/// or the platform data dir on macOS (`~/Library/Application Support/...`).
+/// or the platform data dir (`~/Library/Application Support/...` on macOS,
+/// `~/.local/share/...` on Linux).
 ///
 /// `ProjectDirs::from(...)` is **not** used — it yields a reverse-DNS leaf
 /// (`com.trusted-server.dev-proxy`), not the spec's `trusted-server/dev-proxy`.
@@ -0,0 +1,997 @@
+//! HAR 1.2 session recording (`--record`) and offline replay (`--replay`).
+//!
+//! Recording tees every mapped request and response body as it streams, so the
+//! browser sees no added latency, and writes the archive when the proxy exits.
+//! Bodies are stored exactly as they crossed the proxy — for a Trusted Server
+//! upstream that is the already-rewritten HTML — as text when they are UTF-8
+//! and base64 otherwise (which includes responses that are still
+//! `Content-Encoding`-compressed).
+//!
+//! Replay answers mapped requests from an archive by method and URL and never
+//! dials an upstream. A request recorded several times is answered in recorded
+//! order, repeating the last response once the recordings run out.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use base64::Engine as _;
+use bytes::Bytes;
+use error_stack::{Report, ResultExt as _};
+use http_body_util::{BodyExt as _, Full, combinators::BoxBody};
+use hyper::body::{Body, Frame, SizeHint};
+use hyper::header::{HeaderMap, HeaderName, HeaderValue};
+use hyper::{Method, Request, Response, StatusCode, Uri, Version};
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+
+use super::ProxyError;
+use crate::output;
+
+/// Request headers whose values are replaced before they reach the archive.
+const REDACTED_REQUEST_HEADERS: [&str; 2] = ["authorization", "proxy-authorization"];
+
+/// Recorded response headers that describe the original connection or framing
+/// rather than the response, and are dropped when it is replayed.
+const REPLAY_DROPPED_HEADERS: [&str; 6] = [
+    "connection",
+    "content-length",
+    "keep-alive",
+    "trailer",
+    "transfer-encoding",
+    "upgrade",
+];
+
+/// How mapped traffic is captured or served.
+#[derive(Default)]
+pub enum HarMode {
+    /// Forward to the upstream and record nothing.
+    #[default]
+    Off,
+    /// Forward to the upstream and record every exchange.
+    Record(Arc<HarRecorder>),
+    /// Answer from an archive without contacting the upstream.
+    Replay(HarArchive),
+}
+
+impl HarMode {
+    /// Opens the recorder or archive named by `--record` / `--replay`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProxyError::Har`] if the recording cannot be created or the
+    /// archive cannot be read or parsed.
+    pub fn open(record: Option<&Path>, replay: Option<&Path>) -> Result<Self, Report<ProxyError>> {
+        if let Some(path) = replay {
+            let archive = HarArchive::load(path)?;
+            output::info(&format!(
+                "replaying {} recorded responses from {}; mapped hosts are not contacted",
+                archive.len(),
+                path.display()
+            ));
+            return Ok(Self::Replay(archive));
+        }
+        if let Some(path) = record {
+            let recorder = HarRecorder::create(path)?;
+            output::info(&format!(
+                "recording mapped traffic to {} (written on exit)",
+                path.display()
+            ));
+            return Ok(Self::Record(Arc::new(recorder)));
+        }
+        Ok(Self::Off)
+    }
+
+    /// Writes the recording, if any, and reports where it went. Never fails:
+    /// this runs on shutdown, where the only useful action is a warning.
+    pub fn save(&self) {
+        let Self::Record(recorder) = self else {
+            return;
+        };
+        match recorder.write() {
+            Ok(count) => output::info(&format!(
+                "recorded {count} exchanges to {}",
+                recorder.path.display()
+            )),
+            Err(err) => output::warn(&format!(
+                "could not write the HAR recording {}: {err:?}",
+                recorder.path.display()
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Har {
+    log: HarLog,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct HarLog {
+    version: String,
+    creator: HarCreator,
+    #[serde(default)]
+    entries: Vec<HarEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct HarCreator {
+    name: String,
+    version: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HarEntry {
+    started_date_time: String,
+    time: f64,
+    request: HarRequest,
+    response: HarResponse,
+    #[serde(default)]
+    cache: serde_json::Map<String, serde_json::Value>,
+    #[serde(default)]
+    timings: HarTimings,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    comment: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HarRequest {
+    method: String,
+    url: String,
+    #[serde(default)]
+    http_version: String,
+    #[serde(default)]
+    headers: Vec<HarField>,
+    #[serde(default)]
+    query_string: Vec<HarField>,
+    #[serde(default)]
+    cookies: Vec<serde_json::Value>,
+    #[serde(default)]
+    headers_size: i64,
+    #[serde(default)]
+    body_size: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    post_data: Option<HarPostData>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HarPostData {
+    #[serde(default)]
+    mime_type: String,
+    #[serde(default)]
+    text: String,
+    /// Not part of HAR 1.2 (hence the `_` prefix): marks a base64 body.
+    #[serde(rename = "_encoding", default, skip_serializing_if = "Option::is_none")]
+    encoding: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HarResponse {
+    status: u16,
+    #[serde(default)]
+    status_text: String,
+    #[serde(default)]
+    http_version: String,
+    #[serde(default)]
+    headers: Vec<HarField>,
+    #[serde(default)]
+    cookies: Vec<serde_json::Value>,
+    #[serde(default)]
+    content: HarContent,
+    #[serde(rename = "redirectURL", default)]
+    redirect_url: String,
+    #[serde(default)]
+    headers_size: i64,
+    #[serde(default)]
+    body_size: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HarContent {
+    #[serde(default)]
+    size: i64,
+    #[serde(default)]
+    mime_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    encoding: Option<String>,
+}
+
+/// Phase durations in milliseconds; `-1` marks a phase that was not measured.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct HarTimings {
+    blocked: f64,
+    dns: f64,
+    connect: f64,
+    send: f64,
+    wait: f64,
+    receive: f64,
+    ssl: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct HarField {
+    name: String,
+    value: String,
+}
+
+/// Stores a UTF-8 body as text and anything else as base64.
+fn encode_body(bytes: &[u8]) -> (String, Option<String>) {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => (text.to_string(), None),
+        Err(_) => (
+            base64::engine::general_purpose::STANDARD.encode(bytes),
+            Some("base64".to_string()),
+        ),
+    }
+}
+
+fn decode_body(text: &str, encoding: Option<&str>) -> Result<Vec<u8>, Report<ProxyError>> {
+    match encoding {
+        Some("base64") => base64::engine::general_purpose::STANDARD
+            .decode(text)
+            .change_context(ProxyError::Har)
+            .attach("recorded body is not valid base64"),
+        _ => Ok(text.as_bytes().to_vec()),
+    }
+}
+
+fn har_fields(headers: &HeaderMap, redact: &[&str]) -> Vec<HarField> {
+    headers
+        .iter()
+        .map(|(name, value)| HarField {
+            name: name.as_str().to_string(),
+            value: if redact.contains(&name.as_str()) {
+                "[REDACTED]".to_string()
+            } else {
+                String::from_utf8_lossy(value.as_bytes()).into_owned()
+            },
+        })
+        .collect()
+}
+
+fn query_fields(uri: &Uri) -> Vec<HarField> {
+    uri.query()
+        .map(|query| {
+            url::form_urlencoded::parse(query.as_bytes())
+                .map(|(name, value)| HarField {
+                    name: name.into_owned(),
+                    value: value.into_owned(),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn http_version(version: Version) -> String {
+    format!("{version:?}")
+}
+
+fn content_type(headers: &HeaderMap) -> String {
+    headers
+        .get(hyper::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+fn byte_len(bytes: &[u8]) -> i64 {
+    i64::try_from(bytes.len()).unwrap_or(i64::MAX)
+}
+
+/// The browser-facing URL of a mapped request: always `https`, on the `FROM`
+/// host the browser asked for.
+fn mapped_url(host: &str, uri: &Uri) -> String {
+    let path = uri.path_and_query().map_or("/", |p| p.as_str());
+    format!("https://{host}{path}")
+}
+
+/// Normalizes a method and URL into the replay lookup key, so an archive
+/// exported by a browser (explicit default port, fragment) still matches.
+fn replay_key(method: &str, url: &str) -> Option<String> {
+    let mut url = url::Url::parse(url).ok()?;
+    url.set_fragment(None);
+    Some(format!("{} {url}", method.to_ascii_uppercase()))
+}
+
+/// Collects recorded exchanges and writes them as one HAR archive.
+pub struct HarRecorder {
+    path: PathBuf,
+    next_sequence: AtomicU64,
+    entries: Mutex<Vec<(u64, HarEntry)>>,
+}
+
+impl HarRecorder {
+    /// Creates a recorder for `path` and writes an empty archive there, so an
+    /// unwritable path fails at startup instead of when the session ends.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProxyError::Har`] if the archive cannot be written.
+    pub fn create(path: &Path) -> Result<Self, Report<ProxyError>> {
+        let recorder = Self {
+            path: path.to_path_buf(),
+            next_sequence: AtomicU64::new(0),
+            entries: Mutex::new(Vec::new()),
+        };
+        recorder.write()?;
+        Ok(recorder)
+    }
+
+    /// Number of exchanges recorded so far.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the entry mutex is poisoned by a prior panic while held.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.lock().expect("should lock HAR entries").len()
+    }
+
+    /// Whether nothing has been recorded yet.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Writes every exchange recorded so far, in the order the requests
+    /// arrived, and returns how many there were.
+    ///
+    /// The archive goes to a sibling temp file first and is then renamed into
+    /// place, so a failed write never truncates an earlier archive.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProxyError::Har`] if the archive cannot be serialized or written.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the entry mutex is poisoned by a prior panic while held.
+    pub fn write(&self) -> Result<usize, Report<ProxyError>> {
+        let mut entries = self
+            .entries
+            .lock()
+            .expect("should lock HAR entries")
+            .clone();
+        entries.sort_by_key(|(sequence, _)| *sequence);
+        let count = entries.len();
+        let har = Har {
+            log: HarLog {
+                version: "1.2".to_string(),
+                creator: HarCreator {
+                    name: "ts dev proxy".to_string(),
+                    version: env!("CARGO_PKG_VERSION").to_string(),
+                },
+                entries: entries.into_iter().map(|(_, entry)| entry).collect(),
+            },
+        };
+        let json = serde_json::to_vec_pretty(&har).change_context(ProxyError::Har)?;
+        let mut temp = self.path.as_os_str().to_owned();
+        temp.push(".tmp");
+        let temp = PathBuf::from(temp);
+        std::fs::write(&temp, json)
+            .change_context(ProxyError::Har)
+            .attach_with(|| format!("could not write {}", temp.display()))?;
+        std::fs::rename(&temp, &self.path)
+            .change_context(ProxyError::Har)
+            .attach_with(|| format!("could not replace {}", self.path.display()))?;
+        Ok(count)
+    }
+
+    /// Starts recording one mapped request for `host`, returning the request
+    /// with its body teed into the capture.
+    pub fn start<B>(
+        self: &Arc<Self>,
+        request: Request<B>,
+        host: &str,
+    ) -> (Request<CaptureBody<B>>, Exchange) {
+        let (parts, body) = request.into_parts();
+        let captured = Arc::new(Mutex::new(Vec::new()));
+        let exchange = Exchange {
+            recorder: Arc::clone(self),
+            sequence: self.next_sequence.fetch_add(1, Ordering::Relaxed),
+            started_date_time: chrono::Utc::now()
+                .to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            started: Instant::now(),
+            request: HarRequest {
+                method: parts.method.as_str().to_string(),
+                url: mapped_url(host, &parts.uri),
+                http_version: http_version(parts.version),
+                headers: har_fields(&parts.headers, &REDACTED_REQUEST_HEADERS),
+                query_string: query_fields(&parts.uri),
+                cookies: Vec::new(),
+                headers_size: -1,
+                body_size: 0,
+                post_data: None,
+            },
+            request_mime_type: content_type(&parts.headers),
+            request_body: Arc::clone(&captured),
+        };
+        let body = CaptureBody {
+            inner: body,
+            captured,
+        };
+        (Request::from_parts(parts, body), exchange)
+    }
+
+    fn push(&self, sequence: u64, entry: HarEntry) {
+        self.entries
+            .lock()
+            .expect("should lock HAR entries")
+            .push((sequence, entry));
+    }
+}
+
+/// A request body that copies each data frame into a shared buffer as it is
+/// forwarded.
+pub struct CaptureBody<B> {
+    inner: B,
+    captured: Arc<Mutex<Vec<u8>>>,
+}
+
+impl<B> Body for CaptureBody<B>
+where
+    B: Body<Data = Bytes> + Unpin,
+{
+    type Data = Bytes;
+    type Error = B::Error;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let polled = Pin::new(&mut self.inner).poll_frame(cx);
+        if let Poll::Ready(Some(Ok(frame))) = &polled
+            && let Some(data) = frame.data_ref()
+        {
+            self.captured
+                .lock()
+                .expect("should lock captured request body")
+                .extend_from_slice(data);
+        }
+        polled
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+/// One exchange being recorded, completed once its response body finishes.
+pub struct Exchange {
+    recorder: Arc<HarRecorder>,
+    sequence: u64,
+    started_date_time: String,
+    started: Instant,
+    request: HarRequest,
+    request_mime_type: String,
+    request_body: Arc<Mutex<Vec<u8>>>,
+}
+
+impl Exchange {
+    /// Wraps the response the browser is about to receive so its body is
+    /// recorded as it streams; the entry is stored when the body is dropped.
+    #[must_use]
+    pub fn finish(
+        self,
+        response: Response<BoxBody<Bytes, hyper::Error>>,
+    ) -> Response<BoxBody<Bytes, hyper::Error>> {
+        let wait = self.started.elapsed();
+        let (parts, body) = response.into_parts();
+        let head = HarResponse {
+            status: parts.status.as_u16(),
+            status_text: parts
+                .status
+                .canonical_reason()
+                .unwrap_or_default()
+                .to_string(),
+            http_version: http_version(parts.version),
+            headers: har_fields(&parts.headers, &[]),
+            cookies: Vec::new(),
+            content: HarContent {
+                mime_type: content_type(&parts.headers),
+                ..HarContent::default()
+            },
+            redirect_url: parts
+                .headers
+                .get(hyper::header::LOCATION)
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or_default()
+                .to_string(),
+            headers_size: -1,
+            body_size: 0,
+        };
+        let body = RecordingBody {
+            inner: body,
+            captured: Vec::new(),
+            finished: None,
+            pending: Some((self, head, wait)),
+        };
+        Response::from_parts(parts, body.boxed())
+    }
+
+    fn record(
+        self,
+        mut response: HarResponse,
+        wait: Duration,
+        body: &[u8],
+        receive: Duration,
+        complete: bool,
+    ) {
+        let mut request = self.request;
+        let request_body = self
+            .request_body
+            .lock()
+            .expect("should lock captured request body")
+            .clone();
+        request.body_size = byte_len(&request_body);
+        if !request_body.is_empty() {
+            let (text, encoding) = encode_body(&request_body);
+            request.post_data = Some(HarPostData {
+                mime_type: self.request_mime_type,
+                text,
+                encoding,
+            });
+        }
+        let (text, encoding) = encode_body(body);
+        response.body_size = byte_len(body);
+        response.content.size = byte_len(body);
+        response.content.text = Some(text);
+        response.content.encoding = encoding;
+        let entry = HarEntry {
+            started_date_time: self.started_date_time,
+            time: millis(wait + receive),
+            request,
+            response,
+            cache: serde_json::Map::new(),
+            timings: HarTimings {
+                blocked: -1.0,
+                dns: -1.0,
+                connect: -1.0,
+                send: 0.0,
+                wait: millis(wait),
+                receive: millis(receive),
+                ssl: -1.0,
+            },
+            comment: (!complete).then(|| {
+                "response body incomplete: the stream ended before the upstream finished"
+                    .to_string()
+            }),
+        };
+        self.recorder.push(self.sequence, entry);
+    }
+}
+
+/// A response body that keeps a copy of everything it forwards and records
+/// the exchange when it is dropped — after a clean end of stream, or early if
+/// the browser or upstream abandons it.
+struct RecordingBody {
+    inner: BoxBody<Bytes, hyper::Error>,
+    captured: Vec<u8>,
+    finished: Option<Instant>,
+    pending: Option<(Exchange, HarResponse, Duration)>,
+}
+
+impl Body for RecordingBody {
+    type Data = Bytes;
+    type Error = hyper::Error;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let polled = Pin::new(&mut self.inner).poll_frame(cx);
+        match &polled {
+            Poll::Ready(Some(Ok(frame))) => {
+                if let Some(data) = frame.data_ref() {
+                    self.captured.extend_from_slice(data);
+                }
+                if self.inner.is_end_stream() && self.finished.is_none() {
+                    self.finished = Some(Instant::now());
+                }
+            }
+            Poll::Ready(None) if self.finished.is_none() => {
+                self.finished = Some(Instant::now());
+            }
+            _ => {}
+        }
+        polled
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+impl Drop for RecordingBody {
+    fn drop(&mut self) {
+        let Some((exchange, head, wait)) = self.pending.take() else {
+            return;
+        };
+        let complete = self.finished.is_some();
+        let ended = self.finished.unwrap_or_else(Instant::now);
+        let receive = ended.saturating_duration_since(exchange.started + wait);
+        exchange.record(head, wait, &self.captured, receive, complete);
+    }
+}
+
+/// A response restored from an archive.
+struct RecordedResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+/// Recorded responses for one method and URL, served in order.
+struct ReplayQueue {
+    responses: Vec<RecordedResponse>,
+    next: AtomicUsize,
+}
+
+/// Recorded responses indexed by method and URL.
+pub struct HarArchive {
+    queues: HashMap<String, ReplayQueue>,
+}
+
+impl HarArchive {
+    /// Reads an archive written by `--record` or exported from browser
+    /// developer tools.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProxyError::Har`] if the file cannot be read or is not HAR.
+    pub fn load(path: &Path) -> Result<Self, Report<ProxyError>> {
+        let text = std::fs::read_to_string(path)
+            .change_context(ProxyError::Har)
+            .attach_with(|| format!("could not read {}", path.display()))?;
+        Self::parse(&text).attach_with(|| format!("in {}", path.display()))
+    }
+
+    /// Parses archive JSON. Entries with an unusable URL or status are skipped
+    /// with a warning rather than failing the whole archive.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProxyError::Har`] if the text is not a HAR document or a body
+    /// cannot be decoded.
+    pub fn parse(text: &str) -> Result<Self, Report<ProxyError>> {
+        let har: Har = serde_json::from_str(text)
+            .change_context(ProxyError::Har)
+            .attach("not a HAR 1.2 document")?;
+        let mut queues: HashMap<String, ReplayQueue> = HashMap::new();
+        for entry in har.log.entries {
+            let Some(key) = replay_key(&entry.request.method, &entry.request.url) else {
+                log::warn!("skipping HAR entry with unparseable URL");
+                continue;
+            };
+            let Ok(status) = StatusCode::from_u16(entry.response.status) else {
+                log::warn!("skipping HAR entry with status {}", entry.response.status);
+                continue;
+            };
+            let mut headers = HeaderMap::new();
+            for field in &entry.response.headers {
+                if REPLAY_DROPPED_HEADERS
+                    .iter()
+                    .any(|dropped| field.name.eq_ignore_ascii_case(dropped))
+                {
+                    continue;
+                }
+                if let (Ok(name), Ok(value)) = (
+                    HeaderName::from_bytes(field.name.as_bytes()),
+                    HeaderValue::from_str(&field.value),
+                ) {
+                    headers.append(name, value);
+                }
+            }
+            let content = &entry.response.content;
+            let body = match &content.text {
+                Some(text) => decode_body(text, content.encoding.as_deref())?,
+                None => Vec::new(),
+            };
+            queues
+                .entry(key)
+                .or_insert_with(|| ReplayQueue {
+                    responses: Vec::new(),
+                    next: AtomicUsize::new(0),
+                })
+                .responses
+                .push(RecordedResponse {
+                    status,
+                    headers,
+                    body: Bytes::from(body),
+                });
+        }
+        Ok(Self { queues })
+    }
+
+    /// Number of recorded responses available for replay.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.queues
+            .values()
+            .map(|queue| queue.responses.len())
+            .sum()
+    }
+
+    /// Whether the archive holds no responses.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Answers a mapped request for `host` from the archive, or with `404`
+    /// when nothing was recorded for it.
+    #[must_use]
+    pub fn respond(
+        &self,
+        method: &Method,
+        host: &str,
+        uri: &Uri,
+    ) -> Response<BoxBody<Bytes, hyper::Error>> {
+        let recorded = replay_key(method.as_str(), &mapped_url(host, uri))
+            .and_then(|key| self.queues.get(&key))
+            .and_then(|queue| {
+                let index = queue.next.fetch_add(1, Ordering::Relaxed);
+                queue
+                    .responses
+                    .get(index.min(queue.responses.len().saturating_sub(1)))
+            });
+        let Some(recorded) = recorded else {
+            log::warn!(
+                "replay: no recorded response for {method} https://{host}{}",
+                uri.path()
+            );
+            let mut response = Response::new(
+                Full::new(Bytes::from_static(
+                    b"ts dev proxy: no recorded response for this request\n",
+                ))
+                .map_err(|never| match never {})
+                .boxed(),
+            );
+            *response.status_mut() = StatusCode::NOT_FOUND;
+            response.headers_mut().insert(
+                hyper::header::CONTENT_TYPE,
+                HeaderValue::from_static("text/plain; charset=utf-8"),
+            );
+            return response;
+        };
+        let mut response = Response::new(
+            Full::new(recorded.body.clone())
+                .map_err(|never| match never {})
+                .boxed(),
+        );
+        *response.status_mut() = recorded.status;
+        *response.headers_mut() = recorded.headers.clone();
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn archive_json(entries: &str) -> String {
+        format!(
+            r#"{{"log":{{"version":"1.2","creator":{{"name":"test","version":"1"}},"entries":[{entries}]}}}}"#
+        )
+    }
+
+    fn entry(method: &str, url: &str, status: u16, text: &str) -> String {
+        format!(
+            r#"{{"startedDateTime":"2026-01-01T00:00:00.000Z","time":1,
+                "request":{{"method":"{method}","url":"{url}"}},
+                "response":{{"status":{status},
+                  "headers":[{{"name":"Content-Type","value":"text/html"}},
+                             {{"name":"Content-Length","value":"999"}}],
+                  "content":{{"size":0,"mimeType":"text/html","text":"{text}"}}}}}}"#
+        )
+    }
+
+    async fn body_of(response: Response<BoxBody<Bytes, hyper::Error>>) -> Vec<u8> {
+        response
+            .into_body()
+            .collect()
+            .await
+            .expect("should collect body")
+            .to_bytes()
+            .to_vec()
+    }
+
+    #[test]
+    fn encode_body_keeps_utf8_as_text_and_base64s_binary() {
+        assert_eq!(
+            encode_body(b"<html>"),
+            ("<html>".to_string(), None),
+            "should store UTF-8 as text"
+        );
+        let (text, encoding) = encode_body(&[0x1f, 0x8b, 0xff]);
+        assert_eq!(encoding.as_deref(), Some("base64"), "should mark binary");
+        assert_eq!(
+            decode_body(&text, encoding.as_deref()).expect("should decode"),
+            vec![0x1f, 0x8b, 0xff],
+            "should round-trip binary bytes"
+        );
+    }
+
+    #[test]
+    fn replay_key_normalizes_default_port_and_fragment() {
+        assert_eq!(
+            replay_key("get", "https://www.example.com:443/a?b=1#top"),
+            replay_key("GET", "https://www.example.com/a?b=1"),
+            "should match a browser export against a recorded URL"
+        );
+        assert_ne!(
+            replay_key("GET", "https://www.example.com/a?b=1"),
+            replay_key("GET", "https://www.example.com/a?b=2"),
+            "should keep the query in the key"
+        );
+    }
+
+    #[tokio::test]
+    async fn replay_serves_repeats_in_order_then_repeats_the_last() {
+        let json = archive_json(&format!(
+            "{},{}",
+            entry("GET", "https://www.example.com/", 200, "first"),
+            entry("GET", "https://www.example.com/", 200, "second"),
+        ));
+        let archive = HarArchive::parse(&json).expect("should parse archive");
+        assert_eq!(archive.len(), 2, "should load both entries");
+        let uri: Uri = "/".parse().expect("should parse uri");
+        let mut bodies = Vec::new();
+        for _ in 0..3 {
+            bodies.push(body_of(archive.respond(&Method::GET, "www.example.com", &uri)).await);
+        }
+        assert_eq!(
+            bodies,
+            vec![b"first".to_vec(), b"second".to_vec(), b"second".to_vec()],
+            "should replay in recorded order, then repeat the last response"
+        );
+    }
+
+    #[tokio::test]
+    async fn replay_drops_framing_headers_and_404s_unknown_requests() {
+        let archive = HarArchive::parse(&archive_json(&entry(
+            "GET",
+            "https://www.example.com/page",
+            200,
+            "<p>hi</p>",
+        )))
+        .expect("should parse archive");
+        let hit = archive.respond(
+            &Method::GET,
+            "www.example.com",
+            &"/page".parse().expect("should parse uri"),
+        );
+        assert_eq!(hit.status(), StatusCode::OK, "should replay the status");
+        assert!(
+            hit.headers().get(hyper::header::CONTENT_LENGTH).is_none(),
+            "should drop the recorded Content-Length so the body sets its own"
+        );
+        assert_eq!(
+            hit.headers().get(hyper::header::CONTENT_TYPE),
+            Some(&HeaderValue::from_static("text/html")),
+            "should keep response headers"
+        );
+
+        let miss = archive.respond(
+            &Method::POST,
+            "www.example.com",
+            &"/page".parse().expect("should parse uri"),
+        );
+        assert_eq!(
+            miss.status(),
+            StatusCode::NOT_FOUND,
+            "should not answer a different method from the archive"
+        );
+    }
+
+    #[tokio::test]
+    async fn recorder_writes_request_and_response_with_redacted_credentials() {
+        let dir = tempfile::tempdir().expect("should create temp dir");
+        let path = dir.path().join("session.har");
+        let recorder = Arc::new(HarRecorder::create(&path).expect("should create recorder"));
+
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("/submit?id=7")
+            .header(hyper::header::AUTHORIZATION, "Basic c2VjcmV0")
+            .header(hyper::header::CONTENT_TYPE, "text/plain")
+            .body(Full::new(Bytes::from_static(b"payload")))
+            .expect("should build request");
+        let (request, exchange) = recorder.start(request, "www.example.com");
+        let uploaded = request
+            .into_body()
+            .collect()
+            .await
+            .expect("should stream request body")
+            .to_bytes();
+        assert_eq!(
+            &uploaded[..],
+            b"payload",
+            "should forward the body unchanged"
+        );
+
+        let response = Response::builder()
+            .status(StatusCode::CREATED)
+            .header(hyper::header::CONTENT_TYPE, "text/html")
+            .body(
+                Full::new(Bytes::from_static(b"<p>rewritten</p>"))
+                    .map_err(|never| match never {})
+                    .boxed(),
+            )
+            .expect("should build response");
+        let delivered = body_of(exchange.finish(response)).await;
+        assert_eq!(
+            delivered, b"<p>rewritten</p>",
+            "should forward the response"
+        );
+        assert_eq!(recorder.len(), 1, "should record once the body is dropped");
+
+        recorder.write().expect("should write archive");
+        let written: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).expect("should read archive"))
+                .expect("should be JSON");
+        let entry = &written["log"]["entries"][0];
+        assert_eq!(
+            entry["request"]["url"], "https://www.example.com/submit?id=7",
+            "should record the browser-facing URL"
+        );
+        assert_eq!(
+            entry["request"]["postData"]["text"], "payload",
+            "should record the request body"
+        );
+        assert!(
+            entry["request"]["headers"]
+                .as_array()
+                .expect("should have headers")
+                .iter()
+                .any(|h| h["name"] == "authorization" && h["value"] == "[REDACTED]"),
+            "should redact credentials"
+        );
+        assert_eq!(entry["response"]["status"], 201, "should record the status");
+        assert_eq!(
+            entry["response"]["content"]["text"], "<p>rewritten</p>",
+            "should record the response body"
+        );
+        assert!(entry.get("comment").is_none(), "should be a complete entry");
+
+        let replayed = HarArchive::load(&path).expect("should load the recording");
+        let response = replayed.respond(
+            &Method::POST,
+            "www.example.com",
+            &"/submit?id=7".parse().expect("should parse uri"),
+        );
+        assert_eq!(
+            response.status(),
+            StatusCode::CREATED,
+            "should replay the status"
+        );
+        assert_eq!(
+            body_of(response).await,
+            b"<p>rewritten</p>",
+            "should replay the recorded body"
+        );
+    }
+}
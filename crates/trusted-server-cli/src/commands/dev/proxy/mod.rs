@@ -1,12 +1,14 @@
 pub mod browser;
 pub mod ca;
 pub mod config;
+pub mod har;
 pub mod metrics;
 pub mod prefixed_io;
 pub mod rewrite;
 pub mod server;
 pub mod upstream;
 
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use error_stack::ResultExt as _;
@@ -17,6 +19,7 @@ pub struct ProxyState {
     pub config: Arc<config::ResolvedConfig>,
     pub upstream: upstream::UpstreamClient,
     pub metrics: Arc<metrics::ProxyMetrics>,
+    pub har: har::HarMode,
 }
 
 impl ProxyState {
@@ -29,6 +32,16 @@ impl ProxyState {
     pub fn with_upstream_options(
         config: Arc<config::ResolvedConfig>,
         options: upstream::UpstreamOptions,
+    ) -> Arc<Self> {
+        Self::with_har(config, options, har::HarMode::Off)
+    }
+
+    /// Builds the state with `--record` / `--replay` traffic capture.
+    #[must_use]
+    pub fn with_har(
+        config: Arc<config::ResolvedConfig>,
+        options: upstream::UpstreamOptions,
+        har: har::HarMode,
     ) -> Arc<Self> {
         let metrics = Arc::new(metrics::ProxyMetrics::default());
         Arc::new(Self {
@@ -39,6 +52,7 @@ impl ProxyState {
             ),
             config,
             metrics,
+            har,
         })
     }
 }
@@ -58,6 +72,9 @@ pub enum ProxyError {
     /// A browser could not be launched or configured.
     #[display("browser orchestration error")]
     Browser,
+    /// A HAR recording could not be written or a replay archive could not be read.
+    #[display("HAR archive error")]
+    Har,
 }
 
 impl core::error::Error for ProxyError {}
@@ -144,6 +161,16 @@ pub struct ProxyArgs {
     #[arg(long, value_name = "SECONDS", default_value_t = 10)]
     pub connect_timeout: u64,
 
+    /// Record every mapped request and response (bodies as the browser saw
+    /// them, with timings) to a HAR 1.2 archive, written when the proxy exits.
+    #[arg(long, value_name = "FILE.har", conflicts_with = "replay")]
+    pub record: Option<PathBuf>,
+
+    /// Answer mapped requests from a HAR archive instead of the upstream, so a
+    /// recorded session reproduces offline. Unrecorded requests get `404`.
+    #[arg(long, value_name = "FILE.har")]
+    pub replay: Option<PathBuf>,
+
     /// Optional nested subcommand (`ts dev proxy ca …`). When absent, the proxy
     /// runs with the options above.
     #[command(subcommand)]
@@ -167,7 +194,8 @@ pub enum ProxySub {
 pub enum CaCommand {
     /// Print the per-machine CA certificate path.
     Path,
-    /// Add the CA to the OS trust store (macOS login keychain).
+    /// Add the CA to the OS trust store (macOS login keychain; NSS databases and
+    /// the system store on Linux).
     Install,
     /// Remove the CA from the OS trust store.
     Uninstall,
@@ -208,16 +236,16 @@ pub fn run(args: &ProxyArgs) -> core::result::Result<(), error_stack::Report<Pro
             CaCommand::Regenerate => {
                 // Revoke OS trust for the OLD CA first. The old and new CA share
                 // CA_COMMON_NAME, so `ca_uninstall` (delete-by-CN, a no-op when
-                // absent) removes the soon-to-be-stale cert from the keychain
+                // absent) removes the soon-to-be-stale cert from the trust store
                 // before we replace the files on disk. If revocation cannot be
                 // confirmed, ABORT — rotating the local key while the old CA
                 // stays trusted would contradict the "invalidates prior trust"
                 // promise and leave an exfiltrated old key usable.
                 if !browser::ca_uninstall() {
                     return Err(error_stack::Report::new(ProxyError::CertAuthority).attach(
-                        "could not revoke the previously-installed CA from the keychain; \
+                        "could not revoke the previously-installed CA from the OS trust store; \
                          aborting regenerate so on-disk key material still matches OS trust. \
-                         Remove the old CA manually (Keychain Access), then retry.",
+                         Remove the old CA manually (see the warnings above), then retry.",
                     ));
                 }
                 // Delete the old cert/key BEFORE regenerating. `load_or_generate`
@@ -254,6 +282,7 @@ pub fn run(args: &ProxyArgs) -> core::result::Result<(), error_stack::Report<Pro
     browser::restore_system_proxy_if_pending(&config::ca_dir(args), false);
 
     let mut cfg = config::resolve(args).change_context(ProxyError::Config)?;
+    let har = har::HarMode::open(args.record.as_deref(), args.replay.as_deref())?;
 
     let ca = Arc::new(
         ca::CertAuthority::load_or_generate(&cfg.ca_dir)
@@ -276,7 +305,8 @@ pub fn run(args: &ProxyArgs) -> core::result::Result<(), error_stack::Report<Pro
             .change_context(ProxyError::Server)?;
         cfg.listen = listener.local_addr().change_context(ProxyError::Server)?;
         let cfg = Arc::new(cfg);
-        let state = ProxyState::new(Arc::clone(&cfg));
+        let state =
+            ProxyState::with_har(Arc::clone(&cfg), upstream::UpstreamOptions::default(), har);
         for rule in &cfg.rules.0 {
             if ca.is_cached(&rule.from) {
                 continue;
@@ -305,7 +335,7 @@ pub fn run(args: &ProxyArgs) -> core::result::Result<(), error_stack::Report<Pro
 
         // Race the server against Ctrl-C.  On clean interrupt, restore any
         // system proxy state that was changed for Safari before exiting.
-        tokio::select! {
+        let result = tokio::select! {
             result = &mut server => result.change_context(ProxyError::Server)?,
             _ = tokio::signal::ctrl_c() => {
                 // Interactive: the cached sudo credential may have expired during
@@ -318,7 +348,9 @@ pub fn run(args: &ProxyArgs) -> core::result::Result<(), error_stack::Report<Pro
                 log::debug!("{}", state.metrics.debug_summary());
                 Ok(())
             }
-        }
+        };
+        state.har.save();
+        result
     })
 }
 
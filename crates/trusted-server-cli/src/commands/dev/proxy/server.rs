@@ -26,6 +26,7 @@ use tokio_rustls::TlsAcceptor;
 
 use super::ca::CertAuthority;
 use super::config::ResolvedConfig;
+use super::har::HarMode;
 use super::prefixed_io::PrefixedIo;
 use super::rewrite::rewrite_for;
 use super::{ProxyError, ProxyState};
@@ -510,6 +511,31 @@ async fn forward_request(
     let outcome = rewrite_for(rule);
     let upstream_host = rule.to.host();
     let upstream_port = rule.to.port;
+    // The host the browser asked for, which is what a HAR archive records.
+    let browser_host = request_host(&req).unwrap_or_else(|| connect_host.to_string());
+
+    match &state.har {
+        HarMode::Off => {}
+        HarMode::Replay(archive) => {
+            return Ok(archive.respond(req.method(), &browser_host, req.uri()));
+        }
+        HarMode::Record(recorder) => {
+            let (req, exchange) = recorder.start(req, &browser_host);
+            let response = proxy_to_upstream(
+                req,
+                outcome,
+                state.config.basic_auth.as_ref(),
+                rule,
+                &state.upstream,
+            )
+            .await
+            .unwrap_or_else(|err| {
+                log::warn!("upstream {upstream_host}:{upstream_port} failed: {err:?}");
+                status_response(StatusCode::BAD_GATEWAY)
+            });
+            return Ok(exchange.finish(response));
+        }
+    }
 
     match proxy_to_upstream(
         req,
@@ -545,13 +571,16 @@ fn request_host(req: &Request<Incoming>) -> Option<String> {
     req.uri().host().map(str::to_string)
 }
 
-async fn proxy_to_upstream(
-    mut req: Request<Incoming>,
+async fn proxy_to_upstream<B>(
+    mut req: Request<B>,
     outcome: &super::rewrite::RewriteOutcome,
     basic_auth: Option<&super::config::BasicAuth>,
     rule: &super::rewrite::Rule,
     upstream: &super::upstream::UpstreamClient,
-) -> Result<Response<BoxBody<Bytes, hyper::Error>>, Report<ProxyError>> {
+) -> Result<Response<BoxBody<Bytes, hyper::Error>>, Report<ProxyError>>
+where
+    B: hyper::body::Body<Data = Bytes, Error = hyper::Error> + Send + Sync + 'static,
+{
     let upstream_host = rule.to.host();
     let upstream_port = rule.to.port;
     log::debug!(
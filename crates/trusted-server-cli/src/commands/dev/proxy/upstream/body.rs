@@ -43,7 +43,10 @@ pub struct RequestUploadBody {
 impl RequestUploadBody {
     #[must_use]
     /// Wraps a browser request body and returns its shared completion state.
-    pub fn new(inner: Incoming, known_empty: bool) -> (Self, Arc<AtomicU8>) {
+    pub fn new<B>(inner: B, known_empty: bool) -> (Self, Arc<AtomicU8>)
+    where
+        B: Body<Data = Bytes, Error = hyper::Error> + Send + Sync + 'static,
+    {
         Self::from_boxed(inner.map_err(ProxyBodyError::Hyper).boxed(), known_empty)
     }
 
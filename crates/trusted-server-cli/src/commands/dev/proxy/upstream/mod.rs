@@ -15,7 +15,7 @@ use std::time::Duration;
 use bytes::Bytes;
 use error_stack::{Report, ResultExt as _};
 use http_body_util::{BodyExt as _, combinators::BoxBody};
-use hyper::body::{Body, Incoming};
+use hyper::body::Body;
 use hyper::{Request, Response};
 
 use self::body::{PooledResponseBody, RequestUploadBody};
@@ -204,13 +204,16 @@ impl UpstreamClient {
     /// # Errors
     ///
     /// Returns an acquisition, connection, handshake, or request-dispatch error.
-    pub async fn send(
+    pub async fn send<B>(
         &self,
-        request: Request<Incoming>,
+        request: Request<B>,
         metadata: RequestMetadata,
         rule: &Rule,
         outcome: &RewriteOutcome,
-    ) -> Result<Response<BoxBody<Bytes, hyper::Error>>, Report<ProxyError>> {
+    ) -> Result<Response<BoxBody<Bytes, hyper::Error>>, Report<ProxyError>>
+    where
+        B: Body<Data = Bytes, Error = hyper::Error> + Send + Sync + 'static,
+    {
         let mut outcome_guard = SendOutcomeGuard::new(&self.metrics);
         let request_started = tokio::time::Instant::now();
         let replay_template = ReplayTemplate::capture(&request, metadata);
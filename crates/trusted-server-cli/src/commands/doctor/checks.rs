@@ -0,0 +1,921 @@
+use std::collections::BTreeSet;
+
+use scraper::{Html, Selector};
+use serde_json::{Value as JsonValue, json};
+use trusted_server_core::cache_policy::EdgeCacheHeader;
+use trusted_server_core::constants::COOKIE_TS_EC;
+use trusted_server_core::request_signing::{
+    RequestSigner, SigningParams, VerifySignatureRequest, VerifySignatureResponse,
+};
+use trusted_server_core::settings::CacheSettings;
+use url::Url;
+
+use crate::error::CliResult;
+use crate::http::{HttpClient, HttpRequest, HttpResponse};
+
+const DISCOVERY_PATH: &str = "/.well-known/trusted-server.json";
+const VERIFY_SIGNATURE_PATH: &str = "/verify-signature";
+const AUCTION_PATH: &str = "/auction";
+/// `id` of the tsjs `<script>` tag the HTML processor injects.
+const TSJS_SCRIPT_ID: &str = "trustedserver-js";
+const TSJS_SCRIPT_PATH: &str = "/static/tsjs=";
+/// Length of a 32-byte Ed25519 public key in unpadded URL-safe base64.
+const ED25519_PUBLIC_KEY_B64_LEN: usize = 43;
+/// Length of a 64-byte Ed25519 signature in unpadded URL-safe base64.
+const ED25519_SIGNATURE_B64_LEN: usize = 86;
+/// Error `/verify-signature` reports when the kid resolved but the signature
+/// did not match; any other error means the kid could not be resolved.
+const INVALID_SIGNATURE_ERROR: &str = "Invalid signature";
+const MAX_CACHE_ASSETS: usize = 10;
+const UNREACHABLE_HINT: &str =
+    "check that the URL reaches the deployment and that `ts deploy` completed";
+const REWRITTEN_HINT: &str = "check that nothing in front of Trusted Server rewrites the response";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+    Skip,
+}
+
+impl CheckStatus {
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            Self::Pass => "PASS",
+            Self::Warn => "WARN",
+            Self::Fail => "FAIL",
+            Self::Skip => "SKIP",
+        }
+    }
+}
+
+/// The result of one doctor check, with a remediation hint for warnings and
+/// failures.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CheckOutcome {
+    pub(crate) name: &'static str,
+    pub(crate) status: CheckStatus,
+    pub(crate) detail: String,
+    pub(crate) hint: Option<String>,
+}
+
+impl CheckOutcome {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Pass,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn skip(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Skip,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn warn(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Warn,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Fail,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+}
+
+/// The deployment under test and what the operator supplied to check it.
+pub(crate) struct Deployment<'a> {
+    pub(crate) page_url: &'a Url,
+    pub(crate) client: &'a dyn HttpClient,
+    pub(crate) signer: Option<&'a RequestSigner>,
+    pub(crate) cache: Option<&'a CacheSettings>,
+    pub(crate) slot: &'a str,
+}
+
+/// `path` on the origin of `base_url`.
+pub(crate) fn endpoint(base_url: &Url, path: &str) -> Url {
+    let mut url = base_url.clone();
+    url.set_path(path);
+    url.set_query(None);
+    url.set_fragment(None);
+    url
+}
+
+/// Runs every check in order. Checks never abort the run; later checks that
+/// depend on an earlier one are skipped instead.
+pub(crate) fn run_checks(deployment: &Deployment<'_>) -> Vec<CheckOutcome> {
+    let (discovery, kids) = check_discovery(deployment);
+    let signature = check_signature(deployment, &kids);
+    let page = deployment
+        .client
+        .send(&HttpRequest::get(deployment.page_url.clone()).with_header("accept", "text/html"));
+    let (injection, cookie) = check_page(&page);
+    let auction = check_auction(deployment);
+    let loaded_page = page.as_ref().ok().filter(|response| response.is_success());
+    let cache = check_asset_cache(deployment, loaded_page);
+
+    vec![discovery, signature, injection, cookie, auction, cache]
+}
+
+fn check_discovery(deployment: &Deployment<'_>) -> (CheckOutcome, Vec<String>) {
+    const NAME: &str = "discovery";
+    match fetch_published_keys(deployment.client, deployment.page_url) {
+        Ok(keys) => {
+            let detail = format!(
+                "version {}, {} key(s): {}",
+                keys.version,
+                keys.kids.len(),
+                keys.kids.join(", ")
+            );
+            (CheckOutcome::pass(NAME, detail), keys.kids)
+        }
+        Err(failure) => (
+            CheckOutcome::fail(NAME, failure.detail, failure.hint),
+            Vec::new(),
+        ),
+    }
+}
+
+/// A failed probe of the deployment: what went wrong and how to fix it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ProbeFailure {
+    pub(crate) detail: String,
+    pub(crate) hint: &'static str,
+}
+
+impl ProbeFailure {
+    fn new(detail: impl Into<String>, hint: &'static str) -> Self {
+        Self {
+            detail: detail.into(),
+            hint,
+        }
+    }
+}
+
+/// The request-signing keys published in the discovery document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PublishedKeys {
+    pub(crate) version: String,
+    pub(crate) kids: Vec<String>,
+}
+
+/// Fetches `/.well-known/trusted-server.json` from the origin of `base_url`
+/// and returns the kids of its JWKS, which must all be well-formed.
+pub(crate) fn fetch_published_keys(
+    client: &dyn HttpClient,
+    base_url: &Url,
+) -> Result<PublishedKeys, ProbeFailure> {
+    let response = client
+        .send(&HttpRequest::get(endpoint(base_url, DISCOVERY_PATH)))
+        .map_err(|error| ProbeFailure::new(error, UNREACHABLE_HINT))?;
+    if response.status != 200 {
+        return Err(ProbeFailure::new(
+            format!("{DISCOVERY_PATH} returned HTTP {}", response.status),
+            "every adapter serves discovery; a 404 usually means the URL does not reach Trusted Server",
+        ));
+    }
+    let document = serde_json::from_str::<JsonValue>(&response.body).map_err(|error| {
+        ProbeFailure::new(
+            format!("{DISCOVERY_PATH} is not JSON: {error}"),
+            REWRITTEN_HINT,
+        )
+    })?;
+
+    let keys = document
+        .pointer("/jwks/keys")
+        .and_then(JsonValue::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    if keys.is_empty() {
+        return Err(ProbeFailure::new(
+            "the JWKS publishes no keys",
+            "create a request-signing key with `ts keys rotate`",
+        ));
+    }
+
+    let mut kids = Vec::new();
+    let mut problems = Vec::new();
+    for (index, key) in keys.iter().enumerate() {
+        match validate_jwk(key) {
+            Ok(kid) => kids.push(kid),
+            Err(problem) => problems.push(format!("key {index}: {problem}")),
+        }
+    }
+    if !problems.is_empty() {
+        return Err(ProbeFailure::new(
+            problems.join("; "),
+            "partners cannot verify signatures from malformed keys; rotate in a new key",
+        ));
+    }
+
+    let version = document
+        .get("version")
+        .and_then(JsonValue::as_str)
+        .unwrap_or("unknown")
+        .to_string();
+    Ok(PublishedKeys { version, kids })
+}
+
+/// Returns the kid of a well-formed Ed25519 public JWK.
+fn validate_jwk(key: &JsonValue) -> Result<String, String> {
+    let field = |name: &str| key.get(name).and_then(JsonValue::as_str);
+    let kid = field("kid")
+        .filter(|kid| !kid.is_empty())
+        .ok_or_else(|| "missing `kid`".to_string())?;
+    if field("kty") != Some("OKP") || field("crv") != Some("Ed25519") {
+        return Err(format!("`{kid}` is not an Ed25519 (OKP) key"));
+    }
+    let x = field("x").unwrap_or_default();
+    let is_url_safe = x
+        .bytes()
+        .all(|byte| byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_');
+    if x.len() != ED25519_PUBLIC_KEY_B64_LEN || !is_url_safe {
+        return Err(format!("`{kid}` has no 32-byte URL-safe base64 `x` value"));
+    }
+    Ok(kid.to_string())
+}
+
+/// How `/verify-signature` answered a probe for one kid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum KidVerification {
+    /// The signature verified.
+    Verified,
+    /// The signature was rejected as invalid, so the kid resolved to a key.
+    Rejected,
+    /// The kid could not be resolved in the key store.
+    Unresolved,
+}
+
+/// Probes `/verify-signature` on the origin of `base_url` for `kid`.
+///
+/// With `signer`, whose kid must be `kid`, a real signature is sent. Without
+/// it, a well-formed forgery still proves the endpoint is live and resolves
+/// the kid, since it must come back [`KidVerification::Rejected`].
+pub(crate) fn verify_kid(
+    client: &dyn HttpClient,
+    base_url: &Url,
+    kid: &str,
+    signer: Option<&RequestSigner>,
+) -> Result<KidVerification, ProbeFailure> {
+    let request = match signer {
+        Some(signer) => signed_request(base_url, signer)
+            .map_err(|error| ProbeFailure::new(error, "check the --signing-key file"))?,
+        None => VerifySignatureRequest {
+            payload: "ts forged signature probe".to_string(),
+            signature: "A".repeat(ED25519_SIGNATURE_B64_LEN),
+            kid: kid.to_string(),
+        },
+    };
+    let body = serde_json::to_string(&request).map_err(|error| {
+        ProbeFailure::new(
+            format!("failed to serialize verification request: {error}"),
+            "this is a bug in `ts`",
+        )
+    })?;
+
+    let response = client
+        .send(&HttpRequest::post_json(
+            endpoint(base_url, VERIFY_SIGNATURE_PATH),
+            body,
+        ))
+        .map_err(|error| ProbeFailure::new(error, UNREACHABLE_HINT))?;
+    if !response.is_success() {
+        return Err(ProbeFailure::new(
+            format!("{VERIFY_SIGNATURE_PATH} returned HTTP {}", response.status),
+            "the verify route is public on every adapter; check that the URL reaches Trusted Server",
+        ));
+    }
+    let verdict =
+        serde_json::from_str::<VerifySignatureResponse>(&response.body).map_err(|error| {
+            ProbeFailure::new(
+                format!("{VERIFY_SIGNATURE_PATH} returned an unexpected body: {error}"),
+                REWRITTEN_HINT,
+            )
+        })?;
+
+    Ok(if verdict.verified {
+        KidVerification::Verified
+    } else if verdict.error.as_deref() == Some(INVALID_SIGNATURE_ERROR) {
+        KidVerification::Rejected
+    } else {
+        KidVerification::Unresolved
+    })
+}
+
+fn check_signature(deployment: &Deployment<'_>, kids: &[String]) -> CheckOutcome {
+    const NAME: &str = "signature";
+    let Some(first_kid) = kids.first() else {
+        return CheckOutcome::skip(NAME, "no published keys to verify against");
+    };
+    let kid = match deployment.signer {
+        Some(signer) if !kids.contains(&signer.kid) => {
+            return CheckOutcome::fail(
+                NAME,
+                format!(
+                    "--signing-kid `{}` is not published in the JWKS",
+                    signer.kid
+                ),
+                "pass the kid of an active key, or rotate the key in first",
+            );
+        }
+        Some(signer) => &signer.kid,
+        None => first_kid,
+    };
+
+    let verification = match verify_kid(
+        deployment.client,
+        deployment.page_url,
+        kid,
+        deployment.signer,
+    ) {
+        Ok(verification) => verification,
+        Err(failure) => return CheckOutcome::fail(NAME, failure.detail, failure.hint),
+    };
+    match (deployment.signer.is_some(), verification) {
+        (true, KidVerification::Verified) => {
+            CheckOutcome::pass(NAME, format!("signed payload verified with `{kid}`"))
+        }
+        (false, KidVerification::Verified) => CheckOutcome::fail(
+            NAME,
+            format!("a forged signature for `{kid}` was accepted"),
+            "the deployment is not verifying signatures; do not send partner traffic to it",
+        ),
+        (false, KidVerification::Rejected) => CheckOutcome::pass(
+            NAME,
+            format!(
+                "a forged signature for `{kid}` was rejected; pass --signing-key for a signed round trip"
+            ),
+        ),
+        (true, KidVerification::Rejected) => CheckOutcome::fail(
+            NAME,
+            format!("the signature from `{kid}` was rejected"),
+            "the --signing-key does not match the public key published for this kid",
+        ),
+        (_, KidVerification::Unresolved) => CheckOutcome::fail(
+            NAME,
+            format!("`{kid}` could not be resolved in the key store"),
+            "the JWKS and the signing key store disagree; rotate keys so both are rewritten",
+        ),
+    }
+}
+
+fn signed_request(
+    base_url: &Url,
+    signer: &RequestSigner,
+) -> Result<VerifySignatureRequest, String> {
+    let params = SigningParams::new(
+        "ts-verify".to_string(),
+        base_url.host_str().unwrap_or_default().to_string(),
+        base_url.scheme().to_string(),
+    );
+    let payload = params
+        .build_payload(&signer.kid)
+        .map_err(|report| format!("{report}"))?;
+    let signature = signer
+        .sign(payload.as_bytes())
+        .map_err(|report| format!("{report}"))?;
+    Ok(VerifySignatureRequest {
+        payload,
+        signature,
+        kid: signer.kid.clone(),
+    })
+}
+
+fn check_page(page: &CliResult<HttpResponse>) -> (CheckOutcome, CheckOutcome) {
+    const INJECTION: &str = "tsjs";
+    const COOKIE: &str = "ec-cookie";
+    let response = match page {
+        Ok(response) if response.is_success() => response,
+        Ok(response) => {
+            return (
+                CheckOutcome::fail(
+                    INJECTION,
+                    format!("the page returned HTTP {}", response.status),
+                    "check the publisher origin settings and that the origin is reachable from the edge",
+                ),
+                CheckOutcome::skip(COOKIE, "the page did not load"),
+            );
+        }
+        Err(error) => {
+            return (
+                CheckOutcome::fail(INJECTION, error.clone(), UNREACHABLE_HINT),
+                CheckOutcome::skip(COOKIE, "the page did not load"),
+            );
+        }
+    };
+
+    let injection = if has_tsjs_script(&response.body) {
+        CheckOutcome::pass(INJECTION, "the tsjs bundle is injected into the page")
+    } else {
+        CheckOutcome::fail(
+            INJECTION,
+            "the page HTML has no tsjs script tag",
+            "the HTML was not rewritten; check that the page is served through Trusted Server and not from an upstream cache",
+        )
+    };
+
+    let cookie_prefix = format!("{COOKIE_TS_EC}=");
+    let cookie = if response
+        .header_values("set-cookie")
+        .any(|cookie| cookie.trim_start().starts_with(&cookie_prefix))
+    {
+        CheckOutcome::pass(COOKIE, format!("the `{COOKIE_TS_EC}` cookie is set"))
+    } else {
+        CheckOutcome::warn(
+            COOKIE,
+            format!("no `{COOKIE_TS_EC}` cookie was set"),
+            "the edge cookie is only set when consent allows it; retry from a region without an opt-in requirement, or check the [ec] settings",
+        )
+    };
+
+    (injection, cookie)
+}
+
+fn has_tsjs_script(html: &str) -> bool {
+    let document = Html::parse_document(html);
+    let selector = Selector::parse("script[src]").expect("should parse script selector");
+    document.select(&selector).any(|script| {
+        let element = script.value();
+        element.id() == Some(TSJS_SCRIPT_ID)
+            || element
+                .attr("src")
+                .is_some_and(|src| src.contains(TSJS_SCRIPT_PATH))
+    })
+}
+
+fn check_auction(deployment: &Deployment<'_>) -> CheckOutcome {
+    const NAME: &str = "auction";
+    let body = json!({
+        "adUnits": [{
+            "code": deployment.slot,
+            "mediaTypes": { "banner": { "sizes": [[300, 250]] } },
+        }],
+    })
+    .to_string();
+    let response = match deployment.client.send(&HttpRequest::post_json(
+        endpoint(deployment.page_url, AUCTION_PATH),
+        body,
+    )) {
+        Ok(response) => response,
+        Err(error) => return CheckOutcome::fail(NAME, error, UNREACHABLE_HINT),
+    };
+    if !response.is_success() {
+        return CheckOutcome::fail(
+            NAME,
+            format!("{AUCTION_PATH} returned HTTP {}", response.status),
+            "check that [auction] is enabled with at least one provider",
+        );
+    }
+    let Ok(result) = serde_json::from_str::<JsonValue>(&response.body) else {
+        return CheckOutcome::fail(
+            NAME,
+            format!("{AUCTION_PATH} did not return JSON"),
+            REWRITTEN_HINT,
+        );
+    };
+
+    let bids = result
+        .get("seatbid")
+        .and_then(JsonValue::as_array)
+        .map_or(0, |seats| {
+            seats
+                .iter()
+                .map(|seat| {
+                    seat.get("bid")
+                        .and_then(JsonValue::as_array)
+                        .map_or(0, Vec::len)
+                })
+                .sum()
+        });
+    CheckOutcome::pass(
+        NAME,
+        format!("the test slot `{}` returned {bids} bid(s)", deployment.slot),
+    )
+}
+
+fn check_asset_cache(deployment: &Deployment<'_>, page: Option<&HttpResponse>) -> CheckOutcome {
+    const NAME: &str = "asset-cache";
+    let Some(cache) = deployment.cache else {
+        return CheckOutcome::skip(
+            NAME,
+            "no app config loaded; pass --app-config to check [[cache.asset_rules]]",
+        );
+    };
+    if !cache.asset_rules.iter().any(|rule| rule.enabled) {
+        return CheckOutcome::skip(NAME, "no enabled [[cache.asset_rules]]");
+    }
+    let Some(page) = page else {
+        return CheckOutcome::skip(NAME, "the page did not load");
+    };
+
+    let mut checked = 0;
+    let mut mismatches = Vec::new();
+    for asset_url in first_party_assets(deployment.page_url, &page.body) {
+        let policy = match cache.asset_policy_for_path(asset_url.path()) {
+            Ok(Some(policy)) => policy,
+            Ok(None) => continue,
+            Err(report) => {
+                return CheckOutcome::fail(
+                    NAME,
+                    format!("{report}"),
+                    "run `ts config validate` on the app config",
+                );
+            }
+        };
+        if checked == MAX_CACHE_ASSETS {
+            break;
+        }
+        checked += 1;
+
+        let expected = policy.cache_control_value(EdgeCacheHeader::None);
+        let response = match deployment.client.send(&HttpRequest::get(asset_url.clone())) {
+            Ok(response) => response,
+            Err(error) => {
+                mismatches.push(error);
+                continue;
+            }
+        };
+        let actual = response
+            .header_values("cache-control")
+            .collect::<Vec<_>>()
+            .join(", ");
+        if !missing_directives(&expected, &actual).is_empty() {
+            mismatches.push(format!(
+                "{}: expected `{expected}`, got `{actual}`",
+                asset_url.path()
+            ));
+        }
+    }
+
+    if checked == 0 {
+        return CheckOutcome::warn(
+            NAME,
+            "no first-party page assets matched a cache rule",
+            "compare the rule matchers with the asset paths the page loads",
+        );
+    }
+    if mismatches.is_empty() {
+        return CheckOutcome::pass(NAME, format!("{checked} asset(s) match their cache rules"));
+    }
+    CheckOutcome::fail(
+        NAME,
+        mismatches.join("; "),
+        "an origin or CDN header may be overriding the rule; confirm the assets are served through Trusted Server",
+    )
+}
+
+/// Same-origin script and stylesheet URLs referenced by the page, in document
+/// order.
+fn first_party_assets(page_url: &Url, html: &str) -> Vec<Url> {
+    let document = Html::parse_document(html);
+    let selector = Selector::parse(
+        r#"script[src], link[rel~="stylesheet"][href], link[rel~="preload"][href], link[rel~="modulepreload"][href]"#,
+    )
+    .expect("should parse asset selector");
+
+    let mut seen = BTreeSet::new();
+    document
+        .select(&selector)
+        .filter_map(|element| {
+            let element = element.value();
+            element.attr("src").or_else(|| element.attr("href"))
+        })
+        .filter_map(|reference| page_url.join(reference).ok())
+        .filter(|url| url.origin() == page_url.origin())
+        .filter(|url| seen.insert(url.as_str().to_string()))
+        .collect()
+}
+
+/// Directives of `expected` absent from the `actual` `Cache-Control` value.
+/// Extra directives in `actual`, such as an edge `s-maxage`, are allowed.
+fn missing_directives(expected: &str, actual: &str) -> Vec<String> {
+    let actual = actual
+        .split(',')
+        .map(|directive| directive.trim().to_ascii_lowercase())
+        .collect::<BTreeSet<_>>();
+    expected
+        .split(',')
+        .map(str::trim)
+        .filter(|directive| !directive.is_empty())
+        .filter(|directive| !actual.contains(&directive.to_ascii_lowercase()))
+        .map(ToOwned::to_owned)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    /// Serves canned responses keyed by request path; unknown paths are 404s.
+    #[derive(Default)]
+    struct FakeDeployment {
+        responses: HashMap<String, HttpResponse>,
+    }
+
+    impl FakeDeployment {
+        fn healthy() -> Self {
+            let mut deployment = Self::default();
+            deployment.respond(
+                DISCOVERY_PATH,
+                &[],
+                &json!({
+                    "version": "1.0",
+                    "jwks": { "keys": [jwk("kid-a")] },
+                })
+                .to_string(),
+            );
+            deployment.respond(
+                VERIFY_SIGNATURE_PATH,
+                &[],
+                r#"{"verified":false,"kid":"kid-a","message":"Signature verification failed","error":"Invalid signature"}"#,
+            );
+            deployment.respond(
+                "/",
+                &[(
+                    "set-cookie",
+                    "ts-ec=abc123; Domain=.publisher.example; Path=/; Secure; HttpOnly",
+                )],
+                r#"<html><head>
+                    <script src="/static/tsjs=tsjs-unified.min.js?v=abc" id="trustedserver-js"></script>
+                    <script src="/assets/app.js"></script>
+                    <link rel="stylesheet" href="/assets/site.css">
+                    <script src="https://cdn.vendor.example/assets/tag.js"></script>
+                </head></html>"#,
+            );
+            deployment.respond(
+                AUCTION_PATH,
+                &[],
+                r#"{"id":"auction","seatbid":[{"seat":"mocktioneer","bid":[{"id":"1"}]}]}"#,
+            );
+            deployment.respond(
+                "/assets/app.js",
+                &[("cache-control", "public, max-age=3600, s-maxage=3600")],
+                "",
+            );
+            deployment.respond(
+                "/assets/site.css",
+                &[("cache-control", "public, max-age=3600")],
+                "",
+            );
+            deployment
+        }
+
+        fn respond(&mut self, path: &str, headers: &[(&str, &str)], body: &str) {
+            self.responses.insert(
+                path.to_string(),
+                HttpResponse {
+                    status: 200,
+                    headers: headers
+                        .iter()
+                        .map(|(name, value)| ((*name).to_string(), (*value).to_string()))
+                        .collect(),
+                    body: body.to_string(),
+                },
+            );
+        }
+    }
+
+    impl HttpClient for FakeDeployment {
+        fn send(&self, request: &HttpRequest) -> CliResult<HttpResponse> {
+            Ok(self
+                .responses
+                .get(request.url.path())
+                .cloned()
+                .unwrap_or(HttpResponse {
+                    status: 404,
+                    headers: Vec::new(),
+                    body: String::new(),
+                }))
+        }
+    }
+
+    fn jwk(kid: &str) -> JsonValue {
+        json!({
+            "kty": "OKP",
+            "crv": "Ed25519",
+            "kid": kid,
+            "alg": "EdDSA",
+            "x": "A".repeat(ED25519_PUBLIC_KEY_B64_LEN),
+        })
+    }
+
+    fn cache_rules() -> CacheSettings {
+        toml::from_str(
+            r#"
+            [[asset_rules]]
+            id = "assets"
+            enabled = true
+            path_prefix = "/assets/"
+            browser_ttl_seconds = 3600
+            "#,
+        )
+        .expect("should parse cache rules")
+    }
+
+    fn run(client: &FakeDeployment, signer: Option<&RequestSigner>) -> Vec<CheckOutcome> {
+        let page_url = Url::parse("https://publisher.example/").expect("should parse URL");
+        let cache = cache_rules();
+        run_checks(&Deployment {
+            page_url: &page_url,
+            client,
+            signer,
+            cache: Some(&cache),
+            slot: "ts-doctor",
+        })
+    }
+
+    fn find<'a>(outcomes: &'a [CheckOutcome], name: &str) -> &'a CheckOutcome {
+        outcomes
+            .iter()
+            .find(|outcome| outcome.name == name)
+            .unwrap_or_else(|| panic!("should run the {name} check"))
+    }
+
+    #[test]
+    fn healthy_deployment_passes_every_check() {
+        let outcomes = run(&FakeDeployment::healthy(), None);
+
+        for outcome in &outcomes {
+            assert_eq!(
+                outcome.status,
+                CheckStatus::Pass,
+                "{} should pass: {}",
+                outcome.name,
+                outcome.detail
+            );
+        }
+        assert_eq!(
+            find(&outcomes, "discovery").detail,
+            "version 1.0, 1 key(s): kid-a"
+        );
+        assert_eq!(
+            find(&outcomes, "auction").detail,
+            "the test slot `ts-doctor` returned 1 bid(s)"
+        );
+        assert_eq!(
+            find(&outcomes, "asset-cache").detail,
+            "2 asset(s) match their cache rules",
+            "third-party assets should not be checked"
+        );
+    }
+
+    #[test]
+    fn broken_deployment_reports_failures_with_hints() {
+        let mut deployment = FakeDeployment::healthy();
+        deployment.respond(
+            VERIFY_SIGNATURE_PATH,
+            &[],
+            r#"{"verified":true,"kid":"kid-a","message":"Signature verified successfully"}"#,
+        );
+        deployment.respond(
+            "/",
+            &[],
+            r#"<html><head><script src="/assets/app.js"></script></head></html>"#,
+        );
+        deployment.respond("/assets/app.js", &[("cache-control", "no-cache")], "");
+        deployment.responses.remove(AUCTION_PATH);
+
+        let outcomes = run(&deployment, None);
+
+        let signature = find(&outcomes, "signature");
+        assert_eq!(signature.status, CheckStatus::Fail);
+        assert!(signature.detail.contains("forged signature"));
+        assert_eq!(find(&outcomes, "tsjs").status, CheckStatus::Fail);
+        assert_eq!(find(&outcomes, "ec-cookie").status, CheckStatus::Warn);
+        assert_eq!(find(&outcomes, "auction").status, CheckStatus::Fail);
+        let cache = find(&outcomes, "asset-cache");
+        assert_eq!(cache.status, CheckStatus::Fail);
+        assert_eq!(
+            cache.detail,
+            "/assets/app.js: expected `public, max-age=3600`, got `no-cache`"
+        );
+        assert!(
+            outcomes
+                .iter()
+                .filter(|outcome| outcome.status != CheckStatus::Pass)
+                .all(|outcome| outcome.hint.is_some()),
+            "every warning and failure should carry a hint"
+        );
+    }
+
+    #[test]
+    fn signature_check_fails_when_forged_kid_cannot_be_resolved() {
+        let mut deployment = FakeDeployment::healthy();
+        deployment.respond(
+            VERIFY_SIGNATURE_PATH,
+            &[],
+            r#"{"verified":false,"kid":"kid-a","message":"Verification error","error":"internal verification error"}"#,
+        );
+
+        let outcomes = run(&deployment, None);
+
+        let signature = find(&outcomes, "signature");
+        assert_eq!(signature.status, CheckStatus::Fail);
+        assert_eq!(
+            signature.detail,
+            "`kid-a` could not be resolved in the key store"
+        );
+    }
+
+    #[test]
+    fn signature_check_round_trips_with_operator_key() {
+        let mut deployment = FakeDeployment::healthy();
+        deployment.respond(
+            VERIFY_SIGNATURE_PATH,
+            &[],
+            r#"{"verified":true,"kid":"kid-a","message":"Signature verified successfully"}"#,
+        );
+        let signer = RequestSigner::from_base64_key("kid-a", &format!("{}=", "A".repeat(43)))
+            .expect("should load signing key");
+        let unpublished = RequestSigner::from_base64_key("kid-b", &format!("{}=", "A".repeat(43)))
+            .expect("should load signing key");
+
+        let outcomes = run(&deployment, Some(&signer));
+        assert_eq!(
+            find(&outcomes, "signature").detail,
+            "signed payload verified with `kid-a`"
+        );
+
+        let outcomes = run(&deployment, Some(&unpublished));
+        let signature = find(&outcomes, "signature");
+        assert_eq!(signature.status, CheckStatus::Fail);
+        assert!(signature.detail.contains("not published in the JWKS"));
+    }
+
+    #[test]
+    fn discovery_rejects_empty_and_malformed_key_sets() {
+        let mut deployment = FakeDeployment::healthy();
+        deployment.respond(
+            DISCOVERY_PATH,
+            &[],
+            r#"{"version":"1.0","jwks":{"keys":[]}}"#,
+        );
+        let outcomes = run(&deployment, None);
+        assert_eq!(find(&outcomes, "discovery").status, CheckStatus::Fail);
+        assert_eq!(
+            find(&outcomes, "signature").status,
+            CheckStatus::Skip,
+            "signature check needs a published kid"
+        );
+
+        let mut key = jwk("kid-a");
+        key["crv"] = json!("P-256");
+        assert_eq!(
+            validate_jwk(&key),
+            Err("`kid-a` is not an Ed25519 (OKP) key".to_string())
+        );
+        key = jwk("kid-a");
+        key["x"] = json!("too-short");
+        assert!(validate_jwk(&key).is_err(), "should reject a short key");
+        assert_eq!(validate_jwk(&jwk("kid-a")), Ok("kid-a".to_string()));
+    }
+
+    #[test]
+    fn missing_directives_ignores_order_case_and_extra_directives() {
+        assert!(
+            missing_directives(
+                "public, max-age=3600, immutable",
+                "Immutable, max-age=3600, public, s-maxage=86400"
+            )
+            .is_empty()
+        );
+        assert_eq!(
+            missing_directives("public, max-age=3600", "public, max-age=60"),
+            vec!["max-age=3600".to_string()]
+        );
+    }
+
+    #[test]
+    fn first_party_assets_keeps_same_origin_scripts_and_styles() {
+        let page_url = Url::parse("https://publisher.example/news/").expect("should parse URL");
+        let assets = first_party_assets(
+            &page_url,
+            r#"<script src="app.js"></script>
+               <script src="/app.js"></script>
+               <script>inline()</script>
+               <link rel="icon" href="/favicon.ico">
+               <link rel="preload stylesheet" href="/site.css">
+               <script src="https://cdn.vendor.example/tag.js"></script>"#,
+        );
+
+        let paths = assets.iter().map(Url::path).collect::<Vec<_>>();
+        assert_eq!(paths, vec!["/news/app.js", "/app.js", "/site.css"]);
+    }
+}
@@ -0,0 +1,184 @@
+pub(crate) mod checks;
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use trusted_server_core::config::TrustedServerAppConfig;
+use trusted_server_core::request_signing::RequestSigner;
+use trusted_server_core::settings::CacheSettings;
+use url::Url;
+
+use crate::commands::doctor::checks::{CheckOutcome, CheckStatus, Deployment, run_checks};
+use crate::error::{CliResult, cli_error};
+use crate::http::{HttpClient, parse_deployment_url};
+
+const DEFAULT_APP_CONFIG: &str = "trusted-server.toml";
+
+/// Arguments for the `ts doctor` command.
+#[derive(Debug, clap::Args)]
+pub(crate) struct DoctorArgs {
+    /// Public URL of a page served through the deployment.
+    pub(crate) url: String,
+    /// App config whose `[[cache.asset_rules]]` are checked against the page's
+    /// assets. Defaults to `trusted-server.toml` when that file exists.
+    #[arg(long = "app-config", alias = "config")]
+    pub(crate) app_config: Option<PathBuf>,
+    /// File holding the standard base64 Ed25519 request-signing private key
+    /// used for a signed `/verify-signature` round trip.
+    #[arg(long, requires = "signing_kid")]
+    pub(crate) signing_key: Option<PathBuf>,
+    /// Key id of `--signing-key`; must be published in the JWKS.
+    #[arg(long, requires = "signing_key")]
+    pub(crate) signing_kid: Option<String>,
+    /// Ad slot code sent to `/auction`.
+    #[arg(long, default_value = "ts-doctor")]
+    pub(crate) slot: String,
+    /// Per-request timeout in seconds.
+    #[arg(long, default_value_t = 15)]
+    pub(crate) timeout: u64,
+}
+
+pub(crate) fn run_doctor(
+    args: &DoctorArgs,
+    client: &dyn HttpClient,
+    out: &mut dyn Write,
+) -> CliResult<()> {
+    let page_url = parse_deployment_url(&args.url)?;
+    let signer = load_signer(args.signing_key.as_deref(), args.signing_kid.as_deref())?;
+    let cache = load_cache_settings(args.app_config.as_deref())?;
+    let deployment = Deployment {
+        page_url: &page_url,
+        client,
+        signer: signer.as_ref(),
+        cache: cache.as_ref(),
+        slot: &args.slot,
+    };
+
+    let outcomes = run_checks(&deployment);
+    write_report(&page_url, &outcomes, out)
+}
+
+/// Loads the operator copy of a request-signing key when both `path` and
+/// `kid` are given.
+pub(crate) fn load_signer(
+    path: Option<&Path>,
+    kid: Option<&str>,
+) -> CliResult<Option<RequestSigner>> {
+    let (Some(path), Some(kid)) = (path, kid) else {
+        return Ok(None);
+    };
+    let key = fs::read_to_string(path)
+        .map_err(|error| format!("failed to read signing key {}: {error}", path.display()))?;
+    RequestSigner::from_base64_key(kid, &key)
+        .map(Some)
+        .map_err(|report| format!("invalid signing key {}: {report}", path.display()))
+}
+
+/// Loads the cache rules from `path`, or from the default app config when it
+/// exists. A missing default config skips the cache check.
+fn load_cache_settings(path: Option<&Path>) -> CliResult<Option<CacheSettings>> {
+    let path = match path {
+        Some(path) => path,
+        None if Path::new(DEFAULT_APP_CONFIG).exists() => Path::new(DEFAULT_APP_CONFIG),
+        None => return Ok(None),
+    };
+    let contents = fs::read_to_string(path)
+        .map_err(|error| format!("failed to read config {}: {error}", path.display()))?;
+    let config: TrustedServerAppConfig = toml::from_str(&contents)
+        .map_err(|error| format!("failed to parse config {}: {error}", path.display()))?;
+    Ok(Some(config.into_settings().cache))
+}
+
+fn write_report(page_url: &Url, outcomes: &[CheckOutcome], out: &mut dyn Write) -> CliResult<()> {
+    let write_error = |error: std::io::Error| format!("failed to write command output: {error}");
+    writeln!(out, "Doctor report for {page_url}").map_err(write_error)?;
+    for outcome in outcomes {
+        writeln!(
+            out,
+            "{}  {:<11}  {}",
+            outcome.status.label(),
+            outcome.name,
+            outcome.detail
+        )
+        .map_err(write_error)?;
+        if let Some(hint) = &outcome.hint {
+            writeln!(out, "      hint: {hint}").map_err(write_error)?;
+        }
+    }
+
+    let count = |status: CheckStatus| {
+        outcomes
+            .iter()
+            .filter(|outcome| outcome.status == status)
+            .count()
+    };
+    let failed = count(CheckStatus::Fail);
+    writeln!(
+        out,
+        "{} passed, {} warning(s), {failed} failed, {} skipped",
+        count(CheckStatus::Pass),
+        count(CheckStatus::Warn),
+        count(CheckStatus::Skip)
+    )
+    .map_err(write_error)?;
+
+    if failed > 0 {
+        return cli_error(format!("{failed} doctor check(s) failed"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outcome(name: &'static str, status: CheckStatus, hint: Option<&str>) -> CheckOutcome {
+        CheckOutcome {
+            name,
+            status,
+            detail: format!("{name} detail"),
+            hint: hint.map(ToOwned::to_owned),
+        }
+    }
+
+    #[test]
+    fn write_report_lists_checks_with_hints_and_summary() {
+        let url = Url::parse("https://publisher.example/").expect("should parse URL");
+        let outcomes = vec![
+            outcome("discovery", CheckStatus::Pass, None),
+            outcome("ec-cookie", CheckStatus::Warn, Some("check consent")),
+            outcome("asset-cache", CheckStatus::Skip, None),
+        ];
+        let mut out = Vec::new();
+
+        write_report(&url, &outcomes, &mut out).expect("warnings should not fail the run");
+
+        let report = String::from_utf8(out).expect("report should be UTF-8");
+        assert_eq!(
+            report,
+            "Doctor report for https://publisher.example/\n\
+             PASS  discovery    discovery detail\n\
+             WARN  ec-cookie    ec-cookie detail\n\
+             \x20     hint: check consent\n\
+             SKIP  asset-cache  asset-cache detail\n\
+             1 passed, 1 warning(s), 0 failed, 1 skipped\n"
+        );
+    }
+
+    #[test]
+    fn write_report_fails_when_any_check_fails() {
+        let url = Url::parse("https://publisher.example/").expect("should parse URL");
+        let outcomes = vec![
+            outcome("discovery", CheckStatus::Fail, Some("deploy first")),
+            outcome("auction", CheckStatus::Fail, Some("enable auction")),
+        ];
+        let mut out = Vec::new();
+
+        let error = write_report(&url, &outcomes, &mut out).expect_err("should fail");
+
+        assert_eq!(error, "2 doctor check(s) failed");
+        let report = String::from_utf8(out).expect("report should be UTF-8");
+        assert!(report.contains("0 passed, 0 warning(s), 2 failed, 0 skipped"));
+    }
+}
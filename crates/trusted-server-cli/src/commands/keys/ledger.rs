@@ -0,0 +1,331 @@
+use std::fs;
+use std::path::Path;
+
+use chrono::{DateTime, Duration, NaiveDate, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+
+pub(crate) const DEFAULT_KEYS_FILE: &str = "trusted-server.keys.json";
+pub(crate) const DEFAULT_MAX_AGE_DAYS: u32 = 90;
+
+/// Local record of the request-signing keys `ts keys` created and retired.
+///
+/// The deployment only publishes which keys are active, so key ages, the
+/// current signing key, and overlap windows live here.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct KeyLedger {
+    /// Age in days after which the current key should be rotated.
+    #[serde(default = "default_max_age_days")]
+    pub(crate) max_age_days: u32,
+    /// Kid the deployment signs with, as of the last rotation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) current: Option<String>,
+    #[serde(default)]
+    pub(crate) keys: Vec<KeyRecord>,
+}
+
+impl Default for KeyLedger {
+    fn default() -> Self {
+        Self {
+            max_age_days: DEFAULT_MAX_AGE_DAYS,
+            current: None,
+            keys: Vec::new(),
+        }
+    }
+}
+
+fn default_max_age_days() -> u32 {
+    DEFAULT_MAX_AGE_DAYS
+}
+
+/// Lifecycle timestamps of one key, as RFC 3339 strings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct KeyRecord {
+    pub(crate) kid: String,
+    pub(crate) created_at: String,
+    /// End of the overlap window after the key was rotated out; it is
+    /// deactivated by the next `ts keys rotate` after this time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) retire_after: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) deactivated_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) deleted_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum KeyStatus {
+    /// The key the deployment signs with.
+    Current,
+    /// Published and verifiable, but not scheduled for retirement.
+    Active,
+    /// Rotated out and still published until its overlap window ends.
+    Retiring,
+    Deactivated,
+    Deleted,
+    /// Published by the deployment but not recorded in the ledger.
+    Untracked,
+}
+
+impl KeyStatus {
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            Self::Current => "current",
+            Self::Active => "active",
+            Self::Retiring => "retiring",
+            Self::Deactivated => "deactivated",
+            Self::Deleted => "deleted",
+            Self::Untracked => "untracked",
+        }
+    }
+}
+
+impl KeyLedger {
+    pub(crate) fn record(&self, kid: &str) -> Option<&KeyRecord> {
+        self.keys.iter().find(|record| record.kid == kid)
+    }
+
+    pub(crate) fn status(&self, record: &KeyRecord) -> KeyStatus {
+        if record.deleted_at.is_some() {
+            KeyStatus::Deleted
+        } else if record.deactivated_at.is_some() {
+            KeyStatus::Deactivated
+        } else if self.current.as_deref() == Some(record.kid.as_str()) {
+            KeyStatus::Current
+        } else if record.retire_after.is_some() {
+            KeyStatus::Retiring
+        } else {
+            KeyStatus::Active
+        }
+    }
+
+    /// Creation time of `kid` from the ledger, falling back to the date in a
+    /// generated `ts-YYYY-MM-DD` kid.
+    pub(crate) fn created_at(&self, kid: &str) -> Option<DateTime<Utc>> {
+        self.record(kid)
+            .and_then(|record| parse_timestamp(&record.created_at))
+            .or_else(|| kid_date(kid))
+    }
+
+    /// The key the deployment signs with and its creation time.
+    ///
+    /// Without a recorded rotation, the newest dated kid in `published` is
+    /// assumed to be current.
+    pub(crate) fn current_key(&self, published: &[String]) -> Option<(String, DateTime<Utc>)> {
+        if let Some(kid) = &self.current {
+            return self
+                .created_at(kid)
+                .map(|created_at| (kid.clone(), created_at));
+        }
+        published
+            .iter()
+            .filter_map(|kid| kid_date(kid).map(|created_at| (kid.clone(), created_at)))
+            .max_by_key(|(_, created_at)| *created_at)
+    }
+
+    /// Retiring keys whose overlap window has ended by `now`.
+    pub(crate) fn due_for_retirement(&self, now: DateTime<Utc>) -> Vec<String> {
+        self.keys
+            .iter()
+            .filter(|record| self.status(record) == KeyStatus::Retiring)
+            .filter(|record| {
+                record
+                    .retire_after
+                    .as_deref()
+                    .and_then(parse_timestamp)
+                    .is_some_and(|retire_after| retire_after <= now)
+            })
+            .map(|record| record.kid.clone())
+            .collect()
+    }
+
+    /// Records `new_kid` as current and schedules `previous_kid` to retire
+    /// once `overlap` has passed.
+    pub(crate) fn record_rotation(
+        &mut self,
+        new_kid: &str,
+        previous_kid: Option<&str>,
+        now: DateTime<Utc>,
+        overlap: Duration,
+    ) {
+        if let Some(previous_kid) = previous_kid.filter(|kid| *kid != new_kid) {
+            let created_at = self.created_at(previous_kid).unwrap_or(now);
+            let record = self.entry(previous_kid, created_at);
+            record.retire_after = Some(format_timestamp(now + overlap));
+        }
+        self.keys.retain(|record| record.kid != new_kid);
+        self.keys.push(KeyRecord {
+            kid: new_kid.to_string(),
+            created_at: format_timestamp(now),
+            retire_after: None,
+            deactivated_at: None,
+            deleted_at: None,
+        });
+        self.current = Some(new_kid.to_string());
+    }
+
+    pub(crate) fn record_deactivation(&mut self, kid: &str, deleted: bool, now: DateTime<Utc>) {
+        let created_at = self.created_at(kid).unwrap_or(now);
+        let record = self.entry(kid, created_at);
+        record
+            .deactivated_at
+            .get_or_insert_with(|| format_timestamp(now));
+        if deleted {
+            record.deleted_at = Some(format_timestamp(now));
+        }
+        if self.current.as_deref() == Some(kid) {
+            self.current = None;
+        }
+    }
+
+    fn entry(&mut self, kid: &str, created_at: DateTime<Utc>) -> &mut KeyRecord {
+        let index = match self.keys.iter().position(|record| record.kid == kid) {
+            Some(index) => index,
+            None => {
+                self.keys.push(KeyRecord {
+                    kid: kid.to_string(),
+                    created_at: format_timestamp(created_at),
+                    retire_after: None,
+                    deactivated_at: None,
+                    deleted_at: None,
+                });
+                self.keys.len() - 1
+            }
+        };
+        &mut self.keys[index]
+    }
+}
+
+pub(crate) fn load_ledger(path: &Path) -> Result<KeyLedger, String> {
+    match fs::read_to_string(path) {
+        Ok(contents) => serde_json::from_str(&contents)
+            .map_err(|error| format!("failed to parse keys file {}: {error}", path.display())),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(KeyLedger::default()),
+        Err(error) => Err(format!(
+            "failed to read keys file {}: {error}",
+            path.display()
+        )),
+    }
+}
+
+pub(crate) fn save_ledger(path: &Path, ledger: &KeyLedger) -> Result<(), String> {
+    let mut contents = serde_json::to_string_pretty(ledger)
+        .map_err(|error| format!("failed to serialize keys file: {error}"))?;
+    contents.push('\n');
+    fs::write(path, contents)
+        .map_err(|error| format!("failed to write keys file {}: {error}", path.display()))
+}
+
+pub(crate) fn format_timestamp(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+/// Midnight UTC of the date in a `ts-YYYY-MM-DD` kid, as generated by the
+/// rotate endpoint when no kid is given.
+fn kid_date(kid: &str) -> Option<DateTime<Utc>> {
+    let date = kid.strip_prefix("ts-")?.get(..10)?;
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .ok()?
+        .and_hms_opt(0, 0, 0)
+        .map(|time| time.and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn at(value: &str) -> DateTime<Utc> {
+        parse_timestamp(value).expect("should parse timestamp")
+    }
+
+    #[test]
+    fn rotation_retires_previous_key_after_overlap() {
+        let mut ledger = KeyLedger::default();
+        ledger.record_rotation(
+            "ts-2026-01-01",
+            None,
+            at("2026-01-01T00:00:00Z"),
+            Duration::days(7),
+        );
+        ledger.record_rotation(
+            "ts-2026-04-01",
+            Some("ts-2026-01-01"),
+            at("2026-04-01T00:00:00Z"),
+            Duration::days(7),
+        );
+
+        let previous = ledger
+            .record("ts-2026-01-01")
+            .expect("should keep previous key");
+        assert_eq!(ledger.status(previous), KeyStatus::Retiring);
+        assert_eq!(ledger.current.as_deref(), Some("ts-2026-04-01"));
+        assert!(
+            ledger
+                .due_for_retirement(at("2026-04-07T23:59:59Z"))
+                .is_empty(),
+            "should keep the previous key during the overlap window"
+        );
+        assert_eq!(
+            ledger.due_for_retirement(at("2026-04-08T00:00:00Z")),
+            vec!["ts-2026-01-01".to_string()]
+        );
+
+        ledger.record_deactivation("ts-2026-01-01", true, at("2026-04-08T00:00:00Z"));
+        let previous = ledger
+            .record("ts-2026-01-01")
+            .expect("should keep deleted key");
+        assert_eq!(ledger.status(previous), KeyStatus::Deleted);
+        assert!(
+            ledger
+                .due_for_retirement(at("2026-05-01T00:00:00Z"))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn current_key_falls_back_to_newest_dated_published_kid() {
+        let ledger = KeyLedger::default();
+        let published = vec![
+            "ts-2026-01-01".to_string(),
+            "partner-key".to_string(),
+            "ts-2026-03-15".to_string(),
+        ];
+
+        assert_eq!(
+            ledger.current_key(&published),
+            Some(("ts-2026-03-15".to_string(), at("2026-03-15T00:00:00Z")))
+        );
+        assert_eq!(ledger.created_at("partner-key"), None);
+    }
+
+    #[test]
+    fn keys_file_round_trips_and_defaults_when_missing() {
+        let temp = TempDir::new().expect("should create temp dir");
+        let path = temp.path().join(DEFAULT_KEYS_FILE);
+        let mut ledger = KeyLedger::default();
+        ledger.record_rotation(
+            "ts-2026-01-01",
+            None,
+            at("2026-01-01T00:00:00Z"),
+            Duration::days(7),
+        );
+
+        assert_eq!(
+            load_ledger(&path).expect("should default a missing keys file"),
+            KeyLedger::default()
+        );
+        save_ledger(&path, &ledger).expect("should save keys file");
+        assert_eq!(
+            load_ledger(&path).expect("should load keys file"),
+            ledger,
+            "should round-trip the ledger"
+        );
+    }
+}
@@ -0,0 +1,996 @@
+mod ledger;
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use trusted_server_core::request_signing::{
+    DeactivateKeyRequest, DeactivateKeyResponse, RotateKeyRequest, RotateKeyResponse,
+    kid_is_creatable,
+};
+use url::Url;
+
+use crate::commands::doctor::checks::{
+    CheckStatus, KidVerification, ProbeFailure, endpoint, fetch_published_keys, verify_kid,
+};
+use crate::commands::doctor::load_signer;
+use crate::commands::keys::ledger::{
+    DEFAULT_KEYS_FILE, KeyLedger, KeyStatus, format_timestamp, load_ledger, save_ledger,
+};
+use crate::error::{CliResult, cli_error};
+use crate::http::{HttpClient, HttpRequest, parse_deployment_url};
+
+const ROTATE_PATH: &str = "/_ts/admin/keys/rotate";
+const DEACTIVATE_PATH: &str = "/_ts/admin/keys/deactivate";
+/// Environment variable holding the password of the `/_ts/admin` basic-auth
+/// handler.
+const ADMIN_PASSWORD_ENV: &str = "TS_ADMIN_PASSWORD";
+
+#[derive(Debug, clap::Subcommand)]
+pub(crate) enum KeysCommand {
+    /// List published and recorded keys with their status and age.
+    List(KeysListArgs),
+    /// Rotate to a new signing key and retire keys whose overlap has ended.
+    Rotate(KeysRotateArgs),
+    /// Deactivate only keys whose overlap window has ended; for running from
+    /// a scheduler between rotations.
+    Retire(KeysRetireArgs),
+    /// Deactivate a key so it is no longer published or verifiable.
+    Deactivate(KeysDeactivateArgs),
+    /// Deactivate a key and delete it from the key stores.
+    Delete(KeysDeactivateArgs),
+    /// Check that every published key resolves on `/verify-signature`.
+    Verify(KeysVerifyArgs),
+}
+
+impl KeysCommand {
+    pub(crate) fn target(&self) -> &KeysTargetArgs {
+        match self {
+            Self::List(args) => &args.target,
+            Self::Rotate(args) => &args.target,
+            Self::Retire(args) => &args.target,
+            Self::Deactivate(args) | Self::Delete(args) => &args.target,
+            Self::Verify(args) => &args.target,
+        }
+    }
+}
+
+#[derive(Debug, clap::Args)]
+pub(crate) struct KeysTargetArgs {
+    /// Public URL of the deployment.
+    pub(crate) url: String,
+    /// Local record of key creation, rotation, and retirement. Commit it next
+    /// to the app config so every operator rotates from the same history.
+    #[arg(long, default_value = DEFAULT_KEYS_FILE)]
+    pub(crate) keys_file: PathBuf,
+    /// Per-request timeout in seconds.
+    #[arg(long, default_value_t = 15)]
+    pub(crate) timeout: u64,
+}
+
+#[derive(Debug, clap::Args)]
+pub(crate) struct AdminArgs {
+    /// Basic-auth user of the `/_ts/admin` handler. The password is read from
+    /// `TS_ADMIN_PASSWORD`.
+    #[arg(long, default_value = "admin")]
+    pub(crate) admin_user: String,
+}
+
+#[derive(Debug, clap::Args)]
+pub(crate) struct KeysListArgs {
+    #[command(flatten)]
+    pub(crate) target: KeysTargetArgs,
+    /// Warn when the current key is older than this many days. Defaults to
+    /// `max_age_days` in the keys file.
+    #[arg(long)]
+    pub(crate) max_age_days: Option<u32>,
+}
+
+#[derive(Debug, clap::Args)]
+pub(crate) struct KeysRotateArgs {
+    #[command(flatten)]
+    pub(crate) target: KeysTargetArgs,
+    #[command(flatten)]
+    pub(crate) admin: AdminArgs,
+    /// Kid for the new key. Defaults to a date-based `ts-YYYY-MM-DD` kid.
+    #[arg(long)]
+    pub(crate) kid: Option<String>,
+    /// Days the previous key stays active so partners can refresh their
+    /// JWKS cache.
+    #[arg(long, default_value_t = 7)]
+    pub(crate) overlap_days: u32,
+    /// Only rotate when the current key is older than the maximum age; for
+    /// running from a scheduler.
+    #[arg(long)]
+    pub(crate) scheduled: bool,
+    /// Rotation age in days for `--scheduled`. Defaults to `max_age_days` in
+    /// the keys file.
+    #[arg(long)]
+    pub(crate) max_age_days: Option<u32>,
+}
+
+#[derive(Debug, clap::Args)]
+pub(crate) struct KeysRetireArgs {
+    #[command(flatten)]
+    pub(crate) target: KeysTargetArgs,
+    #[command(flatten)]
+    pub(crate) admin: AdminArgs,
+}
+
+#[derive(Debug, clap::Args)]
+pub(crate) struct KeysDeactivateArgs {
+    #[command(flatten)]
+    pub(crate) target: KeysTargetArgs,
+    #[command(flatten)]
+    pub(crate) admin: AdminArgs,
+    /// Kid of the key.
+    pub(crate) kid: String,
+}
+
+#[derive(Debug, clap::Args)]
+pub(crate) struct KeysVerifyArgs {
+    #[command(flatten)]
+    pub(crate) target: KeysTargetArgs,
+    /// File holding the standard base64 Ed25519 private key of one published
+    /// kid, used for a signed round trip.
+    #[arg(long, requires = "signing_kid")]
+    pub(crate) signing_key: Option<PathBuf>,
+    /// Key id of `--signing-key`.
+    #[arg(long, requires = "signing_key")]
+    pub(crate) signing_kid: Option<String>,
+}
+
+/// Basic-auth credentials for the `/_ts/admin` routes.
+pub(crate) struct AdminCredentials {
+    pub(crate) user: String,
+    pub(crate) password: String,
+}
+
+impl AdminCredentials {
+    /// Pairs `user` with the password in `TS_ADMIN_PASSWORD`.
+    pub(crate) fn from_env(user: &str) -> CliResult<Self> {
+        match std::env::var(ADMIN_PASSWORD_ENV) {
+            Ok(password) if !password.is_empty() => Ok(Self {
+                user: user.to_string(),
+                password,
+            }),
+            _ => cli_error(format!(
+                "set {ADMIN_PASSWORD_ENV} to the password of the `{user}` admin handler"
+            )),
+        }
+    }
+}
+
+pub(crate) fn run_keys(
+    command: &KeysCommand,
+    client: &dyn HttpClient,
+    now: DateTime<Utc>,
+    out: &mut dyn Write,
+) -> CliResult<()> {
+    match command {
+        KeysCommand::List(args) => list_keys(args, client, now, out),
+        KeysCommand::Rotate(args) => {
+            let credentials = AdminCredentials::from_env(&args.admin.admin_user)?;
+            rotate_keys(args, client, &credentials, now, out)
+        }
+        KeysCommand::Retire(args) => {
+            let credentials = AdminCredentials::from_env(&args.admin.admin_user)?;
+            retire_keys(args, client, &credentials, now, out)
+        }
+        KeysCommand::Deactivate(args) => {
+            let credentials = AdminCredentials::from_env(&args.admin.admin_user)?;
+            deactivate_key(args, false, client, &credentials, now, out)
+        }
+        KeysCommand::Delete(args) => {
+            let credentials = AdminCredentials::from_env(&args.admin.admin_user)?;
+            deactivate_key(args, true, client, &credentials, now, out)
+        }
+        KeysCommand::Verify(args) => verify_keys(args, client, out),
+    }
+}
+
+fn write_error(error: std::io::Error) -> String {
+    format!("failed to write command output: {error}")
+}
+
+fn probe_error(failure: ProbeFailure) -> String {
+    format!("{} ({})", failure.detail, failure.hint)
+}
+
+fn list_keys(
+    args: &KeysListArgs,
+    client: &dyn HttpClient,
+    now: DateTime<Utc>,
+    out: &mut dyn Write,
+) -> CliResult<()> {
+    let base_url = parse_deployment_url(&args.target.url)?;
+    let ledger = load_ledger(&args.target.keys_file)?;
+    let published = fetch_published_keys(client, &base_url)
+        .map_err(probe_error)?
+        .kids;
+    let max_age_days = args.max_age_days.unwrap_or(ledger.max_age_days);
+
+    write_key_table(&ledger, &published, now, out)?;
+    if let Some((kid, created_at)) = ledger.current_key(&published) {
+        let age = age_in_days(created_at, now);
+        if age > i64::from(max_age_days) {
+            writeln!(
+                out,
+                "warning: current key `{kid}` is {age} days old, over the maximum of {max_age_days}; run `ts keys rotate`"
+            )
+            .map_err(write_error)?;
+        }
+    }
+    for kid in ledger.due_for_retirement(now) {
+        writeln!(
+            out,
+            "warning: `{kid}` is past its overlap window; run `ts keys retire` to deactivate it"
+        )
+        .map_err(write_error)?;
+    }
+    Ok(())
+}
+
+fn write_key_table(
+    ledger: &KeyLedger,
+    published: &[String],
+    now: DateTime<Utc>,
+    out: &mut dyn Write,
+) -> CliResult<()> {
+    let untracked = published
+        .iter()
+        .filter(|kid| ledger.record(kid).is_none())
+        .map(|kid| (kid.as_str(), KeyStatus::Untracked));
+    let rows = ledger
+        .keys
+        .iter()
+        .map(|record| (record.kid.as_str(), ledger.status(record)))
+        .chain(untracked)
+        .collect::<Vec<_>>();
+    if rows.is_empty() {
+        writeln!(out, "No request-signing keys found.").map_err(write_error)?;
+        return Ok(());
+    }
+
+    writeln!(
+        out,
+        "{:<24} {:<12} {:<10} {:<11} AGE",
+        "KID", "STATUS", "PUBLISHED", "CREATED"
+    )
+    .map_err(write_error)?;
+    for (kid, status) in rows {
+        let created_at = ledger.created_at(kid);
+        let created = created_at.map_or_else(
+            || "-".to_string(),
+            |time| time.format("%Y-%m-%d").to_string(),
+        );
+        let age = created_at.map_or_else(
+            || "-".to_string(),
+            |time| format!("{}d", age_in_days(time, now)),
+        );
+        let is_published = if published.iter().any(|published| published == kid) {
+            "yes"
+        } else {
+            "no"
+        };
+        writeln!(
+            out,
+            "{kid:<24} {:<12} {is_published:<10} {created:<11} {age}",
+            status.label()
+        )
+        .map_err(write_error)?;
+    }
+    Ok(())
+}
+
+fn age_in_days(created_at: DateTime<Utc>, now: DateTime<Utc>) -> i64 {
+    (now - created_at).num_days()
+}
+
+fn rotate_keys(
+    args: &KeysRotateArgs,
+    client: &dyn HttpClient,
+    credentials: &AdminCredentials,
+    now: DateTime<Utc>,
+    out: &mut dyn Write,
+) -> CliResult<()> {
+    if let Some(kid) = args.kid.as_deref().filter(|kid| !kid_is_creatable(kid)) {
+        return cli_error(format!(
+            "invalid kid `{kid}`: start with a lowercase letter and use only ASCII letters, digits, `-`, `_`, `.`, and `:`"
+        ));
+    }
+    let base_url = parse_deployment_url(&args.target.url)?;
+    let keys_file = &args.target.keys_file;
+    let mut ledger = load_ledger(keys_file)?;
+
+    retire_due_keys(
+        &mut ledger,
+        keys_file,
+        client,
+        &base_url,
+        credentials,
+        now,
+        out,
+    )?;
+
+    if args.scheduled {
+        let max_age_days = args.max_age_days.unwrap_or(ledger.max_age_days);
+        // An unreachable deployment also fails the rotation request below.
+        let published = fetch_published_keys(client, &base_url)
+            .map(|keys| keys.kids)
+            .unwrap_or_default();
+        if let Some((kid, created_at)) = ledger.current_key(&published) {
+            let age = age_in_days(created_at, now);
+            if age < i64::from(max_age_days) {
+                writeln!(
+                    out,
+                    "Current key `{kid}` is {age} days old; rotation is due at {max_age_days} days."
+                )
+                .map_err(write_error)?;
+                return Ok(());
+            }
+        }
+    }
+
+    let request = RotateKeyRequest {
+        kid: args.kid.clone(),
+    };
+    let response: RotateKeyResponse =
+        post_admin(client, &base_url, ROTATE_PATH, credentials, &request)?;
+    if !response.success {
+        return cli_error(format!(
+            "key rotation failed: {}",
+            response.error.unwrap_or(response.message)
+        ));
+    }
+
+    let overlap = Duration::days(i64::from(args.overlap_days));
+    ledger.record_rotation(
+        &response.new_kid,
+        response.previous_kid.as_deref(),
+        now,
+        overlap,
+    );
+    save_ledger(keys_file, &ledger)?;
+
+    writeln!(out, "Rotated to `{}`.", response.new_kid).map_err(write_error)?;
+    if let Some(previous_kid) = &response.previous_kid {
+        writeln!(
+            out,
+            "`{previous_kid}` stays active until {}; run `ts keys retire` after that to deactivate it.",
+            format_timestamp(now + overlap)
+        )
+        .map_err(write_error)?;
+    }
+    writeln!(out, "Active keys: {}", response.active_kids.join(", ")).map_err(write_error)?;
+    Ok(())
+}
+
+fn retire_keys(
+    args: &KeysRetireArgs,
+    client: &dyn HttpClient,
+    credentials: &AdminCredentials,
+    now: DateTime<Utc>,
+    out: &mut dyn Write,
+) -> CliResult<()> {
+    let base_url = parse_deployment_url(&args.target.url)?;
+    let keys_file = &args.target.keys_file;
+    let mut ledger = load_ledger(keys_file)?;
+
+    let retired = retire_due_keys(
+        &mut ledger,
+        keys_file,
+        client,
+        &base_url,
+        credentials,
+        now,
+        out,
+    )?;
+    if retired == 0 {
+        writeln!(out, "No keys are past their overlap window.").map_err(write_error)?;
+    }
+    Ok(())
+}
+
+/// Deactivates every key whose overlap window has ended, saving the keys
+/// file after each so a failure part way leaves it accurate. Returns how many
+/// keys were deactivated.
+fn retire_due_keys(
+    ledger: &mut KeyLedger,
+    keys_file: &Path,
+    client: &dyn HttpClient,
+    base_url: &Url,
+    credentials: &AdminCredentials,
+    now: DateTime<Utc>,
+    out: &mut dyn Write,
+) -> CliResult<usize> {
+    let due = ledger.due_for_retirement(now);
+    for kid in &due {
+        post_deactivate(client, base_url, credentials, kid, false)?;
+        ledger.record_deactivation(kid, false, now);
+        save_ledger(keys_file, ledger)?;
+        writeln!(out, "Deactivated `{kid}` after its overlap window.").map_err(write_error)?;
+    }
+    Ok(due.len())
+}
+
+fn deactivate_key(
+    args: &KeysDeactivateArgs,
+    delete: bool,
+    client: &dyn HttpClient,
+    credentials: &AdminCredentials,
+    now: DateTime<Utc>,
+    out: &mut dyn Write,
+) -> CliResult<()> {
+    let base_url = parse_deployment_url(&args.target.url)?;
+    let mut ledger = load_ledger(&args.target.keys_file)?;
+    let response = post_deactivate(client, &base_url, credentials, &args.kid, delete)?;
+    ledger.record_deactivation(&args.kid, delete, now);
+    save_ledger(&args.target.keys_file, &ledger)?;
+
+    let action = if delete { "Deleted" } else { "Deactivated" };
+    writeln!(out, "{action} `{}`.", response.deactivated_kid).map_err(write_error)?;
+    writeln!(
+        out,
+        "Active keys: {}",
+        response.remaining_active_kids.join(", ")
+    )
+    .map_err(write_error)?;
+    Ok(())
+}
+
+fn post_deactivate(
+    client: &dyn HttpClient,
+    base_url: &Url,
+    credentials: &AdminCredentials,
+    kid: &str,
+    delete: bool,
+) -> CliResult<DeactivateKeyResponse> {
+    let request = DeactivateKeyRequest {
+        kid: kid.to_string(),
+        delete,
+    };
+    let response: DeactivateKeyResponse =
+        post_admin(client, base_url, DEACTIVATE_PATH, credentials, &request)?;
+    if !response.success {
+        return cli_error(format!(
+            "failed to deactivate `{kid}`: {}",
+            response.error.unwrap_or(response.message)
+        ));
+    }
+    Ok(response)
+}
+
+/// Posts `request` to an admin key route and parses its JSON response, which
+/// carries the error detail on failure statuses too.
+fn post_admin<Req: Serialize, Resp: DeserializeOwned>(
+    client: &dyn HttpClient,
+    base_url: &Url,
+    path: &str,
+    credentials: &AdminCredentials,
+    request: &Req,
+) -> CliResult<Resp> {
+    let body = serde_json::to_string(request)
+        .map_err(|error| format!("failed to serialize {path} request: {error}"))?;
+    let response = client.send(
+        &HttpRequest::post_json(endpoint(base_url, path), body)
+            .with_basic_auth(&credentials.user, &credentials.password),
+    )?;
+    match response.status {
+        401 | 403 => cli_error(format!(
+            "{path} rejected the admin credentials for `{}`",
+            credentials.user
+        )),
+        404 => cli_error(format!(
+            "{path} returned HTTP 404; check that a basic-auth handler covers `^/_ts/admin`"
+        )),
+        status => serde_json::from_str(&response.body).map_err(|error| {
+            format!("{path} returned HTTP {status} with an unexpected body: {error}")
+        }),
+    }
+}
+
+fn verify_keys(
+    args: &KeysVerifyArgs,
+    client: &dyn HttpClient,
+    out: &mut dyn Write,
+) -> CliResult<()> {
+    let base_url = parse_deployment_url(&args.target.url)?;
+    let signer = load_signer(args.signing_key.as_deref(), args.signing_kid.as_deref())?;
+    let published = fetch_published_keys(client, &base_url)
+        .map_err(probe_error)?
+        .kids;
+
+    let mut failed = 0;
+    if let Some(signer) = signer
+        .as_ref()
+        .filter(|signer| !published.contains(&signer.kid))
+    {
+        failed += 1;
+        writeln!(
+            out,
+            "{}  {:<24}  not published in the JWKS",
+            CheckStatus::Fail.label(),
+            signer.kid
+        )
+        .map_err(write_error)?;
+    }
+    for kid in &published {
+        let signer = signer.as_ref().filter(|signer| signer.kid == *kid);
+        let (status, detail) = match verify_kid(client, &base_url, kid, signer) {
+            Ok(verification) => describe_verification(verification, signer.is_some()),
+            Err(failure) => (CheckStatus::Fail, probe_error(failure)),
+        };
+        if status == CheckStatus::Fail {
+            failed += 1;
+        }
+        writeln!(out, "{}  {kid:<24}  {detail}", status.label()).map_err(write_error)?;
+    }
+
+    if failed > 0 {
+        return cli_error(format!("{failed} key(s) failed verification"));
+    }
+    Ok(())
+}
+
+fn describe_verification(verification: KidVerification, signed: bool) -> (CheckStatus, String) {
+    match (signed, verification) {
+        (true, KidVerification::Verified) => {
+            (CheckStatus::Pass, "signed round trip verified".to_string())
+        }
+        (true, KidVerification::Rejected) => (
+            CheckStatus::Fail,
+            "signature rejected; the signing key does not match the published key".to_string(),
+        ),
+        (false, KidVerification::Rejected) => (
+            CheckStatus::Pass,
+            "resolves in the key store and rejects forged signatures".to_string(),
+        ),
+        (false, KidVerification::Verified) => {
+            (CheckStatus::Fail, "accepted a forged signature".to_string())
+        }
+        (_, KidVerification::Unresolved) => (
+            CheckStatus::Fail,
+            "published in the JWKS but missing from the key store".to_string(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::path::Path;
+
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::http::HttpResponse;
+
+    /// Serves canned responses keyed by request path and records every
+    /// request sent.
+    #[derive(Default)]
+    struct FakeDeployment {
+        responses: HashMap<&'static str, (u16, String)>,
+        requests: RefCell<Vec<HttpRequest>>,
+    }
+
+    impl FakeDeployment {
+        fn publishing(kids: &[&str]) -> Self {
+            let keys = kids
+                .iter()
+                .map(|kid| {
+                    json!({
+                        "kty": "OKP",
+                        "crv": "Ed25519",
+                        "kid": kid,
+                        "alg": "EdDSA",
+                        "x": "A".repeat(43),
+                    })
+                })
+                .collect::<Vec<_>>();
+            let mut deployment = Self::default();
+            deployment.respond(
+                "/.well-known/trusted-server.json",
+                200,
+                json!({ "version": "1.0", "jwks": { "keys": keys } }),
+            );
+            deployment
+        }
+
+        fn respond(&mut self, path: &'static str, status: u16, body: serde_json::Value) {
+            self.responses.insert(path, (status, body.to_string()));
+        }
+
+        fn posted(&self, path: &str) -> Vec<serde_json::Value> {
+            self.requests
+                .borrow()
+                .iter()
+                .filter(|request| request.url.path() == path)
+                .map(|request| {
+                    serde_json::from_str(request.body.as_deref().unwrap_or_default())
+                        .expect("should post JSON")
+                })
+                .collect()
+        }
+    }
+
+    impl HttpClient for FakeDeployment {
+        fn send(&self, request: &HttpRequest) -> CliResult<HttpResponse> {
+            self.requests.borrow_mut().push(request.clone());
+            let (status, body) = self
+                .responses
+                .get(request.url.path())
+                .cloned()
+                .unwrap_or((404, String::new()));
+            Ok(HttpResponse {
+                status,
+                headers: Vec::new(),
+                body,
+            })
+        }
+    }
+
+    fn at(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value)
+            .expect("should parse timestamp")
+            .with_timezone(&Utc)
+    }
+
+    fn target(keys_file: &Path) -> KeysTargetArgs {
+        KeysTargetArgs {
+            url: "https://publisher.example".to_string(),
+            keys_file: keys_file.to_path_buf(),
+            timeout: 15,
+        }
+    }
+
+    fn credentials() -> AdminCredentials {
+        AdminCredentials {
+            user: "admin".to_string(),
+            password: "secret".to_string(),
+        }
+    }
+
+    fn rotate_args(keys_file: &Path, scheduled: bool) -> KeysRotateArgs {
+        KeysRotateArgs {
+            target: target(keys_file),
+            admin: AdminArgs {
+                admin_user: "admin".to_string(),
+            },
+            kid: None,
+            overlap_days: 7,
+            scheduled,
+            max_age_days: None,
+        }
+    }
+
+    fn rotate_response(new_kid: &str, previous_kid: &str) -> serde_json::Value {
+        json!({
+            "success": true,
+            "message": "Key rotated successfully",
+            "new_kid": new_kid,
+            "previous_kid": previous_kid,
+            "active_kids": [previous_kid, new_kid],
+            "jwk": {},
+        })
+    }
+
+    fn deactivate_response(kid: &str, remaining: &[&str]) -> serde_json::Value {
+        json!({
+            "success": true,
+            "message": "Key deactivated successfully",
+            "deactivated_kid": kid,
+            "deleted": false,
+            "remaining_active_kids": remaining,
+        })
+    }
+
+    fn output(out: Vec<u8>) -> String {
+        String::from_utf8(out).expect("output should be UTF-8")
+    }
+
+    #[test]
+    fn rotate_records_overlap_then_retires_previous_key() {
+        let temp = TempDir::new().expect("should create temp dir");
+        let keys_file = temp.path().join(DEFAULT_KEYS_FILE);
+        let mut deployment = FakeDeployment::publishing(&["ts-2026-01-01"]);
+        deployment.respond(
+            ROTATE_PATH,
+            200,
+            rotate_response("ts-2026-04-01", "ts-2026-01-01"),
+        );
+        let mut out = Vec::new();
+
+        rotate_keys(
+            &rotate_args(&keys_file, false),
+            &deployment,
+            &credentials(),
+            at("2026-04-01T00:00:00Z"),
+            &mut out,
+        )
+        .expect("should rotate");
+
+        let request = deployment.requests.borrow()[0].clone();
+        assert_eq!(
+            request.basic_auth,
+            Some(("admin".to_string(), "secret".to_string())),
+            "should authenticate against the admin handler"
+        );
+        assert!(output(out).contains("`ts-2026-01-01` stays active until 2026-04-08T00:00:00Z"));
+        let ledger = load_ledger(&keys_file).expect("should save keys file");
+        assert_eq!(ledger.current.as_deref(), Some("ts-2026-04-01"));
+        assert!(deployment.posted(DEACTIVATE_PATH).is_empty());
+
+        deployment.respond(
+            ROTATE_PATH,
+            200,
+            rotate_response("ts-2026-04-09", "ts-2026-04-01"),
+        );
+        deployment.respond(
+            DEACTIVATE_PATH,
+            200,
+            deactivate_response("ts-2026-01-01", &["ts-2026-04-01"]),
+        );
+        rotate_keys(
+            &rotate_args(&keys_file, false),
+            &deployment,
+            &credentials(),
+            at("2026-04-09T00:00:00Z"),
+            &mut Vec::new(),
+        )
+        .expect("should rotate again");
+
+        assert_eq!(
+            deployment.posted(DEACTIVATE_PATH),
+            vec![json!({ "kid": "ts-2026-01-01", "delete": false })],
+            "should deactivate the key whose overlap window ended"
+        );
+        let ledger = load_ledger(&keys_file).expect("should load keys file");
+        let retired = ledger
+            .record("ts-2026-01-01")
+            .expect("should keep retired key");
+        assert_eq!(ledger.status(retired), KeyStatus::Deactivated);
+    }
+
+    #[test]
+    fn scheduled_rotate_waits_until_current_key_reaches_max_age() {
+        let temp = TempDir::new().expect("should create temp dir");
+        let keys_file = temp.path().join(DEFAULT_KEYS_FILE);
+        let mut deployment = FakeDeployment::publishing(&["ts-2026-01-01"]);
+        deployment.respond(
+            ROTATE_PATH,
+            200,
+            rotate_response("ts-2026-04-01", "ts-2026-01-01"),
+        );
+        let mut out = Vec::new();
+
+        rotate_keys(
+            &rotate_args(&keys_file, true),
+            &deployment,
+            &credentials(),
+            at("2026-02-01T00:00:00Z"),
+            &mut out,
+        )
+        .expect("should skip rotation");
+        assert!(deployment.posted(ROTATE_PATH).is_empty());
+        assert_eq!(
+            output(out),
+            "Current key `ts-2026-01-01` is 31 days old; rotation is due at 90 days.\n"
+        );
+
+        rotate_keys(
+            &rotate_args(&keys_file, true),
+            &deployment,
+            &credentials(),
+            at("2026-04-01T00:00:00Z"),
+            &mut Vec::new(),
+        )
+        .expect("should rotate the aged key");
+        assert_eq!(deployment.posted(ROTATE_PATH), vec![json!({})]);
+    }
+
+    #[test]
+    fn retire_deactivates_only_keys_past_their_overlap_window() {
+        let temp = TempDir::new().expect("should create temp dir");
+        let keys_file = temp.path().join(DEFAULT_KEYS_FILE);
+        let mut ledger = KeyLedger::default();
+        ledger.record_rotation(
+            "ts-2026-04-01",
+            Some("ts-2026-01-01"),
+            at("2026-04-01T00:00:00Z"),
+            Duration::days(7),
+        );
+        save_ledger(&keys_file, &ledger).expect("should save keys file");
+        let mut deployment = FakeDeployment::publishing(&["ts-2026-01-01", "ts-2026-04-01"]);
+        deployment.respond(
+            DEACTIVATE_PATH,
+            200,
+            deactivate_response("ts-2026-01-01", &["ts-2026-04-01"]),
+        );
+        let args = KeysRetireArgs {
+            target: target(&keys_file),
+            admin: AdminArgs {
+                admin_user: "admin".to_string(),
+            },
+        };
+        let mut out = Vec::new();
+
+        retire_keys(
+            &args,
+            &deployment,
+            &credentials(),
+            at("2026-04-05T00:00:00Z"),
+            &mut out,
+        )
+        .expect("should retire nothing inside the overlap window");
+        assert!(deployment.posted(DEACTIVATE_PATH).is_empty());
+        assert_eq!(output(out), "No keys are past their overlap window.\n");
+
+        retire_keys(
+            &args,
+            &deployment,
+            &credentials(),
+            at("2026-04-08T00:00:00Z"),
+            &mut Vec::new(),
+        )
+        .expect("should retire the previous key");
+        assert_eq!(
+            deployment.posted(DEACTIVATE_PATH),
+            vec![json!({ "kid": "ts-2026-01-01", "delete": false })],
+            "should deactivate only the key whose overlap window ended"
+        );
+        assert!(
+            deployment.posted(ROTATE_PATH).is_empty(),
+            "should not rotate"
+        );
+        let ledger = load_ledger(&keys_file).expect("should load keys file");
+        assert_eq!(ledger.current.as_deref(), Some("ts-2026-04-01"));
+    }
+
+    #[test]
+    fn list_warns_on_keys_past_their_overlap_window() {
+        let temp = TempDir::new().expect("should create temp dir");
+        let keys_file = temp.path().join(DEFAULT_KEYS_FILE);
+        let mut ledger = KeyLedger::default();
+        ledger.record_rotation(
+            "ts-2026-04-01",
+            Some("ts-2026-01-01"),
+            at("2026-04-01T00:00:00Z"),
+            Duration::days(7),
+        );
+        save_ledger(&keys_file, &ledger).expect("should save keys file");
+        let deployment = FakeDeployment::publishing(&["ts-2026-01-01", "ts-2026-04-01"]);
+        let args = KeysListArgs {
+            target: target(&keys_file),
+            max_age_days: None,
+        };
+        let mut out = Vec::new();
+
+        list_keys(&args, &deployment, at("2026-04-10T00:00:00Z"), &mut out)
+            .expect("should list keys");
+
+        assert!(
+            output(out).ends_with(
+                "warning: `ts-2026-01-01` is past its overlap window; run `ts keys retire` to deactivate it\n"
+            ),
+            "should point at `ts keys retire`"
+        );
+    }
+
+    #[test]
+    fn list_shows_status_and_age_and_warns_on_aged_key() {
+        let temp = TempDir::new().expect("should create temp dir");
+        let keys_file = temp.path().join(DEFAULT_KEYS_FILE);
+        let mut ledger = KeyLedger::default();
+        ledger.record_rotation(
+            "ts-2026-01-01",
+            None,
+            at("2026-01-01T00:00:00Z"),
+            Duration::days(7),
+        );
+        save_ledger(&keys_file, &ledger).expect("should save keys file");
+        let deployment = FakeDeployment::publishing(&["ts-2026-01-01", "legacy"]);
+        let args = KeysListArgs {
+            target: target(&keys_file),
+            max_age_days: None,
+        };
+        let mut out = Vec::new();
+
+        list_keys(&args, &deployment, at("2026-05-01T00:00:00Z"), &mut out)
+            .expect("should list keys");
+
+        let out = output(out);
+        let lines = out.lines().collect::<Vec<_>>();
+        assert!(lines[0].starts_with("KID"), "should print a header: {out}");
+        assert!(
+            lines[1].starts_with("ts-2026-01-01")
+                && lines[1].contains("current")
+                && lines[1].ends_with("120d"),
+            "should show the tracked key: {out}"
+        );
+        assert!(
+            lines[2].starts_with("legacy") && lines[2].contains("untracked"),
+            "should show the untracked key: {out}"
+        );
+        assert_eq!(
+            lines[3],
+            "warning: current key `ts-2026-01-01` is 120 days old, over the maximum of 90; run `ts keys rotate`"
+        );
+    }
+
+    #[test]
+    fn deactivate_reports_server_errors() {
+        let temp = TempDir::new().expect("should create temp dir");
+        let keys_file = temp.path().join(DEFAULT_KEYS_FILE);
+        let mut deployment = FakeDeployment::publishing(&["ts-2026-01-01"]);
+        deployment.respond(
+            DEACTIVATE_PATH,
+            500,
+            json!({
+                "success": false,
+                "message": "Key deactivation failed",
+                "deactivated_kid": "ts-2026-01-01",
+                "deleted": false,
+                "remaining_active_kids": [],
+                "error": "cannot deactivate the current signing key",
+            }),
+        );
+        let args = KeysDeactivateArgs {
+            target: target(&keys_file),
+            admin: AdminArgs {
+                admin_user: "admin".to_string(),
+            },
+            kid: "ts-2026-01-01".to_string(),
+        };
+
+        let error = deactivate_key(
+            &args,
+            false,
+            &deployment,
+            &credentials(),
+            at("2026-04-01T00:00:00Z"),
+            &mut Vec::new(),
+        )
+        .expect_err("should fail");
+
+        assert_eq!(
+            error,
+            "failed to deactivate `ts-2026-01-01`: cannot deactivate the current signing key"
+        );
+        assert!(
+            !keys_file.exists(),
+            "should not record a failed deactivation"
+        );
+    }
+
+    #[test]
+    fn verify_fails_for_kids_missing_from_the_key_store() {
+        let temp = TempDir::new().expect("should create temp dir");
+        let mut deployment = FakeDeployment::publishing(&["ts-2026-01-01"]);
+        deployment.respond(
+            "/verify-signature",
+            200,
+            json!({
+                "verified": false,
+                "kid": "ts-2026-01-01",
+                "message": "Verification error",
+                "error": "key not found",
+            }),
+        );
+        let args = KeysVerifyArgs {
+            target: target(&temp.path().join(DEFAULT_KEYS_FILE)),
+            signing_key: None,
+            signing_kid: None,
+        };
+        let mut out = Vec::new();
+
+        let error = verify_keys(&args, &deployment, &mut out).expect_err("should fail");
+
+        assert_eq!(error, "1 key(s) failed verification");
+        assert!(output(out).contains("missing from the key store"));
+    }
+}
@@ -3,3 +3,5 @@ pub(crate) mod config;
 // `dev` is `pub` so the macOS-gated `tests/proxy_e2e.rs` suite can reach
 // `commands::dev::proxy`; the other command modules are crate-internal.
 pub mod dev;
+pub(crate) mod doctor;
+pub(crate) mod keys;
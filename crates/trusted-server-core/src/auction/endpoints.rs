@@ -20,6 +20,7 @@ use crate::ec::log_id;
 use crate::ec::prebid_eids::parse_prebid_eids_cookie;
 use crate::ec::registry::PartnerRegistry;
 use crate::error::TrustedServerError;
+use crate::metrics::record_consent_decision;
 use crate::openrtb::{Eid, Uid};
 use crate::platform::RuntimeServices;
 use crate::settings::Settings;
@@ -186,7 +187,9 @@ pub async fn handle_auction(
     // a no-bid response here prevents outbound PBS/APS calls and the forwarding
     // of request-derived signals (UA/IP/geo, and cookies under some Prebid
     // consent-forwarding modes) for traffic that must not run an auction.
-    if !consent_allows_server_side_auction(&consent_context) {
+    let consent_allows_auction = consent_allows_server_side_auction(&consent_context);
+    record_consent_decision(services.metrics(), "auction", consent_allows_auction);
+    if !consent_allows_auction {
         log::info!(
             "/auction: server-side auction consent gate denied; returning no-bid response without contacting providers"
         );
@@ -205,7 +208,8 @@ pub async fn handle_auction(
             AuctionSource::AuctionApi,
             &auction_request,
             ec_context,
-        );
+        )
+        .with_experiment_arms(&settings.experiment_arms);
         emit_auction_events_best_effort_lazy(services, || {
             build_auction_events(
                 observation,
@@ -301,7 +305,8 @@ pub async fn handle_auction(
         AuctionSource::AuctionApi,
         &auction_request,
         ec_context,
-    );
+    )
+    .with_experiment_arms(&settings.experiment_arms);
 
     // Run the auction
     let result = match orchestrator.run_auction(&auction_request, &context).await {
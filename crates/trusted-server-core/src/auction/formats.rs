@@ -209,6 +209,7 @@ pub fn convert_tsjs_to_auction_request(
                 floor_price: None,
                 targeting: HashMap::new(),
                 bidders,
+                pmp: None,
             });
         }
     }
@@ -278,6 +279,7 @@ pub fn convert_tsjs_to_auction_request(
             page: page_url,
         }),
         context,
+        ad_quality: Default::default(),
     })
 }
 
@@ -464,6 +466,7 @@ pub(crate) fn convert_to_openrtb_response_with_report(
             adm,
             adid: bid.ad_id.clone(),
             crid: bid.creative_id.clone(),
+            dealid: bid.deal_id.clone(),
             w: width,
             h: height,
             adomain: bid.adomain.clone().unwrap_or_default(),
@@ -588,6 +591,7 @@ mod tests {
                 floor_price: None,
                 targeting: HashMap::new(),
                 bidders: HashMap::new(),
+                pmp: None,
             }],
             publisher: PublisherInfo {
                 domain: "publisher.example.com".to_string(),
@@ -601,6 +605,7 @@ mod tests {
             device: None,
             site: None,
             context: HashMap::new(),
+            ad_quality: Default::default(),
         }
     }
 
@@ -629,6 +634,9 @@ mod tests {
             bid_id: Some(format!("{bidder}-{slot_id}")),
             ad_id: None,
             creative_id: Some(format!("{bidder}-creative")),
+            deal_id: None,
+            categories: Vec::new(),
+            attributes: Vec::new(),
             renderer: None,
             cache_id: None,
             cache_host: None,
@@ -13,11 +13,16 @@ use crate::error::TrustedServerError;
 use crate::settings::Settings;
 use std::sync::Arc;
 
+pub mod ad_quality;
+pub mod beacon;
+pub mod bid_cache;
 pub mod config;
 pub mod context;
+pub mod creative_scan;
 pub mod endpoints;
 pub mod formats;
 pub mod orchestrator;
+pub mod price_encryption;
 pub mod provider;
 pub mod telemetry;
 #[cfg(test)]
@@ -51,6 +56,8 @@ fn provider_builders() -> &'static [ProviderBuilder] {
         crate::integrations::prebid::register_auction_provider,
         crate::integrations::aps::register_providers,
         crate::integrations::adserver_mock::register_providers,
+        crate::integrations::gam::register_providers,
+        crate::integrations::openrtb_bidders::register_providers,
     ]
 }
 
@@ -3,15 +3,25 @@
 use edgezero_core::body::Body as EdgeBody;
 use error_stack::{Report, ResultExt};
 use http::Request;
+use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::Duration;
 use web_time::Instant;
 
 use crate::error::TrustedServerError;
+use crate::metrics::record_orchestration;
+use crate::otel::{
+    ATTR_AUCTION_ID, ATTR_PROVIDER, ActiveSpan, SPAN_AUCTION_MEDIATOR, SPAN_AUCTION_PROVIDER,
+    SpanKind,
+};
 use crate::platform::{PlatformPendingRequest, RuntimeServices};
 
+use super::ad_quality::AdQualityRules;
+use super::bid_cache::BidCache;
 use super::config::AuctionConfig;
+use super::creative_scan::CreativeScanner;
+use super::price_encryption::encrypt_winning_prices;
 use super::provider::{AuctionProvider, ProviderParseState, ProviderRequestOutcome};
 use super::telemetry::AbandonedProviderCall;
 use super::types::{AuctionContext, AuctionRequest, AuctionResponse, Bid, BidStatus};
@@ -41,6 +51,8 @@ struct ProviderLaunchState {
     provider: Arc<dyn AuctionProvider>,
     effective_timeout_ms: u32,
     parse_state: Option<ProviderParseState>,
+    /// Open `auction.provider` span, recorded when the state is consumed.
+    span: ActiveSpan,
 }
 
 /// Outcome of attempting to dispatch split-phase auction provider requests.
@@ -75,7 +87,8 @@ impl DispatchedAuction {
         let abandoned = self
             .backend_to_provider
             .into_values()
-            .map(|state| {
+            .map(|mut state| {
+                state.span.set_error("abandoned");
                 AbandonedProviderCall::bidder(
                     state.provider_name,
                     Some(u32::try_from(state.started_at.elapsed().as_millis()).unwrap_or(u32::MAX)),
@@ -182,6 +195,23 @@ fn provider_timeout_response(provider_name: &str, response_time_ms: u64) -> Auct
         .with_metadata("message", serde_json::json!("Provider request timed out"))
 }
 
+/// Drop bids that violate the publisher block lists from every provider response.
+fn enforce_ad_quality(responses: &mut [AuctionResponse], rules: &AdQualityRules) {
+    for response in responses {
+        rules.enforce(response);
+    }
+}
+
+/// Scan the creatives of every provider response when scanning is enabled.
+fn scan_creatives(responses: &mut [AuctionResponse], scanner: Option<&CreativeScanner>) {
+    let Some(scanner) = scanner else {
+        return;
+    };
+    for response in responses {
+        scanner.enforce(response);
+    }
+}
+
 /// Compute the remaining time budget from a deadline.
 ///
 /// Returns the number of milliseconds left before `timeout_ms` is exceeded,
@@ -192,6 +222,21 @@ fn remaining_budget_ms(start: Instant, timeout_ms: u32) -> u32 {
     timeout_ms.saturating_sub(elapsed)
 }
 
+/// Open a client span for one provider or mediator call.
+fn provider_call_span(
+    services: &RuntimeServices,
+    request: &AuctionRequest,
+    provider_name: &str,
+    span_name: &'static str,
+) -> ActiveSpan {
+    let mut span = services
+        .request_trace()
+        .start_span(span_name, SpanKind::Client);
+    span.set_attribute(ATTR_AUCTION_ID, request.id.as_str());
+    span.set_attribute(ATTR_PROVIDER, provider_name);
+    span
+}
+
 fn snapshot_context_request(request: &Request<EdgeBody>) -> Request<EdgeBody> {
     let mut snapshot = Request::new(EdgeBody::empty());
     *snapshot.method_mut() = request.method().clone();
@@ -293,17 +338,26 @@ impl AuctionOrchestrator {
         context: &AuctionContext<'_>,
     ) -> Result<OrchestrationResult, Report<TrustedServerError>> {
         let start_time = Instant::now();
+        let request_with_rules = self.with_ad_quality(request, context.services);
+        let request = request_with_rules.as_ref();
+        let bid_cache = BidCache::new(&self.config.bid_cache, context.services);
+        let cached_bids = match &bid_cache {
+            Some(cache) => cache.load(request).await,
+            None => None,
+        };
 
         // Auto-detect strategy based on mediator configuration
         let (strategy_name, result) = if self.config.has_mediator() {
             (
                 "parallel_mediation",
-                self.run_parallel_mediation(request, context).await?,
+                self.run_parallel_mediation(request, context, cached_bids)
+                    .await?,
             )
         } else {
             (
                 "parallel_only",
-                self.run_parallel_only(request, context).await?,
+                self.run_parallel_only(request, context, cached_bids)
+                    .await?,
             )
         };
 
@@ -312,10 +366,20 @@ impl AuctionOrchestrator {
             strategy_name
         );
 
-        Ok(OrchestrationResult {
+        let mut result = OrchestrationResult {
             total_time_ms: start_time.elapsed().as_millis() as u64,
             ..result
-        })
+        };
+        if let Some(cache) = &bid_cache {
+            cache.queue(request, &result);
+        }
+        encrypt_winning_prices(
+            &mut result.winning_bids,
+            &self.config.price_encryption,
+            context.services,
+        );
+        record_orchestration(context.services.metrics(), &result);
+        Ok(result)
     }
 
     /// Run auction with parallel bidding + mediation.
@@ -324,13 +388,20 @@ impl AuctionOrchestrator {
     /// 1. Run all bidders in parallel
     /// 2. Collect bids from all bidders
     /// 3. Send combined bids to mediator for final decision
+    ///
+    /// `cached_bids` from the bid cache compete alongside the fresh bids.
     async fn run_parallel_mediation(
         &self,
         request: &AuctionRequest,
         context: &AuctionContext<'_>,
+        cached_bids: Option<AuctionResponse>,
     ) -> Result<OrchestrationResult, Report<TrustedServerError>> {
         let mediation_start = Instant::now();
-        let provider_responses = self.run_providers_parallel(request, context).await?;
+        let mut provider_responses = self.run_providers_parallel(request, context).await?;
+        provider_responses.extend(cached_bids);
+        enforce_ad_quality(&mut provider_responses, &request.ad_quality);
+        let scanner = self.creative_scanner(context.services);
+        scan_creatives(&mut provider_responses, scanner.as_ref());
 
         let floor_prices = self.floor_prices_by_slot(request);
         let (mediator_response, winning_bids) = if let Some(mediator_name) = &self.config.mediator {
@@ -372,10 +443,19 @@ impl AuctionOrchestrator {
                 services: context.services,
             };
 
+            let mut mediator_span = provider_call_span(
+                context.services,
+                request,
+                mediator.provider_name(),
+                SPAN_AUCTION_MEDIATOR,
+            );
             let start_time = Instant::now();
-            let mediator_resp = match mediator
-                .request_bids(request, &mediator_context)
-                .await
+            let launch = {
+                let _propagation = mediator_span.propagation_scope();
+                mediator.request_bids(request, &mediator_context).await
+            };
+            let mut mediator_resp = match launch
+                .inspect_err(|_| mediator_span.set_error(ERROR_TYPE_LAUNCH_FAILED))
                 .change_context(TrustedServerError::Auction {
                     message: format!("Mediator {} failed to launch", mediator.provider_name()),
                 })? {
@@ -389,6 +469,7 @@ impl AuctionOrchestrator {
                         .http_client()
                         .wait(pending)
                         .await
+                        .inspect_err(|_| mediator_span.set_error(ERROR_TYPE_TRANSPORT))
                         .change_context(TrustedServerError::Auction {
                             message: format!(
                                 "Mediator {} request failed",
@@ -405,11 +486,18 @@ impl AuctionOrchestrator {
                             parse_state.as_deref(),
                         )
                         .await
+                        .inspect_err(|_| mediator_span.set_error(ERROR_TYPE_PARSE_RESPONSE))
                         .change_context(TrustedServerError::Auction {
                             message: format!("Mediator {} parse failed", mediator.provider_name()),
                         })?
                 }
             };
+            mediator_span.set_attribute("bid_count", mediator_resp.bids.len());
+            mediator_span.end();
+            request.ad_quality.enforce(&mut mediator_resp);
+            if let Some(scanner) = &scanner {
+                scanner.enforce(&mut mediator_resp);
+            }
 
             // Extract only mediator bids with comparable numeric prices.
             let winning = mediator_resp
@@ -453,8 +541,15 @@ impl AuctionOrchestrator {
         &self,
         request: &AuctionRequest,
         context: &AuctionContext<'_>,
+        cached_bids: Option<AuctionResponse>,
     ) -> Result<OrchestrationResult, Report<TrustedServerError>> {
-        let provider_responses = self.run_providers_parallel(request, context).await?;
+        let mut provider_responses = self.run_providers_parallel(request, context).await?;
+        provider_responses.extend(cached_bids);
+        enforce_ad_quality(&mut provider_responses, &request.ad_quality);
+        scan_creatives(
+            &mut provider_responses,
+            self.creative_scanner(context.services).as_ref(),
+        );
         let floor_prices = self.floor_prices_by_slot(request);
         let winning_bids = self.select_winning_bids(&provider_responses, &floor_prices);
 
@@ -578,8 +673,20 @@ impl AuctionOrchestrator {
                 effective_timeout
             );
 
+            let mut span = provider_call_span(
+                context.services,
+                request,
+                provider.provider_name(),
+                SPAN_AUCTION_PROVIDER,
+            );
+            span.set_attribute("timeout_ms", u64::from(effective_timeout));
             let start_time = Instant::now();
-            match provider.request_bids(request, &provider_context).await {
+            // Parent the provider's outbound `traceparent` on this span.
+            let outcome = {
+                let _propagation = span.propagation_scope();
+                provider.request_bids(request, &provider_context).await
+            };
+            match outcome {
                 Ok(ProviderRequestOutcome::Pending {
                     request: pending,
                     parse_state,
@@ -599,6 +706,7 @@ impl AuctionOrchestrator {
                             "Provider '{}' pending request has no backend name; response cannot be correlated",
                             provider.provider_name()
                         );
+                        span.set_error(ERROR_TYPE_LAUNCH_FAILED);
                         responses.push(provider_launch_failed_response(
                             provider.provider_name(),
                             start_time.elapsed().as_millis() as u64,
@@ -611,6 +719,7 @@ impl AuctionOrchestrator {
                             provider.provider_name(),
                             request_backend_name,
                         );
+                        span.set_error(ERROR_TYPE_LAUNCH_FAILED);
                         responses.push(provider_launch_failed_response(
                             provider.provider_name(),
                             start_time.elapsed().as_millis() as u64,
@@ -625,6 +734,7 @@ impl AuctionOrchestrator {
                             provider: Arc::clone(provider),
                             effective_timeout_ms: effective_timeout,
                             parse_state,
+                            span,
                         },
                     );
                     pending_requests.push(pending);
@@ -635,6 +745,7 @@ impl AuctionOrchestrator {
                 }
                 Ok(ProviderRequestOutcome::Immediate(response)) => {
                     immediate_response_count += 1;
+                    span.set_attribute("bid_count", response.bids.len());
                     log::debug!(
                         "Provider '{}' completed without an upstream request",
                         provider.provider_name()
@@ -643,6 +754,7 @@ impl AuctionOrchestrator {
                 }
                 Err(e) => {
                     let response_time_ms = start_time.elapsed().as_millis() as u64;
+                    span.set_error(ERROR_TYPE_LAUNCH_FAILED);
                     log::warn!(
                         "Provider '{}' failed to launch request: {:?}",
                         provider.provider_name(),
@@ -713,7 +825,7 @@ impl AuctionOrchestrator {
                         .unwrap_or_default()
                         .to_string();
 
-                    if let Some(state) = backend_to_provider.remove(&backend_name) {
+                    if let Some(mut state) = backend_to_provider.remove(&backend_name) {
                         let response_time_ms = state.started_at.elapsed().as_millis() as u64;
                         let provider_context = AuctionContext {
                             settings: context.settings,
@@ -735,6 +847,9 @@ impl AuctionOrchestrator {
                             .await
                         {
                             Ok(auction_response) => {
+                                state
+                                    .span
+                                    .set_attribute("bid_count", auction_response.bids.len());
                                 log::info!(
                                     "Provider '{}' returned {} bids (status: {:?}, time: {}ms)",
                                     auction_response.provider,
@@ -745,6 +860,7 @@ impl AuctionOrchestrator {
                                 responses.push(auction_response);
                             }
                             Err(e) => {
+                                state.span.set_error(ERROR_TYPE_PARSE_RESPONSE);
                                 // lgtm[rust/cleartext-logging]
                                 // This warning reports provider parse failures only; no secret values are logged.
                                 log::warn!(
@@ -769,8 +885,9 @@ impl AuctionOrchestrator {
                 }
                 Err(e) => {
                     if let Some(ref backend_name) = failed_backend_name {
-                        if let Some(state) = backend_to_provider.remove(backend_name) {
+                        if let Some(mut state) = backend_to_provider.remove(backend_name) {
                             let response_time_ms = state.started_at.elapsed().as_millis() as u64;
+                            state.span.set_error(ERROR_TYPE_TRANSPORT);
                             log::warn!(
                                 "Provider '{}' request failed: {:?}",
                                 state.provider_name,
@@ -808,8 +925,9 @@ impl AuctionOrchestrator {
             }
         }
 
-        for state in backend_to_provider.into_values() {
+        for mut state in backend_to_provider.into_values() {
             let response_time_ms = state.started_at.elapsed().as_millis() as u64;
+            state.span.set_error(ERROR_TYPE_TIMEOUT);
             log::warn!(
                 "Provider '{}' timed out before auction collection completed",
                 state.provider_name
@@ -911,6 +1029,31 @@ impl AuctionOrchestrator {
         winning_bids
     }
 
+    /// Attach the resolved publisher block lists to the request.
+    ///
+    /// Borrows the request unchanged when no block lists are configured.
+    fn with_ad_quality<'r>(
+        &self,
+        request: &'r AuctionRequest,
+        services: &RuntimeServices,
+    ) -> Cow<'r, AuctionRequest> {
+        if self.config.ad_quality.is_empty() {
+            return Cow::Borrowed(request);
+        }
+        let mut request = request.clone();
+        let resolved = AdQualityRules::resolve(&self.config.ad_quality, services);
+        request.ad_quality = std::mem::take(&mut request.ad_quality).merged(resolved);
+        Cow::Owned(request)
+    }
+
+    /// Resolve the creative scanner, or `None` when scanning is disabled.
+    fn creative_scanner(&self, services: &RuntimeServices) -> Option<CreativeScanner> {
+        self.config
+            .creative_scan
+            .enabled
+            .then(|| CreativeScanner::resolve(&self.config.creative_scan, services))
+    }
+
     fn floor_prices_by_slot(&self, request: &AuctionRequest) -> HashMap<String, f64> {
         request
             .slots
@@ -957,6 +1100,8 @@ impl AuctionOrchestrator {
         if provider_names.is_empty() {
             return DispatchAuctionOutcome::NotStarted;
         }
+        let request_with_rules = self.with_ad_quality(request, context.services);
+        let request = request_with_rules.as_ref();
 
         // Mirror run_providers_parallel: reject multi-provider fan-out before
         // any request launches when the platform executes `send_async` eagerly
@@ -1042,8 +1187,20 @@ impl AuctionOrchestrator {
                 services: context.services,
             };
 
+            let mut span = provider_call_span(
+                context.services,
+                request,
+                provider.provider_name(),
+                SPAN_AUCTION_PROVIDER,
+            );
+            span.set_attribute("timeout_ms", u64::from(effective_timeout));
             let start_time = Instant::now();
-            match provider.request_bids(request, &provider_context).await {
+            // Parent the provider's outbound `traceparent` on this span.
+            let outcome = {
+                let _propagation = span.propagation_scope();
+                provider.request_bids(request, &provider_context).await
+            };
+            match outcome {
                 Ok(ProviderRequestOutcome::Pending {
                     request: pending,
                     parse_state,
@@ -1063,6 +1220,7 @@ impl AuctionOrchestrator {
                             "Provider '{}' pending request has no backend name; response cannot be correlated",
                             provider.provider_name()
                         );
+                        span.set_error(ERROR_TYPE_LAUNCH_FAILED);
                         completed_responses.push(provider_launch_failed_response(
                             provider.provider_name(),
                             start_time.elapsed().as_millis() as u64,
@@ -1075,6 +1233,7 @@ impl AuctionOrchestrator {
                             provider.provider_name(),
                             backend_name,
                         );
+                        span.set_error(ERROR_TYPE_LAUNCH_FAILED);
                         completed_responses.push(provider_launch_failed_response(
                             provider.provider_name(),
                             start_time.elapsed().as_millis() as u64,
@@ -1095,16 +1254,19 @@ impl AuctionOrchestrator {
                             provider: Arc::clone(provider),
                             effective_timeout_ms: effective_timeout,
                             parse_state,
+                            span,
                         },
                     );
                     pending_requests.push(pending.with_backend_name(backend_name));
                 }
                 Ok(ProviderRequestOutcome::Immediate(response)) => {
                     immediate_response_count += 1;
+                    span.set_attribute("bid_count", response.bids.len());
                     completed_responses.push(response);
                 }
                 Err(e) => {
                     let response_time_ms = start_time.elapsed().as_millis() as u64;
+                    span.set_error(ERROR_TYPE_LAUNCH_FAILED);
                     log::warn!(
                         "Provider '{}' failed to dispatch request: {:?}",
                         provider.provider_name(),
@@ -1164,6 +1326,29 @@ impl AuctionOrchestrator {
         dispatched: DispatchedAuction,
         services: &RuntimeServices,
         context: &AuctionContext<'_>,
+    ) -> OrchestrationResult {
+        let bid_cache = BidCache::new(&self.config.bid_cache, services);
+        let cache_request = bid_cache.as_ref().map(|_| dispatched.request.clone());
+        let mut result = self
+            .collect_dispatched_auction_inner(dispatched, services, context)
+            .await;
+        if let (Some(cache), Some(request)) = (&bid_cache, &cache_request) {
+            cache.queue(request, &result);
+        }
+        encrypt_winning_prices(
+            &mut result.winning_bids,
+            &self.config.price_encryption,
+            services,
+        );
+        record_orchestration(services.metrics(), &result);
+        result
+    }
+
+    async fn collect_dispatched_auction_inner(
+        &self,
+        dispatched: DispatchedAuction,
+        services: &RuntimeServices,
+        context: &AuctionContext<'_>,
     ) -> OrchestrationResult {
         let DispatchedAuction {
             pending_requests,
@@ -1212,7 +1397,7 @@ impl AuctionOrchestrator {
             match ready {
                 Ok(platform_response) => {
                     let backend_name = platform_response.backend_name.clone().unwrap_or_default();
-                    if let Some(state) = backend_to_provider.remove(&backend_name) {
+                    if let Some(mut state) = backend_to_provider.remove(&backend_name) {
                         let response_time_ms = state.started_at.elapsed().as_millis() as u64;
                         let provider_context = AuctionContext {
                             settings: context.settings,
@@ -1233,6 +1418,9 @@ impl AuctionOrchestrator {
                             .await
                         {
                             Ok(auction_response) => {
+                                state
+                                    .span
+                                    .set_attribute("bid_count", auction_response.bids.len());
                                 log::info!(
                                     "Provider '{}' returned {} bids ({}ms)",
                                     auction_response.provider,
@@ -1242,6 +1430,7 @@ impl AuctionOrchestrator {
                                 responses.push(auction_response);
                             }
                             Err(e) => {
+                                state.span.set_error(ERROR_TYPE_PARSE_RESPONSE);
                                 log::warn!(
                                     "Provider '{}' parse failed: {:?}",
                                     state.provider_name,
@@ -1267,8 +1456,9 @@ impl AuctionOrchestrator {
                     // the provider behind `failed_backend_name` so it appears in
                     // provider_details instead of vanishing.
                     if let Some(ref backend_name) = failed_backend_name {
-                        if let Some(state) = backend_to_provider.remove(backend_name) {
+                        if let Some(mut state) = backend_to_provider.remove(backend_name) {
                             let response_time_ms = state.started_at.elapsed().as_millis() as u64;
+                            state.span.set_error(ERROR_TYPE_TRANSPORT);
                             log::warn!(
                                 "Provider '{}' request failed: {:?}",
                                 state.provider_name,
@@ -1303,8 +1493,9 @@ impl AuctionOrchestrator {
             // `remaining_budget_ms`.
         }
 
-        for state in backend_to_provider.values() {
+        for state in backend_to_provider.values_mut() {
             let response_time_ms = state.started_at.elapsed().as_millis() as u64;
+            state.span.set_error(ERROR_TYPE_TIMEOUT);
             log::warn!(
                 "Provider '{}' timed out before dispatched auction collection completed",
                 state.provider_name
@@ -1315,6 +1506,12 @@ impl AuctionOrchestrator {
             ));
         }
         backend_to_provider.clear();
+        if let Some(cache) = BidCache::new(&self.config.bid_cache, services) {
+            responses.extend(cache.load(&request).await);
+        }
+        enforce_ad_quality(&mut responses, &request.ad_quality);
+        let scanner = self.creative_scanner(context.services);
+        scan_creatives(&mut responses, scanner.as_ref());
 
         let (mediator_response, winning_bids) = if let Some(mediator_name) = &self.config.mediator {
             match self.providers.get(mediator_name.as_str()) {
@@ -1370,56 +1567,76 @@ impl AuctionOrchestrator {
                         provider_responses: Some(&responses),
                         services: context.services,
                     };
-                    let mediator_response =
-                        match mediator.request_bids(&request, &mediator_context).await {
-                            Ok(ProviderRequestOutcome::Immediate(response)) => Some(response),
-                            Ok(ProviderRequestOutcome::Pending {
-                                request: pending,
-                                parse_state,
-                            }) => match services.http_client().wait(pending).await.change_context(
-                                TrustedServerError::Auction {
-                                    message: format!(
-                                        "Mediator {} request failed",
-                                        mediator.provider_name()
-                                    ),
-                                },
-                            ) {
-                                Ok(platform_resp) => match mediator
-                                    .parse_response_with_context_and_state(
-                                        platform_resp,
-                                        mediator_start.elapsed().as_millis() as u64,
-                                        &request,
-                                        &mediator_context,
-                                        parse_state.as_deref(),
-                                    )
-                                    .await
-                                {
-                                    Ok(response) => Some(response),
-                                    Err(error) => {
-                                        log::warn!(
-                                            "Mediator '{}' parse failed: {:?}",
-                                            mediator.provider_name(),
-                                            error
-                                        );
-                                        None
-                                    }
-                                },
+                    let mut mediator_span = provider_call_span(
+                        services,
+                        &request,
+                        mediator.provider_name(),
+                        SPAN_AUCTION_MEDIATOR,
+                    );
+                    let launch = {
+                        let _propagation = mediator_span.propagation_scope();
+                        mediator.request_bids(&request, &mediator_context).await
+                    };
+                    let mediator_response = match launch {
+                        Ok(ProviderRequestOutcome::Immediate(response)) => Some(response),
+                        Ok(ProviderRequestOutcome::Pending {
+                            request: pending,
+                            parse_state,
+                        }) => match services.http_client().wait(pending).await.change_context(
+                            TrustedServerError::Auction {
+                                message: format!(
+                                    "Mediator {} request failed",
+                                    mediator.provider_name()
+                                ),
+                            },
+                        ) {
+                            Ok(platform_resp) => match mediator
+                                .parse_response_with_context_and_state(
+                                    platform_resp,
+                                    mediator_start.elapsed().as_millis() as u64,
+                                    &request,
+                                    &mediator_context,
+                                    parse_state.as_deref(),
+                                )
+                                .await
+                            {
+                                Ok(response) => Some(response),
                                 Err(error) => {
-                                    log::warn!("Mediator request failed: {:?}", error);
+                                    mediator_span.set_error(ERROR_TYPE_PARSE_RESPONSE);
+                                    log::warn!(
+                                        "Mediator '{}' parse failed: {:?}",
+                                        mediator.provider_name(),
+                                        error
+                                    );
                                     None
                                 }
                             },
                             Err(error) => {
-                                log::warn!(
-                                    "Mediator '{}' failed to dispatch: {:?}",
-                                    mediator.provider_name(),
-                                    error
-                                );
+                                mediator_span.set_error(ERROR_TYPE_TRANSPORT);
+                                log::warn!("Mediator request failed: {:?}", error);
                                 None
                             }
-                        };
+                        },
+                        Err(error) => {
+                            mediator_span.set_error(ERROR_TYPE_LAUNCH_FAILED);
+                            log::warn!(
+                                "Mediator '{}' failed to dispatch: {:?}",
+                                mediator.provider_name(),
+                                error
+                            );
+                            None
+                        }
+                    };
+                    if let Some(response) = mediator_response.as_ref() {
+                        mediator_span.set_attribute("bid_count", response.bids.len());
+                    }
+                    mediator_span.end();
 
-                    if let Some(mediator_response) = mediator_response {
+                    if let Some(mut mediator_response) = mediator_response {
+                        request.ad_quality.enforce(&mut mediator_response);
+                        if let Some(scanner) = &scanner {
+                            scanner.enforce(&mut mediator_response);
+                        }
                         let winning = mediator_response
                             .bids
                             .iter()
@@ -1513,7 +1730,12 @@ mod tests {
     use std::time::Duration;
     use web_time::Instant;
 
+    use crate::auction::ad_quality::AD_QUALITY_DROPPED_METADATA_KEY;
+    use crate::auction::bid_cache::{
+        BID_CACHE_HIT_METADATA_KEY, BID_CACHE_PROVIDER, flush_bid_cache_best_effort,
+    };
     use crate::auction::config::AuctionConfig;
+    use crate::auction::creative_scan::CREATIVE_SCAN_FINDINGS_METADATA_KEY;
     use crate::auction::orchestrator::DispatchAuctionOutcome;
     use crate::auction::provider::{AuctionProvider, ProviderRequestOutcome};
     use crate::auction::test_support::create_test_auction_context;
@@ -1521,9 +1743,12 @@ mod tests {
         AdFormat, AdSlot, ApsRendererV1, ApsTagType, AuctionContext, AuctionRequest,
         AuctionResponse, Bid, BidRenderer, BidStatus, MediaType, PublisherInfo, UserInfo,
     };
+    use crate::auction_config_types::{
+        AdQualityConfig, BidCacheConfig, CreativeScanAction, CreativeScanConfig,
+    };
     use crate::error::TrustedServerError;
     use crate::platform::test_support::{
-        StubHttpClient, build_services_with_backend_and_http_client,
+        InMemoryKvStore, StubHttpClient, build_services_with_backend_and_http_client,
         build_services_with_http_client, noop_services,
     };
     use crate::platform::{
@@ -1535,7 +1760,7 @@ mod tests {
     use std::collections::{HashMap, HashSet};
     use std::sync::{Arc, Mutex};
 
-    use super::AuctionOrchestrator;
+    use super::{AuctionOrchestrator, enforce_ad_quality};
 
     // ---------------------------------------------------------------------------
     // Minimal test double for AuctionProvider
@@ -1817,6 +2042,9 @@ mod tests {
             bid_id: (bidder == "aps").then(|| "aps-selected-bid".to_string()),
             ad_id: None,
             creative_id: None,
+            deal_id: None,
+            categories: Vec::new(),
+            attributes: Vec::new(),
             renderer,
             cache_id: None,
             cache_host: None,
@@ -1840,6 +2068,9 @@ mod tests {
             bid_id: None,
             ad_id: Some("creative-123".to_string()),
             creative_id: None,
+            deal_id: None,
+            categories: Vec::new(),
+            attributes: Vec::new(),
             renderer: None,
             cache_id: Some("cache-abc".to_string()),
             cache_host: None,
@@ -2090,6 +2321,7 @@ mod tests {
                     floor_price: Some(1.50),
                     targeting: HashMap::new(),
                     bidders: HashMap::new(),
+                    pmp: None,
                 },
                 AdSlot {
                     id: "sidebar".to_string(),
@@ -2101,6 +2333,7 @@ mod tests {
                     floor_price: Some(1.00),
                     targeting: HashMap::new(),
                     bidders: HashMap::new(),
+                    pmp: None,
                 },
             ],
             publisher: PublisherInfo {
@@ -2115,6 +2348,7 @@ mod tests {
             device: None,
             site: None,
             context: HashMap::new(),
+            ad_quality: Default::default(),
         }
     }
 
@@ -2155,6 +2389,41 @@ mod tests {
         }
     }
 
+    struct ImmediateBidsProvider {
+        bids: Vec<Bid>,
+    }
+
+    #[async_trait::async_trait(?Send)]
+    impl AuctionProvider for ImmediateBidsProvider {
+        fn provider_name(&self) -> &'static str {
+            "bidder"
+        }
+
+        async fn request_bids(
+            &self,
+            _request: &AuctionRequest,
+            _context: &AuctionContext<'_>,
+        ) -> Result<ProviderRequestOutcome, Report<TrustedServerError>> {
+            Ok(ProviderRequestOutcome::Immediate(AuctionResponse::success(
+                self.provider_name(),
+                self.bids.clone(),
+                0,
+            )))
+        }
+
+        async fn parse_response(
+            &self,
+            _response: PlatformResponse,
+            _response_time_ms: u64,
+        ) -> Result<AuctionResponse, Report<TrustedServerError>> {
+            panic!("immediate response should not be parsed");
+        }
+
+        fn timeout_ms(&self) -> u32 {
+            2000
+        }
+    }
+
     struct LaunchFailingProvider;
 
     #[async_trait::async_trait(?Send)]
@@ -2431,6 +2700,9 @@ mod tests {
                 bid_id: None,
                 ad_id: None,
                 creative_id: None,
+                deal_id: None,
+                categories: Vec::new(),
+                attributes: Vec::new(),
                 renderer: None,
                 cache_id: None,
                 cache_host: None,
@@ -2454,6 +2726,9 @@ mod tests {
                 bid_id: None,
                 ad_id: None,
                 creative_id: None,
+                deal_id: None,
+                categories: Vec::new(),
+                attributes: Vec::new(),
                 renderer: None,
                 cache_id: None,
                 cache_host: None,
@@ -2497,6 +2772,10 @@ mod tests {
                 timeout_ms: 2000,
                 creative_store: "creative_store".to_string(),
                 allowed_context_keys: HashSet::from(["permutive_segments".to_string()]),
+                ad_quality: Default::default(),
+                price_encryption: Default::default(),
+                creative_scan: Default::default(),
+                bid_cache: Default::default(),
             };
 
             let orchestrator = AuctionOrchestrator::new(config);
@@ -2518,6 +2797,90 @@ mod tests {
         });
     }
 
+    #[test]
+    fn unused_bids_compete_in_the_next_auction_for_the_same_user() {
+        futures::executor::block_on(async {
+            let services = noop_services().with_bid_cache_store(Arc::new(InMemoryKvStore::new()));
+            let config = AuctionConfig {
+                enabled: true,
+                providers: vec!["bidder".to_string()],
+                timeout_ms: 2000,
+                bid_cache: BidCacheConfig {
+                    enabled: true,
+                    kv_store: Some("bid_cache".to_string()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            let bid = |creative_id: &str, price: f64| {
+                let mut bid = auction_bid("seat", price);
+                bid.slot_id = "header-banner".to_string();
+                bid.creative_id = Some(creative_id.to_string());
+                bid
+            };
+            let request = create_test_auction_request();
+            let settings = create_test_settings();
+            let req = http::Request::builder()
+                .method(http::Method::GET)
+                .uri("https://test.com/test")
+                .body(edgezero_core::body::Body::empty())
+                .expect("should build request");
+            let context = AuctionContext {
+                settings: &settings,
+                request: &req,
+                timeout_ms: 2000,
+                provider_responses: None,
+                services: &services,
+            };
+
+            let mut first = AuctionOrchestrator::new(config.clone());
+            first.register_provider(Arc::new(ImmediateBidsProvider {
+                bids: vec![bid("crid-a", 5.0), bid("crid-b", 3.0)],
+            }));
+            first
+                .run_auction(&request, &context)
+                .await
+                .expect("first auction should complete");
+            flush_bid_cache_best_effort(&config.bid_cache, &services).await;
+
+            let mut refresh = AuctionOrchestrator::new(config);
+            refresh.register_provider(Arc::new(ImmediateBidsProvider {
+                bids: vec![bid("crid-c", 2.0)],
+            }));
+            let result = refresh
+                .run_auction(&request, &context)
+                .await
+                .expect("refresh auction should complete");
+
+            let winner = result
+                .get_winning_bid("header-banner")
+                .expect("should select a winner");
+            assert_eq!(
+                winner.creative_id.as_deref(),
+                Some("crid-b"),
+                "should let the cached runner-up beat the lower fresh bid"
+            );
+            assert!(
+                winner.metadata.contains_key(BID_CACHE_HIT_METADATA_KEY),
+                "should mark the winner as a cache hit"
+            );
+            assert!(
+                result
+                    .get_all_bids_for_slot("header-banner")
+                    .iter()
+                    .all(|bid| bid.creative_id.as_deref() != Some("crid-a")),
+                "should not offer the creative already served to this user"
+            );
+            assert!(
+                result
+                    .provider_responses
+                    .iter()
+                    .any(|response| response.provider == BID_CACHE_PROVIDER),
+                "should report cached bids under the bid cache provider"
+            );
+        });
+    }
+
     #[test]
     fn provider_launch_failures_error_when_no_requests_launch() {
         futures::executor::block_on(async {
@@ -3291,6 +3654,87 @@ mod tests {
         );
     }
 
+    #[test]
+    fn ad_quality_violations_are_dropped_before_winner_selection() {
+        let orchestrator = AuctionOrchestrator::new(AuctionConfig {
+            ad_quality: AdQualityConfig {
+                badv: vec!["bad.example".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        let request = create_test_auction_request();
+        let request = orchestrator.with_ad_quality(&request, &noop_services());
+        assert_eq!(
+            request.ad_quality.badv,
+            vec!["bad.example".to_string()],
+            "should attach configured block lists to the request"
+        );
+
+        let mut blocked = auction_bid("blocked", 5.0);
+        blocked.adomain = Some(vec!["cdn.bad.example".to_string()]);
+        let mut responses = vec![
+            AuctionResponse::success("blocked", vec![blocked], 1),
+            AuctionResponse::success("clean", vec![auction_bid("clean", 1.0)], 1),
+        ];
+        enforce_ad_quality(&mut responses, &request.ad_quality);
+
+        let winners = orchestrator.select_winning_bids(&responses, &HashMap::new());
+        assert_eq!(
+            winners
+                .get("slot-1")
+                .expect("should select the compliant bid")
+                .bidder,
+            "clean",
+            "should not let a blocked advertiser win on price"
+        );
+        assert!(
+            responses[0]
+                .metadata
+                .contains_key(AD_QUALITY_DROPPED_METADATA_KEY),
+            "should record the drop on the offending response"
+        );
+    }
+
+    #[test]
+    fn rejected_creatives_fall_back_to_the_next_highest_bid() {
+        let orchestrator = AuctionOrchestrator::new(AuctionConfig {
+            creative_scan: CreativeScanConfig {
+                enabled: true,
+                default_action: CreativeScanAction::Reject,
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        let scanner = orchestrator.creative_scanner(&noop_services());
+        assert!(scanner.is_some(), "should resolve a scanner when enabled");
+
+        let mut redirecting = auction_bid("redirecting", 5.0);
+        redirecting.creative =
+            Some("<script>window.top.location = 'https://x.example';</script>".to_string());
+        let mut responses = vec![
+            AuctionResponse::success("redirecting", vec![redirecting], 1),
+            AuctionResponse::success("clean", vec![auction_bid("clean", 1.0)], 1),
+        ];
+        scan_creatives(&mut responses, scanner.as_ref());
+
+        let winners = orchestrator.select_winning_bids(&responses, &HashMap::new());
+        assert_eq!(
+            winners
+                .get("slot-1")
+                .expect("should select the clean bid")
+                .bidder,
+            "clean",
+            "should not let a redirecting creative win on price"
+        );
+        assert!(
+            responses[0]
+                .metadata
+                .contains_key(CREATIVE_SCAN_FINDINGS_METADATA_KEY),
+            "should record the findings on the offending response"
+        );
+    }
+
     #[test]
     fn test_apply_floor_prices_drops_bids_without_price() {
         // Price-less bids cannot be compared or delivered and remain fail-closed.
@@ -3315,6 +3759,9 @@ mod tests {
                 bid_id: None,
                 ad_id: None,
                 creative_id: None,
+                deal_id: None,
+                categories: Vec::new(),
+                attributes: Vec::new(),
                 renderer: None,
                 cache_id: None,
                 cache_host: None,
@@ -3359,6 +3806,9 @@ mod tests {
                 bid_id: None,
                 ad_id: None,
                 creative_id: None,
+                deal_id: None,
+                categories: Vec::new(),
+                attributes: Vec::new(),
                 renderer: None,
                 cache_id: None,
                 cache_host: None,
@@ -3398,6 +3848,9 @@ mod tests {
                 bid_id: None,
                 ad_id: None,
                 creative_id: None,
+                deal_id: None,
+                categories: Vec::new(),
+                attributes: Vec::new(),
                 renderer: None,
                 cache_id: None,
                 cache_host: None,
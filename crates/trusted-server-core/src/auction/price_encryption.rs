@@ -0,0 +1,391 @@
+//! Encrypted `${AUCTION_PRICE:B64}` win-price macros.
+//!
+//! Some DSPs only accept the clearing price in encrypted form. They issue each
+//! publisher an encryption key and an integrity key, and expect the standard
+//! `OpenRTB` encrypted price scheme:
+//!
+//! ```text
+//! pad       = HMAC-SHA1(encryption_key, iv)[0..8]
+//! enc_price = price_micros XOR pad
+//! signature = HMAC-SHA1(integrity_key, price_micros || iv)[0..4]
+//! token     = web_safe_base64(iv || enc_price || signature)
+//! ```
+//!
+//! `iv` is 16 random bytes and `price_micros` is the CPM in micros as a
+//! big-endian `u64`. The orchestrator expands the macro in each winning bid's
+//! `nurl`, `burl` and creative once the final price is known; see
+//! [`encrypt_winning_prices`].
+
+use std::collections::{BTreeMap, HashMap};
+
+use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
+use error_stack::{Report, ResultExt};
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+use subtle::ConstantTimeEq;
+
+use crate::auction_config_types::PriceEncryptionKeyConfig;
+use crate::creative::{ENCRYPTED_AUCTION_PRICE_MACRO, expand_encrypted_auction_price_macro};
+use crate::error::TrustedServerError;
+use crate::platform::{RuntimeServices, StoreName};
+
+use super::types::Bid;
+
+type HmacSha1 = Hmac<Sha1>;
+
+const IV_LEN: usize = 16;
+const PRICE_LEN: usize = 8;
+const SIGNATURE_LEN: usize = 4;
+const TOKEN_LEN: usize = IV_LEN + PRICE_LEN + SIGNATURE_LEN;
+
+/// One bidder's price encryption and integrity keys.
+#[derive(Clone)]
+pub struct PriceEncryptionKeys {
+    encryption_key: Vec<u8>,
+    integrity_key: Vec<u8>,
+}
+
+impl std::fmt::Debug for PriceEncryptionKeys {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PriceEncryptionKeys")
+            .finish_non_exhaustive()
+    }
+}
+
+impl PriceEncryptionKeys {
+    /// Build keys from raw key bytes.
+    #[must_use]
+    pub fn new(encryption_key: Vec<u8>, integrity_key: Vec<u8>) -> Self {
+        Self {
+            encryption_key,
+            integrity_key,
+        }
+    }
+
+    /// Load a bidder's keys from the Secret Store.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TrustedServerError::Auction`] when a secret cannot be read or
+    /// is not web-safe base64.
+    pub fn load(
+        config: &PriceEncryptionKeyConfig,
+        services: &RuntimeServices,
+    ) -> Result<Self, Report<TrustedServerError>> {
+        let store = StoreName::from(config.secret_store.as_str());
+        let read = |name: &str| -> Result<Vec<u8>, Report<TrustedServerError>> {
+            let encoded = services
+                .secret_store()
+                .get_string(&store, name)
+                .change_context(TrustedServerError::Auction {
+                    message: format!("failed to read price encryption key '{name}'"),
+                })?;
+            decode_key(&encoded).ok_or_else(|| {
+                Report::new(TrustedServerError::Auction {
+                    message: format!("price encryption key '{name}' is not web-safe base64"),
+                })
+            })
+        };
+        Ok(Self::new(
+            read(&config.encryption_key)?,
+            read(&config.integrity_key)?,
+        ))
+    }
+
+    /// Encrypt a CPM with a fresh random initialization vector.
+    ///
+    /// Returns `None` for negative or non-finite prices, which have no micros
+    /// representation.
+    #[must_use]
+    pub fn encrypt_cpm(&self, cpm: f64) -> Option<String> {
+        if !cpm.is_finite() || cpm < 0.0 {
+            return None;
+        }
+        // Saturating float-to-int cast; CPMs are far below u64::MAX micros.
+        let micros = (cpm * 1_000_000.0).round() as u64;
+        Some(self.encrypt_micros(micros, rand::random()))
+    }
+
+    /// Encrypt a price in micros with the given initialization vector.
+    #[must_use]
+    pub fn encrypt_micros(&self, micros: u64, iv: [u8; IV_LEN]) -> String {
+        let price = micros.to_be_bytes();
+        let pad = hmac_sha1(&self.encryption_key, &[&iv]);
+        let mut token = Vec::with_capacity(TOKEN_LEN);
+        token.extend_from_slice(&iv);
+        token.extend(price.iter().zip(pad.iter()).map(|(byte, pad)| byte ^ pad));
+        token.extend_from_slice(&hmac_sha1(&self.integrity_key, &[&price, &iv])[..SIGNATURE_LEN]);
+        URL_SAFE_NO_PAD.encode(token)
+    }
+
+    /// Decrypt a token and verify its integrity signature.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TrustedServerError::Auction`] when the token is malformed or
+    /// its signature does not match.
+    pub fn decrypt_micros(&self, token: &str) -> Result<u64, Report<TrustedServerError>> {
+        let invalid = |message: &str| {
+            Report::new(TrustedServerError::Auction {
+                message: format!("invalid encrypted price: {message}"),
+            })
+        };
+        let bytes = URL_SAFE_NO_PAD
+            .decode(token.trim_end_matches('='))
+            .map_err(|_| invalid("not web-safe base64"))?;
+        if bytes.len() != TOKEN_LEN {
+            return Err(invalid("unexpected length"));
+        }
+        let (iv, rest) = bytes.split_at(IV_LEN);
+        let (encrypted, signature) = rest.split_at(PRICE_LEN);
+        let pad = hmac_sha1(&self.encryption_key, &[iv]);
+        let mut price = [0u8; PRICE_LEN];
+        for ((byte, encrypted), pad) in price.iter_mut().zip(encrypted).zip(pad) {
+            *byte = encrypted ^ pad;
+        }
+        let expected = hmac_sha1(&self.integrity_key, &[&price, iv]);
+        if !bool::from(expected[..SIGNATURE_LEN].ct_eq(signature)) {
+            return Err(invalid("integrity check failed"));
+        }
+        Ok(u64::from_be_bytes(price))
+    }
+}
+
+/// Expand `${AUCTION_PRICE:B64}` in winning bids whose bidder has keys.
+///
+/// Each bid gets one token, shared by its `nurl`, `burl` and creative. Bids
+/// from bidders without configured keys keep the macro, as before. A key
+/// that cannot be loaded is logged and its bids also keep the macro: the DSP
+/// then rejects the notification instead of receiving a wrong price.
+pub fn encrypt_winning_prices(
+    winning_bids: &mut HashMap<String, Bid>,
+    config: &BTreeMap<String, PriceEncryptionKeyConfig>,
+    services: &RuntimeServices,
+) {
+    if config.is_empty() {
+        return;
+    }
+    let mut loaded: HashMap<&str, Option<PriceEncryptionKeys>> = HashMap::new();
+    for bid in winning_bids.values_mut() {
+        if !carries_encrypted_macro(bid) {
+            continue;
+        }
+        let Some((bidder, key_config)) = config.get_key_value(bid.bidder.as_str()) else {
+            log::debug!(
+                "price encryption: no keys for bidder '{}', leaving {ENCRYPTED_AUCTION_PRICE_MACRO} unexpanded",
+                bid.bidder
+            );
+            continue;
+        };
+        let keys = loaded.entry(bidder.as_str()).or_insert_with(|| {
+            PriceEncryptionKeys::load(key_config, services)
+                .inspect_err(|err| {
+                    log::warn!(
+                        "price encryption: failed to load keys for bidder '{bidder}': {err:?}"
+                    );
+                })
+                .ok()
+        });
+        let Some(token) = keys
+            .as_ref()
+            .zip(bid.price)
+            .and_then(|(keys, cpm)| keys.encrypt_cpm(cpm))
+        else {
+            continue;
+        };
+        for field in [&mut bid.nurl, &mut bid.burl, &mut bid.creative] {
+            if let Some(value) = field.as_mut() {
+                *value = expand_encrypted_auction_price_macro(value, &token);
+            }
+        }
+    }
+}
+
+fn carries_encrypted_macro(bid: &Bid) -> bool {
+    [&bid.nurl, &bid.burl, &bid.creative]
+        .into_iter()
+        .flatten()
+        .any(|value| value.contains(ENCRYPTED_AUCTION_PRICE_MACRO))
+}
+
+fn decode_key(encoded: &str) -> Option<Vec<u8>> {
+    let normalized = encoded
+        .trim()
+        .trim_end_matches('=')
+        .replace('+', "-")
+        .replace('/', "_");
+    URL_SAFE_NO_PAD
+        .decode(normalized)
+        .ok()
+        .filter(|key| !key.is_empty())
+}
+
+fn hmac_sha1(key: &[u8], parts: &[&[u8]]) -> [u8; 20] {
+    let mut mac = HmacSha1::new_from_slice(key).expect("should create HMAC from arbitrary key");
+    for part in parts {
+        mac.update(part);
+    }
+    mac.finalize().into_bytes().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::test_support::{
+        HashMapSecretStore, StubHttpClient, build_services_with_secret_and_http_client,
+    };
+    use std::sync::Arc;
+
+    const ENCRYPTION_KEY: &str = "skU7Ax_NL5pPAFyKdkfZjZz2-VhIN8bjj1rVFOaJ_5o=";
+    const INTEGRITY_KEY: &str = "arO23ykdNqUQ5LEoQ0FVmPkBd7xB5CO89PDZlSjpFxo=";
+    const IV: [u8; IV_LEN] = [
+        0x38, 0x6e, 0x3a, 0xc0, 0x00, 0x0c, 0x0a, 0x08, 0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd,
+        0xef,
+    ];
+
+    fn keys() -> PriceEncryptionKeys {
+        PriceEncryptionKeys::new(
+            decode_key(ENCRYPTION_KEY).expect("should decode encryption key"),
+            decode_key(INTEGRITY_KEY).expect("should decode integrity key"),
+        )
+    }
+
+    fn key_config() -> PriceEncryptionKeyConfig {
+        PriceEncryptionKeyConfig {
+            secret_store: "price_keys".to_string(),
+            encryption_key: "dsp-ekey".to_string(),
+            integrity_key: "dsp-ikey".to_string(),
+        }
+    }
+
+    fn winning_bid(bidder: &str) -> Bid {
+        Bid {
+            slot_id: "atf".to_string(),
+            price: Some(1.25),
+            currency: "USD".to_string(),
+            creative: Some(
+                "<img src=\"https://dsp.example/imp?p=${AUCTION_PRICE:B64}\">".to_string(),
+            ),
+            adomain: None,
+            bidder: bidder.to_string(),
+            width: 300,
+            height: 250,
+            nurl: Some("https://dsp.example/win?p=${AUCTION_PRICE:B64}".to_string()),
+            burl: Some("https://dsp.example/bill?p=${AUCTION_PRICE}".to_string()),
+            bid_id: None,
+            ad_id: None,
+            creative_id: None,
+            deal_id: None,
+            categories: Vec::new(),
+            attributes: Vec::new(),
+            renderer: None,
+            cache_id: None,
+            cache_host: None,
+            cache_path: None,
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn encrypt_micros_matches_reference_vectors() {
+        let keys = keys();
+        for (micros, expected) in [
+            (100, "OG46wAAMCggBI0VniavN7860zmbksILKzhZUCA"),
+            (1900, "OG46wAAMCggBI0VniavN7860zmbksIXCYRLn9w"),
+            (2700, "OG46wAAMCggBI0VniavN7860zmbksIgiHq6-sw"),
+        ] {
+            assert_eq!(
+                keys.encrypt_micros(micros, IV),
+                expected,
+                "should match the reference token for {micros} micros"
+            );
+        }
+    }
+
+    #[test]
+    fn encrypt_cpm_round_trips_and_rejects_tampering() {
+        let keys = keys();
+        let token = keys.encrypt_cpm(1.25).expect("should encrypt a valid CPM");
+
+        assert_eq!(
+            keys.decrypt_micros(&token)
+                .expect("should decrypt its own token"),
+            1_250_000
+        );
+
+        // Character 24 encodes bits of the encrypted price, after the IV.
+        let mut tampered = token.into_bytes();
+        tampered[24] = if tampered[24] == b'A' { b'B' } else { b'A' };
+        let tampered = String::from_utf8(tampered).expect("should stay ASCII");
+        assert!(
+            keys.decrypt_micros(&tampered).is_err(),
+            "should fail the integrity check for a modified price"
+        );
+        assert!(keys.encrypt_cpm(f64::NAN).is_none(), "should reject NaN");
+    }
+
+    #[test]
+    fn encrypt_winning_prices_expands_only_configured_bidders() {
+        let services = build_services_with_secret_and_http_client(
+            HashMapSecretStore::new(HashMap::from([
+                ("dsp-ekey".to_string(), ENCRYPTION_KEY.as_bytes().to_vec()),
+                ("dsp-ikey".to_string(), INTEGRITY_KEY.as_bytes().to_vec()),
+            ])),
+            Arc::new(StubHttpClient::new()),
+        );
+        let config = BTreeMap::from([("dsp".to_string(), key_config())]);
+        let mut winning_bids = HashMap::from([
+            ("atf".to_string(), winning_bid("dsp")),
+            ("btf".to_string(), winning_bid("other")),
+        ]);
+
+        encrypt_winning_prices(&mut winning_bids, &config, &services);
+
+        let encrypted = &winning_bids["atf"];
+        let nurl = encrypted.nurl.as_deref().expect("should keep nurl");
+        let token = nurl
+            .strip_prefix("https://dsp.example/win?p=")
+            .expect("should keep the nurl prefix");
+        assert_eq!(
+            keys()
+                .decrypt_micros(token)
+                .expect("should decrypt nurl price"),
+            1_250_000
+        );
+        assert!(
+            encrypted
+                .creative
+                .as_deref()
+                .is_some_and(|adm| adm.contains(token)),
+            "should use the same token in the creative"
+        );
+        assert_eq!(
+            encrypted.burl.as_deref(),
+            Some("https://dsp.example/bill?p=${AUCTION_PRICE}"),
+            "should leave the clear-price macro for render-time expansion"
+        );
+        assert_eq!(
+            winning_bids["btf"].nurl.as_deref(),
+            Some("https://dsp.example/win?p=${AUCTION_PRICE:B64}"),
+            "should leave bidders without keys untouched"
+        );
+    }
+
+    #[test]
+    fn encrypt_winning_prices_leaves_macro_when_keys_are_missing() {
+        let services = build_services_with_secret_and_http_client(
+            HashMapSecretStore::new(HashMap::new()),
+            Arc::new(StubHttpClient::new()),
+        );
+        let config = BTreeMap::from([("dsp".to_string(), key_config())]);
+        let mut winning_bids = HashMap::from([("atf".to_string(), winning_bid("dsp"))]);
+
+        encrypt_winning_prices(&mut winning_bids, &config, &services);
+
+        assert_eq!(
+            winning_bids["atf"].nurl.as_deref(),
+            Some("https://dsp.example/win?p=${AUCTION_PRICE:B64}"),
+            "should not expand without readable keys"
+        );
+    }
+}
@@ -11,10 +11,13 @@ use error_stack::Report;
 use serde::Serialize;
 use uuid::Uuid;
 
+use crate::auction::bid_cache::BID_CACHE_HIT_METADATA_KEY;
+use crate::auction::creative_scan::{CREATIVE_SCAN_FINDINGS_METADATA_KEY, ScanFindingRecord};
 use crate::auction::orchestrator::OrchestrationResult;
 use crate::auction::types::{AuctionRequest, AuctionResponse, Bid, BidStatus, MediaType};
 use crate::ec::EcContext;
 use crate::error::TrustedServerError;
+use crate::experiments::ExperimentArms;
 use crate::platform::RuntimeServices;
 
 const MAX_PAGE_PATH_BYTES: usize = 256;
@@ -29,14 +32,28 @@ pub enum AuctionSource {
     SpaNavigation,
     /// Explicit `POST /auction` API.
     AuctionApi,
+    /// Lazy-load or refresh auction through `POST /_ts/api/v1/slot-auction`.
+    SlotAuction,
 }
 
 impl AuctionSource {
-    fn as_str(self) -> &'static str {
+    pub(crate) fn as_str(self) -> &'static str {
         match self {
             Self::InitialNavigation => "initial_navigation",
             Self::SpaNavigation => "spa_navigation",
             Self::AuctionApi => "auction_api",
+            Self::SlotAuction => "slot_auction",
+        }
+    }
+
+    /// Parse a label produced by [`Self::as_str`].
+    pub(crate) fn from_label(label: &str) -> Option<Self> {
+        match label {
+            "initial_navigation" => Some(Self::InitialNavigation),
+            "spa_navigation" => Some(Self::SpaNavigation),
+            "auction_api" => Some(Self::AuctionApi),
+            "slot_auction" => Some(Self::SlotAuction),
+            _ => None,
         }
     }
 }
@@ -116,6 +133,8 @@ pub struct AuctionObservationContext {
     pub consent_present: bool,
     /// Requested slot count for this candidate.
     pub slot_count: u16,
+    /// Experiment arms of the request; see [`ExperimentArms::label`].
+    pub experiment_arms: Option<String>,
     started_at: Instant,
 }
 
@@ -175,10 +194,18 @@ impl AuctionObservationContext {
             gdpr_applies: consent.gdpr_applies,
             consent_present: !consent.is_empty(),
             slot_count,
+            experiment_arms: None,
             started_at: Instant::now(),
         }
     }
 
+    /// Tag the observation with the request's experiment arms.
+    #[must_use]
+    pub fn with_experiment_arms(mut self, arms: &ExperimentArms) -> Self {
+        self.experiment_arms = arms.label();
+        self
+    }
+
     /// Return elapsed milliseconds since the observation was created.
     #[must_use]
     pub fn elapsed_ms(&self) -> u64 {
@@ -199,6 +226,7 @@ impl AuctionObservationContext {
             gdpr_applies: false,
             consent_present: false,
             slot_count,
+            experiment_arms: None,
             started_at: Instant::now(),
         }
     }
@@ -264,7 +292,8 @@ pub enum AuctionTerminalOutcome<'a> {
 pub struct AuctionEventRow {
     /// Terminal observation timestamp in UTC.
     pub event_ts: String,
-    /// `summary`, `provider_call`, or `bid`.
+    /// `summary`, `provider_call`, `bid`, `creative_scan`, or a client-reported
+    /// creative event (`render`, `viewable`, `click`).
     pub event_kind: String,
     /// Fresh telemetry auction UUID.
     pub auction_id: String,
@@ -306,6 +335,8 @@ pub struct AuctionEventRow {
     pub provider_response_time_ms: Option<u32>,
     /// Parsed provider bid count.
     pub provider_bid_count: Option<u16>,
+    /// Parsed provider bids that carry a deal ID.
+    pub provider_deal_bid_count: Option<u16>,
     /// Bid slot ID.
     pub slot_id: Option<String>,
     /// Creative width.
@@ -326,6 +357,12 @@ pub struct AuctionEventRow {
     pub ad_domain: Option<String>,
     /// Creative/ad ID.
     pub ad_id: Option<String>,
+    /// PMP or programmatic guaranteed deal ID.
+    pub deal_id: Option<String>,
+    /// `1` when the bid was reused from the bid cache, `0` for fresh bids.
+    pub bid_cache_hit: Option<u8>,
+    /// `experiment:arm` pairs joined by `|`; `None` outside experiments.
+    pub experiment_arms: Option<String>,
 }
 
 impl AuctionEventRow {
@@ -353,6 +390,7 @@ impl AuctionEventRow {
             status: None,
             provider_response_time_ms: None,
             provider_bid_count: None,
+            provider_deal_bid_count: None,
             slot_id: None,
             slot_w: None,
             slot_h: None,
@@ -363,8 +401,24 @@ impl AuctionEventRow {
             is_win: None,
             ad_domain: None,
             ad_id: None,
+            deal_id: None,
+            bid_cache_hit: None,
+            experiment_arms: observation.experiment_arms.clone(),
         }
     }
+
+    /// Build a winning-slot row for a creative event reported after the
+    /// auction, timestamped now.
+    pub(crate) fn for_creative_event(
+        observation: &AuctionObservationContext,
+        event_kind: &str,
+        slot_id: &str,
+    ) -> Self {
+        let mut row = Self::base(observation, event_kind, &current_event_timestamp());
+        row.slot_id = Some(slot_id.to_owned());
+        row.is_win = Some(1);
+        row
+    }
 }
 
 /// A bounded group of rows emitted for one auction observation.
@@ -546,6 +600,13 @@ pub fn build_auction_events(
                 result,
                 delivered_winner_slots,
             );
+            for response in result
+                .provider_responses
+                .iter()
+                .chain(result.mediator_response.as_ref())
+            {
+                push_creative_scan_rows(&mut rows, &observation, &event_ts, response);
+            }
         }
         AuctionTerminalOutcome::ExecutionFailed {
             request: _,
@@ -623,6 +684,7 @@ pub fn build_auction_events(
                 row.status = Some("abandoned".to_owned());
                 row.provider_response_time_ms = provider.response_time_ms;
                 row.provider_bid_count = Some(0);
+                row.provider_deal_bid_count = Some(0);
                 rows.push(row);
             }
         }
@@ -686,6 +748,12 @@ fn push_provider_row(
     row.provider_response_time_ms =
         Some(u32::try_from(response.response_time_ms).unwrap_or(u32::MAX));
     row.provider_bid_count = Some(u16::try_from(response.bids.len()).unwrap_or(u16::MAX));
+    let deal_bids = response
+        .bids
+        .iter()
+        .filter(|bid| bid.deal_id.is_some())
+        .count();
+    row.provider_deal_bid_count = Some(u16::try_from(deal_bids).unwrap_or(u16::MAX));
     rows.push(row);
 }
 
@@ -755,6 +823,34 @@ fn push_bid_rows(
     }
 }
 
+/// One row per creative scan finding, with the action in `status` and the
+/// finding in `terminal_reason`.
+fn push_creative_scan_rows(
+    rows: &mut Vec<AuctionEventRow>,
+    observation: &AuctionObservationContext,
+    event_ts: &str,
+    response: &AuctionResponse,
+) {
+    let Some(findings) = response
+        .metadata
+        .get(CREATIVE_SCAN_FINDINGS_METADATA_KEY)
+        .and_then(|value| serde_json::from_value::<Vec<ScanFindingRecord>>(value.clone()).ok())
+    else {
+        return;
+    };
+    for finding in findings {
+        let mut row = AuctionEventRow::base(observation, "creative_scan", event_ts);
+        row.provider = Some(response.provider.clone());
+        row.status = Some(sanitize_reason(&finding.action));
+        row.terminal_reason = Some(sanitize_reason(&finding.finding));
+        row.slot_id = Some(finding.slot_id);
+        row.seat = Some(finding.seat);
+        row.ad_domain = finding.ad_domain;
+        row.ad_id = finding.ad_id;
+        rows.push(row);
+    }
+}
+
 fn bid_row(
     observation: &AuctionObservationContext,
     event_ts: &str,
@@ -779,6 +875,10 @@ fn bid_row(
         .as_ref()
         .and_then(|domains| domains.first().cloned());
     row.ad_id = bid.ad_id.clone();
+    row.deal_id = bid.deal_id.clone();
+    row.bid_cache_hit = Some(u8::from(
+        bid.metadata.contains_key(BID_CACHE_HIT_METADATA_KEY),
+    ));
     row
 }
 
@@ -934,6 +1034,7 @@ mod tests {
     use serde_json::json;
 
     use crate::auction::types::{AdFormat, AdSlot, PublisherInfo, UserInfo};
+    use crate::experiments::{ExperimentArmAssignment, ExperimentAssignment};
 
     use super::*;
 
@@ -950,6 +1051,7 @@ mod tests {
                 floor_price: None,
                 targeting: HashMap::new(),
                 bidders: HashMap::new(),
+                pmp: None,
             }],
             publisher: PublisherInfo {
                 domain: "test-publisher.example".to_owned(),
@@ -963,6 +1065,7 @@ mod tests {
             device: None,
             site: None,
             context: HashMap::new(),
+            ad_quality: Default::default(),
         }
     }
 
@@ -981,6 +1084,9 @@ mod tests {
             bid_id: None,
             ad_id: ad_id.map(str::to_owned),
             creative_id: None,
+            deal_id: None,
+            categories: Vec::new(),
+            attributes: Vec::new(),
             renderer: None,
             cache_id: None,
             cache_host: None,
@@ -1100,6 +1206,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn rows_carry_the_observation_experiment_arms() {
+        let arms: ExperimentArms = [("timeout", "fast"), ("granularity", "dense")]
+            .into_iter()
+            .map(|(experiment, arm)| ExperimentArmAssignment {
+                experiment: experiment.to_owned(),
+                arm: arm.to_owned(),
+                assignment: ExperimentAssignment::Ec,
+            })
+            .collect();
+        let observation =
+            AuctionObservationContext::for_test(AuctionSource::AuctionApi, "/auction", 1)
+                .with_experiment_arms(&arms);
+
+        let batch = build_auction_events(
+            observation,
+            AuctionTerminalOutcome::Skipped {
+                reason: "consent_denied",
+                elapsed_ms: 0,
+            },
+        );
+
+        assert_eq!(
+            batch.rows()[0].experiment_arms.as_deref(),
+            Some("timeout:fast|granularity:dense"),
+            "should label rows with every assigned arm"
+        );
+    }
+
     #[test]
     fn completed_events_do_not_mark_dropped_winners_as_delivered() {
         let request = test_request("ts-ec-derived-id");
@@ -1179,6 +1314,91 @@ mod tests {
         );
     }
 
+    #[test]
+    fn deal_bids_are_counted_on_provider_rows_and_tagged_on_bid_rows() {
+        let request = test_request("ts-ec-derived-id");
+        let mut deal_bid = bid("slot-1", "kargo", Some("ad-1"), Some(4.0));
+        deal_bid.deal_id = Some("pg-deal-1".to_owned());
+        let provider_success = AuctionResponse::success("prebid", vec![deal_bid.clone()], 42);
+        let result = OrchestrationResult {
+            provider_responses: vec![provider_success],
+            mediator_response: None,
+            winning_bids: HashMap::from([("slot-1".to_owned(), deal_bid)]),
+            total_time_ms: 42,
+            metadata: HashMap::new(),
+        };
+
+        let batch = build_auction_events(
+            AuctionObservationContext::for_test(AuctionSource::AuctionApi, "/auction", 1),
+            AuctionTerminalOutcome::Completed {
+                request: &request,
+                result: &result,
+                delivered_winner_slots: None,
+            },
+        );
+
+        let provider_row = batch
+            .rows()
+            .iter()
+            .find(|row| row.event_kind == "provider_call")
+            .expect("should emit provider row");
+        assert_eq!(provider_row.provider_deal_bid_count, Some(1));
+        let bid_row = batch
+            .rows()
+            .iter()
+            .find(|row| row.event_kind == "bid")
+            .expect("should emit bid row");
+        assert_eq!(bid_row.deal_id.as_deref(), Some("pg-deal-1"));
+        assert_eq!(bid_row.bid_cache_hit, Some(0), "should mark fresh bids");
+    }
+
+    #[test]
+    fn creative_scan_findings_emit_their_own_rows() {
+        let request = test_request("ts-ec-derived-id");
+        let clean = bid("slot-1", "kargo", Some("ad-2"), Some(1.0));
+        let provider_success = AuctionResponse::success("prebid", vec![clean.clone()], 42)
+            .with_metadata(
+                CREATIVE_SCAN_FINDINGS_METADATA_KEY,
+                json!([{
+                    "slot_id": "slot-1",
+                    "seat": "rubicon",
+                    "ad_id": "ad-1",
+                    "ad_domain": "redirect.example",
+                    "finding": "top_navigation",
+                    "action": "rejected"
+                }]),
+            );
+        let result = OrchestrationResult {
+            provider_responses: vec![provider_success],
+            mediator_response: None,
+            winning_bids: HashMap::from([("slot-1".to_owned(), clean)]),
+            total_time_ms: 42,
+            metadata: HashMap::new(),
+        };
+
+        let batch = build_auction_events(
+            AuctionObservationContext::for_test(AuctionSource::AuctionApi, "/auction", 1),
+            AuctionTerminalOutcome::Completed {
+                request: &request,
+                result: &result,
+                delivered_winner_slots: None,
+            },
+        );
+
+        let scan_rows: Vec<_> = batch
+            .rows()
+            .iter()
+            .filter(|row| row.event_kind == "creative_scan")
+            .collect();
+        assert_eq!(scan_rows.len(), 1, "should emit one row per finding");
+        let row = scan_rows[0];
+        assert_eq!(row.provider.as_deref(), Some("prebid"));
+        assert_eq!(row.status.as_deref(), Some("rejected"));
+        assert_eq!(row.terminal_reason.as_deref(), Some("top_navigation"));
+        assert_eq!(row.seat.as_deref(), Some("rubicon"));
+        assert_eq!(row.ad_domain.as_deref(), Some("redirect.example"));
+    }
+
     #[test]
     fn mediated_win_marks_original_bid_once() {
         let request = test_request("req");
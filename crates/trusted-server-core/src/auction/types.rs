@@ -5,6 +5,7 @@ use http::Request;
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashMap};
 
+use crate::auction::ad_quality::AdQualityRules;
 use crate::auction::context::ContextValue;
 use crate::geo::GeoInfo;
 use crate::platform::RuntimeServices;
@@ -31,6 +32,10 @@ pub struct AuctionRequest {
     pub site: Option<SiteInfo>,
     /// Additional context forwarded from the JS client payload.
     pub context: HashMap<String, ContextValue>,
+    /// Publisher block lists, resolved by the orchestrator before providers
+    /// are called.
+    #[serde(default, skip_serializing_if = "AdQualityRules::is_empty")]
+    pub ad_quality: AdQualityRules,
 }
 
 /// Represents a single ad slot/impression.
@@ -46,6 +51,71 @@ pub struct AdSlot {
     pub targeting: HashMap<String, serde_json::Value>,
     /// Bidder configurations (bidder name -> params)
     pub bidders: HashMap<String, serde_json::Value>,
+    /// Private marketplace deals eligible for this slot.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pmp: Option<SlotPmp>,
+}
+
+/// Private marketplace configuration for a slot, forwarded as `imp.pmp`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct SlotPmp {
+    /// Restrict the impression to the listed deals (`OpenRTB` `private_auction`).
+    #[serde(default)]
+    pub private_auction: bool,
+    /// Deals eligible for the impression.
+    pub deals: Vec<SlotDeal>,
+}
+
+/// One PMP or programmatic guaranteed deal.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct SlotDeal {
+    /// Deal ID agreed with the buyer.
+    pub id: String,
+    /// Deal floor in CPM (USD). Falls back to the slot floor when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bidfloor: Option<f64>,
+    /// Buyer seats allowed to bid on the deal. Empty means any seat.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub wseat: Vec<String>,
+}
+
+impl SlotPmp {
+    /// Validate deal IDs and floors.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error string when a deal ID is empty or duplicated, a floor
+    /// is negative or non-finite, or `private_auction` is set without deals.
+    pub fn validate_runtime(&self, slot_id: &str) -> Result<(), String> {
+        if self.private_auction && self.deals.is_empty() {
+            return Err(format!(
+                "slot `{slot_id}` pmp.private_auction requires at least one deal"
+            ));
+        }
+        let mut seen = std::collections::HashSet::new();
+        for deal in &self.deals {
+            if deal.id.trim().is_empty() {
+                return Err(format!("slot `{slot_id}` pmp deal id must not be empty"));
+            }
+            if !seen.insert(deal.id.as_str()) {
+                return Err(format!(
+                    "slot `{slot_id}` pmp deal `{}` is configured twice",
+                    deal.id
+                ));
+            }
+            if let Some(floor) = deal.bidfloor
+                && (!floor.is_finite() || floor < 0.0)
+            {
+                return Err(format!(
+                    "slot `{slot_id}` pmp deal `{}` bidfloor must be a finite value >= 0.0",
+                    deal.id
+                ));
+            }
+        }
+        Ok(())
+    }
 }
 
 /// Ad format specification.
@@ -265,6 +335,15 @@ pub struct Bid {
     pub ad_id: Option<String>,
     /// Optional `OpenRTB` creative identifier.
     pub creative_id: Option<String>,
+    /// `OpenRTB` deal ID when the bid is for a PMP or programmatic guaranteed deal.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deal_id: Option<String>,
+    /// IAB content categories of the creative (`OpenRTB` `cat`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub categories: Vec<String>,
+    /// Creative attributes (`OpenRTB` `attr`, `AdCOM` list 5.3).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attributes: Vec<i32>,
     /// Typed browser renderer capability.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub renderer: Option<BidRenderer>,
@@ -288,6 +367,27 @@ pub struct Bid {
     pub metadata: HashMap<String, serde_json::Value>,
 }
 
+impl Bid {
+    /// Return the identifier used as the `hb_adid` targeting value.
+    ///
+    /// Prefers the Prebid Cache UUID, then the typed-renderer bid ID, then
+    /// `adid`, then the `OpenRTB` bid ID. Empty identifiers count as absent: a
+    /// blank `hb_adid` is falsey on the page, so GPT would skip the key.
+    #[must_use]
+    pub fn targeting_ad_id(&self) -> Option<&str> {
+        let non_empty = |value: Option<&str>| value.filter(|value| !value.is_empty());
+        let renderer_bid_id = self
+            .renderer
+            .as_ref()
+            .and_then(|renderer| renderer.as_aps())
+            .map(|renderer| renderer.bid_id.as_str());
+        non_empty(self.cache_id.as_deref())
+            .or_else(|| non_empty(renderer_bid_id))
+            .or_else(|| non_empty(self.ad_id.as_deref()))
+            .or_else(|| non_empty(self.bid_id.as_deref()))
+    }
+}
+
 /// Per-provider summary included in the auction response.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProviderSummary {
@@ -299,6 +399,9 @@ pub struct ProviderSummary {
     pub bid_count: usize,
     /// Unique bidder/seat names (e.g., "kargo", "pubmatic", "ix").
     pub bidders: Vec<String>,
+    /// Number of returned bids that carry a deal ID.
+    #[serde(default)]
+    pub deal_bid_count: usize,
     /// Response time in milliseconds.
     pub time_ms: u64,
     /// Provider-specific metadata (from [`AuctionResponse::metadata`]).
@@ -317,6 +420,11 @@ impl From<&AuctionResponse> for ProviderSummary {
             status: response.status.clone(),
             bid_count: response.bids.len(),
             bidders,
+            deal_bid_count: response
+                .bids
+                .iter()
+                .filter(|bid| bid.deal_id.is_some())
+                .count(),
             time_ms: response.response_time_ms,
             metadata: response.metadata.clone(),
         }
@@ -416,6 +524,9 @@ mod tests {
             bid_id: None,
             ad_id: None,
             creative_id: None,
+            deal_id: None,
+            categories: Vec::new(),
+            attributes: Vec::new(),
             renderer: None,
             cache_id: None,
             cache_host: None,
@@ -519,6 +630,55 @@ mod tests {
         );
     }
 
+    #[test]
+    fn provider_summary_counts_deal_bids() {
+        let mut deal_bid = make_bid("kargo");
+        deal_bid.deal_id = Some("deal-123".to_owned());
+        let response = AuctionResponse::success("prebid", vec![deal_bid, make_bid("ix")], 40);
+
+        let summary = ProviderSummary::from(&response);
+
+        assert_eq!(summary.deal_bid_count, 1, "should count only deal bids");
+    }
+
+    #[test]
+    fn slot_pmp_rejects_duplicate_and_invalid_deals() {
+        let deal = |id: &str, bidfloor: Option<f64>| SlotDeal {
+            id: id.to_owned(),
+            bidfloor,
+            wseat: Vec::new(),
+        };
+        let cases = [
+            (
+                SlotPmp {
+                    private_auction: true,
+                    deals: Vec::new(),
+                },
+                "requires at least one deal",
+            ),
+            (
+                SlotPmp {
+                    private_auction: false,
+                    deals: vec![deal("d1", None), deal("d1", None)],
+                },
+                "configured twice",
+            ),
+            (
+                SlotPmp {
+                    private_auction: false,
+                    deals: vec![deal("d1", Some(-1.0))],
+                },
+                "bidfloor",
+            ),
+        ];
+        for (pmp, expected) in cases {
+            let err = pmp
+                .validate_runtime("atf")
+                .expect_err("should reject invalid pmp");
+            assert!(err.contains(expected), "should mention {expected}: {err}");
+        }
+    }
+
     #[test]
     fn provider_summary_skips_metadata_in_serialization_when_empty() {
         let response = AuctionResponse::no_bid("aps", 100);
@@ -548,6 +708,9 @@ mod tests {
             bid_id: None,
             ad_id: Some("bid-id".to_string()),
             creative_id: None,
+            deal_id: None,
+            categories: Vec::new(),
+            attributes: Vec::new(),
             renderer: None,
             cache_id: Some("cache-uuid".to_string()),
             cache_host: Some("cache.example.com".to_string()),
@@ -654,6 +817,9 @@ mod tests {
             bid_id: None,
             ad_id: Some("prebid-ad-id-abc".to_string()),
             creative_id: None,
+            deal_id: None,
+            categories: Vec::new(),
+            attributes: Vec::new(),
             renderer: None,
             cache_id: None,
             cache_host: None,
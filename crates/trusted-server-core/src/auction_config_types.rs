@@ -1,7 +1,7 @@
 //! Auction configuration types (separated to avoid circular deps in build.rs).
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 
 /// Auction orchestration configuration.
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -64,6 +64,235 @@ pub struct AuctionConfig {
     /// silently dropped. An empty list blocks all context keys.
     #[serde(default = "default_allowed_context_keys")]
     pub allowed_context_keys: HashSet<String>,
+
+    /// Publisher block lists sent in outgoing bid requests and enforced on
+    /// every provider and mediator bid before winner selection.
+    ///
+    /// Omitted from serialized config blobs while empty so older
+    /// [`AuctionConfig`] schemas keep loading them.
+    #[serde(default, skip_serializing_if = "AdQualityConfig::is_empty")]
+    pub ad_quality: AdQualityConfig,
+
+    /// Per-bidder keys for the encrypted `${AUCTION_PRICE:B64}` macro, keyed
+    /// by the winning bid's `bidder` (seat).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub price_encryption: BTreeMap<String, PriceEncryptionKeyConfig>,
+
+    /// Malware and auto-redirect scanning of bid creatives, applied with ad
+    /// quality enforcement before winner selection.
+    #[serde(default, skip_serializing_if = "CreativeScanConfig::is_default")]
+    pub creative_scan: CreativeScanConfig,
+
+    /// Keep unused bids per EC and slot so they can compete in the next
+    /// auction for the same user (slot refresh, infinite scroll).
+    #[serde(default, skip_serializing_if = "BidCacheConfig::is_default")]
+    pub bid_cache: BidCacheConfig,
+}
+
+/// Per-user bid cache (`[auction.bid_cache]`).
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct BidCacheConfig {
+    /// Cache unused bids. Disabled by default.
+    #[serde(default)]
+    pub enabled: bool,
+    /// KV store holding the cached bids. Required when enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kv_store: Option<String>,
+    /// Lifetime of a bid without an `OpenRTB` `exp`, in seconds.
+    #[serde(default = "default_bid_cache_ttl_secs")]
+    pub default_ttl_secs: u32,
+    /// Upper bound on any cached bid's lifetime, in seconds. Bidder `exp`
+    /// values above it are capped.
+    #[serde(default = "default_bid_cache_max_ttl_secs")]
+    pub max_ttl_secs: u32,
+    /// Highest-priced bids kept per slot.
+    #[serde(default = "default_bid_cache_max_bids_per_slot")]
+    pub max_bids_per_slot: usize,
+}
+
+impl Default for BidCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            kv_store: None,
+            default_ttl_secs: default_bid_cache_ttl_secs(),
+            max_ttl_secs: default_bid_cache_max_ttl_secs(),
+            max_bids_per_slot: default_bid_cache_max_bids_per_slot(),
+        }
+    }
+}
+
+impl BidCacheConfig {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+fn default_bid_cache_ttl_secs() -> u32 {
+    300
+}
+
+fn default_bid_cache_max_ttl_secs() -> u32 {
+    1800
+}
+
+fn default_bid_cache_max_bids_per_slot() -> usize {
+    3
+}
+
+/// What the creative scanner does with a bid whose creative has findings.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CreativeScanAction {
+    /// Keep the bid unchanged and emit the findings as telemetry.
+    #[default]
+    Report,
+    /// Remove the offending markup; reject the bid if findings remain.
+    Strip,
+    /// Drop the bid so the next-highest bid for the slot can win.
+    Reject,
+}
+
+/// Creative scanning policy (`[auction.creative_scan]`).
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct CreativeScanConfig {
+    /// Scan bid creatives. Disabled by default.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Action for providers without an entry in [`Self::providers`].
+    #[serde(default)]
+    pub default_action: CreativeScanAction,
+    /// Per-provider actions, keyed by provider name (e.g. `prebid`).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub providers: BTreeMap<String, CreativeScanAction>,
+    /// Domain reputation list. A creative referencing one of these hosts, or
+    /// a subdomain, is flagged.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blocked_domains: Vec<String>,
+    /// Hosts allowed to serve scripts injected with `document.write`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_script_domains: Vec<String>,
+    /// Config store holding additional domain lists, merged with the lists
+    /// above on every auction.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config_store: Option<String>,
+    /// Config store key of the JSON domain list document.
+    #[serde(
+        default = "default_creative_scan_key",
+        skip_serializing_if = "is_default_creative_scan_key"
+    )]
+    pub config_store_key: String,
+}
+
+impl Default for CreativeScanConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            default_action: CreativeScanAction::default(),
+            providers: BTreeMap::new(),
+            blocked_domains: Vec::new(),
+            allowed_script_domains: Vec::new(),
+            config_store: None,
+            config_store_key: default_creative_scan_key(),
+        }
+    }
+}
+
+impl CreativeScanConfig {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+fn default_creative_scan_key() -> String {
+    "creative_scan".to_owned()
+}
+
+fn is_default_creative_scan_key(value: &String) -> bool {
+    *value == default_creative_scan_key()
+}
+
+/// Secret Store location of one bidder's price encryption keys
+/// (`[auction.price_encryption.<bidder>]`).
+///
+/// Both secrets hold the 32-byte keys the DSP issued, web-safe base64 encoded.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct PriceEncryptionKeyConfig {
+    /// Secret Store holding both keys.
+    pub secret_store: String,
+    /// Secret name of the encryption key.
+    pub encryption_key: String,
+    /// Secret name of the integrity key.
+    pub integrity_key: String,
+}
+
+/// Ad quality block lists (`[auction.ad_quality]`).
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct AdQualityConfig {
+    /// Blocked advertiser domains (`OpenRTB` `badv`). Subdomains are blocked too.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub badv: Vec<String>,
+    /// Blocked IAB content categories (`OpenRTB` `bcat`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bcat: Vec<String>,
+    /// Blocked creative attributes (`OpenRTB` `battr`, `AdCOM` list 5.3).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub battr: Vec<i32>,
+    /// Blocked buyer seats (`OpenRTB` `bseat`), matched against the bid's seat.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blocked_seats: Vec<String>,
+    /// Blocked creative IDs (`crid`). Enforced only; `OpenRTB` has no request field.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blocked_creative_ids: Vec<String>,
+    /// Config store holding additional block lists, merged with the lists above
+    /// on every auction so ad ops can block an advertiser without a deploy.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config_store: Option<String>,
+    /// Config store key of the JSON block list document.
+    #[serde(
+        default = "default_ad_quality_key",
+        skip_serializing_if = "is_default_ad_quality_key"
+    )]
+    pub config_store_key: String,
+}
+
+impl Default for AdQualityConfig {
+    fn default() -> Self {
+        Self {
+            badv: Vec::new(),
+            bcat: Vec::new(),
+            battr: Vec::new(),
+            blocked_seats: Vec::new(),
+            blocked_creative_ids: Vec::new(),
+            config_store: None,
+            config_store_key: default_ad_quality_key(),
+        }
+    }
+}
+
+impl AdQualityConfig {
+    /// Whether no block list or config store source is configured.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.badv.is_empty()
+            && self.bcat.is_empty()
+            && self.battr.is_empty()
+            && self.blocked_seats.is_empty()
+            && self.blocked_creative_ids.is_empty()
+            && self.config_store.is_none()
+    }
+}
+
+fn default_ad_quality_key() -> String {
+    "ad_quality".to_owned()
+}
+
+fn is_default_ad_quality_key(value: &String) -> bool {
+    *value == default_ad_quality_key()
 }
 
 impl Default for AuctionConfig {
@@ -77,6 +306,10 @@ impl Default for AuctionConfig {
             timeout_ms: default_timeout(),
             creative_store: default_creative_store(),
             allowed_context_keys: HashSet::new(),
+            ad_quality: AdQualityConfig::default(),
+            price_encryption: BTreeMap::new(),
+            creative_scan: CreativeScanConfig::default(),
+            bid_cache: BidCacheConfig::default(),
         }
     }
 }
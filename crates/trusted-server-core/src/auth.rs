@@ -342,7 +342,7 @@ mod tests {
             r#"path = "^/_ts/admin"
             username = "admin"
             password = "admin-pass""#,
-            r#"path = "^/_ts/admin/(keys/rotate|keys/deactivate|ec|eids)$"
+            r#"path = "^/_ts/admin/(keys/rotate|keys/deactivate|ec|eids|metrics|config/release)$"
             username = "admin"
             password = "strong-test-password"
 
@@ -13,14 +13,16 @@ use error_stack::Report;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use validator::{Validate, ValidationError, ValidationErrors};
 
+use crate::config_schema::validate_schema_version;
 use crate::ec::registry::PartnerRegistry;
 use crate::error::TrustedServerError;
+use crate::experiments::settings_with_overrides;
 use crate::integrations::{
     adserver_mock::AdServerMockConfig, aps::ApsConfig, datadome::DataDomeConfig,
-    didomi::DidomiIntegrationConfig, google_tag_manager::GoogleTagManagerConfig, gpt::GptConfig,
-    gpt_diagnostics::GptDiagnosticsConfig, lockr::LockrConfig, nextjs::NextJsIntegrationConfig,
-    osano::OsanoConfig, permutive::PermutiveConfig, prebid, sourcepoint::SourcepointConfig,
-    testlight::TestlightConfig,
+    didomi::DidomiIntegrationConfig, gam, google_tag_manager::GoogleTagManagerConfig,
+    gpt::GptConfig, gpt_diagnostics::GptDiagnosticsConfig, lockr::LockrConfig,
+    nextjs::NextJsIntegrationConfig, openrtb_bidders, osano::OsanoConfig,
+    permutive::PermutiveConfig, prebid, sourcepoint::SourcepointConfig, testlight::TestlightConfig,
 };
 use crate::settings::{IntegrationConfig, Settings};
 
@@ -118,35 +120,64 @@ impl edgezero_core::app_config::AppConfigMeta for TrustedServerAppConfig {
 /// Runs Trusted Server deploy-time validation for pushed app config.
 ///
 /// This supplements [`Settings`] structural validation with checks that should
-/// fail before an operator publishes a config blob: placeholder secrets,
-/// enabled integration startup checks, auction provider references, and EC
-/// partner registry construction.
+/// fail before an operator publishes a config blob: the declared config schema
+/// version, placeholder secrets,
+/// enabled integration startup checks, auction provider references, EC
+/// partner registry construction, and the settings each experiment arm
+/// produces.
 ///
 /// # Errors
 ///
 /// Returns [`TrustedServerError`] when the config should not be deployed.
 pub fn validate_settings_for_deploy(settings: &Settings) -> Result<(), Report<TrustedServerError>> {
+    if let Some(version) = settings.config_schema_version {
+        validate_schema_version(version)?;
+    }
     settings.reject_placeholder_secrets()?;
     let enabled_auction_providers = validate_enabled_integrations(settings)?;
     validate_auction_provider_names(settings, &enabled_auction_providers)?;
     PartnerRegistry::from_config(&settings.ec.partners).map(|_| ())?;
+    validate_experiment_arms(settings)
+}
+
+/// Validates every arm's overrides on their own. Arms of different
+/// experiments that conflict when combined are caught at runtime, which
+/// serves the base settings instead.
+fn validate_experiment_arms(settings: &Settings) -> Result<(), Report<TrustedServerError>> {
+    for experiment in &settings.experiments {
+        for arm in experiment
+            .arms
+            .iter()
+            .filter(|arm| !arm.overrides.is_empty())
+        {
+            settings_with_overrides(settings, [&arm.overrides])
+                .and_then(|applied| validate_settings_for_deploy(&applied))
+                .map_err(|report| {
+                    report.attach(format!("experiment `{}` arm `{}`", experiment.id, arm.id))
+                })?;
+        }
+    }
     Ok(())
 }
 
 fn validate_enabled_integrations(
     settings: &Settings,
-) -> Result<HashSet<&'static str>, Report<TrustedServerError>> {
+) -> Result<HashSet<String>, Report<TrustedServerError>> {
     let mut enabled_auction_providers = HashSet::new();
 
     if validate_prebid(settings)? {
-        enabled_auction_providers.insert("prebid");
+        enabled_auction_providers.insert("prebid".to_string());
     }
     if validate_integration::<ApsConfig>(settings, "aps")? {
-        enabled_auction_providers.insert("aps");
+        enabled_auction_providers.insert("aps".to_string());
     }
     if validate_integration::<AdServerMockConfig>(settings, "adserver_mock")? {
-        enabled_auction_providers.insert("adserver_mock");
+        enabled_auction_providers.insert("adserver_mock".to_string());
     }
+    if gam::validate_config_for_startup(settings)? {
+        enabled_auction_providers.insert("gam".to_string());
+    }
+    enabled_auction_providers.extend(openrtb_bidders::enabled_bidder_names(settings)?);
     validate_integration::<TestlightConfig>(settings, "testlight")?;
     validate_integration::<NextJsIntegrationConfig>(settings, "nextjs")?;
     validate_integration::<PermutiveConfig>(settings, "permutive")?;
@@ -182,7 +213,7 @@ where
 
 fn validate_auction_provider_names(
     settings: &Settings,
-    enabled_auction_providers: &HashSet<&'static str>,
+    enabled_auction_providers: &HashSet<String>,
 ) -> Result<(), Report<TrustedServerError>> {
     if !settings.auction.enabled {
         return Ok(());
@@ -194,7 +225,7 @@ fn validate_auction_provider_names(
         .iter()
         .chain(settings.auction.mediator.iter())
     {
-        if !enabled_auction_providers.contains(provider_name.as_str()) {
+        if !enabled_auction_providers.contains(provider_name) {
             return Err(Report::new(TrustedServerError::Configuration {
                 message: format!(
                     "auction provider `{provider_name}` is listed in [auction] but no enabled integration provides it"
@@ -357,6 +388,50 @@ password = "production-admin-password-32-bytes"
         );
     }
 
+    #[test]
+    fn deploy_validation_rejects_invalid_experiment_arm() {
+        let mut settings = valid_settings();
+        settings.experiments = vec![
+            serde_json::from_value(serde_json::json!({
+                "id": "timeout",
+                "arms": [
+                    { "id": "control" },
+                    { "id": "fast", "overrides": { "auction": { "timeout_ms": "fast" } } },
+                ],
+            }))
+            .expect("should deserialize experiment"),
+        ];
+
+        let err = validate_settings_for_deploy(&settings)
+            .expect_err("should reject an arm whose overrides do not deserialize");
+
+        assert!(
+            format!("{err:?}").contains("experiment `timeout` arm `fast`"),
+            "error should name the experiment arm: {err:?}"
+        );
+
+        settings.experiments[0].arms[1].overrides =
+            serde_json::from_value(serde_json::json!({ "auction": { "timeout_ms": 500 } }))
+                .expect("should deserialize overrides");
+        validate_settings_for_deploy(&settings).expect("should accept a valid arm override");
+    }
+
+    #[test]
+    fn deploy_validation_rejects_a_newer_config_schema() {
+        let mut settings = valid_settings();
+        settings.config_schema_version = Some(crate::config_schema::CONFIG_SCHEMA_VERSION);
+        validate_settings_for_deploy(&settings).expect("should accept the current schema");
+
+        settings.config_schema_version = Some(crate::config_schema::CONFIG_SCHEMA_VERSION + 1);
+        let err = validate_settings_for_deploy(&settings)
+            .expect_err("should reject a config written for a newer schema");
+
+        assert!(
+            format!("{err:?}").contains("is not supported"),
+            "error should name the unsupported schema: {err:?}"
+        );
+    }
+
     #[test]
     fn deploy_validation_rejects_external_prebid_bundle_without_proxy_allowed_domains() {
         let mut settings = valid_settings();
@@ -444,6 +519,26 @@ password = "production-admin-password-32-bytes"
         }
     }
 
+    #[test]
+    fn deploy_validation_accepts_configured_openrtb_bidder_as_auction_provider() {
+        let mut settings = valid_settings();
+        settings
+            .integrations
+            .insert_config(
+                "openrtb_bidders",
+                &serde_json::json!([{
+                    "name": "example_ssp",
+                    "endpoint": "https://ssp.example/openrtb2/auction",
+                }]),
+            )
+            .expect("should insert OpenRTB bidder config");
+        settings.auction.enabled = true;
+        settings.auction.providers = vec!["example_ssp".to_string()];
+
+        validate_settings_for_deploy(&settings)
+            .expect("should accept a configured OpenRTB bidder as an auction provider");
+    }
+
     #[test]
     fn validate_trait_reports_deploy_errors() {
         let mut settings = valid_settings();
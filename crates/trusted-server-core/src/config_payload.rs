@@ -2,12 +2,18 @@
 //!
 //! The `ts` CLI delegates blob construction and config-store writes to
 //! `EdgeZero`'s typed config push path. Runtime loading only needs to verify the
-//! stored [`edgezero_core::blob_envelope::BlobEnvelope`] and reconstruct
-//! [`Settings`] from its data value.
+//! stored [`edgezero_core::blob_envelope::BlobEnvelope`], check the operator
+//! signature against the pinned config-signing keys (see
+//! [`crate::config_signing`]), and reconstruct [`Settings`] from its data value.
+//! With versioned pushes the logical key holds a
+//! [`crate::config_release::ConfigRelease`] manifest instead; see
+//! [`config_blob_from_envelope`].
 
 use edgezero_core::blob_envelope::BlobEnvelope;
 use error_stack::Report;
 
+use crate::config_release::{CONFIG_RELEASE_FIELD, ConfigRelease};
+use crate::config_signing::{PinnedConfigKey, pinned_config_keys, verify_config_value};
 use crate::error::TrustedServerError;
 use crate::settings::Settings;
 
@@ -16,13 +22,62 @@ pub const CONFIG_BLOB_KEY: &str = "trusted_server_config";
 
 /// Reconstruct validated [`Settings`] from a serialized config blob envelope.
 ///
+/// The envelope must be signed by one of the keys pinned into this binary at
+/// build time; when none are pinned, signing is not enforced.
+///
 /// # Errors
 ///
 /// Returns [`TrustedServerError::Configuration`] when the envelope cannot be
-/// parsed, fails integrity verification, or contains invalid settings data.
+/// parsed, fails integrity or signature verification, or contains invalid
+/// settings data.
 pub fn settings_from_config_blob(
     envelope_json: &str,
 ) -> Result<Settings, Report<TrustedServerError>> {
+    settings_from_config_blob_with_keys(envelope_json, &pinned_config_keys()?)
+}
+
+/// Reconstruct validated [`Settings`] from a config blob envelope signed by
+/// one of `pinned`.
+///
+/// # Errors
+///
+/// Returns [`TrustedServerError::Configuration`] when the envelope cannot be
+/// parsed, fails integrity or signature verification, holds a release
+/// manifest, or contains invalid settings data.
+pub fn settings_from_config_blob_with_keys(
+    envelope_json: &str,
+    pinned: &[PinnedConfigKey],
+) -> Result<Settings, Report<TrustedServerError>> {
+    match config_blob_from_envelope(envelope_json, pinned)? {
+        ConfigBlob::Settings(settings) => Ok(*settings),
+        ConfigBlob::Release(_) => Err(Report::new(TrustedServerError::Configuration {
+            message: "Trusted Server app-config blob is a release manifest, not settings"
+                .to_string(),
+        })
+        .attach("versioned configs must be loaded through the config store")),
+    }
+}
+
+/// Contents of a verified app-config blob.
+#[derive(Debug)]
+pub enum ConfigBlob {
+    /// Settings pushed in place or stored under a version key.
+    Settings(Box<Settings>),
+    /// Release manifest written by `ts config push --versioned`.
+    Release(ConfigRelease),
+}
+
+/// Verify a serialized config blob envelope and decode its contents.
+///
+/// # Errors
+///
+/// Returns [`TrustedServerError::Configuration`] when the envelope cannot be
+/// parsed, fails integrity or signature verification, or its data is neither
+/// valid settings nor a valid release manifest.
+pub fn config_blob_from_envelope(
+    envelope_json: &str,
+    pinned: &[PinnedConfigKey],
+) -> Result<ConfigBlob, Report<TrustedServerError>> {
     let envelope: BlobEnvelope = serde_json::from_str(envelope_json).map_err(|error| {
         Report::new(TrustedServerError::Configuration {
             message: "failed to parse Trusted Server app-config blob envelope".to_string(),
@@ -36,14 +91,27 @@ pub fn settings_from_config_blob(
         .attach(error.to_string())
     })?;
 
-    let settings = Settings::from_json_value(envelope.into_data())?;
+    let mut data = envelope.into_data();
+    verify_config_value(&mut data, pinned)?;
+    if let Some(release) = data.get_mut(CONFIG_RELEASE_FIELD) {
+        let release: ConfigRelease = serde_json::from_value(release.take()).map_err(|error| {
+            Report::new(TrustedServerError::Configuration {
+                message: "failed to parse Trusted Server config release manifest".to_string(),
+            })
+            .attach(error.to_string())
+        })?;
+        release.validate_release()?;
+        return Ok(ConfigBlob::Release(release));
+    }
+    let settings = Settings::from_json_value(data)?;
     settings.reject_placeholder_secrets()?;
-    Ok(settings)
+    Ok(ConfigBlob::Settings(Box::new(settings)))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config_signing::{ConfigSigner, parse_pinned_config_keys};
     use crate::redacted::Redacted;
     use crate::test_support::tests::crate_test_settings_str;
     use serde::Deserialize;
@@ -192,4 +260,94 @@ mod tests {
             "error should mention integrity verification"
         );
     }
+
+    fn signer() -> ConfigSigner {
+        ConfigSigner::from_base64_key("ops-2026", "AQIDBAUGBwgJCgsMDQ4PEBESExQVFhcYGRobHB0eHyA=")
+            .expect("should parse signing key")
+    }
+
+    fn signed_envelope_json(settings: &Settings, signer: &ConfigSigner) -> String {
+        let data = serde_json::to_value(settings).expect("should serialize settings to JSON");
+        let data = signer.sign_value(data).expect("should sign settings");
+        let envelope = BlobEnvelope::new(data, "2026-01-01T00:00:00Z".to_string());
+        serde_json::to_string(&envelope).expect("should serialize envelope")
+    }
+
+    #[test]
+    fn signed_blob_loads_with_pinned_key() {
+        let signer = signer();
+        let pinned = parse_pinned_config_keys(&format!("ops-2026={}", signer.public_key()))
+            .expect("should parse pinned key");
+        let original = test_settings();
+
+        let reconstructed =
+            settings_from_config_blob_with_keys(&signed_envelope_json(&original, &signer), &pinned)
+                .expect("should load a blob signed by a pinned key");
+
+        assert_eq!(
+            reconstructed.publisher.domain, original.publisher.domain,
+            "should preserve settings behind the signature"
+        );
+    }
+
+    #[test]
+    fn rehashed_blob_with_forged_settings_is_rejected() {
+        let signer = signer();
+        let pinned = parse_pinned_config_keys(&format!("ops-2026={}", signer.public_key()))
+            .expect("should parse pinned key");
+        let signed: BlobEnvelope =
+            serde_json::from_str(&signed_envelope_json(&test_settings(), &signer))
+                .expect("should parse envelope");
+        let mut data = signed.into_data();
+        data["publisher"]["domain"] = serde_json::json!("attacker.example");
+        // A config-store writer can always recompute the integrity hash.
+        let forged = BlobEnvelope::new(data, "2026-01-02T00:00:00Z".to_string());
+        let forged = serde_json::to_string(&forged).expect("should serialize forged envelope");
+
+        let err = settings_from_config_blob_with_keys(&forged, &pinned)
+            .expect_err("should reject settings the operator did not sign");
+
+        assert!(
+            err.to_string().contains("signature"),
+            "error should mention the signature: {err}"
+        );
+    }
+
+    #[test]
+    fn unsigned_blob_is_rejected_when_keys_are_pinned() {
+        let pinned = parse_pinned_config_keys(&format!("ops-2026={}", signer().public_key()))
+            .expect("should parse pinned key");
+
+        let err = settings_from_config_blob_with_keys(&envelope_json(&test_settings()), &pinned)
+            .expect_err("should require a signature");
+
+        assert!(
+            err.to_string().contains("not signed"),
+            "error should explain the missing signature: {err}"
+        );
+    }
+
+    #[test]
+    fn release_manifest_blob_decodes_as_release() {
+        let mut release = ConfigRelease::default();
+        release
+            .record_push("2026-10-01T00:00:00Z".to_string(), None, None)
+            .expect("should record push");
+        let data = serde_json::json!({ CONFIG_RELEASE_FIELD: release });
+        let envelope = BlobEnvelope::new(data, "2026-10-01T00:00:00Z".to_string());
+        let envelope_json = serde_json::to_string(&envelope).expect("should serialize envelope");
+
+        let blob = config_blob_from_envelope(&envelope_json, &[]).expect("should decode blob");
+        let ConfigBlob::Release(decoded) = blob else {
+            panic!("should decode a release manifest");
+        };
+        assert_eq!(decoded, release, "should preserve the manifest");
+
+        let err = settings_from_config_blob_with_keys(&envelope_json, &[])
+            .expect_err("should not treat a manifest as settings");
+        assert!(
+            err.to_string().contains("release manifest"),
+            "error should explain the manifest: {err}"
+        );
+    }
 }
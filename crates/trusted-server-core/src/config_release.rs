@@ -0,0 +1,562 @@
+//! Versioned app-config releases and staged rollout.
+//!
+//! `ts config push --versioned` stores each pushed config under its own
+//! config-store key ([`version_key`]) and writes a [`ConfigRelease`] manifest
+//! to the logical config key instead of overwriting the settings in place. The
+//! manifest names the stable version, an optional staged candidate served to a
+//! percentage of traffic, and the retained version history, so rolling back
+//! is a manifest rewrite rather than a re-push of old settings.
+//!
+//! Staged traffic is bucketed by the EC hash ([`rollout_bucket`]), so a
+//! browser keeps seeing the same version for the whole rollout. Requests
+//! without an EC always get the stable version.
+//!
+//! `GET /_ts/admin/config/release` ([`handle_admin_config_release`]) serves
+//! the deployed manifest, so `ts config history`, `promote`, and `rollback`
+//! start from what the config store holds rather than from local state.
+
+use cookie::Cookie;
+use edgezero_core::body::Body as EdgeBody;
+use error_stack::{Report, ResultExt};
+use http::{Response, StatusCode, header};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use validator::{Validate, ValidationError, ValidationErrors};
+
+use crate::constants::COOKIE_TS_EC;
+use crate::ec::generation::{ec_hash, is_valid_ec_id};
+use crate::error::TrustedServerError;
+use crate::platform::{PlatformConfigStore, StoreName};
+use crate::settings_data::get_config_release;
+
+/// Top-level envelope data field that marks a [`ConfigRelease`] manifest.
+pub const CONFIG_RELEASE_FIELD: &str = "ts_config_release";
+
+/// Number of versions `ts config push --versioned` keeps in the manifest by
+/// default. The stable and candidate versions are always kept.
+pub const DEFAULT_RETAINED_VERSIONS: usize = 10;
+
+/// Returns the config-store key holding `version` of the config at `key`.
+#[must_use]
+pub fn version_key(key: &str, version: u32) -> String {
+    format!("{key}.v{version}")
+}
+
+/// Release manifest stored at the logical config key.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigRelease {
+    /// Version served to all traffic outside a staged rollout.
+    pub stable: u32,
+    /// Version being rolled out to part of the traffic.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub candidate: Option<StagedCandidate>,
+    /// Retained versions, oldest first.
+    #[serde(default)]
+    pub versions: Vec<ConfigVersion>,
+}
+
+/// A version served to `percent` of EC-bucketed traffic before promotion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StagedCandidate {
+    /// Candidate config version.
+    pub version: u32,
+    /// Share of EC buckets (`0..100`) served the candidate, `1..=99`.
+    pub percent: u8,
+}
+
+/// One retained config version.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigVersion {
+    /// Monotonic version number.
+    pub version: u32,
+    /// RFC 3339 timestamp of the push.
+    pub pushed_at: String,
+    /// Optional operator note from `ts config push --note`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+impl ConfigRelease {
+    /// Returns the version to serve for a request in `bucket`.
+    ///
+    /// `bucket` is the request's [`rollout_bucket`]; `None` (no EC) always
+    /// selects the stable version.
+    #[must_use]
+    pub fn select(&self, bucket: Option<u8>) -> u32 {
+        match (self.candidate, bucket) {
+            (Some(candidate), Some(bucket)) if bucket < candidate.percent => candidate.version,
+            _ => self.stable,
+        }
+    }
+
+    /// Returns the versions to try for a request in `bucket`, in order: the
+    /// [selected](Self::select) version, then stable, then the retained
+    /// versions older than stable, newest first.
+    ///
+    /// The runtime skips a version that fails to load or verify, so a bad push
+    /// falls back to the last version in the config store that still verifies.
+    /// Versions newer than stable were rolled back from and are never tried.
+    #[must_use]
+    pub fn fallback_order(&self, bucket: Option<u8>) -> Vec<u32> {
+        let mut order = vec![self.select(bucket)];
+        if order[0] != self.stable {
+            order.push(self.stable);
+        }
+        let mut previous: Vec<u32> = self
+            .versions
+            .iter()
+            .map(|entry| entry.version)
+            .filter(|version| *version < self.stable)
+            .collect();
+        previous.sort_unstable_by(|a, b| b.cmp(a));
+        order.extend(previous);
+        order
+    }
+
+    /// Records a newly pushed version and returns its number.
+    ///
+    /// With `stage`, the new version becomes the candidate for that percentage
+    /// of traffic (replacing any earlier candidate); otherwise it becomes
+    /// stable immediately. The first version of a release is always stable.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TrustedServerError::Configuration`] when `stage` is outside
+    /// `1..=99`.
+    pub fn record_push(
+        &mut self,
+        pushed_at: String,
+        note: Option<String>,
+        stage: Option<u8>,
+    ) -> Result<u32, Report<TrustedServerError>> {
+        if let Some(percent) = stage {
+            validate_percent(percent)?;
+        }
+        let version = self
+            .versions
+            .iter()
+            .map(|entry| entry.version)
+            .max()
+            .unwrap_or(0)
+            .max(self.stable)
+            + 1;
+        self.versions.push(ConfigVersion {
+            version,
+            pushed_at,
+            note,
+        });
+        match stage {
+            Some(percent) if self.stable != 0 => {
+                self.candidate = Some(StagedCandidate { version, percent });
+            }
+            _ => {
+                self.stable = version;
+                self.candidate = None;
+            }
+        }
+        Ok(version)
+    }
+
+    /// Moves the candidate to `percent` of traffic, or to all traffic when
+    /// `percent` is `None`. Returns the candidate version.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TrustedServerError::Configuration`] when no candidate is
+    /// staged or `percent` is outside `1..=99`.
+    pub fn promote(&mut self, percent: Option<u8>) -> Result<u32, Report<TrustedServerError>> {
+        let Some(candidate) = self.candidate.as_mut() else {
+            return Err(release_error("no candidate version is staged".to_string()));
+        };
+        let version = candidate.version;
+        match percent {
+            Some(percent) => {
+                validate_percent(percent)?;
+                candidate.percent = percent;
+            }
+            None => {
+                self.stable = version;
+                self.candidate = None;
+            }
+        }
+        Ok(version)
+    }
+
+    /// Serves `version` to all traffic and abandons any staged candidate.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TrustedServerError::Configuration`] when `version` is not
+    /// retained.
+    pub fn rollback(&mut self, version: u32) -> Result<(), Report<TrustedServerError>> {
+        if !self.versions.iter().any(|entry| entry.version == version) {
+            return Err(release_error(format!(
+                "config version {version} is not retained; see `ts config history`"
+            )));
+        }
+        self.stable = version;
+        self.candidate = None;
+        Ok(())
+    }
+
+    /// Drops the oldest versions beyond `keep`, never the stable or candidate
+    /// version. Returns the dropped version numbers.
+    pub fn retain_latest(&mut self, keep: usize) -> Vec<u32> {
+        let mut dropped = Vec::new();
+        let mut excess = self.versions.len().saturating_sub(keep);
+        let stable = self.stable;
+        let candidate = self.candidate.map(|candidate| candidate.version);
+        self.versions.retain(|entry| {
+            let pinned = entry.version == stable || Some(entry.version) == candidate;
+            if excess > 0 && !pinned {
+                excess -= 1;
+                dropped.push(entry.version);
+                false
+            } else {
+                true
+            }
+        });
+        dropped
+    }
+
+    /// Checks that the manifest only references retained versions.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TrustedServerError::Configuration`] describing the first
+    /// inconsistency.
+    pub fn validate_release(&self) -> Result<(), Report<TrustedServerError>> {
+        let retained = |version: u32| self.versions.iter().any(|entry| entry.version == version);
+        if !retained(self.stable) {
+            return Err(release_error(format!(
+                "stable config version {} is not in the release history",
+                self.stable
+            )));
+        }
+        if let Some(candidate) = self.candidate {
+            validate_percent(candidate.percent)?;
+            if candidate.version == self.stable || !retained(candidate.version) {
+                return Err(release_error(format!(
+                    "candidate config version {} must be a retained version other than stable",
+                    candidate.version
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// [`ConfigRelease`] as pushed through `EdgeZero`'s typed config path.
+///
+/// Serializes as `{"ts_config_release": {...}}` so the runtime can tell a
+/// manifest from a settings blob. Signed pushes wrap it in
+/// [`crate::config_signing::SignedConfig`] like
+/// [`crate::config::TrustedServerAppConfig`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigReleaseDocument {
+    /// The wrapped manifest.
+    pub release: ConfigRelease,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigReleaseDocumentShape<R> {
+    ts_config_release: R,
+}
+
+impl Serialize for ConfigReleaseDocument {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        ConfigReleaseDocumentShape {
+            ts_config_release: &self.release,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ConfigReleaseDocument {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let shape = ConfigReleaseDocumentShape::<ConfigRelease>::deserialize(deserializer)?;
+        Ok(Self {
+            release: shape.ts_config_release,
+        })
+    }
+}
+
+impl Validate for ConfigReleaseDocument {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        self.release.validate_release().map_err(|report| {
+            let mut errors = ValidationErrors::new();
+            let mut error = ValidationError::new("config_release");
+            error.message = Some(report.current_context().to_string().into());
+            errors.add(CONFIG_RELEASE_FIELD, error);
+            errors
+        })
+    }
+}
+
+impl edgezero_core::app_config::AppConfigMeta for ConfigReleaseDocument {
+    const SECRET_FIELDS: &'static [edgezero_core::app_config::SecretField] = &[];
+}
+
+/// Maps an EC ID to its rollout bucket in `0..100`.
+///
+/// Returns `None` for malformed IDs so they are served the stable version.
+#[must_use]
+pub fn rollout_bucket(ec_id: &str) -> Option<u8> {
+    if !is_valid_ec_id(ec_id) {
+        return None;
+    }
+    let prefix = u32::from_str_radix(&ec_hash(ec_id)[..8], 16).ok()?;
+    u8::try_from(prefix % 100).ok()
+}
+
+/// Reads the rollout bucket from the EC cookie in a `Cookie` header value.
+#[must_use]
+pub fn rollout_bucket_from_cookie_header(cookie_header: &str) -> Option<u8> {
+    Cookie::split_parse(cookie_header)
+        .filter_map(Result::ok)
+        .find(|cookie| cookie.name() == COOKIE_TS_EC)
+        .and_then(|cookie| rollout_bucket(cookie.value()))
+}
+
+fn validate_percent(percent: u8) -> Result<(), Report<TrustedServerError>> {
+    if (1..=99).contains(&percent) {
+        Ok(())
+    } else {
+        Err(release_error(format!(
+            "staged rollout percentage must be between 1 and 99, got {percent}"
+        )))
+    }
+}
+
+/// Body of `GET /_ts/admin/config/release`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeployedConfigRelease {
+    /// Config-store key the runtime loads its config from.
+    pub key: String,
+    /// Manifest deployed at `key`, or `None` when settings were pushed in
+    /// place.
+    pub release: Option<ConfigRelease>,
+}
+
+/// Serves the release manifest deployed at `key`.
+///
+/// # Errors
+///
+/// Returns [`TrustedServerError::Configuration`] when the config entry cannot
+/// be loaded or the response cannot be serialized.
+pub fn handle_admin_config_release(
+    config_store: &dyn PlatformConfigStore,
+    store_name: &StoreName,
+    key: &str,
+) -> Result<Response<EdgeBody>, Report<TrustedServerError>> {
+    let payload = DeployedConfigRelease {
+        key: key.to_string(),
+        release: get_config_release(config_store, store_name, key)?,
+    };
+    let body =
+        serde_json::to_string(&payload).change_context(TrustedServerError::Configuration {
+            message: "failed to serialize deployed config release".to_string(),
+        })?;
+    Ok(Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+        .header(header::CACHE_CONTROL, "no-store")
+        .body(EdgeBody::from(body.into_bytes()))
+        .expect("should build config release response"))
+}
+
+fn release_error(message: String) -> Report<TrustedServerError> {
+    Report::new(TrustedServerError::Configuration { message })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pushed(release: &mut ConfigRelease, stage: Option<u8>) -> u32 {
+        release
+            .record_push("2026-10-01T00:00:00Z".to_string(), None, stage)
+            .expect("should record push")
+    }
+
+    #[test]
+    fn staged_push_serves_candidate_to_low_buckets_only() {
+        let mut release = ConfigRelease::default();
+        let first = pushed(&mut release, Some(10));
+        let second = pushed(&mut release, Some(10));
+
+        assert_eq!(
+            release.stable, first,
+            "first version should go straight to stable"
+        );
+        assert_eq!(
+            release.select(Some(9)),
+            second,
+            "bucket 9 should get the candidate"
+        );
+        assert_eq!(
+            release.select(Some(10)),
+            first,
+            "bucket 10 should stay on stable"
+        );
+        assert_eq!(
+            release.select(None),
+            first,
+            "requests without an EC should stay on stable"
+        );
+        release
+            .validate_release()
+            .expect("should be a consistent release");
+    }
+
+    #[test]
+    fn promote_widens_then_completes_the_rollout() {
+        let mut release = ConfigRelease::default();
+        pushed(&mut release, None);
+        let candidate = pushed(&mut release, Some(5));
+
+        release.promote(Some(50)).expect("should widen the rollout");
+        assert_eq!(
+            release.select(Some(49)),
+            candidate,
+            "should serve the wider share"
+        );
+
+        release.promote(None).expect("should promote the candidate");
+        assert_eq!(release.stable, candidate, "candidate should become stable");
+        assert_eq!(release.candidate, None, "should clear the candidate");
+        release
+            .promote(None)
+            .expect_err("should refuse to promote without a candidate");
+    }
+
+    #[test]
+    fn rollback_requires_a_retained_version() {
+        let mut release = ConfigRelease::default();
+        let first = pushed(&mut release, None);
+        pushed(&mut release, None);
+        pushed(&mut release, Some(20));
+
+        release.rollback(first).expect("should roll back");
+
+        assert_eq!(
+            release.stable, first,
+            "should serve the rolled-back version"
+        );
+        assert_eq!(
+            release.candidate, None,
+            "should abandon the staged candidate"
+        );
+        release
+            .rollback(99)
+            .expect_err("should reject an unknown version");
+    }
+
+    #[test]
+    fn fallback_order_walks_back_from_the_selected_version() {
+        let mut release = ConfigRelease::default();
+        for _ in 0..3 {
+            pushed(&mut release, None);
+        }
+        pushed(&mut release, Some(20));
+
+        assert_eq!(release.fallback_order(Some(0)), vec![4, 3, 2, 1]);
+        assert_eq!(release.fallback_order(None), vec![3, 2, 1]);
+
+        release.rollback(2).expect("should roll back");
+        assert_eq!(
+            release.fallback_order(None),
+            vec![2, 1],
+            "should not fall forward to versions rolled back from"
+        );
+    }
+
+    #[test]
+    fn retain_latest_keeps_stable_and_candidate() {
+        let mut release = ConfigRelease::default();
+        let first = pushed(&mut release, None);
+        for _ in 0..4 {
+            pushed(&mut release, None);
+        }
+        release
+            .rollback(first)
+            .expect("should roll back to the first version");
+
+        let dropped = release.retain_latest(2);
+
+        assert_eq!(
+            dropped,
+            vec![2, 3, 4],
+            "should drop the oldest unpinned versions"
+        );
+        let kept: Vec<u32> = release.versions.iter().map(|entry| entry.version).collect();
+        assert_eq!(
+            kept,
+            vec![first, 5],
+            "should keep stable and the newest version"
+        );
+    }
+
+    #[test]
+    fn document_round_trips_through_the_release_field() {
+        let mut release = ConfigRelease::default();
+        pushed(&mut release, None);
+        let document = ConfigReleaseDocument { release };
+
+        let value = serde_json::to_value(&document).expect("should serialize document");
+        assert!(
+            value.get(CONFIG_RELEASE_FIELD).is_some(),
+            "should nest the manifest under the release field"
+        );
+        let parsed: ConfigReleaseDocument =
+            serde_json::from_value(value).expect("should parse document");
+        assert_eq!(parsed, document, "should round-trip the manifest");
+    }
+
+    #[test]
+    fn invalid_stage_percentages_are_rejected() {
+        let mut release = ConfigRelease::default();
+        pushed(&mut release, None);
+
+        for percent in [0, 100] {
+            release
+                .record_push("2026-10-01T00:00:00Z".to_string(), None, Some(percent))
+                .expect_err("should reject out-of-range percentages");
+        }
+    }
+
+    #[test]
+    fn rollout_bucket_uses_the_ec_hash() {
+        let ec_id = format!("{}.AbC123", "0000000a".to_string() + &"f".repeat(56));
+
+        assert_eq!(
+            rollout_bucket(&ec_id),
+            Some(10),
+            "0x0000000a should land in bucket 10"
+        );
+        assert_eq!(
+            rollout_bucket_from_cookie_header(&format!("other=1; {COOKIE_TS_EC}={ec_id}")),
+            Some(10),
+            "should read the EC cookie"
+        );
+        assert_eq!(
+            rollout_bucket("not-an-ec"),
+            None,
+            "malformed IDs have no bucket"
+        );
+        assert_eq!(
+            rollout_bucket_from_cookie_header("other=1"),
+            None,
+            "requests without an EC have no bucket"
+        );
+    }
+}
@@ -0,0 +1,404 @@
+//! Trusted Server config schema versions.
+//!
+//! Most settings structs use `deny_unknown_fields`, so a config blob carrying a
+//! key an older binary does not know fails that binary's configuration load.
+//! Each schema version records the keys it introduced ([`SCHEMA_CHANGES`]):
+//! `ts config validate --target-binary` reports the keys a pushed blob carries
+//! that an older schema rejects, and `ts config migrate` rewrites a config file
+//! from one schema version to another.
+//!
+//! When a change adds a key that can reach serialized config blobs, or renames
+//! a key, bump [`CONFIG_SCHEMA_VERSION`] and record the change here. Keep new
+//! keys out of blobs while at their default (`skip_serializing_if`) so configs
+//! that do not use a feature stay loadable by older binaries.
+//!
+//! Key paths are dotted field names. A `[]` suffix on a segment matches every
+//! element of an array, so `creative_opportunities.slot[].pmp` is the `pmp`
+//! table of any slot.
+
+use error_stack::Report;
+use serde_json::Value as JsonValue;
+
+use crate::error::TrustedServerError;
+
+/// Schema version written and understood by this build.
+pub const CONFIG_SCHEMA_VERSION: u32 = 2;
+
+/// Top-level config field declaring the schema version a file was written
+/// for. It is never serialized into config blobs.
+pub const CONFIG_SCHEMA_VERSION_FIELD: &str = "config_schema_version";
+
+/// A change made by one schema version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaChange {
+    /// `path` was added in `since`; earlier schemas reject it.
+    Added {
+        /// Schema version that introduced the key.
+        since: u32,
+        /// Key path.
+        path: &'static str,
+    },
+    /// The key at `path` was renamed to `to` (a sibling field name) in `since`.
+    Renamed {
+        /// Schema version that renamed the key.
+        since: u32,
+        /// Key path before the rename.
+        path: &'static str,
+        /// Field name after the rename.
+        to: &'static str,
+    },
+}
+
+impl SchemaChange {
+    /// Schema version that made the change.
+    #[must_use]
+    pub fn since(&self) -> u32 {
+        match self {
+            Self::Added { since, .. } | Self::Renamed { since, .. } => *since,
+        }
+    }
+}
+
+/// Changes made by each schema version after 1, oldest first.
+pub const SCHEMA_CHANGES: &[SchemaChange] = &[
+    SchemaChange::Added {
+        since: 2,
+        path: "tracing",
+    },
+    SchemaChange::Added {
+        since: 2,
+        path: "events",
+    },
+    SchemaChange::Added {
+        since: 2,
+        path: "experiments",
+    },
+    SchemaChange::Added {
+        since: 2,
+        path: "proxy.vast_max_wrapper_depth",
+    },
+    SchemaChange::Added {
+        since: 2,
+        path: "proxy.creative_cache",
+    },
+    SchemaChange::Added {
+        since: 2,
+        path: "auction.ad_quality",
+    },
+    SchemaChange::Added {
+        since: 2,
+        path: "auction.price_encryption",
+    },
+    SchemaChange::Added {
+        since: 2,
+        path: "auction.creative_scan",
+    },
+    SchemaChange::Added {
+        since: 2,
+        path: "auction.bid_cache",
+    },
+    SchemaChange::Added {
+        since: 2,
+        path: "creative_opportunities.slot[].pmp",
+    },
+    SchemaChange::Added {
+        since: 2,
+        path: "creative_opportunities.slot[].lazy_load",
+    },
+    SchemaChange::Added {
+        since: 2,
+        path: "creative_opportunities.slot[].refresh",
+    },
+];
+
+/// A key present in a config that a given schema version does not know.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownKey {
+    /// Concrete key path, with array indexes (`creative_opportunities.slot[0].pmp`).
+    pub path: String,
+    /// Schema version that introduced the key, or renamed it to this name.
+    pub since: u32,
+}
+
+/// One segment of a concrete key path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeySegment {
+    /// Table field.
+    Field(String),
+    /// Array element.
+    Index(usize),
+}
+
+/// Splits a concrete key path such as `creative_opportunities.slot[0].pmp`
+/// into segments.
+#[must_use]
+pub fn key_segments(path: &str) -> Vec<KeySegment> {
+    let mut segments = Vec::new();
+    for part in path.split('.') {
+        let (field, indexes) = part.split_once('[').unwrap_or((part, ""));
+        segments.push(KeySegment::Field(field.to_string()));
+        for index in indexes.split('[') {
+            if let Ok(index) = index.trim_end_matches(']').parse() {
+                segments.push(KeySegment::Index(index));
+            }
+        }
+    }
+    segments
+}
+
+/// Returns the concrete paths in `config` matching the schema key `pattern`.
+#[must_use]
+pub fn find_keys(config: &JsonValue, pattern: &str) -> Vec<String> {
+    let mut found = Vec::new();
+    collect_keys(config, pattern, "", &mut found);
+    found
+}
+
+fn collect_keys(value: &JsonValue, pattern: &str, prefix: &str, found: &mut Vec<String>) {
+    let (head, rest) = match pattern.split_once('.') {
+        Some((head, rest)) => (head, Some(rest)),
+        None => (pattern, None),
+    };
+    let (field, each_element) = match head.strip_suffix("[]") {
+        Some(field) => (field, true),
+        None => (head, false),
+    };
+    let Some(child) = value.as_object().and_then(|object| object.get(field)) else {
+        return;
+    };
+    let path = if prefix.is_empty() {
+        field.to_string()
+    } else {
+        format!("{prefix}.{field}")
+    };
+
+    let mut visit = |child: &JsonValue, path: String| match rest {
+        Some(rest) => collect_keys(child, rest, &path, found),
+        None => found.push(path),
+    };
+    if each_element {
+        for (index, element) in child.as_array().into_iter().flatten().enumerate() {
+            visit(element, format!("{path}[{index}]"));
+        }
+    } else {
+        visit(child, path);
+    }
+}
+
+/// Returns the keys in `config` that binaries on schema `target` reject:
+/// keys added after `target`, and names that a later schema renamed keys to.
+#[must_use]
+pub fn keys_unknown_to(
+    config: &JsonValue,
+    target: u32,
+    changes: &[SchemaChange],
+) -> Vec<UnknownKey> {
+    let mut unknown = Vec::new();
+    for change in changes.iter().filter(|change| change.since() > target) {
+        let pattern = match change {
+            SchemaChange::Added { path, .. } => (*path).to_string(),
+            SchemaChange::Renamed { path, to, .. } => renamed_path(path, to),
+        };
+        unknown.extend(
+            find_keys(config, &pattern)
+                .into_iter()
+                .map(|path| UnknownKey {
+                    path,
+                    since: change.since(),
+                }),
+        );
+    }
+    unknown
+}
+
+/// Returns the schema version a config was written for: its declared
+/// [`CONFIG_SCHEMA_VERSION_FIELD`], or the oldest version that knows every
+/// key it uses.
+#[must_use]
+pub fn detect_schema_version(config: &JsonValue, changes: &[SchemaChange]) -> u32 {
+    if let Some(declared) = declared_schema_version(config) {
+        return declared;
+    }
+    keys_unknown_to(config, 1, changes)
+        .iter()
+        .map(|key| key.since)
+        .max()
+        .unwrap_or(1)
+}
+
+/// Returns the config's declared [`CONFIG_SCHEMA_VERSION_FIELD`], if any.
+#[must_use]
+pub fn declared_schema_version(config: &JsonValue) -> Option<u32> {
+    config
+        .get(CONFIG_SCHEMA_VERSION_FIELD)
+        .and_then(JsonValue::as_u64)
+        .and_then(|version| u32::try_from(version).ok())
+}
+
+/// Returns `path` with its last field renamed to `to`.
+#[must_use]
+pub fn renamed_path(path: &str, to: &str) -> String {
+    match path.rsplit_once('.') {
+        Some((parent, _)) => format!("{parent}.{to}"),
+        None => to.to_string(),
+    }
+}
+
+/// Checks that `version` is a schema version this build knows.
+///
+/// # Errors
+///
+/// Returns [`TrustedServerError::Configuration`] when `version` is 0 or newer
+/// than [`CONFIG_SCHEMA_VERSION`].
+pub fn validate_schema_version(version: u32) -> Result<(), Report<TrustedServerError>> {
+    if version == 0 || version > CONFIG_SCHEMA_VERSION {
+        return Err(Report::new(TrustedServerError::Configuration {
+            message: format!(
+                "config schema version {version} is not supported; this build knows versions 1 to {CONFIG_SCHEMA_VERSION}"
+            ),
+        }));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::settings::Settings;
+    use crate::test_support::tests::crate_test_settings_str;
+
+    const RENAMES: &[SchemaChange] = &[
+        SchemaChange::Added {
+            since: 2,
+            path: "creative_opportunities.slot[].refresh",
+        },
+        SchemaChange::Renamed {
+            since: 3,
+            path: "integrations.aps.pub_id",
+            to: "account_id",
+        },
+    ];
+
+    fn slots_config() -> JsonValue {
+        json!({
+            "creative_opportunities": {
+                "slot": [
+                    { "id": "top" },
+                    { "id": "side", "refresh": { "interval_secs": 30 } },
+                    { "id": "footer", "refresh": { "interval_secs": 60 } }
+                ]
+            }
+        })
+    }
+
+    #[test]
+    fn find_keys_expands_array_elements() {
+        assert_eq!(
+            find_keys(&slots_config(), "creative_opportunities.slot[].refresh"),
+            vec![
+                "creative_opportunities.slot[1].refresh".to_string(),
+                "creative_opportunities.slot[2].refresh".to_string(),
+            ],
+            "should return one concrete path per slot carrying the key"
+        );
+        assert!(
+            find_keys(&slots_config(), "creative_opportunities.slot[].pmp").is_empty(),
+            "should find nothing when no slot carries the key"
+        );
+    }
+
+    #[test]
+    fn key_segments_round_trips_concrete_paths() {
+        assert_eq!(
+            key_segments("creative_opportunities.slot[1].refresh"),
+            vec![
+                KeySegment::Field("creative_opportunities".to_string()),
+                KeySegment::Field("slot".to_string()),
+                KeySegment::Index(1),
+                KeySegment::Field("refresh".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn keys_unknown_to_reports_additions_and_renames_after_target() {
+        let mut config = slots_config();
+        config["integrations"] = json!({ "aps": { "account_id": "123" } });
+
+        let unknown_to_1 = keys_unknown_to(&config, 1, RENAMES);
+        assert_eq!(
+            unknown_to_1.len(),
+            3,
+            "schema 1 should reject both refresh tables and the renamed key: {unknown_to_1:?}"
+        );
+        assert_eq!(
+            keys_unknown_to(&config, 2, RENAMES),
+            vec![UnknownKey {
+                path: "integrations.aps.account_id".to_string(),
+                since: 3,
+            }],
+            "schema 2 should only reject the renamed key"
+        );
+        assert!(
+            keys_unknown_to(&config, 3, RENAMES).is_empty(),
+            "the newest schema should know every key"
+        );
+    }
+
+    #[test]
+    fn detect_schema_version_prefers_the_declared_version() {
+        assert_eq!(detect_schema_version(&json!({}), RENAMES), 1);
+        assert_eq!(detect_schema_version(&slots_config(), RENAMES), 2);
+
+        let mut config = slots_config();
+        config[CONFIG_SCHEMA_VERSION_FIELD] = json!(3);
+        assert_eq!(
+            detect_schema_version(&config, RENAMES),
+            3,
+            "should use the declared version"
+        );
+    }
+
+    #[test]
+    fn default_settings_blob_loads_on_schema_1() {
+        let settings =
+            Settings::from_toml(&crate_test_settings_str()).expect("should parse test settings");
+        let blob = serde_json::to_value(&settings).expect("should serialize settings");
+
+        assert_eq!(
+            keys_unknown_to(&blob, 1, SCHEMA_CHANGES),
+            Vec::new(),
+            "a config using no schema 2 features should stay loadable by schema 1 binaries"
+        );
+        assert!(
+            blob.get(CONFIG_SCHEMA_VERSION_FIELD).is_none(),
+            "the declared schema version should never reach the blob"
+        );
+    }
+
+    #[test]
+    fn schema_changes_are_ordered_and_current() {
+        assert!(
+            SCHEMA_CHANGES
+                .windows(2)
+                .all(|pair| pair[0].since() <= pair[1].since()),
+            "changes should be listed oldest first"
+        );
+        assert!(
+            SCHEMA_CHANGES
+                .iter()
+                .all(|change| (2..=CONFIG_SCHEMA_VERSION).contains(&change.since())),
+            "every change should belong to a known schema version"
+        );
+    }
+
+    #[test]
+    fn validate_schema_version_rejects_unknown_versions() {
+        assert!(validate_schema_version(1).is_ok());
+        assert!(validate_schema_version(CONFIG_SCHEMA_VERSION).is_ok());
+        assert!(validate_schema_version(0).is_err());
+        assert!(validate_schema_version(CONFIG_SCHEMA_VERSION + 1).is_err());
+    }
+}
@@ -0,0 +1,550 @@
+//! Operator signatures for Trusted Server app-config blobs.
+//!
+//! The blob envelope hash only detects corruption: anyone with config-store
+//! write access can publish a new envelope with a matching hash. `ts config
+//! push --signing-key` therefore signs the settings payload with an operator
+//! Ed25519 key through [`serialize_signed`] and pushes it as a [`SignedConfig`],
+//! which stores the signature under [`CONFIG_SIGNATURE_FIELD`] in the envelope
+//! data. At load time the runtime verifies that signature against the
+//! public keys pinned into the binary at build time through
+//! [`PINNED_KEYS_ENV`] before any [`crate::settings::Settings`] are constructed.
+//!
+//! The signed message is a fixed domain separator followed by the hex SHA-256 of
+//! the payload serialized as canonical JSON (object keys sorted, no
+//! whitespace), so the signature does not depend on how the config store or
+//! the envelope writer orders keys.
+
+use base64::{Engine as _, engine::general_purpose};
+use ed25519_dalek::{Signature, Signer as _, SigningKey, Verifier as _, VerifyingKey};
+use error_stack::Report;
+use serde::de::{DeserializeOwned, Error as _};
+use serde::ser::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value as JsonValue};
+use sha2::{Digest as _, Sha256};
+use validator::{Validate, ValidationErrors};
+
+use crate::error::TrustedServerError;
+use crate::request_signing::signing::parse_ed25519_signing_key;
+
+/// Top-level envelope data field holding the [`ConfigSignature`].
+pub const CONFIG_SIGNATURE_FIELD: &str = "config_signature";
+
+/// Build-time environment variable listing pinned config-signing public keys.
+///
+/// The value is a comma-separated list of `kid=key` pairs where `key` is the
+/// URL-safe base64 Ed25519 public key, the same encoding as the JWK `x`
+/// parameter used for request signing. Pinning several keys allows rotation.
+pub const PINNED_KEYS_ENV: &str = "TRUSTED_SERVER_CONFIG_PUBLIC_KEYS";
+
+/// Domain separator so a config signature can never be replayed as a request
+/// signature made with the same key, or vice versa.
+const SIGNATURE_CONTEXT: &[u8] = b"trusted-server-config-v1\n";
+
+const SIGNATURE_ALGORITHM: &str = "EdDSA";
+
+/// Detached signature over an app-config payload.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigSignature {
+    /// Identifier of the pinned key that verifies the signature.
+    pub kid: String,
+    /// Signature algorithm; always `EdDSA`.
+    pub alg: String,
+    /// URL-safe base64 Ed25519 signature.
+    pub sig: String,
+}
+
+/// Signs app-config payloads with an operator Ed25519 key.
+pub struct ConfigSigner {
+    key: SigningKey,
+    kid: String,
+}
+
+impl ConfigSigner {
+    /// Creates a signer from a standard base64 Ed25519 private key, the format
+    /// request-signing keys use in the secret store.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TrustedServerError::Configuration`] when `kid` is empty or the
+    /// key is not 32 bytes of base64.
+    pub fn from_base64_key(
+        kid: impl Into<String>,
+        key_b64: &str,
+    ) -> Result<Self, Report<TrustedServerError>> {
+        let kid = kid.into();
+        if kid.trim().is_empty() {
+            return Err(Report::new(TrustedServerError::Configuration {
+                message: "config signing key id must not be empty".to_string(),
+            }));
+        }
+        let key = parse_ed25519_signing_key(key_b64.trim().as_bytes())?;
+        Ok(Self { key, kid })
+    }
+
+    /// Key identifier written into every signature.
+    #[must_use]
+    pub fn kid(&self) -> &str {
+        &self.kid
+    }
+
+    /// URL-safe base64 public key, as expected in [`PINNED_KEYS_ENV`].
+    #[must_use]
+    pub fn public_key(&self) -> String {
+        general_purpose::URL_SAFE_NO_PAD.encode(self.key.verifying_key().as_bytes())
+    }
+
+    /// Signs `payload` and returns it with the signature attached under
+    /// [`CONFIG_SIGNATURE_FIELD`]. An existing signature is replaced.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TrustedServerError::Configuration`] when `payload` is not a
+    /// JSON object.
+    pub fn sign_value(&self, payload: JsonValue) -> Result<JsonValue, Report<TrustedServerError>> {
+        let JsonValue::Object(mut object) = payload else {
+            return Err(Report::new(TrustedServerError::Configuration {
+                message: "app-config payload must be a JSON object to be signed".to_string(),
+            }));
+        };
+        object.remove(CONFIG_SIGNATURE_FIELD);
+        let signature = self.key.sign(&signed_message(&object));
+        let signature = ConfigSignature {
+            kid: self.kid.clone(),
+            alg: SIGNATURE_ALGORITHM.to_string(),
+            sig: general_purpose::URL_SAFE_NO_PAD.encode(signature.to_bytes()),
+        };
+        let signature = serde_json::to_value(signature).map_err(|error| {
+            Report::new(TrustedServerError::Configuration {
+                message: format!("failed to serialize config signature: {error}"),
+            })
+        })?;
+        object.insert(CONFIG_SIGNATURE_FIELD.to_string(), signature);
+        Ok(JsonValue::Object(object))
+    }
+}
+
+/// Serializes `value` and signs it with `signer`, returning the payload with
+/// the signature attached under [`CONFIG_SIGNATURE_FIELD`].
+///
+/// # Errors
+///
+/// Returns [`TrustedServerError::Configuration`] when `value` cannot be
+/// serialized or does not serialize as a JSON object.
+pub fn serialize_signed<T: Serialize>(
+    value: &T,
+    signer: &ConfigSigner,
+) -> Result<JsonValue, Report<TrustedServerError>> {
+    let payload = serde_json::to_value(value).map_err(|error| {
+        Report::new(TrustedServerError::Configuration {
+            message: format!("failed to serialize app-config payload: {error}"),
+        })
+    })?;
+    signer.sign_value(payload)
+}
+
+/// An app-config document pushed together with its [`ConfigSignature`].
+///
+/// Plain data: it serializes as the document with the signature added under
+/// [`CONFIG_SIGNATURE_FIELD`] and deserializes by splitting that field off
+/// again, so the typed push path carries a signature made ahead of time by
+/// [`SignedConfig::sign`].
+#[derive(Debug, Clone)]
+pub struct SignedConfig<T> {
+    config: T,
+    signature: ConfigSignature,
+}
+
+impl<T: Serialize> SignedConfig<T> {
+    /// Signs `config` with `signer`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TrustedServerError::Configuration`] when `config` cannot be
+    /// serialized as a JSON object.
+    pub fn sign(config: T, signer: &ConfigSigner) -> Result<Self, Report<TrustedServerError>> {
+        let mut payload = serialize_signed(&config, signer)?;
+        let signature = payload
+            .as_object_mut()
+            .and_then(|object| object.remove(CONFIG_SIGNATURE_FIELD))
+            .and_then(|signature| serde_json::from_value(signature).ok())
+            .ok_or_else(|| {
+                Report::new(TrustedServerError::Configuration {
+                    message: "signed app-config payload is missing its signature".to_string(),
+                })
+            })?;
+        Ok(Self { config, signature })
+    }
+}
+
+impl<T> SignedConfig<T> {
+    /// The signed document.
+    #[must_use]
+    pub fn config(&self) -> &T {
+        &self.config
+    }
+
+    /// Signature over the serialized document.
+    #[must_use]
+    pub fn signature(&self) -> &ConfigSignature {
+        &self.signature
+    }
+}
+
+impl<T: Serialize> Serialize for SignedConfig<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut payload = serde_json::to_value(&self.config).map_err(S::Error::custom)?;
+        let object = payload
+            .as_object_mut()
+            .ok_or_else(|| S::Error::custom("signed app config must serialize as an object"))?;
+        let signature = serde_json::to_value(&self.signature).map_err(S::Error::custom)?;
+        object.insert(CONFIG_SIGNATURE_FIELD.to_string(), signature);
+        payload.serialize(serializer)
+    }
+}
+
+impl<'de, T: DeserializeOwned> Deserialize<'de> for SignedConfig<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let mut payload = JsonValue::deserialize(deserializer)?;
+        let signature = payload
+            .as_object_mut()
+            .and_then(|object| object.remove(CONFIG_SIGNATURE_FIELD))
+            .ok_or_else(|| D::Error::missing_field(CONFIG_SIGNATURE_FIELD))?;
+        let signature = serde_json::from_value(signature).map_err(D::Error::custom)?;
+        let config = serde_json::from_value(payload).map_err(D::Error::custom)?;
+        Ok(Self { config, signature })
+    }
+}
+
+impl<T: Validate> Validate for SignedConfig<T> {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        self.config.validate()
+    }
+}
+
+impl<T: edgezero_core::app_config::AppConfigMeta> edgezero_core::app_config::AppConfigMeta
+    for SignedConfig<T>
+{
+    const SECRET_FIELDS: &'static [edgezero_core::app_config::SecretField] = T::SECRET_FIELDS;
+}
+
+/// Public key trusted to sign app-config blobs.
+#[derive(Debug, Clone)]
+pub struct PinnedConfigKey {
+    kid: String,
+    key: VerifyingKey,
+}
+
+impl PinnedConfigKey {
+    /// Parses one `kid=key` entry of [`PINNED_KEYS_ENV`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TrustedServerError::Configuration`] when the entry is not
+    /// `kid=key` or the key is not a valid Ed25519 public key.
+    pub fn parse(entry: &str) -> Result<Self, Report<TrustedServerError>> {
+        let invalid = |detail: &str| {
+            Report::new(TrustedServerError::Configuration {
+                message: format!("invalid pinned config signing key `{entry}`: {detail}"),
+            })
+        };
+        let (kid, key_b64) = entry
+            .split_once('=')
+            .map(|(kid, key)| (kid.trim(), key.trim()))
+            .filter(|(kid, key)| !kid.is_empty() && !key.is_empty())
+            .ok_or_else(|| invalid("expected `kid=key`"))?;
+        let bytes = general_purpose::URL_SAFE_NO_PAD
+            .decode(key_b64)
+            .map_err(|_| invalid("key is not URL-safe base64"))?;
+        let bytes: [u8; 32] = bytes
+            .try_into()
+            .map_err(|_| invalid("key must be 32 bytes"))?;
+        let key = VerifyingKey::from_bytes(&bytes)
+            .map_err(|_| invalid("key is not a valid Ed25519 point"))?;
+        Ok(Self {
+            kid: kid.to_string(),
+            key,
+        })
+    }
+}
+
+/// Parses a comma-separated [`PINNED_KEYS_ENV`] value.
+///
+/// # Errors
+///
+/// Returns [`TrustedServerError::Configuration`] when any entry is malformed.
+pub fn parse_pinned_config_keys(
+    value: &str,
+) -> Result<Vec<PinnedConfigKey>, Report<TrustedServerError>> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(PinnedConfigKey::parse)
+        .collect()
+}
+
+/// Returns the keys pinned into this binary at build time.
+///
+/// # Errors
+///
+/// Returns [`TrustedServerError::Configuration`] when the build-time value is
+/// malformed. Loading then fails closed rather than running unverified.
+pub fn pinned_config_keys() -> Result<Vec<PinnedConfigKey>, Report<TrustedServerError>> {
+    option_env!("TRUSTED_SERVER_CONFIG_PUBLIC_KEYS")
+        .map_or_else(|| Ok(Vec::new()), parse_pinned_config_keys)
+}
+
+/// Verifies and removes the signature on an app-config payload.
+///
+/// With no pinned keys, signing is not enforced and any signature is stripped
+/// unverified. Once keys are pinned, the payload must carry a signature from
+/// one of them.
+///
+/// # Errors
+///
+/// Returns [`TrustedServerError::Configuration`] when keys are pinned and the
+/// payload is unsigned, signed by an unknown key, or the signature does not
+/// match.
+pub fn verify_config_value(
+    payload: &mut JsonValue,
+    pinned: &[PinnedConfigKey],
+) -> Result<(), Report<TrustedServerError>> {
+    let signature = match payload.as_object_mut() {
+        Some(object) => object.remove(CONFIG_SIGNATURE_FIELD),
+        None => None,
+    };
+    if pinned.is_empty() {
+        if signature.is_some() {
+            log::warn!(
+                "App-config blob is signed but no config signing keys are pinned; signature not verified"
+            );
+        }
+        return Ok(());
+    }
+
+    let rejected = |message: String| Report::new(TrustedServerError::Configuration { message });
+    let signature = signature
+        .ok_or_else(|| rejected("app-config blob is not signed by a pinned key".to_string()))?;
+    let signature: ConfigSignature = serde_json::from_value(signature)
+        .map_err(|error| rejected(format!("malformed app-config signature: {error}")))?;
+    if signature.alg != SIGNATURE_ALGORITHM {
+        return Err(rejected(format!(
+            "unsupported app-config signature algorithm `{}`",
+            signature.alg
+        )));
+    }
+    let pinned_key = pinned
+        .iter()
+        .find(|key| key.kid == signature.kid)
+        .ok_or_else(|| {
+            rejected(format!(
+                "app-config blob is signed by unpinned key `{}`",
+                signature.kid
+            ))
+        })?;
+    let sig_bytes: [u8; 64] = general_purpose::URL_SAFE_NO_PAD
+        .decode(&signature.sig)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| rejected("app-config signature must be 64 bytes of base64".to_string()))?;
+    let object = payload
+        .as_object()
+        .ok_or_else(|| rejected("app-config payload must be a JSON object".to_string()))?;
+
+    pinned_key
+        .key
+        .verify(&signed_message(object), &Signature::from_bytes(&sig_bytes))
+        .map_err(|_| {
+            rejected(format!(
+                "app-config signature does not verify with pinned key `{}`",
+                signature.kid
+            ))
+        })
+}
+
+fn signed_message(payload: &Map<String, JsonValue>) -> Vec<u8> {
+    let mut canonical = String::new();
+    write_canonical_object(payload, &mut canonical);
+    let digest = format!("{:x}", Sha256::digest(canonical.as_bytes()));
+    [SIGNATURE_CONTEXT, digest.as_bytes()].concat()
+}
+
+fn write_canonical(value: &JsonValue, out: &mut String) {
+    match value {
+        JsonValue::Object(object) => write_canonical_object(object, out),
+        JsonValue::Array(items) => {
+            out.push('[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        scalar => out.push_str(&scalar.to_string()),
+    }
+}
+
+fn write_canonical_object(object: &Map<String, JsonValue>, out: &mut String) {
+    let mut entries: Vec<_> = object.iter().collect();
+    entries.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+    out.push('{');
+    for (index, (key, value)) in entries.into_iter().enumerate() {
+        if index > 0 {
+            out.push(',');
+        }
+        out.push_str(&JsonValue::String(key.clone()).to_string());
+        out.push(':');
+        write_canonical(value, out);
+    }
+    out.push('}');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const SIGNING_KEY_B64: &str = "AQIDBAUGBwgJCgsMDQ4PEBESExQVFhcYGRobHB0eHyA=";
+
+    fn signer() -> ConfigSigner {
+        ConfigSigner::from_base64_key("ops-2026", SIGNING_KEY_B64).expect("should parse key")
+    }
+
+    fn pinned(signer: &ConfigSigner) -> Vec<PinnedConfigKey> {
+        parse_pinned_config_keys(&format!("{}={}", signer.kid(), signer.public_key()))
+            .expect("should parse pinned key")
+    }
+
+    #[test]
+    fn signed_payload_verifies_and_strips_the_signature() {
+        let signer = signer();
+        let mut payload = signer
+            .sign_value(json!({"publisher": {"domain": "example.com"}, "handlers": []}))
+            .expect("should sign payload");
+
+        verify_config_value(&mut payload, &pinned(&signer)).expect("should verify signature");
+
+        assert_eq!(
+            payload,
+            json!({"publisher": {"domain": "example.com"}, "handlers": []}),
+            "should remove the signature before settings are built"
+        );
+    }
+
+    #[test]
+    fn signature_is_independent_of_key_order() {
+        let signer = signer();
+        let signed = signer
+            .sign_value(json!({"a": 1, "b": {"y": true, "x": [1, "two"]}}))
+            .expect("should sign payload");
+        let mut reordered = serde_json::from_str::<JsonValue>(
+            r#"{"b":{"x":[1,"two"],"y":true},"config_signature":null,"a":1}"#,
+        )
+        .expect("should parse reordered payload");
+        reordered[CONFIG_SIGNATURE_FIELD] = signed[CONFIG_SIGNATURE_FIELD].clone();
+
+        verify_config_value(&mut reordered, &pinned(&signer))
+            .expect("should verify regardless of key order");
+    }
+
+    #[test]
+    fn tampered_payload_is_rejected() {
+        let signer = signer();
+        let mut payload = signer
+            .sign_value(json!({"publisher": {"domain": "example.com"}}))
+            .expect("should sign payload");
+        payload["publisher"]["domain"] = json!("attacker.example");
+
+        let err = verify_config_value(&mut payload, &pinned(&signer))
+            .expect_err("should reject a modified payload");
+
+        assert!(
+            err.to_string().contains("does not verify"),
+            "error should explain the signature mismatch: {err}"
+        );
+    }
+
+    #[test]
+    fn unsigned_payload_is_rejected_once_keys_are_pinned() {
+        let mut payload = json!({"publisher": {"domain": "example.com"}});
+
+        let err = verify_config_value(&mut payload, &pinned(&signer()))
+            .expect_err("should require a signature");
+
+        assert!(
+            err.to_string().contains("not signed"),
+            "error should explain the missing signature: {err}"
+        );
+    }
+
+    #[test]
+    fn signature_from_an_unpinned_key_is_rejected() {
+        let signer = signer();
+        let mut payload = signer
+            .sign_value(json!({"publisher": {"domain": "example.com"}}))
+            .expect("should sign payload");
+        let pinned_other = parse_pinned_config_keys(&format!("other={}", signer.public_key()))
+            .expect("should parse pinned key");
+
+        let err = verify_config_value(&mut payload, &pinned_other)
+            .expect_err("should reject an unpinned kid");
+
+        assert!(
+            err.to_string().contains("unpinned key"),
+            "error should name the unknown key: {err}"
+        );
+    }
+
+    #[test]
+    fn unsigned_payload_is_accepted_without_pinned_keys() {
+        let mut payload = json!({"publisher": {"domain": "example.com"}});
+
+        verify_config_value(&mut payload, &[]).expect("should not enforce signing");
+    }
+
+    #[test]
+    fn signed_config_round_trips_and_verifies() {
+        let signer = signer();
+        let document = json!({"publisher": {"domain": "example.com"}});
+        let signed = SignedConfig::sign(document.clone(), &signer).expect("should sign document");
+
+        let mut payload = serde_json::to_value(&signed).expect("should serialize signed config");
+        let parsed: SignedConfig<JsonValue> =
+            serde_json::from_value(payload.clone()).expect("should parse signed config");
+        verify_config_value(&mut payload, &pinned(&signer)).expect("should verify signature");
+
+        assert_eq!(payload, document, "should carry the document unchanged");
+        assert_eq!(parsed.config(), &document);
+        assert_eq!(parsed.signature(), signed.signature());
+        assert!(
+            serde_json::from_value::<SignedConfig<JsonValue>>(document).is_err(),
+            "should require a signature"
+        );
+    }
+
+    #[test]
+    fn malformed_pinned_keys_are_rejected() {
+        for value in ["ops", "ops=", "=abc", "ops=not-base64!", "ops=AQID"] {
+            assert!(
+                parse_pinned_config_keys(value).is_err(),
+                "should reject pinned key value `{value}`"
+            );
+        }
+        assert!(
+            parse_pinned_config_keys(" , ")
+                .expect("should accept an empty list")
+                .is_empty(),
+            "should ignore empty entries"
+        );
+    }
+}
@@ -5,6 +5,11 @@ pub const COOKIE_TS_EC: &str = "ts-ec";
 /// JSON array of Extended User IDs (`[{ source, uids }]`) from identity providers.
 pub const COOKIE_TS_EIDS: &str = "ts-eids";
 pub const COOKIE_TS_TESTER: &str = "ts-tester";
+/// JS-readable cookie listing the request's experiment arms as
+/// `experiment:arm` pairs joined by `|`.
+pub const COOKIE_TS_EXPERIMENT_ARMS: &str = "ts-exp";
+/// Random unit ID for experiments with `assignment = "cookie"`.
+pub const COOKIE_TS_EXPERIMENT_ID: &str = "ts-exp-id";
 pub const COOKIE_SHAREDID: &str = "sharedId";
 
 pub const HEADER_X_PUB_USER_ID: HeaderName = HeaderName::from_static("x-pub-user-id");
@@ -194,13 +194,21 @@ pub(super) fn build_proxy_url(settings: &Settings, clear_url: &str, base_origin:
     )
 }
 
+/// Build a signed first-party proxy URL whose signature also covers `extra`
+/// query pairs. See [`build_proxy_url`] for the origin semantics.
 #[inline]
 pub(super) fn build_proxy_url_with_extras(
     settings: &Settings,
     clear_url: &str,
+    base_origin: &str,
     extra: &[(String, String)],
 ) -> String {
-    build_signed_url_for(settings, clear_url, "/first-party/proxy", extra)
+    build_signed_url_for(
+        settings,
+        clear_url,
+        &format!("{base_origin}/first-party/proxy"),
+        extra,
+    )
 }
 
 /// Build a signed first-party click URL, prefixing `base_origin` before the
@@ -517,7 +525,7 @@ pub fn sanitize_creative_html(markup: &str) -> String {
 /// mode, input over the 1 MiB per-creative cap is rejected (empty string).
 #[must_use]
 pub(crate) fn process_auction_creative(settings: &Settings, raw: &str) -> String {
-    process_auction_creative_with_rewriter(settings, raw, |sanitized| {
+    process_auction_creative_with_rewriter(settings, raw, "", |sanitized| {
         rewrite_creative_html(settings, sanitized)
     })
 }
@@ -534,7 +542,7 @@ pub(crate) fn process_inline_auction_creative(
     base_origin: &str,
     raw: &str,
 ) -> String {
-    process_auction_creative_with_rewriter(settings, raw, |sanitized| {
+    process_auction_creative_with_rewriter(settings, raw, base_origin, |sanitized| {
         rewrite_inline_creative_html(settings, base_origin, sanitized)
     })
 }
@@ -542,6 +550,7 @@ pub(crate) fn process_inline_auction_creative(
 fn process_auction_creative_with_rewriter(
     settings: &Settings,
     raw: &str,
+    base_origin: &str,
     rewrite: impl FnOnce(&str) -> String,
 ) -> String {
     // The per-creative size cap is a delivery invariant, not a sanitizer
@@ -557,6 +566,15 @@ fn process_auction_creative_with_rewriter(
         );
         return String::new();
     }
+    // Video bids carry a VAST document in `adm`. The HTML sanitizer would
+    // destroy it, so VAST is only ever rewritten, never sanitized.
+    if crate::vast::is_vast_document(raw) {
+        return if settings.auction.rewrite_creatives {
+            crate::vast::rewrite_vast(settings, raw, base_origin, 0)
+        } else {
+            raw.to_owned()
+        };
+    }
     let sanitized = if settings.auction.sanitize_creatives {
         sanitize_creative_html(raw)
     } else {
@@ -638,10 +656,11 @@ const AUCTION_PRICE_MACRO: &str = "${AUCTION_PRICE}";
 /// URL and never resolve to a price.
 ///
 /// Only the exact `${AUCTION_PRICE}` token is expanded. The encrypted
-/// `${AUCTION_PRICE:B64}` variant requires the DSP's key and is left intact; the
-/// full-token match cannot corrupt it because it lacks the closing brace the
-/// clear token ends with. `cpm` is formatted with its shortest round-trip
-/// representation, preserving the exact value without inventing precision.
+/// `${AUCTION_PRICE:B64}` variant is left intact (see
+/// [`expand_encrypted_auction_price_macro`]); the full-token match cannot
+/// corrupt it because it lacks the closing brace the clear token ends with.
+/// `cpm` is formatted with its shortest round-trip representation, preserving
+/// the exact value without inventing precision.
 #[must_use]
 pub fn expand_auction_price_macro(markup: &str, cpm: f64) -> String {
     if !markup.contains(AUCTION_PRICE_MACRO) {
@@ -650,6 +669,23 @@ pub fn expand_auction_price_macro(markup: &str, cpm: f64) -> String {
     markup.replace(AUCTION_PRICE_MACRO, &cpm.to_string())
 }
 
+/// The encrypted auction price macro, expanded with a per-bidder ciphertext.
+pub const ENCRYPTED_AUCTION_PRICE_MACRO: &str = "${AUCTION_PRICE:B64}";
+
+/// Substitute the `${AUCTION_PRICE:B64}` macro with an encrypted price token.
+///
+/// `encrypted_price` is produced by
+/// [`crate::auction::price_encryption::PriceEncryptionKeys::encrypt_cpm`] with
+/// the winning bidder's keys. The token is web-safe base64, so it needs no
+/// further escaping in URLs or markup.
+#[must_use]
+pub fn expand_encrypted_auction_price_macro(markup: &str, encrypted_price: &str) -> String {
+    if !markup.contains(ENCRYPTED_AUCTION_PRICE_MACRO) {
+        return markup.to_owned();
+    }
+    markup.replace(ENCRYPTED_AUCTION_PRICE_MACRO, encrypted_price)
+}
+
 /// Shared creative rewriter. `base_origin` is prefixed onto first-party proxy and
 /// click paths (empty for root-relative, `https://<domain>` for absolute);
 /// `inject_tsjs` controls the `<body>` tsjs bundle injection; `max_output_size`
@@ -1135,14 +1171,27 @@ mod tests {
     #[test]
     fn expand_auction_price_leaves_encrypted_variant_untouched() {
         use super::expand_auction_price_macro;
-        // The `:B64` encrypted variant requires the DSP key we do not hold; only
-        // the clear-price token is expanded, and the full-token match must not
-        // corrupt the encrypted one.
+        // The `:B64` encrypted variant is expanded separately with the DSP's
+        // keys; only the clear-price token is expanded here, and the full-token
+        // match must not corrupt the encrypted one.
         let out = expand_auction_price_macro("a=${AUCTION_PRICE}&b=${AUCTION_PRICE:B64}", 1.25);
         assert!(out.contains("a=1.25&"), "{out}");
         assert!(out.contains("b=${AUCTION_PRICE:B64}"), "{out}");
     }
 
+    #[test]
+    fn expand_encrypted_auction_price_leaves_clear_variant_untouched() {
+        use super::expand_encrypted_auction_price_macro;
+        let out = expand_encrypted_auction_price_macro(
+            "a=${AUCTION_PRICE}&b=${AUCTION_PRICE:B64}",
+            "OG46wAAMCggBI0VniavN7860zmbksILKzhZUCA",
+        );
+        assert_eq!(
+            out, "a=${AUCTION_PRICE}&b=OG46wAAMCggBI0VniavN7860zmbksILKzhZUCA",
+            "should replace only the encrypted token"
+        );
+    }
+
     #[test]
     fn inline_rewrite_emits_absolute_urls_and_omits_tsjs() {
         // The inline creative renders in a foreign origin (PUC's srcdoc under
@@ -1726,6 +1775,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn process_auction_creative_rewrites_vast_without_sanitizing() {
+        let mut settings = crate::test_support::tests::create_test_settings();
+        settings.auction.sanitize_creatives = true;
+        settings.auction.rewrite_creatives = true;
+        let vast = r#"<VAST version="4.0"><Ad><InLine><Impression><![CDATA[https://track.example/imp]]></Impression><Creatives><Creative><Linear><MediaFiles><MediaFile><![CDATA[https://cdn.example/video.mp4]]></MediaFile></MediaFiles></Linear></Creative></Creatives></InLine></Ad></VAST>"#;
+
+        let processed = process_auction_creative(&settings, vast);
+
+        assert!(
+            processed.starts_with(r#"<VAST version="4.0">"#),
+            "should keep the VAST document intact: {processed}"
+        );
+        assert!(
+            processed.contains("/first-party/proxy?tsurl="),
+            "should rewrite VAST media and tracking URLs: {processed}"
+        );
+        assert!(
+            !processed.contains("https://cdn.example/video.mp4"),
+            "should not leave the media file URL direct: {processed}"
+        );
+    }
+
     #[test]
     fn process_auction_creative_rewrites_raw_markup_without_sanitizing() {
         // The fourth mode: rewriting enabled, sanitization disabled. Eligible
@@ -0,0 +1,548 @@
+//! KV-backed cache of rewritten creative assets served by `/first-party/proxy`.
+//!
+//! High-volume campaigns fetch the same creative HTML, CSS, VAST and JavaScript
+//! millions of times. The cache keeps two kinds of entries:
+//!
+//! | Entry | Key                                          | Value                          |
+//! | ----- | -------------------------------------------- | ------------------------------ |
+//! | URL   | signed target URL and VAST wrapper depth     | content hash of upstream bytes |
+//! | Body  | content hash and VAST wrapper depth          | rewritten bytes and headers    |
+//!
+//! A URL hit serves the rewritten body without contacting upstream. On a URL
+//! miss the proxy still fetches, but byte-identical upstream responses behind
+//! different URLs share one body entry, so each is rewritten only once.
+//!
+//! Both keys embed [`crate::publisher::template_fingerprint`] — a digest of the
+//! full settings and the compiled tsjs bundle — and
+//! [`CREATIVE_CACHE_SCHEMA_VERSION`]. A config change or a rewriter change
+//! therefore misses instead of serving bytes produced under the old rules.
+//!
+//! Entry lifetimes come from the upstream `Cache-Control` header (see
+//! [`cacheable_lifetime`]), capped at `proxy.creative_cache.max_ttl_secs`. KV
+//! failures are logged and treated as misses; the cache never fails a request.
+
+use std::time::Duration;
+
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use bytes::Bytes;
+use edgezero_core::body::Body as EdgeBody;
+use http::{HeaderMap, HeaderValue, Response, StatusCode, header};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest as _, Sha256};
+use web_time::{SystemTime, UNIX_EPOCH};
+
+use crate::platform::PlatformKvStore;
+use crate::settings::Settings;
+
+/// Version of the cached entry layout and of the creative rewriters.
+///
+/// Bump on any change to what the HTML, CSS or VAST rewriters emit that is not
+/// already captured by the settings fingerprint.
+pub const CREATIVE_CACHE_SCHEMA_VERSION: u32 = 1;
+
+/// Rewritten bodies larger than this are served but not cached.
+const MAX_CACHED_BODY_SIZE: usize = 2 * 1024 * 1024;
+
+const URL_KEY_PREFIX: &str = "creative-url:";
+const BODY_KEY_PREFIX: &str = "creative-body:";
+
+/// Returns `true` for content types the cache holds: the rewritten creative
+/// types plus JavaScript, which passes through unchanged.
+#[must_use]
+pub fn is_cacheable_content_type(content_type: &str) -> bool {
+    let ct = content_type.to_ascii_lowercase();
+    ct.contains("text/html")
+        || ct.contains("text/css")
+        || ct.starts_with("application/xml")
+        || ct.starts_with("text/xml")
+        || ct.contains("javascript")
+}
+
+/// How long a response may be cached, derived from its upstream headers.
+///
+/// Returns [`None`] unless the response is a `200` whose `Cache-Control`
+/// grants a positive `s-maxage` or `max-age` (in that order of precedence)
+/// without `private`, `no-store` or `no-cache`. Responses that set cookies or
+/// vary on anything other than `Accept-Encoding` are personalised and never
+/// cached. An `Age` header shortens the lifetime, and `max_ttl_secs` caps it.
+#[must_use]
+pub fn cacheable_lifetime(
+    status: StatusCode,
+    headers: &HeaderMap,
+    max_ttl_secs: u64,
+) -> Option<Duration> {
+    if status != StatusCode::OK || headers.contains_key(header::SET_COOKIE) {
+        return None;
+    }
+
+    for vary in headers.get_all(header::VARY) {
+        let vary = vary.to_str().ok()?;
+        if vary
+            .split(',')
+            .map(str::trim)
+            .any(|name| !name.is_empty() && !name.eq_ignore_ascii_case("accept-encoding"))
+        {
+            return None;
+        }
+    }
+
+    let mut max_age = None;
+    let mut s_maxage = None;
+    for value in headers.get_all(header::CACHE_CONTROL) {
+        for directive in value.to_str().ok()?.split(',') {
+            let directive = directive.trim().to_ascii_lowercase();
+            let (name, arg) = match directive.split_once('=') {
+                Some((name, arg)) => (name.trim(), Some(arg.trim().trim_matches('"'))),
+                None => (directive.as_str(), None),
+            };
+            match name {
+                "private" | "no-store" | "no-cache" => return None,
+                "max-age" => max_age = arg.and_then(|v| v.parse::<u64>().ok()),
+                "s-maxage" => s_maxage = arg.and_then(|v| v.parse::<u64>().ok()),
+                _ => {}
+            }
+        }
+    }
+
+    let age = headers
+        .get(header::AGE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .unwrap_or(0);
+    let lifetime = s_maxage.or(max_age)?.saturating_sub(age).min(max_ttl_secs);
+    (lifetime > 0).then(|| Duration::from_secs(lifetime))
+}
+
+/// Hash identifying upstream creative bytes.
+///
+/// The content type is part of the hash because the same bytes are rewritten
+/// differently as HTML, CSS or XML.
+#[must_use]
+pub fn creative_content_hash(content_type: &str, body: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    update_length_prefixed(&mut hasher, content_type.to_ascii_lowercase().as_bytes());
+    update_length_prefixed(&mut hasher, body);
+    hex::encode(hasher.finalize())
+}
+
+/// A rewritten creative ready to be served.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedCreative {
+    /// `Content-Type` of the rewritten body.
+    pub content_type: String,
+    /// `Content-Encoding` of the rewritten body, when it is still compressed.
+    pub content_encoding: Option<String>,
+    /// Rewritten body bytes.
+    pub body: Bytes,
+}
+
+impl CachedCreative {
+    /// Capture a finalized proxy response's body and representation headers.
+    #[must_use]
+    pub fn from_headers(headers: &HeaderMap, body: Bytes) -> Self {
+        let header_string = |name: header::HeaderName| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        Self {
+            content_type: header_string(header::CONTENT_TYPE).unwrap_or_default(),
+            content_encoding: header_string(header::CONTENT_ENCODING),
+            body,
+        }
+    }
+
+    /// Build the `200` response served on a cache hit.
+    ///
+    /// `remaining` becomes the response `max-age`, so browsers never keep a
+    /// creative longer than upstream allowed.
+    #[must_use]
+    pub fn into_response(self, remaining: Duration) -> Response<EdgeBody> {
+        let mut response = Response::new(EdgeBody::from(self.body.to_vec()));
+        let headers = response.headers_mut();
+        if let Ok(value) = HeaderValue::from_str(&self.content_type) {
+            headers.insert(header::CONTENT_TYPE, value);
+        }
+        if let Some(value) = self
+            .content_encoding
+            .as_deref()
+            .and_then(|v| HeaderValue::from_str(v).ok())
+        {
+            headers.insert(header::CONTENT_ENCODING, value);
+        }
+        if let Ok(value) = HeaderValue::from_str(&format!("max-age={}", remaining.as_secs())) {
+            headers.insert(header::CACHE_CONTROL, value);
+        }
+        response
+    }
+}
+
+/// URL entry: points a signed target at the body entry it last resolved to.
+#[derive(Debug, Serialize, Deserialize)]
+struct UrlEntry {
+    content_hash: String,
+    expires_at: u64,
+}
+
+/// Body entry: the rewritten creative, base64-encoded inside JSON.
+#[derive(Debug, Serialize, Deserialize)]
+struct BodyEntry {
+    content_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    content_encoding: Option<String>,
+    body: String,
+    expires_at: u64,
+}
+
+/// Read and write access to the creative cache for one request.
+pub struct CreativeCache<'a> {
+    store: &'a dyn PlatformKvStore,
+    fingerprint: String,
+}
+
+impl<'a> CreativeCache<'a> {
+    /// Bind the cache to `store` under the rewrite rules `settings` produce.
+    #[must_use]
+    pub fn new(settings: &Settings, store: &'a dyn PlatformKvStore) -> Self {
+        Self {
+            store,
+            fingerprint: crate::publisher::template_fingerprint(settings),
+        }
+    }
+
+    /// Look up the rewritten creative last served for `target_url`.
+    ///
+    /// Returns the creative and its remaining lifetime, or [`None`] when either
+    /// entry is missing, expired or unreadable.
+    pub async fn get_by_url(
+        &self,
+        target_url: &str,
+        vast_wrapper_depth: u8,
+    ) -> Option<(CachedCreative, Duration)> {
+        let key = self.url_key(target_url, vast_wrapper_depth);
+        let entry: UrlEntry = self.read(&key).await?;
+        remaining(entry.expires_at)?;
+        self.get_by_hash(&entry.content_hash, vast_wrapper_depth)
+            .await
+    }
+
+    /// Look up the rewritten creative for upstream bytes hashing to `content_hash`.
+    pub async fn get_by_hash(
+        &self,
+        content_hash: &str,
+        vast_wrapper_depth: u8,
+    ) -> Option<(CachedCreative, Duration)> {
+        let key = self.body_key(content_hash, vast_wrapper_depth);
+        let entry: BodyEntry = self.read(&key).await?;
+        let left = remaining(entry.expires_at)?;
+        let body = match STANDARD.decode(entry.body.as_bytes()) {
+            Ok(body) => Bytes::from(body),
+            Err(e) => {
+                log::warn!("creative cache: undecodable body entry {key}: {e}");
+                return None;
+            }
+        };
+        Some((
+            CachedCreative {
+                content_type: entry.content_type,
+                content_encoding: entry.content_encoding,
+                body,
+            },
+            left,
+        ))
+    }
+
+    /// Point `target_url` at the body entry for `content_hash`.
+    pub async fn put_url(
+        &self,
+        target_url: &str,
+        vast_wrapper_depth: u8,
+        content_hash: &str,
+        ttl: Duration,
+    ) {
+        let key = self.url_key(target_url, vast_wrapper_depth);
+        let entry = UrlEntry {
+            content_hash: content_hash.to_string(),
+            expires_at: expires_at(ttl),
+        };
+        self.write(&key, &entry, ttl).await;
+    }
+
+    /// Store a rewritten creative and point `target_url` at it.
+    pub async fn put(
+        &self,
+        target_url: &str,
+        vast_wrapper_depth: u8,
+        content_hash: &str,
+        ttl: Duration,
+        creative: &CachedCreative,
+    ) {
+        if creative.body.len() > MAX_CACHED_BODY_SIZE {
+            log::debug!(
+                "creative cache: {} byte body exceeds {} byte cap; not caching",
+                creative.body.len(),
+                MAX_CACHED_BODY_SIZE
+            );
+            return;
+        }
+        let key = self.body_key(content_hash, vast_wrapper_depth);
+        let entry = BodyEntry {
+            content_type: creative.content_type.clone(),
+            content_encoding: creative.content_encoding.clone(),
+            body: STANDARD.encode(&creative.body),
+            expires_at: expires_at(ttl),
+        };
+        self.write(&key, &entry, ttl).await;
+        self.put_url(target_url, vast_wrapper_depth, content_hash, ttl)
+            .await;
+    }
+
+    fn url_key(&self, target_url: &str, vast_wrapper_depth: u8) -> String {
+        format!(
+            "{URL_KEY_PREFIX}{}",
+            self.digest(&[target_url.as_bytes(), &[vast_wrapper_depth]])
+        )
+    }
+
+    fn body_key(&self, content_hash: &str, vast_wrapper_depth: u8) -> String {
+        format!(
+            "{BODY_KEY_PREFIX}{}",
+            self.digest(&[content_hash.as_bytes(), &[vast_wrapper_depth]])
+        )
+    }
+
+    fn digest(&self, parts: &[&[u8]]) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(CREATIVE_CACHE_SCHEMA_VERSION.to_be_bytes());
+        update_length_prefixed(&mut hasher, self.fingerprint.as_bytes());
+        for part in parts {
+            update_length_prefixed(&mut hasher, part);
+        }
+        hex::encode(hasher.finalize())
+    }
+
+    async fn read<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let bytes = match self.store.get_bytes(key).await {
+            Ok(Some(bytes)) => bytes,
+            Ok(None) => return None,
+            Err(e) => {
+                log::debug!("creative cache: lookup of {key} failed: {e}");
+                return None;
+            }
+        };
+        serde_json::from_slice(&bytes)
+            .inspect_err(|e| log::warn!("creative cache: invalid entry {key}: {e}"))
+            .ok()
+    }
+
+    async fn write<T: Serialize>(&self, key: &str, entry: &T, ttl: Duration) {
+        let body = match serde_json::to_vec(entry) {
+            Ok(body) => body,
+            Err(e) => {
+                log::warn!("creative cache: failed to serialize {key}: {e}");
+                return;
+            }
+        };
+        if let Err(e) = self
+            .store
+            .put_bytes_with_ttl(key, Bytes::from(body), ttl)
+            .await
+        {
+            log::debug!("creative cache: write of {key} failed: {e}");
+        }
+    }
+}
+
+fn update_length_prefixed(hasher: &mut Sha256, bytes: &[u8]) {
+    hasher.update((bytes.len() as u64).to_be_bytes());
+    hasher.update(bytes);
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn expires_at(ttl: Duration) -> u64 {
+    now_secs().saturating_add(ttl.as_secs())
+}
+
+/// Remaining lifetime of an entry, or [`None`] once it has expired. Checked on
+/// read because not every platform KV store enforces TTLs.
+fn remaining(expires_at: u64) -> Option<Duration> {
+    let left = expires_at.checked_sub(now_secs())?;
+    (left > 0).then(|| Duration::from_secs(left))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::test_support::InMemoryKvStore;
+    use crate::test_support::tests::create_test_settings;
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.append(*name, HeaderValue::from_static(value));
+        }
+        map
+    }
+
+    #[test]
+    fn cacheable_lifetime_prefers_s_maxage_and_applies_age_and_cap() {
+        let h = headers(&[
+            ("cache-control", "public, max-age=60, s-maxage=600"),
+            ("age", "100"),
+        ]);
+        assert_eq!(
+            cacheable_lifetime(StatusCode::OK, &h, 86_400),
+            Some(Duration::from_secs(500)),
+            "should use s-maxage minus age"
+        );
+        assert_eq!(
+            cacheable_lifetime(StatusCode::OK, &h, 30),
+            Some(Duration::from_secs(30)),
+            "should cap the lifetime at max_ttl_secs"
+        );
+    }
+
+    #[test]
+    fn cacheable_lifetime_rejects_personalised_or_uncacheable_responses() {
+        let cases = [
+            headers(&[("cache-control", "private, max-age=600")]),
+            headers(&[("cache-control", "no-store")]),
+            headers(&[("cache-control", "max-age=0")]),
+            headers(&[]),
+            headers(&[("cache-control", "max-age=600"), ("set-cookie", "a=1")]),
+            headers(&[("cache-control", "max-age=600"), ("vary", "Cookie")]),
+        ];
+        for h in &cases {
+            assert_eq!(
+                cacheable_lifetime(StatusCode::OK, h, 86_400),
+                None,
+                "should not cache {h:?}"
+            );
+        }
+
+        let ok = headers(&[
+            ("cache-control", "max-age=600"),
+            ("vary", "Accept-Encoding"),
+        ]);
+        assert_eq!(
+            cacheable_lifetime(StatusCode::NOT_FOUND, &ok, 86_400),
+            None,
+            "should only cache 200 responses"
+        );
+        assert!(
+            cacheable_lifetime(StatusCode::OK, &ok, 86_400).is_some(),
+            "should allow Vary: Accept-Encoding"
+        );
+    }
+
+    #[test]
+    fn content_hash_depends_on_content_type() {
+        assert_ne!(
+            creative_content_hash("text/html", b"body{}"),
+            creative_content_hash("text/css", b"body{}"),
+            "should hash the content type with the bytes"
+        );
+        assert_eq!(
+            creative_content_hash("TEXT/HTML", b"x"),
+            creative_content_hash("text/html", b"x"),
+            "should ignore content type case"
+        );
+    }
+
+    #[test]
+    fn identical_bytes_share_one_body_entry_across_urls() {
+        futures::executor::block_on(async {
+            let settings = create_test_settings();
+            let store = InMemoryKvStore::new();
+            let cache = CreativeCache::new(&settings, &store);
+            let hash = creative_content_hash("text/html", b"<p>ad</p>");
+            let creative = CachedCreative {
+                content_type: "text/html; charset=utf-8".to_string(),
+                content_encoding: None,
+                body: Bytes::from_static(b"<p>rewritten</p>"),
+            };
+
+            cache
+                .put(
+                    "https://cdn.example/a.html",
+                    0,
+                    &hash,
+                    Duration::from_secs(60),
+                    &creative,
+                )
+                .await;
+            cache
+                .put_url(
+                    "https://cdn.example/b.html",
+                    0,
+                    &hash,
+                    Duration::from_secs(60),
+                )
+                .await;
+
+            let bodies = store
+                .keys()
+                .into_iter()
+                .filter(|key| key.starts_with(BODY_KEY_PREFIX))
+                .count();
+            assert_eq!(bodies, 1, "should store the rewritten body once");
+
+            let (hit, left) = cache
+                .get_by_url("https://cdn.example/b.html", 0)
+                .await
+                .expect("should resolve the second URL to the shared body");
+            assert_eq!(hit, creative, "should return the stored creative");
+            assert!(left <= Duration::from_secs(60), "should not extend the TTL");
+            assert!(
+                cache
+                    .get_by_url("https://cdn.example/b.html", 1)
+                    .await
+                    .is_none(),
+                "should key entries by VAST wrapper depth"
+            );
+        });
+    }
+
+    #[test]
+    fn settings_change_invalidates_entries() {
+        futures::executor::block_on(async {
+            let settings = create_test_settings();
+            let store = InMemoryKvStore::new();
+            let creative = CachedCreative {
+                content_type: "text/css; charset=utf-8".to_string(),
+                content_encoding: Some("gzip".to_string()),
+                body: Bytes::from_static(b"compressed"),
+            };
+            CreativeCache::new(&settings, &store)
+                .put(
+                    "https://cdn.example/a.css",
+                    0,
+                    "hash",
+                    Duration::from_secs(60),
+                    &creative,
+                )
+                .await;
+
+            let mut changed = create_test_settings();
+            changed
+                .proxy
+                .allowed_domains
+                .push("new.example".to_string());
+            assert!(
+                CreativeCache::new(&changed, &store)
+                    .get_by_url("https://cdn.example/a.css", 0)
+                    .await
+                    .is_none(),
+                "should miss after the rewrite rules change"
+            );
+        });
+    }
+}
@@ -10,7 +10,7 @@ use serde::{Deserialize, Serialize};
 
 use glob::Pattern;
 
-use crate::auction::types::{AdFormat, AdSlot, MediaType};
+use crate::auction::types::{AdFormat, AdSlot, MediaType, SlotPmp};
 use crate::price_bucket::PriceGranularity;
 use crate::settings::vec_from_seq_or_map;
 
@@ -545,6 +545,22 @@ pub struct CreativeOpportunitySlot {
     /// Provider-specific slot identifiers.
     #[serde(default)]
     pub providers: SlotProviders,
+    /// Private marketplace and programmatic guaranteed deals for this slot.
+    ///
+    /// Forwarded to bidders as `imp.pmp`. Omitted from serialized config when
+    /// unset so an older `deny_unknown_fields` binary still loads it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pmp: Option<SlotPmp>,
+    /// Defer this slot's auction until it approaches the viewport.
+    ///
+    /// Lazy slots are still rendered into the page's slot definitions but are
+    /// left out of the document-level auction; tsjs auctions them through
+    /// `POST /_ts/api/v1/slot-auction` once they scroll near the viewport.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub lazy_load: bool,
+    /// In-page refresh policy. Refresh auctions are refused when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh: Option<SlotRefresh>,
     /// Pre-compiled [`page_patterns`](Self::page_patterns) for hot-path matching.
     ///
     /// Populated by [`compile_patterns`](Self::compile_patterns) once at startup
@@ -620,6 +636,14 @@ impl CreativeOpportunitySlot {
             ));
         }
 
+        if let Some(pmp) = &self.pmp {
+            pmp.validate_runtime(&self.id)?;
+        }
+
+        if let Some(refresh) = &self.refresh {
+            refresh.validate_runtime(&self.id)?;
+        }
+
         // An explicit empty/whitespace `div_id` override is rejected: the
         // injected JS resolves slots with `candidate.id.startsWith(slot.div_id)`,
         // and every element id starts with the empty string, so an empty override
@@ -894,6 +918,7 @@ impl CreativeOpportunitySlot {
                 .map(|(k, v)| (k.clone(), serde_json::Value::String(v.clone())))
                 .collect(),
             bidders,
+            pmp: self.pmp.clone(),
         }
     }
 }
@@ -931,6 +956,81 @@ impl CreativeOpportunityFormat {
     }
 }
 
+/// Refresh policy for a [`CreativeOpportunitySlot`].
+///
+/// tsjs re-auctions the slot through `POST /_ts/api/v1/slot-auction` when its
+/// refresh timer fires; the endpoint re-checks every limit here before running
+/// the auction, so a misbehaving client cannot refresh faster or more often
+/// than configured.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct SlotRefresh {
+    /// Minimum seconds between two auctions for the slot. At least
+    /// [`MIN_SLOT_REFRESH_INTERVAL_SECS`].
+    pub interval_secs: u32,
+    /// Maximum number of refresh auctions per page view.
+    pub max_refreshes: u32,
+    /// Only refresh once the current creative has been viewable.
+    #[serde(default = "default_require_viewable")]
+    pub require_viewable: bool,
+}
+
+/// Shortest refresh interval a slot may configure.
+///
+/// Matches the 30-second floor GAM and most SSPs require for refreshed
+/// inventory; faster refresh is routinely flagged as invalid traffic.
+pub const MIN_SLOT_REFRESH_INTERVAL_SECS: u32 = 30;
+
+const fn default_require_viewable() -> bool {
+    true
+}
+
+impl SlotRefresh {
+    fn validate_runtime(&self, slot_id: &str) -> Result<(), String> {
+        if self.interval_secs < MIN_SLOT_REFRESH_INTERVAL_SECS {
+            return Err(format!(
+                "slot `{slot_id}` refresh.interval_secs must be at least \
+                 {MIN_SLOT_REFRESH_INTERVAL_SECS}, got {}",
+                self.interval_secs
+            ));
+        }
+        if self.max_refreshes == 0 {
+            return Err(format!(
+                "slot `{slot_id}` refresh.max_refreshes must be greater than 0; \
+                 remove the refresh table to disable refresh"
+            ));
+        }
+        Ok(())
+    }
+
+    /// Checks a client-reported refresh attempt against this policy.
+    ///
+    /// `refresh_count` is the 1-based index of the refresh being requested,
+    /// `elapsed_ms` the time since the slot's previous auction, and `viewable`
+    /// whether the creative currently in the slot reached viewability.
+    ///
+    /// # Errors
+    ///
+    /// Returns the rejection reason label reported back to the client.
+    pub fn admits(
+        &self,
+        refresh_count: u32,
+        elapsed_ms: u64,
+        viewable: bool,
+    ) -> Result<(), &'static str> {
+        if refresh_count == 0 || refresh_count > self.max_refreshes {
+            return Err("max_refreshes");
+        }
+        if elapsed_ms < u64::from(self.interval_secs) * 1000 {
+            return Err("interval");
+        }
+        if self.require_viewable && !viewable {
+            return Err("not_viewable");
+        }
+        Ok(())
+    }
+}
+
 /// Provider-specific slot identifiers for a [`CreativeOpportunitySlot`].
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
@@ -1028,6 +1128,9 @@ mod tests {
             floor_price: Some(0.50),
             targeting: Default::default(),
             providers: Default::default(),
+            pmp: None,
+            lazy_load: false,
+            refresh: None,
             compiled_patterns: Vec::new(),
             compiled_unit: None,
         }
@@ -1812,6 +1915,81 @@ mod tests {
         );
     }
 
+    #[test]
+    fn validate_runtime_rejects_refresh_faster_than_the_minimum_interval() {
+        let mut slot: CreativeOpportunitySlot = toml::from_str(
+            r#"
+id = "sidebar"
+page_patterns = ["/"]
+formats = [{ width = 300, height = 600 }]
+lazy_load = true
+
+[refresh]
+interval_secs = 15
+max_refreshes = 3
+"#,
+        )
+        .expect("should parse slot with refresh");
+        slot.compile_patterns();
+
+        assert!(slot.lazy_load, "should parse lazy_load");
+        let refresh = slot.refresh.clone().expect("should parse refresh");
+        assert!(
+            refresh.require_viewable,
+            "require_viewable should default to true"
+        );
+        assert!(
+            slot.validate_runtime().is_err(),
+            "an interval under {MIN_SLOT_REFRESH_INTERVAL_SECS}s should fail validation"
+        );
+
+        slot.refresh = Some(SlotRefresh {
+            interval_secs: MIN_SLOT_REFRESH_INTERVAL_SECS,
+            max_refreshes: 0,
+            require_viewable: true,
+        });
+        assert!(
+            slot.validate_runtime().is_err(),
+            "zero max_refreshes should fail validation"
+        );
+
+        slot.refresh = Some(SlotRefresh {
+            interval_secs: MIN_SLOT_REFRESH_INTERVAL_SECS,
+            max_refreshes: 3,
+            require_viewable: true,
+        });
+        assert!(
+            slot.validate_runtime().is_ok(),
+            "a refresh at the minimum interval should pass validation"
+        );
+    }
+
+    #[test]
+    fn slot_refresh_admits_only_attempts_within_policy() {
+        let refresh = SlotRefresh {
+            interval_secs: 30,
+            max_refreshes: 2,
+            require_viewable: true,
+        };
+
+        assert_eq!(refresh.admits(1, 30_000, true), Ok(()));
+        assert_eq!(refresh.admits(2, 45_000, true), Ok(()));
+        assert_eq!(refresh.admits(0, 30_000, true), Err("max_refreshes"));
+        assert_eq!(refresh.admits(3, 30_000, true), Err("max_refreshes"));
+        assert_eq!(refresh.admits(1, 29_999, true), Err("interval"));
+        assert_eq!(refresh.admits(1, 30_000, false), Err("not_viewable"));
+
+        let unconditional = SlotRefresh {
+            require_viewable: false,
+            ..refresh
+        };
+        assert_eq!(
+            unconditional.admits(1, 30_000, false),
+            Ok(()),
+            "should refresh unviewed slots when viewability is not required"
+        );
+    }
+
     #[test]
     fn validate_runtime_rejects_invalid_floor_prices() {
         let mut slot = make_slot("atf", vec!["/"]);
@@ -1861,6 +2039,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn to_ad_slot_forwards_pmp_deals_parsed_from_toml() {
+        let slot: CreativeOpportunitySlot = toml::from_str(
+            r#"
+id = "atf"
+page_patterns = ["/"]
+formats = [{ width = 300, height = 250 }]
+
+[pmp]
+private_auction = true
+
+[[pmp.deals]]
+id = "pg-deal-1"
+bidfloor = 12.5
+wseat = ["seat-a"]
+"#,
+        )
+        .expect("should parse slot with pmp");
+
+        let ad_slot = slot.to_ad_slot();
+
+        let pmp = ad_slot.pmp.expect("should forward pmp");
+        assert!(pmp.private_auction);
+        assert_eq!(pmp.deals.len(), 1);
+        assert_eq!(pmp.deals[0].id, "pg-deal-1");
+        assert_eq!(pmp.deals[0].bidfloor, Some(12.5));
+        assert_eq!(pmp.deals[0].wseat, vec!["seat-a".to_string()]);
+    }
+
     #[test]
     fn to_ad_slot_sets_floor_price_and_formats() {
         let mut slot = make_slot("atf", vec!["/"]);
@@ -48,6 +48,12 @@ const ADMIN_EC_PATH: &str = "/_ts/admin/ec";
 /// Route used by the request-only EID cookie diagnostic.
 const ADMIN_EIDS_PATH: &str = "/_ts/admin/eids";
 
+/// Route used by the operational metrics scrape.
+const ADMIN_METRICS_PATH: &str = "/_ts/admin/metrics";
+
+/// Route serving the deployed config release manifest.
+const ADMIN_CONFIG_RELEASE_PATH: &str = "/_ts/admin/config/release";
+
 /// Reserved Trusted Server admin prefix.
 ///
 /// Mirrors the documented `^/_ts/admin` basic-auth handler regex, so every
@@ -77,7 +83,11 @@ enum AdminDiagnosticShape {
 }
 
 fn admin_diagnostic_shape(path: &str) -> Option<AdminDiagnosticShape> {
-    if path == ADMIN_EC_PATH || path == ADMIN_EIDS_PATH {
+    if path == ADMIN_EC_PATH
+        || path == ADMIN_EIDS_PATH
+        || path == ADMIN_METRICS_PATH
+        || path == ADMIN_CONFIG_RELEASE_PATH
+    {
         return Some(AdminDiagnosticShape::ValidResource);
     }
 
@@ -794,6 +804,8 @@ mod tests {
             "/_ts/admin/ec".to_owned(),
             format!("/_ts/admin/ec/{ec_id}"),
             "/_ts/admin/eids".to_owned(),
+            "/_ts/admin/metrics".to_owned(),
+            "/_ts/admin/config/release".to_owned(),
         ];
         let methods = [
             http::Method::POST,
@@ -18,6 +18,7 @@ use std::time::Duration;
 use error_stack::{Report, ResultExt};
 
 use crate::error::TrustedServerError;
+use crate::metrics::{METRIC_KV_OPERATIONS, MetricsRegistry};
 
 use super::current_timestamp;
 use super::generation::ec_hash;
@@ -103,6 +104,63 @@ fn apply_partner_id_updates(entry: &mut KvEntry, updates: &[PartnerIdUpdate]) ->
     changed
 }
 
+/// [`EcKvStore`] decorator that counts operations and failures.
+///
+/// Precondition failures are normal CAS control flow and count as `ok`.
+struct MeteredEcKvStore {
+    inner: Arc<dyn EcKvStore>,
+    metrics: Arc<MetricsRegistry>,
+}
+
+impl MeteredEcKvStore {
+    fn observe<T>(
+        &self,
+        op: &'static str,
+        result: Result<T, Report<TrustedServerError>>,
+    ) -> Result<T, Report<TrustedServerError>> {
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        self.metrics.increment(
+            METRIC_KV_OPERATIONS,
+            &[
+                ("store", self.inner.store_name()),
+                ("op", op),
+                ("result", outcome),
+            ],
+        );
+        result
+    }
+}
+
+impl EcKvStore for MeteredEcKvStore {
+    fn store_name(&self) -> &str {
+        self.inner.store_name()
+    }
+
+    fn lookup(&self, key: &str) -> Result<Option<EcKvLookup>, Report<TrustedServerError>> {
+        self.observe("lookup", self.inner.lookup(key))
+    }
+
+    fn insert(
+        &self,
+        key: &str,
+        write: EcKvWrite<'_>,
+    ) -> Result<EcKvWriteOutcome, Report<TrustedServerError>> {
+        self.observe("insert", self.inner.insert(key, write))
+    }
+
+    fn count_keys_with_prefix(
+        &self,
+        prefix: &str,
+        limit: u32,
+    ) -> Result<u32, Report<TrustedServerError>> {
+        self.observe("list", self.inner.count_keys_with_prefix(prefix, limit))
+    }
+
+    fn delete(&self, key: &str) -> Result<(), Report<TrustedServerError>> {
+        self.observe("delete", self.inner.delete(key))
+    }
+}
+
 /// EC identity graph on top of the platform KV store primitives.
 ///
 /// Each EC ID (`{64hex}.{6alnum}`) maps to a JSON-encoded [`KvEntry`]
@@ -132,6 +190,18 @@ impl KvIdentityGraph {
         }
     }
 
+    /// Records every store operation in `metrics` as
+    /// [`METRIC_KV_OPERATIONS`], labelled by store, operation and result.
+    #[must_use]
+    pub fn with_metrics(self, metrics: Arc<MetricsRegistry>) -> Self {
+        Self {
+            store: Arc::new(MeteredEcKvStore {
+                inner: self.store,
+                metrics,
+            }),
+        }
+    }
+
     /// Returns the configured store name.
     #[must_use]
     pub fn store_name(&self) -> &str {
@@ -1039,6 +1109,44 @@ mod tests {
         );
     }
 
+    #[test]
+    fn with_metrics_counts_successful_and_failed_operations() {
+        let metrics = Arc::new(MetricsRegistry::new());
+        let healthy =
+            KvIdentityGraph::new(InMemoryEcKv::new("ec-store")).with_metrics(Arc::clone(&metrics));
+        let failing = KvIdentityGraph::new(crate::ec::kv_backend::test_support::FailingEcKv::new(
+            "broken-store",
+        ))
+        .with_metrics(Arc::clone(&metrics));
+
+        healthy.get("ec-4").expect("should read missing entry");
+        failing
+            .get("ec-4")
+            .expect_err("should surface the store failure");
+
+        assert_eq!(
+            metrics.counter_value(
+                METRIC_KV_OPERATIONS,
+                &[("store", "ec-store"), ("op", "lookup"), ("result", "ok")]
+            ),
+            1,
+            "should count the successful lookup"
+        );
+        assert_eq!(
+            metrics.counter_value(
+                METRIC_KV_OPERATIONS,
+                &[
+                    ("store", "broken-store"),
+                    ("op", "lookup"),
+                    ("result", "error")
+                ]
+            ),
+            1,
+            "should count the failed lookup"
+        );
+        assert_eq!(healthy.store_name(), "ec-store");
+    }
+
     #[test]
     fn apply_partner_id_updates_returns_unchanged_for_empty_updates() {
         let mut entry = live_entry();
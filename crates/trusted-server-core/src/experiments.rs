@@ -0,0 +1,835 @@
+//! Traffic-split experiments over [`Settings`] fields.
+//!
+//! Each `[[experiments]]` entry splits traffic into weighted arms. A user is
+//! assigned deterministically from a unit ID — the EC hash, or the random
+//! `ts-exp-id` cookie — so they stay in the same arm across requests. The
+//! `ts-exp-id` cookie is consent-gated like the EC cookie; see
+//! [`ExperimentAssignment::Cookie`]. An arm's
+//! `overrides` table is deep-merged over the base settings for that request:
+//! tables merge key by key, every other value (including arrays) replaces the
+//! base value.
+//!
+//! The assigned arms are recorded on auction telemetry rows and written to the
+//! JS-readable `ts-exp` cookie, which tsjs exposes as `window.tsjs.experiments`.
+//!
+//! Overrides are applied when settings are loaded for a request, so they take
+//! effect on adapters that build settings per request (Fastly). Adapters that
+//! build settings once per process serve the base settings and log
+//! [`warn_experiments_unsupported`] at startup; `ts config push` rejects
+//! experiments for them.
+
+use cookie::Cookie;
+use error_stack::{Report, ResultExt};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map as JsonMap, Value as JsonValue};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::consent::ConsentContext;
+use crate::constants::{COOKIE_TS_EC, COOKIE_TS_EXPERIMENT_ARMS, COOKIE_TS_EXPERIMENT_ID};
+use crate::ec::consent::{ec_consent_granted, ec_consent_withdrawn};
+use crate::ec::generation::{ec_hash, is_valid_ec_id};
+use crate::error::TrustedServerError;
+use crate::settings::Settings;
+
+/// Maximum age for the experiment cookies (1 year in seconds).
+const COOKIE_MAX_AGE: i32 = 365 * 24 * 60 * 60;
+
+/// Maximum length of experiment and arm IDs.
+const MAX_ID_LEN: usize = 32;
+
+/// Separates `experiment:arm` pairs in [`ExperimentArms::label`].
+const LABEL_PAIR_SEPARATOR: char = '|';
+
+/// One traffic-split experiment.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExperimentConfig {
+    /// Stable experiment ID (`[a-z0-9_-]`, up to 32 characters). Changing it
+    /// reshuffles every user's assignment.
+    pub id: String,
+    /// Disabled experiments assign no one and apply no overrides.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Unit the assignment is derived from.
+    #[serde(default)]
+    pub assignment: ExperimentAssignment,
+    /// Arms traffic is split across, in proportion to their weights.
+    pub arms: Vec<ExperimentArm>,
+}
+
+/// Unit an experiment assigns users by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExperimentAssignment {
+    /// The EC hash. Users without an EC are not enrolled.
+    #[default]
+    Ec,
+    /// A random first-party `ts-exp-id` cookie, set on first visit only when
+    /// consent allows the EC cookie. Until it is set, users with an EC are
+    /// bucketed by an ID derived from it, which becomes their cookie once
+    /// consent allows; users without one get a new ID each request.
+    Cookie,
+}
+
+/// One arm of an [`ExperimentConfig`].
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExperimentArm {
+    /// Arm ID (`[a-z0-9_-]`, up to 32 characters).
+    pub id: String,
+    /// Relative share of traffic.
+    #[serde(default = "default_arm_weight")]
+    pub weight: u32,
+    /// Settings fields this arm overrides, in the `trusted-server.toml` shape.
+    /// Empty for a control arm.
+    #[serde(default, skip_serializing_if = "JsonMap::is_empty")]
+    pub overrides: JsonMap<String, JsonValue>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_arm_weight() -> u32 {
+    1
+}
+
+/// The arm a request was assigned in one experiment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExperimentArmAssignment {
+    /// Experiment ID.
+    pub experiment: String,
+    /// Arm ID.
+    pub arm: String,
+    /// Unit the assignment was derived from.
+    pub assignment: ExperimentAssignment,
+}
+
+/// Arms assigned to the current request, in experiment config order.
+///
+/// Runtime-only: set by [`apply_experiments`] and never serialized.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExperimentArms(Vec<ExperimentArmAssignment>);
+
+impl ExperimentArms {
+    /// Returns whether the request is enrolled in no experiment.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Iterates the assigned arms.
+    pub fn iter(&self) -> impl Iterator<Item = &ExperimentArmAssignment> {
+        self.0.iter()
+    }
+
+    /// Returns `experiment:arm` pairs joined by `|`, or `None` when the
+    /// request is enrolled in no experiment.
+    ///
+    /// This is the `experiment_arms` telemetry column and the `ts-exp` cookie
+    /// value.
+    #[must_use]
+    pub fn label(&self) -> Option<String> {
+        if self.0.is_empty() {
+            return None;
+        }
+        let pairs: Vec<String> = self
+            .0
+            .iter()
+            .map(|entry| format!("{}:{}", entry.experiment, entry.arm))
+            .collect();
+        Some(pairs.join(&LABEL_PAIR_SEPARATOR.to_string()))
+    }
+}
+
+impl FromIterator<ExperimentArmAssignment> for ExperimentArms {
+    fn from_iter<I: IntoIterator<Item = ExperimentArmAssignment>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+/// Unit IDs a request can be assigned by, read from its cookies.
+///
+/// The default value carries no units and enrolls no one.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExperimentUnits {
+    ec_hash: Option<String>,
+    cookie_id: Option<String>,
+    cookie_id_is_new: bool,
+    arms_cookie: Option<String>,
+}
+
+impl ExperimentUnits {
+    /// Reads the EC, `ts-exp-id`, and `ts-exp` cookies from a `Cookie` header
+    /// value. When the request has no valid `ts-exp-id`, derives one from the
+    /// EC, or mints a random one without an EC; it is only set on the
+    /// response when a cookie-assigned experiment enrolls the user and
+    /// consent allows it.
+    #[must_use]
+    pub fn from_cookie_header(cookie_header: Option<&str>) -> Self {
+        let mut ec_hash_value = None;
+        let mut cookie_id = None;
+        let mut arms_cookie = None;
+        for cookie in Cookie::split_parse(cookie_header.unwrap_or_default()).filter_map(Result::ok)
+        {
+            match cookie.name() {
+                COOKIE_TS_EC if is_valid_ec_id(cookie.value()) => {
+                    ec_hash_value = Some(ec_hash(cookie.value()).to_ascii_lowercase());
+                }
+                COOKIE_TS_EXPERIMENT_ID if is_valid_unit_cookie(cookie.value()) => {
+                    cookie_id = Some(cookie.value().to_owned());
+                }
+                COOKIE_TS_EXPERIMENT_ARMS => arms_cookie = Some(cookie.value().to_owned()),
+                _ => {}
+            }
+        }
+        let cookie_id_is_new = cookie_id.is_none();
+        let cookie_id = cookie_id.unwrap_or_else(|| match &ec_hash_value {
+            Some(hash) => unit_cookie_from_ec_hash(hash),
+            None => Uuid::new_v4().simple().to_string(),
+        });
+        Self {
+            ec_hash: ec_hash_value,
+            cookie_id: Some(cookie_id),
+            cookie_id_is_new,
+            arms_cookie,
+        }
+    }
+
+    fn unit(&self, assignment: ExperimentAssignment) -> Option<&str> {
+        match assignment {
+            ExperimentAssignment::Ec => self.ec_hash.as_deref(),
+            ExperimentAssignment::Cookie => self.cookie_id.as_deref(),
+        }
+    }
+}
+
+/// Derives a stable `ts-exp-id` from the EC hash, so a user keeps their arm
+/// when consent later allows the cookie to be set.
+fn unit_cookie_from_ec_hash(ec_hash: &str) -> String {
+    let digest = Sha256::new()
+        .chain_update(COOKIE_TS_EXPERIMENT_ID.as_bytes())
+        .chain_update(b":")
+        .chain_update(ec_hash.as_bytes())
+        .finalize();
+    hex::encode(&digest[..16])
+}
+
+fn is_valid_unit_cookie(value: &str) -> bool {
+    value.len() == 32
+        && value
+            .bytes()
+            .all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+/// Validates `[[experiments]]` structure.
+///
+/// Whether each arm's overrides produce valid settings is checked at deploy
+/// time by [`crate::config::validate_settings_for_deploy`].
+///
+/// # Errors
+///
+/// Returns [`TrustedServerError::Configuration`] for malformed or duplicate
+/// IDs, experiments with fewer than two arms, zero weights, or overrides that
+/// target `experiments` itself.
+pub fn validate_experiments(
+    experiments: &[ExperimentConfig],
+) -> Result<(), Report<TrustedServerError>> {
+    let mut experiment_ids = std::collections::HashSet::new();
+    for experiment in experiments {
+        validate_id("experiments.id", &experiment.id)?;
+        if !experiment_ids.insert(experiment.id.as_str()) {
+            return Err(experiment_error(format!(
+                "experiment `{}` is defined more than once",
+                experiment.id
+            )));
+        }
+        if experiment.arms.len() < 2 {
+            return Err(experiment_error(format!(
+                "experiment `{}` must have at least two arms",
+                experiment.id
+            )));
+        }
+        let mut arm_ids = std::collections::HashSet::new();
+        for arm in &experiment.arms {
+            validate_id("experiments.arms.id", &arm.id)?;
+            if !arm_ids.insert(arm.id.as_str()) {
+                return Err(experiment_error(format!(
+                    "experiment `{}` defines arm `{}` more than once",
+                    experiment.id, arm.id
+                )));
+            }
+            if arm.weight == 0 {
+                return Err(experiment_error(format!(
+                    "experiment `{}` arm `{}` must have a weight of at least 1",
+                    experiment.id, arm.id
+                )));
+            }
+            if arm.overrides.contains_key("experiments") {
+                return Err(experiment_error(format!(
+                    "experiment `{}` arm `{}` must not override experiments",
+                    experiment.id, arm.id
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn validate_id(field: &str, id: &str) -> Result<(), Report<TrustedServerError>> {
+    let valid = !id.is_empty()
+        && id.len() <= MAX_ID_LEN
+        && id
+            .bytes()
+            .all(|b| matches!(b, b'a'..=b'z' | b'0'..=b'9' | b'_' | b'-'));
+    if valid {
+        Ok(())
+    } else {
+        Err(experiment_error(format!(
+            "{field} `{id}` must be 1-{MAX_ID_LEN} characters of [a-z0-9_-]"
+        )))
+    }
+}
+
+/// Assigns the request to one arm of every enabled experiment it has a unit
+/// ID for.
+#[must_use]
+pub fn assign_arms(experiments: &[ExperimentConfig], units: &ExperimentUnits) -> ExperimentArms {
+    experiments
+        .iter()
+        .filter(|experiment| experiment.enabled)
+        .filter_map(|experiment| {
+            let unit = units.unit(experiment.assignment)?;
+            let arm = select_arm(experiment, unit)?;
+            Some(ExperimentArmAssignment {
+                experiment: experiment.id.clone(),
+                arm: arm.id.clone(),
+                assignment: experiment.assignment,
+            })
+        })
+        .collect()
+}
+
+fn select_arm<'a>(experiment: &'a ExperimentConfig, unit: &str) -> Option<&'a ExperimentArm> {
+    let total: u64 = experiment
+        .arms
+        .iter()
+        .map(|arm| u64::from(arm.weight))
+        .sum();
+    if total == 0 {
+        return None;
+    }
+    // Salting with the experiment ID keeps assignments independent across
+    // experiments that share a unit.
+    let digest = Sha256::new()
+        .chain_update(experiment.id.as_bytes())
+        .chain_update(b":")
+        .chain_update(unit.as_bytes())
+        .finalize();
+    let mut prefix = [0_u8; 8];
+    prefix.copy_from_slice(&digest[..8]);
+    let mut point = u64::from_be_bytes(prefix) % total;
+    experiment.arms.iter().find(|arm| {
+        let weight = u64::from(arm.weight);
+        if point < weight {
+            true
+        } else {
+            point -= weight;
+            false
+        }
+    })
+}
+
+/// Returns `settings` with the overrides of the request's assigned arms
+/// applied and [`Settings::experiment_arms`] set.
+///
+/// When the combined overrides do not produce valid settings, logs the error
+/// and returns the base settings unenrolled, so a bad arm never takes the
+/// request down.
+#[must_use]
+pub fn apply_experiments(settings: Settings, units: &ExperimentUnits) -> Settings {
+    let arms = assign_arms(&settings.experiments, units);
+    if arms.is_empty() {
+        return settings;
+    }
+
+    let overrides: Vec<&JsonMap<String, JsonValue>> = arms
+        .iter()
+        .filter_map(|assigned| arm_config(&settings, assigned))
+        .map(|arm| &arm.overrides)
+        .filter(|overrides| !overrides.is_empty())
+        .collect();
+    if overrides.is_empty() {
+        let mut settings = settings;
+        settings.experiment_arms = arms;
+        return settings;
+    }
+
+    match settings_with_overrides(&settings, overrides) {
+        Ok(mut applied) => {
+            applied.experiments = settings.experiments;
+            applied.experiment_arms = arms;
+            applied
+        }
+        Err(report) => {
+            log::warn!(
+                "experiment arms {} rejected; serving base settings: {report:?}",
+                arms.label().unwrap_or_default()
+            );
+            settings
+        }
+    }
+}
+
+fn arm_config<'a>(
+    settings: &'a Settings,
+    assigned: &ExperimentArmAssignment,
+) -> Option<&'a ExperimentArm> {
+    settings
+        .experiments
+        .iter()
+        .find(|experiment| experiment.id == assigned.experiment)?
+        .arms
+        .iter()
+        .find(|arm| arm.id == assigned.arm)
+}
+
+/// Merges `overrides` over `settings` and re-runs full settings validation.
+///
+/// The result has no `experiments`, so validating it cannot recurse into arm
+/// overrides.
+///
+/// # Errors
+///
+/// Returns [`TrustedServerError::Configuration`] when the merged settings do
+/// not deserialize or validate.
+pub(crate) fn settings_with_overrides<'a>(
+    settings: &Settings,
+    overrides: impl IntoIterator<Item = &'a JsonMap<String, JsonValue>>,
+) -> Result<Settings, Report<TrustedServerError>> {
+    let mut value =
+        serde_json::to_value(settings).change_context(TrustedServerError::Configuration {
+            message: "failed to serialize settings for experiment overrides".to_string(),
+        })?;
+    for table in overrides {
+        merge_overrides(&mut value, table);
+    }
+    if let Some(root) = value.as_object_mut() {
+        root.remove("experiments");
+    }
+    Settings::from_json_value(value)
+}
+
+fn merge_overrides(target: &mut JsonValue, overrides: &JsonMap<String, JsonValue>) {
+    let JsonValue::Object(target) = target else {
+        *target = JsonValue::Object(overrides.clone());
+        return;
+    };
+    for (key, value) in overrides {
+        match (target.get_mut(key), value) {
+            (Some(existing @ JsonValue::Object(_)), JsonValue::Object(nested)) => {
+                merge_overrides(existing, nested);
+            }
+            _ => {
+                target.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+/// Returns the `Set-Cookie` values that persist the request's experiment
+/// state: a new `ts-exp-id` when a cookie-assigned experiment enrolled the
+/// user, and `ts-exp` whenever the assigned arms differ from the request's.
+///
+/// `consent` is the request's EC consent, or `None` when the route did not
+/// evaluate it. The `ts-exp-id` cookie is set only when it grants EC consent,
+/// and an existing one is expired when consent is explicitly withdrawn.
+#[must_use]
+pub fn experiment_set_cookies(
+    settings: &Settings,
+    units: &ExperimentUnits,
+    consent: Option<&ConsentContext>,
+) -> Vec<String> {
+    let domain = &settings.publisher.cookie_domain;
+    let mut cookies = Vec::new();
+    let arms = &settings.experiment_arms;
+    if units.cookie_id_is_new {
+        if let Some(cookie_id) = units.cookie_id.as_deref()
+            && consent.is_some_and(ec_consent_granted)
+            && arms
+                .iter()
+                .any(|assigned| assigned.assignment == ExperimentAssignment::Cookie)
+        {
+            cookies.push(format!(
+                "{COOKIE_TS_EXPERIMENT_ID}={cookie_id}; Domain={domain}; Path=/; Secure; SameSite=Lax; Max-Age={COOKIE_MAX_AGE}; HttpOnly",
+            ));
+        }
+    } else if consent.is_some_and(ec_consent_withdrawn) {
+        cookies.push(format!(
+            "{COOKIE_TS_EXPERIMENT_ID}=; Domain={domain}; Path=/; Secure; SameSite=Lax; Max-Age=0; HttpOnly"
+        ));
+    }
+    match (arms.label(), units.arms_cookie.as_deref()) {
+        (Some(label), current) if current != Some(label.as_str()) => {
+            // Not HttpOnly: tsjs reads this cookie.
+            cookies.push(format!(
+                "{COOKIE_TS_EXPERIMENT_ARMS}={label}; Domain={domain}; Path=/; Secure; SameSite=Lax; Max-Age={COOKIE_MAX_AGE}"
+            ));
+        }
+        (None, Some(_)) => cookies.push(format!(
+            "{COOKIE_TS_EXPERIMENT_ARMS}=; Domain={domain}; Path=/; Secure; SameSite=Lax; Max-Age=0"
+        )),
+        _ => {}
+    }
+    cookies
+}
+
+/// Warns that `adapter` serves base settings for every request, for adapters
+/// that build settings once per process and so never apply experiment arms.
+pub fn warn_experiments_unsupported(settings: &Settings, adapter: &str) {
+    let enabled: Vec<&str> = settings
+        .experiments
+        .iter()
+        .filter(|experiment| experiment.enabled)
+        .map(|experiment| experiment.id.as_str())
+        .collect();
+    if !enabled.is_empty() {
+        log::warn!(
+            "the {adapter} adapter does not apply [[experiments]]; serving base settings for {}",
+            enabled.join(", ")
+        );
+    }
+}
+
+fn experiment_error(message: String) -> Report<TrustedServerError> {
+    Report::new(TrustedServerError::Configuration { message })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consent::jurisdiction::Jurisdiction;
+    use crate::test_support::tests::crate_test_settings_str;
+
+    const EC_ID: &str = "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef.AbC123";
+
+    fn settings_with_experiments(experiments_toml: &str) -> Settings {
+        Settings::from_toml(&format!(
+            "{}\n{experiments_toml}",
+            crate_test_settings_str()
+        ))
+        .expect("should parse settings with experiments")
+    }
+
+    const TIMEOUT_EXPERIMENT: &str = r#"
+        [[experiments]]
+        id = "timeout"
+
+        [[experiments.arms]]
+        id = "control"
+
+        [[experiments.arms]]
+        id = "fast"
+        [experiments.arms.overrides.auction]
+        timeout_ms = 321
+    "#;
+
+    const COOKIE_EXPERIMENT: &str = r#"
+        [[experiments]]
+        id = "layout"
+        assignment = "cookie"
+        [[experiments.arms]]
+        id = "a"
+        [[experiments.arms]]
+        id = "b"
+    "#;
+
+    fn granted_consent() -> ConsentContext {
+        ConsentContext {
+            jurisdiction: Jurisdiction::NonRegulated,
+            ..ConsentContext::default()
+        }
+    }
+
+    fn ec_units() -> ExperimentUnits {
+        ExperimentUnits::from_cookie_header(Some(&format!("{COOKIE_TS_EC}={EC_ID}")))
+    }
+
+    #[test]
+    fn assignment_is_deterministic_per_unit() {
+        let settings = settings_with_experiments(TIMEOUT_EXPERIMENT);
+
+        let first = assign_arms(&settings.experiments, &ec_units());
+        let second = assign_arms(&settings.experiments, &ec_units());
+
+        assert_eq!(first, second, "should assign the same EC to the same arm");
+        assert_eq!(first.iter().count(), 1, "should enroll in the experiment");
+    }
+
+    #[test]
+    fn assignment_follows_arm_weights() {
+        let settings = settings_with_experiments(
+            r#"
+            [[experiments]]
+            id = "split"
+            assignment = "cookie"
+            [[experiments.arms]]
+            id = "small"
+            weight = 1
+            [[experiments.arms]]
+            id = "large"
+            weight = 3
+            "#,
+        );
+
+        let large = (0..400)
+            .filter(|_| {
+                let arms = assign_arms(
+                    &settings.experiments,
+                    &ExperimentUnits::from_cookie_header(None),
+                );
+                arms.label().as_deref() == Some("split:large")
+            })
+            .count();
+
+        assert!(
+            (240..=360).contains(&large),
+            "should send about three quarters of units to the heavier arm, got {large}/400"
+        );
+    }
+
+    #[test]
+    fn ec_experiments_skip_requests_without_an_ec() {
+        let settings = settings_with_experiments(TIMEOUT_EXPERIMENT);
+
+        let arms = assign_arms(
+            &settings.experiments,
+            &ExperimentUnits::from_cookie_header(None),
+        );
+
+        assert!(arms.is_empty(), "should not enroll a request without an EC");
+    }
+
+    #[test]
+    fn applied_arm_overrides_settings_fields() {
+        let base = settings_with_experiments(TIMEOUT_EXPERIMENT);
+        let base_timeout = base.auction.timeout_ms;
+        let units = (0..64)
+            .map(|_| ExperimentUnits::from_cookie_header(None))
+            .map(|mut units| {
+                units.ec_hash = units.cookie_id.as_ref().map(|id| id.repeat(2));
+                units
+            })
+            .find(|units| {
+                assign_arms(&base.experiments, units).label().as_deref() == Some("timeout:fast")
+            })
+            .expect("should find a unit in the fast arm");
+
+        let applied = apply_experiments(base, &units);
+
+        assert_eq!(
+            applied.auction.timeout_ms, 321,
+            "should apply the arm override"
+        );
+        assert_ne!(
+            base_timeout, 321,
+            "base timeout should differ from the override"
+        );
+        assert_eq!(
+            applied.experiment_arms.label().as_deref(),
+            Some("timeout:fast"),
+            "should record the assigned arm"
+        );
+        assert_eq!(
+            applied.experiments.len(),
+            1,
+            "should keep the experiment config"
+        );
+    }
+
+    #[test]
+    fn invalid_overrides_fall_back_to_base_settings() {
+        let settings = settings_with_experiments(
+            r#"
+            [[experiments]]
+            id = "broken"
+            assignment = "cookie"
+            [[experiments.arms]]
+            id = "a"
+            [experiments.arms.overrides]
+            no_such_section = true
+            [[experiments.arms]]
+            id = "b"
+            [experiments.arms.overrides]
+            no_such_section = true
+            "#,
+        );
+        let timeout = settings.auction.timeout_ms;
+
+        let applied = apply_experiments(settings, &ExperimentUnits::from_cookie_header(None));
+
+        assert!(
+            applied.experiment_arms.is_empty(),
+            "should not record arms whose overrides were rejected"
+        );
+        assert_eq!(
+            applied.auction.timeout_ms, timeout,
+            "should serve base settings"
+        );
+    }
+
+    #[test]
+    fn merge_replaces_leaves_and_merges_tables() {
+        let mut target = serde_json::json!({"auction": {"timeout_ms": 1000, "providers": ["a", "b"], "enabled": true}});
+        let overrides = serde_json::json!({"auction": {"timeout_ms": 500, "providers": ["c"]}});
+
+        merge_overrides(
+            &mut target,
+            overrides.as_object().expect("should be an object"),
+        );
+
+        assert_eq!(
+            target,
+            serde_json::json!({"auction": {"timeout_ms": 500, "providers": ["c"], "enabled": true}}),
+            "should merge tables and replace arrays"
+        );
+    }
+
+    #[test]
+    fn validation_rejects_malformed_experiments() {
+        for (toml, expected) in [
+            (
+                "[[experiments]]\nid = \"solo\"\n[[experiments.arms]]\nid = \"only\"\n",
+                "at least two arms",
+            ),
+            (
+                "[[experiments]]\nid = \"Bad Id\"\n[[experiments.arms]]\nid = \"a\"\n[[experiments.arms]]\nid = \"b\"\n",
+                "experiments.id",
+            ),
+            (
+                "[[experiments]]\nid = \"dup\"\n[[experiments.arms]]\nid = \"a\"\n[[experiments.arms]]\nid = \"a\"\n",
+                "more than once",
+            ),
+            (
+                "[[experiments]]\nid = \"zero\"\n[[experiments.arms]]\nid = \"a\"\nweight = 0\n[[experiments.arms]]\nid = \"b\"\n",
+                "weight",
+            ),
+        ] {
+            let err = Settings::from_toml(&format!("{}\n{toml}", crate_test_settings_str()))
+                .expect_err("should reject malformed experiment");
+            assert!(
+                format!("{err:?}").contains(expected),
+                "should report `{expected}`: {err:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn set_cookies_persist_new_cookie_units_and_changed_arms() {
+        let settings = settings_with_experiments(COOKIE_EXPERIMENT);
+        let units = ExperimentUnits::from_cookie_header(None);
+        let applied = apply_experiments(settings, &units);
+        let label = applied.experiment_arms.label().expect("should enroll");
+
+        let cookies = experiment_set_cookies(&applied, &units, Some(&granted_consent()));
+
+        assert_eq!(cookies.len(), 2, "should set the unit and arms cookies");
+        let cookie_id = units
+            .cookie_id
+            .as_deref()
+            .expect("should mint a unit cookie");
+        assert!(cookies[0].starts_with(&format!("{COOKIE_TS_EXPERIMENT_ID}={cookie_id};")));
+        assert!(
+            cookies[0].contains("HttpOnly"),
+            "unit cookie should be HttpOnly"
+        );
+        assert!(cookies[1].starts_with(&format!("{COOKIE_TS_EXPERIMENT_ARMS}={label};")));
+        assert!(
+            !cookies[1].contains("HttpOnly"),
+            "arms cookie should be readable by tsjs"
+        );
+
+        let returning = ExperimentUnits::from_cookie_header(Some(&format!(
+            "{COOKIE_TS_EXPERIMENT_ID}={cookie_id}; {COOKIE_TS_EXPERIMENT_ARMS}={label}"
+        )));
+        assert!(
+            experiment_set_cookies(&applied, &returning, Some(&granted_consent())).is_empty(),
+            "should not reset cookies that already match"
+        );
+    }
+
+    #[test]
+    fn set_cookies_clear_arms_when_no_longer_enrolled() {
+        let settings = settings_with_experiments("");
+        let units = ExperimentUnits::from_cookie_header(Some(&format!(
+            "{COOKIE_TS_EXPERIMENT_ARMS}=old:arm"
+        )));
+
+        let cookies = experiment_set_cookies(&settings, &units, None);
+
+        assert_eq!(cookies.len(), 1, "should clear the stale arms cookie");
+        assert!(cookies[0].contains("Max-Age=0"), "should expire the cookie");
+    }
+
+    #[test]
+    fn unit_cookie_waits_for_consent() {
+        let settings = settings_with_experiments(COOKIE_EXPERIMENT);
+        let units = ExperimentUnits::from_cookie_header(None);
+        let applied = apply_experiments(settings, &units);
+
+        for consent in [None, Some(ConsentContext::default())] {
+            let cookies = experiment_set_cookies(&applied, &units, consent.as_ref());
+            assert!(
+                cookies
+                    .iter()
+                    .all(|cookie| !cookie.starts_with(&format!("{COOKIE_TS_EXPERIMENT_ID}="))),
+                "should not set the unit cookie without consent: {cookies:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn unit_without_cookie_is_derived_from_the_ec() {
+        let first = ec_units();
+        let second = ec_units();
+
+        let cookie_id = first.cookie_id.as_deref().expect("should derive a unit");
+        assert_eq!(
+            first.cookie_id, second.cookie_id,
+            "should bucket the same EC into the same unit without a cookie"
+        );
+        assert!(
+            is_valid_unit_cookie(cookie_id),
+            "derived unit should be a valid cookie value"
+        );
+        assert_ne!(
+            ExperimentUnits::from_cookie_header(None).cookie_id,
+            ExperimentUnits::from_cookie_header(None).cookie_id,
+            "should use a per-request unit without an EC"
+        );
+    }
+
+    #[test]
+    fn unit_cookie_is_expired_when_consent_is_withdrawn() {
+        let settings = settings_with_experiments(COOKIE_EXPERIMENT);
+        let units = ExperimentUnits::from_cookie_header(Some(&format!(
+            "{COOKIE_TS_EXPERIMENT_ID}=0123456789abcdef0123456789abcdef"
+        )));
+        let applied = apply_experiments(settings, &units);
+        let withdrawn = ConsentContext {
+            jurisdiction: Jurisdiction::UsState("CA".to_owned()),
+            gpc: true,
+            ..ConsentContext::default()
+        };
+
+        let cookies = experiment_set_cookies(&applied, &units, Some(&withdrawn));
+
+        assert!(
+            cookies.iter().any(|cookie| cookie
+                .starts_with(&format!("{COOKIE_TS_EXPERIMENT_ID}=;"))
+                && cookie.contains("Max-Age=0")),
+            "should expire the unit cookie: {cookies:?}"
+        );
+    }
+}
@@ -332,6 +332,13 @@ impl AdServerMockProvider {
                         .or_else(|| original.and_then(|bid| bid.bid_id.clone())),
                     ad_id: original.and_then(|bid| bid.ad_id.clone()),
                     creative_id: original.and_then(|bid| bid.creative_id.clone()),
+                    deal_id: original.and_then(|bid| bid.deal_id.clone()),
+                    categories: original
+                        .map(|bid| bid.categories.clone())
+                        .unwrap_or_default(),
+                    attributes: original
+                        .map(|bid| bid.attributes.clone())
+                        .unwrap_or_default(),
                     renderer: original.and_then(|bid| bid.renderer.clone()),
                     cache_id: original.and_then(|b| b.cache_id.clone()),
                     cache_host: original.and_then(|b| b.cache_host.clone()),
@@ -608,6 +615,7 @@ mod tests {
                 floor_price: Some(1.50),
                 targeting: HashMap::new(),
                 bidders: HashMap::new(),
+                pmp: None,
             }],
             publisher: PublisherInfo {
                 domain: "test.com".to_string(),
@@ -625,6 +633,7 @@ mod tests {
             }),
             site: None,
             context: HashMap::new(),
+            ad_quality: Default::default(),
         }
     }
 
@@ -643,6 +652,9 @@ mod tests {
             bid_id: Some(bid_id.to_string()),
             ad_id: None,
             creative_id: Some(format!("creative-{bid_id}")),
+            deal_id: None,
+            categories: Vec::new(),
+            attributes: Vec::new(),
             renderer: Some(BidRenderer::Aps(ApsRendererV1 {
                 version: 1,
                 account_id: "example-account".to_string(),
@@ -692,6 +704,9 @@ mod tests {
                     bid_id: None,
                     ad_id: None,
                     creative_id: None,
+                    deal_id: None,
+                    categories: Vec::new(),
+                    attributes: Vec::new(),
                     renderer: None,
                     cache_id: None,
                     cache_host: None,
@@ -718,6 +733,9 @@ mod tests {
                     bid_id: None,
                     ad_id: Some("mock-bid-001".to_string()),
                     creative_id: None,
+                    deal_id: None,
+                    categories: Vec::new(),
+                    attributes: Vec::new(),
                     renderer: None,
                     cache_id: None,
                     cache_host: None,
@@ -840,6 +858,9 @@ mod tests {
                 bid_id: Some("source-bid-id".to_string()),
                 ad_id: Some("bid-impression-id".to_string()),
                 creative_id: Some("source-creative-id".to_string()),
+                deal_id: None,
+                categories: Vec::new(),
+                attributes: Vec::new(),
                 renderer: Some(BidRenderer::Aps(ApsRendererV1 {
                     version: 1,
                     account_id: "example-account".to_string(),
@@ -948,6 +969,9 @@ mod tests {
                 bid_id: Some("019f7e2a-b45b-70b0-a2d1-b651c430700b".to_string()),
                 ad_id: None,
                 creative_id: None,
+                deal_id: None,
+                categories: Vec::new(),
+                attributes: Vec::new(),
                 renderer: None,
                 cache_id: None,
                 cache_host: None,
@@ -1076,6 +1100,7 @@ mod tests {
                 floor_price: None,
                 targeting: HashMap::new(),
                 bidders: HashMap::new(),
+                pmp: None,
             }],
             publisher: PublisherInfo {
                 domain: "test.com".to_string(),
@@ -1089,6 +1114,7 @@ mod tests {
             device: None,
             site: None,
             context: HashMap::new(),
+            ad_quality: Default::default(),
         };
 
         let bidder_responses = vec![AuctionResponse {
@@ -1108,6 +1134,9 @@ mod tests {
                 bid_id: None,
                 ad_id: None,
                 creative_id: None,
+                deal_id: None,
+                categories: Vec::new(),
+                attributes: Vec::new(),
                 renderer: None,
                 cache_id: None,
                 cache_host: None,
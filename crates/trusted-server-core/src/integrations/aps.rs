@@ -29,7 +29,7 @@ use crate::integrations::{
 };
 use crate::openrtb::{
     Banner, Device, Format, Geo, Imp, OpenRtbRequest, Publisher, Regs, RegsExt, Site, ToExt, User,
-    UserExt, to_openrtb_i32,
+    UserExt, apply_ad_quality, to_openrtb_i32, to_openrtb_pmp,
 };
 use crate::platform::{PlatformHttpRequest, PlatformResponse, RuntimeServices};
 use crate::settings::{IntegrationConfig, Settings};
@@ -486,6 +486,7 @@ impl ApsAuctionProvider {
                     }),
                     bidfloor: slot.floor_price,
                     bidfloorcur: slot.floor_price.map(|_| DEFAULT_CURRENCY.to_string()),
+                    pmp: to_openrtb_pmp(slot.pmp.as_ref(), slot.floor_price, DEFAULT_CURRENCY),
                     secure: Some(true),
                     ..Default::default()
                 })
@@ -546,7 +547,7 @@ impl ApsAuctionProvider {
         // query-string identifiers and can leak the deployment host.
         let (site_domain, page) = self.inventory_site_identity(&request.publisher.domain, page);
 
-        OpenRtbRequest {
+        let mut openrtb = OpenRtbRequest {
             id: Some(request.id.clone()),
             imp,
             site: Some(Site {
@@ -573,7 +574,9 @@ impl ApsAuctionProvider {
             }
             .to_ext(),
             ..Default::default()
-        }
+        };
+        apply_ad_quality(&mut openrtb, &request.ad_quality);
+        openrtb
     }
 
     fn serialize_openrtb_request(
@@ -823,6 +826,13 @@ impl ApsAuctionProvider {
             bid_id: Some(bid_id.to_string()),
             ad_id: value.get("adid").and_then(Json::as_str).map(str::to_string),
             creative_id,
+            deal_id: value
+                .get("dealid")
+                .and_then(Json::as_str)
+                .filter(|deal_id| !deal_id.is_empty())
+                .map(str::to_string),
+            categories: Vec::new(),
+            attributes: Vec::new(),
             renderer: Some(renderer),
             cache_id: None,
             cache_host: None,
@@ -1307,6 +1317,7 @@ mod tests {
                 floor_price: Some(1.0),
                 targeting: HashMap::new(),
                 bidders: HashMap::new(),
+                pmp: None,
             }],
             publisher: PublisherInfo {
                 domain: "publisher.example".to_string(),
@@ -1320,6 +1331,7 @@ mod tests {
             device: None,
             site: None,
             context: HashMap::new(),
+            ad_quality: Default::default(),
         }
     }
 
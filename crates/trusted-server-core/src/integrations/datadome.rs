@@ -79,6 +79,7 @@ use crate::integrations::{
 };
 use crate::platform::{PlatformHttpRequest, RuntimeServices};
 use crate::settings::{IntegrationConfig, Settings};
+use crate::third_party_log::send_third_party_request;
 
 mod protection;
 mod protection_scope;
@@ -622,6 +623,7 @@ impl DataDomeIntegration {
     /// Handle the /tags.js endpoint - fetch and rewrite the `DataDome` SDK.
     async fn handle_tags_js(
         &self,
+        settings: &Settings,
         services: &RuntimeServices,
         req: http::Request<EdgeBody>,
     ) -> Result<http::Response<EdgeBody>, Report<TrustedServerError>> {
@@ -649,11 +651,14 @@ impl DataDomeIntegration {
                 .insert(header::USER_AGENT, ua.clone());
         }
 
-        let backend_resp = services
-            .http_client()
-            .send(PlatformHttpRequest::new(backend_req, backend))
-            .await
-            .change_context(Self::error("Failed to fetch tags.js from DataDome"))?;
+        let backend_resp = send_third_party_request(
+            services,
+            settings,
+            DATADOME_INTEGRATION_ID,
+            PlatformHttpRequest::new(backend_req, backend),
+        )
+        .await
+        .change_context(Self::error("Failed to fetch tags.js from DataDome"))?;
 
         if backend_resp.response.status() != StatusCode::OK {
             log::warn!(
@@ -705,6 +710,7 @@ impl DataDomeIntegration {
     /// Handle the /js/* signal collection endpoint - proxy pass-through to api-js.datadome.co.
     async fn handle_js_api(
         &self,
+        settings: &Settings,
         services: &RuntimeServices,
         req: http::Request<EdgeBody>,
     ) -> Result<http::Response<EdgeBody>, Report<TrustedServerError>> {
@@ -765,11 +771,14 @@ impl DataDomeIntegration {
             }
         }
 
-        let backend_resp = services
-            .http_client()
-            .send(PlatformHttpRequest::new(backend_req, backend))
-            .await
-            .change_context(Self::error("Failed to proxy signal request to DataDome"))?;
+        let backend_resp = send_third_party_request(
+            services,
+            settings,
+            DATADOME_INTEGRATION_ID,
+            PlatformHttpRequest::new(backend_req, backend),
+        )
+        .await
+        .change_context(Self::error("Failed to proxy signal request to DataDome"))?;
 
         log::info!(
             "[datadome] Signal request returned status {}",
@@ -823,16 +832,16 @@ impl IntegrationProxy for DataDomeIntegration {
 
     async fn handle(
         &self,
-        _settings: &Settings,
+        settings: &Settings,
         services: &RuntimeServices,
         req: http::Request<EdgeBody>,
     ) -> Result<http::Response<EdgeBody>, Report<TrustedServerError>> {
         let path = req.uri().path().to_string();
 
         if path == "/integrations/datadome/tags.js" {
-            self.handle_tags_js(services, req).await
+            self.handle_tags_js(settings, services, req).await
         } else if path.starts_with("/integrations/datadome/js/") {
-            self.handle_js_api(services, req).await
+            self.handle_js_api(settings, services, req).await
         } else {
             Err(Report::new(Self::error(format!(
                 "Unknown DataDome route: {}",
@@ -618,6 +618,7 @@ fn is_internal_path(path: &str) -> bool {
         || path.starts_with("/_ts/admin/")
         || path == "/_ts/api/v1/identify"
         || path == "/_ts/api/v1/batch-sync"
+        || path == "/_ts/api/v1/event"
 }
 
 fn request_host(req: &Request<EdgeBody>) -> String {
@@ -17,6 +17,7 @@ use crate::integrations::{
 };
 use crate::platform::{PlatformHttpRequest, RuntimeServices};
 use crate::settings::{IntegrationConfig, Settings};
+use crate::third_party_log::send_third_party_request;
 
 const DIDOMI_INTEGRATION_ID: &str = "didomi";
 const DIDOMI_DEFAULT_PREFIX: &str = "/integrations/didomi/consent";
@@ -277,7 +278,7 @@ impl IntegrationProxy for DidomiIntegration {
 
     async fn handle(
         &self,
-        _settings: &Settings,
+        settings: &Settings,
         services: &RuntimeServices,
         req: http::Request<EdgeBody>,
     ) -> Result<http::Response<EdgeBody>, Report<TrustedServerError>> {
@@ -318,11 +319,14 @@ impl IntegrationProxy for DidomiIntegration {
             proxy_req.headers_mut(),
         );
 
-        let mut response = services
-            .http_client()
-            .send(PlatformHttpRequest::new(proxy_req, backend_name))
-            .await
-            .change_context(Self::error("Didomi upstream request failed"))?;
+        let mut response = send_third_party_request(
+            services,
+            settings,
+            DIDOMI_INTEGRATION_ID,
+            PlatformHttpRequest::new(proxy_req, backend_name),
+        )
+        .await
+        .change_context(Self::error("Didomi upstream request failed"))?;
 
         if matches!(backend, DidomiBackend::Sdk) {
             Self::add_cors_headers(&mut response.response);
@@ -0,0 +1,945 @@
+//! Google Ad Manager server-side mediator.
+//!
+//! Runs after the header-bidding providers when `[auction].mediator = "gam"`.
+//! The winning header-bidding bid per slot is sent to GAM as `hb_pb`,
+//! `hb_bidder`, `hb_adid` and, for deal bids, `hb_deal` key-values on a
+//! single-request (SRA) ad call, together with the slot's rendered
+//! `gam_unit_path`, consent signals and a PPID derived from the EC ID. GAM's per-slot decision is mapped back into an
+//! [`AuctionResponse`]:
+//!
+//! - a configured header-bidding line item selects the header-bidding winner;
+//! - an empty slot passes back to the header-bidding winner, if any; and
+//! - any other line item wins with GAM's creative markup.
+//!
+//! The response is read as newline-delimited JSON, one object per requested
+//! slot in request order (`output=ldjh`):
+//!
+//! ```json
+//! {"_empty_":false,"_line_item_ids_":[123],"_creative_ids_":[456],"_width_":300,"_height_":250,"_html_":"<div>…</div>"}
+//! ```
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use edgezero_core::body::Body as EdgeBody;
+use error_stack::{Report, ResultExt};
+use http::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest as _, Sha256};
+use url::Url;
+use validator::Validate;
+
+use crate::auction::provider::{AuctionProvider, ProviderRequestOutcome};
+use crate::auction::types::{
+    AdSlot, AuctionContext, AuctionRequest, AuctionResponse, Bid, BidStatus, MediaType,
+};
+use crate::error::TrustedServerError;
+use crate::integrations::{
+    UPSTREAM_RTB_MAX_RESPONSE_BYTES, collect_response_bounded,
+    ensure_integration_backend_with_timeout, predict_integration_backend_name,
+};
+use crate::platform::{PlatformHttpRequest, PlatformResponse, RuntimeServices};
+use crate::price_bucket::{PriceGranularity, price_bucket};
+use crate::settings::{IntegrationConfig, Settings};
+
+/// Integration and provider name for the GAM mediator.
+pub const GAM_INTEGRATION_ID: &str = "gam";
+
+// ============================================================================
+// Configuration
+// ============================================================================
+
+/// Configuration for the GAM server-side mediator.
+#[derive(Debug, Clone, Deserialize, Serialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct GamConfig {
+    /// Whether this integration is enabled.
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+
+    /// GAM network code. Defaults to `creative_opportunities.gam_network_id`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network_code: Option<String>,
+
+    /// GAM ad request endpoint. Point this at a local stand-in for testing.
+    #[serde(default = "default_endpoint")]
+    #[validate(url)]
+    pub endpoint: String,
+
+    /// Timeout in milliseconds.
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u32,
+
+    /// Line items that serve header-bidding demand (the Prebid line items).
+    /// When GAM selects one, the header-bidding winner renders.
+    #[serde(default)]
+    pub header_bidding_line_item_ids: Vec<u64>,
+
+    /// Price granularity for `hb_pb`. Defaults to
+    /// `creative_opportunities.price_granularity`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub price_granularity: Option<PriceGranularity>,
+
+    /// Send a PPID derived from the EC ID when one is present.
+    #[serde(default = "default_send_ppid")]
+    pub send_ppid: bool,
+}
+
+fn default_enabled() -> bool {
+    false
+}
+
+fn default_endpoint() -> String {
+    "https://securepubads.g.doubleclick.net/gampad/ads".to_string()
+}
+
+fn default_timeout_ms() -> u32 {
+    500
+}
+
+fn default_send_ppid() -> bool {
+    true
+}
+
+impl IntegrationConfig for GamConfig {
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+}
+
+// ============================================================================
+// Provider
+// ============================================================================
+
+/// One slot's decision in GAM's `ldjh` response.
+#[derive(Debug, Default, Deserialize)]
+struct GamSlotResponse {
+    #[serde(rename = "_empty_", default)]
+    empty: bool,
+    #[serde(rename = "_line_item_ids_", default)]
+    line_item_ids: Vec<u64>,
+    #[serde(rename = "_creative_ids_", default)]
+    creative_ids: Vec<u64>,
+    #[serde(rename = "_width_", default)]
+    width: u32,
+    #[serde(rename = "_height_", default)]
+    height: u32,
+    #[serde(rename = "_html_", default)]
+    html: Option<String>,
+}
+
+/// GAM server-side mediator provider.
+pub struct GamMediatorProvider {
+    config: GamConfig,
+    network_code: String,
+    granularity: PriceGranularity,
+}
+
+impl GamMediatorProvider {
+    /// Create a GAM mediator for a resolved network code and granularity.
+    #[must_use]
+    pub fn new(config: GamConfig, network_code: String, granularity: PriceGranularity) -> Self {
+        Self {
+            config,
+            network_code,
+            granularity,
+        }
+    }
+
+    /// Highest-priced header-bidding bid per slot that clears the slot floor.
+    fn header_bidding_winners(
+        request: &AuctionRequest,
+        provider_responses: &[AuctionResponse],
+    ) -> HashMap<String, Bid> {
+        let floors: HashMap<&str, f64> = request
+            .slots
+            .iter()
+            .filter_map(|slot| slot.floor_price.map(|floor| (slot.id.as_str(), floor)))
+            .collect();
+        let mut winners: HashMap<String, Bid> = HashMap::new();
+        for bid in provider_responses
+            .iter()
+            .filter(|response| response.status == BidStatus::Success)
+            .flat_map(|response| &response.bids)
+        {
+            let Some(price) = bid.price else {
+                continue;
+            };
+            if floors
+                .get(bid.slot_id.as_str())
+                .is_some_and(|floor| price < *floor)
+            {
+                continue;
+            }
+            let replace = winners
+                .get(&bid.slot_id)
+                .is_none_or(|current| price > current.price.unwrap_or_default());
+            if replace {
+                winners.insert(bid.slot_id.clone(), bid.clone());
+            }
+        }
+        winners
+    }
+
+    /// Resolve the ad unit path for a slot: the creative-opportunity slot's
+    /// rendered `gam_unit_path` when configured, otherwise `/<network>/<slot>`.
+    fn unit_path(&self, settings: &Settings, slot: &AdSlot, page_path: &str) -> String {
+        settings
+            .creative_opportunities
+            .as_ref()
+            .and_then(|co| {
+                let co_slot = co.slot.iter().find(|candidate| candidate.id == slot.id)?;
+                co_slot.render_gam_unit_path(&co.gam_network_id, &co.section_for_path(page_path))
+            })
+            .unwrap_or_else(|| format!("/{}/{}", self.network_code, slot.id))
+    }
+
+    /// Key-values for one slot, encoded as GAM's `scp` value.
+    fn slot_targeting(&self, slot: &AdSlot, winner: Option<&Bid>) -> String {
+        let mut pairs = url::form_urlencoded::Serializer::new(String::new());
+        let mut keys: Vec<&String> = slot.targeting.keys().collect();
+        keys.sort();
+        for key in keys {
+            if let Some(value) = slot.targeting[key].as_str() {
+                pairs.append_pair(key, value);
+            }
+        }
+        if let Some(bid) = winner
+            && let Some(price) = bid.price
+        {
+            pairs.append_pair("hb_pb", &price_bucket(price, self.granularity));
+            pairs.append_pair("hb_bidder", &bid.bidder);
+            if let Some(ad_id) = bid.targeting_ad_id() {
+                pairs.append_pair("hb_adid", ad_id);
+            }
+            if let Some(deal_id) = bid.deal_id.as_deref().filter(|id| !id.is_empty()) {
+                pairs.append_pair("hb_deal", deal_id);
+            }
+        }
+        pairs.finish()
+    }
+
+    fn slot_sizes(slot: &AdSlot) -> String {
+        slot.formats
+            .iter()
+            .filter(|format| format.media_type == MediaType::Banner)
+            .map(|format| format!("{}x{}", format.width, format.height))
+            .collect::<Vec<_>>()
+            .join("|")
+    }
+
+    /// Derive a PPID from the EC ID. GAM requires an opaque publisher ID, so
+    /// the EC ID itself is never sent.
+    fn ppid(ec_id: &str) -> String {
+        hex::encode(Sha256::digest(ec_id.as_bytes()))
+    }
+
+    /// Build the SRA ad request URL for every slot in the auction.
+    fn build_ad_request_url(
+        &self,
+        request: &AuctionRequest,
+        settings: &Settings,
+        winners: &HashMap<String, Bid>,
+    ) -> Result<Url, Report<TrustedServerError>> {
+        let mut url =
+            Url::parse(&self.config.endpoint).change_context(TrustedServerError::Auction {
+                message: format!("Invalid GAM endpoint: {}", self.config.endpoint),
+            })?;
+        let page_url = request.publisher.page_url.as_deref();
+        let page_path = page_url
+            .and_then(|value| Url::parse(value).ok())
+            .map_or_else(|| "/".to_string(), |value| value.path().to_string());
+
+        // SRA encodes unit paths as indexes into a shared segment list.
+        let mut parts: Vec<String> = Vec::new();
+        let mut encoded_units = Vec::with_capacity(request.slots.len());
+        for slot in &request.slots {
+            let path = self.unit_path(settings, slot, &page_path);
+            let indexes: Vec<String> = path
+                .split('/')
+                .filter(|segment| !segment.is_empty())
+                .map(|segment| {
+                    let index = parts
+                        .iter()
+                        .position(|part| part == segment)
+                        .unwrap_or_else(|| {
+                            parts.push(segment.to_string());
+                            parts.len() - 1
+                        });
+                    index.to_string()
+                })
+                .collect();
+            encoded_units.push(format!("/{}", indexes.join("/")));
+        }
+
+        let sizes: Vec<String> = request.slots.iter().map(Self::slot_sizes).collect();
+        let targeting: Vec<String> = request
+            .slots
+            .iter()
+            .map(|slot| self.slot_targeting(slot, winners.get(&slot.id)))
+            .collect();
+
+        {
+            let mut query = url.query_pairs_mut();
+            query
+                .append_pair("iu_parts", &parts.join(","))
+                .append_pair("enc_prev_ius", &encoded_units.join(","))
+                .append_pair("prev_iu_szs", &sizes.join(","))
+                .append_pair("prev_scp", &targeting.join("|"))
+                .append_pair("correlator", &rand::random::<u32>().to_string())
+                .append_pair("output", "ldjh")
+                .append_pair("impl", "fifs");
+            if let Some(page_url) = page_url {
+                query.append_pair("url", page_url);
+            }
+            if self.config.send_ppid
+                && let Some(ec_id) = request.user.id.as_deref().filter(|id| !id.is_empty())
+            {
+                query.append_pair("ppid", &Self::ppid(ec_id));
+            }
+            if let Some(consent) = request.user.consent.as_ref() {
+                query.append_pair("gdpr", if consent.gdpr_applies { "1" } else { "0" });
+                if let Some(tc) = consent.raw_tc_string.as_deref() {
+                    query.append_pair("gdpr_consent", tc);
+                }
+                if let Some(us_privacy) = consent.raw_us_privacy.as_deref() {
+                    query.append_pair("us_privacy", us_privacy);
+                }
+                if let Some(gpp) = consent.raw_gpp_string.as_deref() {
+                    query.append_pair("gpp", gpp);
+                }
+                if let Some(ids) = consent.gpp_section_ids.as_ref() {
+                    let ids: Vec<String> = ids.iter().map(ToString::to_string).collect();
+                    query.append_pair("gpp_sid", &ids.join(","));
+                }
+            }
+        }
+        Ok(url)
+    }
+
+    /// Map GAM's per-slot decisions into mediated bids.
+    fn map_gam_response(
+        &self,
+        body: &str,
+        response_time_ms: u64,
+        request: &AuctionRequest,
+        winners: &HashMap<String, Bid>,
+    ) -> AuctionResponse {
+        let mut lines = body.lines().filter(|line| !line.trim().is_empty());
+        let mut bids = Vec::new();
+        let mut outcomes: HashMap<&'static str, u64> = HashMap::new();
+
+        for slot in &request.slots {
+            let decision = match lines.next().map(serde_json::from_str::<GamSlotResponse>) {
+                Some(Ok(decision)) => decision,
+                Some(Err(error)) => {
+                    log::warn!("gam: unparseable decision for slot '{}': {error}", slot.id);
+                    *outcomes.entry("unparseable").or_default() += 1;
+                    GamSlotResponse {
+                        empty: true,
+                        ..GamSlotResponse::default()
+                    }
+                }
+                None => GamSlotResponse {
+                    empty: true,
+                    ..GamSlotResponse::default()
+                },
+            };
+            let winner = winners.get(&slot.id);
+            let line_item_id = decision.line_item_ids.first().copied();
+
+            let (outcome, bid) = match line_item_id {
+                None => ("passback", winner.cloned()),
+                Some(_) if decision.empty => ("passback", winner.cloned()),
+                Some(id) if self.config.header_bidding_line_item_ids.contains(&id) => {
+                    ("header_bidding", winner.cloned())
+                }
+                Some(id) => ("line_item", self.line_item_bid(slot, &decision, id, winner)),
+            };
+            *outcomes.entry(outcome).or_default() += 1;
+            let Some(mut bid) = bid else {
+                continue;
+            };
+            bid.metadata
+                .insert("gam_outcome".to_string(), json!(outcome));
+            if let Some(id) = line_item_id {
+                bid.metadata
+                    .insert("gam_line_item_id".to_string(), json!(id));
+            }
+            bids.push(bid);
+        }
+
+        let mut response = if bids.is_empty() {
+            AuctionResponse::no_bid(GAM_INTEGRATION_ID, response_time_ms)
+        } else {
+            AuctionResponse::success(GAM_INTEGRATION_ID, bids, response_time_ms)
+        };
+        response
+            .metadata
+            .insert("gam_outcomes".to_string(), json!(outcomes));
+        response
+    }
+
+    /// Build the bid for a GAM-selected (non-header-bidding) line item.
+    ///
+    /// GAM does not report a price, so the bid carries the header-bidding
+    /// winner's price (GAM ranked the line item above it) or the slot floor.
+    /// It exists only so the orchestrator's floor and winner checks pass.
+    fn line_item_bid(
+        &self,
+        slot: &AdSlot,
+        decision: &GamSlotResponse,
+        line_item_id: u64,
+        winner: Option<&Bid>,
+    ) -> Option<Bid> {
+        let html = decision
+            .html
+            .as_deref()
+            .filter(|html| !html.trim().is_empty())?;
+        let fallback_size = slot
+            .formats
+            .iter()
+            .find(|format| format.media_type == MediaType::Banner);
+        let (width, height) = if decision.width > 0 && decision.height > 0 {
+            (decision.width, decision.height)
+        } else {
+            fallback_size.map(|format| (format.width, format.height))?
+        };
+        let creative_id = decision.creative_ids.first().map(ToString::to_string);
+        let price = winner
+            .and_then(|bid| bid.price)
+            .or(slot.floor_price)
+            .unwrap_or(0.0);
+        let mut metadata = HashMap::new();
+        metadata.insert(
+            "price_source".to_string(),
+            json!(if winner.is_some() {
+                "hb_winner"
+            } else {
+                "floor"
+            }),
+        );
+
+        Some(Bid {
+            slot_id: slot.id.clone(),
+            price: Some(price),
+            currency: "USD".to_string(),
+            creative: Some(html.to_string()),
+            adomain: None,
+            bidder: GAM_INTEGRATION_ID.to_string(),
+            width,
+            height,
+            nurl: None,
+            burl: None,
+            bid_id: Some(format!(
+                "gam-{line_item_id}-{}",
+                creative_id.as_deref().unwrap_or("0")
+            )),
+            ad_id: None,
+            creative_id,
+            deal_id: None,
+            categories: Vec::new(),
+            attributes: Vec::new(),
+            renderer: None,
+            cache_id: None,
+            cache_host: None,
+            cache_path: None,
+            metadata,
+        })
+    }
+
+    async fn parse_response_inner(
+        &self,
+        response: PlatformResponse,
+        response_time_ms: u64,
+        request: &AuctionRequest,
+        winners: &HashMap<String, Bid>,
+    ) -> Result<AuctionResponse, Report<TrustedServerError>> {
+        let response = response.response;
+        let status = response.status();
+        if status == StatusCode::NO_CONTENT {
+            return Ok(self.map_gam_response("", response_time_ms, request, winners));
+        }
+        if !status.is_success() {
+            log::warn!("GAM returned non-success: {status}");
+            return Ok(AuctionResponse::error(GAM_INTEGRATION_ID, response_time_ms));
+        }
+
+        let body = collect_response_bounded(
+            response.into_body(),
+            UPSTREAM_RTB_MAX_RESPONSE_BYTES,
+            GAM_INTEGRATION_ID,
+        )
+        .await
+        .change_context(TrustedServerError::Auction {
+            message: "Failed to read GAM response body".to_string(),
+        })?;
+        let body = String::from_utf8_lossy(&body);
+        let mediated = self.map_gam_response(&body, response_time_ms, request, winners);
+
+        log::info!(
+            "GAM returned {} mediated bids in {}ms",
+            mediated.bids.len(),
+            response_time_ms
+        );
+        Ok(mediated)
+    }
+}
+
+#[async_trait(?Send)]
+impl AuctionProvider for GamMediatorProvider {
+    fn provider_name(&self) -> &'static str {
+        GAM_INTEGRATION_ID
+    }
+
+    async fn request_bids(
+        &self,
+        request: &AuctionRequest,
+        context: &AuctionContext<'_>,
+    ) -> Result<ProviderRequestOutcome, Report<TrustedServerError>> {
+        let bidder_responses = context.provider_responses.unwrap_or(&[]);
+        let winners = Self::header_bidding_winners(request, bidder_responses);
+
+        log::info!(
+            "GAM: mediating {} slots with {} header-bidding winners",
+            request.slots.len(),
+            winners.len()
+        );
+
+        let url = self.build_ad_request_url(request, context.settings, &winners)?;
+        let mut builder = http::Request::builder()
+            .method(Method::GET)
+            .uri(url.as_str());
+        if let Some(user_agent) = request
+            .device
+            .as_ref()
+            .and_then(|device| device.user_agent.as_deref())
+        {
+            builder = builder.header(http::header::USER_AGENT, user_agent);
+        }
+        let req = builder
+            .body(EdgeBody::empty())
+            .change_context(TrustedServerError::Auction {
+                message: "Failed to build GAM ad request".to_string(),
+            })?;
+
+        let backend_name = ensure_integration_backend_with_timeout(
+            context.services,
+            &self.config.endpoint,
+            GAM_INTEGRATION_ID,
+            Duration::from_millis(u64::from(context.timeout_ms)),
+        )
+        .change_context(TrustedServerError::Auction {
+            message: format!(
+                "Failed to resolve backend for GAM endpoint: {}",
+                self.config.endpoint
+            ),
+        })?;
+
+        let pending = context
+            .services
+            .http_client()
+            .send_async(PlatformHttpRequest::new(req, backend_name))
+            .await
+            .change_context(TrustedServerError::Auction {
+                message: "Failed to send GAM ad request".to_string(),
+            })?;
+
+        Ok(ProviderRequestOutcome::pending(pending))
+    }
+
+    async fn parse_response(
+        &self,
+        _response: PlatformResponse,
+        response_time_ms: u64,
+    ) -> Result<AuctionResponse, Report<TrustedServerError>> {
+        // Without the auction request the per-slot decisions cannot be
+        // matched to slots. The orchestrator always calls
+        // [`parse_response_with_context`].
+        log::debug!("gam: parsing without context — no slots to map decisions onto");
+        Ok(AuctionResponse::error(GAM_INTEGRATION_ID, response_time_ms))
+    }
+
+    async fn parse_response_with_context(
+        &self,
+        response: PlatformResponse,
+        response_time_ms: u64,
+        request: &AuctionRequest,
+        context: &AuctionContext<'_>,
+    ) -> Result<AuctionResponse, Report<TrustedServerError>> {
+        let winners =
+            Self::header_bidding_winners(request, context.provider_responses.unwrap_or(&[]));
+        self.parse_response_inner(response, response_time_ms, request, &winners)
+            .await
+    }
+
+    fn supports_media_type(&self, media_type: &MediaType) -> bool {
+        matches!(media_type, MediaType::Banner)
+    }
+
+    fn timeout_ms(&self) -> u32 {
+        self.config.timeout_ms
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    fn backend_name(&self, services: &RuntimeServices, timeout_ms: u32) -> Option<String> {
+        predict_integration_backend_name(
+            services,
+            &self.config.endpoint,
+            GAM_INTEGRATION_ID,
+            Duration::from_millis(u64::from(timeout_ms)),
+        )
+        .inspect_err(|e| {
+            log::error!(
+                "Failed to predict backend name for GAM endpoint '{}': {e:?}",
+                self.config.endpoint
+            );
+        })
+        .ok()
+    }
+}
+
+// ============================================================================
+// Auto-Registration
+// ============================================================================
+
+/// Resolve the network code from the integration or creative-opportunity config.
+fn resolve_network_code(
+    settings: &Settings,
+    config: &GamConfig,
+) -> Result<String, Report<TrustedServerError>> {
+    config
+        .network_code
+        .clone()
+        .or_else(|| {
+            settings
+                .creative_opportunities
+                .as_ref()
+                .map(|co| co.gam_network_id.clone())
+        })
+        .filter(|code| !code.trim().is_empty())
+        .ok_or_else(|| {
+            Report::new(TrustedServerError::Configuration {
+                message: "integrations.gam requires network_code or creative_opportunities.gam_network_id"
+                    .to_string(),
+            })
+        })
+}
+
+/// Validate the GAM mediator configuration, returning whether it is enabled.
+///
+/// # Errors
+///
+/// Returns an error when the GAM integration is enabled with invalid
+/// configuration or without a network code.
+pub fn validate_config_for_startup(
+    settings: &Settings,
+) -> Result<bool, Report<TrustedServerError>> {
+    let Some(config) = settings.integration_config::<GamConfig>(GAM_INTEGRATION_ID)? else {
+        return Ok(false);
+    };
+    resolve_network_code(settings, &config)?;
+    Ok(true)
+}
+
+/// Auto-register the GAM mediator based on settings configuration.
+///
+/// # Errors
+///
+/// Returns an error when the GAM integration is enabled with invalid
+/// configuration or without a network code.
+pub fn register_providers(
+    settings: &Settings,
+) -> Result<Vec<Arc<dyn AuctionProvider>>, Report<TrustedServerError>> {
+    let Some(config) = settings.integration_config::<GamConfig>(GAM_INTEGRATION_ID)? else {
+        log::debug!("GAM mediator config not found or disabled");
+        return Ok(Vec::new());
+    };
+    let network_code = resolve_network_code(settings, &config)?;
+    let granularity = config.price_granularity.unwrap_or_else(|| {
+        settings
+            .creative_opportunities
+            .as_ref()
+            .map(|co| co.price_granularity)
+            .unwrap_or_default()
+    });
+    log::info!(
+        "Registering GAM mediator (network: {network_code}, endpoint: {})",
+        config.endpoint
+    );
+    Ok(vec![Arc::new(GamMediatorProvider::new(
+        config,
+        network_code,
+        granularity,
+    ))])
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auction::types::{AdFormat, PublisherInfo, UserInfo};
+    use crate::platform::test_support::{StubHttpClient, build_services_with_http_client};
+    use crate::test_support::tests::{VALID_SYNTHETIC_ID, crate_test_settings_str};
+
+    fn settings() -> Settings {
+        let toml = format!(
+            r#"{}
+[creative_opportunities]
+gam_network_id = "99999"
+section_root = "home"
+
+[[creative_opportunities.slot]]
+id = "atf"
+gam_unit_path = "/{{network_id}}/example/{{section}}"
+page_patterns = ["/**"]
+formats = [{{ width = 300, height = 250 }}]
+
+[integrations.gam]
+enabled = true
+endpoint = "http://localhost:6767/gampad/ads"
+header_bidding_line_item_ids = [111]
+"#,
+            crate_test_settings_str()
+        );
+        Settings::from_toml(&toml).expect("should parse settings")
+    }
+
+    fn provider(settings: &Settings) -> GamMediatorProvider {
+        let config = settings
+            .integration_config::<GamConfig>(GAM_INTEGRATION_ID)
+            .expect("should validate GAM config")
+            .expect("should enable GAM");
+        GamMediatorProvider::new(config, "99999".to_string(), PriceGranularity::Dense)
+    }
+
+    fn slot(id: &str, floor_price: Option<f64>) -> AdSlot {
+        AdSlot {
+            id: id.to_string(),
+            formats: vec![AdFormat {
+                media_type: MediaType::Banner,
+                width: 300,
+                height: 250,
+            }],
+            floor_price,
+            targeting: HashMap::from([("pos".to_string(), json!("top"))]),
+            bidders: HashMap::new(),
+            pmp: None,
+        }
+    }
+
+    fn request() -> AuctionRequest {
+        AuctionRequest {
+            id: "fictional-auction".to_string(),
+            slots: vec![slot("atf", None), slot("sidebar", Some(0.5))],
+            publisher: PublisherInfo {
+                domain: "publisher.example".to_string(),
+                page_url: Some("https://publisher.example/news/story".to_string()),
+            },
+            user: UserInfo {
+                id: Some(VALID_SYNTHETIC_ID.to_string()),
+                consent: None,
+                eids: None,
+            },
+            device: None,
+            site: None,
+            context: HashMap::new(),
+            ad_quality: Default::default(),
+        }
+    }
+
+    fn hb_bid(slot_id: &str, bidder: &str, price: f64) -> Bid {
+        Bid {
+            slot_id: slot_id.to_string(),
+            price: Some(price),
+            currency: "USD".to_string(),
+            creative: Some(format!("<div>{bidder}</div>")),
+            adomain: None,
+            bidder: bidder.to_string(),
+            width: 300,
+            height: 250,
+            nurl: None,
+            burl: None,
+            bid_id: Some(format!("{bidder}-bid")),
+            ad_id: None,
+            creative_id: None,
+            deal_id: None,
+            categories: Vec::new(),
+            attributes: Vec::new(),
+            renderer: None,
+            cache_id: None,
+            cache_host: None,
+            cache_path: None,
+            metadata: HashMap::new(),
+        }
+    }
+
+    fn provider_responses() -> Vec<AuctionResponse> {
+        vec![
+            AuctionResponse::success(
+                "prebid",
+                vec![
+                    hb_bid("atf", "kargo", 1.234),
+                    hb_bid("sidebar", "kargo", 0.4),
+                ],
+                20,
+            ),
+            AuctionResponse::success("aps", vec![hb_bid("atf", "amazon", 2.5)], 30),
+        ]
+    }
+
+    #[test]
+    fn header_bidding_winners_pick_highest_bid_above_floor() {
+        let winners =
+            GamMediatorProvider::header_bidding_winners(&request(), &provider_responses());
+
+        assert_eq!(winners.len(), 1, "should drop the sidebar bid below floor");
+        assert_eq!(winners["atf"].bidder, "amazon");
+    }
+
+    #[test]
+    fn ad_request_carries_unit_paths_key_values_and_ppid() {
+        let settings = settings();
+        let provider = provider(&settings);
+        let winners =
+            GamMediatorProvider::header_bidding_winners(&request(), &provider_responses());
+
+        let url = provider
+            .build_ad_request_url(&request(), &settings, &winners)
+            .expect("should build GAM URL");
+        let params: HashMap<String, String> = url.query_pairs().into_owned().collect();
+
+        assert_eq!(params["iu_parts"], "99999,example,news,sidebar");
+        assert_eq!(params["enc_prev_ius"], "/0/1/2,/0/3");
+        assert_eq!(params["prev_iu_szs"], "300x250,300x250");
+        assert_eq!(
+            params["prev_scp"],
+            "pos=top&hb_pb=2.50&hb_bidder=amazon&hb_adid=amazon-bid|pos=top"
+        );
+        assert_eq!(params["output"], "ldjh");
+        assert_eq!(params["ppid"].len(), 64, "should send a hashed PPID");
+        assert!(
+            !url.as_str().contains(VALID_SYNTHETIC_ID),
+            "should never send the raw EC ID"
+        );
+    }
+
+    #[test]
+    fn slot_targeting_adds_hb_deal_for_deal_winner() {
+        let settings = settings();
+        let provider = provider(&settings);
+        let request = request();
+        let mut winner = hb_bid("atf", "kargo", 3.0);
+        winner.deal_id = Some("pg-deal-1".to_string());
+
+        let scp = provider.slot_targeting(&request.slots[0], Some(&winner));
+
+        assert_eq!(
+            scp, "pos=top&hb_pb=3.00&hb_bidder=kargo&hb_adid=kargo-bid&hb_deal=pg-deal-1",
+            "should append hb_deal after the header-bidding keys"
+        );
+    }
+
+    #[test]
+    fn gam_response_maps_line_items_header_bidding_and_passback() {
+        let settings = settings();
+        let provider = provider(&settings);
+        let mut request = request();
+        request.slots.push(slot("footer", None));
+        let mut responses = provider_responses();
+        responses[0].bids.push(hb_bid("footer", "ix", 0.9));
+        let winners = GamMediatorProvider::header_bidding_winners(&request, &responses);
+        let body = concat!(
+            r#"{"_empty_":false,"_line_item_ids_":[111],"_creative_ids_":[7],"_html_":"<puc/>"}"#,
+            "\n",
+            r#"{"_empty_":false,"_line_item_ids_":[222],"_creative_ids_":[8],"_width_":300,"_height_":250,"_html_":"<div>direct</div>"}"#,
+            "\n",
+            r#"{"_empty_":true}"#,
+        );
+
+        let mediated = provider.map_gam_response(body, 12, &request, &winners);
+
+        let by_slot: HashMap<&str, &Bid> = mediated
+            .bids
+            .iter()
+            .map(|bid| (bid.slot_id.as_str(), bid))
+            .collect();
+        assert_eq!(by_slot["atf"].bidder, "amazon");
+        assert_eq!(by_slot["atf"].metadata["gam_outcome"], "header_bidding");
+        let direct = by_slot["sidebar"];
+        assert_eq!(direct.bidder, "gam");
+        assert_eq!(direct.creative.as_deref(), Some("<div>direct</div>"));
+        assert_eq!(direct.price, Some(0.5), "should carry the slot floor");
+        assert_eq!(direct.creative_id.as_deref(), Some("8"));
+        assert_eq!(by_slot["footer"].bidder, "ix");
+        assert_eq!(by_slot["footer"].metadata["gam_outcome"], "passback");
+    }
+
+    #[test]
+    fn request_bids_sends_get_to_configured_stand_in() {
+        let settings = settings();
+        let provider = provider(&settings);
+        let stub = Arc::new(StubHttpClient::new());
+        stub.push_response(200, Vec::new());
+        let services = build_services_with_http_client(
+            Arc::clone(&stub) as Arc<dyn crate::platform::PlatformHttpClient>
+        );
+        let downstream = http::Request::builder()
+            .uri("https://publisher.example/auction")
+            .body(EdgeBody::empty())
+            .expect("should build downstream request");
+        let responses = provider_responses();
+        let context = AuctionContext {
+            settings: &settings,
+            request: &downstream,
+            timeout_ms: 300,
+            provider_responses: Some(&responses),
+            services: &services,
+        };
+
+        let outcome = futures::executor::block_on(provider.request_bids(&request(), &context))
+            .expect("should dispatch GAM request");
+
+        assert!(
+            matches!(outcome, ProviderRequestOutcome::Pending { .. }),
+            "should return a pending request"
+        );
+        let uris = stub.recorded_async_request_uris();
+        assert_eq!(uris.len(), 1);
+        assert!(
+            uris[0].starts_with("http://localhost:6767/gampad/ads?"),
+            "should call the configured endpoint: {}",
+            uris[0]
+        );
+    }
+
+    #[test]
+    fn registration_requires_network_code() {
+        let toml = format!(
+            r#"{}
+[integrations.gam]
+enabled = true
+"#,
+            crate_test_settings_str()
+        );
+        let settings = Settings::from_toml(&toml).expect("should parse settings");
+
+        let Err(err) = register_providers(&settings) else {
+            panic!("should reject GAM without a network code");
+        };
+
+        assert!(
+            err.to_string().contains("network_code"),
+            "should mention network_code: {err}"
+        );
+    }
+}
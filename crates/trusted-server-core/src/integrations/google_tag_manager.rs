@@ -315,7 +315,8 @@ impl GoogleTagManagerIntegration {
         req: &mut Request<EdgeBody>,
         target_url: &'a str,
     ) -> Result<ProxyRequestConfig<'a>, PayloadSizeError> {
-        let mut proxy_config = ProxyRequestConfig::new(target_url);
+        let mut proxy_config =
+            ProxyRequestConfig::new(target_url).with_integration(GTM_INTEGRATION_ID);
         proxy_config.forward_ec_id = false;
 
         // If it's a POST request (e.g. /collect beacon), we must manually attach the body
@@ -145,7 +145,8 @@ impl GptIntegration {
     ) -> ProxyRequestConfig<'a> {
         let mut config = ProxyRequestConfig::new(target_url)
             .with_streaming()
-            .without_forward_headers();
+            .without_forward_headers()
+            .with_integration(GPT_INTEGRATION_ID);
         config.follow_redirects = false;
         config.forward_ec_id = false;
 
@@ -515,6 +515,7 @@
           "hb_pb",
           "hb_bidder",
           "hb_adid",
+          "hb_deal",
           "hb_cache_host",
           "hb_cache_path",
         ].forEach(function (k) {
@@ -27,6 +27,7 @@ use crate::integrations::{
 };
 use crate::platform::{PlatformHttpRequest, RuntimeServices};
 use crate::settings::{IntegrationConfig, Settings};
+use crate::third_party_log::send_third_party_request;
 
 const LOCKR_INTEGRATION_ID: &str = "lockr";
 
@@ -107,7 +108,7 @@ impl LockrIntegration {
     /// Handle SDK serving — fetch from Lockr CDN and serve through first-party domain.
     async fn handle_sdk_serving(
         &self,
-        _settings: &Settings,
+        settings: &Settings,
         services: &RuntimeServices,
     ) -> Result<http::Response<EdgeBody>, Report<TrustedServerError>> {
         let sdk_url = &self.config.sdk_url;
@@ -126,15 +127,18 @@ impl LockrIntegration {
         let backend_name = Self::backend_name_for_url(services, sdk_url)
             .change_context(Self::error("Failed to determine backend for SDK fetch"))?;
 
-        let lockr_response = services
-            .http_client()
-            .send(PlatformHttpRequest::new(lockr_req, backend_name))
-            .await
-            .change_context(Self::error(format!(
-                "Failed to fetch Lockr SDK from {}",
-                sdk_url
-            )))?
-            .response;
+        let lockr_response = send_third_party_request(
+            services,
+            settings,
+            LOCKR_INTEGRATION_ID,
+            PlatformHttpRequest::new(lockr_req, backend_name),
+        )
+        .await
+        .change_context(Self::error(format!(
+            "Failed to fetch Lockr SDK from {}",
+            sdk_url
+        )))?
+        .response;
 
         if !lockr_response.status().is_success() {
             log::error!(
@@ -178,7 +182,7 @@ impl LockrIntegration {
     /// Handle API proxy — forward requests to the configured Lockr API endpoint.
     async fn handle_api_proxy(
         &self,
-        _settings: &Settings,
+        settings: &Settings,
         services: &RuntimeServices,
         req: http::Request<EdgeBody>,
     ) -> Result<http::Response<EdgeBody>, Report<TrustedServerError>> {
@@ -222,15 +226,18 @@ impl LockrIntegration {
         let backend_name = Self::backend_name_for_url(services, &self.config.api_endpoint)
             .change_context(Self::error("Failed to determine backend for API proxy"))?;
 
-        let response = services
-            .http_client()
-            .send(PlatformHttpRequest::new(target_req, backend_name))
-            .await
-            .change_context(Self::error(format!(
-                "Failed to forward request to {}",
-                target_url
-            )))?
-            .response;
+        let response = send_third_party_request(
+            services,
+            settings,
+            LOCKR_INTEGRATION_ID,
+            PlatformHttpRequest::new(target_req, backend_name),
+        )
+        .await
+        .change_context(Self::error(format!(
+            "Failed to forward request to {}",
+            target_url
+        )))?
+        .response;
 
         log::info!("Lockr API responded with status {}", response.status());
 
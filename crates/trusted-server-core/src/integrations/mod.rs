@@ -15,11 +15,13 @@ pub mod adserver_mock;
 pub mod aps;
 pub mod datadome;
 pub mod didomi;
+pub mod gam;
 pub mod google_tag_manager;
 pub mod gpt;
 pub mod gpt_diagnostics;
 pub mod lockr;
 pub mod nextjs;
+pub mod openrtb_bidders;
 pub mod osano;
 pub mod permutive;
 pub mod prebid;
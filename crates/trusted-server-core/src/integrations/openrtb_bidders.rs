@@ -0,0 +1,1395 @@
+//! Config-driven generic `OpenRTB` 2.6 bidders.
+//!
+//! Each `[[integrations.openrtb_bidders]]` entry registers one auction provider
+//! that posts a standard `BidRequest` to a direct SSP endpoint and parses a
+//! standard `BidResponse`. Connecting a new SSP is a configuration change:
+//!
+//! ```toml
+//! [[integrations.openrtb_bidders]]
+//! name = "magnite"
+//! endpoint = "https://ssp.example/openrtb2/auction"
+//! seat = "12345"
+//! timeout_ms = 700
+//! media_types = ["banner", "video"]
+//! imp_ext = { rp = { zone_id = "{params.zoneId}", size = "{width}x{height}" } }
+//!
+//! [integrations.openrtb_bidders.header_auth]
+//! header = "Authorization"
+//! secret_name = "magnite_api_token"
+//! prefix = "Bearer "
+//! ```
+//!
+//! `imp_ext` is a template rendered once per slot into `imp.ext`. String values
+//! may contain `{slot_id}`, `{width}`, `{height}`, `{seat}` and
+//! `{params.<key>}` placeholders, where `params` is the slot's
+//! `bidders.<name>` object. A string that is exactly one placeholder keeps the
+//! substituted value's JSON type. Slots missing a referenced param are not
+//! sent to that bidder.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use edgezero_core::body::Body as EdgeBody;
+use error_stack::{Report, ResultExt};
+use http::header::HeaderName;
+use http::{HeaderValue, Method, StatusCode, header};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map as JsonMap, Value as Json, json};
+use url::Url;
+use validator::{Validate, ValidationError};
+
+use crate::auction::bid_cache::BID_EXP_METADATA_KEY;
+use crate::auction::provider::{AuctionProvider, ProviderRequestOutcome};
+use crate::auction::types::{
+    AdFormat, AdSlot, AuctionContext, AuctionRequest, AuctionResponse, Bid, MediaType,
+};
+use crate::error::TrustedServerError;
+use crate::integrations::{
+    UPSTREAM_RTB_MAX_RESPONSE_BYTES, collect_response_bounded,
+    ensure_integration_backend_with_timeout, predict_integration_backend_name,
+};
+use crate::openrtb::{
+    Banner, Device, Format, Geo, Imp, OpenRtbBid, OpenRtbRequest, OpenRtbResponse, Publisher, Regs,
+    RegsExt, Site, ToExt, User, UserExt, Video, apply_ad_quality, to_openrtb_i32, to_openrtb_pmp,
+};
+use crate::platform::{PlatformHttpRequest, PlatformResponse, RuntimeServices, StoreName};
+use crate::settings::{IntegrationConfig, Settings};
+
+/// Integration key holding the `[[integrations.openrtb_bidders]]` array.
+pub const OPENRTB_BIDDERS_INTEGRATION_ID: &str = "openrtb_bidders";
+
+/// Provider names owned by built-in integrations or the JS orchestrator.
+const RESERVED_BIDDER_NAMES: &[&str] = &["prebid", "aps", "adserver_mock", "gam", "trustedServer"];
+const MAX_BIDDER_NAME_BYTES: usize = 64;
+const MAX_PAGE_URL_BYTES: usize = 8192;
+const OPENRTB_MTYPE_BANNER: i32 = 1;
+const OPENRTB_MTYPE_VIDEO: i32 = 2;
+
+// ============================================================================
+// Configuration
+// ============================================================================
+
+/// All configured generic `OpenRTB` bidders.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, Validate)]
+#[serde(transparent)]
+#[validate(schema(function = "validate_unique_bidder_names"))]
+pub struct OpenRtbBiddersConfig {
+    /// One entry per `[[integrations.openrtb_bidders]]` table.
+    #[serde(deserialize_with = "crate::settings::vec_from_seq_or_map")]
+    #[validate(nested)]
+    pub bidders: Vec<OpenRtbBidderConfig>,
+}
+
+impl IntegrationConfig for OpenRtbBiddersConfig {
+    fn is_enabled(&self) -> bool {
+        self.bidders.iter().any(|bidder| bidder.enabled)
+    }
+}
+
+/// Configuration for one direct `OpenRTB` SSP endpoint.
+#[derive(Debug, Clone, Deserialize, Serialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct OpenRtbBidderConfig {
+    /// Whether this bidder participates in auctions.
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Provider name used in `[auction].providers`, slot `bidders` keys and
+    /// bid attribution.
+    #[validate(custom(function = "validate_bidder_name"))]
+    pub name: String,
+    /// HTTPS `OpenRTB` 2.6 bid endpoint.
+    #[validate(custom(function = "validate_bidder_endpoint"))]
+    pub endpoint: String,
+    /// Publisher seat or account ID at the SSP, sent as `site.publisher.id`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seat: Option<String>,
+    /// Timeout in milliseconds.
+    #[serde(default = "default_timeout_ms")]
+    #[validate(range(min = 1))]
+    pub timeout_ms: u32,
+    /// Media types this bidder is offered. `native` is not supported.
+    #[serde(default = "default_media_types")]
+    #[validate(custom(function = "validate_media_types"))]
+    pub media_types: Vec<MediaType>,
+    /// Template rendered into each `imp.ext`.
+    #[serde(default)]
+    #[validate(custom(function = "validate_imp_ext_template"))]
+    pub imp_ext: JsonMap<String, Json>,
+    /// Optional request header carrying a credential from the secret store.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(nested)]
+    pub header_auth: Option<OpenRtbHeaderAuth>,
+    /// Bid currency requested via `cur` and required on responses.
+    #[serde(default = "default_currency")]
+    pub currency: String,
+    /// MIME types advertised on video impressions.
+    #[serde(default = "default_video_mimes")]
+    pub video_mimes: Vec<String>,
+}
+
+/// Header authentication for an [`OpenRtbBidderConfig`].
+#[derive(Debug, Clone, Deserialize, Serialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct OpenRtbHeaderAuth {
+    /// Header name, e.g. `Authorization` or `X-Api-Key`.
+    #[validate(custom(function = "validate_auth_header_name"))]
+    pub header: String,
+    /// Secret store holding the credential.
+    #[serde(default = "default_secret_store")]
+    #[validate(length(min = 1))]
+    pub secret_store: String,
+    /// Secret key holding the credential.
+    #[validate(length(min = 1))]
+    pub secret_name: String,
+    /// Text prepended to the credential, e.g. `"Bearer "`.
+    #[serde(default)]
+    pub prefix: String,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_timeout_ms() -> u32 {
+    800
+}
+
+fn default_media_types() -> Vec<MediaType> {
+    vec![MediaType::Banner]
+}
+
+fn default_currency() -> String {
+    "USD".to_string()
+}
+
+fn default_video_mimes() -> Vec<String> {
+    vec![
+        "video/mp4".to_string(),
+        "application/javascript".to_string(),
+    ]
+}
+
+fn default_secret_store() -> String {
+    "ts_secrets".to_string()
+}
+
+fn validate_bidder_name(value: &str) -> Result<(), ValidationError> {
+    let valid_chars = value
+        .bytes()
+        .all(|byte| byte.is_ascii_lowercase() || byte.is_ascii_digit() || byte == b'_');
+    if value.is_empty() || value.len() > MAX_BIDDER_NAME_BYTES || !valid_chars {
+        return Err(ValidationError::new("invalid_openrtb_bidder_name"));
+    }
+    if RESERVED_BIDDER_NAMES.contains(&value) {
+        let mut error = ValidationError::new("reserved_openrtb_bidder_name");
+        error.message = Some(format!("`{value}` is reserved for a built-in provider").into());
+        return Err(error);
+    }
+    Ok(())
+}
+
+fn validate_bidder_endpoint(value: &str) -> Result<(), ValidationError> {
+    let parsed =
+        Url::parse(value).map_err(|_| ValidationError::new("invalid_openrtb_bidder_endpoint"))?;
+    if parsed.scheme() != "https"
+        || parsed.host_str().is_none()
+        || !parsed.username().is_empty()
+        || parsed.password().is_some()
+    {
+        return Err(ValidationError::new("invalid_openrtb_bidder_endpoint"));
+    }
+    Ok(())
+}
+
+fn validate_media_types(value: &[MediaType]) -> Result<(), ValidationError> {
+    if value.is_empty() {
+        return Err(ValidationError::new("empty_openrtb_bidder_media_types"));
+    }
+    if value.contains(&MediaType::Native) {
+        let mut error = ValidationError::new("unsupported_openrtb_bidder_media_type");
+        error.message = Some("generic OpenRTB bidders support banner and video only".into());
+        return Err(error);
+    }
+    Ok(())
+}
+
+fn validate_auth_header_name(value: &str) -> Result<(), ValidationError> {
+    HeaderName::from_bytes(value.as_bytes())
+        .map(|_| ())
+        .map_err(|_| ValidationError::new("invalid_openrtb_auth_header"))
+}
+
+fn validate_imp_ext_template(value: &JsonMap<String, Json>) -> Result<(), ValidationError> {
+    let mut keys = Vec::new();
+    for item in value.values() {
+        collect_placeholders(item, &mut keys);
+    }
+    if let Some(key) = keys.iter().find(|key| !is_known_placeholder(key)) {
+        let mut error = ValidationError::new("unknown_openrtb_imp_ext_placeholder");
+        error.message = Some(format!("unknown imp_ext placeholder `{{{key}}}`").into());
+        return Err(error);
+    }
+    Ok(())
+}
+
+fn validate_unique_bidder_names(config: &OpenRtbBiddersConfig) -> Result<(), ValidationError> {
+    let mut seen = HashSet::new();
+    for bidder in &config.bidders {
+        if !seen.insert(bidder.name.as_str()) {
+            let mut error = ValidationError::new("duplicate_openrtb_bidder_name");
+            error.message = Some(format!("bidder `{}` is configured twice", bidder.name).into());
+            return Err(error);
+        }
+    }
+    Ok(())
+}
+
+// ============================================================================
+// imp.ext templating
+// ============================================================================
+
+fn is_known_placeholder(key: &str) -> bool {
+    matches!(key, "slot_id" | "width" | "height" | "seat")
+        || key
+            .strip_prefix("params.")
+            .is_some_and(|param| !param.is_empty())
+}
+
+/// Split `value` into literal text and `{placeholder}` keys.
+fn template_parts(value: &str) -> Vec<TemplatePart<'_>> {
+    let mut parts = Vec::new();
+    let mut rest = value;
+    while let Some(start) = rest.find('{') {
+        let after = &rest[start + 1..];
+        let Some(end) = after.find('}') else {
+            break;
+        };
+        let key = &after[..end];
+        let is_key = !key.is_empty()
+            && key
+                .bytes()
+                .all(|byte| byte.is_ascii_alphanumeric() || byte == b'_' || byte == b'.');
+        if is_key {
+            if start > 0 {
+                parts.push(TemplatePart::Literal(&rest[..start]));
+            }
+            parts.push(TemplatePart::Placeholder(key));
+        } else {
+            parts.push(TemplatePart::Literal(&rest[..=start + end + 1]));
+        }
+        rest = &after[end + 1..];
+    }
+    if !rest.is_empty() {
+        parts.push(TemplatePart::Literal(rest));
+    }
+    parts
+}
+
+enum TemplatePart<'a> {
+    Literal(&'a str),
+    Placeholder(&'a str),
+}
+
+fn collect_placeholders(value: &Json, keys: &mut Vec<String>) {
+    match value {
+        Json::String(text) => {
+            for part in template_parts(text) {
+                if let TemplatePart::Placeholder(key) = part {
+                    keys.push(key.to_string());
+                }
+            }
+        }
+        Json::Array(items) => items
+            .iter()
+            .for_each(|item| collect_placeholders(item, keys)),
+        Json::Object(map) => map
+            .values()
+            .for_each(|item| collect_placeholders(item, keys)),
+        _ => {}
+    }
+}
+
+/// Values available to one slot's `imp_ext` rendering.
+struct TemplateVars<'a> {
+    slot_id: &'a str,
+    width: u32,
+    height: u32,
+    seat: Option<&'a str>,
+    params: Option<&'a Json>,
+}
+
+impl TemplateVars<'_> {
+    fn lookup(&self, key: &str) -> Option<Json> {
+        match key {
+            "slot_id" => Some(json!(self.slot_id)),
+            "width" => Some(json!(self.width)),
+            "height" => Some(json!(self.height)),
+            "seat" => self.seat.map(|seat| json!(seat)),
+            _ => {
+                let param = key.strip_prefix("params.")?;
+                self.params?
+                    .get(param)
+                    .filter(|value| !value.is_null())
+                    .cloned()
+            }
+        }
+    }
+}
+
+/// Render one template value, returning the first placeholder that could not
+/// be resolved on failure.
+fn render_template(template: &Json, vars: &TemplateVars<'_>) -> Result<Json, String> {
+    match template {
+        Json::String(text) => {
+            let parts = template_parts(text);
+            if let [TemplatePart::Placeholder(key)] = parts.as_slice() {
+                return vars.lookup(key).ok_or_else(|| (*key).to_string());
+            }
+            let mut rendered = String::with_capacity(text.len());
+            for part in parts {
+                match part {
+                    TemplatePart::Literal(literal) => rendered.push_str(literal),
+                    TemplatePart::Placeholder(key) => match vars.lookup(key) {
+                        Some(Json::String(value)) => rendered.push_str(&value),
+                        Some(value) => rendered.push_str(&value.to_string()),
+                        None => return Err(key.to_string()),
+                    },
+                }
+            }
+            Ok(Json::String(rendered))
+        }
+        Json::Array(items) => items
+            .iter()
+            .map(|item| render_template(item, vars))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Json::Array),
+        Json::Object(map) => map
+            .iter()
+            .map(|(key, item)| render_template(item, vars).map(|value| (key.clone(), value)))
+            .collect::<Result<JsonMap<_, _>, _>>()
+            .map(Json::Object),
+        other => Ok(other.clone()),
+    }
+}
+
+// ============================================================================
+// Provider
+// ============================================================================
+
+/// Generic `OpenRTB` 2.6 auction provider for one configured SSP.
+pub struct OpenRtbBidderProvider {
+    name: &'static str,
+    config: OpenRtbBidderConfig,
+}
+
+impl OpenRtbBidderProvider {
+    /// Create a provider from validated configuration.
+    #[must_use]
+    pub fn new(config: OpenRtbBidderConfig) -> Self {
+        // Intentional leak: runs once per configured bidder at startup.
+        // `AuctionProvider::provider_name` requires `&'static str`.
+        let name: &'static str = Box::leak(config.name.clone().into_boxed_str());
+        Self { name, config }
+    }
+
+    fn supports(&self, media_type: &MediaType) -> bool {
+        self.config.media_types.contains(media_type)
+    }
+
+    fn build_imp(&self, slot: &AdSlot) -> Result<Option<Imp>, String> {
+        let slot_context = format!("slot '{}'", slot.id);
+        let banner_formats: Vec<&AdFormat> = slot
+            .formats
+            .iter()
+            .filter(|format| format.media_type == MediaType::Banner)
+            .filter(|_| self.supports(&MediaType::Banner))
+            .collect();
+        let video_format = slot
+            .formats
+            .iter()
+            .find(|format| format.media_type == MediaType::Video)
+            .filter(|_| self.supports(&MediaType::Video));
+        let Some(primary) = banner_formats.first().copied().or(video_format) else {
+            return Ok(None);
+        };
+
+        let banner = (!banner_formats.is_empty()).then(|| {
+            let format: Vec<Format> = banner_formats
+                .iter()
+                .map(|format| Format {
+                    w: to_openrtb_i32(format.width, "format.w", &slot_context),
+                    h: to_openrtb_i32(format.height, "format.h", &slot_context),
+                    ..Default::default()
+                })
+                .collect();
+            Banner {
+                w: format.first().and_then(|format| format.w),
+                h: format.first().and_then(|format| format.h),
+                format,
+                ..Default::default()
+            }
+        });
+        let video = video_format.map(|format| Video {
+            mimes: self.config.video_mimes.clone(),
+            w: to_openrtb_i32(format.width, "video.w", &slot_context),
+            h: to_openrtb_i32(format.height, "video.h", &slot_context),
+            ..Default::default()
+        });
+
+        let vars = TemplateVars {
+            slot_id: &slot.id,
+            width: primary.width,
+            height: primary.height,
+            seat: self.config.seat.as_deref(),
+            params: slot.bidders.get(self.name),
+        };
+        let ext = match render_template(&Json::Object(self.config.imp_ext.clone()), &vars)? {
+            Json::Object(map) if !map.is_empty() => Some(map),
+            _ => None,
+        };
+
+        Ok(Some(Imp {
+            id: Some(slot.id.clone()),
+            tagid: Some(slot.id.clone()),
+            banner,
+            video,
+            bidfloor: slot.floor_price,
+            bidfloorcur: slot.floor_price.map(|_| self.config.currency.clone()),
+            pmp: to_openrtb_pmp(slot.pmp.as_ref(), slot.floor_price, &self.config.currency),
+            secure: Some(true),
+            ext,
+            ..Default::default()
+        }))
+    }
+
+    fn build_regs(consent: Option<&crate::consent::ConsentContext>) -> Option<Regs> {
+        let consent = consent?;
+        let ext = RegsExt {
+            gdpr: Some(u8::from(consent.gdpr_applies)),
+            us_privacy: consent.raw_us_privacy.clone(),
+            gpp: consent.raw_gpp_string.clone(),
+            gpp_sid: consent.gpp_section_ids.clone(),
+        };
+        Some(Regs {
+            gdpr: Some(consent.gdpr_applies),
+            us_privacy: ext.us_privacy.clone(),
+            gpp: ext.gpp.clone(),
+            gpp_sid: ext
+                .gpp_sid
+                .as_ref()
+                .map(|ids| ids.iter().map(|id| i32::from(*id)).collect())
+                .unwrap_or_default(),
+            ext: ext.to_ext(),
+            ..Default::default()
+        })
+    }
+
+    fn page_url(request: &AuctionRequest) -> String {
+        request
+            .publisher
+            .page_url
+            .as_deref()
+            .filter(|value| value.len() <= MAX_PAGE_URL_BYTES)
+            .and_then(|value| Url::parse(value).ok())
+            .filter(|url| matches!(url.scheme(), "http" | "https") && url.host_str().is_some())
+            .map(|mut url| {
+                url.set_query(None);
+                url.set_fragment(None);
+                url.to_string()
+            })
+            .unwrap_or_else(|| format!("https://{}", request.publisher.domain))
+    }
+
+    /// Build the `BidRequest`, returning it together with per-slot skip reasons.
+    fn build_openrtb_request(
+        &self,
+        request: &AuctionRequest,
+        context: &AuctionContext<'_>,
+    ) -> (OpenRtbRequest, BTreeMap<String, u64>) {
+        let mut skipped = BTreeMap::new();
+        let mut imp = Vec::new();
+        for slot in &request.slots {
+            match self.build_imp(slot) {
+                Ok(Some(built)) => imp.push(built),
+                Ok(None) => {}
+                Err(missing) => {
+                    log::debug!(
+                        "{}: skipping slot '{}' — imp_ext placeholder `{{{missing}}}` is unresolved",
+                        self.name,
+                        slot.id
+                    );
+                    *skipped.entry("missing_imp_param".to_string()).or_default() += 1;
+                }
+            }
+        }
+
+        let consent = request.user.consent.as_ref();
+        let raw_tc = consent.and_then(|value| value.raw_tc_string.clone());
+        let user = Some(User {
+            id: request.user.id.clone(),
+            consent: raw_tc.clone(),
+            ext: UserExt {
+                consent: raw_tc,
+                consented_providers_settings: None,
+                eids: request.user.eids.clone(),
+            }
+            .to_ext(),
+            ..Default::default()
+        });
+        let device = request.device.as_ref().map(|device| Device {
+            ua: device.user_agent.clone(),
+            ip: device.ip.clone(),
+            geo: device.geo.as_ref().map(|geo| Geo {
+                country: Some(geo.country.clone()),
+                region: geo.region.clone(),
+                city: Some(geo.city.clone()),
+                metro: (geo.metro_code > 0).then(|| geo.metro_code.to_string()),
+                r#type: Some(2),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+
+        let mut openrtb = OpenRtbRequest {
+            id: Some(request.id.clone()),
+            imp,
+            site: Some(Site {
+                domain: Some(request.publisher.domain.clone()),
+                page: Some(Self::page_url(request)),
+                publisher: Some(Publisher {
+                    id: self.config.seat.clone(),
+                    domain: Some(request.publisher.domain.clone()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            user,
+            device,
+            regs: Self::build_regs(consent),
+            tmax: to_openrtb_i32(context.timeout_ms, "tmax", self.name),
+            cur: vec![self.config.currency.clone()],
+            ..Default::default()
+        };
+        apply_ad_quality(&mut openrtb, &request.ad_quality);
+        (openrtb, skipped)
+    }
+
+    fn auth_header_value(
+        &self,
+        services: &RuntimeServices,
+    ) -> Result<Option<(HeaderName, HeaderValue)>, Report<TrustedServerError>> {
+        let Some(auth) = &self.config.header_auth else {
+            return Ok(None);
+        };
+        let secret = services
+            .secret_store()
+            .get_string(
+                &StoreName::from(auth.secret_store.as_str()),
+                &auth.secret_name,
+            )
+            .change_context(TrustedServerError::Auction {
+                message: format!("Failed to load {} auth credential", self.name),
+            })?;
+        let name = HeaderName::from_bytes(auth.header.as_bytes()).change_context(
+            TrustedServerError::Auction {
+                message: format!("Invalid {} auth header name", self.name),
+            },
+        )?;
+        let mut value = HeaderValue::from_str(&format!("{}{}", auth.prefix, secret.trim()))
+            .change_context(TrustedServerError::Auction {
+                message: format!("Invalid {} auth header value", self.name),
+            })?;
+        value.set_sensitive(true);
+        Ok(Some((name, value)))
+    }
+
+    fn bid_media_type(bid: &OpenRtbBid, adm: &str) -> Option<MediaType> {
+        match bid.mtype {
+            Some(OPENRTB_MTYPE_BANNER) => Some(MediaType::Banner),
+            Some(OPENRTB_MTYPE_VIDEO) => Some(MediaType::Video),
+            Some(_) => None,
+            None => {
+                let markup = adm.trim_start();
+                if markup.starts_with("<VAST") || markup.starts_with("<?xml") {
+                    Some(MediaType::Video)
+                } else {
+                    Some(MediaType::Banner)
+                }
+            }
+        }
+    }
+
+    fn bid_dimensions(
+        bid: &OpenRtbBid,
+        slot: &AdSlot,
+        media_type: &MediaType,
+    ) -> Option<(u32, u32)> {
+        let formats: Vec<&AdFormat> = slot
+            .formats
+            .iter()
+            .filter(|format| &format.media_type == media_type)
+            .collect();
+        let reported = bid
+            .w
+            .zip(bid.h)
+            .and_then(|(w, h)| Some((u32::try_from(w).ok()?, u32::try_from(h).ok()?)))
+            .filter(|(w, h)| *w > 0 && *h > 0);
+        match (reported, media_type) {
+            (Some((w, h)), MediaType::Banner) => formats
+                .iter()
+                .any(|format| format.width == w && format.height == h)
+                .then_some((w, h)),
+            (Some(dimensions), _) => Some(dimensions),
+            (None, _) => match formats.as_slice() {
+                [only] => Some((only.width, only.height)),
+                _ => None,
+            },
+        }
+    }
+
+    fn parse_bid(
+        &self,
+        bid: &OpenRtbBid,
+        seat: Option<&str>,
+        slots: &HashMap<&str, &AdSlot>,
+    ) -> Result<Bid, &'static str> {
+        let slot_id = bid.impid.as_deref().ok_or("unknown_impid")?;
+        let slot = slots.get(slot_id).ok_or("unknown_impid")?;
+        let price = bid
+            .price
+            .filter(|price| price.is_finite() && *price >= 0.0)
+            .ok_or("invalid_price")?;
+        let adm = bid
+            .adm
+            .as_deref()
+            .filter(|adm| !adm.trim().is_empty())
+            .ok_or("missing_adm")?;
+        let media_type = Self::bid_media_type(bid, adm)
+            .filter(|media_type| self.supports(media_type))
+            .filter(|media_type| {
+                slot.formats
+                    .iter()
+                    .any(|format| &format.media_type == media_type)
+            })
+            .ok_or("unsupported_media_type")?;
+        let (width, height) =
+            Self::bid_dimensions(bid, slot, &media_type).ok_or("invalid_dimensions")?;
+
+        let mut metadata = HashMap::new();
+        if let Some(seat) = seat {
+            metadata.insert("seat".to_string(), json!(seat));
+        }
+        if let Some(exp) = bid.exp.filter(|exp| *exp > 0) {
+            metadata.insert(BID_EXP_METADATA_KEY.to_string(), json!(exp));
+        }
+
+        Ok(Bid {
+            slot_id: slot_id.to_string(),
+            price: Some(price),
+            currency: self.config.currency.clone(),
+            creative: Some(adm.to_string()),
+            adomain: (!bid.adomain.is_empty()).then(|| bid.adomain.clone()),
+            bidder: self.name.to_string(),
+            width,
+            height,
+            nurl: bid.nurl.clone(),
+            burl: bid.burl.clone(),
+            bid_id: bid.id.clone(),
+            ad_id: bid.adid.clone(),
+            creative_id: bid.crid.clone(),
+            deal_id: bid.dealid.clone().filter(|id| !id.is_empty()),
+            categories: bid.cat.clone(),
+            attributes: bid.attr.clone(),
+            renderer: None,
+            cache_id: None,
+            cache_host: None,
+            cache_path: None,
+            metadata,
+        })
+    }
+
+    fn parse_openrtb_response(
+        &self,
+        response: &OpenRtbResponse,
+        response_time_ms: u64,
+        request: &AuctionRequest,
+    ) -> AuctionResponse {
+        if response
+            .cur
+            .as_deref()
+            .is_some_and(|currency| !currency.eq_ignore_ascii_case(&self.config.currency))
+        {
+            return AuctionResponse::no_bid(self.name, response_time_ms)
+                .with_metadata("drop_reasons", json!({"unsupported_currency": 1}));
+        }
+
+        let slots: HashMap<&str, &AdSlot> = request
+            .slots
+            .iter()
+            .map(|slot| (slot.id.as_str(), slot))
+            .collect();
+        let mut reasons: BTreeMap<String, u64> = BTreeMap::new();
+        let mut selected: HashMap<String, Bid> = HashMap::new();
+        let mut dropped = 0_u64;
+
+        for seatbid in &response.seatbid {
+            for bid in &seatbid.bid {
+                match self.parse_bid(bid, seatbid.seat.as_deref(), &slots) {
+                    Ok(candidate) => {
+                        let candidate_price = candidate.price.unwrap_or_default();
+                        let replace = selected.get(&candidate.slot_id).is_none_or(|current| {
+                            candidate_price > current.price.unwrap_or_default()
+                        });
+                        if replace {
+                            if selected
+                                .insert(candidate.slot_id.clone(), candidate)
+                                .is_some()
+                            {
+                                dropped += 1;
+                                *reasons.entry("lost_to_higher_bid".to_string()).or_default() += 1;
+                            }
+                        } else {
+                            dropped += 1;
+                            *reasons.entry("lost_to_higher_bid".to_string()).or_default() += 1;
+                        }
+                    }
+                    Err(reason) => {
+                        dropped += 1;
+                        *reasons.entry(reason.to_string()).or_default() += 1;
+                    }
+                }
+            }
+        }
+
+        let accepted = selected.len();
+        let mut parsed = if selected.is_empty() {
+            AuctionResponse::no_bid(self.name, response_time_ms)
+        } else {
+            AuctionResponse::success(
+                self.name,
+                selected.into_values().collect(),
+                response_time_ms,
+            )
+        };
+        parsed.metadata.extend([
+            ("seatbid_count".to_string(), json!(response.seatbid.len())),
+            ("accepted_bid_count".to_string(), json!(accepted)),
+            ("dropped_bid_count".to_string(), json!(dropped)),
+            ("drop_reasons".to_string(), json!(reasons)),
+        ]);
+        parsed
+    }
+
+    async fn parse_response_inner(
+        &self,
+        response: PlatformResponse,
+        response_time_ms: u64,
+        request: Option<&AuctionRequest>,
+    ) -> Result<AuctionResponse, Report<TrustedServerError>> {
+        let response = response.response;
+        let status = response.status();
+        if status == StatusCode::NO_CONTENT {
+            return Ok(AuctionResponse::no_bid(self.name, response_time_ms));
+        }
+        if !status.is_success() {
+            log::warn!("{} returns non-success status {}", self.name, status);
+            return Ok(AuctionResponse::error(self.name, response_time_ms)
+                .with_metadata("error_type", json!("http_status")));
+        }
+        let body = collect_response_bounded(
+            response.into_body(),
+            UPSTREAM_RTB_MAX_RESPONSE_BYTES,
+            self.name,
+        )
+        .await
+        .change_context(TrustedServerError::Auction {
+            message: format!("Failed to read {} response body", self.name),
+        })?;
+        if body.is_empty() {
+            return Ok(AuctionResponse::no_bid(self.name, response_time_ms));
+        }
+        let parsed: OpenRtbResponse = match serde_json::from_slice(&body) {
+            Ok(parsed) => parsed,
+            Err(error) => {
+                log::warn!("Failed to parse {} OpenRTB response: {error}", self.name);
+                return Ok(AuctionResponse::error(self.name, response_time_ms)
+                    .with_metadata("error_type", json!("parse_response"))
+                    .with_metadata("drop_reasons", json!({"unexpected_response_shape": 1})));
+            }
+        };
+        let Some(request) = request else {
+            log::error!(
+                "{} cannot parse a bid response without the original auction request",
+                self.name
+            );
+            return Ok(AuctionResponse::error(self.name, response_time_ms)
+                .with_metadata("drop_reasons", json!({"missing_request_context": 1})));
+        };
+        let parsed = self.parse_openrtb_response(&parsed, response_time_ms, request);
+        log::info!(
+            "{} returns {} accepted bids in {}ms",
+            self.name,
+            parsed.bids.len(),
+            response_time_ms
+        );
+        Ok(parsed)
+    }
+}
+
+#[async_trait(?Send)]
+impl AuctionProvider for OpenRtbBidderProvider {
+    fn provider_name(&self) -> &'static str {
+        self.name
+    }
+
+    async fn request_bids(
+        &self,
+        request: &AuctionRequest,
+        context: &AuctionContext<'_>,
+    ) -> Result<ProviderRequestOutcome, Report<TrustedServerError>> {
+        let (openrtb, skipped) = self.build_openrtb_request(request, context);
+        if openrtb.imp.is_empty() {
+            let response =
+                AuctionResponse::no_bid(self.name, 0).with_metadata("drop_reasons", json!(skipped));
+            return Ok(ProviderRequestOutcome::Immediate(response));
+        }
+        log::info!(
+            "{} requests bids for {} impressions",
+            self.name,
+            openrtb.imp.len()
+        );
+        let body = serde_json::to_vec(&openrtb).change_context(TrustedServerError::Auction {
+            message: format!("Failed to serialize {} OpenRTB request", self.name),
+        })?;
+
+        let mut builder = http::Request::builder()
+            .method(Method::POST)
+            .uri(&self.config.endpoint)
+            .header(header::CONTENT_TYPE, "application/json")
+            .header("x-openrtb-version", "2.6");
+        if let Some((name, value)) = self.auth_header_value(context.services)? {
+            builder = builder.header(name, value);
+        }
+        let outbound_request =
+            builder
+                .body(EdgeBody::from(body))
+                .change_context(TrustedServerError::Auction {
+                    message: format!("Failed to build {} request", self.name),
+                })?;
+
+        let backend = ensure_integration_backend_with_timeout(
+            context.services,
+            &self.config.endpoint,
+            self.name,
+            Duration::from_millis(u64::from(context.timeout_ms)),
+        )
+        .change_context(TrustedServerError::Auction {
+            message: format!("Failed to resolve {} backend", self.name),
+        })?;
+        let pending = context
+            .services
+            .http_client()
+            .send_async(PlatformHttpRequest::new(outbound_request, backend))
+            .await
+            .change_context(TrustedServerError::Auction {
+                message: format!("Failed to send {} request", self.name),
+            })?;
+        Ok(ProviderRequestOutcome::pending(pending))
+    }
+
+    async fn parse_response(
+        &self,
+        response: PlatformResponse,
+        response_time_ms: u64,
+    ) -> Result<AuctionResponse, Report<TrustedServerError>> {
+        self.parse_response_inner(response, response_time_ms, None)
+            .await
+    }
+
+    async fn parse_response_with_context(
+        &self,
+        response: PlatformResponse,
+        response_time_ms: u64,
+        request: &AuctionRequest,
+        _context: &AuctionContext<'_>,
+    ) -> Result<AuctionResponse, Report<TrustedServerError>> {
+        self.parse_response_inner(response, response_time_ms, Some(request))
+            .await
+    }
+
+    fn supports_media_type(&self, media_type: &MediaType) -> bool {
+        self.supports(media_type)
+    }
+
+    fn timeout_ms(&self) -> u32 {
+        self.config.timeout_ms
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    fn backend_name(&self, services: &RuntimeServices, timeout_ms: u32) -> Option<String> {
+        predict_integration_backend_name(
+            services,
+            &self.config.endpoint,
+            self.name,
+            Duration::from_millis(u64::from(timeout_ms)),
+        )
+        .inspect_err(|error| {
+            log::error!("Failed to predict {} backend name: {error:?}", self.name);
+        })
+        .ok()
+    }
+}
+
+/// Return the names of every enabled generic `OpenRTB` bidder.
+///
+/// # Errors
+///
+/// Returns an error when the `openrtb_bidders` configuration is invalid.
+pub fn enabled_bidder_names(
+    settings: &Settings,
+) -> Result<Vec<String>, Report<TrustedServerError>> {
+    let Some(config) =
+        settings.integration_config::<OpenRtbBiddersConfig>(OPENRTB_BIDDERS_INTEGRATION_ID)?
+    else {
+        return Ok(Vec::new());
+    };
+    Ok(config
+        .bidders
+        .into_iter()
+        .filter(|bidder| bidder.enabled)
+        .map(|bidder| bidder.name)
+        .collect())
+}
+
+/// Register one auction provider per enabled generic `OpenRTB` bidder.
+///
+/// # Errors
+///
+/// Returns an error when the `openrtb_bidders` configuration is invalid.
+pub fn register_providers(
+    settings: &Settings,
+) -> Result<Vec<Arc<dyn AuctionProvider>>, Report<TrustedServerError>> {
+    let Some(config) =
+        settings.integration_config::<OpenRtbBiddersConfig>(OPENRTB_BIDDERS_INTEGRATION_ID)?
+    else {
+        return Ok(Vec::new());
+    };
+    Ok(config
+        .bidders
+        .into_iter()
+        .filter(|bidder| bidder.enabled)
+        .map(|bidder| {
+            log::info!("Registering generic OpenRTB bidder '{}'", bidder.name);
+            Arc::new(OpenRtbBidderProvider::new(bidder)) as Arc<dyn AuctionProvider>
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auction::types::{PublisherInfo, UserInfo};
+    use crate::platform::test_support::{
+        HashMapSecretStore, StubHttpClient, build_services_with_secret_and_http_client,
+        noop_services,
+    };
+    use crate::test_support::tests::{crate_test_settings_str, create_test_settings};
+
+    fn bidder_config(value: Json) -> OpenRtbBidderConfig {
+        serde_json::from_value(value).expect("should parse bidder config")
+    }
+
+    fn provider() -> OpenRtbBidderProvider {
+        OpenRtbBidderProvider::new(bidder_config(json!({
+            "name": "example_ssp",
+            "endpoint": "https://ssp.example/openrtb2/auction",
+            "seat": "seat-42",
+            "media_types": ["banner", "video"],
+            "imp_ext": {
+                "example_ssp": {
+                    "zone": "{params.zoneId}",
+                    "size": "{width}x{height}",
+                    "tag": "{slot_id}"
+                }
+            }
+        })))
+    }
+
+    fn slot(id: &str, formats: Vec<AdFormat>, params: Option<Json>) -> AdSlot {
+        AdSlot {
+            id: id.to_string(),
+            formats,
+            floor_price: Some(0.5),
+            targeting: HashMap::new(),
+            bidders: params
+                .map(|params| HashMap::from([("example_ssp".to_string(), params)]))
+                .unwrap_or_default(),
+            pmp: None,
+        }
+    }
+
+    fn banner(width: u32, height: u32) -> AdFormat {
+        AdFormat {
+            media_type: MediaType::Banner,
+            width,
+            height,
+        }
+    }
+
+    fn request() -> AuctionRequest {
+        AuctionRequest {
+            id: "fictional-auction".to_string(),
+            slots: vec![
+                slot(
+                    "atf",
+                    vec![banner(300, 250), banner(300, 600)],
+                    Some(json!({"zoneId": 1234})),
+                ),
+                slot(
+                    "preroll",
+                    vec![AdFormat {
+                        media_type: MediaType::Video,
+                        width: 640,
+                        height: 360,
+                    }],
+                    Some(json!({"zoneId": 99})),
+                ),
+                slot("no-params", vec![banner(728, 90)], None),
+            ],
+            publisher: PublisherInfo {
+                domain: "publisher.example".to_string(),
+                page_url: Some(
+                    "https://publisher.example/article?session=abc#comments".to_string(),
+                ),
+            },
+            user: UserInfo {
+                id: Some("fictional-user".to_string()),
+                consent: None,
+                eids: None,
+            },
+            device: None,
+            site: None,
+            context: HashMap::new(),
+            ad_quality: Default::default(),
+        }
+    }
+
+    fn context<'a>(
+        settings: &'a Settings,
+        downstream: &'a http::Request<EdgeBody>,
+        services: &'a RuntimeServices,
+    ) -> AuctionContext<'a> {
+        AuctionContext {
+            settings,
+            request: downstream,
+            timeout_ms: 650,
+            provider_responses: None,
+            services,
+        }
+    }
+
+    fn downstream() -> http::Request<EdgeBody> {
+        http::Request::builder()
+            .uri("https://publisher.example/auction")
+            .body(EdgeBody::empty())
+            .expect("should build downstream request")
+    }
+
+    #[test]
+    fn settings_parse_bidder_array_and_apply_defaults() {
+        let toml = format!(
+            r#"{}
+            [[integrations.openrtb_bidders]]
+            name = "index"
+            endpoint = "https://index.example/bid"
+
+            [[integrations.openrtb_bidders]]
+            name = "kargo"
+            endpoint = "https://kargo.example/bid"
+            enabled = false
+            "#,
+            crate_test_settings_str()
+        );
+        let settings = Settings::from_toml(&toml).expect("should parse settings");
+
+        let config = settings
+            .integration_config::<OpenRtbBiddersConfig>(OPENRTB_BIDDERS_INTEGRATION_ID)
+            .expect("should validate bidders")
+            .expect("should be enabled");
+        assert_eq!(config.bidders.len(), 2);
+        assert_eq!(config.bidders[0].timeout_ms, 800);
+        assert_eq!(config.bidders[0].media_types, vec![MediaType::Banner]);
+        assert_eq!(config.bidders[0].currency, "USD");
+        assert_eq!(
+            enabled_bidder_names(&settings).expect("should list bidders"),
+            vec!["index".to_string()],
+            "should skip disabled bidders"
+        );
+        assert_eq!(
+            register_providers(&settings)
+                .expect("should register providers")
+                .iter()
+                .map(|provider| provider.provider_name())
+                .collect::<Vec<_>>(),
+            vec!["index"]
+        );
+    }
+
+    #[test]
+    fn config_validation_rejects_bad_bidders() {
+        let cases = [
+            (
+                json!([{"name": "aps", "endpoint": "https://a.example/bid"}]),
+                "reserved",
+            ),
+            (
+                json!([
+                    {"name": "dup", "endpoint": "https://a.example/bid"},
+                    {"name": "dup", "endpoint": "https://b.example/bid"}
+                ]),
+                "configured twice",
+            ),
+            (
+                json!([{"name": "plain", "endpoint": "http://a.example/bid"}]),
+                "endpoint",
+            ),
+            (
+                json!([{
+                    "name": "native_only",
+                    "endpoint": "https://a.example/bid",
+                    "media_types": ["native"]
+                }]),
+                "banner and video only",
+            ),
+            (
+                json!([{
+                    "name": "typo",
+                    "endpoint": "https://a.example/bid",
+                    "imp_ext": {"zone": "{param.zoneId}"}
+                }]),
+                "placeholder",
+            ),
+        ];
+        for (value, expected) in cases {
+            let config: OpenRtbBiddersConfig =
+                serde_json::from_value(value).expect("should deserialize bidder list");
+            let err = config
+                .validate()
+                .expect_err("should reject invalid bidders");
+            assert!(
+                err.to_string().contains(expected),
+                "should mention {expected}: {err}"
+            );
+        }
+    }
+
+    #[test]
+    fn render_template_preserves_types_and_interpolates_strings() {
+        let params = json!({"zoneId": 1234, "site": "front"});
+        let vars = TemplateVars {
+            slot_id: "atf",
+            width: 300,
+            height: 250,
+            seat: Some("seat-42"),
+            params: Some(&params),
+        };
+        let rendered = render_template(
+            &json!({
+                "zone": "{params.zoneId}",
+                "label": "{params.site}-{slot_id}",
+                "size": ["{width}", "{height}"],
+                "literal": "{not a placeholder}",
+                "fixed": true
+            }),
+            &vars,
+        )
+        .expect("should render template");
+
+        assert_eq!(
+            rendered,
+            json!({
+                "zone": 1234,
+                "label": "front-atf",
+                "size": [300, 250],
+                "literal": "{not a placeholder}",
+                "fixed": true
+            })
+        );
+        assert_eq!(
+            render_template(&json!("{params.missing}"), &vars),
+            Err("params.missing".to_string())
+        );
+    }
+
+    #[test]
+    fn build_openrtb_request_renders_imps_and_skips_slots_missing_params() {
+        let provider = provider();
+        let settings = create_test_settings();
+        let downstream = downstream();
+        let services = noop_services();
+        let context = context(&settings, &downstream, &services);
+
+        let (openrtb, skipped) = provider.build_openrtb_request(&request(), &context);
+
+        assert_eq!(openrtb.imp.len(), 2, "should skip the slot without params");
+        assert_eq!(skipped.get("missing_imp_param"), Some(&1));
+        let atf = &openrtb.imp[0];
+        let banner = atf.banner.as_ref().expect("should build banner");
+        assert_eq!(banner.format.len(), 2);
+        assert_eq!((banner.w, banner.h), (Some(300), Some(250)));
+        assert_eq!(atf.bidfloorcur.as_deref(), Some("USD"));
+        assert_eq!(
+            Json::Object(atf.ext.clone().expect("should render imp ext")),
+            json!({"example_ssp": {"zone": 1234, "size": "300x250", "tag": "atf"}})
+        );
+        let video = openrtb.imp[1].video.as_ref().expect("should build video");
+        assert_eq!((video.w, video.h), (Some(640), Some(360)));
+        assert!(video.mimes.contains(&"video/mp4".to_string()));
+
+        let site = openrtb.site.as_ref().expect("should build site");
+        assert_eq!(
+            site.page.as_deref(),
+            Some("https://publisher.example/article")
+        );
+        assert_eq!(
+            site.publisher
+                .as_ref()
+                .and_then(|publisher| publisher.id.as_deref()),
+            Some("seat-42")
+        );
+        assert_eq!(openrtb.tmax, Some(650));
+        assert_eq!(openrtb.cur, vec!["USD".to_string()]);
+    }
+
+    #[test]
+    fn request_bids_sends_auth_header_from_secret_store() {
+        let provider = OpenRtbBidderProvider::new(bidder_config(json!({
+            "name": "example_ssp",
+            "endpoint": "https://ssp.example/openrtb2/auction",
+            "header_auth": {
+                "header": "X-Api-Key",
+                "secret_name": "example_ssp_key",
+                "prefix": "Token "
+            }
+        })));
+        let stub = Arc::new(StubHttpClient::new());
+        stub.push_response(204, Vec::new());
+        let services = build_services_with_secret_and_http_client(
+            HashMapSecretStore::new(HashMap::from([(
+                "example_ssp_key".to_string(),
+                b"s3cr3t".to_vec(),
+            )])),
+            Arc::clone(&stub) as Arc<dyn crate::platform::PlatformHttpClient>,
+        );
+        let settings = create_test_settings();
+        let downstream = downstream();
+        let context = context(&settings, &downstream, &services);
+
+        let outcome = futures::executor::block_on(provider.request_bids(&request(), &context))
+            .expect("should dispatch request");
+
+        assert!(
+            matches!(outcome, ProviderRequestOutcome::Pending { .. }),
+            "should return a pending request"
+        );
+        let headers = stub
+            .recorded_request_headers()
+            .into_iter()
+            .next()
+            .expect("should capture request headers");
+        assert!(
+            headers
+                .iter()
+                .any(|(name, value)| name == "x-api-key" && value == "Token s3cr3t"),
+            "should send the configured auth header: {headers:?}"
+        );
+        assert_eq!(
+            stub.recorded_async_request_uris(),
+            vec!["https://ssp.example/openrtb2/auction".to_string()]
+        );
+    }
+
+    #[test]
+    fn parse_openrtb_response_keeps_highest_valid_bid_per_slot() {
+        let provider = provider();
+        let response: OpenRtbResponse = serde_json::from_value(json!({
+            "id": "fictional-auction",
+            "cur": "USD",
+            "seatbid": [{
+                "seat": "buyer-1",
+                "bid": [
+                    {"id": "b1", "impid": "atf", "price": 1.25, "w": 300, "h": 250,
+                     "adm": "<div>low</div>", "crid": "c1", "adomain": ["low.example"]},
+                    {"id": "b2", "impid": "atf", "price": 2.5, "w": 300, "h": 600,
+                     "adm": "<div>high</div>", "crid": "c2", "mtype": 1,
+                     "nurl": "https://ssp.example/win", "dealid": "pmp-7"},
+                    {"id": "b3", "impid": "atf", "price": 9.0, "w": 970, "h": 250,
+                     "adm": "<div>wrong size</div>"},
+                    {"id": "b4", "impid": "preroll", "price": 4.0,
+                     "adm": "<VAST version=\"4.0\"></VAST>"},
+                    {"id": "b5", "impid": "unknown", "price": 3.0, "adm": "<div></div>"},
+                    {"id": "b6", "impid": "atf", "price": 5.0, "w": 300, "h": 250}
+                ]
+            }]
+        }))
+        .expect("should parse OpenRTB response");
+
+        let parsed = provider.parse_openrtb_response(&response, 42, &request());
+
+        assert_eq!(parsed.bids.len(), 2);
+        let atf = parsed
+            .bids
+            .iter()
+            .find(|bid| bid.slot_id == "atf")
+            .expect("should select atf bid");
+        assert_eq!(atf.bid_id.as_deref(), Some("b2"));
+        assert_eq!((atf.width, atf.height), (300, 600));
+        assert_eq!(atf.creative.as_deref(), Some("<div>high</div>"));
+        assert_eq!(atf.bidder, "example_ssp");
+        assert_eq!(atf.nurl.as_deref(), Some("https://ssp.example/win"));
+        assert_eq!(atf.metadata.get("seat"), Some(&json!("buyer-1")));
+        assert_eq!(atf.deal_id.as_deref(), Some("pmp-7"));
+        let preroll = parsed
+            .bids
+            .iter()
+            .find(|bid| bid.slot_id == "preroll")
+            .expect("should select video bid");
+        assert_eq!(
+            (preroll.width, preroll.height),
+            (640, 360),
+            "should fall back to the slot's video size"
+        );
+        assert_eq!(
+            parsed.metadata.get("drop_reasons"),
+            Some(&json!({
+                "invalid_dimensions": 1,
+                "lost_to_higher_bid": 1,
+                "missing_adm": 1,
+                "unknown_impid": 1
+            }))
+        );
+    }
+
+    #[test]
+    fn parse_openrtb_response_rejects_unexpected_currency() {
+        let provider = provider();
+        let response: OpenRtbResponse = serde_json::from_value(json!({
+            "cur": "EUR",
+            "seatbid": [{"bid": [{"id": "b1", "impid": "atf", "price": 1.0, "adm": "<div></div>"}]}]
+        }))
+        .expect("should parse OpenRTB response");
+
+        let parsed = provider.parse_openrtb_response(&response, 5, &request());
+
+        assert!(parsed.bids.is_empty(), "should drop non-USD bids");
+        assert_eq!(
+            parsed.metadata.get("drop_reasons"),
+            Some(&json!({"unsupported_currency": 1}))
+        );
+    }
+}
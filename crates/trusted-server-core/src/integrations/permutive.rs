@@ -23,6 +23,7 @@ use crate::integrations::{
 };
 use crate::platform::{PlatformHttpRequest, RuntimeServices};
 use crate::settings::{IntegrationConfig, Settings};
+use crate::third_party_log::send_third_party_request;
 
 const PERMUTIVE_INTEGRATION_ID: &str = "permutive";
 
@@ -107,7 +108,7 @@ impl PermutiveIntegration {
     /// Handle SDK serving - fetch from Permutive CDN and serve through first-party domain.
     async fn handle_sdk_serving(
         &self,
-        _settings: &Settings,
+        settings: &Settings,
         services: &RuntimeServices,
     ) -> Result<http::Response<EdgeBody>, Report<TrustedServerError>> {
         log::info!("Handling Permutive SDK request");
@@ -129,15 +130,18 @@ impl PermutiveIntegration {
         let backend_name = Self::backend_name_for_url(services, &sdk_url)
             .change_context(Self::error("Failed to determine backend for SDK fetch"))?;
 
-        let permutive_response = services
-            .http_client()
-            .send(PlatformHttpRequest::new(permutive_req, backend_name))
-            .await
-            .change_context(Self::error(format!(
-                "Failed to fetch Permutive SDK from {}",
-                sdk_url
-            )))?
-            .response;
+        let permutive_response = send_third_party_request(
+            services,
+            settings,
+            PERMUTIVE_INTEGRATION_ID,
+            PlatformHttpRequest::new(permutive_req, backend_name),
+        )
+        .await
+        .change_context(Self::error(format!(
+            "Failed to fetch Permutive SDK from {}",
+            sdk_url
+        )))?
+        .response;
 
         if !permutive_response.status().is_success() {
             log::error!(
@@ -182,6 +186,7 @@ impl PermutiveIntegration {
 
     async fn forward_proxy_request(
         &self,
+        settings: &Settings,
         services: &RuntimeServices,
         req: http::Request<EdgeBody>,
         route_prefix: &str,
@@ -236,15 +241,18 @@ impl PermutiveIntegration {
                 format!("Failed to determine backend for {} proxy", route_name),
             ))?;
 
-        let response = services
-            .http_client()
-            .send(PlatformHttpRequest::new(target_req, backend_name))
-            .await
-            .change_context(Self::error(format!(
-                "Failed to forward request to {}",
-                target_url
-            )))?
-            .response;
+        let response = send_third_party_request(
+            services,
+            settings,
+            PERMUTIVE_INTEGRATION_ID,
+            PlatformHttpRequest::new(target_req, backend_name),
+        )
+        .await
+        .change_context(Self::error(format!(
+            "Failed to forward request to {}",
+            target_url
+        )))?
+        .response;
 
         log::info!(
             "{} responded with status: {}",
@@ -361,6 +369,7 @@ impl IntegrationProxy for PermutiveIntegration {
 
         if path.starts_with("/integrations/permutive/api/") {
             self.forward_proxy_request(
+                settings,
                 services,
                 req,
                 "/integrations/permutive/api",
@@ -370,6 +379,7 @@ impl IntegrationProxy for PermutiveIntegration {
             .await
         } else if path.starts_with("/integrations/permutive/secure-signal/") {
             self.forward_proxy_request(
+                settings,
                 services,
                 req,
                 "/integrations/permutive/secure-signal",
@@ -379,6 +389,7 @@ impl IntegrationProxy for PermutiveIntegration {
             .await
         } else if path.starts_with("/integrations/permutive/events/") {
             self.forward_proxy_request(
+                settings,
                 services,
                 req,
                 "/integrations/permutive/events",
@@ -388,6 +399,7 @@ impl IntegrationProxy for PermutiveIntegration {
             .await
         } else if path.starts_with("/integrations/permutive/sync/") {
             self.forward_proxy_request(
+                settings,
                 services,
                 req,
                 "/integrations/permutive/sync",
@@ -397,6 +409,7 @@ impl IntegrationProxy for PermutiveIntegration {
             .await
         } else if path.starts_with("/integrations/permutive/cdn/") {
             self.forward_proxy_request(
+                settings,
                 services,
                 req,
                 "/integrations/permutive/cdn",
@@ -18,6 +18,7 @@ use serde_json::Value as Json;
 use url::{Url, Url as ParsedUrl};
 use validator::{Validate, ValidationError};
 
+use crate::auction::bid_cache::BID_EXP_METADATA_KEY;
 use crate::auction::orchestrator::ERROR_TYPE_HTTP_STATUS;
 use crate::auction::provider::{AuctionProvider, ProviderRequestOutcome};
 use crate::auction::types::{
@@ -37,7 +38,7 @@ use crate::integrations::{
 use crate::openrtb::{
     Banner, ConsentedProvidersSettings, Device, Format, Geo, Imp, ImpExt, ImpStoredRequest,
     OpenRtbRequest, PrebidExt, PrebidImpExt, Publisher, Regs, RegsExt, RequestExt, Site, ToExt,
-    TrustedServerExt, User, UserExt, to_openrtb_i32,
+    TrustedServerExt, User, UserExt, apply_ad_quality, to_openrtb_i32, to_openrtb_pmp,
 };
 use crate::platform::{PlatformHttpRequest, PlatformResponse, RuntimeServices};
 use crate::proxy::{ProxyRequestConfig, is_host_allowed, proxy_request};
@@ -931,7 +932,8 @@ impl PrebidIntegration {
             .without_forward_headers()
             .with_streaming()
             .with_allowed_domains(&settings.proxy.allowed_domains)
-            .with_https_only();
+            .with_https_only()
+            .with_integration(PREBID_INTEGRATION_ID);
 
         let response = proxy_request(settings, req, proxy_config, services).await?;
         Ok(self.sanitize_external_bundle_response(response, cache_mode))
@@ -1796,6 +1798,7 @@ impl PrebidAuctionProvider {
                     // multi-currency support is needed, this should come from
                     // config or the AdSlot itself.
                     bidfloorcur: slot.floor_price.map(|_| DEFAULT_CURRENCY.to_string()),
+                    pmp: to_openrtb_pmp(slot.pmp.as_ref(), slot.floor_price, DEFAULT_CURRENCY),
                     secure: Some(true), // require HTTPS creatives
                     tagid: Some(slot.id.clone()),
                     ext: ImpExt {
@@ -1984,7 +1987,7 @@ impl PrebidAuctionProvider {
         // edge timeouts.
         let tmax = to_openrtb_i32(context.timeout_ms, "tmax", "request");
 
-        let request = OpenRtbRequest {
+        let mut openrtb = OpenRtbRequest {
             id: Some(request.id.clone()),
             imp: imps,
             site: Some(Site {
@@ -2006,9 +2009,10 @@ impl PrebidAuctionProvider {
             ext,
             ..Default::default()
         };
+        apply_ad_quality(&mut openrtb, &request.ad_quality);
 
         PrebidRequestBuild {
-            request,
+            request: openrtb,
             disposition,
         }
     }
@@ -2378,6 +2382,40 @@ impl PrebidAuctionProvider {
             .get("crid")
             .and_then(|v| v.as_str())
             .map(String::from);
+        let metadata = bid_obj
+            .get("exp")
+            .and_then(serde_json::Value::as_u64)
+            .filter(|exp| *exp > 0)
+            .map(|exp| {
+                std::collections::HashMap::from([(
+                    BID_EXP_METADATA_KEY.to_string(),
+                    serde_json::json!(exp),
+                )])
+            })
+            .unwrap_or_default();
+        let deal_id = bid_obj
+            .get("dealid")
+            .and_then(|v| v.as_str())
+            .filter(|id| !id.is_empty())
+            .map(String::from);
+        let categories = bid_obj
+            .get("cat")
+            .and_then(|v| v.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| v.as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let attributes = bid_obj
+            .get("attr")
+            .and_then(|v| v.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| v.as_i64().and_then(|v| i32::try_from(v).ok()))
+                    .collect()
+            })
+            .unwrap_or_default();
 
         let adomain = bid_obj
             .get("adomain")
@@ -2445,11 +2483,14 @@ impl PrebidAuctionProvider {
             bid_id,
             ad_id,
             creative_id,
+            deal_id,
+            categories,
+            attributes,
             renderer: None,
             cache_id,
             cache_host,
             cache_path,
-            metadata: std::collections::HashMap::new(),
+            metadata,
         })
     }
 }
@@ -2565,6 +2606,10 @@ impl AuctionProvider for PrebidAuctionProvider {
             self.config.consent_forwarding,
             context.services.client_info().client_ip,
         );
+        crate::otel::inject_traceparent(
+            pbs_req.headers_mut(),
+            context.services.request_trace().traceparent(),
+        );
 
         let pbs_body = serde_json::to_vec(&openrtb).change_context(TrustedServerError::Prebid {
             message: "Failed to serialize Prebid request body".to_string(),
@@ -2877,6 +2922,7 @@ mod tests {
                 floor_price: None,
                 targeting: HashMap::new(),
                 bidders: HashMap::new(),
+                pmp: None,
             }],
             publisher: PublisherInfo {
                 domain: "pub.example".to_string(),
@@ -2890,6 +2936,7 @@ mod tests {
             device: None,
             site: None,
             context: HashMap::new(),
+            ad_quality: Default::default(),
         }
     }
 
@@ -5007,6 +5054,7 @@ external_bundle_sri = "sha384-AAAA"
             floor_price: None,
             targeting: HashMap::new(),
             bidders: HashMap::new(),
+            pmp: None,
         }];
 
         let settings = make_settings();
@@ -5026,6 +5074,81 @@ external_bundle_sri = "sha384-AAAA"
         );
     }
 
+    #[test]
+    fn to_openrtb_forwards_slot_pmp_deals() {
+        use crate::auction::types::{SlotDeal, SlotPmp};
+
+        let provider = PrebidAuctionProvider::new(base_config());
+        let mut auction_request = create_test_auction_request();
+        auction_request.slots[0].floor_price = Some(2.0);
+        auction_request.slots[0].pmp = Some(SlotPmp {
+            private_auction: false,
+            deals: vec![SlotDeal {
+                id: "pmp-1".to_string(),
+                bidfloor: None,
+                wseat: vec!["seat-a".to_string()],
+            }],
+        });
+
+        let settings = make_settings();
+        let request = build_test_request();
+        let context = create_test_auction_context(&settings, &request);
+
+        let openrtb = provider.to_openrtb(
+            &auction_request,
+            &context,
+            None,
+            make_request_info(&context),
+        );
+
+        let pmp = openrtb.imp[0].pmp.as_ref().expect("should set imp.pmp");
+        assert_eq!(pmp.private_auction, Some(false));
+        assert_eq!(pmp.deals[0].id.as_deref(), Some("pmp-1"));
+        assert_eq!(
+            pmp.deals[0].bidfloor,
+            Some(2.0),
+            "should inherit the slot floor"
+        );
+        assert_eq!(pmp.deals[0].wseat, vec!["seat-a".to_string()]);
+    }
+
+    #[test]
+    fn to_openrtb_forwards_ad_quality_block_lists() {
+        use crate::auction::ad_quality::AdQualityRules;
+
+        let provider = PrebidAuctionProvider::new(base_config());
+        let mut auction_request = create_test_auction_request();
+        auction_request.ad_quality = AdQualityRules {
+            badv: vec!["bad.example".to_string()],
+            bcat: vec!["IAB25".to_string()],
+            battr: vec![8],
+            ..AdQualityRules::default()
+        };
+
+        let settings = make_settings();
+        let request = build_test_request();
+        let context = create_test_auction_context(&settings, &request);
+
+        let openrtb = provider.to_openrtb(
+            &auction_request,
+            &context,
+            None,
+            make_request_info(&context),
+        );
+
+        assert_eq!(openrtb.badv, vec!["bad.example".to_string()]);
+        assert_eq!(openrtb.bcat, vec!["IAB25".to_string()]);
+        assert_eq!(
+            openrtb.imp[0]
+                .banner
+                .as_ref()
+                .expect("should build banner")
+                .battr,
+            vec![8],
+            "should forward blocked creative attributes on the banner"
+        );
+    }
+
     #[test]
     fn to_openrtb_sets_geo_lat_lon_metro() {
         let provider = PrebidAuctionProvider::new(base_config());
@@ -5851,6 +5974,7 @@ external_bundle_sri = "sha384-AAAA"
             }),
             site: None,
             context: HashMap::new(),
+            ad_quality: Default::default(),
         }
     }
 
@@ -5865,6 +5989,7 @@ external_bundle_sri = "sha384-AAAA"
             floor_price: None,
             targeting: HashMap::new(),
             bidders,
+            pmp: None,
         }
     }
 
@@ -7655,6 +7780,48 @@ set = { networkId = 42 }
         );
     }
 
+    #[test]
+    fn parse_bid_extracts_dealid() {
+        let bid_json = serde_json::json!({
+            "id": "bid-1",
+            "impid": "atf_sidebar_ad",
+            "price": 6.0,
+            "w": 300,
+            "h": 250,
+            "dealid": "pg-deal-1",
+        });
+        let provider = PrebidAuctionProvider::new(base_config());
+        let bid = provider
+            .parse_bid(&bid_json, "example-bidder")
+            .expect("should parse bid");
+        assert_eq!(
+            bid.deal_id.as_deref(),
+            Some("pg-deal-1"),
+            "should carry the OpenRTB dealid onto the bid"
+        );
+    }
+
+    #[test]
+    fn parse_bid_carries_exp_for_the_bid_cache() {
+        let bid_json = serde_json::json!({
+            "id": "bid-1",
+            "impid": "atf_sidebar_ad",
+            "price": 6.0,
+            "w": 300,
+            "h": 250,
+            "exp": 120,
+        });
+        let provider = PrebidAuctionProvider::new(base_config());
+        let bid = provider
+            .parse_bid(&bid_json, "example-bidder")
+            .expect("should parse bid");
+        assert_eq!(
+            bid.metadata.get(BID_EXP_METADATA_KEY),
+            Some(&serde_json::json!(120)),
+            "should carry the OpenRTB exp into bid metadata"
+        );
+    }
+
     #[test]
     fn copy_request_headers_replaces_client_supplied_xff_with_attested_ip() {
         let from = http::Request::builder()
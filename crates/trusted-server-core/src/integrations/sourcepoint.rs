@@ -41,6 +41,7 @@ use crate::integrations::{
 };
 use crate::platform::{PlatformHttpRequest, RuntimeServices};
 use crate::settings::{IntegrationConfig, Settings};
+use crate::third_party_log::send_third_party_request;
 
 const SOURCEPOINT_INTEGRATION_ID: &str = "sourcepoint";
 const SOURCEPOINT_CDN_HOST: &str = "cdn.privacy-mgmt.com";
@@ -797,7 +798,7 @@ impl IntegrationProxy for SourcepointIntegration {
 
     async fn handle(
         &self,
-        _settings: &Settings,
+        settings: &Settings,
         services: &RuntimeServices,
         req: Request<EdgeBody>,
     ) -> Result<Response<EdgeBody>, Report<TrustedServerError>> {
@@ -870,12 +871,15 @@ impl IntegrationProxy for SourcepointIntegration {
             None,
         )?;
 
-        let mut response = services
-            .http_client()
-            .send(PlatformHttpRequest::new(proxy_req, backend_name))
-            .await
-            .change_context(Self::error("Sourcepoint upstream request failed"))?
-            .response;
+        let mut response = send_third_party_request(
+            services,
+            settings,
+            SOURCEPOINT_INTEGRATION_ID,
+            PlatformHttpRequest::new(proxy_req, backend_name),
+        )
+        .await
+        .change_context(Self::error("Sourcepoint upstream request failed"))?
+        .response;
 
         log::info!(
             "Sourcepoint: upstream responded with status {}",
@@ -196,7 +196,8 @@ impl IntegrationProxy for TestlightIntegration {
 
         let payload_bytes = Self::rewrite_request_body(&payload_bytes, &ec_id)?;
 
-        let mut proxy_config = ProxyRequestConfig::new(&self.config.endpoint);
+        let mut proxy_config = ProxyRequestConfig::new(&self.config.endpoint)
+            .with_integration(TESTLIGHT_INTEGRATION_ID);
         proxy_config.forward_ec_id = false;
         proxy_config.body = Some(payload_bytes);
         proxy_config.stream_passthrough = true;
@@ -5,19 +5,24 @@
 //!
 //! # Modules
 //!
+//! - [`access_log`]: Sampled access-log middleware with field redaction
 //! - [`auth`]: Basic authentication enforcement helpers
 //! - [`constants`]: Application-wide constants and configuration values
 //! - [`cookies`]: Cookie parsing and generation utilities
 //! - [`error`]: Error types and error handling utilities
 //! - [`consent`]: Consent signal extraction and logging
 //! - [`geo`]: Geographic location utilities and DMA code extraction
+//! - [`metrics`]: Operational counters and histograms, Prometheus rendering
 //! - [`models`]: Data models for ad serving and callbacks
+//! - [`otel`]: OpenTelemetry trace spans and W3C `traceparent` propagation
 //! - [`integrations::prebid`]: Prebid integration and real-time bidding support
 //! - [`settings`]: Configuration management and validation
 //! - [`streaming_replacer`]: Streaming URL replacement for large responses
 //! - [`ec`]: Edge Cookie (EC) identity subsystem — ID generation, consent gating, lifecycle
 //! - [`test_support`]: Testing utilities and mocks
 //! - [`tester_cookie`]: Optional tester-cookie endpoint helpers
+//! - [`third_party_log`]: Sampled log of outbound calls to third-party hosts
+//! - [`tinybird`]: Tinybird Events API client shared by telemetry sinks
 
 #![cfg_attr(
     test,
@@ -31,6 +36,7 @@
     )
 )]
 
+pub mod access_log;
 pub(crate) mod asset_image_optimizer;
 pub mod auction;
 pub mod auction_config_types;
@@ -38,23 +44,30 @@ pub mod auth;
 pub mod cache_policy;
 pub mod config;
 pub mod config_payload;
+pub mod config_release;
+pub mod config_schema;
+pub mod config_signing;
 pub mod consent;
 pub mod consent_config;
 pub mod constants;
 pub mod cookies;
 pub mod creative;
+pub mod creative_cache;
 pub mod creative_opportunities;
 pub mod ec;
 pub(crate) mod edge_cookie;
 pub mod error;
+pub mod experiments;
 pub mod geo;
 pub mod host_header;
 pub(crate) mod host_rewrite;
 pub mod html_processor;
 pub mod http_util;
 pub mod integrations;
+pub mod metrics;
 pub mod models;
 pub mod openrtb;
+pub mod otel;
 pub mod platform;
 pub mod price_bucket;
 pub mod proxy;
@@ -71,7 +84,10 @@ pub mod streaming_processor;
 pub mod streaming_replacer;
 pub mod test_support;
 pub mod tester_cookie;
+pub mod third_party_log;
+pub mod tinybird;
 pub mod tsjs;
+pub mod vast;
 
 #[cfg(test)]
 mod migration_guards;
@@ -0,0 +1,860 @@
+//! In-process operational metrics.
+//!
+//! [`MetricsRegistry`] keeps counters and latency histograms keyed by a metric
+//! name plus a small, fixed set of labels (provider, status, route, ...). It is
+//! carried on [`RuntimeServices`] so core code records into whatever registry
+//! the adapter supplied.
+//!
+//! How the numbers leave the process depends on the adapter's lifetime:
+//!
+//! - Long-lived adapters (Axum) share one registry across requests and serve
+//!   it from the authenticated `GET /_ts/admin/metrics` endpoint in the
+//!   Prometheus text exposition format ([`handle_admin_metrics`]).
+//! - Per-request adapters (Fastly, Spin) cannot be scraped, so they drain the
+//!   registry at the end of each request and hand the rows to the
+//!   [`MetricsSink`] built by [`metrics_sink_from_settings`]
+//!   ([`flush_metrics_best_effort`]). Their endpoint answers with
+//!   [`admin_metrics_not_supported`].
+//! - Cloudflare neither serves nor flushes metrics yet and answers the
+//!   endpoint the same way.
+//!
+//! Label values come from configuration (provider and store names) or from
+//! closed sets defined here, never from request data, so series cardinality
+//! stays bounded. [`MAX_SERIES`] is a defensive cap on top of that.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use chrono::Utc;
+use edgezero_core::body::Body as EdgeBody;
+use error_stack::Report;
+use http::{HeaderValue, Response, StatusCode, header};
+use serde::Serialize;
+
+use crate::auction::orchestrator::OrchestrationResult;
+use crate::auction::types::{AuctionResponse, BidStatus};
+use crate::error::TrustedServerError;
+use crate::platform::RuntimeServices;
+use crate::settings::{Settings, TinybirdSettings};
+use crate::tinybird::TinybirdEventsTarget;
+
+/// Content type of the Prometheus text exposition format.
+pub const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Requests handled, by route class and response status.
+pub const METRIC_HTTP_REQUESTS: &str = "ts_http_requests_total";
+/// Auction provider calls, by provider and outcome.
+pub const METRIC_PROVIDER_REQUESTS: &str = "ts_auction_provider_requests_total";
+/// Auction provider response time in milliseconds, by provider.
+pub const METRIC_PROVIDER_LATENCY: &str = "ts_auction_provider_latency_ms";
+/// Bids returned, by provider.
+pub const METRIC_PROVIDER_BIDS: &str = "ts_auction_provider_bids_total";
+/// Completed auctions, by whether any slot produced a winner.
+pub const METRIC_AUCTIONS: &str = "ts_auctions_total";
+/// Shared template cache lookups, by result.
+pub const METRIC_TEMPLATE_CACHE: &str = "ts_template_cache_lookups_total";
+/// Server-side auction consent decisions, by surface and outcome.
+pub const METRIC_CONSENT_DECISIONS: &str = "ts_consent_decisions_total";
+/// EC identity-graph KV operations, by store, operation and result.
+pub const METRIC_KV_OPERATIONS: &str = "ts_kv_operations_total";
+
+/// `HELP` text for every metric this crate records.
+const METRIC_HELP: &[(&str, &str)] = &[
+    (
+        METRIC_HTTP_REQUESTS,
+        "Requests handled, by route class and response status.",
+    ),
+    (
+        METRIC_PROVIDER_REQUESTS,
+        "Auction provider calls, by provider and outcome.",
+    ),
+    (
+        METRIC_PROVIDER_LATENCY,
+        "Auction provider response time in milliseconds.",
+    ),
+    (METRIC_PROVIDER_BIDS, "Bids returned, by provider."),
+    (
+        METRIC_AUCTIONS,
+        "Completed auctions, by whether any slot produced a winner.",
+    ),
+    (
+        METRIC_TEMPLATE_CACHE,
+        "Shared template cache lookups, by result.",
+    ),
+    (
+        METRIC_CONSENT_DECISIONS,
+        "Server-side auction consent decisions, by surface and outcome.",
+    ),
+    (
+        METRIC_KV_OPERATIONS,
+        "EC identity-graph KV operations, by store, operation and result.",
+    ),
+];
+
+/// Upper bounds, in milliseconds, of the latency histogram buckets.
+const LATENCY_BUCKETS_MS: [f64; 10] = [
+    5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0, 5000.0,
+];
+
+/// Maximum number of distinct series one registry holds.
+///
+/// Observations that would create a series past this cap are dropped.
+pub const MAX_SERIES: usize = 2048;
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct SeriesKey {
+    name: &'static str,
+    labels: Vec<(&'static str, String)>,
+}
+
+impl SeriesKey {
+    fn new(name: &'static str, labels: &[(&'static str, &str)]) -> Self {
+        Self {
+            name,
+            labels: labels
+                .iter()
+                .map(|(key, value)| (*key, (*value).to_owned()))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct Histogram {
+    /// Per-bucket (non-cumulative) counts; the last slot is `+Inf`.
+    buckets: [u64; LATENCY_BUCKETS_MS.len() + 1],
+    count: u64,
+    sum: f64,
+}
+
+impl Histogram {
+    fn observe(&mut self, value: f64) {
+        let index = LATENCY_BUCKETS_MS
+            .iter()
+            .position(|bound| value <= *bound)
+            .unwrap_or(LATENCY_BUCKETS_MS.len());
+        self.buckets[index] += 1;
+        self.count += 1;
+        self.sum += value;
+    }
+}
+
+#[derive(Debug, Default)]
+struct MetricsState {
+    counters: BTreeMap<SeriesKey, u64>,
+    histograms: BTreeMap<SeriesKey, Histogram>,
+}
+
+impl MetricsState {
+    fn series_count(&self) -> usize {
+        self.counters.len() + self.histograms.len()
+    }
+}
+
+/// Thread-safe counter and histogram registry.
+#[derive(Debug, Default)]
+pub struct MetricsRegistry {
+    state: Mutex<MetricsState>,
+}
+
+impl MetricsRegistry {
+    /// Create an empty registry.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, MetricsState> {
+        // Metrics are best-effort; a panic elsewhere must not disable them.
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Increment a counter by one.
+    pub fn increment(&self, name: &'static str, labels: &[(&'static str, &str)]) {
+        self.add(name, labels, 1);
+    }
+
+    /// Increment a counter by `value`.
+    pub fn add(&self, name: &'static str, labels: &[(&'static str, &str)], value: u64) {
+        let key = SeriesKey::new(name, labels);
+        let mut state = self.lock_state();
+        if !state.counters.contains_key(&key) && state.series_count() >= MAX_SERIES {
+            log::debug!("metrics: series cap reached, dropping {name}");
+            return;
+        }
+        *state.counters.entry(key).or_default() += value;
+    }
+
+    /// Record one observation in a latency histogram.
+    pub fn observe(&self, name: &'static str, labels: &[(&'static str, &str)], value: f64) {
+        let key = SeriesKey::new(name, labels);
+        let mut state = self.lock_state();
+        if !state.histograms.contains_key(&key) && state.series_count() >= MAX_SERIES {
+            log::debug!("metrics: series cap reached, dropping {name}");
+            return;
+        }
+        state.histograms.entry(key).or_default().observe(value);
+    }
+
+    /// Return the current value of a counter, or zero when it was never
+    /// incremented.
+    #[must_use]
+    pub fn counter_value(&self, name: &'static str, labels: &[(&'static str, &str)]) -> u64 {
+        self.lock_state()
+            .counters
+            .get(&SeriesKey::new(name, labels))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Return whether nothing has been recorded since creation or the last
+    /// [`take_snapshot`](Self::take_snapshot).
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.lock_state().series_count() == 0
+    }
+
+    /// Render every series in the Prometheus text exposition format.
+    #[must_use]
+    pub fn render_prometheus(&self) -> String {
+        let state = self.lock_state();
+        let mut out = String::new();
+        let mut last_name = "";
+
+        for (key, value) in &state.counters {
+            if key.name != last_name {
+                write_metric_header(&mut out, key.name, "counter");
+                last_name = key.name;
+            }
+            let _ = writeln!(
+                out,
+                "{}{} {value}",
+                key.name,
+                format_labels(&key.labels, None)
+            );
+        }
+
+        last_name = "";
+        for (key, histogram) in &state.histograms {
+            if key.name != last_name {
+                write_metric_header(&mut out, key.name, "histogram");
+                last_name = key.name;
+            }
+            let mut cumulative = 0;
+            for (index, count) in histogram.buckets.iter().enumerate() {
+                cumulative += count;
+                let bound = LATENCY_BUCKETS_MS
+                    .get(index)
+                    .map_or_else(|| "+Inf".to_owned(), ToString::to_string);
+                let _ = writeln!(
+                    out,
+                    "{}_bucket{} {cumulative}",
+                    key.name,
+                    format_labels(&key.labels, Some(&bound))
+                );
+            }
+            let labels = format_labels(&key.labels, None);
+            let _ = writeln!(out, "{}_sum{labels} {}", key.name, histogram.sum);
+            let _ = writeln!(out, "{}_count{labels} {}", key.name, histogram.count);
+        }
+
+        out
+    }
+
+    /// Drain every series into a snapshot, leaving the registry empty.
+    ///
+    /// Per-request adapters call this once after the response is sent, so each
+    /// flushed row is a delta for that request.
+    #[must_use]
+    pub fn take_snapshot(&self) -> MetricsSnapshot {
+        let state = std::mem::take(&mut *self.lock_state());
+        let event_ts = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f").to_string();
+        let mut rows = Vec::with_capacity(state.series_count());
+        for (key, value) in state.counters {
+            rows.push(MetricRow::new(
+                &event_ts,
+                key,
+                "counter",
+                value as f64,
+                value,
+            ));
+        }
+        for (key, histogram) in state.histograms {
+            rows.push(MetricRow::new(
+                &event_ts,
+                key,
+                "histogram",
+                histogram.sum,
+                histogram.count,
+            ));
+        }
+        MetricsSnapshot { rows }
+    }
+}
+
+fn write_metric_header(out: &mut String, name: &str, kind: &str) {
+    if let Some((_, help)) = METRIC_HELP.iter().find(|(metric, _)| *metric == name) {
+        let _ = writeln!(out, "# HELP {name} {help}");
+    }
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+fn format_labels(labels: &[(&'static str, String)], le: Option<&str>) -> String {
+    if labels.is_empty() && le.is_none() {
+        return String::new();
+    }
+    let mut parts: Vec<String> = labels
+        .iter()
+        .map(|(key, value)| format!("{key}=\"{}\"", escape_label_value(value)))
+        .collect();
+    if let Some(le) = le {
+        parts.push(format!("le=\"{le}\""));
+    }
+    format!("{{{}}}", parts.join(","))
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// One flushed series.
+///
+/// Counters report their value in both `value` and `count`; histograms report
+/// the sum of observations in `value` and the number of observations in
+/// `count`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MetricRow {
+    /// Flush timestamp, `YYYY-MM-DD HH:MM:SS.mmm` UTC.
+    pub event_ts: String,
+    /// Metric name.
+    pub metric: String,
+    /// `counter` or `histogram`.
+    pub kind: String,
+    /// Label set.
+    pub labels: BTreeMap<String, String>,
+    /// Counter increment or histogram sum.
+    pub value: f64,
+    /// Counter increment or histogram observation count.
+    pub count: u64,
+}
+
+impl MetricRow {
+    fn new(event_ts: &str, key: SeriesKey, kind: &str, value: f64, count: u64) -> Self {
+        Self {
+            event_ts: event_ts.to_owned(),
+            metric: key.name.to_owned(),
+            kind: kind.to_owned(),
+            labels: key
+                .labels
+                .into_iter()
+                .map(|(label, value)| (label.to_owned(), value))
+                .collect(),
+            value,
+            count,
+        }
+    }
+}
+
+/// Series drained from a [`MetricsRegistry`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MetricsSnapshot {
+    rows: Vec<MetricRow>,
+}
+
+impl MetricsSnapshot {
+    /// Return the flushed rows.
+    #[must_use]
+    pub fn rows(&self) -> &[MetricRow] {
+        &self.rows
+    }
+
+    /// Return the number of rows.
+    #[must_use]
+    pub fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Return whether the snapshot has no rows.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Serialize rows as newline-delimited JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when a row cannot be serialized or the payload exceeds
+    /// `max_body_bytes`.
+    pub fn to_ndjson(&self, max_body_bytes: usize) -> Result<String, Report<TrustedServerError>> {
+        let mut body = String::new();
+        for row in &self.rows {
+            let line = serde_json::to_string(row).map_err(|err| {
+                Report::new(TrustedServerError::Proxy {
+                    message: format!("failed to serialize metrics row: {err}"),
+                })
+            })?;
+            body.push_str(&line);
+            body.push('\n');
+            if body.len() > max_body_bytes {
+                return Err(Report::new(TrustedServerError::Proxy {
+                    message: format!("metrics payload exceeds {max_body_bytes} byte limit"),
+                }));
+            }
+        }
+        Ok(body)
+    }
+}
+
+/// Sink for metrics flushed by per-request adapters.
+#[async_trait::async_trait(?Send)]
+pub trait MetricsSink: Send + Sync {
+    /// Return whether this sink emits anything.
+    ///
+    /// When `false`, [`flush_metrics_best_effort`] leaves the registry intact.
+    fn is_enabled(&self) -> bool {
+        true
+    }
+
+    /// Emit one drained snapshot.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the sink cannot start emission. Callers should use
+    /// [`flush_metrics_best_effort`].
+    async fn emit_metrics(
+        &self,
+        services: &RuntimeServices,
+        snapshot: MetricsSnapshot,
+    ) -> Result<(), Report<TrustedServerError>>;
+}
+
+/// No-op metrics sink used by scraped adapters and when flushing is disabled.
+pub struct NoopMetricsSink;
+
+#[async_trait::async_trait(?Send)]
+impl MetricsSink for NoopMetricsSink {
+    fn is_enabled(&self) -> bool {
+        false
+    }
+
+    async fn emit_metrics(
+        &self,
+        _services: &RuntimeServices,
+        _snapshot: MetricsSnapshot,
+    ) -> Result<(), Report<TrustedServerError>> {
+        Ok(())
+    }
+}
+
+/// Build the metrics sink configured by `[tinybird]`.
+///
+/// Returns [`NoopMetricsSink`] unless both `tinybird.enabled` and
+/// `tinybird.metrics_enabled` are set.
+#[must_use]
+pub fn metrics_sink_from_settings(settings: &Settings) -> Arc<dyn MetricsSink> {
+    if settings.tinybird.enabled && settings.tinybird.metrics_enabled {
+        Arc::new(TinybirdMetricsSink::new(&settings.tinybird))
+    } else {
+        Arc::new(NoopMetricsSink)
+    }
+}
+
+/// Appends drained metric rows to the `tinybird.metrics_dataset` datasource.
+#[derive(Debug, Clone)]
+pub struct TinybirdMetricsSink {
+    target: TinybirdEventsTarget,
+}
+
+impl TinybirdMetricsSink {
+    /// Build a sink for the metrics dataset of `config`.
+    #[must_use]
+    pub fn new(config: &TinybirdSettings) -> Self {
+        Self {
+            target: TinybirdEventsTarget::new(
+                "metrics",
+                config,
+                &config.metrics_dataset,
+                &config.metrics_token_secret,
+            ),
+        }
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl MetricsSink for TinybirdMetricsSink {
+    async fn emit_metrics(
+        &self,
+        services: &RuntimeServices,
+        snapshot: MetricsSnapshot,
+    ) -> Result<(), Report<TrustedServerError>> {
+        if snapshot.is_empty() {
+            return Ok(());
+        }
+
+        let body = snapshot.to_ndjson(self.target.max_body_bytes())?;
+        self.target
+            .post_ndjson(services, body, snapshot.row_count())
+            .await
+    }
+}
+
+/// Drain the request's metrics into the configured sink without letting
+/// errors affect customer traffic.
+pub async fn flush_metrics_best_effort(services: &RuntimeServices) {
+    let sink = services.metrics_sink();
+    if !sink.is_enabled() {
+        return;
+    }
+    let snapshot = services.metrics().take_snapshot();
+    if snapshot.is_empty() {
+        return;
+    }
+    if let Err(err) = sink.emit_metrics(services, snapshot).await {
+        log::warn!("metrics flush skipped: {err:?}");
+    }
+}
+
+/// Map a request path onto a low-cardinality route class.
+#[must_use]
+pub fn route_label(path: &str) -> &'static str {
+    if path == "/auction" {
+        "auction"
+    } else if path.starts_with("/_ts/admin") {
+        "admin"
+    } else if path.starts_with("/_ts/api/") {
+        "api"
+    } else if path.starts_with("/_ts/page-bids") || path.starts_with("/__ts/page-bids") {
+        "page_bids"
+    } else if path.starts_with("/first-party/") {
+        "first_party"
+    } else if path.starts_with("/static/tsjs=") {
+        "tsjs"
+    } else if path.starts_with("/integrations/") {
+        "integration"
+    } else if path.starts_with("/.well-known/") || path == "/verify-signature" {
+        "discovery"
+    } else {
+        "publisher"
+    }
+}
+
+/// Record one handled request.
+pub fn record_http_request(metrics: &MetricsRegistry, path: &str, status: StatusCode) {
+    metrics.increment(
+        METRIC_HTTP_REQUESTS,
+        &[("route", route_label(path)), ("status", status.as_str())],
+    );
+}
+
+/// Record provider outcomes, latency and bid counts for a finished auction.
+pub(crate) fn record_orchestration(metrics: &MetricsRegistry, result: &OrchestrationResult) {
+    for response in result
+        .provider_responses
+        .iter()
+        .chain(result.mediator_response.as_ref())
+    {
+        record_provider_response(metrics, response);
+    }
+    let outcome = if result.winning_bids.is_empty() {
+        "no_winner"
+    } else {
+        "won"
+    };
+    metrics.increment(METRIC_AUCTIONS, &[("outcome", outcome)]);
+}
+
+fn record_provider_response(metrics: &MetricsRegistry, response: &AuctionResponse) {
+    let provider = response.provider.as_str();
+    let outcome = match response.status {
+        BidStatus::Success => "bid",
+        BidStatus::NoBid => "no_bid",
+        BidStatus::Pending => "pending",
+        BidStatus::Error => response
+            .metadata
+            .get("error_type")
+            .and_then(serde_json::Value::as_str)
+            .and_then(|error_type| {
+                crate::auction::orchestrator::ERROR_TYPE_ALL
+                    .iter()
+                    .find(|known| **known == error_type)
+                    .copied()
+            })
+            .unwrap_or("error"),
+    };
+    metrics.increment(
+        METRIC_PROVIDER_REQUESTS,
+        &[("provider", provider), ("outcome", outcome)],
+    );
+    metrics.observe(
+        METRIC_PROVIDER_LATENCY,
+        &[("provider", provider)],
+        response.response_time_ms as f64,
+    );
+    if !response.bids.is_empty() {
+        metrics.add(
+            METRIC_PROVIDER_BIDS,
+            &[("provider", provider)],
+            response.bids.len() as u64,
+        );
+    }
+}
+
+/// Record a server-side auction consent decision for `surface`.
+pub(crate) fn record_consent_decision(
+    metrics: &MetricsRegistry,
+    surface: &'static str,
+    allowed: bool,
+) {
+    let outcome = if allowed { "allowed" } else { "denied" };
+    metrics.increment(
+        METRIC_CONSENT_DECISIONS,
+        &[("surface", surface), ("outcome", outcome)],
+    );
+}
+
+/// Serve `GET /_ts/admin/metrics` from a long-lived registry.
+#[must_use]
+pub fn handle_admin_metrics(metrics: &MetricsRegistry) -> Response<EdgeBody> {
+    let mut response = Response::new(EdgeBody::from(metrics.render_prometheus()));
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static(PROMETHEUS_CONTENT_TYPE),
+    );
+    response
+        .headers_mut()
+        .insert(header::CACHE_CONTROL, HeaderValue::from_static("no-store"));
+    response
+}
+
+/// Returns the `501` used by adapters whose instances do not outlive a
+/// request, so there is no registry worth scraping.
+#[must_use]
+pub fn admin_metrics_not_supported() -> Response<EdgeBody> {
+    let mut response = Response::new(EdgeBody::from(
+        "Metrics are not scrapeable on this platform.\n\
+         On Fastly and Spin, enable tinybird.metrics_enabled to flush them as telemetry events.\n",
+    ));
+    *response.status_mut() = StatusCode::NOT_IMPLEMENTED;
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("text/plain; charset=utf-8"),
+    );
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn counters_accumulate_per_label_set() {
+        let metrics = MetricsRegistry::new();
+        metrics.increment(
+            METRIC_HTTP_REQUESTS,
+            &[("route", "auction"), ("status", "200")],
+        );
+        metrics.increment(
+            METRIC_HTTP_REQUESTS,
+            &[("route", "auction"), ("status", "200")],
+        );
+        metrics.increment(
+            METRIC_HTTP_REQUESTS,
+            &[("route", "auction"), ("status", "500")],
+        );
+
+        assert_eq!(
+            metrics.counter_value(
+                METRIC_HTTP_REQUESTS,
+                &[("route", "auction"), ("status", "200")]
+            ),
+            2,
+            "should accumulate the same series"
+        );
+        assert_eq!(
+            metrics.counter_value(
+                METRIC_HTTP_REQUESTS,
+                &[("route", "auction"), ("status", "500")]
+            ),
+            1,
+            "should keep distinct label sets apart"
+        );
+    }
+
+    #[test]
+    fn prometheus_output_includes_help_type_and_histogram_buckets() {
+        let metrics = MetricsRegistry::new();
+        metrics.increment(
+            METRIC_PROVIDER_REQUESTS,
+            &[("provider", "prebid"), ("outcome", "bid")],
+        );
+        metrics.observe(METRIC_PROVIDER_LATENCY, &[("provider", "prebid")], 42.0);
+        metrics.observe(METRIC_PROVIDER_LATENCY, &[("provider", "prebid")], 9000.0);
+
+        let text = metrics.render_prometheus();
+
+        assert!(
+            text.contains("# TYPE ts_auction_provider_requests_total counter"),
+            "should declare counter type, got: {text}"
+        );
+        assert!(
+            text.contains(
+                "ts_auction_provider_requests_total{provider=\"prebid\",outcome=\"bid\"} 1"
+            ),
+            "should render counter sample, got: {text}"
+        );
+        assert!(
+            text.contains("ts_auction_provider_latency_ms_bucket{provider=\"prebid\",le=\"50\"} 1"),
+            "should render cumulative bucket, got: {text}"
+        );
+        assert!(
+            text.contains(
+                "ts_auction_provider_latency_ms_bucket{provider=\"prebid\",le=\"+Inf\"} 2"
+            ),
+            "should count overflow in +Inf bucket, got: {text}"
+        );
+        assert!(
+            text.contains("ts_auction_provider_latency_ms_count{provider=\"prebid\"} 2"),
+            "should render histogram count, got: {text}"
+        );
+    }
+
+    #[test]
+    fn label_values_are_escaped() {
+        let metrics = MetricsRegistry::new();
+        metrics.increment(METRIC_KV_OPERATIONS, &[("store", "a\"b\\c")]);
+
+        assert!(
+            metrics
+                .render_prometheus()
+                .contains("store=\"a\\\"b\\\\c\""),
+            "should escape quotes and backslashes"
+        );
+    }
+
+    #[test]
+    fn take_snapshot_drains_registry() {
+        let metrics = MetricsRegistry::new();
+        metrics.add(METRIC_PROVIDER_BIDS, &[("provider", "aps")], 3);
+        metrics.observe(METRIC_PROVIDER_LATENCY, &[("provider", "aps")], 120.0);
+
+        let snapshot = metrics.take_snapshot();
+
+        assert_eq!(snapshot.row_count(), 2, "should flush both series");
+        assert!(metrics.is_empty(), "should leave the registry empty");
+        let bids = snapshot
+            .rows()
+            .iter()
+            .find(|row| row.metric == METRIC_PROVIDER_BIDS)
+            .expect("should include bid counter");
+        assert_eq!(bids.count, 3);
+        assert_eq!(bids.labels.get("provider").map(String::as_str), Some("aps"));
+        let ndjson = snapshot
+            .to_ndjson(64 * 1024)
+            .expect("should serialize snapshot");
+        assert_eq!(ndjson.lines().count(), 2, "should write one line per row");
+    }
+
+    #[test]
+    fn series_cap_drops_new_series() {
+        let metrics = MetricsRegistry::new();
+        for index in 0..MAX_SERIES {
+            metrics.increment(METRIC_KV_OPERATIONS, &[("store", &index.to_string())]);
+        }
+        metrics.increment(METRIC_KV_OPERATIONS, &[("store", "overflow")]);
+
+        assert_eq!(
+            metrics.counter_value(METRIC_KV_OPERATIONS, &[("store", "overflow")]),
+            0,
+            "should drop series past the cap"
+        );
+        metrics.increment(METRIC_KV_OPERATIONS, &[("store", "0")]);
+        assert_eq!(
+            metrics.counter_value(METRIC_KV_OPERATIONS, &[("store", "0")]),
+            2,
+            "should keep updating existing series at the cap"
+        );
+    }
+
+    #[test]
+    fn orchestration_records_provider_outcomes() {
+        let metrics = MetricsRegistry::new();
+        let timeout = AuctionResponse {
+            provider: "aps".to_owned(),
+            bids: Vec::new(),
+            status: BidStatus::Error,
+            response_time_ms: 800,
+            metadata: HashMap::from([(
+                "error_type".to_owned(),
+                serde_json::json!(crate::auction::orchestrator::ERROR_TYPE_TIMEOUT),
+            )]),
+        };
+        let no_bid = AuctionResponse {
+            provider: "prebid".to_owned(),
+            bids: Vec::new(),
+            status: BidStatus::NoBid,
+            response_time_ms: 90,
+            metadata: HashMap::new(),
+        };
+        let result = OrchestrationResult {
+            provider_responses: vec![timeout, no_bid],
+            mediator_response: None,
+            winning_bids: HashMap::new(),
+            total_time_ms: 800,
+            metadata: HashMap::new(),
+        };
+
+        record_orchestration(&metrics, &result);
+
+        assert_eq!(
+            metrics.counter_value(
+                METRIC_PROVIDER_REQUESTS,
+                &[("provider", "aps"), ("outcome", "timeout")]
+            ),
+            1,
+            "should classify timeouts from error metadata"
+        );
+        assert_eq!(
+            metrics.counter_value(
+                METRIC_PROVIDER_REQUESTS,
+                &[("provider", "prebid"), ("outcome", "no_bid")]
+            ),
+            1
+        );
+        assert_eq!(
+            metrics.counter_value(METRIC_AUCTIONS, &[("outcome", "no_winner")]),
+            1
+        );
+    }
+
+    #[test]
+    fn route_label_collapses_paths_to_route_classes() {
+        assert_eq!(route_label("/auction"), "auction");
+        assert_eq!(route_label("/_ts/admin/metrics"), "admin");
+        assert_eq!(route_label("/first-party/proxy"), "first_party");
+        assert_eq!(route_label("/news/story-123"), "publisher");
+    }
+
+    #[test]
+    fn admin_metrics_response_uses_prometheus_content_type() {
+        let metrics = MetricsRegistry::new();
+        record_consent_decision(&metrics, "auction", false);
+
+        let response = handle_admin_metrics(&metrics);
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response
+                .headers()
+                .get(header::CONTENT_TYPE)
+                .and_then(|value| value.to_str().ok()),
+            Some(PROMETHEUS_CONTENT_TYPE)
+        );
+    }
+}
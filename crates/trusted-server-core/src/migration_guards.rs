@@ -33,8 +33,17 @@ fn checked_sources() -> &'static [(&'static str, &'static str)] {
             "asset_image_optimizer.rs",
             include_str!("asset_image_optimizer.rs"),
         ),
+        (
+            "auction/ad_quality.rs",
+            include_str!("auction/ad_quality.rs"),
+        ),
+        ("auction/bid_cache.rs", include_str!("auction/bid_cache.rs")),
         ("auction/config.rs", include_str!("auction/config.rs")),
         ("auction/context.rs", include_str!("auction/context.rs")),
+        (
+            "auction/creative_scan.rs",
+            include_str!("auction/creative_scan.rs"),
+        ),
         ("auction/endpoints.rs", include_str!("auction/endpoints.rs")),
         ("auction/formats.rs", include_str!("auction/formats.rs")),
         ("auction/mod.rs", include_str!("auction/mod.rs")),
@@ -42,6 +51,10 @@ fn checked_sources() -> &'static [(&'static str, &'static str)] {
             "auction/orchestrator.rs",
             include_str!("auction/orchestrator.rs"),
         ),
+        (
+            "auction/price_encryption.rs",
+            include_str!("auction/price_encryption.rs"),
+        ),
         ("auction/provider.rs", include_str!("auction/provider.rs")),
         (
             "auction/test_support.rs",
@@ -73,6 +86,7 @@ fn checked_sources() -> &'static [(&'static str, &'static str)] {
         ("constants.rs", include_str!("constants.rs")),
         ("cookies.rs", include_str!("cookies.rs")),
         ("creative.rs", include_str!("creative.rs")),
+        ("creative_cache.rs", include_str!("creative_cache.rs")),
         ("ec/auth.rs", include_str!("ec/auth.rs")),
         ("ec/batch_sync.rs", include_str!("ec/batch_sync.rs")),
         ("ec/consent.rs", include_str!("ec/consent.rs")),
@@ -228,6 +242,7 @@ fn checked_sources() -> &'static [(&'static str, &'static str)] {
         ),
         ("test_support.rs", include_str!("test_support.rs")),
         ("tsjs.rs", include_str!("tsjs.rs")),
+        ("vast.rs", include_str!("vast.rs")),
     ]
 }
 
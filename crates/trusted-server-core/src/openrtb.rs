@@ -1,14 +1,16 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::auction::types::{BidRenderer, OrchestratorExt};
+use crate::auction::ad_quality::AdQualityRules;
+use crate::auction::types::{BidRenderer, OrchestratorExt, SlotPmp};
 
 pub type OpenRtbRequest = trusted_server_openrtb::BidRequest;
 pub type OpenRtbResponse = trusted_server_openrtb::BidResponse;
 pub type OpenRtbBid = trusted_server_openrtb::Bid;
 
 pub use trusted_server_openrtb::{
-    Banner, Bid, BidResponse, Device, Format, Geo, Imp, Publisher, Regs, SeatBid, Site, ToExt, User,
+    Banner, Bid, BidResponse, Deal, Device, Format, Geo, Imp, Pmp, Publisher, Regs, SeatBid, Site,
+    ToExt, User, Video,
 };
 
 /// Convert a `u32` value to `i32` for `OpenRTB` fields, logging a warning and
@@ -25,6 +27,60 @@ pub fn to_openrtb_i32(value: u32, field_name: &str, context: &str) -> Option<i32
     }
 }
 
+/// Build an `imp.pmp` object from a slot's deal configuration.
+///
+/// Deals without their own floor inherit `slot_floor`. Returns `None` when the
+/// slot has no deals, so open-auction slots stay unchanged.
+#[must_use]
+pub fn to_openrtb_pmp(
+    pmp: Option<&SlotPmp>,
+    slot_floor: Option<f64>,
+    currency: &str,
+) -> Option<Pmp> {
+    let pmp = pmp.filter(|pmp| !pmp.deals.is_empty())?;
+    let deals = pmp
+        .deals
+        .iter()
+        .map(|deal| {
+            let bidfloor = deal.bidfloor.or(slot_floor);
+            Deal {
+                id: Some(deal.id.clone()),
+                bidfloor,
+                bidfloorcur: bidfloor.map(|_| currency.to_string()),
+                wseat: deal.wseat.clone(),
+                ..Default::default()
+            }
+        })
+        .collect();
+    Some(Pmp {
+        private_auction: Some(pmp.private_auction),
+        deals,
+        ..Default::default()
+    })
+}
+
+/// Copy publisher block lists onto an outgoing bid request.
+///
+/// Sets `badv`, `bcat` and `bseat` on the request and `battr` on every banner
+/// and video object. The orchestrator still enforces the rules on responses,
+/// so bidders that ignore these fields cannot win with blocked creatives.
+pub fn apply_ad_quality(request: &mut OpenRtbRequest, rules: &AdQualityRules) {
+    if rules.is_empty() {
+        return;
+    }
+    request.badv.clone_from(&rules.badv);
+    request.bcat.clone_from(&rules.bcat);
+    request.bseat.clone_from(&rules.blocked_seats);
+    for imp in &mut request.imp {
+        if let Some(banner) = imp.banner.as_mut() {
+            banner.battr.clone_from(&rules.battr);
+        }
+        if let Some(video) = imp.video.as_mut() {
+            video.battr.clone_from(&rules.battr);
+        }
+    }
+}
+
 // ============================================================================
 // Extension types (project-specific, not part of the OpenRTB spec)
 // ============================================================================
@@ -265,6 +321,82 @@ mod tests {
         assert_eq!(serialized, expected);
     }
 
+    #[test]
+    fn to_openrtb_pmp_inherits_slot_floor_and_serializes_private_auction() {
+        use crate::auction::types::SlotDeal;
+
+        let slot_pmp = SlotPmp {
+            private_auction: true,
+            deals: vec![
+                SlotDeal {
+                    id: "pg-1".to_owned(),
+                    bidfloor: Some(12.0),
+                    wseat: vec!["seat-a".to_owned()],
+                },
+                SlotDeal {
+                    id: "pmp-2".to_owned(),
+                    bidfloor: None,
+                    wseat: vec![],
+                },
+            ],
+        };
+
+        let pmp = to_openrtb_pmp(Some(&slot_pmp), Some(1.5), "USD").expect("should build pmp");
+        let serialized = serde_json::to_value(&pmp).expect("should serialize");
+
+        assert_eq!(
+            serialized,
+            serde_json::json!({
+                "private_auction": 1,
+                "deals": [
+                    {"id": "pg-1", "bidfloor": 12.0, "bidfloorcur": "USD", "wseat": ["seat-a"]},
+                    {"id": "pmp-2", "bidfloor": 1.5, "bidfloorcur": "USD"}
+                ]
+            }),
+            "should inherit the slot floor for deals without their own"
+        );
+        assert!(
+            to_openrtb_pmp(Some(&SlotPmp::default()), Some(1.5), "USD").is_none(),
+            "should omit pmp when no deals are configured"
+        );
+    }
+
+    #[test]
+    fn apply_ad_quality_sets_request_and_imp_block_lists() {
+        let mut request = OpenRtbRequest {
+            imp: vec![Imp {
+                banner: Some(Banner::default()),
+                video: Some(Video::default()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let rules = AdQualityRules {
+            badv: vec!["bad.example".to_owned()],
+            bcat: vec!["IAB25".to_owned()],
+            battr: vec![8],
+            blocked_seats: vec!["seat-x".to_owned()],
+            ..Default::default()
+        };
+
+        apply_ad_quality(&mut request, &rules);
+
+        assert_eq!(request.badv, vec!["bad.example".to_owned()]);
+        assert_eq!(request.bcat, vec!["IAB25".to_owned()]);
+        assert_eq!(request.bseat, vec!["seat-x".to_owned()]);
+        let imp = &request.imp[0];
+        assert_eq!(
+            imp.banner.as_ref().expect("should keep banner").battr,
+            vec![8],
+            "should block attributes on banner"
+        );
+        assert_eq!(
+            imp.video.as_ref().expect("should keep video").battr,
+            vec![8],
+            "should block attributes on video"
+        );
+    }
+
     #[test]
     fn regs_serializes_dual_placement_consent_fields() {
         // Mirror the production pattern: build ext, then duplicate into top-level.
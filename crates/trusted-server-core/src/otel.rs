@@ -0,0 +1,1093 @@
+//! OpenTelemetry trace spans for publisher requests and auctions.
+//!
+//! Core owns W3C trace-context propagation, the request-scoped span collector
+//! and the OTLP/HTTP JSON encoding. Platform adapters provide the concrete
+//! exporter, mirroring [`crate::auction::telemetry::AuctionTelemetrySink`].
+//!
+//! A [`RequestTrace`] is created once per request by the adapter entry point
+//! and carried on [`RuntimeServices`]. Instrumented code opens child spans with
+//! [`RequestTrace::start_span`]; each [`ActiveSpan`] records itself when it is
+//! dropped, so early returns still close their span. The adapter exports the
+//! collected spans after the response has been sent, which keeps export off
+//! the client's critical path and lets the `</body>` hold spans complete.
+
+use std::sync::{Arc, Mutex, PoisonError};
+
+use error_stack::Report;
+use http::{HeaderMap, HeaderValue};
+use serde_json::{Value as JsonValue, json};
+
+use crate::error::TrustedServerError;
+use crate::platform::RuntimeServices;
+use crate::settings::TracingSettings;
+
+/// W3C trace-context request header.
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+
+/// Instrumentation scope name reported on exported spans.
+const INSTRUMENTATION_SCOPE: &str = "trusted-server";
+
+/// Root span name used until a handler names the request.
+const DEFAULT_ROOT_SPAN_NAME: &str = "request";
+
+/// Span covering one auction provider call, from launch to parse.
+pub(crate) const SPAN_AUCTION_PROVIDER: &str = "auction.provider";
+/// Span covering the mediator call.
+pub(crate) const SPAN_AUCTION_MEDIATOR: &str = "auction.mediator";
+/// Span covering the publisher origin fetch up to response headers.
+pub(crate) const SPAN_ORIGIN_FETCH: &str = "origin.fetch";
+/// Span covering the `</body>` hold while the dispatched auction is collected.
+pub(crate) const SPAN_HTML_AUCTION_HOLD: &str = "html.auction_hold";
+/// Span covering creative processing of the winning bids.
+pub(crate) const SPAN_CREATIVE_REWRITE: &str = "creative.rewrite";
+
+/// Span attribute naming the auction.
+pub(crate) const ATTR_AUCTION_ID: &str = "auction_id";
+/// Span attribute naming the auction provider.
+pub(crate) const ATTR_PROVIDER: &str = "provider";
+
+/// OTLP status code for a span that ended in error.
+const OTLP_STATUS_ERROR: u8 = 2;
+
+/// Parsed W3C `traceparent` header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceParent {
+    /// 16-byte trace identifier shared by every span in the trace.
+    pub trace_id: [u8; 16],
+    /// 8-byte identifier of the calling span.
+    pub parent_id: [u8; 8],
+    /// Whether the caller sampled this trace.
+    pub sampled: bool,
+}
+
+impl TraceParent {
+    /// Parses a `traceparent` header value.
+    ///
+    /// Accepts version `00` exactly and higher versions with trailing fields,
+    /// as the specification requires. Returns `None` for malformed values,
+    /// the reserved `ff` version and all-zero identifiers.
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        let mut parts = value.split('-');
+        let version = parts.next()?;
+        let trace_id = parts.next()?;
+        let parent_id = parts.next()?;
+        let flags = parts.next()?;
+
+        if version.len() != 2 || version.eq_ignore_ascii_case("ff") {
+            return None;
+        }
+        let version = u8::from_str_radix(version, 16).ok()?;
+        if version == 0 && parts.next().is_some() {
+            return None;
+        }
+
+        let trace_id = decode_lower_hex::<16>(trace_id)?;
+        let parent_id = decode_lower_hex::<8>(parent_id)?;
+        let flags = decode_lower_hex::<1>(flags)?;
+        if trace_id == [0; 16] || parent_id == [0; 8] {
+            return None;
+        }
+
+        Some(Self {
+            trace_id,
+            parent_id,
+            sampled: flags[0] & 0x01 == 0x01,
+        })
+    }
+
+    /// Formats this trace context as a version `00` header value.
+    #[must_use]
+    pub fn to_header_value(&self) -> String {
+        format!(
+            "00-{}-{}-{}",
+            hex::encode(self.trace_id),
+            hex::encode(self.parent_id),
+            if self.sampled { "01" } else { "00" }
+        )
+    }
+}
+
+/// Sets the `traceparent` header on an outbound request.
+///
+/// Replaces any value copied from the client so the downstream span parents
+/// on ours. Leaves the headers untouched when `traceparent` is `None`, i.e.
+/// when tracing is disabled.
+pub fn inject_traceparent(headers: &mut HeaderMap, traceparent: Option<TraceParent>) {
+    let Some(traceparent) = traceparent else {
+        return;
+    };
+    if let Ok(value) = HeaderValue::from_str(&traceparent.to_header_value()) {
+        headers.insert(TRACEPARENT_HEADER, value);
+    }
+}
+
+fn decode_lower_hex<const N: usize>(value: &str) -> Option<[u8; N]> {
+    // The specification only permits lowercase hex; `hex::decode` would also
+    // accept uppercase, which would then round-trip differently downstream.
+    if value.len() != N * 2
+        || !value
+            .bytes()
+            .all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+    {
+        return None;
+    }
+    let mut out = [0u8; N];
+    hex::decode_to_slice(value, &mut out).ok()?;
+    Some(out)
+}
+
+/// Span kind, using the OTLP enumeration values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpanKind {
+    /// Work inside Trusted Server, such as the `</body>` hold.
+    Internal,
+    /// The inbound request handled by Trusted Server.
+    Server,
+    /// An outbound call to an origin, SSP or mediator.
+    Client,
+}
+
+impl SpanKind {
+    fn otlp_code(self) -> u8 {
+        match self {
+            Self::Internal => 1,
+            Self::Server => 2,
+            Self::Client => 3,
+        }
+    }
+}
+
+/// Span attribute value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttributeValue {
+    /// UTF-8 string value.
+    String(String),
+    /// Signed integer value.
+    Int(i64),
+    /// Boolean value.
+    Bool(bool),
+}
+
+impl AttributeValue {
+    fn to_otlp_json(&self) -> JsonValue {
+        match self {
+            Self::String(value) => json!({ "stringValue": value }),
+            // OTLP/JSON encodes 64-bit integers as decimal strings.
+            Self::Int(value) => json!({ "intValue": value.to_string() }),
+            Self::Bool(value) => json!({ "boolValue": value }),
+        }
+    }
+}
+
+impl From<&str> for AttributeValue {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_owned())
+    }
+}
+
+impl From<String> for AttributeValue {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<i64> for AttributeValue {
+    fn from(value: i64) -> Self {
+        Self::Int(value)
+    }
+}
+
+impl From<u64> for AttributeValue {
+    fn from(value: u64) -> Self {
+        Self::Int(i64::try_from(value).unwrap_or(i64::MAX))
+    }
+}
+
+impl From<usize> for AttributeValue {
+    fn from(value: usize) -> Self {
+        Self::Int(i64::try_from(value).unwrap_or(i64::MAX))
+    }
+}
+
+impl From<u16> for AttributeValue {
+    fn from(value: u16) -> Self {
+        Self::Int(i64::from(value))
+    }
+}
+
+impl From<bool> for AttributeValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+/// A finished span ready for export.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpanRecord {
+    /// Span operation name, e.g. `origin.fetch`.
+    pub name: &'static str,
+    /// 8-byte span identifier.
+    pub span_id: [u8; 8],
+    /// Parent span identifier; `None` only for a root span without a remote parent.
+    pub parent_span_id: Option<[u8; 8]>,
+    /// Span kind.
+    pub kind: SpanKind,
+    /// Start time in nanoseconds since the Unix epoch.
+    pub start_unix_nanos: u64,
+    /// End time in nanoseconds since the Unix epoch.
+    pub end_unix_nanos: u64,
+    /// Span attributes in insertion order.
+    pub attributes: Vec<(&'static str, AttributeValue)>,
+    /// Whether the span ended in error.
+    pub error: bool,
+}
+
+impl SpanRecord {
+    /// An unrecorded span carrying only its name and kind.
+    fn placeholder(name: &'static str, kind: SpanKind) -> Self {
+        Self {
+            name,
+            span_id: [0; 8],
+            parent_span_id: None,
+            kind,
+            start_unix_nanos: 0,
+            end_unix_nanos: 0,
+            attributes: Vec::new(),
+            error: false,
+        }
+    }
+
+    fn to_otlp_json(&self, trace_id: &[u8; 16]) -> JsonValue {
+        let mut span = json!({
+            "traceId": hex::encode(trace_id),
+            "spanId": hex::encode(self.span_id),
+            "name": self.name,
+            "kind": self.kind.otlp_code(),
+            "startTimeUnixNano": self.start_unix_nanos.to_string(),
+            "endTimeUnixNano": self.end_unix_nanos.to_string(),
+            "attributes": encode_attributes(&self.attributes),
+        });
+        if let Some(parent) = self.parent_span_id {
+            span["parentSpanId"] = JsonValue::String(hex::encode(parent));
+        }
+        if self.error {
+            span["status"] = json!({ "code": OTLP_STATUS_ERROR });
+        }
+        span
+    }
+}
+
+fn encode_attributes(attributes: &[(&'static str, AttributeValue)]) -> JsonValue {
+    JsonValue::Array(
+        attributes
+            .iter()
+            .map(|(key, value)| json!({ "key": key, "value": value.to_otlp_json() }))
+            .collect(),
+    )
+}
+
+/// Spans collected for one trace, handed to a [`TraceExporter`].
+#[derive(Debug, Clone)]
+pub struct SpanBatch {
+    trace_id: [u8; 16],
+    spans: Vec<SpanRecord>,
+}
+
+impl SpanBatch {
+    /// Returns the trace identifier shared by every span in the batch.
+    #[must_use]
+    pub fn trace_id(&self) -> [u8; 16] {
+        self.trace_id
+    }
+
+    /// Returns the collected spans.
+    #[must_use]
+    pub fn spans(&self) -> &[SpanRecord] {
+        &self.spans
+    }
+
+    /// Returns whether the batch has no spans.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    /// Encodes the batch as an OTLP/HTTP JSON `ExportTraceServiceRequest`.
+    #[must_use]
+    pub fn to_otlp_json(&self, service_name: &str) -> JsonValue {
+        let spans: Vec<JsonValue> = self
+            .spans
+            .iter()
+            .map(|span| span.to_otlp_json(&self.trace_id))
+            .collect();
+        json!({
+            "resourceSpans": [{
+                "resource": {
+                    "attributes": [
+                        { "key": "service.name", "value": { "stringValue": service_name } }
+                    ]
+                },
+                "scopeSpans": [{
+                    "scope": { "name": INSTRUMENTATION_SCOPE },
+                    "spans": spans,
+                }]
+            }]
+        })
+    }
+}
+
+/// Mutable state of a [`RequestTrace`], guarded by one lock.
+#[derive(Debug)]
+struct TraceState {
+    root_name: &'static str,
+    root_attributes: Vec<(&'static str, AttributeValue)>,
+    root_error: bool,
+    propagation_parent: Option<[u8; 8]>,
+    spans: Vec<SpanRecord>,
+    dropped_spans: usize,
+    /// Set once [`RequestTrace::finish`] has emitted the root span.
+    root_finished: bool,
+}
+
+impl Default for TraceState {
+    fn default() -> Self {
+        Self {
+            root_name: DEFAULT_ROOT_SPAN_NAME,
+            root_attributes: Vec::new(),
+            root_error: false,
+            propagation_parent: None,
+            spans: Vec::new(),
+            dropped_spans: 0,
+            root_finished: false,
+        }
+    }
+}
+
+/// Request-scoped span collector.
+///
+/// A disabled trace (the default on [`RuntimeServices`]) records nothing and
+/// propagates nothing, so instrumented code needs no configuration checks.
+#[derive(Debug)]
+pub struct RequestTrace {
+    enabled: bool,
+    sampled: bool,
+    trace_id: [u8; 16],
+    root_span_id: [u8; 8],
+    remote_parent: Option<[u8; 8]>,
+    start_unix_nanos: u64,
+    max_spans: usize,
+    state: Mutex<TraceState>,
+}
+
+impl RequestTrace {
+    /// Returns a trace that records and propagates nothing.
+    #[must_use]
+    pub fn disabled() -> Self {
+        Self {
+            enabled: false,
+            sampled: false,
+            trace_id: [0; 16],
+            root_span_id: [0; 8],
+            remote_parent: None,
+            start_unix_nanos: 0,
+            max_spans: 0,
+            state: Mutex::new(TraceState::default()),
+        }
+    }
+
+    /// Starts the trace for an inbound request.
+    ///
+    /// Continues the caller's trace when the request carries a valid
+    /// `traceparent`, otherwise starts a new one. Either way the trace is
+    /// sampled at [`TracingSettings::sample_rate`]; the caller's sampled flag
+    /// is honoured only with [`TracingSettings::trust_incoming_sampling`], so
+    /// clients cannot force every request to be recorded and exported.
+    #[must_use]
+    pub fn from_request_headers(headers: &HeaderMap, settings: &TracingSettings) -> Self {
+        if !settings.enabled {
+            return Self::disabled();
+        }
+
+        let incoming = headers
+            .get(TRACEPARENT_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(TraceParent::parse);
+        let (trace_id, remote_parent, sampled) = match incoming {
+            Some(parent) if settings.trust_incoming_sampling => {
+                (parent.trace_id, Some(parent.parent_id), parent.sampled)
+            }
+            Some(parent) => (
+                parent.trace_id,
+                Some(parent.parent_id),
+                sample_trace_id(&parent.trace_id, settings.sample_rate),
+            ),
+            None => {
+                let trace_id = random_trace_id();
+                (
+                    trace_id,
+                    None,
+                    sample_trace_id(&trace_id, settings.sample_rate),
+                )
+            }
+        };
+
+        Self {
+            enabled: true,
+            sampled,
+            trace_id,
+            root_span_id: random_span_id(),
+            remote_parent,
+            start_unix_nanos: now_unix_nanos(),
+            max_spans: settings.max_spans_per_request,
+            state: Mutex::new(TraceState::default()),
+        }
+    }
+
+    /// Returns whether spans from this trace will be exported.
+    #[must_use]
+    pub fn is_recording(&self) -> bool {
+        self.enabled && self.sampled
+    }
+
+    /// Returns the trace identifier, or `None` when tracing is disabled.
+    #[must_use]
+    pub fn trace_id(&self) -> Option<[u8; 16]> {
+        self.enabled.then_some(self.trace_id)
+    }
+
+    /// Names the root span, e.g. `publisher.request`.
+    pub fn set_root_name(&self, name: &'static str) {
+        if self.is_recording() {
+            self.lock_state().root_name = name;
+        }
+    }
+
+    /// Adds an attribute to the root span.
+    pub fn set_root_attribute(&self, key: &'static str, value: impl Into<AttributeValue>) {
+        if self.is_recording() {
+            self.lock_state().root_attributes.push((key, value.into()));
+        }
+    }
+
+    /// Marks the root span as failed.
+    pub fn set_root_error(&self) {
+        if self.is_recording() {
+            self.lock_state().root_error = true;
+        }
+    }
+
+    /// Opens a child span of the root span.
+    #[must_use]
+    pub fn start_span(self: &Arc<Self>, name: &'static str, kind: SpanKind) -> ActiveSpan {
+        if !self.is_recording() {
+            return ActiveSpan::detached(name, kind);
+        }
+        ActiveSpan {
+            trace: Some(Arc::clone(self)),
+            record: SpanRecord {
+                name,
+                span_id: random_span_id(),
+                parent_span_id: Some(self.root_span_id),
+                kind,
+                start_unix_nanos: now_unix_nanos(),
+                end_unix_nanos: 0,
+                attributes: Vec::new(),
+                error: false,
+            },
+        }
+    }
+
+    /// Returns the `traceparent` to send on an outbound request.
+    ///
+    /// The parent is the span installed by [`ActiveSpan::propagation_scope`]
+    /// if one is active, otherwise the root span. Unsampled traces still
+    /// propagate (with the sampled flag cleared) so downstream services make
+    /// the same sampling decision.
+    #[must_use]
+    pub fn traceparent(&self) -> Option<TraceParent> {
+        if !self.enabled {
+            return None;
+        }
+        let parent_id = self
+            .lock_state()
+            .propagation_parent
+            .unwrap_or(self.root_span_id);
+        Some(TraceParent {
+            trace_id: self.trace_id,
+            parent_id,
+            sampled: self.sampled,
+        })
+    }
+
+    /// Ends the root span and drains every recorded span into a batch.
+    ///
+    /// Returns `None` when the trace is not recording. The root span is
+    /// emitted by the first call only; later calls return just the spans
+    /// recorded since the previous drain, or `None` when there are none.
+    #[must_use]
+    pub fn finish(&self) -> Option<SpanBatch> {
+        if !self.is_recording() {
+            return None;
+        }
+        let mut state = self.lock_state();
+        let mut spans = std::mem::take(&mut state.spans);
+        if state.root_finished {
+            return (!spans.is_empty()).then(|| SpanBatch {
+                trace_id: self.trace_id,
+                spans,
+            });
+        }
+        state.root_finished = true;
+        if state.dropped_spans > 0 {
+            log::debug!(
+                "trace {} dropped {} span(s) over the per-request limit",
+                hex::encode(self.trace_id),
+                state.dropped_spans
+            );
+        }
+        let mut root_attributes = std::mem::take(&mut state.root_attributes);
+        if state.dropped_spans > 0 {
+            root_attributes.push(("ts.dropped_spans", state.dropped_spans.into()));
+        }
+        spans.push(SpanRecord {
+            name: state.root_name,
+            span_id: self.root_span_id,
+            parent_span_id: self.remote_parent,
+            kind: SpanKind::Server,
+            start_unix_nanos: self.start_unix_nanos,
+            end_unix_nanos: now_unix_nanos(),
+            attributes: root_attributes,
+            error: state.root_error,
+        });
+        Some(SpanBatch {
+            trace_id: self.trace_id,
+            spans,
+        })
+    }
+
+    fn record(&self, span: SpanRecord) {
+        let mut state = self.lock_state();
+        // One slot is reserved for the root span appended by `finish`.
+        if state.spans.len() + 1 >= self.max_spans {
+            state.dropped_spans += 1;
+            return;
+        }
+        state.spans.push(span);
+    }
+
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, TraceState> {
+        // Every holder is a short, infallible push or read, so a poisoned lock
+        // still holds consistent data.
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Default for RequestTrace {
+    fn default() -> Self {
+        Self::disabled()
+    }
+}
+
+/// An open span. Records itself into its [`RequestTrace`] when dropped.
+#[derive(Debug)]
+pub struct ActiveSpan {
+    trace: Option<Arc<RequestTrace>>,
+    record: SpanRecord,
+}
+
+impl ActiveSpan {
+    fn detached(name: &'static str, kind: SpanKind) -> Self {
+        Self {
+            trace: None,
+            record: SpanRecord::placeholder(name, kind),
+        }
+    }
+
+    /// Returns whether this span will be recorded.
+    #[must_use]
+    pub fn is_recording(&self) -> bool {
+        self.trace.is_some()
+    }
+
+    /// Adds an attribute. A no-op when the trace is not recording.
+    pub fn set_attribute(&mut self, key: &'static str, value: impl Into<AttributeValue>) {
+        if self.is_recording() {
+            self.record.attributes.push((key, value.into()));
+        }
+    }
+
+    /// Marks the span as failed and records the failure classification.
+    pub fn set_error(&mut self, error_type: &str) {
+        if self.is_recording() {
+            self.record.error = true;
+            self.record
+                .attributes
+                .push(("error.type", error_type.into()));
+        }
+    }
+
+    /// Returns the `traceparent` naming this span as the caller.
+    #[must_use]
+    pub fn traceparent(&self) -> Option<TraceParent> {
+        let trace = self.trace.as_ref()?;
+        Some(TraceParent {
+            trace_id: trace.trace_id,
+            parent_id: self.record.span_id,
+            sampled: trace.sampled,
+        })
+    }
+
+    /// Makes this span the parent for [`RequestTrace::traceparent`] until the
+    /// returned guard is dropped.
+    ///
+    /// Lets code that builds its own outbound request, such as an auction
+    /// provider, parent the downstream server span under the orchestrator's
+    /// client span without threading the span through its API.
+    #[must_use]
+    pub fn propagation_scope(&self) -> PropagationScope {
+        let Some(trace) = self.trace.as_ref() else {
+            return PropagationScope {
+                trace: None,
+                previous: None,
+            };
+        };
+        let previous = trace
+            .lock_state()
+            .propagation_parent
+            .replace(self.record.span_id);
+        PropagationScope {
+            trace: Some(Arc::clone(trace)),
+            previous,
+        }
+    }
+
+    /// Ends the span now rather than at the end of its scope.
+    pub fn end(self) {
+        drop(self);
+    }
+}
+
+impl Drop for ActiveSpan {
+    fn drop(&mut self) {
+        if let Some(trace) = self.trace.take() {
+            let placeholder = SpanRecord::placeholder(self.record.name, self.record.kind);
+            let mut record = std::mem::replace(&mut self.record, placeholder);
+            record.end_unix_nanos = now_unix_nanos().max(record.start_unix_nanos);
+            trace.record(record);
+        }
+    }
+}
+
+/// Guard returned by [`ActiveSpan::propagation_scope`].
+#[derive(Debug)]
+pub struct PropagationScope {
+    trace: Option<Arc<RequestTrace>>,
+    previous: Option<[u8; 8]>,
+}
+
+impl Drop for PropagationScope {
+    fn drop(&mut self) {
+        if let Some(trace) = self.trace.take() {
+            trace.lock_state().propagation_parent = self.previous;
+        }
+    }
+}
+
+/// Exporter for finished request traces.
+#[async_trait::async_trait(?Send)]
+pub trait TraceExporter: Send + Sync {
+    /// Return whether this exporter sends spans anywhere.
+    fn is_enabled(&self) -> bool {
+        true
+    }
+
+    /// Export one request's spans.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the exporter cannot start the export. Callers
+    /// should use [`export_request_trace_best_effort`].
+    async fn export_spans(
+        &self,
+        services: &RuntimeServices,
+        batch: SpanBatch,
+    ) -> Result<(), Report<TrustedServerError>>;
+}
+
+/// No-op exporter used when tracing is disabled.
+pub struct NoopTraceExporter;
+
+#[async_trait::async_trait(?Send)]
+impl TraceExporter for NoopTraceExporter {
+    fn is_enabled(&self) -> bool {
+        false
+    }
+
+    async fn export_spans(
+        &self,
+        _services: &RuntimeServices,
+        _batch: SpanBatch,
+    ) -> Result<(), Report<TrustedServerError>> {
+        Ok(())
+    }
+}
+
+/// Finish the request trace and export it without affecting customer traffic.
+pub async fn export_request_trace_best_effort(services: &RuntimeServices) {
+    let exporter = services.trace_exporter();
+    if !exporter.is_enabled() {
+        return;
+    }
+    let Some(batch) = services.request_trace().finish() else {
+        return;
+    };
+    if let Err(err) = exporter.export_spans(services, batch).await {
+        log::warn!("trace export skipped: {err:?}");
+    }
+}
+
+/// Makes the head-based sampling decision for a new trace.
+///
+/// Compares the low 8 bytes of the trace ID against the rate, like the
+/// OpenTelemetry `TraceIdRatioBased` sampler, so the decision is a pure
+/// function of the trace ID.
+fn sample_trace_id(trace_id: &[u8; 16], rate: f64) -> bool {
+    if rate >= 1.0 {
+        return true;
+    }
+    if rate <= 0.0 {
+        return false;
+    }
+    let mut low = [0u8; 8];
+    low.copy_from_slice(&trace_id[8..]);
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss,
+        reason = "rate is validated to 0.0..=1.0, so the bound fits in u64"
+    )]
+    let bound = (rate * u64::MAX as f64) as u64;
+    u64::from_be_bytes(low) < bound
+}
+
+fn random_trace_id() -> [u8; 16] {
+    let mut id: [u8; 16] = rand::random();
+    if id == [0; 16] {
+        id[15] = 1;
+    }
+    id
+}
+
+fn random_span_id() -> [u8; 8] {
+    let mut id: [u8; 8] = rand::random();
+    if id == [0; 8] {
+        id[7] = 1;
+    }
+    id
+}
+
+/// Current time in nanoseconds since the Unix epoch.
+///
+/// Uses [`web_time::SystemTime`] so the clock also works on
+/// `wasm32-unknown-unknown`.
+fn now_unix_nanos() -> u64 {
+    web_time::SystemTime::now()
+        .duration_since(web_time::UNIX_EPOCH)
+        .map(|d| u64::try_from(d.as_nanos()).unwrap_or(u64::MAX))
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_TRACEPARENT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+    fn enabled_settings(sample_rate: f64) -> TracingSettings {
+        TracingSettings {
+            enabled: true,
+            endpoint: "https://otlp.example.com".to_owned(),
+            sample_rate,
+            ..TracingSettings::default()
+        }
+    }
+
+    fn headers_with_traceparent(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            TRACEPARENT_HEADER,
+            HeaderValue::from_str(value).expect("should build header value"),
+        );
+        headers
+    }
+
+    #[test]
+    fn traceparent_round_trips() {
+        let parsed = TraceParent::parse(SAMPLE_TRACEPARENT).expect("should parse traceparent");
+
+        assert!(parsed.sampled, "should read the sampled flag");
+        assert_eq!(
+            parsed.to_header_value(),
+            SAMPLE_TRACEPARENT,
+            "should format the same header value"
+        );
+    }
+
+    #[test]
+    fn traceparent_rejects_invalid_values() {
+        for value in [
+            "",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7",
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+            "00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01",
+            "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra",
+        ] {
+            assert!(
+                TraceParent::parse(value).is_none(),
+                "should reject traceparent {value:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn traceparent_accepts_future_version_with_extra_fields() {
+        let parsed =
+            TraceParent::parse("01-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-00-extra")
+                .expect("should parse a future version");
+
+        assert!(!parsed.sampled, "should read a cleared sampled flag");
+    }
+
+    #[test]
+    fn inject_traceparent_replaces_client_value_only_when_enabled() {
+        let mut headers = headers_with_traceparent(SAMPLE_TRACEPARENT);
+        inject_traceparent(&mut headers, None);
+        assert_eq!(
+            headers.get(TRACEPARENT_HEADER).map(HeaderValue::as_bytes),
+            Some(SAMPLE_TRACEPARENT.as_bytes()),
+            "should leave the header untouched when tracing is disabled"
+        );
+
+        let trace = RequestTrace::from_request_headers(&HeaderMap::new(), &enabled_settings(1.0));
+        let ours = trace.traceparent().expect("should propagate");
+        inject_traceparent(&mut headers, Some(ours));
+        assert_eq!(
+            headers.get(TRACEPARENT_HEADER).map(HeaderValue::as_bytes),
+            Some(ours.to_header_value().as_bytes()),
+            "should replace the client value"
+        );
+    }
+
+    #[test]
+    fn disabled_trace_records_and_propagates_nothing() {
+        let trace = Arc::new(RequestTrace::disabled());
+        let mut span = trace.start_span("origin.fetch", SpanKind::Client);
+        span.set_attribute("provider", "prebid");
+
+        assert!(!span.is_recording(), "should not record when disabled");
+        assert!(trace.traceparent().is_none(), "should not propagate");
+        drop(span);
+        assert!(trace.finish().is_none(), "should export nothing");
+    }
+
+    #[test]
+    fn incoming_traceparent_is_continued() {
+        let settings = TracingSettings {
+            trust_incoming_sampling: true,
+            ..enabled_settings(0.0)
+        };
+        let trace = Arc::new(RequestTrace::from_request_headers(
+            &headers_with_traceparent(SAMPLE_TRACEPARENT),
+            &settings,
+        ));
+        let incoming = TraceParent::parse(SAMPLE_TRACEPARENT).expect("should parse traceparent");
+
+        assert!(
+            trace.is_recording(),
+            "should honour a trusted caller's sampled flag over the local rate"
+        );
+        let batch = trace.finish().expect("should produce a batch");
+        let root = batch.spans().last().expect("should include the root span");
+        assert_eq!(batch.trace_id(), incoming.trace_id, "should keep trace id");
+        assert_eq!(
+            root.parent_span_id,
+            Some(incoming.parent_id),
+            "should parent the root span on the caller"
+        );
+    }
+
+    #[test]
+    fn untrusted_sampled_parent_is_sampled_at_the_local_rate() {
+        let trace = RequestTrace::from_request_headers(
+            &headers_with_traceparent(SAMPLE_TRACEPARENT),
+            &enabled_settings(0.0),
+        );
+        let incoming = TraceParent::parse(SAMPLE_TRACEPARENT).expect("should parse traceparent");
+
+        assert!(
+            !trace.is_recording(),
+            "should not let a client's sampled flag bypass sample_rate"
+        );
+        let propagated = trace.traceparent().expect("should still propagate");
+        assert_eq!(
+            propagated.trace_id, incoming.trace_id,
+            "should still continue the caller's trace"
+        );
+        assert!(!propagated.sampled, "should propagate the local decision");
+    }
+
+    #[test]
+    fn finish_emits_root_span_only_once() {
+        let trace = Arc::new(RequestTrace::from_request_headers(
+            &HeaderMap::new(),
+            &enabled_settings(1.0),
+        ));
+
+        let first = trace.finish().expect("should produce a batch");
+        assert_eq!(first.spans().len(), 1, "should emit the root span");
+        assert!(
+            trace.finish().is_none(),
+            "should not re-export the root span"
+        );
+
+        trace.start_span("late.work", SpanKind::Internal).end();
+        let late = trace.finish().expect("should drain late spans");
+        assert_eq!(late.spans().len(), 1, "should only drain the late span");
+        assert_eq!(late.spans()[0].name, "late.work");
+    }
+
+    #[test]
+    fn unsampled_trace_still_propagates_with_flag_cleared() {
+        let trace = RequestTrace::from_request_headers(&HeaderMap::new(), &enabled_settings(0.0));
+
+        assert!(!trace.is_recording(), "should not sample at rate 0");
+        let propagated = trace.traceparent().expect("should still propagate");
+        assert!(!propagated.sampled, "should clear the sampled flag");
+    }
+
+    #[test]
+    fn child_spans_nest_under_root_and_propagation_scope() {
+        let trace = Arc::new(RequestTrace::from_request_headers(
+            &HeaderMap::new(),
+            &enabled_settings(1.0),
+        ));
+        trace.set_root_name("publisher.request");
+        let root_parent = trace.traceparent().expect("should propagate").parent_id;
+
+        let mut provider = trace.start_span("auction.provider", SpanKind::Client);
+        provider.set_attribute("auction_id", "auction-1");
+        provider.set_attribute("provider", "prebid");
+        let provider_id = provider
+            .traceparent()
+            .expect("should have context")
+            .parent_id;
+        {
+            let _scope = provider.propagation_scope();
+            assert_eq!(
+                trace.traceparent().expect("should propagate").parent_id,
+                provider_id,
+                "should propagate the scoped span"
+            );
+        }
+        assert_eq!(
+            trace.traceparent().expect("should propagate").parent_id,
+            root_parent,
+            "should restore the root span after the scope"
+        );
+        provider.set_error("timeout");
+        provider.end();
+
+        let batch = trace.finish().expect("should produce a batch");
+        assert_eq!(batch.spans().len(), 2, "should record child and root");
+        let child = &batch.spans()[0];
+        let root = &batch.spans()[1];
+        assert_eq!(root.name, "publisher.request", "should use the root name");
+        assert_eq!(root.span_id, root_parent, "should keep the root span id");
+        assert_eq!(
+            child.parent_span_id,
+            Some(root.span_id),
+            "should parent the child on the root"
+        );
+        assert!(child.error, "should mark the child as failed");
+        assert!(
+            child.end_unix_nanos >= child.start_unix_nanos,
+            "should end after it starts"
+        );
+    }
+
+    #[test]
+    fn span_limit_drops_excess_spans() {
+        let settings = TracingSettings {
+            max_spans_per_request: 2,
+            ..enabled_settings(1.0)
+        };
+        let trace = Arc::new(RequestTrace::from_request_headers(
+            &HeaderMap::new(),
+            &settings,
+        ));
+        for _ in 0..3 {
+            trace.start_span("auction.provider", SpanKind::Client).end();
+        }
+
+        let batch = trace.finish().expect("should produce a batch");
+        assert_eq!(batch.spans().len(), 2, "should keep one child plus root");
+        let root = batch.spans().last().expect("should include the root span");
+        assert!(
+            root.attributes
+                .contains(&("ts.dropped_spans", AttributeValue::Int(2))),
+            "should report dropped spans on the root"
+        );
+    }
+
+    #[test]
+    fn otlp_json_encodes_spans() {
+        let trace = Arc::new(RequestTrace::from_request_headers(
+            &headers_with_traceparent(SAMPLE_TRACEPARENT),
+            &enabled_settings(1.0),
+        ));
+        let mut span = trace.start_span("origin.fetch", SpanKind::Client);
+        span.set_attribute("http.response.status_code", 200u16);
+        span.end();
+
+        let body = trace
+            .finish()
+            .expect("should produce a batch")
+            .to_otlp_json("trusted-server-test");
+        let resource = &body["resourceSpans"][0];
+        let spans = &resource["scopeSpans"][0]["spans"];
+
+        assert_eq!(
+            resource["resource"]["attributes"][0]["value"]["stringValue"], "trusted-server-test",
+            "should set service.name"
+        );
+        assert_eq!(
+            spans[0]["traceId"], "4bf92f3577b34da6a3ce929d0e0e4736",
+            "should hex-encode the trace id"
+        );
+        assert_eq!(spans[0]["kind"], 3, "should encode client kind");
+        assert_eq!(
+            spans[0]["attributes"][0]["value"]["intValue"], "200",
+            "should encode integers as strings"
+        );
+        assert_eq!(
+            spans[1]["parentSpanId"], "00f067aa0ba902b7",
+            "should parent the root span on the caller"
+        );
+    }
+
+    #[test]
+    fn sampling_is_deterministic_per_trace_id() {
+        let low = [0u8; 16];
+        let mut high = [0u8; 16];
+        high[8..].copy_from_slice(&[0xff; 8]);
+
+        assert!(sample_trace_id(&low, 0.5), "should sample low trace ids");
+        assert!(!sample_trace_id(&high, 0.5), "should skip high trace ids");
+        assert!(sample_trace_id(&high, 1.0), "should always sample at 1.0");
+        assert!(!sample_trace_id(&low, 0.0), "should never sample at 0.0");
+    }
+}
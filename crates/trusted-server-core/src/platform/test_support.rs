@@ -129,6 +129,75 @@ impl PlatformSecretStore for HashMapSecretStore {
     }
 }
 
+/// In-memory [`PlatformKvStore`](super::PlatformKvStore) for handlers that
+/// read back what they wrote. TTLs are recorded but never enforced.
+#[derive(Default)]
+pub(crate) struct InMemoryKvStore {
+    data: Mutex<HashMap<String, bytes::Bytes>>,
+}
+
+impl InMemoryKvStore {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keys currently stored, sorted.
+    pub(crate) fn keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self
+            .data
+            .lock()
+            .expect("should lock KV data")
+            .keys()
+            .cloned()
+            .collect();
+        keys.sort();
+        keys
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl super::PlatformKvStore for InMemoryKvStore {
+    async fn get_bytes(&self, key: &str) -> Result<Option<bytes::Bytes>, super::KvError> {
+        Ok(self
+            .data
+            .lock()
+            .expect("should lock KV data")
+            .get(key)
+            .cloned())
+    }
+
+    async fn put_bytes(&self, key: &str, value: bytes::Bytes) -> Result<(), super::KvError> {
+        self.data
+            .lock()
+            .expect("should lock KV data")
+            .insert(key.to_owned(), value);
+        Ok(())
+    }
+
+    async fn put_bytes_with_ttl(
+        &self,
+        key: &str,
+        value: bytes::Bytes,
+        _ttl: std::time::Duration,
+    ) -> Result<(), super::KvError> {
+        self.put_bytes(key, value).await
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), super::KvError> {
+        self.data.lock().expect("should lock KV data").remove(key);
+        Ok(())
+    }
+
+    async fn list_keys_page(
+        &self,
+        _prefix: &str,
+        _cursor: Option<&str>,
+        _limit: usize,
+    ) -> Result<edgezero_core::key_value_store::KvPage, super::KvError> {
+        Ok(edgezero_core::key_value_store::KvPage::default())
+    }
+}
+
 pub(crate) struct NoopBackend;
 
 impl PlatformBackend for NoopBackend {
@@ -232,6 +301,9 @@ pub(crate) struct StubHttpClient {
     stream_response_flags: Mutex<Vec<bool>>,
     request_methods: Mutex<Vec<String>>,
     request_uris: Mutex<Vec<String>>,
+    // URIs captured per send_async call, kept apart from `request_uris` so
+    // synchronous-fetch assertions do not count fan-out requests.
+    async_request_uris: Mutex<Vec<String>>,
     // Outgoing request bodies captured per send call, collected to bytes.
     request_bodies: Mutex<Vec<Vec<u8>>>,
 }
@@ -256,6 +328,7 @@ impl StubHttpClient {
             stream_response_flags: Mutex::new(Vec::new()),
             request_methods: Mutex::new(Vec::new()),
             request_uris: Mutex::new(Vec::new()),
+            async_request_uris: Mutex::new(Vec::new()),
             request_bodies: Mutex::new(Vec::new()),
         }
     }
@@ -363,6 +436,14 @@ impl StubHttpClient {
             .clone()
     }
 
+    /// Return request URIs captured per `send_async` call, in order.
+    pub fn recorded_async_request_uris(&self) -> Vec<String> {
+        self.async_request_uris
+            .lock()
+            .expect("should lock async request URIs")
+            .clone()
+    }
+
     /// Return request bodies captured per `send` call, in order.
     ///
     /// Each entry is the outgoing request body collected to bytes. Bodies are
@@ -485,6 +566,10 @@ impl PlatformHttpClient for StubHttpClient {
             .lock()
             .expect("should lock calls")
             .push(backend_name.clone());
+        self.async_request_uris
+            .lock()
+            .expect("should lock async request URIs")
+            .push(request.request.uri().to_string());
         self.cache_bypass_flags
             .lock()
             .expect("should lock cache bypass flags")
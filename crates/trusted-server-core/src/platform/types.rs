@@ -3,7 +3,13 @@ use std::net::IpAddr;
 use std::sync::Arc;
 use std::time::Duration;
 
+use crate::auction::bid_cache::BidCacheWrites;
 use crate::auction::telemetry::{AuctionTelemetrySink, NoopAuctionTelemetrySink};
+use crate::metrics::{MetricsRegistry, MetricsSink, NoopMetricsSink};
+use crate::otel::{NoopTraceExporter, RequestTrace, TraceExporter};
+use crate::third_party_log::{
+    NoopThirdPartyRequestSink, ThirdPartyRequestLog, ThirdPartyRequestSink,
+};
 
 use super::{
     PlatformBackend, PlatformConfigStore, PlatformGeo, PlatformHttpClient, PlatformKvStore,
@@ -168,6 +174,15 @@ pub struct RuntimeServices {
     /// per-request basis by cloning [`RuntimeServices`] with
     /// [`RuntimeServices::with_kv_store`].
     pub(crate) kv_store: Arc<dyn PlatformKvStore>,
+    /// KV store holding cached auction bids, when the bid cache is enabled.
+    ///
+    /// Separate from [`Self::kv_store`] because auction routes already use
+    /// that slot for consent. `None` until an adapter injects it with
+    /// [`RuntimeServices::with_bid_cache_store`].
+    pub(crate) bid_cache_store: Option<Arc<dyn PlatformKvStore>>,
+    /// Bid cache updates queued by this request's auctions, written after the
+    /// response has been sent.
+    pub(crate) bid_cache_writes: Arc<BidCacheWrites>,
     /// Shared transformed-template cache. Defaults to
     /// [`UnavailableTemplateCache`], so adapters without one degrade to transforming
     /// per request rather than failing. Spike-only; see
@@ -186,6 +201,21 @@ pub struct RuntimeServices {
     pub(crate) geo: Arc<dyn PlatformGeo>,
     /// Auction telemetry sink.
     pub(crate) auction_telemetry_sink: Arc<dyn AuctionTelemetrySink>,
+    /// Span collector for the current request. Defaults to
+    /// [`RequestTrace::disabled`], which records and propagates nothing.
+    pub(crate) request_trace: Arc<RequestTrace>,
+    /// Exporter for finished request traces.
+    pub(crate) trace_exporter: Arc<dyn TraceExporter>,
+    /// Operational metrics registry. Long-lived adapters share one across
+    /// requests; per-request adapters flush it through `metrics_sink`.
+    pub(crate) metrics: Arc<MetricsRegistry>,
+    /// Sink for metrics flushed by per-request adapters.
+    pub(crate) metrics_sink: Arc<dyn MetricsSink>,
+    /// Third-party calls made during this request. Defaults to an unsampled
+    /// log that records nothing.
+    pub(crate) third_party_log: Arc<ThirdPartyRequestLog>,
+    /// Sink for the third-party request log.
+    pub(crate) third_party_sink: Arc<dyn ThirdPartyRequestSink>,
     /// Per-request client metadata extracted at the entry point.
     pub(crate) client_info: ClientInfo,
 }
@@ -233,6 +263,18 @@ impl RuntimeServices {
         &*self.kv_store
     }
 
+    /// Returns the bid cache KV store, when one was injected.
+    #[must_use]
+    pub fn bid_cache_store(&self) -> Option<&dyn PlatformKvStore> {
+        self.bid_cache_store.as_deref()
+    }
+
+    /// Returns the bid cache updates queued for this request.
+    #[must_use]
+    pub fn bid_cache_writes(&self) -> &BidCacheWrites {
+        &self.bid_cache_writes
+    }
+
     /// The shared transformed-template cache. Spike-only.
     #[must_use]
     pub fn template_cache(&self) -> &dyn super::PlatformTemplateCache {
@@ -269,6 +311,42 @@ impl RuntimeServices {
         &*self.auction_telemetry_sink
     }
 
+    /// Returns the span collector for the current request.
+    #[must_use]
+    pub fn request_trace(&self) -> &Arc<RequestTrace> {
+        &self.request_trace
+    }
+
+    /// Returns the trace exporter.
+    #[must_use]
+    pub fn trace_exporter(&self) -> &dyn TraceExporter {
+        &*self.trace_exporter
+    }
+
+    /// Returns the operational metrics registry.
+    #[must_use]
+    pub fn metrics(&self) -> &Arc<MetricsRegistry> {
+        &self.metrics
+    }
+
+    /// Returns the metrics sink.
+    #[must_use]
+    pub fn metrics_sink(&self) -> &dyn MetricsSink {
+        &*self.metrics_sink
+    }
+
+    /// Returns the third-party request log for this request.
+    #[must_use]
+    pub fn third_party_log(&self) -> &ThirdPartyRequestLog {
+        &self.third_party_log
+    }
+
+    /// Returns the third-party request sink.
+    #[must_use]
+    pub fn third_party_sink(&self) -> &dyn ThirdPartyRequestSink {
+        &*self.third_party_sink
+    }
+
     /// Returns per-request client metadata (IP address, TLS details).
     #[must_use]
     pub fn client_info(&self) -> &ClientInfo {
@@ -295,6 +373,15 @@ impl RuntimeServices {
         }
     }
 
+    /// Returns a clone of this instance with the bid cache KV store set.
+    #[must_use]
+    pub fn with_bid_cache_store(self, store: Arc<dyn PlatformKvStore>) -> Self {
+        Self {
+            bid_cache_store: Some(store),
+            ..self
+        }
+    }
+
     /// Returns a clone of this instance with the template cache replaced.
     ///
     /// Spike-only (#1009).
@@ -317,6 +404,33 @@ impl RuntimeServices {
             ..self
         }
     }
+
+    /// Returns a clone of this instance with the metrics registry replaced.
+    ///
+    /// Long-lived adapters use this to share one process-wide registry with
+    /// services built per request.
+    #[must_use]
+    pub fn with_metrics(self, metrics: Arc<MetricsRegistry>) -> Self {
+        Self { metrics, ..self }
+    }
+
+    /// Returns a clone of this instance with the metrics sink replaced.
+    #[must_use]
+    pub fn with_metrics_sink(self, metrics_sink: Arc<dyn MetricsSink>) -> Self {
+        Self {
+            metrics_sink,
+            ..self
+        }
+    }
+
+    /// Returns a clone of this instance with the third-party request log replaced.
+    #[must_use]
+    pub fn with_third_party_log(self, third_party_log: Arc<ThirdPartyRequestLog>) -> Self {
+        Self {
+            third_party_log,
+            ..self
+        }
+    }
 }
 
 impl fmt::Debug for RuntimeServices {
@@ -335,12 +449,19 @@ pub struct RuntimeServicesBuilder {
     config_store: Option<Arc<dyn PlatformConfigStore>>,
     secret_store: Option<Arc<dyn PlatformSecretStore>>,
     kv_store: Option<Arc<dyn PlatformKvStore>>,
+    bid_cache_writes: Option<Arc<BidCacheWrites>>,
     template_cache: Option<Arc<dyn super::PlatformTemplateCache>>,
     template_assembler: Option<Arc<dyn super::PlatformTemplateAssembler>>,
     backend: Option<Arc<dyn PlatformBackend>>,
     http_client: Option<Arc<dyn PlatformHttpClient>>,
     geo: Option<Arc<dyn PlatformGeo>>,
     auction_telemetry_sink: Option<Arc<dyn AuctionTelemetrySink>>,
+    request_trace: Option<Arc<RequestTrace>>,
+    trace_exporter: Option<Arc<dyn TraceExporter>>,
+    metrics: Option<Arc<MetricsRegistry>>,
+    metrics_sink: Option<Arc<dyn MetricsSink>>,
+    third_party_log: Option<Arc<ThirdPartyRequestLog>>,
+    third_party_sink: Option<Arc<dyn ThirdPartyRequestSink>>,
     client_info: Option<ClientInfo>,
 }
 
@@ -350,12 +471,19 @@ impl RuntimeServicesBuilder {
             config_store: None,
             secret_store: None,
             kv_store: None,
+            bid_cache_writes: None,
             template_cache: None,
             template_assembler: None,
             backend: None,
             http_client: None,
             geo: None,
             auction_telemetry_sink: None,
+            request_trace: None,
+            trace_exporter: None,
+            metrics: None,
+            metrics_sink: None,
+            third_party_log: None,
+            third_party_sink: None,
             client_info: None,
         }
     }
@@ -429,6 +557,55 @@ impl RuntimeServicesBuilder {
         self
     }
 
+    /// Set the span collector for the current request.
+    #[must_use]
+    pub fn request_trace(mut self, request_trace: Arc<RequestTrace>) -> Self {
+        self.request_trace = Some(request_trace);
+        self
+    }
+
+    /// Set the trace exporter.
+    #[must_use]
+    pub fn trace_exporter(mut self, trace_exporter: Arc<dyn TraceExporter>) -> Self {
+        self.trace_exporter = Some(trace_exporter);
+        self
+    }
+
+    /// Set the operational metrics registry.
+    #[must_use]
+    pub fn metrics(mut self, metrics: Arc<MetricsRegistry>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Set the metrics sink.
+    #[must_use]
+    pub fn metrics_sink(mut self, metrics_sink: Arc<dyn MetricsSink>) -> Self {
+        self.metrics_sink = Some(metrics_sink);
+        self
+    }
+
+    /// Set the bid cache write queue for this request.
+    #[must_use]
+    pub fn bid_cache_writes(mut self, bid_cache_writes: Arc<BidCacheWrites>) -> Self {
+        self.bid_cache_writes = Some(bid_cache_writes);
+        self
+    }
+
+    /// Set the third-party request log for this request.
+    #[must_use]
+    pub fn third_party_log(mut self, third_party_log: Arc<ThirdPartyRequestLog>) -> Self {
+        self.third_party_log = Some(third_party_log);
+        self
+    }
+
+    /// Set the third-party request sink.
+    #[must_use]
+    pub fn third_party_sink(mut self, third_party_sink: Arc<dyn ThirdPartyRequestSink>) -> Self {
+        self.third_party_sink = Some(third_party_sink);
+        self
+    }
+
     /// Set the per-request client metadata.
     #[must_use]
     pub fn client_info(mut self, client_info: ClientInfo) -> Self {
@@ -453,6 +630,8 @@ impl RuntimeServicesBuilder {
             kv_store: self
                 .kv_store
                 .expect("should set kv_store before building RuntimeServices"),
+            bid_cache_store: None,
+            bid_cache_writes: self.bid_cache_writes.unwrap_or_default(),
             // Defaulted rather than required: an adapter with no template cache
             // should degrade to transforming per request, not fail to build.
             template_cache: self
@@ -473,6 +652,20 @@ impl RuntimeServicesBuilder {
             auction_telemetry_sink: self
                 .auction_telemetry_sink
                 .unwrap_or_else(|| Arc::new(NoopAuctionTelemetrySink)),
+            request_trace: self
+                .request_trace
+                .unwrap_or_else(|| Arc::new(RequestTrace::disabled())),
+            trace_exporter: self
+                .trace_exporter
+                .unwrap_or_else(|| Arc::new(NoopTraceExporter)),
+            metrics: self.metrics.unwrap_or_default(),
+            metrics_sink: self
+                .metrics_sink
+                .unwrap_or_else(|| Arc::new(NoopMetricsSink)),
+            third_party_log: self.third_party_log.unwrap_or_default(),
+            third_party_sink: self
+                .third_party_sink
+                .unwrap_or_else(|| Arc::new(NoopThirdPartyRequestSink)),
             client_info: self
                 .client_info
                 .expect("should set client_info before building RuntimeServices"),
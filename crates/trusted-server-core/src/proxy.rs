@@ -23,6 +23,10 @@ use crate::constants::{
     HEADER_USER_AGENT, HEADER_X_FORWARDED_FOR,
 };
 use crate::creative::{CreativeCssProcessor, CreativeHtmlProcessor};
+use crate::creative_cache::{
+    CachedCreative, CreativeCache, cacheable_lifetime, creative_content_hash,
+    is_cacheable_content_type,
+};
 use crate::edge_cookie::get_ec_id;
 use crate::error::TrustedServerError;
 use crate::platform::{
@@ -35,6 +39,10 @@ use crate::settings::{
     AssetOriginAuth, OriginQueryPolicy, ProxyAssetRoute, S3SigV4AuthConfig, Settings,
 };
 use crate::streaming_processor::{Compression, PipelineConfig, StreamProcessor, StreamingPipeline};
+use crate::third_party_log::{
+    ThirdPartyCall, ThirdPartyTransport, record_third_party_call, send_third_party_request,
+};
+use crate::vast::{CreativeVastProcessor, VAST_WRAPPER_DEPTH_PARAM};
 
 /// Chunk size used for streaming content through the rewrite pipeline.
 const STREAMING_CHUNK_SIZE: usize = 8192;
@@ -371,6 +379,14 @@ pub struct ProxyRequestConfig<'a> {
     pub allowed_domains: &'a [String],
     /// Require the initial target and every followed redirect hop to use HTTPS.
     pub require_https: bool,
+    /// Label recorded in the third-party request log, usually the integration ID.
+    pub integration: &'a str,
+    /// Wrapper depth of a VAST document fetched by this request; `0` unless the
+    /// request follows a signed `VASTAdTagURI`.
+    pub vast_wrapper_depth: u8,
+    /// Serve and store rewritten bodies through the creative cache. Only
+    /// honoured when `proxy.creative_cache` is enabled.
+    pub cache_creatives: bool,
 }
 
 impl<'a> ProxyRequestConfig<'a> {
@@ -387,6 +403,9 @@ impl<'a> ProxyRequestConfig<'a> {
             stream_passthrough: false,
             allowed_domains: &[],
             require_https: false,
+            integration: "proxy",
+            vast_wrapper_depth: 0,
+            cache_creatives: false,
         }
     }
 
@@ -438,6 +457,27 @@ impl<'a> ProxyRequestConfig<'a> {
         self.require_https = true;
         self
     }
+
+    /// Attribute outbound calls to `integration` in the third-party request log.
+    #[must_use]
+    pub fn with_integration(mut self, integration: &'a str) -> Self {
+        self.integration = integration;
+        self
+    }
+
+    /// Mark the target as a VAST wrapper hop at `depth`.
+    #[must_use]
+    pub fn with_vast_wrapper_depth(mut self, depth: u8) -> Self {
+        self.vast_wrapper_depth = depth;
+        self
+    }
+
+    /// Route the rewritten response through the creative cache.
+    #[must_use]
+    pub fn with_creative_cache(mut self, enabled: bool) -> Self {
+        self.cache_creatives = enabled;
+        self
+    }
 }
 
 /// Encodings we support decompressing in `finalize_proxied_response`.
@@ -646,6 +686,7 @@ fn finalize_proxied_response(
     req: &Request<EdgeBody>,
     target_url: &str,
     mut beresp: Response<EdgeBody>,
+    vast_wrapper_depth: u8,
 ) -> Result<Response<EdgeBody>, Report<TrustedServerError>> {
     let meta = origin_response_metadata(req, &beresp, target_url, true);
     let ct = meta.ct_raw.to_ascii_lowercase();
@@ -673,6 +714,19 @@ fn finalize_proxied_response(
         );
     }
 
+    // VAST tags are served as `application/xml` or `text/xml`. Other XML
+    // passes through the processor unchanged.
+    if ct.starts_with("application/xml") || ct.starts_with("text/xml") {
+        let processor = CreativeVastProcessor::new(settings, vast_wrapper_depth);
+        return process_response_with_pipeline(
+            beresp,
+            processor,
+            compression,
+            "application/xml; charset=utf-8",
+            "Failed to process XML response",
+        );
+    }
+
     apply_image_passthrough_metadata(req, target_url, &ct, &mut beresp, "");
     Ok(beresp)
 }
@@ -729,21 +783,94 @@ fn finalize_response(
     url: &str,
     beresp: Response<EdgeBody>,
     stream_passthrough: bool,
+    vast_wrapper_depth: u8,
 ) -> Result<Response<EdgeBody>, Report<TrustedServerError>> {
     let mut response = if stream_passthrough {
         finalize_proxied_response_streaming(req, url, beresp)
     } else {
-        finalize_proxied_response(settings, req, url, beresp)?
+        finalize_proxied_response(settings, req, url, beresp, vast_wrapper_depth)?
     };
     strip_cors_policy(&mut response);
     Ok(response)
 }
 
+/// Finalize a proxied creative through the creative cache.
+///
+/// Upstream bytes already rewritten under the current rules are served from
+/// the cache instead of being rewritten again; otherwise the finalized response
+/// is stored for later requests. Responses the cache cannot hold (see
+/// [`cacheable_lifetime`]) are finalized as usual.
+async fn finalize_cached_response(
+    settings: &Settings,
+    req: &Request<EdgeBody>,
+    url: &str,
+    beresp: Response<EdgeBody>,
+    cache_url: &str,
+    vast_wrapper_depth: u8,
+    services: &RuntimeServices,
+) -> Result<Response<EdgeBody>, Report<TrustedServerError>> {
+    let content_type = beresp
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|h| h.to_str().ok())
+        .unwrap_or("")
+        .to_string();
+    let ttl = cacheable_lifetime(
+        beresp.status(),
+        beresp.headers(),
+        settings.proxy.creative_cache.max_ttl_secs,
+    );
+    let Some(ttl) =
+        ttl.filter(|_| is_cacheable_content_type(&content_type) && !beresp.body().is_stream())
+    else {
+        return finalize_response(settings, req, url, beresp, false, vast_wrapper_depth);
+    };
+
+    let cache = CreativeCache::new(settings, services.kv_store());
+    let (parts, body) = beresp.into_parts();
+    let raw = body.into_bytes().unwrap_or_default();
+    let content_hash = creative_content_hash(&content_type, &raw);
+
+    if let Some((creative, remaining)) = cache.get_by_hash(&content_hash, vast_wrapper_depth).await
+    {
+        log::debug!("creative cache: reusing rewritten body for {cache_url}");
+        cache
+            .put_url(
+                cache_url,
+                vast_wrapper_depth,
+                &content_hash,
+                ttl.min(remaining),
+            )
+            .await;
+        return Ok(creative.into_response(ttl.min(remaining)));
+    }
+
+    let response = finalize_response(
+        settings,
+        req,
+        url,
+        Response::from_parts(parts, EdgeBody::from(raw.to_vec())),
+        false,
+        vast_wrapper_depth,
+    )?;
+    let (parts, body) = response.into_parts();
+    let rewritten = body.into_bytes().unwrap_or_default();
+    let creative = CachedCreative::from_headers(&parts.headers, rewritten.clone());
+    cache
+        .put(cache_url, vast_wrapper_depth, &content_hash, ttl, &creative)
+        .await;
+    Ok(Response::from_parts(
+        parts,
+        EdgeBody::from(rewritten.to_vec()),
+    ))
+}
+
 /// Bundles per-request header configuration and [`RuntimeServices`] for the proxy redirect loop.
 struct ProxyRequestHeaders<'a> {
     additional_headers: &'a [(header::HeaderName, HeaderValue)],
     copy_request_headers: bool,
     services: &'a RuntimeServices,
+    integration: &'a str,
 }
 
 struct ProxyRedirectPolicy<'a> {
@@ -751,6 +878,9 @@ struct ProxyRedirectPolicy<'a> {
     stream_passthrough: bool,
     allowed_domains: &'a [String],
     require_https: bool,
+    vast_wrapper_depth: u8,
+    /// Signed target the creative cache keys this response by, when caching.
+    cache_url: Option<&'a str>,
 }
 
 /// Proxy a request to a clear target URL while reusing creative rewrite logic.
@@ -779,6 +909,9 @@ pub async fn proxy_request(
         stream_passthrough,
         allowed_domains,
         require_https,
+        integration,
+        vast_wrapper_depth,
+        cache_creatives,
     } = config;
 
     let mut target_url_parsed = url::Url::parse(target_url).map_err(|_| {
@@ -800,12 +933,16 @@ pub async fn proxy_request(
             additional_headers: &headers,
             copy_request_headers,
             services,
+            integration,
         },
         ProxyRedirectPolicy {
             follow_redirects,
             stream_passthrough,
             allowed_domains,
             require_https,
+            vast_wrapper_depth,
+            cache_url: (cache_creatives && settings.proxy.creative_cache.enabled)
+                .then_some(target_url),
         },
     )
     .await
@@ -1416,14 +1553,16 @@ async fn proxy_with_redirects(
                     message: "failed to build proxy request".to_string(),
                 })?;
 
-        let platform_resp = request_headers
-            .services
-            .http_client()
-            .send(PlatformHttpRequest::new(edge_req, backend_name))
-            .await
-            .change_context(TrustedServerError::Proxy {
-                message: "Failed to proxy".to_string(),
-            })?;
+        let platform_resp = send_third_party_request(
+            request_headers.services,
+            settings,
+            request_headers.integration,
+            PlatformHttpRequest::new(edge_req, backend_name),
+        )
+        .await
+        .change_context(TrustedServerError::Proxy {
+            message: "Failed to proxy".to_string(),
+        })?;
 
         let beresp = platform_resp.response;
 
@@ -1434,6 +1573,7 @@ async fn proxy_with_redirects(
                 &current_url,
                 beresp,
                 redirect_policy.stream_passthrough,
+                redirect_policy.vast_wrapper_depth,
             );
         }
 
@@ -1448,12 +1588,27 @@ async fn proxy_with_redirects(
         );
 
         if !is_redirect {
+            if let Some(cache_url) = redirect_policy.cache_url
+                && !redirect_policy.stream_passthrough
+            {
+                return finalize_cached_response(
+                    settings,
+                    req,
+                    &current_url,
+                    beresp,
+                    cache_url,
+                    redirect_policy.vast_wrapper_depth,
+                    request_headers.services,
+                )
+                .await;
+            }
             return finalize_response(
                 settings,
                 req,
                 &current_url,
                 beresp,
                 redirect_policy.stream_passthrough,
+                redirect_policy.vast_wrapper_depth,
             );
         }
 
@@ -1469,6 +1624,7 @@ async fn proxy_with_redirects(
                 &current_url,
                 beresp,
                 redirect_policy.stream_passthrough,
+                redirect_policy.vast_wrapper_depth,
             );
         };
 
@@ -1477,7 +1633,13 @@ async fn proxy_with_redirects(
                 "redirect limit reached for {}; returning redirect response",
                 current_url
             );
-            return finalize_proxied_response(settings, req, &current_url, beresp);
+            return finalize_proxied_response(
+                settings,
+                req,
+                &current_url,
+                beresp,
+                redirect_policy.vast_wrapper_depth,
+            );
         }
 
         let next_url = url::Url::parse(location)
@@ -1502,6 +1664,7 @@ async fn proxy_with_redirects(
                 &current_url,
                 beresp,
                 redirect_policy.stream_passthrough,
+                redirect_policy.vast_wrapper_depth,
             );
         }
 
@@ -1553,6 +1716,8 @@ async fn proxy_with_redirects(
 /// - If the response is an image or the request `Accept` indicates images, ensures an
 ///   `application/octet-stream` content type if origin omitted it, and logs likely 1×1
 ///   pixels using simple size/URL heuristics. No special response (still proxied).
+/// - When `proxy.creative_cache` is enabled, cacheable creatives are served from and
+///   stored in the KV store selected on `services` (see [`crate::creative_cache`]).
 ///
 /// # Errors
 ///
@@ -1563,8 +1728,21 @@ pub async fn handle_first_party_proxy(
     req: Request<EdgeBody>,
 ) -> Result<Response<EdgeBody>, Report<TrustedServerError>> {
     // Parse, reconstruct, and validate the signed target URL
-    let SignedTarget { target_url, .. } =
-        reconstruct_and_validate_signed_target(settings, &req.uri().to_string())?;
+    let SignedTarget {
+        target_url,
+        vast_wrapper_depth,
+        ..
+    } = reconstruct_and_validate_signed_target(settings, &req.uri().to_string())?;
+
+    let cache_creatives = settings.proxy.creative_cache.enabled;
+    if cache_creatives
+        && let Some((creative, remaining)) = CreativeCache::new(settings, services.kv_store())
+            .get_by_url(&target_url, vast_wrapper_depth)
+            .await
+    {
+        log::debug!("creative cache: serving {target_url} without an upstream fetch");
+        return Ok(creative.into_response(remaining));
+    }
 
     proxy_request(
         settings,
@@ -1579,6 +1757,9 @@ pub async fn handle_first_party_proxy(
             stream_passthrough: false,
             allowed_domains: &settings.proxy.allowed_domains,
             require_https: false,
+            integration: "first_party_proxy",
+            vast_wrapper_depth,
+            cache_creatives,
         },
         services,
     )
@@ -1597,13 +1778,14 @@ pub async fn handle_first_party_proxy(
 /// Returns an error if the signed target cannot be reconstructed or validation fails.
 pub async fn handle_first_party_click(
     settings: &Settings,
-    _services: &RuntimeServices,
+    services: &RuntimeServices,
     req: Request<EdgeBody>,
 ) -> Result<Response<EdgeBody>, Report<TrustedServerError>> {
     let SignedTarget {
         target_url: full_for_token,
         tsurl,
         had_params,
+        ..
     } = reconstruct_and_validate_signed_target(settings, &req.uri().to_string())?;
 
     let ec_id = match get_ec_id(&req) {
@@ -1662,6 +1844,21 @@ pub async fn handle_first_party_click(
         header::CACHE_CONTROL,
         HeaderValue::from_static("no-store, private"),
     );
+
+    if let Ok(url) = url::Url::parse(&redirect_target) {
+        record_third_party_call(
+            services,
+            settings,
+            ThirdPartyCall {
+                integration: "first_party_click",
+                host: url.host_str().unwrap_or_default(),
+                method: req.method(),
+                transport: ThirdPartyTransport::Redirect,
+            },
+            Some(&response),
+            Duration::ZERO,
+        );
+    }
     Ok(response)
 }
 
@@ -1760,7 +1957,7 @@ pub async fn handle_first_party_proxy_sign(
     let mut base = parsed.clone();
     base.set_query(None);
     base.set_fragment(None);
-    let proxied = crate::creative::build_proxy_url_with_extras(settings, &abs, &extras);
+    let proxied = crate::creative::build_proxy_url_with_extras(settings, &abs, "", &extras);
 
     let resp = ProxySignResp {
         href: proxied,
@@ -1950,7 +2147,9 @@ pub async fn handle_first_party_proxy_rebuild(
     // handle_first_party_proxy_sign attaches; `tstoken`/`tsurl` are the signature
     // and target. Allowing del/add here would let a public rebuild request strip
     // the expiration from a still-valid click URL and re-sign a non-expiring one.
-    const RESERVED_SIGNING_PARAMS: &[&str] = &["tsexp", "tstoken", "tsurl"];
+    // `tsvast` is the VAST wrapper depth; resetting it would lift the hop limit.
+    const RESERVED_SIGNING_PARAMS: &[&str] =
+        &["tsexp", "tstoken", "tsurl", VAST_WRAPPER_DEPTH_PARAM];
 
     // Apply removals
     if let Some(del) = &payload.del {
@@ -2060,6 +2259,8 @@ struct SignedTarget {
     target_url: String,
     tsurl: String,
     had_params: bool,
+    /// Signed VAST wrapper depth (`tsvast`), `0` when absent.
+    vast_wrapper_depth: u8,
 }
 
 /// Placeholder authority used to parse origin-form request targets.
@@ -2121,6 +2322,7 @@ fn reconstruct_and_validate_signed_target(
     let mut ser = url::form_urlencoded::Serializer::new(String::new());
     let mut had_params = false;
     let mut tsexp: Option<String> = None;
+    let mut tsvast: Option<String> = None;
     for (k, v) in parsed.query_pairs() {
         let key = k.as_ref();
         let value = v.into_owned();
@@ -2137,6 +2339,9 @@ fn reconstruct_and_validate_signed_target(
             ser.append_pair(key, &value);
             continue;
         }
+        if key == VAST_WRAPPER_DEPTH_PARAM {
+            tsvast = Some(value.clone());
+        }
         ser.append_pair(key, &value);
         had_params = true;
     }
@@ -2186,10 +2391,21 @@ fn reconstruct_and_validate_signed_target(
         }
     }
 
+    // Parsed only after the signature check, so the depth cannot be forged.
+    let vast_wrapper_depth = match tsvast {
+        Some(value) => value.parse::<u8>().map_err(|_| {
+            Report::new(TrustedServerError::Proxy {
+                message: "invalid tsvast".to_string(),
+            })
+        })?,
+        None => 0,
+    };
+
     Ok(SignedTarget {
         target_url: full_for_token,
         tsurl,
         had_params,
+        vast_wrapper_depth,
     })
 }
 
@@ -2216,7 +2432,7 @@ mod tests {
     use crate::creative;
     use crate::error::{IntoHttpResponse, TrustedServerError};
     use crate::platform::test_support::{
-        HashMapSecretStore, StubHttpClient, build_services_with_http_client,
+        HashMapSecretStore, InMemoryKvStore, StubHttpClient, build_services_with_http_client,
         build_services_with_secret_and_http_client, noop_services,
     };
     use crate::platform::{
@@ -2705,6 +2921,32 @@ mod tests {
         assert_eq!(err.current_context().status_code(), StatusCode::BAD_GATEWAY);
     }
 
+    #[test]
+    fn reconstruct_reads_signed_vast_wrapper_depth() {
+        let settings = create_test_settings();
+        let tsurl = "https://cdn.example/vast.xml";
+        let canonical = format!("{tsurl}?tsvast=2");
+        let sig = crate::http_util::compute_encrypted_sha256_token(&settings, &canonical);
+        let tsurl_encoded =
+            url::form_urlencoded::byte_serialize(tsurl.as_bytes()).collect::<String>();
+
+        let signed = format!(
+            "https://edge.example/first-party/proxy?tsurl={tsurl_encoded}&tsvast=2&tstoken={sig}"
+        );
+        let target = reconstruct_and_validate_signed_target(&settings, &signed)
+            .expect("should accept a signed tsvast");
+        assert_eq!(
+            target.vast_wrapper_depth, 2,
+            "should expose the signed wrapper depth"
+        );
+
+        let tampered = format!(
+            "https://edge.example/first-party/proxy?tsurl={tsurl_encoded}&tsvast=0&tstoken={sig}"
+        );
+        reconstruct_and_validate_signed_target(&settings, &tampered)
+            .expect_err("should reject a tsvast that does not match the signature");
+    }
+
     #[test]
     fn reconstruct_rejects_tampered_tstoken() {
         let settings = create_test_settings();
@@ -2771,6 +3013,47 @@ mod tests {
         });
     }
 
+    #[test]
+    fn click_records_redirect_in_third_party_log() {
+        futures::executor::block_on(async {
+            let settings = create_test_settings();
+            let tsurl = "https://cdn.example/a.png";
+            let sig = crate::http_util::compute_encrypted_sha256_token(&settings, tsurl);
+            let req = build_http_request(
+                Method::GET,
+                format!(
+                    "https://edge.example/first-party/click?tsurl={}&tstoken={}",
+                    url::form_urlencoded::byte_serialize(tsurl.as_bytes()).collect::<String>(),
+                    sig
+                ),
+            );
+            let services = noop_services().with_third_party_log(Arc::new(
+                crate::third_party_log::ThirdPartyRequestLog::new(true),
+            ));
+
+            handle_first_party_click(&settings, &services, req)
+                .await
+                .expect("should redirect");
+
+            let batch = services.third_party_log().take_batch();
+            assert_eq!(batch.row_count(), 1, "should record the click redirect");
+            let row = &batch.rows()[0];
+            assert_eq!(
+                row.integration, "first_party_click",
+                "should label click route"
+            );
+            assert_eq!(
+                row.destination_host, "cdn.example",
+                "should record target host"
+            );
+            assert_eq!(
+                row.transport, "redirect",
+                "should record redirect transport"
+            );
+            assert_eq!(row.status, 302, "should record redirect status");
+        });
+    }
+
     #[test]
     fn click_appends_ec_id_when_present() {
         futures::executor::block_on(async {
@@ -2899,6 +3182,7 @@ mod tests {
                     "https://cdn.example/asset.bin",
                     beresp,
                     streaming,
+                    0,
                 )
                 .expect("finalize should succeed");
 
@@ -2936,6 +3220,7 @@ mod tests {
             "https://cdn.example/app.mjs",
             build_http_response(StatusCode::OK, EdgeBody::from("body{}")),
             false,
+            0,
         )
         .expect("finalize should succeed");
 
@@ -2951,6 +3236,7 @@ mod tests {
             "https://cdn.example/font.woff2",
             build_http_response(StatusCode::OK, EdgeBody::from("font")),
             true,
+            0,
         )
         .expect("streaming finalize should succeed");
 
@@ -3342,6 +3628,71 @@ mod tests {
         });
     }
 
+    #[test]
+    fn first_party_proxy_serves_repeat_creatives_from_the_creative_cache() {
+        futures::executor::block_on(async {
+            let mut settings = create_test_settings();
+            settings.proxy.creative_cache.enabled = true;
+            settings.proxy.creative_cache.kv_store = Some("creative_cache".to_string());
+            let stub = Arc::new(StubHttpClient::new());
+            stub.push_response_with_headers(
+                200,
+                b"body{background:url(https://cdn.example/bg.png)}".to_vec(),
+                vec![
+                    ("content-type", "text/css"),
+                    ("cache-control", "public, max-age=300"),
+                ],
+            );
+            let store = Arc::new(InMemoryKvStore::new());
+            let services =
+                build_services_with_http_client(Arc::clone(&stub) as Arc<dyn PlatformHttpClient>)
+                    .with_kv_store(Arc::clone(&store) as Arc<dyn crate::platform::PlatformKvStore>);
+            let first_party =
+                creative::build_proxy_url(&settings, "https://cdn.example/creative.css", "");
+            let url = format!("https://edge.example{first_party}");
+
+            let first = handle_first_party_proxy(
+                &settings,
+                &services,
+                build_http_request(Method::GET, &url),
+            )
+            .await
+            .expect("should proxy the first request upstream");
+            let first_body = response_body_string(first);
+            assert!(
+                first_body.contains("/first-party/proxy?tsurl="),
+                "should rewrite the creative on a miss: {first_body}"
+            );
+            assert!(
+                !store.keys().is_empty(),
+                "should store the rewritten creative"
+            );
+
+            let second = handle_first_party_proxy(
+                &settings,
+                &services,
+                build_http_request(Method::GET, &url),
+            )
+            .await
+            .expect("should serve the repeat request");
+            assert!(
+                response_header(&second, header::CACHE_CONTROL)
+                    .is_some_and(|value| value.starts_with("max-age=")),
+                "should bound browser caching by the remaining entry lifetime"
+            );
+            assert_eq!(
+                response_body_string(second),
+                first_body,
+                "should serve the cached rewritten body"
+            );
+            assert_eq!(
+                stub.recorded_backend_names().len(),
+                1,
+                "should fetch upstream only once"
+            );
+        });
+    }
+
     #[test]
     fn proxy_invalid_target_url_errors() {
         futures::executor::block_on(async {
@@ -3760,6 +4111,9 @@ mod tests {
                     stream_passthrough: false,
                     allowed_domains: &[],
                     require_https: false,
+                    integration: "proxy",
+                    vast_wrapper_depth: 0,
+                    cache_creatives: false,
                 },
                 &services,
             )
@@ -3801,6 +4155,9 @@ mod tests {
                     stream_passthrough: false,
                     allowed_domains: &[],
                     require_https: false,
+                    integration: "proxy",
+                    vast_wrapper_depth: 0,
+                    cache_creatives: false,
                 },
                 &services,
             )
@@ -3847,6 +4204,9 @@ mod tests {
                     stream_passthrough: false,
                     allowed_domains: &[],
                     require_https: false,
+                    integration: "proxy",
+                    vast_wrapper_depth: 0,
+                    cache_creatives: false,
                 },
                 &services,
             )
@@ -3896,6 +4256,9 @@ mod tests {
                     stream_passthrough: false,
                     allowed_domains: &[],
                     require_https: false,
+                    integration: "proxy",
+                    vast_wrapper_depth: 0,
+                    cache_creatives: false,
                 },
                 &services,
             )
@@ -3961,6 +4324,9 @@ mod tests {
                     stream_passthrough: false,
                     allowed_domains: &[],
                     require_https: false,
+                    integration: "proxy",
+                    vast_wrapper_depth: 0,
+                    cache_creatives: false,
                 },
                 &services,
             )
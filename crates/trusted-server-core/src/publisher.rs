@@ -35,6 +35,7 @@ use flate2::write::{GzEncoder, ZlibEncoder};
 use futures::StreamExt as _;
 use http::{HeaderValue, Method, Request, Response, StatusCode, Uri, header};
 
+use crate::auction::beacon::attach_event_tokens;
 use crate::auction::endpoints::{
     merge_auction_eids, resolve_auction_eids, resolve_client_auction_eids,
 };
@@ -65,6 +66,11 @@ use crate::error::TrustedServerError;
 use crate::html_processor::BodyCloseInjection;
 use crate::http_util::{RequestInfo, is_navigation_request, serve_static_with_etag};
 use crate::integrations::IntegrationRegistry;
+use crate::metrics::{METRIC_TEMPLATE_CACHE, record_consent_decision};
+use crate::otel::{
+    ATTR_AUCTION_ID, SPAN_CREATIVE_REWRITE, SPAN_HTML_AUCTION_HOLD, SPAN_ORIGIN_FETCH, SpanKind,
+    inject_traceparent,
+};
 use crate::platform::{
     GeoInfo, PlatformBackendSpec, PlatformHttpRequest, RuntimeServices, VarySpec,
     contains_publisher_esi_directive,
@@ -118,6 +124,12 @@ impl TemplateCacheResponseState {
     }
 }
 
+fn record_template_cache_lookup(services: &RuntimeServices, state: TemplateCacheResponseState) {
+    services
+        .metrics()
+        .increment(METRIC_TEMPLATE_CACHE, &[("result", state.as_str())]);
+}
+
 fn set_template_cache_response_state(
     response: &mut Response<EdgeBody>,
     state: TemplateCacheResponseState,
@@ -1961,7 +1973,7 @@ fn assemble_if_shared(
 ///
 /// Does not panic: serializing the already-deserialized typed settings to a JSON value is
 /// infallible for this schema.
-fn template_fingerprint(settings: &Settings) -> String {
+pub(crate) fn template_fingerprint(settings: &Settings) -> String {
     use sha2::Digest as _;
 
     let mut hasher = sha2::Sha256::new();
@@ -3142,6 +3154,15 @@ impl AdBidsState {
     }
 }
 
+/// Auction identifiers stamped into a rendered bid map.
+#[derive(Clone, Copy)]
+pub(crate) struct BidMapAuctionIds<'a> {
+    /// Diagnostics auction ID exposed as `hb_auction_id`, when enabled.
+    pub(crate) diagnostics: Option<&'a str>,
+    /// Telemetry observation that `ts_event` beacon tokens reference.
+    pub(crate) observation: Option<&'a AuctionObservationContext>,
+}
+
 pub(crate) fn write_bids_to_state(
     winning_bids: &std::collections::HashMap<String, Bid>,
     price_granularity: PriceGranularity,
@@ -3149,21 +3170,24 @@ pub(crate) fn write_bids_to_state(
     settings: &Settings,
     request_origin: &str,
     include_debug_bid: bool,
-    auction_id: Option<&str>,
+    auction_ids: BidMapAuctionIds<'_>,
 ) -> std::collections::HashSet<String> {
     log::debug!(
         "write_bids_to_state: {} winning bid(s): [{}]",
         winning_bids.len(),
         winning_bids.keys().cloned().collect::<Vec<_>>().join(", ")
     );
-    let bid_map = build_bid_map_with_auction_id(
+    let mut bid_map = build_bid_map_with_auction_id(
         winning_bids,
         price_granularity,
         settings,
         request_origin,
         include_debug_bid,
-        auction_id,
+        auction_ids.diagnostics,
     );
+    if let Some(observation) = auction_ids.observation {
+        attach_event_tokens(&mut bid_map, winning_bids, settings, observation);
+    }
     let delivered_winner_slots = bid_map.keys().cloned().collect();
     ad_bids_state.set(bid_map);
     delivered_winner_slots
@@ -3869,6 +3893,10 @@ async fn collect_non_html_auction(
             &make_collect_context(settings, services, &placeholder),
         )
         .await;
+    let mut rewrite_span = services
+        .request_trace()
+        .start_span(SPAN_CREATIVE_REWRITE, SpanKind::Internal);
+    rewrite_span.set_attribute("winning_bids", result.winning_bids.len());
     let delivered_winner_slots = write_bids_to_state(
         &result.winning_bids,
         params.price_granularity,
@@ -3876,8 +3904,12 @@ async fn collect_non_html_auction(
         settings,
         &request_origin(&params.request_scheme, &params.request_host),
         settings.debug.inject_adm_for_testing,
-        auction_id.as_deref(),
+        BidMapAuctionIds {
+            diagnostics: auction_id.as_deref(),
+            observation: telemetry.observation.as_ref(),
+        },
     );
+    rewrite_span.end();
     if let (Some(observation), Some(auction_request)) =
         (telemetry.observation, telemetry.auction_request.as_ref())
     {
@@ -3916,6 +3948,14 @@ async fn collect_stream_auction(
         .as_ref()
         .and_then(|_| diagnostics_auction_id(settings));
     log::info!("body_close_hold_loop: collecting dispatched auction before held body tail");
+    // Covers the whole `</body>` hold: the time between origin EOF and the
+    // held tail being released is exactly the DOMContentLoaded slip.
+    let mut hold_span = services
+        .request_trace()
+        .start_span(SPAN_HTML_AUCTION_HOLD, SpanKind::Internal);
+    if let Some(auction_request) = telemetry.auction_request.as_ref() {
+        hold_span.set_attribute(ATTR_AUCTION_ID, auction_request.id.as_str());
+    }
     let placeholder = mediator_placeholder_request();
     let collect_ctx = make_collect_context(settings, services, &placeholder);
     let result = orchestrator
@@ -3925,6 +3965,11 @@ async fn collect_stream_auction(
         "body_close_hold_loop: collect complete - {} winning bid(s)",
         result.winning_bids.len()
     );
+    hold_span.set_attribute("winning_bids", result.winning_bids.len());
+    let mut rewrite_span = services
+        .request_trace()
+        .start_span(SPAN_CREATIVE_REWRITE, SpanKind::Internal);
+    rewrite_span.set_attribute("winning_bids", result.winning_bids.len());
     let delivered_winner_slots = write_bids_to_state(
         &result.winning_bids,
         *price_granularity,
@@ -3932,8 +3977,12 @@ async fn collect_stream_auction(
         settings,
         request_origin,
         settings.debug.inject_adm_for_testing,
-        auction_id.as_deref(),
+        BidMapAuctionIds {
+            diagnostics: auction_id.as_deref(),
+            observation: telemetry.observation.as_ref(),
+        },
     );
+    rewrite_span.end();
     if let (Some(observation), Some(auction_request)) =
         (telemetry.observation, telemetry.auction_request.as_ref())
     {
@@ -4024,6 +4073,11 @@ pub async fn handle_publisher_request(
 ) -> Result<PublisherResponse, Report<TrustedServerError>> {
     log::debug!("Proxying request to publisher_origin");
 
+    let trace = services.request_trace();
+    trace.set_root_name("publisher.request");
+    trace.set_root_attribute("http.request.method", req.method().as_str());
+    trace.set_root_attribute("url.path", req.uri().path());
+
     // Adapter fallbacks prepare this before EC/cookie handling. Keep this
     // idempotent call as a direct-handler safety net and for focused tests.
     let gpt_diagnostics =
@@ -4129,6 +4183,9 @@ pub async fn handle_publisher_request(
     // requests carrying an EU TCF signal require effective TCF Purpose 1
     // (storage/access) before firing. Known non-GDPR jurisdictions are free.
     let consent_allows_auction = consent_allows_server_side_auction(&consent_context);
+    if !matched_slots.is_empty() {
+        record_consent_decision(services.metrics(), "publisher", consent_allows_auction);
+    }
 
     let should_run_ad_stack = should_run_server_side_ad_stack(
         is_get,
@@ -4181,7 +4238,8 @@ pub async fn handle_publisher_request(
     let assembly_mode = configured_assembly_mode(settings);
 
     let mut auction_request_for_telemetry: Option<AuctionRequest> = None;
-    let mut dispatched_auction = if matched_slots.is_empty() {
+    let auction_slots = document_auction_slots(&matched_slots);
+    let mut dispatched_auction = if auction_slots.is_empty() {
         None
     } else {
         // Telemetry attribution must use the same publisher identity as the
@@ -4193,13 +4251,14 @@ pub async fn handle_publisher_request(
             AuctionSource::InitialNavigation,
             &settings.publisher.domain,
             &request_path,
-            matched_slots.len(),
+            auction_slots.len(),
             ec_context,
-        );
+        )
+        .with_experiment_arms(&settings.experiment_arms);
 
         if should_run_auction {
             let slots_ctx = MatchedSlotsContext {
-                matched_slots: &matched_slots,
+                matched_slots: &auction_slots,
                 request_path: &request_path,
             };
             let mut auction_request = build_auction_request(
@@ -4238,6 +4297,7 @@ pub async fn handle_publisher_request(
                 .await
             {
                 DispatchAuctionOutcome::Dispatched(dispatched) => {
+                    trace.set_root_attribute(ATTR_AUCTION_ID, auction_request.id.as_str());
                     auction_request_for_telemetry = Some(auction_request);
                     auction_observation = Some(observation);
                     Some(dispatched)
@@ -4483,6 +4543,7 @@ pub async fn handle_publisher_request(
                     }
                     template_cache_response_state = Some(TemplateCacheResponseState::Invalid);
                 } else {
+                    record_template_cache_lookup(services, TemplateCacheResponseState::Hit);
                     // Deliberately *not* assembled here. The auction is still in flight,
                     // and awaiting it now would hold the first byte until it resolves —
                     // measured at ~100x worse TTFB than doing nothing. The finalizer
@@ -4532,6 +4593,9 @@ pub async fn handle_publisher_request(
                 template_cache_response_state = Some(TemplateCacheResponseState::BackendError);
             }
         }
+        if let Some(state) = template_cache_response_state {
+            record_template_cache_lookup(services, state);
+        }
     }
 
     // SSP requests are already racing through the platform HTTP client, so
@@ -4542,6 +4606,14 @@ pub async fn handle_publisher_request(
     // without streaming support may reject the flag outright rather than
     // silently buffering, which would fail every publisher fetch.
     let request_method = req.method().clone();
+    // The span ends once response headers arrive; body time is covered by the
+    // root span and, for held documents, by the `</body>` hold span.
+    let mut origin_span = trace.start_span(SPAN_ORIGIN_FETCH, SpanKind::Client);
+    origin_span.set_attribute("server.address", origin_host.as_str());
+    inject_traceparent(
+        req.headers_mut(),
+        origin_span.traceparent().or_else(|| trace.traceparent()),
+    );
     let mut platform_request = PlatformHttpRequest::new(req, backend_name);
     if services.http_client().supports_streaming_responses() {
         platform_request = platform_request.with_stream_response();
@@ -4553,6 +4625,8 @@ pub async fn handle_publisher_request(
     let mut response = match services.http_client().send(platform_request).await {
         Ok(platform_response) => platform_response.response,
         Err(err) => {
+            origin_span.set_error(ERROR_TYPE_TRANSPORT);
+            trace.set_root_error();
             if let Some(dispatched) = dispatched_auction.take() {
                 emit_abandoned_auction(
                     services,
@@ -4568,6 +4642,8 @@ pub async fn handle_publisher_request(
         }
     };
 
+    origin_span.set_attribute("http.response.status_code", response.status().as_u16());
+    origin_span.end();
     log::debug!(
         "Publisher origin response received: status={}, header_count={}",
         response.status(),
@@ -4983,6 +5059,7 @@ pub(crate) fn build_auction_request(
             page: page_url,
         }),
         context: std::collections::HashMap::new(),
+        ad_quality: Default::default(),
     }
 }
 
@@ -5036,10 +5113,8 @@ const GAM_TARGETING_VALUE_MAX_LEN: usize = 40;
 
 /// Treat an empty identifier as absent.
 ///
-/// `Option::or` considers `Some("")` present, which would let a blank
-/// `cacheId`/`adid` win the `hb_adid` precedence below and emit an empty
-/// targeting value — falsey on the page, so GPT skips the key and the render
-/// bridge has nothing to match, exactly the failure the fallback chain closes.
+/// A blank `cacheId` is treated as absent by [`Bid::targeting_ad_id`], so the
+/// cache-coordinate gate below must treat it the same way.
 fn non_empty(value: Option<&str>) -> Option<&str> {
     value.filter(|value| !value.is_empty())
 }
@@ -5047,7 +5122,7 @@ fn non_empty(value: Option<&str>) -> Option<&str> {
 /// Build a price-bucketed bid map from winning bids.
 ///
 /// Returns a JSON object map of slot ID → bid metadata including the bucketed
-/// CPM (`hb_pb`), bidder (`hb_bidder`), and optional ad ID, nurl, and burl.
+/// CPM (`hb_pb`), bidder (`hb_bidder`), and optional ad ID, deal ID, nurl, and burl.
 #[cfg(test)]
 pub(crate) fn build_bid_map(
     winning_bids: &std::collections::HashMap<String, Bid>,
@@ -5118,15 +5193,7 @@ pub(crate) fn build_bid_map_with_auction_id(
                 // OpenRTB bid ID. The latter is the last resort: it is unique per
                 // bid instance rather than a creative, but GAM echoes it verbatim
                 // so the render bridge can find the exact winning bid.
-                let renderer_bid_id = bid.renderer.as_ref().and_then(|renderer| {
-                    renderer
-                        .as_aps()
-                        .map(|renderer| renderer.bid_id.as_str())
-                });
-                let hb_adid = non_empty(bid.cache_id.as_deref())
-                    .or_else(|| renderer_bid_id.and_then(|id| non_empty(Some(id))))
-                    .or_else(|| non_empty(bid.ad_id.as_deref()))
-                    .or_else(|| non_empty(bid.bid_id.as_deref()));
+                let hb_adid = bid.targeting_ad_id();
                 if let Some(id) = hb_adid {
                     // GAM drops an over-long targeting value, so the creative
                     // echoes nothing and the bridge's equality check never
@@ -5145,6 +5212,14 @@ pub(crate) fn build_bid_map_with_auction_id(
                         serde_json::Value::String(id.to_string()),
                     );
                 }
+                // hb_deal: lets GAM line items target PMP and programmatic
+                // guaranteed demand by deal ID.
+                if let Some(deal_id) = bid.deal_id.as_deref().filter(|id| !id.is_empty()) {
+                    obj.insert(
+                        "hb_deal".to_string(),
+                        serde_json::Value::String(deal_id.to_string()),
+                    );
+                }
 
                 // Win/billing notification URLs, fired verbatim by the bridge.
                 // Per OpenRTB these are the canonical carriers of
@@ -5238,8 +5313,8 @@ pub(crate) fn build_bid_map_with_auction_id(
                     // to a non-cache identifier (`adid`, then the bid id).
                     // Emitting the coordinates without a cache UUID would point
                     // the Universal Creative at `?uuid=<non-cache-id>` — a
-                    // guaranteed cache miss. The gate matches the `non_empty`
-                    // chain used for hb_adid above so a blank `cacheId` cannot
+                    // guaranteed cache miss. The gate matches the empty-as-absent
+                    // chain in `Bid::targeting_ad_id` so a blank `cacheId` cannot
                     // pass here while losing the hb_adid precedence.
                     if non_empty(bid.cache_id.as_deref()).is_some() {
                         if let Some(ref host) = bid.cache_host {
@@ -5443,13 +5518,41 @@ pub(crate) fn build_slot_json(
         .iter()
         .map(|(k, v)| (k.clone(), serde_json::Value::String(v.clone())))
         .collect();
-    Some(serde_json::json!({
+    let mut slot_json = serde_json::json!({
         "id": slot.id,
         "gam_unit_path": gam_path,
         "div_id": div_id,
         "formats": formats,
         "targeting": targeting,
-    }))
+    });
+    // Only emitted when configured, so slots without a lazy-load or refresh
+    // policy keep the exact wire shape older tsjs bundles expect.
+    if slot.lazy_load {
+        slot_json["lazy_load"] = serde_json::Value::Bool(true);
+    }
+    if let Some(refresh) = &slot.refresh {
+        slot_json["refresh"] = serde_json::json!({
+            "interval_secs": refresh.interval_secs,
+            "max_refreshes": refresh.max_refreshes,
+            "require_viewable": refresh.require_viewable,
+        });
+    }
+    Some(slot_json)
+}
+
+/// Slots that take part in a document-level auction.
+///
+/// [`lazy_load`](crate::creative_opportunities::CreativeOpportunitySlot::lazy_load)
+/// slots keep their slot definitions in the page but are auctioned later,
+/// through [`handle_slot_auction`], once they approach the viewport.
+fn document_auction_slots(
+    matched_slots: &[crate::creative_opportunities::CreativeOpportunitySlot],
+) -> Vec<crate::creative_opportunities::CreativeOpportunitySlot> {
+    matched_slots
+        .iter()
+        .filter(|slot| !slot.lazy_load)
+        .cloned()
+        .collect()
 }
 
 /// Match creative-opportunity slots and omit dynamic GAM paths that cannot be
@@ -6348,15 +6451,13 @@ pub async fn handle_page_bids(
 
     let matched_slots = match_renderable_slots(auction.slots, co_config, &path_param);
 
-    let request_info = crate::http_util::RequestInfo::from_request(&req, services.client_info());
-    let ec_id = ec_context.ec_value().filter(|_| ec_context.ec_allowed());
     let consent_context = ec_context.consent();
-    let geo = ec_context.geo_info().cloned();
     let cookie_jar = handle_request_cookies(&req)?;
 
     // Same fail-closed jurisdiction-aware gate the publisher navigation path
     // uses — relies on the adapter's geo-aware EC context.
     let consent_allows_auction = consent_allows_server_side_auction(consent_context);
+    record_consent_decision(services.metrics(), "page_bids", consent_allows_auction);
 
     // Same bot / prefetch guards the publisher path uses — without them this
     // endpoint would fire real SSP auctions on Sec-Purpose=prefetch warm-up
@@ -6390,130 +6491,37 @@ pub async fn handle_page_bids(
     // skip the live auction, matching the existing bot/prefetch behaviour.
     let ad_stack_enabled = auction_enabled && consent_allows_auction;
 
-    let (winning_bids, prebuilt_bid_map) = if matched_slots.is_empty() {
-        (std::collections::HashMap::new(), None)
+    let skip_reason = if !auction_enabled {
+        Some("auction_disabled")
+    } else if !consent_allows_auction {
+        Some("consent_denied")
+    } else if is_bot {
+        Some("bot")
+    } else if is_prefetch {
+        Some("prefetch")
     } else {
-        // Same publisher identity as the outbound bid request — see the
-        // matching note on the initial-navigation observation above.
-        let observation = AuctionObservationContext::from_parts(
-            AuctionSource::SpaNavigation,
-            &settings.publisher.domain,
-            &path_param,
-            matched_slots.len(),
-            ec_context,
-        );
-        if ad_stack_enabled && !is_bot && !is_prefetch {
-            let slots_ctx = MatchedSlotsContext {
-                matched_slots: &matched_slots,
-                request_path: &path_param,
-            };
-            let mut auction_request = build_auction_request(
-                &slots_ctx,
-                ec_id,
-                consent_context,
-                &request_info,
-                &settings.publisher.domain,
-                req.headers()
-                    .get("user-agent")
-                    .and_then(|v| v.to_str().ok()),
-            );
-            apply_auction_eids_and_device(
-                &mut auction_request,
-                &AuctionEidTargeting {
-                    cookie_jar: cookie_jar.as_ref(),
-                    ec_id,
-                    kv,
-                    partner_registry: auction.registry,
-                    ec_context,
-                    services,
-                    geo: geo.as_ref(),
-                    path_label: "Page-bids",
-                },
-            );
-            let timeout_ms = co_config
-                .auction_timeout_ms
-                .unwrap_or(settings.auction.timeout_ms);
-            let auction_context = AuctionContext {
-                settings,
-                request: &req,
-                timeout_ms,
-                provider_responses: None,
-                services,
-            };
-            match auction
-                .orchestrator
-                .run_auction(&auction_request, &auction_context)
-                .await
-            {
-                Ok(result) => {
-                    let winning_bids = result.winning_bids.clone();
-                    let auction_id = diagnostics_auction_id(settings);
-                    let bid_map = build_bid_map_with_auction_id(
-                        &winning_bids,
-                        co_config.price_granularity,
-                        settings,
-                        &page_bids_request_origin,
-                        settings.debug.inject_adm_for_testing,
-                        auction_id.as_deref(),
-                    );
-                    let delivered_winner_slots = bid_map.keys().cloned().collect();
-                    emit_auction_events_best_effort_lazy(services, || {
-                        build_auction_events(
-                            observation,
-                            AuctionTerminalOutcome::Completed {
-                                request: &auction_request,
-                                result: &result,
-                                delivered_winner_slots: Some(&delivered_winner_slots),
-                            },
-                        )
-                    })
-                    .await;
-                    (winning_bids, Some(bid_map))
-                }
-                Err(e) => {
-                    log::warn!("page-bids auction failed: {e:?}");
-                    let elapsed_ms = observation.elapsed_ms();
-                    emit_auction_events_best_effort_lazy(services, || {
-                        build_auction_events(
-                            observation,
-                            AuctionTerminalOutcome::ExecutionFailed {
-                                request: Some(&auction_request),
-                                provider_responses: &[],
-                                reason: "execution_failed",
-                                elapsed_ms,
-                            },
-                        )
-                    })
-                    .await;
-                    (std::collections::HashMap::new(), None)
-                }
-            }
-        } else {
-            let skip_reason = if !auction_enabled {
-                "auction_disabled"
-            } else if !consent_allows_auction {
-                "consent_denied"
-            } else if is_bot {
-                "bot"
-            } else if is_prefetch {
-                "prefetch"
-            } else {
-                "not_ad_stack_eligible"
-            };
-            let elapsed_ms = observation.elapsed_ms();
-            emit_auction_events_best_effort_lazy(services, || {
-                build_auction_events(
-                    observation,
-                    AuctionTerminalOutcome::Skipped {
-                        reason: skip_reason,
-                        elapsed_ms,
-                    },
-                )
-            })
-            .await;
-            (std::collections::HashMap::new(), None)
-        }
+        None
     };
+    let auction_slots = document_auction_slots(&matched_slots);
+    let (winning_bids, prebuilt_bid_map) = run_targeted_slot_auction(
+        &TargetedSlotAuction {
+            settings,
+            services,
+            kv,
+            auction: &auction,
+            ec_context,
+            req: &req,
+            cookie_jar: cookie_jar.as_ref(),
+            co_config,
+            request_path: &path_param,
+            request_origin: &page_bids_request_origin,
+            source: AuctionSource::SpaNavigation,
+            path_label: "Page-bids",
+            skip_reason,
+        },
+        &auction_slots,
+    )
+    .await;
 
     let bid_map = prebuilt_bid_map.unwrap_or_else(|| {
         build_bid_map_with_auction_id(
@@ -6557,6 +6565,175 @@ pub async fn handle_page_bids(
     Ok(response)
 }
 
+/// Per-request inputs for [`run_targeted_slot_auction`], bundled to keep the
+/// helper within the project's 7-argument cap.
+struct TargetedSlotAuction<'a> {
+    settings: &'a Settings,
+    services: &'a RuntimeServices,
+    kv: Option<&'a KvIdentityGraph>,
+    auction: &'a AuctionDispatch<'a>,
+    ec_context: &'a EcContext,
+    req: &'a Request<EdgeBody>,
+    cookie_jar: Option<&'a CookieJar>,
+    co_config: &'a crate::creative_opportunities::CreativeOpportunitiesConfig,
+    request_path: &'a str,
+    /// Trusted request origin for absolute inline creative URLs.
+    request_origin: &'a str,
+    source: AuctionSource,
+    /// Prefix for log lines (e.g. `"Page-bids"`).
+    path_label: &'a str,
+    /// Telemetry skip reason when the auction must not run, `None` to run it.
+    skip_reason: Option<&'static str>,
+}
+
+/// Runs a server-side auction for an explicit slot set outside the HTML
+/// navigation path and returns the winning bids plus their rendered bid map.
+///
+/// Shared by [`handle_page_bids`] and [`handle_slot_auction`] so both
+/// endpoints build the bid request, gate EIDs by consent and emit auction
+/// telemetry identically. Returns an empty result without telemetry when
+/// `slots` is empty, and emits a `Skipped` outcome when
+/// [`skip_reason`](TargetedSlotAuction::skip_reason) is set. Auction failures
+/// are logged and reported as no bids rather than errors.
+async fn run_targeted_slot_auction(
+    target: &TargetedSlotAuction<'_>,
+    slots: &[crate::creative_opportunities::CreativeOpportunitySlot],
+) -> (
+    std::collections::HashMap<String, Bid>,
+    Option<serde_json::Map<String, serde_json::Value>>,
+) {
+    if slots.is_empty() {
+        return (std::collections::HashMap::new(), None);
+    }
+
+    let TargetedSlotAuction {
+        settings,
+        services,
+        ec_context,
+        req,
+        co_config,
+        request_path,
+        ..
+    } = *target;
+
+    // Same publisher identity as the outbound bid request — see the matching
+    // note on the initial-navigation observation in `handle_publisher_request`.
+    let observation = AuctionObservationContext::from_parts(
+        target.source,
+        &settings.publisher.domain,
+        request_path,
+        slots.len(),
+        ec_context,
+    )
+    .with_experiment_arms(&settings.experiment_arms);
+
+    if let Some(skip_reason) = target.skip_reason {
+        let elapsed_ms = observation.elapsed_ms();
+        emit_auction_events_best_effort_lazy(services, || {
+            build_auction_events(
+                observation,
+                AuctionTerminalOutcome::Skipped {
+                    reason: skip_reason,
+                    elapsed_ms,
+                },
+            )
+        })
+        .await;
+        return (std::collections::HashMap::new(), None);
+    }
+
+    let request_info = crate::http_util::RequestInfo::from_request(req, services.client_info());
+    let ec_id = ec_context.ec_value().filter(|_| ec_context.ec_allowed());
+    let geo = ec_context.geo_info().cloned();
+    let slots_ctx = MatchedSlotsContext {
+        matched_slots: slots,
+        request_path,
+    };
+    let mut auction_request = build_auction_request(
+        &slots_ctx,
+        ec_id,
+        ec_context.consent(),
+        &request_info,
+        &settings.publisher.domain,
+        req.headers()
+            .get("user-agent")
+            .and_then(|v| v.to_str().ok()),
+    );
+    apply_auction_eids_and_device(
+        &mut auction_request,
+        &AuctionEidTargeting {
+            cookie_jar: target.cookie_jar,
+            ec_id,
+            kv: target.kv,
+            partner_registry: target.auction.registry,
+            ec_context,
+            services,
+            geo: geo.as_ref(),
+            path_label: target.path_label,
+        },
+    );
+    let timeout_ms = co_config
+        .auction_timeout_ms
+        .unwrap_or(settings.auction.timeout_ms);
+    let auction_context = AuctionContext {
+        settings,
+        request: req,
+        timeout_ms,
+        provider_responses: None,
+        services,
+    };
+    match target
+        .auction
+        .orchestrator
+        .run_auction(&auction_request, &auction_context)
+        .await
+    {
+        Ok(result) => {
+            let winning_bids = result.winning_bids.clone();
+            let auction_id = diagnostics_auction_id(settings);
+            let mut bid_map = build_bid_map_with_auction_id(
+                &winning_bids,
+                co_config.price_granularity,
+                settings,
+                target.request_origin,
+                settings.debug.inject_adm_for_testing,
+                auction_id.as_deref(),
+            );
+            attach_event_tokens(&mut bid_map, &winning_bids, settings, &observation);
+            let delivered_winner_slots = bid_map.keys().cloned().collect();
+            emit_auction_events_best_effort_lazy(services, || {
+                build_auction_events(
+                    observation,
+                    AuctionTerminalOutcome::Completed {
+                        request: &auction_request,
+                        result: &result,
+                        delivered_winner_slots: Some(&delivered_winner_slots),
+                    },
+                )
+            })
+            .await;
+            (winning_bids, Some(bid_map))
+        }
+        Err(e) => {
+            log::warn!("{} auction failed: {e:?}", target.path_label);
+            let elapsed_ms = observation.elapsed_ms();
+            emit_auction_events_best_effort_lazy(services, || {
+                build_auction_events(
+                    observation,
+                    AuctionTerminalOutcome::ExecutionFailed {
+                        request: Some(&auction_request),
+                        provider_responses: &[],
+                        reason: "execution_failed",
+                        elapsed_ms,
+                    },
+                )
+            })
+            .await;
+            (std::collections::HashMap::new(), None)
+        }
+    }
+}
+
 /// Marks a response served through [`PAGE_BIDS_LEGACY_PATH`] as deprecated.
 ///
 /// Attaches the RFC 9745 `deprecation` link relation pointing at the removal
@@ -6581,6 +6758,276 @@ fn mark_deprecated_alias(response: &mut Response<EdgeBody>, is_legacy_alias: boo
     );
 }
 
+/// Canonical URL path of the slot-level auction endpoint.
+///
+/// tsjs posts here when a
+/// [`lazy_load`](crate::creative_opportunities::CreativeOpportunitySlot::lazy_load)
+/// slot approaches the viewport or a slot's
+/// [`refresh`](crate::creative_opportunities::CreativeOpportunitySlot::refresh)
+/// timer fires. Adapters register this path for `POST` and deny `OPTIONS` with
+/// [`page_bids_preflight_denied`].
+pub const SLOT_AUCTION_PATH: &str = "/_ts/api/v1/slot-auction";
+
+/// Maximum accepted slot-auction body size.
+const MAX_SLOT_AUCTION_BODY_BYTES: usize = 8 * 1024;
+
+/// Maximum number of slots one slot-auction request may name. tsjs batches
+/// slots that become due together, which is a handful at most.
+const MAX_SLOT_AUCTION_SLOTS: usize = 16;
+
+/// Why tsjs requested a slot auction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum SlotAuctionReason {
+    /// A lazy-load slot approached the viewport.
+    Lazy,
+    /// The slot's refresh timer fired.
+    Refresh,
+}
+
+/// `POST /_ts/api/v1/slot-auction` request body.
+#[derive(Debug, serde::Deserialize)]
+struct SlotAuctionRequest {
+    /// `location.pathname` of the page the slots live on.
+    path: String,
+    slots: Vec<SlotAuctionSlot>,
+}
+
+/// One slot named in a [`SlotAuctionRequest`].
+#[derive(Debug, serde::Deserialize)]
+struct SlotAuctionSlot {
+    id: String,
+    reason: SlotAuctionReason,
+    /// 1-based index of the refresh being requested. Ignored for lazy loads.
+    #[serde(default)]
+    refresh_count: u32,
+    /// Milliseconds since the slot's previous auction. Ignored for lazy loads.
+    #[serde(default)]
+    elapsed_ms: u64,
+    /// Whether the creative currently in the slot reached viewability.
+    #[serde(default)]
+    viewable: bool,
+}
+
+/// Same-origin gate for `/_ts/api/v1/slot-auction`.
+///
+/// Mirrors [`page_bids_request_allowed`]: the endpoint dispatches real SSP
+/// auctions, so it admits `Sec-Fetch-Site: same-origin`, or — for clients
+/// predating Fetch Metadata — the non-simple `X-TSJS-Slot-Auction` header that
+/// a cross-origin caller cannot attach without a preflight this endpoint never
+/// grants.
+fn slot_auction_request_allowed(req: &Request<EdgeBody>) -> bool {
+    match req
+        .headers()
+        .get("sec-fetch-site")
+        .and_then(|v| v.to_str().ok())
+    {
+        Some(site) => site == "same-origin",
+        None => req.headers().contains_key("x-tsjs-slot-auction"),
+    }
+}
+
+/// Splits the requested slots into the ones admitted to the auction and a
+/// `slot id → reason` map of the ones refused.
+///
+/// A slot is refused when it does not match `path`, is lazily
+/// requested without being a [`lazy_load`] slot, or is refreshed in breach of
+/// its [`SlotRefresh`](crate::creative_opportunities::SlotRefresh) policy. The
+/// limits are re-checked here rather than trusted from tsjs so a misbehaving
+/// bundle cannot refresh faster or more often than configured.
+///
+/// [`lazy_load`]: crate::creative_opportunities::CreativeOpportunitySlot::lazy_load
+fn admit_slot_auction_slots(
+    matched_slots: &[crate::creative_opportunities::CreativeOpportunitySlot],
+    requested: &[SlotAuctionSlot],
+) -> (
+    Vec<crate::creative_opportunities::CreativeOpportunitySlot>,
+    serde_json::Map<String, serde_json::Value>,
+) {
+    let mut admitted: Vec<crate::creative_opportunities::CreativeOpportunitySlot> = Vec::new();
+    let mut rejected = serde_json::Map::new();
+    for request in requested {
+        // A slot named twice keeps the verdict of its first entry rather than
+        // being reported as both auctioned and refused.
+        if admitted.iter().any(|slot| slot.id == request.id) || rejected.contains_key(&request.id) {
+            log::debug!("slot-auction: ignoring duplicate slot '{}'", request.id);
+            continue;
+        }
+        let verdict = if let Some(slot) = matched_slots.iter().find(|slot| slot.id == request.id) {
+            match request.reason {
+                SlotAuctionReason::Lazy if slot.lazy_load => Ok(slot),
+                SlotAuctionReason::Lazy => Err("not_lazy"),
+                SlotAuctionReason::Refresh => match &slot.refresh {
+                    Some(refresh) => refresh
+                        .admits(request.refresh_count, request.elapsed_ms, request.viewable)
+                        .map(|()| slot),
+                    None => Err("refresh_disabled"),
+                },
+            }
+        } else {
+            Err("unknown_slot")
+        };
+        match verdict {
+            Ok(slot) => admitted.push(slot.clone()),
+            Err(reason) => {
+                log::debug!("slot-auction: refusing slot '{}' ({reason})", request.id);
+                rejected.insert(
+                    request.id.clone(),
+                    serde_json::Value::String(reason.to_string()),
+                );
+            }
+        }
+    }
+    (admitted, rejected)
+}
+
+/// Handle `POST /_ts/api/v1/slot-auction` — server-side auction for individual
+/// slots after the page has loaded.
+///
+/// tsjs calls this when a lazy-load slot nears the viewport or a refresh timer
+/// fires. The request reuses the document path's building blocks: the same
+/// consent, bot and prefetch gates as [`handle_page_bids`], the same bid
+/// request construction and EID gating, and the same orchestrator (so the bid
+/// cache, ad-quality rules and creative scanning all apply). The response has
+/// the page-bids shape plus a `rejected` map naming the slots refused by
+/// [`admit_slot_auction_slots`] and why.
+///
+/// # Errors
+///
+/// Returns [`TrustedServerError::BadRequest`] when the body is oversized,
+/// malformed or names too many slots, and [`TrustedServerError`] if cookie
+/// parsing or response serialization fails.
+pub async fn handle_slot_auction(
+    settings: &Settings,
+    services: &RuntimeServices,
+    kv: Option<&KvIdentityGraph>,
+    auction: AuctionDispatch<'_>,
+    ec_context: &EcContext,
+    req: Request<EdgeBody>,
+) -> Result<Response<EdgeBody>, Report<TrustedServerError>> {
+    if !slot_auction_request_allowed(&req) {
+        log::debug!(
+            "slot-auction: rejecting request (sec-fetch-site={:?}, tsjs header present={})",
+            req.headers()
+                .get("sec-fetch-site")
+                .and_then(|v| v.to_str().ok()),
+            req.headers().contains_key("x-tsjs-slot-auction")
+        );
+        return Ok(page_bids_preflight_denied());
+    }
+
+    let Some(co_config) = &settings.creative_opportunities else {
+        let mut response = Response::new(EdgeBody::from("Creative opportunities not configured"));
+        *response.status_mut() = StatusCode::NOT_FOUND;
+        return Ok(response);
+    };
+
+    let request_info = RequestInfo::from_request(&req, services.client_info());
+    let slot_auction_request_origin = request_origin(&request_info.scheme, &request_info.host);
+    let cookie_jar = handle_request_cookies(&req)?;
+    let consent_allows_auction = consent_allows_server_side_auction(ec_context.consent());
+    record_consent_decision(services.metrics(), "slot_auction", consent_allows_auction);
+    let is_prefetch = is_prefetch_request(&req);
+    let is_bot = is_bot_user_agent(&req);
+
+    // The body is consumed below, but the auction still needs the request
+    // headers (User-Agent, cookies, client hints), so keep the head.
+    let (parts, body) = req.into_parts();
+    let body = body
+        .into_bytes_bounded(MAX_SLOT_AUCTION_BODY_BYTES)
+        .await
+        .change_context(TrustedServerError::BadRequest {
+            message: format!("slot-auction body exceeds {MAX_SLOT_AUCTION_BODY_BYTES} bytes"),
+        })?;
+    let slot_request: SlotAuctionRequest =
+        serde_json::from_slice(&body).change_context(TrustedServerError::BadRequest {
+            message: "failed to parse slot-auction body".to_owned(),
+        })?;
+    if slot_request.slots.len() > MAX_SLOT_AUCTION_SLOTS {
+        return Err(Report::new(TrustedServerError::BadRequest {
+            message: format!("slot-auction request names more than {MAX_SLOT_AUCTION_SLOTS} slots"),
+        }));
+    }
+    let req = Request::from_parts(parts, EdgeBody::empty());
+
+    let path = normalize_page_bids_path(&slot_request.path);
+    let matched_slots = match_renderable_slots(auction.slots, co_config, &path);
+    let (admitted_slots, rejected) = admit_slot_auction_slots(&matched_slots, &slot_request.slots);
+
+    // Kill switch and consent denial return no slots, exactly like page-bids,
+    // so tsjs leaves the placements alone instead of refreshing them without
+    // server-side demand.
+    let auction_enabled = auction.orchestrator.is_enabled();
+    let ad_stack_enabled = auction_enabled && consent_allows_auction;
+    let skip_reason = if !auction_enabled {
+        Some("auction_disabled")
+    } else if !consent_allows_auction {
+        Some("consent_denied")
+    } else if is_bot {
+        Some("bot")
+    } else if is_prefetch {
+        Some("prefetch")
+    } else {
+        None
+    };
+    let (winning_bids, prebuilt_bid_map) = run_targeted_slot_auction(
+        &TargetedSlotAuction {
+            settings,
+            services,
+            kv,
+            auction: &auction,
+            ec_context,
+            req: &req,
+            cookie_jar: cookie_jar.as_ref(),
+            co_config,
+            request_path: &path,
+            request_origin: &slot_auction_request_origin,
+            source: AuctionSource::SlotAuction,
+            path_label: "Slot-auction",
+            skip_reason,
+        },
+        &admitted_slots,
+    )
+    .await;
+
+    let bid_map = prebuilt_bid_map.unwrap_or_else(|| {
+        build_bid_map_with_auction_id(
+            &winning_bids,
+            co_config.price_granularity,
+            settings,
+            &slot_auction_request_origin,
+            settings.debug.inject_adm_for_testing,
+            None,
+        )
+    });
+    let slots_json: Vec<serde_json::Value> = if ad_stack_enabled {
+        let section = co_config.section_for_path(&path);
+        admitted_slots
+            .iter()
+            .filter_map(|slot| build_slot_json(slot, co_config, &section))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let body = serde_json::json!({
+        "slots": slots_json,
+        "bids": bid_map,
+        "rejected": rejected,
+    });
+    let body = serde_json::to_string(&body).change_context(TrustedServerError::Proxy {
+        message: "Failed to serialize slot-auction response".to_string(),
+    })?;
+
+    let mut response = Response::new(EdgeBody::from(body));
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/json"),
+    );
+    enforce_terminal_private_cache_privacy(&mut response);
+    Ok(response)
+}
+
 #[cfg(test)]
 mod tests {
     use std::future::Future as _;
@@ -6622,6 +7069,9 @@ mod tests {
             bid_id: None,
             ad_id: None,
             creative_id: None,
+            deal_id: None,
+            categories: Vec::new(),
+            attributes: Vec::new(),
             renderer: None,
             cache_id: None,
             cache_host: None,
@@ -7496,6 +7946,7 @@ mod tests {
                 floor_price: None,
                 targeting: Default::default(),
                 bidders: Default::default(),
+                pmp: None,
             }],
             publisher: PublisherInfo {
                 domain: "test-publisher.com".to_string(),
@@ -7509,6 +7960,7 @@ mod tests {
             device: None,
             site: None,
             context: Default::default(),
+            ad_quality: Default::default(),
         }
     }
 
@@ -8609,6 +9061,9 @@ mod tests {
                 floor_price: None,
                 targeting: Default::default(),
                 providers: Default::default(),
+                pmp: None,
+                lazy_load: false,
+                refresh: None,
                 compiled_patterns: Vec::new(),
                 compiled_unit: None,
             }
@@ -8680,6 +9135,9 @@ mod tests {
                         burl: None,
                         bid_id: None,
                         creative_id: None,
+                        deal_id: None,
+                        categories: Vec::new(),
+                        attributes: Vec::new(),
                         renderer: None,
                         ad_id: Some("stub-creative-1".to_string()),
                         cache_id: None,
@@ -12250,6 +12708,9 @@ mod tests {
                 floor_price: None,
                 targeting: Default::default(),
                 providers: Default::default(),
+                pmp: None,
+                lazy_load: false,
+                refresh: None,
                 compiled_patterns: Vec::new(),
                 compiled_unit: None,
             }
@@ -12716,6 +13177,9 @@ mod tests {
                 floor_price: None,
                 targeting: Default::default(),
                 providers: Default::default(),
+                pmp: None,
+                lazy_load: false,
+                refresh: None,
                 compiled_patterns: Vec::new(),
                 compiled_unit: None,
             }
@@ -16776,9 +17240,9 @@ mod tests {
     #[cfg(test)]
     mod creative_opportunities_tests {
         use super::super::{
-            AdBidsState, MatchedSlotsContext, build_ad_slots_script, build_auction_request,
-            build_bid_map, build_bids_script, diagnostics_auction_id, html_escape_for_script,
-            write_bids_to_state,
+            AdBidsState, BidMapAuctionIds, MatchedSlotsContext, build_ad_slots_script,
+            build_auction_request, build_bid_map, build_bids_script, diagnostics_auction_id,
+            html_escape_for_script, write_bids_to_state,
         };
         use crate::auction::types::{ApsRendererV1, ApsTagType, Bid, BidRenderer, MediaType};
         use crate::consent::ConsentContext;
@@ -16828,6 +17292,9 @@ mod tests {
                     .into_iter()
                     .collect(),
                 providers: Default::default(),
+                pmp: None,
+                lazy_load: false,
+                refresh: None,
                 compiled_patterns: Vec::new(),
                 compiled_unit: None,
             }
@@ -16854,6 +17321,9 @@ mod tests {
                 burl: Some(burl.to_string()),
                 bid_id: None,
                 creative_id: None,
+                deal_id: None,
+                categories: Vec::new(),
+                attributes: Vec::new(),
                 renderer: None,
                 ad_id: Some(ad_id.to_string()),
                 cache_id: None,
@@ -17067,6 +17537,60 @@ mod tests {
             assert_ne!(first, second, "each auction should mint its own token");
         }
 
+        #[test]
+        fn write_bids_to_state_attaches_event_tokens_for_observed_auctions() {
+            let mut settings = test_settings();
+            settings.events.enabled = true;
+            let mut winning_bids = HashMap::new();
+            winning_bids.insert(
+                "atf_sidebar_ad".to_string(),
+                make_bid(
+                    "atf_sidebar_ad",
+                    1.50,
+                    "example_bidder",
+                    "abc123",
+                    "https://example.com/win",
+                    "https://example.com/bill",
+                ),
+            );
+            let observation = crate::auction::telemetry::AuctionObservationContext::from_parts(
+                crate::auction::telemetry::AuctionSource::InitialNavigation,
+                "test-publisher.example",
+                "/",
+                1,
+                &crate::ec::EcContext::new_for_test(None, ConsentContext::default()),
+            );
+
+            let state = AdBidsState::default();
+            write_bids_to_state(
+                &winning_bids,
+                PriceGranularity::Dense,
+                &state,
+                &settings,
+                "",
+                false,
+                BidMapAuctionIds {
+                    diagnostics: None,
+                    observation: Some(&observation),
+                },
+            );
+
+            let bids = state.bids();
+            let slot = bids["atf_sidebar_ad"]
+                .as_object()
+                .expect("should write the winning slot");
+            assert!(
+                slot["ts_event"]
+                    .as_str()
+                    .is_some_and(|token| !token.is_empty()),
+                "should attach an event token to the winning slot"
+            );
+            assert!(
+                !slot.contains_key("nurl") && !slot.contains_key("burl"),
+                "should withhold notification URLs fired server-side"
+            );
+        }
+
         #[test]
         fn initial_document_bids_script_includes_auction_id_only_for_winning_bids() {
             let slot = make_slot();
@@ -17109,7 +17633,10 @@ mod tests {
                 &test_settings(),
                 "",
                 false,
-                Some(&auction_request.id),
+                BidMapAuctionIds {
+                    diagnostics: Some(&auction_request.id),
+                    observation: None,
+                },
             );
             let script = state
                 .script_cell()
@@ -17144,7 +17671,10 @@ mod tests {
                 &test_settings(),
                 "",
                 false,
-                Some(&auction_request.id),
+                BidMapAuctionIds {
+                    diagnostics: Some(&auction_request.id),
+                    observation: None,
+                },
             );
             let empty_script = state
                 .script_cell()
@@ -17449,6 +17979,9 @@ mod tests {
                 ad_id: Some("bid-impression-id".to_string()),
                 bid_id: Some("openrtb-bid-id".to_string()),
                 creative_id: None,
+                deal_id: None,
+                categories: Vec::new(),
+                attributes: Vec::new(),
                 // No typed renderer: these cases assert what happens when the
                 // supplied markup is the bid's only render source.
                 renderer: None,
@@ -17851,6 +18384,9 @@ mod tests {
                     burl: None,
                     bid_id: None,
                     creative_id: None,
+                    deal_id: None,
+                    categories: Vec::new(),
+                    attributes: Vec::new(),
                     renderer: None,
                     ad_id: Some("bid-impression-id".to_string()),
                     cache_id: Some("f47447a0-b759-4f2f-9887-af458b79b570".to_string()),
@@ -17907,6 +18443,9 @@ mod tests {
                     bid_id: None,
                     ad_id: Some("aps-bid-token".to_string()),
                     creative_id: None,
+                    deal_id: None,
+                    categories: Vec::new(),
+                    attributes: Vec::new(),
                     renderer: None,
                     cache_id: None,
                     cache_host: None,
@@ -17941,6 +18480,34 @@ mod tests {
             );
         }
 
+        #[test]
+        fn bid_map_emits_hb_deal_only_for_deal_bids() {
+            let mut deal_bid = make_bid("atf_sidebar_ad", 4.00, "kargo", "ad-1", "", "");
+            deal_bid.deal_id = Some("pg-deal-1".to_string());
+            let open_bid = make_bid("below_content_ad", 1.00, "kargo", "ad-2", "", "");
+            let winning_bids = HashMap::from([
+                ("atf_sidebar_ad".to_string(), deal_bid),
+                ("below_content_ad".to_string(), open_bid),
+            ]);
+
+            let map = build_bid_map(
+                &winning_bids,
+                PriceGranularity::Dense,
+                &test_settings(),
+                "",
+                false,
+            );
+
+            assert_eq!(
+                map["atf_sidebar_ad"]["hb_deal"], "pg-deal-1",
+                "should emit hb_deal for a deal bid"
+            );
+            assert!(
+                map["below_content_ad"].get("hb_deal").is_none(),
+                "should omit hb_deal for an open-auction bid"
+            );
+        }
+
         #[test]
         fn bid_map_exposes_aps_renderer_and_selected_bid_id() {
             // Sanitization is opt-in, so enable it: the script-only creative
@@ -18018,6 +18585,9 @@ mod tests {
                     burl: None,
                     bid_id: None,
                     creative_id: None,
+                    deal_id: None,
+                    categories: Vec::new(),
+                    attributes: Vec::new(),
                     renderer: None,
                     ad_id: None,
                     cache_id: None,
@@ -18062,6 +18632,9 @@ mod tests {
                     burl: None,
                     bid_id: None,
                     creative_id: None,
+                    deal_id: None,
+                    categories: Vec::new(),
+                    attributes: Vec::new(),
                     renderer: None,
                     ad_id: None,
                     cache_id: None,
@@ -18439,6 +19012,9 @@ mod tests {
                         burl: None,
                         bid_id: None,
                         creative_id: None,
+                        deal_id: None,
+                        categories: Vec::new(),
+                        attributes: Vec::new(),
                         renderer: None,
                         ad_id: Some("winner-123".to_string()),
                         cache_id: None,
@@ -18547,6 +19123,9 @@ mod tests {
                 floor_price: Some(0.50),
                 targeting: Default::default(),
                 providers: Default::default(),
+                pmp: None,
+                lazy_load: false,
+                refresh: None,
                 compiled_patterns: Vec::new(),
                 compiled_unit: None,
             }]
@@ -19326,6 +19905,234 @@ mod tests {
                 "consent denial must produce no bids"
             );
         }
+
+        /// Builds a same-origin slot-auction request with `body` as JSON.
+        fn make_slot_auction_request(body: &serde_json::Value) -> Request<EdgeBody> {
+            let mut req = Request::builder()
+                .method(Method::POST)
+                .uri(format!("https://test-publisher.com{SLOT_AUCTION_PATH}"))
+                .body(EdgeBody::from(body.to_string()))
+                .expect("should build slot-auction request");
+            set_test_header(&mut req, "sec-fetch-site", "same-origin");
+            req
+        }
+
+        async fn run_slot_auction(
+            settings: &Settings,
+            services: &RuntimeServices,
+            orchestrator: &AuctionOrchestrator,
+            slots: &[CreativeOpportunitySlot],
+            req: Request<EdgeBody>,
+        ) -> serde_json::Value {
+            let response = handle_slot_auction(
+                settings,
+                services,
+                None,
+                AuctionDispatch {
+                    orchestrator,
+                    slots,
+                    registry: None,
+                },
+                &consent_allowing_ec_context(),
+                req,
+            )
+            .await
+            .expect("should return slot-auction response");
+            assert_eq!(
+                response.status(),
+                StatusCode::OK,
+                "slot-auction should answer 200"
+            );
+            serde_json::from_slice(&response.into_body().into_bytes().unwrap_or_default())
+                .expect("should be json")
+        }
+
+        #[tokio::test]
+        async fn page_bids_leaves_lazy_slots_out_of_the_auction() {
+            let mut settings = settings_with_co();
+            settings.auction.providers = vec![AUCTION_ID_TEST_PROVIDER.to_string()];
+            let mut slots = article_slot();
+            slots[0].lazy_load = true;
+            let captured = Arc::new(Mutex::new(None));
+            let orchestrator = auction_id_test_orchestrator(&settings, Arc::clone(&captured), true);
+
+            let body = run_page_bids_consent_allowed(
+                &settings,
+                &orchestrator,
+                &slots,
+                make_page_bids_request("/2024/01/my-article/"),
+            )
+            .await;
+
+            assert!(
+                captured
+                    .lock()
+                    .expect("should lock captured request")
+                    .is_none(),
+                "lazy slots must not enter the document-level auction"
+            );
+            assert_eq!(
+                body["slots"][0]["lazy_load"],
+                serde_json::Value::Bool(true),
+                "lazy slots should still be defined so tsjs can observe them"
+            );
+        }
+
+        #[tokio::test]
+        async fn slot_auction_runs_lazy_slots_and_reports_refused_ones() {
+            let mut settings = settings_with_co();
+            settings.auction.providers = vec![AUCTION_ID_TEST_PROVIDER.to_string()];
+            let mut slots = article_slot();
+            slots[0].lazy_load = true;
+            let stub = Arc::new(StubHttpClient::new());
+            stub.push_response(200, b"winner".to_vec());
+            let services = build_services_with_http_client(
+                Arc::clone(&stub) as Arc<dyn crate::platform::PlatformHttpClient>
+            );
+            let captured = Arc::new(Mutex::new(None));
+            let orchestrator = auction_id_test_orchestrator(&settings, Arc::clone(&captured), true);
+
+            let body = run_slot_auction(
+                &settings,
+                &services,
+                &orchestrator,
+                &slots,
+                make_slot_auction_request(&serde_json::json!({
+                    "path": "/2024/01/my-article/",
+                    "slots": [
+                        { "id": "atf", "reason": "lazy" },
+                        { "id": "atf", "reason": "lazy" },
+                        { "id": "missing", "reason": "lazy" },
+                    ],
+                })),
+            )
+            .await;
+
+            let auction_request = captured
+                .lock()
+                .expect("should lock captured request")
+                .clone()
+                .expect("should run the slot auction");
+            assert_eq!(
+                auction_request
+                    .slots
+                    .iter()
+                    .map(|slot| slot.id.as_str())
+                    .collect::<Vec<_>>(),
+                vec!["atf"],
+                "only the admitted slot should be auctioned, once"
+            );
+            assert!(
+                body["bids"]["atf"].is_object(),
+                "should return the lazy slot's winning bid"
+            );
+            assert_eq!(body["slots"][0]["id"], "atf", "should define the slot");
+            assert_eq!(
+                body["rejected"],
+                serde_json::json!({ "missing": "unknown_slot" }),
+                "should report the unknown slot, and not reject the first `atf` as a duplicate"
+            );
+        }
+
+        #[tokio::test]
+        async fn slot_auction_enforces_the_slot_refresh_policy() {
+            let mut settings = settings_with_co();
+            settings.auction.providers = vec![AUCTION_ID_TEST_PROVIDER.to_string()];
+            let mut slots = article_slot();
+            slots[0].refresh = Some(crate::creative_opportunities::SlotRefresh {
+                interval_secs: 30,
+                max_refreshes: 2,
+                require_viewable: true,
+            });
+            let captured = Arc::new(Mutex::new(None));
+            let orchestrator = auction_id_test_orchestrator(&settings, Arc::clone(&captured), true);
+
+            for (attempt, expected) in [
+                (
+                    serde_json::json!({ "id": "atf", "reason": "refresh", "refresh_count": 1,
+                        "elapsed_ms": 10_000, "viewable": true }),
+                    "interval",
+                ),
+                (
+                    serde_json::json!({ "id": "atf", "reason": "refresh", "refresh_count": 3,
+                        "elapsed_ms": 60_000, "viewable": true }),
+                    "max_refreshes",
+                ),
+                (
+                    serde_json::json!({ "id": "atf", "reason": "refresh", "refresh_count": 1,
+                        "elapsed_ms": 60_000, "viewable": false }),
+                    "not_viewable",
+                ),
+                (
+                    serde_json::json!({ "id": "atf", "reason": "lazy" }),
+                    "not_lazy",
+                ),
+            ] {
+                let body = run_slot_auction(
+                    &settings,
+                    &noop_services(),
+                    &orchestrator,
+                    &slots,
+                    make_slot_auction_request(&serde_json::json!({
+                        "path": "/2024/01/my-article/",
+                        "slots": [attempt],
+                    })),
+                )
+                .await;
+
+                assert_eq!(
+                    body["rejected"]["atf"], expected,
+                    "should refuse the refresh with `{expected}`"
+                );
+                assert!(
+                    body["slots"]
+                        .as_array()
+                        .expect("slots should be array")
+                        .is_empty(),
+                    "a refused slot should not be redefined"
+                );
+            }
+            assert!(
+                captured
+                    .lock()
+                    .expect("should lock captured request")
+                    .is_none(),
+                "refused refreshes must not reach the bidders"
+            );
+        }
+
+        #[tokio::test]
+        async fn slot_auction_rejects_cross_site_requests() {
+            let settings = settings_with_co();
+            let orchestrator = AuctionOrchestrator::new(settings.auction.clone());
+            let slots = article_slot();
+            let mut req = make_slot_auction_request(&serde_json::json!({
+                "path": "/2024/01/my-article/",
+                "slots": [],
+            }));
+            set_test_header(&mut req, "sec-fetch-site", "cross-site");
+
+            let response = handle_slot_auction(
+                &settings,
+                &noop_services(),
+                None,
+                AuctionDispatch {
+                    orchestrator: &orchestrator,
+                    slots: &slots,
+                    registry: None,
+                },
+                &consent_allowing_ec_context(),
+                req,
+            )
+            .await
+            .expect("should return slot-auction response");
+
+            assert_eq!(
+                response.status(),
+                StatusCode::FORBIDDEN,
+                "cross-site slot auctions should be refused"
+            );
+        }
     }
 
     #[test]
@@ -19482,6 +20289,9 @@ mod tests {
                 floor_price: Some(0.50),
                 targeting: Default::default(),
                 providers: Default::default(),
+                pmp: None,
+                lazy_load: false,
+                refresh: None,
                 compiled_patterns: Vec::new(),
                 compiled_unit: None,
             }]
@@ -34,7 +34,9 @@ pub fn get_current_key_id(
 /// via [`crate::request_signing::rotation::KeyRotationManager`]. A non-base64
 /// value in the secret store indicates data corruption and is surfaced as an
 /// explicit error rather than silently falling back to a length heuristic.
-fn parse_ed25519_signing_key(key_bytes: &[u8]) -> Result<SigningKey, Report<TrustedServerError>> {
+pub(crate) fn parse_ed25519_signing_key(
+    key_bytes: &[u8],
+) -> Result<SigningKey, Report<TrustedServerError>> {
     let bytes = general_purpose::STANDARD.decode(key_bytes).map_err(|_| {
         Report::new(TrustedServerError::Configuration {
             message:
@@ -156,6 +158,27 @@ impl RequestSigner {
         })
     }
 
+    /// Creates a `RequestSigner` from a standard base64 Ed25519 private key,
+    /// the format [`KeyRotationManager`](crate::request_signing::KeyRotationManager)
+    /// writes to the secret store.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `kid` is empty or the key is not 32 bytes of base64.
+    pub fn from_base64_key(
+        kid: impl Into<String>,
+        key_b64: &str,
+    ) -> Result<Self, Report<TrustedServerError>> {
+        let kid = kid.into();
+        if kid.trim().is_empty() {
+            return Err(Report::new(TrustedServerError::Configuration {
+                message: "request signing key id must not be empty".into(),
+            }));
+        }
+        let key = parse_ed25519_signing_key(key_b64.trim().as_bytes())?;
+        Ok(Self { key, kid })
+    }
+
     /// Signs a payload using the Ed25519 signing key.
     ///
     /// # Errors
@@ -300,6 +323,28 @@ mod tests {
         assert!(verified, "should verify a valid signature");
     }
 
+    #[test]
+    fn from_base64_key_signs_verifiably_with_stored_key() {
+        let services = build_request_signing_services();
+        let key_bytes = services
+            .secret_store()
+            .get_bytes(&SIGNING_STORE_NAME, "test-kid")
+            .expect("should read signing key");
+        let key_b64 = String::from_utf8(key_bytes).expect("should store key as text");
+        let signer = RequestSigner::from_base64_key("test-kid", &format!("{key_b64}\n"))
+            .expect("should create signer from base64 key");
+
+        let signature = signer.sign(b"operator payload").expect("should sign");
+        let verified = verify_signature(b"operator payload", &signature, "test-kid", &services)
+            .expect("should attempt verification");
+
+        assert!(verified, "should verify a signature from the loaded key");
+        assert!(
+            RequestSigner::from_base64_key(" ", &key_b64).is_err(),
+            "should reject an empty kid"
+        );
+    }
+
     #[test]
     fn verify_returns_false_for_wrong_payload() {
         let services = build_request_signing_services();
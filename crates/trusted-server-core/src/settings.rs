@@ -13,11 +13,13 @@ use std::time::Duration;
 use url::Url;
 use validator::{Validate, ValidationError};
 
+use crate::access_log::AccessLogField;
 use crate::auction_config_types::AuctionConfig;
 use crate::cache_policy::{CachePolicy, CacheVisibility};
 use crate::consent_config::ConsentConfig;
 use crate::creative_opportunities::CreativeOpportunitiesConfig;
 use crate::error::TrustedServerError;
+use crate::experiments::{ExperimentArms, ExperimentConfig, validate_experiments};
 use crate::host_header::validate_host_header_override_value;
 use crate::platform::PlatformImageOptimizerRegion;
 use crate::redacted::Redacted;
@@ -1587,12 +1589,74 @@ pub struct Proxy {
     /// Path-prefix-based asset proxy routes evaluated before publisher fallback.
     #[serde(default, deserialize_with = "vec_from_seq_or_map")]
     pub asset_routes: Vec<ProxyAssetRoute>,
+    /// Maximum number of VAST wrapper hops followed through the first-party
+    /// proxy. A wrapper past this depth is answered with an empty (no-ad) VAST
+    /// document. Omitted from serialized config blobs while at the default so
+    /// older binaries keep loading them.
+    #[serde(
+        default = "default_vast_max_wrapper_depth",
+        skip_serializing_if = "is_default_vast_max_wrapper_depth"
+    )]
+    pub vast_max_wrapper_depth: u8,
+    /// KV-backed cache of rewritten creative bodies served by
+    /// `/first-party/proxy`. Omitted from serialized config blobs while at the
+    /// default.
+    #[serde(default, skip_serializing_if = "ProxyCreativeCache::is_default")]
+    pub creative_cache: ProxyCreativeCache,
+}
+
+/// Creative asset cache for `/first-party/proxy`.
+///
+/// Rewritten HTML, CSS, VAST and JavaScript bodies are stored in the KV store
+/// keyed by a hash of the upstream bytes and the rewrite-rules fingerprint, so
+/// byte-identical creatives are rewritten once. Entries live as long as the
+/// upstream `Cache-Control` allows, capped at [`Self::max_ttl_secs`].
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProxyCreativeCache {
+    /// Enable the cache. Disabled by default.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Name of the KV store holding cached creatives. Required when enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kv_store: Option<String>,
+    /// Upper bound on an entry's lifetime, whatever upstream allows.
+    #[serde(default = "default_creative_cache_max_ttl_secs")]
+    pub max_ttl_secs: u64,
+}
+
+fn default_creative_cache_max_ttl_secs() -> u64 {
+    86_400
+}
+
+impl Default for ProxyCreativeCache {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            kv_store: None,
+            max_ttl_secs: default_creative_cache_max_ttl_secs(),
+        }
+    }
+}
+
+impl ProxyCreativeCache {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
 }
 
 fn default_certificate_check() -> bool {
     true
 }
 
+fn default_vast_max_wrapper_depth() -> u8 {
+    5
+}
+
+fn is_default_vast_max_wrapper_depth(value: &u8) -> bool {
+    *value == default_vast_max_wrapper_depth()
+}
+
 fn is_admin_placeholder_password(password: &str) -> bool {
     Handler::is_placeholder_password(password)
         || matches!(
@@ -1607,6 +1671,8 @@ impl Default for Proxy {
             certificate_check: default_certificate_check(),
             allowed_domains: Vec::new(),
             asset_routes: Vec::new(),
+            vast_max_wrapper_depth: default_vast_max_wrapper_depth(),
+            creative_cache: ProxyCreativeCache::default(),
         }
     }
 }
@@ -1672,12 +1738,27 @@ impl Proxy {
     ///
     /// # Errors
     ///
-    /// Returns a configuration error if any configured asset route is invalid.
+    /// Returns a configuration error if any configured asset route is invalid,
+    /// or if the creative cache is enabled without a KV store.
     pub fn prepare_runtime(&self) -> Result<(), Report<TrustedServerError>> {
         for route in &self.asset_routes {
             route.prepare_runtime()?;
         }
 
+        if self.creative_cache.enabled
+            && self
+                .creative_cache
+                .kv_store
+                .as_deref()
+                .is_none_or(|name| name.trim().is_empty())
+        {
+            return Err(Report::new(TrustedServerError::Configuration {
+                message:
+                    "proxy.creative_cache.kv_store must be set when the creative cache is enabled"
+                        .to_string(),
+            }));
+        }
+
         Ok(())
     }
 
@@ -1719,21 +1800,50 @@ pub struct TinybirdSettings {
     /// Secret key containing the auction datasource APPEND token.
     #[serde(default = "default_tinybird_auction_token_secret")]
     pub auction_token_secret: String,
-    /// Reserved for future access-log telemetry.
+    /// Emit sampled access-log rows to [`Self::access_dataset`].
     ///
-    /// `true` is rejected until an access-log emitter is wired, so operators
-    /// cannot enable a setting that silently emits nothing.
+    /// Emitted by the core access-log middleware on every adapter. Requires
+    /// [`Self::enabled`].
     #[serde(default)]
     pub access_enabled: bool,
-    /// Future access-log Events API datasource name.
+    /// Access-log Events API datasource name.
     #[serde(default = "default_tinybird_access_dataset")]
     pub access_dataset: String,
-    /// Future Secret Store key containing the access-log datasource APPEND token.
+    /// Secret Store key containing the access-log datasource APPEND token.
     #[serde(default = "default_tinybird_access_token_secret")]
     pub access_token_secret: String,
-    /// Future fraction of requests to emit for optional access telemetry.
+    /// Fraction of requests to emit access-log rows for.
     #[serde(default)]
     pub access_sample_rate: f64,
+    /// Columns populated in each access-log row. Defaults to every field.
+    #[serde(default = "default_tinybird_access_fields")]
+    pub access_fields: Vec<AccessLogField>,
+    /// Flush per-request operational metrics to [`Self::metrics_dataset`].
+    ///
+    /// Only used by per-request adapters (Fastly); long-lived adapters serve
+    /// `/_ts/admin/metrics` instead. Requires [`Self::enabled`].
+    #[serde(default)]
+    pub metrics_enabled: bool,
+    /// Metrics Events API datasource name.
+    #[serde(default = "default_tinybird_metrics_dataset")]
+    pub metrics_dataset: String,
+    /// Secret Store key containing the metrics datasource APPEND token.
+    #[serde(default = "default_tinybird_metrics_token_secret")]
+    pub metrics_token_secret: String,
+    /// Flush the sampled third-party request log to [`Self::third_party_dataset`].
+    ///
+    /// Only used by per-request adapters (Fastly). Requires [`Self::enabled`].
+    #[serde(default)]
+    pub third_party_enabled: bool,
+    /// Third-party request log Events API datasource name.
+    #[serde(default = "default_tinybird_third_party_dataset")]
+    pub third_party_dataset: String,
+    /// Secret Store key containing the third-party datasource APPEND token.
+    #[serde(default = "default_tinybird_third_party_token_secret")]
+    pub third_party_token_secret: String,
+    /// Fraction of requests whose outbound third-party calls are recorded.
+    #[serde(default = "default_tinybird_third_party_sample_rate")]
+    pub third_party_sample_rate: f64,
     /// Defensive maximum NDJSON body size for one Events API request.
     #[serde(default = "default_tinybird_max_body_bytes")]
     pub max_body_bytes: usize,
@@ -1759,6 +1869,30 @@ fn default_tinybird_access_token_secret() -> String {
     "tinybird_access_append_token".to_owned()
 }
 
+fn default_tinybird_access_fields() -> Vec<AccessLogField> {
+    AccessLogField::ALL.to_vec()
+}
+
+fn default_tinybird_metrics_dataset() -> String {
+    "metrics_raw".to_owned()
+}
+
+fn default_tinybird_metrics_token_secret() -> String {
+    "tinybird_metrics_append_token".to_owned()
+}
+
+fn default_tinybird_third_party_dataset() -> String {
+    "third_party_requests_raw".to_owned()
+}
+
+fn default_tinybird_third_party_token_secret() -> String {
+    "tinybird_third_party_append_token".to_owned()
+}
+
+fn default_tinybird_third_party_sample_rate() -> f64 {
+    0.1
+}
+
 fn default_tinybird_max_body_bytes() -> usize {
     1024 * 1024
 }
@@ -1775,6 +1909,14 @@ impl Default for TinybirdSettings {
             access_dataset: default_tinybird_access_dataset(),
             access_token_secret: default_tinybird_access_token_secret(),
             access_sample_rate: 0.0,
+            access_fields: default_tinybird_access_fields(),
+            metrics_enabled: false,
+            metrics_dataset: default_tinybird_metrics_dataset(),
+            metrics_token_secret: default_tinybird_metrics_token_secret(),
+            third_party_enabled: false,
+            third_party_dataset: default_tinybird_third_party_dataset(),
+            third_party_token_secret: default_tinybird_third_party_token_secret(),
+            third_party_sample_rate: default_tinybird_third_party_sample_rate(),
             max_body_bytes: default_tinybird_max_body_bytes(),
         }
     }
@@ -1788,6 +1930,10 @@ impl TinybirdSettings {
         self.auction_token_secret = self.auction_token_secret.trim().to_owned();
         self.access_dataset = self.access_dataset.trim().to_owned();
         self.access_token_secret = self.access_token_secret.trim().to_owned();
+        self.metrics_dataset = self.metrics_dataset.trim().to_owned();
+        self.metrics_token_secret = self.metrics_token_secret.trim().to_owned();
+        self.third_party_dataset = self.third_party_dataset.trim().to_owned();
+        self.third_party_token_secret = self.third_party_token_secret.trim().to_owned();
     }
 
     fn prepare_runtime(&mut self) -> Result<(), Report<TrustedServerError>> {
@@ -1797,14 +1943,29 @@ impl TinybirdSettings {
                 message: "tinybird.access_sample_rate must be between 0.0 and 1.0".to_owned(),
             }));
         }
+        if !(0.0..=1.0).contains(&self.third_party_sample_rate) {
+            return Err(Report::new(TrustedServerError::Configuration {
+                message: "tinybird.third_party_sample_rate must be between 0.0 and 1.0".to_owned(),
+            }));
+        }
         if self.max_body_bytes < 1024 {
             return Err(Report::new(TrustedServerError::Configuration {
                 message: "tinybird.max_body_bytes must be at least 1024".to_owned(),
             }));
         }
-        if self.access_enabled {
+        if self.access_enabled && !self.enabled {
             return Err(Report::new(TrustedServerError::Configuration {
-                message: "tinybird.access_enabled is reserved for future access-log telemetry; no emitter is currently wired".to_owned(),
+                message: "tinybird.access_enabled requires tinybird.enabled".to_owned(),
+            }));
+        }
+        if self.metrics_enabled && !self.enabled {
+            return Err(Report::new(TrustedServerError::Configuration {
+                message: "tinybird.metrics_enabled requires tinybird.enabled".to_owned(),
+            }));
+        }
+        if self.third_party_enabled && !self.enabled {
+            return Err(Report::new(TrustedServerError::Configuration {
+                message: "tinybird.third_party_enabled requires tinybird.enabled".to_owned(),
             }));
         }
         if !self.enabled {
@@ -1822,6 +1983,28 @@ impl TinybirdSettings {
             validate_tinybird_dataset(&self.auction_dataset, "tinybird.auction_dataset")?;
             validate_tinybird_secret(&self.auction_token_secret, "tinybird.auction_token_secret")?;
         }
+        if self.access_enabled {
+            validate_tinybird_dataset(&self.access_dataset, "tinybird.access_dataset")?;
+            validate_tinybird_secret(&self.access_token_secret, "tinybird.access_token_secret")?;
+            if self.access_fields.is_empty() {
+                return Err(Report::new(TrustedServerError::Configuration {
+                    message:
+                        "tinybird.access_fields must not be empty when access logging is enabled"
+                            .to_owned(),
+                }));
+            }
+        }
+        if self.metrics_enabled {
+            validate_tinybird_dataset(&self.metrics_dataset, "tinybird.metrics_dataset")?;
+            validate_tinybird_secret(&self.metrics_token_secret, "tinybird.metrics_token_secret")?;
+        }
+        if self.third_party_enabled {
+            validate_tinybird_dataset(&self.third_party_dataset, "tinybird.third_party_dataset")?;
+            validate_tinybird_secret(
+                &self.third_party_token_secret,
+                "tinybird.third_party_token_secret",
+            )?;
+        }
         Ok(())
     }
 }
@@ -1869,6 +2052,256 @@ fn validate_tinybird_secret(value: &str, setting: &str) -> Result<(), Report<Tru
     Ok(())
 }
 
+/// OpenTelemetry trace export configuration.
+///
+/// Spans are exported over OTLP/HTTP with JSON encoding to
+/// `{endpoint}/v1/traces` after the response has been sent.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TracingSettings {
+    /// Master enablement for span collection, export and `traceparent`
+    /// propagation.
+    #[serde(default)]
+    pub enabled: bool,
+    /// OTLP/HTTP collector base URL, e.g. `https://otlp.example.com`.
+    #[serde(default)]
+    pub endpoint: String,
+    /// `service.name` resource attribute reported on every span.
+    #[serde(default = "default_tracing_service_name")]
+    pub service_name: String,
+    /// Fraction of traces to sample. Requests that arrive with a
+    /// `traceparent` continue the caller's trace but are sampled at this rate
+    /// too, unless [`Self::trust_incoming_sampling`] is set.
+    #[serde(default = "default_tracing_sample_rate")]
+    pub sample_rate: f64,
+    /// Follow the sampled flag of an inbound `traceparent` instead of
+    /// [`Self::sample_rate`]. Any client can set that flag, so enable this
+    /// only when every request reaches Trusted Server through a tier that
+    /// sets or strips `traceparent` itself.
+    #[serde(default)]
+    pub trust_incoming_sampling: bool,
+    /// Secret Store name containing the collector credential.
+    #[serde(default = "default_tracing_secret_store")]
+    pub secret_store: String,
+    /// Request header carrying the collector credential.
+    #[serde(default = "default_tracing_auth_header")]
+    pub auth_header: String,
+    /// Secret key whose value is sent verbatim in [`Self::auth_header`].
+    /// Unset for collectors that need no credential.
+    #[serde(default)]
+    pub auth_token_secret: Option<String>,
+    /// Upper bound on spans exported for one request, including the root span.
+    #[serde(default = "default_tracing_max_spans_per_request")]
+    pub max_spans_per_request: usize,
+}
+
+fn default_tracing_service_name() -> String {
+    "trusted-server".to_owned()
+}
+
+fn default_tracing_sample_rate() -> f64 {
+    0.1
+}
+
+fn default_tracing_secret_store() -> String {
+    "ts_secrets".to_owned()
+}
+
+fn default_tracing_auth_header() -> String {
+    "authorization".to_owned()
+}
+
+fn default_tracing_max_spans_per_request() -> usize {
+    128
+}
+
+impl Default for TracingSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: String::new(),
+            service_name: default_tracing_service_name(),
+            sample_rate: default_tracing_sample_rate(),
+            trust_incoming_sampling: false,
+            secret_store: default_tracing_secret_store(),
+            auth_header: default_tracing_auth_header(),
+            auth_token_secret: None,
+            max_spans_per_request: default_tracing_max_spans_per_request(),
+        }
+    }
+}
+
+impl TracingSettings {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    fn normalize(&mut self) {
+        self.endpoint = self.endpoint.trim().trim_end_matches('/').to_owned();
+        self.service_name = self.service_name.trim().to_owned();
+        self.secret_store = self.secret_store.trim().to_owned();
+        self.auth_header = self.auth_header.trim().to_ascii_lowercase();
+        if let Some(secret) = &mut self.auth_token_secret {
+            *secret = secret.trim().to_owned();
+        }
+    }
+
+    fn prepare_runtime(&mut self) -> Result<(), Report<TrustedServerError>> {
+        self.normalize();
+        if !(0.0..=1.0).contains(&self.sample_rate) {
+            return Err(Report::new(TrustedServerError::Configuration {
+                message: "tracing.sample_rate must be between 0.0 and 1.0".to_owned(),
+            }));
+        }
+        if self.max_spans_per_request < 2 {
+            return Err(Report::new(TrustedServerError::Configuration {
+                message: "tracing.max_spans_per_request must be at least 2".to_owned(),
+            }));
+        }
+        if !self.enabled {
+            return Ok(());
+        }
+        let endpoint = Url::parse(&self.endpoint).map_err(|_| {
+            Report::new(TrustedServerError::Configuration {
+                message: "tracing.endpoint must be an absolute https URL".to_owned(),
+            })
+        })?;
+        if endpoint.scheme() != "https"
+            || endpoint.host_str().is_none_or(str::is_empty)
+            || endpoint.query().is_some()
+            || endpoint.fragment().is_some()
+            || !endpoint.username().is_empty()
+            || endpoint.password().is_some()
+        {
+            return Err(Report::new(TrustedServerError::Configuration {
+                message:
+                    "tracing.endpoint must be an https URL without credentials, query, or fragment"
+                        .to_owned(),
+            }));
+        }
+        if self.service_name.is_empty() {
+            return Err(Report::new(TrustedServerError::Configuration {
+                message: "tracing.service_name must not be empty".to_owned(),
+            }));
+        }
+        if let Some(secret) = &self.auth_token_secret {
+            if http::HeaderName::from_bytes(self.auth_header.as_bytes()).is_err() {
+                return Err(Report::new(TrustedServerError::Configuration {
+                    message: "tracing.auth_header must be a valid header name".to_owned(),
+                }));
+            }
+            if self.secret_store.is_empty() {
+                return Err(Report::new(TrustedServerError::Configuration {
+                    message: "tracing.secret_store must not be empty when tracing.auth_token_secret is set"
+                        .to_owned(),
+                }));
+            }
+            validate_tinybird_secret(secret, "tracing.auth_token_secret")?;
+        }
+        Ok(())
+    }
+
+    /// Returns the OTLP/HTTP traces URL derived from [`Self::endpoint`].
+    #[must_use]
+    pub fn traces_url(&self) -> String {
+        format!("{}/v1/traces", self.endpoint)
+    }
+}
+
+/// Creative lifecycle event beacon configuration.
+///
+/// When enabled, every winning bid delivered to the page carries an opaque
+/// `ts_event` token. The client posts it back to `/_ts/api/v1/event` on
+/// render, viewability and click so the server can fire win/billing
+/// notifications and record the event for win-to-render reconciliation.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct EventsSettings {
+    /// Master enablement for event tokens and the beacon endpoint.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Fire `nurl`/`burl` from the edge instead of the browser. The URLs are
+    /// then withheld from the page so they cannot be fired twice.
+    ///
+    /// Off by default: replays are deduplicated through the KV store, which is
+    /// eventually consistent, so a replay can occasionally re-fire a notice.
+    /// Requires [`Self::kv_store`].
+    #[serde(default)]
+    pub server_side_notifications: bool,
+    /// Name of the KV store recording the first use of each event token.
+    /// Without it beacons are not deduplicated. Fastly opens the named store
+    /// for the beacon route; other adapters use their default KV store.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kv_store: Option<String>,
+    /// Client event that triggers the billing notification (`burl`).
+    #[serde(default)]
+    pub billing_event: BillingEvent,
+    /// Lifetime of an event token, measured from the auction.
+    #[serde(default = "default_events_token_ttl_secs")]
+    pub token_ttl_secs: u64,
+}
+
+/// Client event that triggers the `burl` billing notification.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BillingEvent {
+    /// Bill when the creative renders (`OpenRTB` default).
+    #[default]
+    Render,
+    /// Bill when the creative becomes viewable.
+    Viewable,
+}
+
+/// Upper bound on [`EventsSettings::token_ttl_secs`]. First-use records live
+/// in the KV store for the token lifetime, so keep the window short.
+const MAX_EVENTS_TOKEN_TTL_SECS: u64 = 24 * 60 * 60;
+
+fn default_events_token_ttl_secs() -> u64 {
+    3600
+}
+
+impl Default for EventsSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            server_side_notifications: false,
+            kv_store: None,
+            billing_event: BillingEvent::default(),
+            token_ttl_secs: default_events_token_ttl_secs(),
+        }
+    }
+}
+
+impl EventsSettings {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    fn prepare_runtime(&self) -> Result<(), Report<TrustedServerError>> {
+        if self.token_ttl_secs == 0 || self.token_ttl_secs > MAX_EVENTS_TOKEN_TTL_SECS {
+            return Err(Report::new(TrustedServerError::Configuration {
+                message: format!(
+                    "events.token_ttl_secs must be between 1 and {MAX_EVENTS_TOKEN_TTL_SECS}"
+                ),
+            }));
+        }
+        if self.enabled
+            && self.server_side_notifications
+            && self
+                .kv_store
+                .as_deref()
+                .is_none_or(|name| name.trim().is_empty())
+        {
+            return Err(Report::new(TrustedServerError::Configuration {
+                message:
+                    "events.kv_store must be set when events.server_side_notifications is enabled"
+                        .to_string(),
+            }));
+        }
+        Ok(())
+    }
+}
+
 /// Cache behavior configuration.
 #[derive(Debug, Default, Clone, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
@@ -2599,6 +3032,10 @@ pub struct TesterCookieConfig {
 #[derive(Debug, Default, Clone, Deserialize, Serialize, Validate)]
 #[serde(deny_unknown_fields)]
 pub struct Settings {
+    /// Config schema version the file was written for; see
+    /// [`crate::config_schema`]. Never serialized into config blobs.
+    #[serde(default, skip_serializing)]
+    pub config_schema_version: Option<u32>,
     #[validate(nested)]
     pub publisher: Publisher,
     #[serde(default)]
@@ -2631,8 +3068,21 @@ pub struct Settings {
     pub image_optimizer: ImageOptimizerSettings,
     #[serde(default)]
     pub tinybird: TinybirdSettings,
+    // Tables added after config schema 1 are omitted from serialized config
+    // blobs while at their defaults so older binaries keep loading them; see
+    // `crate::config_schema`.
+    #[serde(default, skip_serializing_if = "TracingSettings::is_default")]
+    pub tracing: TracingSettings,
+    #[serde(default, skip_serializing_if = "EventsSettings::is_default")]
+    pub events: EventsSettings,
     #[serde(default)]
     pub debug: DebugConfig,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub experiments: Vec<ExperimentConfig>,
+    /// Experiment arms assigned to the current request; see
+    /// [`crate::experiments::apply_experiments`].
+    #[serde(skip)]
+    pub experiment_arms: ExperimentArms,
 }
 
 impl Settings {
@@ -2738,13 +3188,31 @@ impl Settings {
     ///
     /// Returns a configuration error if any cached runtime artifact cannot be
     /// prepared, if any handler path regex does not compile, if a creative
-    /// opportunity slot is invalid, or if
+    /// opportunity slot is invalid, if the bid cache or server-side event
+    /// notifications are enabled without a KV store, if an experiment is
+    /// malformed, or if
     /// [`AuctionDebugCommentOptions::metadata_keys`] names an unsupported key.
     pub fn prepare_runtime(&mut self) -> Result<(), Report<TrustedServerError>> {
         self.image_optimizer.prepare_runtime()?;
         self.cache.prepare_runtime()?;
         self.proxy.prepare_runtime()?;
+        if self.auction.bid_cache.enabled
+            && self
+                .auction
+                .bid_cache
+                .kv_store
+                .as_deref()
+                .is_none_or(|name| name.trim().is_empty())
+        {
+            return Err(Report::new(TrustedServerError::Configuration {
+                message: "auction.bid_cache.kv_store must be set when the bid cache is enabled"
+                    .to_string(),
+            }));
+        }
         self.tinybird.prepare_runtime()?;
+        self.tracing.prepare_runtime()?;
+        self.events.prepare_runtime()?;
+        validate_experiments(&self.experiments)?;
         self.debug
             .auction_html_comment_options
             .validate_metadata_keys()?;
@@ -2922,6 +3390,8 @@ impl Settings {
         "/_ts/admin/ec",
         "/_ts/admin/ec/{id}",
         "/_ts/admin/eids",
+        "/_ts/admin/metrics",
+        "/_ts/admin/config/release",
     ];
 
     /// Probes that establish handler coverage for the dynamic
@@ -3564,18 +4034,199 @@ mod tests {
     }
 
     #[test]
-    fn tinybird_access_enabled_is_rejected_until_emitter_is_wired() {
+    fn tinybird_access_enabled_requires_tinybird_enabled() {
         let toml = format!(
             "{}\n[tinybird]\naccess_enabled = true\n",
             crate_test_settings_str()
         );
 
         let err = Settings::from_toml(&toml)
-            .expect_err("should reject access telemetry before emitter exists");
+            .expect_err("should reject access telemetry without tinybird.enabled");
+        assert!(
+            format!("{err:?}").contains("tinybird.access_enabled requires tinybird.enabled"),
+            "should report missing tinybird.enabled: {err:?}"
+        );
+    }
+
+    #[test]
+    fn tinybird_access_fields_parse_and_default_to_all() {
+        let toml = format!(
+            "{}\n[tinybird]\nenabled = true\napi_host = \"api.us-east.aws.tinybird.co\"\naccess_enabled = true\naccess_sample_rate = 0.05\naccess_fields = [\"route\", \"status\", \"consent_jurisdiction\"]\n",
+            crate_test_settings_str()
+        );
+
+        let settings = Settings::from_toml(&toml).expect("should accept access-log settings");
+        assert_eq!(
+            settings.tinybird.access_fields,
+            vec![
+                AccessLogField::Route,
+                AccessLogField::Status,
+                AccessLogField::ConsentJurisdiction
+            ]
+        );
+        assert_eq!(
+            TinybirdSettings::default().access_fields,
+            AccessLogField::ALL.to_vec(),
+            "should default to every access-log field"
+        );
+    }
+
+    #[test]
+    fn tinybird_metrics_enabled_requires_tinybird_enabled() {
+        let toml = format!(
+            "{}\n[tinybird]\nmetrics_enabled = true\n",
+            crate_test_settings_str()
+        );
+
+        let err = Settings::from_toml(&toml)
+            .expect_err("should reject metrics flushing without Tinybird enabled");
+        assert!(
+            format!("{err:?}").contains("tinybird.metrics_enabled"),
+            "should report tinybird.metrics_enabled dependency: {err:?}"
+        );
+    }
+
+    #[test]
+    fn tinybird_third_party_sample_rate_must_be_a_fraction() {
+        let toml = format!(
+            "{}\n[tinybird]\nthird_party_sample_rate = 1.5\n",
+            crate_test_settings_str()
+        );
+
+        let err = Settings::from_toml(&toml)
+            .expect_err("should reject a third-party sample rate above 1.0");
+        assert!(
+            format!("{err:?}").contains("tinybird.third_party_sample_rate"),
+            "should report the invalid sample rate: {err:?}"
+        );
+    }
+
+    #[test]
+    fn tracing_defaults_to_disabled() {
+        let settings = Settings::from_toml(&crate_test_settings_str())
+            .expect("should parse settings without tracing block");
+
+        assert!(!settings.tracing.enabled, "tracing should default disabled");
+        assert_eq!(settings.tracing.service_name, "trusted-server");
+        assert_eq!(settings.tracing.auth_header, "authorization");
+    }
+
+    #[test]
+    fn tracing_enabled_requires_https_endpoint() {
+        let toml = format!(
+            "{}\n[tracing]\nenabled = true\nendpoint = \"http://otlp.example.com\"\n",
+            crate_test_settings_str()
+        );
+
+        let err = Settings::from_toml(&toml).expect_err("should reject plain-http endpoint");
+        assert!(
+            format!("{err:?}").contains("tracing.endpoint"),
+            "should report tracing.endpoint validation error: {err:?}"
+        );
+    }
+
+    #[test]
+    fn auction_bid_cache_requires_kv_store_when_enabled() {
+        let toml = format!(
+            "{}\n[auction.bid_cache]\nenabled = true\n",
+            crate_test_settings_str()
+        );
+
+        let err =
+            Settings::from_toml(&toml).expect_err("should reject a bid cache without a store");
+        assert!(
+            format!("{err:?}").contains("auction.bid_cache.kv_store"),
+            "should report auction.bid_cache.kv_store validation error: {err:?}"
+        );
+
+        let toml = format!(
+            "{}\n[auction.bid_cache]\nenabled = true\nkv_store = \"bid_cache\"\n",
+            crate_test_settings_str()
+        );
+        let settings = Settings::from_toml(&toml).expect("should accept a bid cache with a store");
+        assert_eq!(settings.auction.bid_cache.default_ttl_secs, 300);
+    }
+
+    #[test]
+    fn tracing_rejects_out_of_range_sample_rate() {
+        let toml = format!(
+            "{}\n[tracing]\nsample_rate = 1.5\n",
+            crate_test_settings_str()
+        );
+
+        let err = Settings::from_toml(&toml).expect_err("should reject sample rate above 1.0");
+        assert!(
+            format!("{err:?}").contains("tracing.sample_rate"),
+            "should report tracing.sample_rate validation error: {err:?}"
+        );
+    }
+
+    #[test]
+    fn tracing_normalizes_endpoint_and_builds_traces_url() {
+        let toml = format!(
+            "{}\n[tracing]\nenabled = true\nendpoint = \"https://otlp.example.com/\"\nauth_header = \"X-Api-Key\"\nauth_token_secret = \"otlp_token\"\n",
+            crate_test_settings_str()
+        );
+
+        let settings = Settings::from_toml(&toml).expect("should accept tracing settings");
+        assert_eq!(
+            settings.tracing.traces_url(),
+            "https://otlp.example.com/v1/traces"
+        );
+        assert_eq!(settings.tracing.auth_header, "x-api-key");
+    }
+
+    #[test]
+    fn events_default_to_disabled_with_client_side_render_billing() {
+        let settings = Settings::from_toml(&crate_test_settings_str())
+            .expect("should parse settings without events block");
+
+        assert!(!settings.events.enabled, "events should default disabled");
+        assert!(
+            !settings.events.server_side_notifications,
+            "should leave notifications to the browser by default"
+        );
+        assert_eq!(settings.events.billing_event, BillingEvent::Render);
+        assert_eq!(settings.events.token_ttl_secs, 3600);
+    }
+
+    #[test]
+    fn events_reject_out_of_range_token_ttl() {
+        for ttl in ["0", "86401"] {
+            let toml = format!(
+                "{}\n[events]\nenabled = true\ntoken_ttl_secs = {ttl}\n",
+                crate_test_settings_str()
+            );
+
+            let err = Settings::from_toml(&toml).expect_err("should reject token ttl");
+            assert!(
+                format!("{err:?}").contains("events.token_ttl_secs"),
+                "should report events.token_ttl_secs validation error for {ttl}: {err:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn events_server_side_notifications_require_kv_store() {
+        let toml = format!(
+            "{}\n[events]\nenabled = true\nserver_side_notifications = true\n",
+            crate_test_settings_str()
+        );
+
+        let err = Settings::from_toml(&toml)
+            .expect_err("should reject server-side notifications without a store");
         assert!(
-            format!("{err:?}").contains("tinybird.access_enabled"),
-            "should report unsupported tinybird.access_enabled setting: {err:?}"
+            format!("{err:?}").contains("events.kv_store"),
+            "should report events.kv_store validation error: {err:?}"
         );
+
+        let toml = format!(
+            "{}\n[events]\nenabled = true\nserver_side_notifications = true\nkv_store = \"events\"\n",
+            crate_test_settings_str()
+        );
+        let settings = Settings::from_toml(&toml)
+            .expect("should accept server-side notifications with a store");
+        assert_eq!(settings.events.kv_store.as_deref(), Some("events"));
     }
 
     #[test]
@@ -5364,6 +6015,21 @@ origin_host_header_overide = "www.example.com""#,
         );
     }
 
+    #[test]
+    fn proxy_creative_cache_requires_kv_store_when_enabled() {
+        let mut proxy = Proxy::default();
+        proxy.creative_cache.enabled = true;
+
+        proxy
+            .prepare_runtime()
+            .expect_err("should reject an enabled creative cache without a KV store");
+
+        proxy.creative_cache.kv_store = Some("creative_cache".to_string());
+        proxy
+            .prepare_runtime()
+            .expect("should accept an enabled creative cache with a KV store");
+    }
+
     // --- Proxy::normalize ---
 
     #[test]
@@ -5375,6 +6041,8 @@ origin_host_header_overide = "www.example.com""#,
                 "*.Example.Org".to_string(),
             ],
             asset_routes: vec![],
+            vast_max_wrapper_depth: 5,
+            creative_cache: ProxyCreativeCache::default(),
         };
         proxy.normalize();
         assert_eq!(
@@ -5395,6 +6063,8 @@ origin_host_header_overide = "www.example.com""#,
                 "cdn.example.com".to_string(),
             ],
             asset_routes: vec![],
+            vast_max_wrapper_depth: 5,
+            creative_cache: ProxyCreativeCache::default(),
         };
         proxy.normalize();
         assert_eq!(
@@ -5410,6 +6080,8 @@ origin_host_header_overide = "www.example.com""#,
             certificate_check: true,
             allowed_domains: vec!["*".to_string(), "tracker.com".to_string()],
             asset_routes: vec![],
+            vast_max_wrapper_depth: 5,
+            creative_cache: ProxyCreativeCache::default(),
         };
         proxy.normalize();
         assert_eq!(
@@ -5425,6 +6097,8 @@ origin_host_header_overide = "www.example.com""#,
             certificate_check: true,
             allowed_domains: vec!["*".to_string()],
             asset_routes: vec![],
+            vast_max_wrapper_depth: 5,
+            creative_cache: ProxyCreativeCache::default(),
         };
         proxy.normalize();
         assert!(
@@ -5439,6 +6113,8 @@ origin_host_header_overide = "www.example.com""#,
             certificate_check: true,
             allowed_domains: vec!["  ".to_string(), "\t".to_string()],
             asset_routes: vec![],
+            vast_max_wrapper_depth: 5,
+            creative_cache: ProxyCreativeCache::default(),
         };
         proxy.normalize();
         assert!(
@@ -5457,6 +6133,8 @@ origin_host_header_overide = "www.example.com""#,
                 origin_url: "  https://assets.example.com  ".to_string(),
                 ..Default::default()
             }],
+            vast_max_wrapper_depth: 5,
+            creative_cache: ProxyCreativeCache::default(),
         };
         proxy.normalize();
         assert_eq!(
@@ -5481,6 +6159,8 @@ origin_host_header_overide = "www.example.com""#,
                 target_path: Some("  /rewritten/$1  ".to_string()),
                 ..Default::default()
             }],
+            vast_max_wrapper_depth: 5,
+            creative_cache: ProxyCreativeCache::default(),
         };
         proxy.normalize();
 
@@ -5960,6 +6640,8 @@ origin_host_header_overide = "www.example.com""#,
                     ..Default::default()
                 },
             ],
+            vast_max_wrapper_depth: 5,
+            creative_cache: ProxyCreativeCache::default(),
         };
 
         let route = proxy
@@ -5988,6 +6670,8 @@ origin_host_header_overide = "www.example.com""#,
                     ..Default::default()
                 },
             ],
+            vast_max_wrapper_depth: 5,
+            creative_cache: ProxyCreativeCache::default(),
         };
 
         let route = proxy
@@ -6244,6 +6928,8 @@ origin_host_header_overide = "www.example.com""#,
                 "/_ts/admin/ec",
                 "/_ts/admin/ec/{id}",
                 "/_ts/admin/eids",
+                "/_ts/admin/metrics",
+                "/_ts/admin/config/release",
             ],
             "should report every admin endpoint as uncovered"
         );
@@ -6283,6 +6969,8 @@ origin_host_header_overide = "www.example.com""#,
                 "/_ts/admin/ec",
                 "/_ts/admin/ec/{id}",
                 "/_ts/admin/eids",
+                "/_ts/admin/metrics",
+                "/_ts/admin/config/release",
             ],
             "should detect the admin endpoints not covered by the narrow handler"
         );
@@ -6294,7 +6982,7 @@ origin_host_header_overide = "www.example.com""#,
             r#"path = "^/_ts/admin"
             username = "admin"
             password = "admin-pass""#,
-            r#"path = "^/_ts/admin/(keys/rotate|keys/deactivate|ec|eids)$"
+            r#"path = "^/_ts/admin/(keys/rotate|keys/deactivate|ec|eids|metrics|config/release)$"
             username = "admin"
             password = "strong-test-password"
 
@@ -6319,7 +7007,7 @@ origin_host_header_overide = "www.example.com""#,
             r#"path = "^/_ts/admin"
             username = "admin"
             password = "admin-pass""#,
-            r#"path = "^/_ts/admin/(keys/rotate|keys/deactivate|ec|eids)$"
+            r#"path = "^/_ts/admin/(keys/rotate|keys/deactivate|ec|eids|metrics|config/release)$"
             username = "admin"
             password = "strong-test-password"
 
@@ -6402,7 +7090,7 @@ origin_host_header_overide = "www.example.com""#,
             r#"path = "^/_ts/admin"
             username = "admin"
             password = "admin-pass""#,
-            r#"path = "^/_ts/admin/(keys/rotate|keys/deactivate|ec|eids)$"
+            r#"path = "^/_ts/admin/(keys/rotate|keys/deactivate|ec|eids|metrics|config/release)$"
             username = "admin"
             password = "strong-test-password"
 
@@ -6428,7 +7116,7 @@ origin_host_header_overide = "www.example.com""#,
             r#"path = "^/_ts/admin"
             username = "admin"
             password = "admin-pass""#,
-            r#"path = "^/_ts/admin/(keys/rotate|keys/deactivate|ec|eids)$"
+            r#"path = "^/_ts/admin/(keys/rotate|keys/deactivate|ec|eids|metrics|config/release)$"
             username = "admin"
             password = "strong-test-password"
 
@@ -6453,7 +7141,7 @@ origin_host_header_overide = "www.example.com""#,
             r#"path = "^/_ts/admin"
             username = "admin"
             password = "admin-pass""#,
-            r#"path = "^/_ts/admin/(keys/rotate|keys/deactivate|ec|eids)$"
+            r#"path = "^/_ts/admin/(keys/rotate|keys/deactivate|ec|eids|metrics|config/release)$"
             username = "admin"
             password = "strong-test-password"
 
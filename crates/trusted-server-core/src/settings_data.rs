@@ -3,7 +3,9 @@ use error_stack::{Report, ResultExt};
 use serde::Deserialize;
 use sha2::{Digest as _, Sha256};
 
-use crate::config_payload::settings_from_config_blob;
+use crate::config_payload::{ConfigBlob, config_blob_from_envelope};
+use crate::config_release::{ConfigRelease, version_key};
+use crate::config_signing::{PinnedConfigKey, pinned_config_keys};
 use crate::error::TrustedServerError;
 use crate::platform::{PlatformConfigStore, StoreName};
 use crate::settings::Settings;
@@ -40,21 +42,170 @@ pub fn default_config_key() -> String {
     EnvConfig::from_env().store_key("config", DEFAULT_CONFIG_STORE_ID)
 }
 
+/// Returns the config-store key holding the last config `ts config push`
+/// wrote to `key`.
+///
+/// The CLI writes every signed push of the logical key — settings pushed in
+/// place or a release manifest — here as well, so a rejected entry at `key`
+/// can fall back to the last push made through the CLI.
+#[must_use]
+pub fn last_good_config_key(key: &str) -> String {
+    format!("{key}.last_good")
+}
+
 /// Loads [`Settings`] from a platform config store and key.
 ///
+/// When the key holds a versioned release manifest, the stable version is
+/// loaded; see [`get_settings_for_rollout`].
+///
 /// # Errors
 ///
 /// Returns [`TrustedServerError::Configuration`] when the config blob is
-/// missing, cannot be read, fails envelope verification, or fails Trusted
-/// Server settings validation.
+/// missing, cannot be read, or is rejected.
 pub fn get_settings_from_config_store(
     config_store: &dyn PlatformConfigStore,
     store_name: &StoreName,
     key: &str,
 ) -> Result<Settings, Report<TrustedServerError>> {
+    get_settings_for_rollout(config_store, store_name, key, None)
+}
+
+/// Loads the [`Settings`] a request in `rollout_bucket` should be served.
+///
+/// A settings blob pushed in place is returned as is. When the entry at `key`
+/// is missing or rejected, the copy at [`last_good_config_key`] is loaded
+/// instead; without one, loading fails closed. A [`ConfigRelease`] manifest
+/// selects the staged candidate for
+/// buckets inside its rollout percentage and the stable version otherwise
+/// (including when `rollout_bucket` is `None`). A version that fails to load or
+/// verify falls back along [`ConfigRelease::fallback_order`]: candidate to
+/// stable, then stable to the previous retained versions still in the config
+/// store.
+///
+/// # Errors
+///
+/// Returns [`TrustedServerError::Configuration`] when the config blob is
+/// missing, cannot be read, or is rejected, or when no version of a release
+/// loads.
+pub fn get_settings_for_rollout(
+    config_store: &dyn PlatformConfigStore,
+    store_name: &StoreName,
+    key: &str,
+    rollout_bucket: Option<u8>,
+) -> Result<Settings, Report<TrustedServerError>> {
+    let pinned = pinned_config_keys()?;
+    match load_logical_config_blob(config_store, store_name, key, &pinned)? {
+        ConfigBlob::Settings(settings) => Ok(*settings),
+        ConfigBlob::Release(release) => load_release_version(
+            config_store,
+            store_name,
+            key,
+            &release,
+            rollout_bucket,
+            &pinned,
+        ),
+    }
+}
+
+/// Loads the [`ConfigRelease`] manifest deployed at `key`, or `None` when the
+/// key holds settings pushed in place.
+///
+/// The entry is resolved like [`get_settings_for_rollout`] resolves it,
+/// including the fallback to [`last_good_config_key`], so the manifest
+/// returned is the one the runtime serves from.
+///
+/// # Errors
+///
+/// Returns [`TrustedServerError::Configuration`] when neither the entry nor
+/// its last-good copy loads.
+pub fn get_config_release(
+    config_store: &dyn PlatformConfigStore,
+    store_name: &StoreName,
+    key: &str,
+) -> Result<Option<ConfigRelease>, Report<TrustedServerError>> {
+    let pinned = pinned_config_keys()?;
+    match load_logical_config_blob(config_store, store_name, key, &pinned)? {
+        ConfigBlob::Settings(_) => Ok(None),
+        ConfigBlob::Release(release) => Ok(Some(release)),
+    }
+}
+
+fn load_logical_config_blob(
+    config_store: &dyn PlatformConfigStore,
+    store_name: &StoreName,
+    key: &str,
+    pinned: &[PinnedConfigKey],
+) -> Result<ConfigBlob, Report<TrustedServerError>> {
+    match load_config_blob(config_store, store_name, key, pinned) {
+        Ok(blob) => Ok(blob),
+        Err(report) => {
+            let fallback_key = last_good_config_key(key);
+            let Ok(blob) = load_config_blob(config_store, store_name, &fallback_key, pinned) else {
+                return Err(report);
+            };
+            log::error!(
+                "Config key `{key}` failed to load; serving the last good push from `{fallback_key}`: {report:?}"
+            );
+            Ok(blob)
+        }
+    }
+}
+
+fn load_config_blob(
+    config_store: &dyn PlatformConfigStore,
+    store_name: &StoreName,
+    key: &str,
+    pinned: &[PinnedConfigKey],
+) -> Result<ConfigBlob, Report<TrustedServerError>> {
     let raw_value = read_config_entry(config_store, store_name, key)?;
     let envelope_json = resolve_fastly_chunk_pointer(config_store, store_name, &raw_value)?;
-    settings_from_config_blob(&envelope_json)
+    config_blob_from_envelope(&envelope_json, pinned)
+}
+
+fn load_release_version(
+    config_store: &dyn PlatformConfigStore,
+    store_name: &StoreName,
+    key: &str,
+    release: &ConfigRelease,
+    rollout_bucket: Option<u8>,
+    pinned: &[PinnedConfigKey],
+) -> Result<Settings, Report<TrustedServerError>> {
+    let mut rejected = None;
+    for version in release.fallback_order(rollout_bucket) {
+        match load_config_version(config_store, store_name, key, version, pinned) {
+            Ok(settings) => {
+                log::debug!("Serving Trusted Server config version {version}");
+                return Ok(settings);
+            }
+            Err(report) => {
+                log::error!(
+                    "Config version {version} failed to load; trying the previous version: {report:?}"
+                );
+                rejected = Some(report);
+            }
+        }
+    }
+    Err(rejected.unwrap_or_else(|| {
+        Report::new(TrustedServerError::Configuration {
+            message: "config release manifest lists no versions".to_string(),
+        })
+    }))
+}
+
+fn load_config_version(
+    config_store: &dyn PlatformConfigStore,
+    store_name: &StoreName,
+    key: &str,
+    version: u32,
+    pinned: &[PinnedConfigKey],
+) -> Result<Settings, Report<TrustedServerError>> {
+    let version_key = version_key(key, version);
+    match load_config_blob(config_store, store_name, &version_key, pinned)? {
+        ConfigBlob::Settings(settings) => Ok(*settings),
+        ConfigBlob::Release(_) => configuration_error(format!(
+            "config version key `{version_key}` holds a release manifest instead of settings"
+        )),
+    }
 }
 
 fn read_config_entry(
@@ -177,6 +328,7 @@ fn configuration_error<T>(message: String) -> Result<T, Report<TrustedServerErro
 mod tests {
     use super::*;
     use crate::config_payload::CONFIG_BLOB_KEY;
+    use crate::config_release::CONFIG_RELEASE_FIELD;
     use crate::platform::PlatformError;
     use crate::settings::Settings;
     use crate::test_support::tests::crate_test_settings_str;
@@ -331,4 +483,176 @@ mod tests {
             "error should mention missing blob key"
         );
     }
+
+    #[test]
+    fn tampered_in_place_push_serves_the_last_good_push() {
+        let previous =
+            Settings::from_toml(&crate_test_settings_str()).expect("should parse test settings");
+        let mut pushed = previous.clone();
+        pushed.publisher.domain = "tampered.example.com".to_string();
+        let mut tampered: BlobEnvelope =
+            serde_json::from_str(&envelope_json(&pushed)).expect("should parse envelope");
+        tampered.sha256 = "ff".repeat(32);
+        let store = MemoryConfigStore {
+            entries: BTreeMap::from([
+                (
+                    CONFIG_BLOB_KEY.to_string(),
+                    serde_json::to_string(&tampered).expect("should serialize envelope"),
+                ),
+                (
+                    last_good_config_key(CONFIG_BLOB_KEY),
+                    envelope_json(&previous),
+                ),
+            ]),
+        };
+
+        let loaded =
+            get_settings_from_config_store(&store, &StoreName::from("app_config"), CONFIG_BLOB_KEY)
+                .expect("should fall back to the last good push");
+
+        assert_eq!(
+            loaded.publisher.domain, previous.publisher.domain,
+            "should serve the previous verified config"
+        );
+    }
+
+    #[test]
+    fn tampered_in_place_push_without_last_good_fails_closed() {
+        let mut tampered: BlobEnvelope = serde_json::from_str(&envelope_json(
+            &Settings::from_toml(&crate_test_settings_str()).expect("should parse test settings"),
+        ))
+        .expect("should parse envelope");
+        tampered.sha256 = "ff".repeat(32);
+        let store = MemoryConfigStore {
+            entries: BTreeMap::from([(
+                CONFIG_BLOB_KEY.to_string(),
+                serde_json::to_string(&tampered).expect("should serialize envelope"),
+            )]),
+        };
+
+        let err =
+            get_settings_from_config_store(&store, &StoreName::from("app_config"), CONFIG_BLOB_KEY)
+                .expect_err("should fail closed without a last good push");
+
+        assert!(
+            err.to_string().contains("integrity verification"),
+            "should report the rejected entry: {err:?}"
+        );
+    }
+
+    fn release_store(release: &ConfigRelease, versions: &[(u32, &Settings)]) -> MemoryConfigStore {
+        let data = json!({ CONFIG_RELEASE_FIELD: release });
+        let manifest = BlobEnvelope::new(data, "2026-10-01T00:00:00Z".to_string());
+        let mut entries = BTreeMap::from([(
+            CONFIG_BLOB_KEY.to_string(),
+            serde_json::to_string(&manifest).expect("should serialize manifest"),
+        )]);
+        for (version, settings) in versions {
+            entries.insert(
+                version_key(CONFIG_BLOB_KEY, *version),
+                envelope_json(settings),
+            );
+        }
+        MemoryConfigStore { entries }
+    }
+
+    fn staged_release() -> ConfigRelease {
+        let mut release = ConfigRelease::default();
+        for stage in [None, Some(25)] {
+            release
+                .record_push("2026-10-01T00:00:00Z".to_string(), None, stage)
+                .expect("should record push");
+        }
+        release
+    }
+
+    #[test]
+    fn reads_the_deployed_release_manifest() {
+        let settings =
+            Settings::from_toml(&crate_test_settings_str()).expect("should parse test settings");
+        let store_name = StoreName::from("app_config");
+        let release_store = release_store(&staged_release(), &[]);
+        let in_place_store = MemoryConfigStore {
+            entries: BTreeMap::from([(CONFIG_BLOB_KEY.to_string(), envelope_json(&settings))]),
+        };
+
+        let release = get_config_release(&release_store, &store_name, CONFIG_BLOB_KEY)
+            .expect("should read the manifest");
+        let in_place = get_config_release(&in_place_store, &store_name, CONFIG_BLOB_KEY)
+            .expect("should read in-place settings");
+
+        assert_eq!(
+            release,
+            Some(staged_release()),
+            "should return the manifest"
+        );
+        assert_eq!(in_place, None, "settings pushed in place have no manifest");
+    }
+
+    #[test]
+    fn release_manifest_serves_candidate_only_inside_the_rollout() {
+        let stable =
+            Settings::from_toml(&crate_test_settings_str()).expect("should parse test settings");
+        let mut candidate = stable.clone();
+        candidate.publisher.domain = "candidate.example.com".to_string();
+        let store = release_store(&staged_release(), &[(1, &stable), (2, &candidate)]);
+        let store_name = StoreName::from("app_config");
+
+        let staged = get_settings_for_rollout(&store, &store_name, CONFIG_BLOB_KEY, Some(24))
+            .expect("should load the candidate");
+        let unstaged = get_settings_for_rollout(&store, &store_name, CONFIG_BLOB_KEY, Some(25))
+            .expect("should load stable");
+        let no_ec = get_settings_from_config_store(&store, &store_name, CONFIG_BLOB_KEY)
+            .expect("should load stable without a bucket");
+
+        assert_eq!(staged.publisher.domain, "candidate.example.com");
+        assert_eq!(unstaged.publisher.domain, stable.publisher.domain);
+        assert_eq!(no_ec.publisher.domain, stable.publisher.domain);
+    }
+
+    #[test]
+    fn rejected_stable_version_falls_back_to_the_previous_version() {
+        let previous =
+            Settings::from_toml(&crate_test_settings_str()).expect("should parse test settings");
+        let mut release = ConfigRelease::default();
+        for _ in 0..2 {
+            release
+                .record_push("2026-10-01T00:00:00Z".to_string(), None, None)
+                .expect("should record push");
+        }
+        let mut store = release_store(&release, &[(1, &previous)]);
+        store.entries.insert(
+            version_key(CONFIG_BLOB_KEY, 2),
+            "{\"tampered\": true}".to_string(),
+        );
+
+        let loaded =
+            get_settings_from_config_store(&store, &StoreName::from("app_config"), CONFIG_BLOB_KEY)
+                .expect("should fall back to the previous version");
+
+        assert_eq!(
+            loaded.publisher.domain, previous.publisher.domain,
+            "should serve the last version that still verifies"
+        );
+    }
+
+    #[test]
+    fn missing_candidate_version_falls_back_to_stable() {
+        let stable =
+            Settings::from_toml(&crate_test_settings_str()).expect("should parse test settings");
+        let store = release_store(&staged_release(), &[(1, &stable)]);
+
+        let loaded = get_settings_for_rollout(
+            &store,
+            &StoreName::from("app_config"),
+            CONFIG_BLOB_KEY,
+            Some(0),
+        )
+        .expect("should fall back to the stable version");
+
+        assert_eq!(
+            loaded.publisher.domain, stable.publisher.domain,
+            "should serve stable when the candidate is unavailable"
+        );
+    }
 }
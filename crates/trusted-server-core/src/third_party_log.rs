@@ -0,0 +1,465 @@
+//! Third-party request transparency log.
+//!
+//! Records one row per outbound call Trusted Server makes on a publisher's
+//! behalf — [`crate::proxy::proxy_request`], the first-party proxy and click
+//! routes, and integration proxies — so publishers can audit which vendors
+//! their pages actually talk to.
+//!
+//! Rows are buffered in the [`ThirdPartyRequestLog`] carried on
+//! [`RuntimeServices`] and drained into a [`ThirdPartyRequestSink`] after the
+//! response has been sent ([`flush_third_party_requests_best_effort`]).
+//! Sampling is decided once per request, so a sampled page view reports every
+//! vendor it reached. Rows carry the destination host only; paths and query
+//! strings can contain identifiers and are never recorded.
+
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+
+use chrono::Utc;
+use edgezero_core::body::Body as EdgeBody;
+use error_stack::Report;
+use http::{Method, Response, header};
+use serde::Serialize;
+use web_time::Instant;
+
+use crate::error::TrustedServerError;
+use crate::platform::{PlatformError, PlatformHttpRequest, PlatformResponse, RuntimeServices};
+use crate::proxy::is_host_allowed;
+use crate::settings::Settings;
+
+/// Defensive cap on rows buffered for one request.
+const MAX_ROWS_PER_REQUEST: usize = 256;
+
+/// How the browser or edge reached the third party.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ThirdPartyTransport {
+    /// Trusted Server fetched the resource from the edge.
+    Fetch,
+    /// Trusted Server redirected the browser to the third party.
+    Redirect,
+}
+
+impl ThirdPartyTransport {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Fetch => "fetch",
+            Self::Redirect => "redirect",
+        }
+    }
+}
+
+/// One recorded third-party call.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ThirdPartyRequestRow {
+    /// Call timestamp, `YYYY-MM-DD HH:MM:SS.mmm` UTC.
+    pub event_ts: String,
+    /// Publisher domain the call was made for.
+    pub publisher_domain: String,
+    /// Integration that made the call, or `proxy`/`first_party_*` for core routes.
+    pub integration: String,
+    /// Destination host, lowercased, without port.
+    pub destination_host: String,
+    /// HTTP method.
+    pub method: String,
+    /// `fetch` or `redirect`.
+    pub transport: String,
+    /// Upstream status, or `0` when no response was received.
+    pub status: u16,
+    /// Response body size from `Content-Length`, when the upstream sent one.
+    pub response_bytes: Option<u64>,
+    /// Time until response headers arrived.
+    pub latency_ms: u32,
+    /// `1` when the host matches `proxy.allowed_domains`.
+    pub allowlisted: u8,
+}
+
+/// Calls drained from a [`ThirdPartyRequestLog`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ThirdPartyRequestBatch {
+    rows: Vec<ThirdPartyRequestRow>,
+}
+
+impl ThirdPartyRequestBatch {
+    /// Build a batch from rows.
+    #[must_use]
+    pub fn new(rows: Vec<ThirdPartyRequestRow>) -> Self {
+        Self { rows }
+    }
+
+    /// Return the drained rows.
+    #[must_use]
+    pub fn rows(&self) -> &[ThirdPartyRequestRow] {
+        &self.rows
+    }
+
+    /// Return the number of rows.
+    #[must_use]
+    pub fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Return whether the batch has no rows.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Serialize rows as newline-delimited JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when a row cannot be serialized or the payload exceeds
+    /// `max_body_bytes`.
+    pub fn to_ndjson(&self, max_body_bytes: usize) -> Result<String, Report<TrustedServerError>> {
+        let mut body = String::new();
+        for row in &self.rows {
+            let line = serde_json::to_string(row).map_err(|err| {
+                Report::new(TrustedServerError::Proxy {
+                    message: format!("failed to serialize third-party request row: {err}"),
+                })
+            })?;
+            body.push_str(&line);
+            body.push('\n');
+            if body.len() > max_body_bytes {
+                return Err(Report::new(TrustedServerError::Proxy {
+                    message: format!(
+                        "third-party request payload exceeds {max_body_bytes} byte limit"
+                    ),
+                }));
+            }
+        }
+        Ok(body)
+    }
+}
+
+/// Per-request buffer of third-party calls.
+///
+/// The default log is unsampled and records nothing, so adapters without a
+/// sink pay only the sampling check.
+#[derive(Debug, Default)]
+pub struct ThirdPartyRequestLog {
+    sampled: bool,
+    rows: Mutex<Vec<ThirdPartyRequestRow>>,
+}
+
+impl ThirdPartyRequestLog {
+    /// Build a log that records when `sampled` is true.
+    #[must_use]
+    pub fn new(sampled: bool) -> Self {
+        Self {
+            sampled,
+            rows: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Build a log sampled at `rate` (`0.0`–`1.0`).
+    #[must_use]
+    pub fn with_sample_rate(rate: f64) -> Self {
+        Self::new(rate > 0.0 && rand::random::<f64>() < rate)
+    }
+
+    /// Return whether calls made during this request are recorded.
+    #[must_use]
+    pub fn is_recording(&self) -> bool {
+        self.sampled
+    }
+
+    fn push(&self, row: ThirdPartyRequestRow) {
+        let mut rows = self.rows.lock().unwrap_or_else(PoisonError::into_inner);
+        if rows.len() < MAX_ROWS_PER_REQUEST {
+            rows.push(row);
+        }
+    }
+
+    /// Drain the buffered rows.
+    #[must_use]
+    pub fn take_batch(&self) -> ThirdPartyRequestBatch {
+        let mut rows = self.rows.lock().unwrap_or_else(PoisonError::into_inner);
+        ThirdPartyRequestBatch {
+            rows: std::mem::take(&mut *rows),
+        }
+    }
+}
+
+/// Sink for third-party request rows flushed after the response.
+#[async_trait::async_trait(?Send)]
+pub trait ThirdPartyRequestSink: Send + Sync {
+    /// Return whether this sink emits anything.
+    fn is_enabled(&self) -> bool {
+        true
+    }
+
+    /// Emit one drained batch.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the sink cannot start emission. Callers should use
+    /// [`flush_third_party_requests_best_effort`].
+    async fn emit_third_party_requests(
+        &self,
+        services: &RuntimeServices,
+        batch: ThirdPartyRequestBatch,
+    ) -> Result<(), Report<TrustedServerError>>;
+}
+
+/// No-op sink used when the transparency log is disabled.
+pub struct NoopThirdPartyRequestSink;
+
+#[async_trait::async_trait(?Send)]
+impl ThirdPartyRequestSink for NoopThirdPartyRequestSink {
+    fn is_enabled(&self) -> bool {
+        false
+    }
+
+    async fn emit_third_party_requests(
+        &self,
+        _services: &RuntimeServices,
+        _batch: ThirdPartyRequestBatch,
+    ) -> Result<(), Report<TrustedServerError>> {
+        Ok(())
+    }
+}
+
+/// Drain the request's third-party calls into the configured sink without
+/// letting errors affect customer traffic.
+pub async fn flush_third_party_requests_best_effort(services: &RuntimeServices) {
+    let sink = services.third_party_sink();
+    if !sink.is_enabled() {
+        return;
+    }
+    let batch = services.third_party_log().take_batch();
+    if batch.is_empty() {
+        return;
+    }
+    if let Err(err) = sink.emit_third_party_requests(services, batch).await {
+        log::warn!("third-party request log flush skipped: {err:?}");
+    }
+}
+
+/// Identity of one outbound call.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ThirdPartyCall<'a> {
+    /// Integration ID, or a core route label.
+    pub(crate) integration: &'a str,
+    /// Destination host.
+    pub(crate) host: &'a str,
+    /// HTTP method.
+    pub(crate) method: &'a Method,
+    /// How the third party was reached.
+    pub(crate) transport: ThirdPartyTransport,
+}
+
+/// Record one outbound call when the request is sampled.
+///
+/// `response` is `None` when the call failed before response headers arrived.
+pub(crate) fn record_third_party_call(
+    services: &RuntimeServices,
+    settings: &Settings,
+    call: ThirdPartyCall<'_>,
+    response: Option<&Response<EdgeBody>>,
+    latency: Duration,
+) {
+    let log = services.third_party_log();
+    if !log.is_recording() {
+        return;
+    }
+
+    let host = call.host.to_ascii_lowercase();
+    let allowlisted = settings
+        .proxy
+        .allowed_domains
+        .iter()
+        .any(|pattern| is_host_allowed(&host, pattern));
+    log.push(ThirdPartyRequestRow {
+        event_ts: Utc::now().format("%Y-%m-%d %H:%M:%S%.3f").to_string(),
+        publisher_domain: settings.publisher.domain.clone(),
+        integration: call.integration.to_owned(),
+        destination_host: host,
+        method: call.method.as_str().to_owned(),
+        transport: call.transport.as_str().to_owned(),
+        status: response.map_or(0, |response| response.status().as_u16()),
+        response_bytes: response.and_then(|response| {
+            response
+                .headers()
+                .get(header::CONTENT_LENGTH)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse().ok())
+        }),
+        latency_ms: u32::try_from(latency.as_millis()).unwrap_or(u32::MAX),
+        allowlisted: u8::from(allowlisted),
+    });
+}
+
+/// Send an outbound request through the platform client and record it.
+///
+/// Drop-in replacement for `services.http_client().send(request)` on paths
+/// that reach third parties on the publisher's behalf.
+///
+/// # Errors
+///
+/// Returns the platform client's error unchanged.
+pub(crate) async fn send_third_party_request(
+    services: &RuntimeServices,
+    settings: &Settings,
+    integration: &str,
+    request: PlatformHttpRequest,
+) -> Result<PlatformResponse, Report<PlatformError>> {
+    let host = request.request.uri().host().unwrap_or_default().to_owned();
+    let method = request.request.method().clone();
+    let started = Instant::now();
+    let result = services.http_client().send(request).await;
+    record_third_party_call(
+        services,
+        settings,
+        ThirdPartyCall {
+            integration,
+            host: &host,
+            method: &method,
+            transport: ThirdPartyTransport::Fetch,
+        },
+        result.as_ref().ok().map(|response| &response.response),
+        started.elapsed(),
+    );
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use http::StatusCode;
+
+    use super::*;
+    use crate::platform::test_support::{StubHttpClient, build_services_with_http_client};
+    use crate::test_support::tests::create_test_settings;
+
+    fn recording_services(http_client: Arc<StubHttpClient>) -> RuntimeServices {
+        build_services_with_http_client(http_client)
+            .with_third_party_log(Arc::new(ThirdPartyRequestLog::new(true)))
+    }
+
+    fn outbound_request(uri: &str) -> PlatformHttpRequest {
+        let request = http::Request::builder()
+            .method(Method::GET)
+            .uri(uri)
+            .body(EdgeBody::empty())
+            .expect("should build request");
+        PlatformHttpRequest::new(request, "backend")
+    }
+
+    #[test]
+    fn unsampled_log_records_nothing() {
+        let settings = create_test_settings();
+        let services = build_services_with_http_client(Arc::new(StubHttpClient::new()));
+
+        record_third_party_call(
+            &services,
+            &settings,
+            ThirdPartyCall {
+                integration: "lockr",
+                host: "aim.loc.kr",
+                method: &Method::GET,
+                transport: ThirdPartyTransport::Fetch,
+            },
+            None,
+            Duration::from_millis(5),
+        );
+
+        assert!(
+            services.third_party_log().take_batch().is_empty(),
+            "should not record calls for unsampled requests"
+        );
+    }
+
+    #[test]
+    fn send_records_host_status_bytes_and_allowlist() {
+        let mut settings = create_test_settings();
+        settings.proxy.allowed_domains = vec!["*.loc.kr".to_string()];
+        let http_client = Arc::new(StubHttpClient::new());
+        http_client.push_response_with_headers(
+            200,
+            b"console.log(1)".to_vec(),
+            vec![("content-length", "14")],
+        );
+        let services = recording_services(http_client);
+
+        futures::executor::block_on(send_third_party_request(
+            &services,
+            &settings,
+            "lockr",
+            outbound_request("https://AIM.loc.kr/identity-lockr.js?uid=secret"),
+        ))
+        .expect("should send request");
+
+        let batch = services.third_party_log().take_batch();
+        assert_eq!(batch.row_count(), 1, "should record one call");
+        let row = &batch.rows()[0];
+        assert_eq!(row.integration, "lockr", "should record integration");
+        assert_eq!(
+            row.destination_host, "aim.loc.kr",
+            "should record host only"
+        );
+        assert_eq!(row.method, "GET", "should record method");
+        assert_eq!(row.transport, "fetch", "should record fetch transport");
+        assert_eq!(row.status, 200, "should record upstream status");
+        assert_eq!(row.response_bytes, Some(14), "should record Content-Length");
+        assert_eq!(row.allowlisted, 1, "should match proxy.allowed_domains");
+    }
+
+    #[test]
+    fn failed_send_records_zero_status_outside_allowlist() {
+        let settings = create_test_settings();
+        let services = recording_services(Arc::new(StubHttpClient::new()));
+
+        let result = futures::executor::block_on(send_third_party_request(
+            &services,
+            &settings,
+            "permutive",
+            outbound_request("https://api.permutive.com/v2.0/identify"),
+        ));
+
+        assert!(result.is_err(), "should surface the platform error");
+        let batch = services.third_party_log().take_batch();
+        assert_eq!(batch.row_count(), 1, "should record failed calls");
+        assert_eq!(
+            batch.rows()[0].status,
+            0,
+            "should record missing status as 0"
+        );
+        assert_eq!(batch.rows()[0].allowlisted, 0, "should flag unlisted hosts");
+    }
+
+    #[test]
+    fn batch_serializes_rows_as_ndjson() {
+        let settings = create_test_settings();
+        let services = recording_services(Arc::new(StubHttpClient::new()));
+        let mut response = Response::new(EdgeBody::empty());
+        *response.status_mut() = StatusCode::FOUND;
+
+        for host in ["a.example", "b.example"] {
+            record_third_party_call(
+                &services,
+                &settings,
+                ThirdPartyCall {
+                    integration: "first_party_click",
+                    host,
+                    method: &Method::GET,
+                    transport: ThirdPartyTransport::Redirect,
+                },
+                Some(&response),
+                Duration::ZERO,
+            );
+        }
+
+        let body = services
+            .third_party_log()
+            .take_batch()
+            .to_ndjson(4096)
+            .expect("should serialize batch");
+        assert_eq!(body.lines().count(), 2, "should emit one line per row");
+        assert!(
+            body.contains("\"transport\":\"redirect\"") && body.contains("\"status\":302"),
+            "should serialize redirect rows: {body}"
+        );
+    }
+}
@@ -0,0 +1,209 @@
+//! Tinybird Events API client shared by telemetry sinks.
+//!
+//! Core sinks (access logs, metrics) and adapter sinks (auction events, the
+//! third-party request log) all append newline-delimited JSON to a Tinybird
+//! datasource through the platform HTTP client, so the same target works on
+//! every adapter.
+
+use std::time::Duration;
+
+use edgezero_core::body::Body as EdgeBody;
+use edgezero_core::http::{Request as EdgeRequest, request_builder as edge_request_builder};
+use error_stack::{Report, ResultExt as _};
+use http::{HeaderValue, Method, header};
+
+use crate::error::TrustedServerError;
+use crate::platform::{PlatformBackendSpec, PlatformHttpRequest, RuntimeServices, StoreName};
+use crate::settings::TinybirdSettings;
+
+const TINYBIRD_EVENTS_PATH: &str = "/v0/events";
+/// Content type for Events API request bodies.
+pub const TINYBIRD_NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+const TINYBIRD_FIRST_BYTE_TIMEOUT: Duration = Duration::from_secs(2);
+const TINYBIRD_BETWEEN_BYTES_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// One Tinybird Events API datasource plus the credentials to append to it.
+#[derive(Debug, Clone)]
+pub struct TinybirdEventsTarget {
+    kind: &'static str,
+    api_host: String,
+    dataset: String,
+    secret_store: StoreName,
+    token_secret: String,
+    uri: String,
+    backend_spec: PlatformBackendSpec,
+    max_body_bytes: usize,
+}
+
+impl TinybirdEventsTarget {
+    /// Build a target for `dataset`, authenticated by the APPEND token stored
+    /// under `token_secret`. `kind` labels log lines and errors.
+    #[must_use]
+    pub fn new(
+        kind: &'static str,
+        config: &TinybirdSettings,
+        dataset: &str,
+        token_secret: &str,
+    ) -> Self {
+        Self {
+            kind,
+            api_host: config.api_host.clone(),
+            dataset: dataset.to_owned(),
+            secret_store: StoreName::from(config.secret_store.clone()),
+            token_secret: token_secret.to_owned(),
+            uri: tinybird_events_uri(&config.api_host, dataset),
+            backend_spec: tinybird_backend_spec(&config.api_host),
+            max_body_bytes: config.max_body_bytes,
+        }
+    }
+
+    fn load_append_token(
+        &self,
+        services: &RuntimeServices,
+    ) -> Result<String, Report<TrustedServerError>> {
+        let token = services
+            .secret_store()
+            .get_string(&self.secret_store, &self.token_secret)
+            .change_context(TrustedServerError::Proxy {
+                message: format!("Tinybird {} append token unavailable", self.kind),
+            })?;
+        let token = token.trim().to_owned();
+        if token.is_empty() {
+            return Err(Report::new(TrustedServerError::Proxy {
+                message: format!("Tinybird {} append token is empty", self.kind),
+            }));
+        }
+        Ok(token)
+    }
+
+    fn ensure_backend(
+        &self,
+        services: &RuntimeServices,
+    ) -> Result<String, Report<TrustedServerError>> {
+        services
+            .backend()
+            .ensure(&self.backend_spec)
+            .change_context(TrustedServerError::Proxy {
+                message: "Tinybird backend registration failed".to_owned(),
+            })
+    }
+
+    fn authorization_header(token: &str) -> Result<HeaderValue, Report<TrustedServerError>> {
+        HeaderValue::from_str(&format!("Bearer {token}")).change_context(
+            TrustedServerError::InvalidHeaderValue {
+                message: "invalid Tinybird authorization header".to_owned(),
+            },
+        )
+    }
+
+    fn build_events_request(
+        &self,
+        body: String,
+        auth_header: HeaderValue,
+    ) -> Result<EdgeRequest, Report<TrustedServerError>> {
+        edge_request_builder()
+            .method(Method::POST)
+            .uri(self.uri.as_str())
+            .header(header::AUTHORIZATION, auth_header)
+            .header(header::CONTENT_TYPE, TINYBIRD_NDJSON_CONTENT_TYPE)
+            .body(EdgeBody::from(body))
+            .change_context(TrustedServerError::Proxy {
+                message: "failed to build Tinybird Events API request".to_owned(),
+            })
+    }
+
+    /// Return the configured NDJSON body limit.
+    #[must_use]
+    pub fn max_body_bytes(&self) -> usize {
+        self.max_body_bytes
+    }
+
+    /// Start an NDJSON POST to the dataset without waiting for the response.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the append token is missing, the backend cannot
+    /// be registered, or the request cannot be started.
+    pub async fn post_ndjson(
+        &self,
+        services: &RuntimeServices,
+        body: String,
+        row_count: usize,
+    ) -> Result<(), Report<TrustedServerError>> {
+        let body_len = body.len();
+        let token = self.load_append_token(services)?;
+        let auth_header = Self::authorization_header(&token)?;
+        let backend_name = self.ensure_backend(services)?;
+        let request = self.build_events_request(body, auth_header)?;
+
+        log::info!(
+            "sending {} telemetry to Tinybird dataset={} rows={} bytes={} host={} backend={}",
+            self.kind,
+            self.dataset,
+            row_count,
+            body_len,
+            self.api_host,
+            backend_name
+        );
+
+        let pending = services
+            .http_client()
+            .send_async(PlatformHttpRequest::new(request, backend_name))
+            .await
+            .change_context(TrustedServerError::Proxy {
+                message: "failed to start Tinybird Events API request".to_owned(),
+            })?;
+        drop(pending);
+        Ok(())
+    }
+}
+
+fn tinybird_backend_spec(api_host: &str) -> PlatformBackendSpec {
+    PlatformBackendSpec {
+        scheme: "https".to_owned(),
+        host: api_host.to_owned(),
+        port: None,
+        host_header_override: None,
+        certificate_check: true,
+        first_byte_timeout: TINYBIRD_FIRST_BYTE_TIMEOUT,
+        between_bytes_timeout: TINYBIRD_BETWEEN_BYTES_TIMEOUT,
+        discriminator: None,
+    }
+}
+
+fn tinybird_events_uri(api_host: &str, dataset: &str) -> String {
+    format!(
+        "https://{api_host}{TINYBIRD_EVENTS_PATH}?name={}",
+        urlencoding::encode(dataset)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_uri_targets_dataset_on_region_host() {
+        assert_eq!(
+            tinybird_events_uri("api.us-east.aws.tinybird.co", "auction_events_raw"),
+            "https://api.us-east.aws.tinybird.co/v0/events?name=auction_events_raw"
+        );
+    }
+
+    #[test]
+    fn events_uri_urlencodes_dataset_name() {
+        assert_eq!(
+            tinybird_events_uri("api.us-east.aws.tinybird.co", "auction events/raw"),
+            "https://api.us-east.aws.tinybird.co/v0/events?name=auction%20events%2Fraw"
+        );
+    }
+
+    #[test]
+    fn backend_spec_uses_matching_tls_host() {
+        let spec = tinybird_backend_spec("api.us-east.aws.tinybird.co");
+        assert_eq!(spec.scheme, "https");
+        assert_eq!(spec.host, "api.us-east.aws.tinybird.co");
+        assert_eq!(spec.host_header_override, None);
+        assert!(spec.certificate_check, "should verify Tinybird TLS cert");
+    }
+}
@@ -0,0 +1,567 @@
+//! VAST creative rewriting.
+//!
+//! Video demand returns VAST 2/3/4 XML instead of HTML. The rewriter routes the
+//! URLs a video player fetches through first-party endpoints, matching what
+//! [`crate::creative`] does for HTML and CSS creatives:
+//!
+//! - `<MediaFile>`, `<Impression>`, `<Tracking>`, `<ClickTracking>`, `<Error>`,
+//!   `<StaticResource>` and `<IFrameResource>` → signed `/first-party/proxy`
+//! - `<ClickThrough>`, `<NonLinearClickThrough>` and `<CompanionClickThrough>`
+//!   → signed `/first-party/click`
+//! - Wrapper `<VASTAdTagURI>` → signed `/first-party/proxy` carrying the next
+//!   wrapper depth in the signed `tsvast` parameter. The proxy rewrites the
+//!   wrapped document again, so the whole chain stays first-party. Past
+//!   `proxy.vast_max_wrapper_depth` hops the document is replaced with an empty
+//!   (no-ad) VAST response.
+//!
+//! `proxy.allowed_domains` applies to every URL: a URL whose host is not
+//! allowed is emptied, and a wrapper pointing at such a host yields the no-ad
+//! response. URLs carrying player macros such as `[ERRORCODE]` or
+//! `[CACHEBUSTING]` keep their direct form, because the player substitutes
+//! them after delivery and a signature would no longer match.
+//!
+//! Rewritten values are emitted as CDATA. Relative URLs, non-network schemes
+//! and URLs excluded by `[rewrite]` are left unchanged.
+//!
+//! [`CreativeVastProcessor`] plugs into the streaming pipeline but does not
+//! rewrite incrementally: it buffers the whole document, up to 2 MiB, and
+//! rewrites it when the body ends. A wrapper past the depth limit replaces the
+//! entire document with the no-ad response, so nothing can be emitted before
+//! every `<VASTAdTagURI>` has been seen, and a URL split across chunks would
+//! otherwise need its own carry-over parser. VAST documents are small, so the
+//! buffer bounds memory without delaying playback noticeably.
+
+use std::io;
+
+use crate::creative::{build_click_url, build_proxy_url, build_proxy_url_with_extras, to_abs};
+use crate::proxy::is_host_allowed;
+use crate::settings::Settings;
+use crate::streaming_processor::StreamProcessor;
+
+/// Signed query parameter carrying the wrapper depth of a proxied VAST tag.
+pub const VAST_WRAPPER_DEPTH_PARAM: &str = "tsvast";
+
+/// Maximum size of a VAST document buffered for rewriting.
+const MAX_VAST_BODY_SIZE: usize = 2 * 1024 * 1024;
+
+/// Elements whose URL the player fetches; routed through the proxy.
+const PROXIED_ELEMENTS: &[&str] = &[
+    "MediaFile",
+    "Impression",
+    "Tracking",
+    "ClickTracking",
+    "Error",
+    "StaticResource",
+    "IFrameResource",
+];
+
+/// Elements whose URL the viewer navigates to; routed through the click endpoint.
+const CLICK_ELEMENTS: &[&str] = &[
+    "ClickThrough",
+    "NonLinearClickThrough",
+    "CompanionClickThrough",
+];
+
+const WRAPPER_ELEMENT: &str = "VASTAdTagURI";
+
+/// Returns `true` when `body` is a VAST document.
+///
+/// Skips a byte-order mark, whitespace, the XML declaration and comments
+/// before checking for a `<VAST` root element.
+#[must_use]
+pub fn is_vast_document(body: &str) -> bool {
+    let mut rest = body.trim_start_matches('\u{feff}').trim_start();
+    loop {
+        if rest.starts_with("<?") {
+            let Some(end) = rest.find("?>") else {
+                return false;
+            };
+            rest = rest[end + 2..].trim_start();
+        } else if rest.starts_with("<!--") {
+            let Some(end) = rest.find("-->") else {
+                return false;
+            };
+            rest = rest[end + 3..].trim_start();
+        } else {
+            return starts_element(rest, "VAST");
+        }
+    }
+}
+
+/// Rewrite a VAST document's URLs to signed first-party endpoints.
+///
+/// `depth` is the wrapper depth of this document: `0` for a document taken
+/// from a bid, and the signed `tsvast` value for one fetched through the
+/// proxy. `base_origin` is prefixed onto the first-party paths, as in
+/// [`crate::creative::rewrite_inline_creative_html`].
+#[must_use]
+pub fn rewrite_vast(settings: &Settings, xml: &str, base_origin: &str, depth: u8) -> String {
+    let mut out = String::with_capacity(xml.len() + xml.len() / 2);
+    let mut rest = xml;
+    while let Some(open) = rest.find('<') {
+        out.push_str(&rest[..open]);
+        let tail = &rest[open..];
+        if let Some(len) = markup_section_len(tail) {
+            out.push_str(&tail[..len]);
+            rest = &tail[len..];
+            continue;
+        }
+        let target = PROXIED_ELEMENTS
+            .iter()
+            .map(|name| (*name, UrlKind::Proxy))
+            .chain(CLICK_ELEMENTS.iter().map(|name| (*name, UrlKind::Click)))
+            .chain(std::iter::once((WRAPPER_ELEMENT, UrlKind::Wrapper)))
+            .find(|(name, _)| starts_element(&tail[1..], name));
+        let Some((name, kind)) = target else {
+            out.push('<');
+            rest = &tail[1..];
+            continue;
+        };
+        let Some(element) = UrlElement::parse(tail, name) else {
+            out.push('<');
+            rest = &tail[1..];
+            continue;
+        };
+        match rewrite_url(settings, &element.url(), kind, base_origin, depth) {
+            Rewrite::Keep => out.push_str(&tail[..element.len]),
+            Rewrite::Replace(url) => {
+                out.push_str(element.start_tag);
+                out.push_str("<![CDATA[");
+                out.push_str(&url);
+                out.push_str("]]>");
+                out.push_str(element.end_tag);
+            }
+            Rewrite::Remove => {
+                out.push_str(element.start_tag);
+                out.push_str(element.end_tag);
+            }
+            Rewrite::NoAd => return empty_vast(xml),
+        }
+        rest = &tail[element.len..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Stream processor for proxied VAST documents.
+///
+/// Buffers the whole response, up to `MAX_VAST_BODY_SIZE`, and rewrites it
+/// with [`rewrite_vast`] when the stream ends; see the module docs for why it
+/// does not rewrite chunk by chunk. XML that is not a VAST document passes
+/// through unchanged.
+pub struct CreativeVastProcessor<'a> {
+    settings: &'a Settings,
+    depth: u8,
+    buffer: Vec<u8>,
+}
+
+impl<'a> CreativeVastProcessor<'a> {
+    /// Create a processor for a document at wrapper depth `depth`.
+    #[must_use]
+    pub fn new(settings: &'a Settings, depth: u8) -> Self {
+        Self {
+            settings,
+            depth,
+            buffer: Vec::new(),
+        }
+    }
+}
+
+impl StreamProcessor for CreativeVastProcessor<'_> {
+    fn process_chunk(&mut self, chunk: &[u8], is_last: bool) -> Result<Vec<u8>, io::Error> {
+        if self.buffer.len() + chunk.len() > MAX_VAST_BODY_SIZE {
+            return Err(io::Error::other(format!(
+                "XML response body exceeds maximum rewritable size of {MAX_VAST_BODY_SIZE} bytes"
+            )));
+        }
+        self.buffer.extend_from_slice(chunk);
+
+        if !is_last {
+            return Ok(Vec::new());
+        }
+        let xml = String::from_utf8(std::mem::take(&mut self.buffer))
+            .map_err(|e| io::Error::other(format!("Invalid UTF-8 in XML: {e}")))?;
+        if !is_vast_document(&xml) {
+            return Ok(xml.into_bytes());
+        }
+        Ok(rewrite_vast(self.settings, &xml, "", self.depth).into_bytes())
+    }
+
+    fn reset(&mut self) {
+        self.buffer.clear();
+    }
+}
+
+#[derive(Clone, Copy)]
+enum UrlKind {
+    Proxy,
+    Click,
+    Wrapper,
+}
+
+enum Rewrite {
+    Keep,
+    Replace(String),
+    Remove,
+    NoAd,
+}
+
+fn rewrite_url(
+    settings: &Settings,
+    url: &str,
+    kind: UrlKind,
+    base_origin: &str,
+    depth: u8,
+) -> Rewrite {
+    let Some(abs) = to_abs(settings, url) else {
+        return Rewrite::Keep;
+    };
+    let host = url::Url::parse(&abs)
+        .ok()
+        .and_then(|parsed| parsed.host_str().map(str::to_owned))
+        .unwrap_or_default();
+    let allowed = &settings.proxy.allowed_domains;
+    if !allowed.is_empty()
+        && !allowed
+            .iter()
+            .any(|pattern| is_host_allowed(&host, pattern))
+    {
+        log::warn!("vast: dropping URL to `{host}`: host not in proxy allowed_domains");
+        return match kind {
+            UrlKind::Wrapper => Rewrite::NoAd,
+            UrlKind::Proxy | UrlKind::Click => Rewrite::Remove,
+        };
+    }
+    match kind {
+        UrlKind::Wrapper => {
+            let next = depth.saturating_add(1);
+            if next > settings.proxy.vast_max_wrapper_depth {
+                log::warn!(
+                    "vast: wrapper depth limit {} reached; returning an empty VAST response",
+                    settings.proxy.vast_max_wrapper_depth
+                );
+                return Rewrite::NoAd;
+            }
+            Rewrite::Replace(build_proxy_url_with_extras(
+                settings,
+                &abs,
+                base_origin,
+                &[(VAST_WRAPPER_DEPTH_PARAM.to_string(), next.to_string())],
+            ))
+        }
+        // Player macros are substituted after delivery; signing would freeze them.
+        _ if has_player_macro(&abs) => Rewrite::Keep,
+        UrlKind::Proxy => Rewrite::Replace(build_proxy_url(settings, &abs, base_origin)),
+        UrlKind::Click => Rewrite::Replace(build_click_url(settings, &abs, base_origin)),
+    }
+}
+
+/// A URL-carrying element: start tag, text content and end tag.
+struct UrlElement<'a> {
+    start_tag: &'a str,
+    content: &'a str,
+    end_tag: &'a str,
+    len: usize,
+}
+
+impl<'a> UrlElement<'a> {
+    /// Parse the element `name` at the start of `tail`. Returns `None` for a
+    /// self-closing or unterminated element.
+    fn parse(tail: &'a str, name: &str) -> Option<Self> {
+        let start_len = start_tag_len(tail)?;
+        let start_tag = &tail[..start_len];
+        if start_tag.ends_with("/>") {
+            return None;
+        }
+        let body = &tail[start_len..];
+        let close = format!("</{name}");
+        let content_len = find_outside_cdata(body, &close)?;
+        let after = &body[content_len..];
+        let end_len = after.find('>')? + 1;
+        Some(Self {
+            start_tag,
+            content: &body[..content_len],
+            end_tag: &after[..end_len],
+            len: start_len + content_len + end_len,
+        })
+    }
+
+    fn url(&self) -> String {
+        let trimmed = self.content.trim();
+        match trimmed
+            .strip_prefix("<![CDATA[")
+            .and_then(|inner| inner.strip_suffix("]]>"))
+        {
+            Some(inner) => inner.trim().to_owned(),
+            None => unescape_xml(trimmed),
+        }
+    }
+}
+
+/// Returns `true` when `s` starts with element `name` followed by a boundary.
+fn starts_element(s: &str, name: &str) -> bool {
+    let s = s.strip_prefix('<').unwrap_or(s);
+    s.strip_prefix(name).is_some_and(|after| {
+        after
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_whitespace() || c == '>' || c == '/')
+    })
+}
+
+/// Length of a CDATA section, comment or processing instruction at the start
+/// of `tail`, copied through verbatim so their contents are never rewritten.
+fn markup_section_len(tail: &str) -> Option<usize> {
+    let terminator = if tail.starts_with("<![CDATA[") {
+        "]]>"
+    } else if tail.starts_with("<!--") {
+        "-->"
+    } else if tail.starts_with("<?") {
+        "?>"
+    } else {
+        return None;
+    };
+    Some(
+        tail.find(terminator)
+            .map_or(tail.len(), |end| end + terminator.len()),
+    )
+}
+
+/// Length of the start tag at the start of `tail`, honouring quoted attributes.
+fn start_tag_len(tail: &str) -> Option<usize> {
+    let mut quote: Option<char> = None;
+    for (index, c) in tail.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '>') => return Some(index + 1),
+            (None, _) => {}
+        }
+    }
+    None
+}
+
+/// Find `needle` in `haystack`, skipping CDATA sections.
+fn find_outside_cdata(haystack: &str, needle: &str) -> Option<usize> {
+    let mut offset = 0;
+    loop {
+        let rest = &haystack[offset..];
+        let next_needle = rest.find(needle)?;
+        match rest.find("<![CDATA[") {
+            Some(cdata) if cdata < next_needle => {
+                let end = rest[cdata..].find("]]>")? + cdata + 3;
+                offset += end;
+            }
+            _ => return Some(offset + next_needle),
+        }
+    }
+}
+
+fn has_player_macro(url: &str) -> bool {
+    let mut rest = url;
+    while let Some(open) = rest.find('[') {
+        let after = &rest[open + 1..];
+        let Some(close) = after.find(']') else {
+            return false;
+        };
+        let token = &after[..close];
+        if !token.is_empty()
+            && token
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+        {
+            return true;
+        }
+        rest = after;
+    }
+    false
+}
+
+fn unescape_xml(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// An empty VAST response of the same version, which players treat as no ad.
+fn empty_vast(xml: &str) -> String {
+    let version = xml
+        .find("<VAST")
+        .and_then(|start| {
+            let tag = &xml[start..start + start_tag_len(&xml[start..])?];
+            let value = tag.split("version=").nth(1)?;
+            let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+            value[1..].split(quote).next().map(str::to_owned)
+        })
+        .filter(|version| {
+            !version.is_empty() && version.chars().all(|c| c.is_ascii_digit() || c == '.')
+        })
+        .unwrap_or_else(|| "4.0".to_string());
+    format!("<?xml version=\"1.0\" encoding=\"UTF-8\"?><VAST version=\"{version}\"/>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::tests::create_test_settings;
+
+    const INLINE_VAST: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<VAST version="4.0">
+  <Ad id="1">
+    <InLine>
+      <Impression id="imp"><![CDATA[https://track.example/imp?id=1]]></Impression>
+      <Error><![CDATA[https://track.example/err?code=[ERRORCODE]]]></Error>
+      <Creatives>
+        <Creative>
+          <Linear>
+            <TrackingEvents>
+              <Tracking event="start">https://track.example/start?a=1&amp;b=2</Tracking>
+            </TrackingEvents>
+            <VideoClicks>
+              <ClickThrough><![CDATA[https://landing.example/page]]></ClickThrough>
+            </VideoClicks>
+            <MediaFiles>
+              <MediaFile delivery="progressive" type="video/mp4" width="640" height="360">
+                <![CDATA[https://cdn.example/video.mp4]]>
+              </MediaFile>
+            </MediaFiles>
+          </Linear>
+        </Creative>
+      </Creatives>
+    </InLine>
+  </Ad>
+</VAST>"#;
+
+    fn wrapper_vast(tag_uri: &str) -> String {
+        format!(
+            r#"<VAST version="3.0"><Ad><Wrapper><VASTAdTagURI><![CDATA[{tag_uri}]]></VASTAdTagURI><Impression><![CDATA[https://track.example/w]]></Impression></Wrapper></Ad></VAST>"#
+        )
+    }
+
+    fn cdata_values<'a>(xml: &'a str, element: &str) -> Vec<&'a str> {
+        xml.split(&format!("<{element}"))
+            .skip(1)
+            .filter_map(|part| {
+                let inner = part.split("<![CDATA[").nth(1)?;
+                inner.split("]]>").next()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn detects_vast_documents_after_prolog() {
+        assert!(is_vast_document(INLINE_VAST));
+        assert!(is_vast_document(
+            "\u{feff}<!-- ad --><VAST version=\"2.0\"/>"
+        ));
+        assert!(!is_vast_document("<VASTly/>"));
+        assert!(!is_vast_document("<html><body>VAST</body></html>"));
+    }
+
+    #[test]
+    fn rewrites_inline_urls_to_signed_first_party_endpoints() {
+        let settings = create_test_settings();
+        let out = rewrite_vast(&settings, INLINE_VAST, "", 0);
+
+        for element in ["Impression", "Tracking", "MediaFile"] {
+            let values = cdata_values(&out, element);
+            assert_eq!(values.len(), 1, "should rewrite one {element}: {out}");
+            assert!(
+                values[0].starts_with("/first-party/proxy?tsurl="),
+                "should proxy {element}: {}",
+                values[0]
+            );
+        }
+        let tracking = cdata_values(&out, "Tracking")[0];
+        assert!(
+            tracking.contains("a=1&b=2"),
+            "should unescape entities before signing: {tracking}"
+        );
+        assert!(
+            cdata_values(&out, "ClickThrough")[0].starts_with("/first-party/click?tsurl="),
+            "should route ClickThrough through the click endpoint"
+        );
+        assert!(
+            out.contains("https://track.example/err?code=[ERRORCODE]"),
+            "should leave URLs with player macros direct"
+        );
+        assert!(
+            out.contains("<TrackingEvents>"),
+            "should keep container elements"
+        );
+    }
+
+    #[test]
+    fn wrapper_tag_carries_signed_next_depth() {
+        let settings = create_test_settings();
+        let out = rewrite_vast(
+            &settings,
+            &wrapper_vast("https://ads.example/vast?id=9"),
+            "https://pub.example",
+            1,
+        );
+
+        let tag = cdata_values(&out, "VASTAdTagURI")[0];
+        assert!(
+            tag.starts_with("https://pub.example/first-party/proxy?tsurl="),
+            "should proxy the wrapped tag against the base origin: {tag}"
+        );
+        assert!(
+            tag.contains("tsvast=2&tstoken="),
+            "should sign the next wrapper depth: {tag}"
+        );
+    }
+
+    #[test]
+    fn wrapper_past_max_depth_becomes_empty_vast() {
+        let mut settings = create_test_settings();
+        settings.proxy.vast_max_wrapper_depth = 2;
+
+        let out = rewrite_vast(&settings, &wrapper_vast("https://ads.example/vast"), "", 2);
+
+        assert_eq!(
+            out, "<?xml version=\"1.0\" encoding=\"UTF-8\"?><VAST version=\"3.0\"/>",
+            "should answer with an empty VAST document"
+        );
+    }
+
+    #[test]
+    fn enforces_proxy_allowed_domains() {
+        let settings = create_test_settings();
+        let xml = r#"<VAST version="4.0"><Ad><InLine><Impression><![CDATA[https://tracker.blocked.test/i]]></Impression></InLine></Ad></VAST>"#;
+
+        let out = rewrite_vast(&settings, xml, "", 0);
+        assert!(
+            out.contains("<Impression></Impression>"),
+            "should empty URLs outside allowed_domains: {out}"
+        );
+
+        let out = rewrite_vast(
+            &settings,
+            &wrapper_vast("https://ads.blocked.test/vast"),
+            "",
+            0,
+        );
+        assert!(
+            !out.contains("blocked.test"),
+            "should not follow a wrapper outside allowed_domains: {out}"
+        );
+    }
+
+    #[test]
+    fn processor_passes_non_vast_xml_through() {
+        let settings = create_test_settings();
+        let xml = "<rss><item>https://cdn.example/a.mp4</item></rss>";
+        let mut processor = CreativeVastProcessor::new(&settings, 0);
+
+        let out = processor
+            .process_chunk(xml.as_bytes(), true)
+            .expect("should process XML");
+
+        assert_eq!(out, xml.as_bytes());
+    }
+}
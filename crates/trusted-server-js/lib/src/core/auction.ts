@@ -58,6 +58,8 @@ export interface AuctionBid {
   seat: string;
   /** Creative ID. */
   creativeId: string;
+  /** PMP or programmatic guaranteed deal ID, when the bid is a deal bid. */
+  dealId?: string;
   /** Advertiser domains. */
   adomain: string[];
 }
@@ -154,6 +156,7 @@ export function parseAuctionResponse(body: any): AuctionBid[] {
         height,
         seat,
         creativeId,
+        ...(typeof bid?.dealid === 'string' && bid.dealid ? { dealId: bid.dealid } : {}),
         adomain: Array.isArray(bid?.adomain)
           ? bid.adomain.filter((domain: unknown): domain is string => typeof domain === 'string')
           : [],
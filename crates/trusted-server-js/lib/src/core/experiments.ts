@@ -0,0 +1,16 @@
+// Experiment arms the edge assigned to this browser, read from the `ts-exp` cookie.
+const EXPERIMENT_COOKIE = 'ts-exp';
+
+// Parse `experiment:arm` pairs joined by `|` into an experiment → arm map.
+export function readExperimentArms(cookieString: string): Record<string, string> {
+  const prefix = `${EXPERIMENT_COOKIE}=`;
+  const entry = cookieString.split('; ').find((cookie) => cookie.startsWith(prefix));
+  const arms: Record<string, string> = {};
+  if (!entry) return arms;
+  for (const pair of entry.slice(prefix.length).split('|')) {
+    const sep = pair.indexOf(':');
+    if (sep <= 0 || sep === pair.length - 1) continue;
+    arms[pair.slice(0, sep)] = pair.slice(sep + 1);
+  }
+  return arms;
+}
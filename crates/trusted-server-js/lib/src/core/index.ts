@@ -13,6 +13,7 @@ import { log } from './log';
 import { setConfig, getConfig } from './config';
 import { requestAds } from './request';
 import { installQueue } from './queue';
+import { readExperimentArms } from './experiments';
 
 const VERSION = '0.1.0';
 
@@ -42,6 +43,9 @@ api.requestAds = requestAds;
 // values instead of throwing. Injected scripts overwrite these wholesale.
 api.adSlots ??= [];
 api.bids ??= {};
+// Arms of the edge's traffic-split experiments, so page code and analytics
+// can segment by arm. The edge keeps the cookie in sync on every response.
+api.experiments = readExperimentArms(typeof document === 'undefined' ? '' : document.cookie);
 // Point global tsjs
 w.tsjs = api;
 
@@ -27,6 +27,17 @@ export interface AuctionSlot {
   div_id: string;
   formats: Array<[number, number]>;
   targeting?: Record<string, string>;
+  /** Auction this slot through the slot-auction endpoint once it nears the viewport. */
+  lazy_load?: boolean;
+  /** In-page refresh policy. Absent when the slot does not refresh. */
+  refresh?: AuctionSlotRefresh;
+}
+
+/** Refresh policy of an {@link AuctionSlot}. The server re-checks every limit. */
+export interface AuctionSlotRefresh {
+  interval_secs: number;
+  max_refreshes: number;
+  require_viewable: boolean;
 }
 
 /** Debug-only copy of server-side bid fields exposed for pipeline inspection. */
@@ -83,6 +94,8 @@ export interface AuctionBidData {
   hb_pb?: string;
   hb_bidder?: string;
   hb_adid?: string;
+  /** Deal ID of the winning bid, present only for PMP and PG demand. */
+  hb_deal?: string;
   hb_cache_host?: string;
   hb_cache_path?: string;
   /** Opaque server-auction correlation ID used only by GPT diagnostics. */
@@ -93,6 +106,12 @@ export interface AuctionBidData {
   h?: number;
   nurl?: string;
   burl?: string;
+  /**
+   * Opaque event token posted to `/_ts/api/v1/event` on render, viewability
+   * and click. Present when `[events]` is enabled; with server-side
+   * notifications `nurl`/`burl` are then omitted.
+   */
+  ts_event?: string;
   /** Typed winning-bid renderer capability. */
   renderer?: AuctionBidRenderer;
   /** Winning creative width used by the inline render bridge. */
@@ -392,6 +411,8 @@ export interface TsjsApi {
   adSlots?: AuctionSlot[];
   /** Winning bid targeting data injected before </body>. */
   bids?: Record<string, AuctionBidData>;
+  /** Experiment ID → assigned arm ID, read from the edge-set `ts-exp` cookie. */
+  experiments?: Record<string, string>;
   /**
    * Bounded client-side Prebid APS renderer capabilities keyed by Prebid's generated
    * `hb_adid`. The Universal Creative bridge consumes each entry at most once.
@@ -409,6 +430,7 @@ export interface TsjsApi {
    * Win/billing beacons already fired, keyed by `slotId|bidIdentity|kind|url`.
    * Used by the GPT render bridge so a bid's nurl/burl fire at most once even
    * across repeated Prebid Universal Creative requests for the same adId.
+   * Creative events share the map, keyed by `slotId|token|type`.
    */
   firedBeacons?: Record<string, boolean>;
   /** Slot-level GPT targeting keys TS applied on the previous route. */
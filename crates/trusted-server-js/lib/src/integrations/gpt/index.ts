@@ -17,6 +17,13 @@ import {
 } from '../aps/render';
 
 import { installGptGuard } from './script_guard';
+import {
+  SlotScheduler,
+  fetchSlotAuction,
+  slotAuctionEntry,
+  type SlotAuctionReason,
+  type SlotLifecycle,
+} from './slot_auction';
 
 /**
  * Google Publisher Tags (GPT) Integration Shim
@@ -44,6 +51,7 @@ const TS_BID_TARGETING_KEYS = [
   'hb_pb',
   'hb_bidder',
   'hb_adid',
+  'hb_deal',
   'hb_cache_host',
   'hb_cache_path',
 ] as const;
@@ -529,7 +537,48 @@ function injectAdmIntoSlot(divId: string, adm: string): void {
   }
 }
 
+/** Trusted Server creative lifecycle event endpoint. */
+const EVENT_BEACON_PATH = '/_ts/api/v1/event';
+
+type CreativeEventType = 'render' | 'viewable' | 'click';
+
+function creativeEventKey(slotId: string, token: string, type: CreativeEventType): string {
+  return `${slotId}|${token}|${type}`;
+}
+
+/** Post a creative event for a bid carrying a server-minted `ts_event` token, once per type. */
+function sendCreativeEvent(type: CreativeEventType, slotId: string, bid: AuctionBidData): void {
+  const token = bid.ts_event;
+  if (!slotId || !token) return;
+
+  const fired = (window.tsjs!.firedBeacons ??= {});
+  const beaconKey = creativeEventKey(slotId, token, type);
+  if (fired[beaconKey]) return;
+
+  if (queueWinBillingBeacon(EVENT_BEACON_PATH, JSON.stringify({ type, token, slot: slotId }))) {
+    fired[beaconKey] = true;
+  }
+}
+
+/**
+ * Report a post-render event (viewable, click) for the slot rendered in `divId`.
+ * Only creatives the render bridge actually served report, so a publisher or
+ * house line item winning the GAM slot never counts against the TS bid.
+ */
+function sendPostRenderCreativeEvent(type: 'viewable' | 'click', divId: string): void {
+  const ts = window.tsjs;
+  const slotId = (ts?.divToSlotId ?? {})[divId];
+  if (!ts || !slotId) return;
+
+  const bid = (ts.bids ?? {})[slotId];
+  const token = bid?.ts_event;
+  if (!bid || !token || !ts.firedBeacons?.[creativeEventKey(slotId, token, 'render')]) return;
+
+  sendCreativeEvent(type, slotId, bid);
+}
+
 function fireWinBillingBeacons(slotId: string, bid: AuctionBidData): void {
+  sendCreativeEvent('render', slotId, bid);
   if (!slotId || (!bid.nurl && !bid.burl)) return;
 
   const fired = (window.tsjs!.firedBeacons ??= {});
@@ -551,10 +600,10 @@ function fireWinBillingBeacons(slotId: string, bid: AuctionBidData): void {
   }
 }
 
-function queueWinBillingBeacon(url: string): boolean {
+function queueWinBillingBeacon(url: string, body?: string): boolean {
   if (typeof navigator !== 'undefined' && typeof navigator.sendBeacon === 'function') {
     try {
-      if (navigator.sendBeacon(url)) {
+      if (body === undefined ? navigator.sendBeacon(url) : navigator.sendBeacon(url, body)) {
         return true;
       }
     } catch (err) {
@@ -564,7 +613,7 @@ function queueWinBillingBeacon(url: string): boolean {
 
   if (typeof fetch === 'function') {
     try {
-      void fetch(url, { method: 'POST', keepalive: true, mode: 'no-cors' });
+      void fetch(url, { method: 'POST', keepalive: true, mode: 'no-cors', body });
       return true;
     } catch (err) {
       log.warn('[tsjs-gpt] win/billing fetch fallback failed', err);
@@ -929,6 +978,18 @@ function installLatePublisherSlotHandoff(ts: TsjsApi): void {
   });
 }
 
+/** GPT slot a slot-auction result is applied to. */
+interface SlotAuctionTarget {
+  gptSlot: GoogleTagSlot;
+  divId: string;
+  /** TS defined the slot, so a lazy load must `display()` it. */
+  tsOwned: boolean;
+}
+
+/** Lazy-load and refresh scheduling for the route adInit() last applied. */
+let slotScheduler: SlotScheduler | undefined;
+let slotAuctionTargets = new Map<string, SlotAuctionTarget>();
+
 export function installTsAdInit(): void {
   const ts = (window.tsjs ??= {} as TsjsApi);
   installInitialLoadDetector(ts);
@@ -954,6 +1015,14 @@ export function installTsAdInit(): void {
 
     g.cmd?.push(() => {
       if ((ts.navGeneration ?? 0) !== generation) return;
+      // Lazy-load observers and refresh timers belong to the route being
+      // replaced; cancel them before its slots are destroyed.
+      slotScheduler?.dispose();
+      const scheduler = new SlotScheduler((state, reason) => {
+        void runSlotAuction(ts, generation, state, reason);
+      });
+      slotScheduler = scheduler;
+      slotAuctionTargets = new Map();
       // Destroy previously defined TS slots before redefining for the new page.
       if (ts.prevGptSlots && ts.prevGptSlots.length > 0) {
         const destroyedSlotElementIds = new Set(
@@ -981,6 +1050,11 @@ export function installTsAdInit(): void {
       // alone no-ops for a slot that was never displayed, so these are
       // display()ed instead of refreshed.
       const slotsToDisplay: string[] = [];
+      // TS-defined slots requested now rather than deferred to a lazy load.
+      const eagerNewSlots: GoogleTagSlot[] = [];
+      // Lazy slots without a document-auction bid, auctioned near the viewport.
+      const lazySlots: Array<{ element: HTMLElement; slot: AuctionSlot }> = [];
+      const refreshingSlots: AuctionSlot[] = [];
       const divToSlotId: Record<string, string> = {};
       const prevSlotTargetingKeys = ts.prevSlotTargetingKeys ?? {};
       const nextSlotTargetingKeys: Record<string, string[]> = {};
@@ -1100,11 +1174,19 @@ export function installTsAdInit(): void {
         const slotTargetingKeys = Object.keys(slot.targeting ?? {});
         nextSlotTargetingKeys[actualDivId] = slotTargetingKeys;
         if (slotDivId2 !== actualDivId) nextSlotTargetingKeys[slotDivId2] = slotTargetingKeys;
-        if (tsOwned) {
-          newSlots.push(gptSlot);
-          slotsToDisplay.push(slotDivId2);
+        slotAuctionTargets.set(slot.id, { gptSlot, divId: slotDivId2, tsOwned });
+        if (tsOwned) newSlots.push(gptSlot);
+        if (slot.lazy_load && !bids[slot.id]) {
+          // No ad request until the slot's own auction has run.
+          lazySlots.push({ element: el, slot });
         } else {
-          slotsToRefresh.push(gptSlot);
+          if (tsOwned) {
+            eagerNewSlots.push(gptSlot);
+            slotsToDisplay.push(slotDivId2);
+          } else {
+            slotsToRefresh.push(gptSlot);
+          }
+          if (slot.refresh) refreshingSlots.push(slot);
         }
 
         // Trusted Server APS winners carry their own typed renderer and never
@@ -1119,7 +1201,8 @@ export function installTsAdInit(): void {
       // Whether this call produced any TS slot to render. A gated page-bids
       // response (auction kill switch or consent denial) returns no slots, so
       // the loops above leave these empty.
-      const hasRenderableWork = slotsToDisplay.length > 0 || slotsToRefresh.length > 0;
+      const hasRenderableWork =
+        slotsToDisplay.length > 0 || slotsToRefresh.length > 0 || lazySlots.length > 0;
 
       // enableSingleRequest and enableServices must only be called once per page
       // load. Skip activating GPT services when TS has nothing to display or
@@ -1149,6 +1232,25 @@ export function installTsAdInit(): void {
             injectAdmIntoSlot(divId, bid.adm);
           }
         });
+
+        g.pubads!().addEventListener?.('impressionViewable', (event: SlotRenderEndedEvent) => {
+          const viewableDivId = event.slot?.getSlotElementId?.() ?? '';
+          sendPostRenderCreativeEvent('viewable', viewableDivId);
+          const viewableSlotId = (ts.divToSlotId ?? {})[viewableDivId];
+          if (viewableSlotId) slotScheduler?.markViewable(viewableSlotId);
+        });
+
+        // Cross-origin creative clicks are invisible to the page; the
+        // window losing focus to an iframe inside a TS slot is the closest
+        // observable signal.
+        window.addEventListener('blur', () => {
+          const active = document.activeElement;
+          if (!(active instanceof HTMLIFrameElement)) return;
+          const divId = Object.keys(ts.divToSlotId ?? {}).find((id) =>
+            document.getElementById(id)?.contains(active)
+          );
+          if (divId) sendPostRenderCreativeEvent('click', divId);
+        });
       }
 
       // Register and render TS-defined slots. GPT requires display() for a
@@ -1169,7 +1271,7 @@ export function installTsAdInit(): void {
       // add them in that case; otherwise display() + refresh() would
       // double-request the impression.
       const slotsNeedingRefresh = ts.gptInitialLoadDisabled
-        ? slotsToRefresh.concat(newSlots)
+        ? slotsToRefresh.concat(eagerNewSlots)
         : slotsToRefresh;
 
       if (slotsNeedingRefresh.length > 0) {
@@ -1185,10 +1287,82 @@ export function installTsAdInit(): void {
           ts.adInitRefreshInProgress = false;
         }
       }
+
+      lazySlots.forEach(({ element, slot }) => scheduler.observeLazy(element, slot));
+      refreshingSlots.forEach((slot) => scheduler.loaded(slot));
     });
   };
 }
 
+/**
+ * Run the slot auction for one lazy or refreshing slot and hand the result to GPT.
+ *
+ * A lazy slot always renders — without server-side demand when the auction
+ * fails or the server refuses it — so it is never left blank. A failed or
+ * refused refresh keeps the current creative and stops refreshing the slot.
+ */
+async function runSlotAuction(
+  ts: TsjsApi,
+  generation: number,
+  state: SlotLifecycle,
+  reason: SlotAuctionReason
+): Promise<void> {
+  const slotId = state.slot.id;
+  const scheduler = slotScheduler;
+  const response = await fetchSlotAuction(window.location.pathname, [
+    slotAuctionEntry(state, reason, Date.now()),
+  ]);
+  const g = (window as GptWindow).googletag;
+  if (!g || (ts.navGeneration ?? 0) !== generation) return;
+
+  g.cmd?.push(() => {
+    const target = slotAuctionTargets.get(slotId);
+    if ((ts.navGeneration ?? 0) !== generation || !target || !scheduler) return;
+    if (scheduler !== slotScheduler) return;
+
+    const refusal = response?.rejected?.[slotId];
+    if (reason === 'refresh' && (!response || refusal)) {
+      log.debug('slot auction: refresh not run', { slotId, refusal });
+      scheduler.stop(slotId);
+      return;
+    }
+
+    const bid = response?.bids?.[slotId];
+    const nextBids = { ...(ts.bids ?? {}) };
+    if (bid) {
+      nextBids[slotId] = bid;
+    } else {
+      delete nextBids[slotId];
+    }
+    ts.bids = nextBids;
+    clearTargetingKeys(target.gptSlot, TS_BID_TARGETING_KEYS);
+    TS_BID_TARGETING_KEYS.forEach((key) => {
+      if (bid?.[key]) target.gptSlot.setTargeting(key, String(bid[key]!));
+    });
+
+    if (reason === 'lazy' && target.tsOwned) {
+      withGptSlotHandoffInternal(ts, () => g.display?.(target.divId));
+      syncInitialLoadDisabled(g, ts);
+      // display() fetches the ad unless initial load is disabled, in which
+      // case the request has to come from the refresh() below.
+      if (!ts.gptInitialLoadDisabled) {
+        scheduler.loaded(state.slot, reason);
+        return;
+      }
+    }
+
+    // Same one-shot slim-Prebid bypass as adInit(): this refresh delivers
+    // server-side targeting and must not start a client-side auction.
+    ts.adInitRefreshInProgress = true;
+    try {
+      withGptSlotHandoffInternal(ts, () => g.pubads!().refresh([target.gptSlot]));
+    } finally {
+      ts.adInitRefreshInProgress = false;
+    }
+    scheduler.loaded(state.slot, reason);
+  });
+}
+
 interface PageBidsResponse {
   slots: AuctionSlot[];
   bids: Record<string, AuctionBidData>;
@@ -0,0 +1,206 @@
+import { log } from '../../core/log';
+import type { AuctionBidData, AuctionSlot } from '../../core/types';
+
+/** Slot-level auction endpoint. Mirrors `SLOT_AUCTION_PATH` in Rust. */
+export const SLOT_AUCTION_PATH = '/_ts/api/v1/slot-auction';
+
+/**
+ * How far outside the viewport a lazy slot starts its auction. Large enough
+ * that the bid usually arrives before the slot scrolls into view.
+ */
+export const LAZY_LOAD_ROOT_MARGIN = '200px 0px';
+
+export type SlotAuctionReason = 'lazy' | 'refresh';
+
+/** One slot named in a slot-auction request. */
+export interface SlotAuctionEntry {
+  id: string;
+  reason: SlotAuctionReason;
+  /** 1-based index of the requested refresh. */
+  refresh_count?: number;
+  /** Milliseconds since the slot's previous auction. */
+  elapsed_ms?: number;
+  /** Whether the creative currently in the slot reached viewability. */
+  viewable?: boolean;
+}
+
+export interface SlotAuctionResponse {
+  slots: AuctionSlot[];
+  bids: Record<string, AuctionBidData>;
+  /** Slots the server refused, with the reason. */
+  rejected?: Record<string, string>;
+}
+
+/** Lifecycle of one slot between auctions. */
+export interface SlotLifecycle {
+  slot: AuctionSlot;
+  /** `Date.now()` of the slot's previous auction. */
+  lastAuctionAt: number;
+  /** Refresh auctions already run for the slot. */
+  refreshCount: number;
+  /** Whether the creative currently in the slot reached viewability. */
+  viewable: boolean;
+  /** The refresh interval elapsed while the creative was not yet viewable. */
+  awaitingViewable: boolean;
+  timer?: ReturnType<typeof setTimeout>;
+}
+
+/** Build the request entry for `state`. */
+export function slotAuctionEntry(
+  state: SlotLifecycle,
+  reason: SlotAuctionReason,
+  now: number
+): SlotAuctionEntry {
+  if (reason === 'lazy') return { id: state.slot.id, reason };
+  return {
+    id: state.slot.id,
+    reason,
+    refresh_count: state.refreshCount + 1,
+    elapsed_ms: Math.max(0, Math.round(now - state.lastAuctionAt)),
+    viewable: state.viewable,
+  };
+}
+
+/**
+ * Post a slot auction. Resolves `null` on any transport or server failure so
+ * the caller can fall back to rendering the slot without server-side demand.
+ */
+export async function fetchSlotAuction(
+  path: string,
+  entries: SlotAuctionEntry[]
+): Promise<SlotAuctionResponse | null> {
+  try {
+    const res = await fetch(SLOT_AUCTION_PATH, {
+      method: 'POST',
+      credentials: 'include',
+      // Non-simple header doubles as a CSRF token, like `X-TSJS-Page-Bids`.
+      headers: { 'Content-Type': 'application/json', 'X-TSJS-Slot-Auction': '1' },
+      body: JSON.stringify({ path, slots: entries }),
+    });
+    if (!res.ok) {
+      log.debug('slot auction: endpoint answered', res.status);
+      return null;
+    }
+    return (await res.json()) as SlotAuctionResponse;
+  } catch (err) {
+    log.debug('slot auction: request failed', err);
+    return null;
+  }
+}
+
+/**
+ * Decides when lazy and refreshing slots are due for an auction.
+ *
+ * Lazy slots are due once they come within {@link LAZY_LOAD_ROOT_MARGIN} of the
+ * viewport. Refreshing slots are due once their interval has elapsed since the
+ * previous auction and, when the policy requires it, the current creative was
+ * viewable. The scheduler only decides timing; `onDue` runs the auction and
+ * reports back through {@link loaded}.
+ */
+export class SlotScheduler {
+  private readonly onDue: (state: SlotLifecycle, reason: SlotAuctionReason) => void;
+  private readonly now: () => number;
+  private readonly states = new Map<string, SlotLifecycle>();
+  private observer?: IntersectionObserver;
+  private disposed = false;
+
+  constructor(
+    onDue: (state: SlotLifecycle, reason: SlotAuctionReason) => void,
+    now: () => number = Date.now
+  ) {
+    this.onDue = onDue;
+    this.now = now;
+  }
+
+  /** Run a lazy auction for `slot` once `element` nears the viewport. */
+  observeLazy(element: Element, slot: AuctionSlot): void {
+    const state = this.track(slot);
+    if (typeof IntersectionObserver === 'undefined') {
+      this.onDue(state, 'lazy');
+      return;
+    }
+    this.observer ??= new IntersectionObserver(
+      (records) => {
+        for (const record of records) {
+          if (!record.isIntersecting) continue;
+          this.observer?.unobserve(record.target);
+          const id = (record.target as HTMLElement).dataset.tsSlotId;
+          const due = id ? this.states.get(id) : undefined;
+          if (due && !this.disposed) this.onDue(due, 'lazy');
+        }
+      },
+      { rootMargin: LAZY_LOAD_ROOT_MARGIN }
+    );
+    (element as HTMLElement).dataset.tsSlotId = slot.id;
+    this.observer.observe(element);
+  }
+
+  /**
+   * Record that `slot` just ran an auction and arm its refresh timer. A
+   * `refresh` auction counts against the slot's `max_refreshes`.
+   */
+  loaded(slot: AuctionSlot, reason?: SlotAuctionReason): void {
+    const state = this.track(slot);
+    if (reason === 'refresh') state.refreshCount += 1;
+    state.lastAuctionAt = this.now();
+    state.viewable = false;
+    state.awaitingViewable = false;
+    if (state.timer !== undefined) clearTimeout(state.timer);
+    state.timer = undefined;
+
+    const policy = slot.refresh;
+    if (!policy || state.refreshCount >= policy.max_refreshes || this.disposed) return;
+    state.timer = setTimeout(() => {
+      state.timer = undefined;
+      if (this.disposed) return;
+      if (policy.require_viewable && !state.viewable) {
+        state.awaitingViewable = true;
+        return;
+      }
+      this.onDue(state, 'refresh');
+    }, policy.interval_secs * 1000);
+  }
+
+  /** Stop refreshing `slotId`, e.g. after the server refused a refresh. */
+  stop(slotId: string): void {
+    const state = this.states.get(slotId);
+    if (state?.timer !== undefined) clearTimeout(state.timer);
+    this.states.delete(slotId);
+  }
+
+  /** Note that the creative in `slotId` reached viewability. */
+  markViewable(slotId: string): void {
+    const state = this.states.get(slotId);
+    if (!state) return;
+    state.viewable = true;
+    if (state.awaitingViewable && !this.disposed) {
+      state.awaitingViewable = false;
+      this.onDue(state, 'refresh');
+    }
+  }
+
+  /** Cancel every timer and observer, e.g. on SPA navigation. */
+  dispose(): void {
+    this.disposed = true;
+    this.observer?.disconnect();
+    for (const state of this.states.values()) {
+      if (state.timer !== undefined) clearTimeout(state.timer);
+    }
+    this.states.clear();
+  }
+
+  private track(slot: AuctionSlot): SlotLifecycle {
+    let state = this.states.get(slot.id);
+    if (!state) {
+      state = {
+        slot,
+        lastAuctionAt: this.now(),
+        refreshCount: 0,
+        viewable: false,
+        awaitingViewable: false,
+      };
+      this.states.set(slot.id, state);
+    }
+    return state;
+  }
+}
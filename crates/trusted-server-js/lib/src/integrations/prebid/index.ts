@@ -125,6 +125,7 @@ const TS_REFRESH_TARGETING_KEYS = [
   'hb_pb',
   'hb_bidder',
   'hb_adid',
+  'hb_deal',
   'hb_cache_host',
   'hb_cache_path',
 ] as const;
@@ -338,6 +339,7 @@ export function auctionBidsToPrebidBids(
         ...(renderer ? { [APS_RENDERER_FIELD]: renderer } : {}),
         ttl: 300,
         creativeId: bid.creativeId,
+        ...(bid.dealId ? { dealId: bid.dealId } : {}),
         netRevenue: true,
         currency: 'USD',
         bidderCode: bid.seat,
@@ -192,6 +192,23 @@ describe('auction/parseAuctionResponse', () => {
     });
   });
 
+  it('carries a non-empty dealid as dealId', () => {
+    const bids = parseAuctionResponse({
+      seatbid: [
+        {
+          seat: 'appnexus',
+          bid: [
+            { impid: 'div-1', price: 12, adm: '<div>PG</div>', dealid: 'pg-deal-1' },
+            { impid: 'div-2', price: 1, adm: '<div>Open</div>', dealid: '' },
+          ],
+        },
+      ],
+    });
+
+    expect(bids[0].dealId).toBe('pg-deal-1');
+    expect(bids[1]).not.toHaveProperty('dealId');
+  });
+
   it('parses an APS typed renderer without requiring adm', () => {
     const renderer = apsRenderer('fictional-creative-id');
     const bids = parseAuctionResponse({
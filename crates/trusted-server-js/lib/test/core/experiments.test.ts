@@ -0,0 +1,22 @@
+import { describe, it, expect } from 'vitest';
+
+import { readExperimentArms } from '../../src/core/experiments';
+
+describe('core/experiments', () => {
+  it('parses every experiment arm from the ts-exp cookie', () => {
+    const cookie = 'ts-ec=abc; ts-exp=timeout:fast|granularity:dense; other=1';
+
+    expect(readExperimentArms(cookie)).toEqual({ timeout: 'fast', granularity: 'dense' });
+  });
+
+  it('returns no arms without the cookie', () => {
+    expect(readExperimentArms('ts-ec=abc')).toEqual({});
+    expect(readExperimentArms('')).toEqual({});
+  });
+
+  it('skips malformed pairs', () => {
+    expect(readExperimentArms('ts-exp=timeout:fast|broken|:arm|exp:')).toEqual({
+      timeout: 'fast',
+    });
+  });
+});
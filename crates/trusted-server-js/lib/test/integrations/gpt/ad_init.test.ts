@@ -542,6 +542,7 @@ describe('installTsAdInit', () => {
           hb_pb: '1.00',
           hb_bidder: 'kargo',
           hb_adid: 'abc-uuid',
+          hb_deal: 'pg-deal-1',
           hb_cache_host: 'cache.example.com',
           hb_cache_path: '/pbc/v1/cache',
           nurl: 'https://ssp/win',
@@ -560,6 +561,7 @@ describe('installTsAdInit', () => {
     expect(mockSlot.setTargeting).toHaveBeenCalledWith('hb_pb', '1.00');
     expect(mockSlot.setTargeting).toHaveBeenCalledWith('hb_bidder', 'kargo');
     expect(mockSlot.setTargeting).toHaveBeenCalledWith('hb_adid', 'abc-uuid');
+    expect(mockSlot.setTargeting).toHaveBeenCalledWith('hb_deal', 'pg-deal-1');
     expect(mockSlot.setTargeting).toHaveBeenCalledWith('hb_cache_host', 'cache.example.com');
     expect(mockSlot.setTargeting).toHaveBeenCalledWith('hb_cache_path', '/pbc/v1/cache');
     expect(mockSlot.setTargeting).toHaveBeenCalledWith('ts_initial', '1');
@@ -3054,6 +3056,39 @@ describe('installTsRenderBridge', () => {
     expect(recordTrustedServerCreativeFailure).not.toHaveBeenCalled();
   });
 
+  it('posts a render event once for a bid carrying a ts_event token', async () => {
+    const beaconSpy = vi.spyOn(navigator, 'sendBeacon').mockReturnValue(true);
+    const tsjs = (window as TestWindow).tsjs!;
+    tsjs.bids.homepage_header.adm = '<div>Creative</div>';
+    tsjs.bids.homepage_header.ts_event = 'opaque-event-token';
+    // Server-side notifications withhold nurl/burl from the page.
+    delete tsjs.bids.homepage_header.nurl;
+    delete tsjs.bids.homepage_header.burl;
+
+    const bridgeListener = await captureBridgeListener();
+    const source = createTrustedSlotIframe();
+    const request = () =>
+      bridgeListener(
+        Object.assign(new Event('message'), {
+          data: JSON.stringify({ message: 'Prebid Request', adId: 'test-cache-uuid' }),
+          ports: [{ postMessage: vi.fn() }],
+          source,
+          stopImmediatePropagation: vi.fn(),
+        }) as unknown as MessageEvent
+      );
+    request();
+    request();
+
+    expect(beaconSpy).toHaveBeenCalledTimes(1);
+    expect(beaconSpy).toHaveBeenCalledWith('/_ts/api/v1/event', expect.any(String));
+    expect(JSON.parse(beaconSpy.mock.calls[0][1] as string)).toEqual({
+      type: 'render',
+      token: 'opaque-event-token',
+      slot: 'homepage_header',
+    });
+    beaconSpy.mockRestore();
+  });
+
   it('records no creative evidence for an ad ID the requesting slot does not own', async () => {
     const recordTrustedServerCreativeRequest = vi.fn().mockReturnValue(42);
     const recordTrustedServerCreativeResponse = vi.fn();
@@ -0,0 +1,131 @@
+import { describe, it, expect, vi, beforeEach, afterEach } from 'vitest';
+
+import type { AuctionSlot } from '../../../src/core/types';
+import {
+  SLOT_AUCTION_PATH,
+  SlotScheduler,
+  fetchSlotAuction,
+  slotAuctionEntry,
+  type SlotAuctionReason,
+  type SlotLifecycle,
+} from '../../../src/integrations/gpt/slot_auction';
+
+function refreshingSlot(requireViewable: boolean): AuctionSlot {
+  return {
+    id: 'sidebar',
+    gam_unit_path: '/123/sidebar',
+    div_id: 'div-sidebar',
+    formats: [[300, 250]],
+    refresh: { interval_secs: 30, max_refreshes: 2, require_viewable: requireViewable },
+  };
+}
+
+describe('SlotScheduler', () => {
+  let due: Array<[string, SlotAuctionReason]>;
+  let scheduler: SlotScheduler;
+
+  beforeEach(() => {
+    vi.useFakeTimers();
+    due = [];
+    scheduler = new SlotScheduler((state, reason) => due.push([state.slot.id, reason]));
+  });
+
+  afterEach(() => {
+    scheduler.dispose();
+    vi.useRealTimers();
+  });
+
+  it('refreshes after the interval, up to max_refreshes', () => {
+    const slot = refreshingSlot(false);
+    scheduler.loaded(slot);
+
+    vi.advanceTimersByTime(29_999);
+    expect(due).toEqual([]);
+    vi.advanceTimersByTime(1);
+    expect(due).toEqual([['sidebar', 'refresh']]);
+
+    scheduler.loaded(slot, 'refresh');
+    vi.advanceTimersByTime(30_000);
+    scheduler.loaded(slot, 'refresh');
+    vi.advanceTimersByTime(60_000);
+
+    expect(due).toHaveLength(2);
+  });
+
+  it('waits for viewability when the policy requires it', () => {
+    const slot = refreshingSlot(true);
+    scheduler.loaded(slot);
+
+    vi.advanceTimersByTime(30_000);
+    expect(due).toEqual([]);
+
+    scheduler.markViewable('sidebar');
+    expect(due).toEqual([['sidebar', 'refresh']]);
+  });
+
+  it('does not fire after dispose or stop', () => {
+    const slot = refreshingSlot(false);
+    scheduler.loaded(slot);
+    scheduler.stop('sidebar');
+    vi.advanceTimersByTime(30_000);
+
+    scheduler.loaded(slot);
+    scheduler.dispose();
+    vi.advanceTimersByTime(30_000);
+
+    expect(due).toEqual([]);
+  });
+});
+
+describe('slotAuctionEntry', () => {
+  it('reports the refresh index, elapsed time and viewability', () => {
+    const state: SlotLifecycle = {
+      slot: refreshingSlot(true),
+      lastAuctionAt: 1_000,
+      refreshCount: 1,
+      viewable: true,
+      awaitingViewable: false,
+    };
+
+    expect(slotAuctionEntry(state, 'refresh', 46_000)).toEqual({
+      id: 'sidebar',
+      reason: 'refresh',
+      refresh_count: 2,
+      elapsed_ms: 45_000,
+      viewable: true,
+    });
+    expect(slotAuctionEntry(state, 'lazy', 46_000)).toEqual({ id: 'sidebar', reason: 'lazy' });
+  });
+});
+
+describe('fetchSlotAuction', () => {
+  afterEach(() => {
+    vi.unstubAllGlobals();
+  });
+
+  it('posts the slots with the CSRF header', async () => {
+    const fetchStub = vi.fn().mockResolvedValue({
+      ok: true,
+      json: async () => ({ slots: [], bids: {}, rejected: { sidebar: 'interval' } }),
+    });
+    vi.stubGlobal('fetch', fetchStub);
+
+    const response = await fetchSlotAuction('/news/story', [{ id: 'sidebar', reason: 'lazy' }]);
+
+    expect(response?.rejected).toEqual({ sidebar: 'interval' });
+    const [url, init] = fetchStub.mock.calls[0];
+    expect(url).toBe(SLOT_AUCTION_PATH);
+    expect(init.method).toBe('POST');
+    expect(init.headers['X-TSJS-Slot-Auction']).toBe('1');
+    expect(JSON.parse(init.body)).toEqual({
+      path: '/news/story',
+      slots: [{ id: 'sidebar', reason: 'lazy' }],
+    });
+  });
+
+  it('resolves null when the endpoint fails', async () => {
+    vi.stubGlobal('fetch', vi.fn().mockResolvedValue({ ok: false, status: 503 }));
+
+    expect(await fetchSlotAuction('/', [{ id: 'sidebar', reason: 'lazy' }])).toBeNull();
+  });
+});
@@ -288,6 +288,26 @@ describe('prebid/auctionBidsToPrebidBids', () => {
     });
   });
 
+  it('passes the deal ID through so Prebid sets hb_deal', () => {
+    const auctionBids: AuctionBid[] = [
+      {
+        impid: 'div-gpt-1',
+        adm: '<div>Ad</div>',
+        price: 12,
+        width: 300,
+        height: 250,
+        seat: 'appnexus',
+        creativeId: 'cr-123',
+        dealId: 'pg-deal-1',
+        adomain: ['example.com'],
+      },
+    ];
+
+    const result = auctionBidsToPrebidBids(auctionBids, [], true);
+
+    expect(result[0]).toMatchObject({ dealId: 'pg-deal-1' });
+  });
+
   it('preserves an APS renderer without converting it to executable markup', () => {
     const renderer = apsRenderer();
     const auctionBids: AuctionBid[] = [
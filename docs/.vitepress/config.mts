@@ -172,7 +172,13 @@ export default withMermaid(
             },
             {
               text: 'SSP',
-              items: [{ text: 'Kargo', link: '/guide/integrations/kargo' }],
+              items: [
+                { text: 'Kargo', link: '/guide/integrations/kargo' },
+                {
+                  text: 'OpenRTB Bidders',
+                  link: '/guide/integrations/openrtb-bidders',
+                },
+              ],
             },
             {
               text: 'Framework Support',